@@ -242,6 +242,11 @@ pub enum CallProfile {
     /// Should allow for _almost_ reproducible measurements. Note that all packets for WebRTC
     /// will flow through the lossy stream.
     DeterministicLoss(u8),
+    /// Applies a schedule of loss, latency, jitter, and rate limits inside the client (via its
+    /// injectable network), to incoming packets and to HTTP requests. The schedule is given as
+    /// `{offset in seconds}:{impairments}` steps separated by semicolons, such as
+    /// `0:loss=15;10:loss=100;20:latency_ms=80,jitter_ms=20,rate_kbps=300`.
+    Impairments(String),
 }
 
 /// General structure for configuration settings to send to the cli.
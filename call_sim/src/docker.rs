@@ -838,8 +838,14 @@ pub async fn start_cli(
         args.push("--ip=172.28.0.3".to_string());
     }
 
-    if let CallProfile::DeterministicLoss(loss_rate) = call_config.profile {
-        args.push(format!("--deterministic-loss={}", loss_rate));
+    match &call_config.profile {
+        CallProfile::None => {}
+        CallProfile::DeterministicLoss(loss_rate) => {
+            args.push(format!("--deterministic-loss={}", loss_rate));
+        }
+        CallProfile::Impairments(schedule) => {
+            args.push(format!("--impairments={}", schedule));
+        }
     }
 
     args.extend(call_config.extra_cli_args.iter().cloned());
@@ -25,9 +25,10 @@ use itertools::{iproduct, Itertools};
 
 use crate::{
     common::{
-        AudioAnalysisMode, AudioConfig, CallConfig, CallProfile::DeterministicLoss, ChartDimension,
-        GroupConfig, NetworkConfig, NetworkConfigWithOffset, NetworkProfile, SummaryReportColumns,
-        TestCaseConfig, VideoConfig,
+        AudioAnalysisMode, AudioConfig, CallConfig,
+        CallProfile::{DeterministicLoss, Impairments},
+        ChartDimension, GroupConfig, NetworkConfig, NetworkConfigWithOffset, NetworkProfile,
+        SummaryReportColumns, TestCaseConfig, VideoConfig,
     },
     docker::{build_images, clean_network, clean_up},
     test::{CallTypeConfig, Test},
@@ -258,6 +259,64 @@ async fn run_deterministic_loss_test(test: &mut Test) -> Result<()> {
     Ok(())
 }
 
+// Test how audio holds up under impairments applied inside the clients, which unlike the
+// network profiles start and stop at precise times: steady loss, and an outage long enough
+// that ICE has to reconnect (look for how long that took in the client logs).
+async fn run_impairment_test(test: &mut Test) -> Result<()> {
+    let schedules = [
+        ("loss_15", "0:loss=15"),
+        ("latency_150_jitter_50", "0:latency_ms=150,jitter_ms=50"),
+        ("rate_limit_32", "0:rate_kbps=32"),
+        ("outage_10s", "10:loss=100;20:"),
+    ];
+
+    let test_cases = schedules.map(|(name, schedule)| TestCaseConfig {
+        test_case_name: name.to_string(),
+        length_seconds: 40,
+        client_a_config: CallConfig {
+            audio: AudioConfig {
+                input_name: "normal_phrasing".to_string(),
+                generate_spectrogram: false,
+                ..Default::default()
+            },
+            profile: Impairments(schedule.to_string()),
+            ..Default::default()
+        },
+        client_b_config: CallConfig {
+            audio: AudioConfig {
+                input_name: "normal_phrasing".to_string(),
+                visqol_speech_analysis: true,
+                pesq_speech_analysis: true,
+                plc_speech_analysis: true,
+                ..Default::default()
+            },
+            profile: Impairments(schedule.to_string()),
+            ..Default::default()
+        },
+        iterations: 3,
+        ..Default::default()
+    });
+
+    test.run(
+        GroupConfig {
+            group_name: "impairment_test".to_string(),
+            summary_report_columns: SummaryReportColumns {
+                show_visqol_mos_speech: true,
+                show_pesq_mos: true,
+                show_plc_mos: true,
+                show_video: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        test_cases.into(),
+        vec![NetworkProfile::None],
+    )
+    .await?;
+
+    Ok(())
+}
+
 // Here is a test to run without a TURN server, with a TURN server, and forcing the use of a
 // TURN server over UDP, and then forcing the use of a TURN server over TCP.
 // Notes:
@@ -757,6 +816,7 @@ async fn main() -> Result<()> {
             "minimal_example" => run_minimal_example(test).await?,
             "baseline" => run_baseline(test).await?,
             "deterministic_loss_test" => run_deterministic_loss_test(test).await?,
+            "impairment_test" => run_impairment_test(test).await?,
             "relay_tests" => run_relay_tests(test).await?,
             "video_send_over_bandwidth" => run_video_send_over_bandwidth(test).await?,
             "video_compare_vp8_vs_vp9" => run_video_compare_vp8_vs_vp9(test).await?,
@@ -35,6 +35,19 @@ public class CallLinkRootKey {
     }
   }
 
+  /**
+   * Returns the same key at the newest version, to pass to
+   * {@link CallManager#migrateCallLink}.
+   */
+  @NonNull
+  public CallLinkRootKey withLatestVersion() {
+    try {
+      return new CallLinkRootKey(nativeWithLatestVersion(rawKey));
+    } catch (CallException e) {
+      throw new AssertionError(e);
+    }
+  }
+
   /** Returns the internal storage, so don't modify it! */
   @NonNull
   public byte[] getKeyBytes() {
@@ -54,5 +67,6 @@ public class CallLinkRootKey {
   private static native byte[] nativeParseKeyString(String keyString) throws CallException;
   private static native void nativeValidateKeyBytes(byte[] keyBytes) throws CallException;
   private static native byte[] nativeDeriveRoomId(byte[] keyBytes) throws CallException;
+  private static native byte[] nativeWithLatestVersion(byte[] keyBytes) throws CallException;
   private static native String nativeToFormattedString(byte[] keyBytes) throws CallException;
 }
\ No newline at end of file
@@ -6,6 +6,7 @@
 package org.signal.ringrtc;
 
 import androidx.annotation.NonNull;
+import androidx.annotation.Nullable;
 import java.time.Instant;
 
 public class CallLinkState {
@@ -23,17 +24,34 @@ public class CallLinkState {
   private final boolean revoked;
   @NonNull
   private final Instant expiration;
+  /** Is never null, but may be empty. */
+  @NonNull
+  private final String description;
+  @Nullable
+  private final Integer accentColor;
+  @Nullable
+  private final String avatar;
+  private final boolean oldAdminPasskeyRejected;
 
   /** Should only be used for testing. */
   public CallLinkState(@NonNull String name, @NonNull Restrictions restrictions, boolean revoked, @NonNull Instant expiration) {
+    this(name, restrictions, revoked, expiration, "", null, null);
+  }
+
+  /** Should only be used for testing. */
+  public CallLinkState(@NonNull String name, @NonNull Restrictions restrictions, boolean revoked, @NonNull Instant expiration, @NonNull String description, @Nullable Integer accentColor, @Nullable String avatar) {
     this.name = name;
     this.restrictions = restrictions;
     this.revoked = revoked;
     this.expiration = expiration;
+    this.description = description;
+    this.accentColor = accentColor;
+    this.avatar = avatar;
+    this.oldAdminPasskeyRejected = false;
   }
 
   @CalledByNative
-  private CallLinkState(@NonNull String name, int rawRestrictions, boolean revoked, long expirationEpochSecond) {
+  private CallLinkState(@NonNull String name, int rawRestrictions, boolean revoked, long expirationEpochSecond, @NonNull String description, long rawAccentColor, @Nullable String avatar, boolean oldAdminPasskeyRejected) {
     this.name = name;
     switch (rawRestrictions) {
     case 0:
@@ -47,6 +65,10 @@ public class CallLinkState {
     }
     this.revoked = revoked;
     this.expiration = Instant.ofEpochSecond(expirationEpochSecond);
+    this.description = description;
+    this.accentColor = rawAccentColor >= 0 ? (int) rawAccentColor : null;
+    this.avatar = avatar;
+    this.oldAdminPasskeyRejected = oldAdminPasskeyRejected;
   }
 
   /** Is never null, but may be empty. */
@@ -68,4 +90,27 @@ public class CallLinkState {
   public Instant getExpiration() {
     return expiration;
   }
+
+  /** Is never null, but may be empty. */
+  @NonNull
+  public String getDescription() {
+    return description;
+  }
+
+  /** An RGB color, as 0xRRGGBB, or null if none was set. */
+  @Nullable
+  public Integer getAccentColor() {
+    return accentColor;
+  }
+
+  /** An app-defined reference to an avatar stored elsewhere, or null if none was set. */
+  @Nullable
+  public String getAvatar() {
+    return avatar;
+  }
+
+  /** Set when rotating the admin passkey found that an earlier attempt had already gone through. */
+  public boolean wasOldAdminPasskeyRejected() {
+    return oldAdminPasskeyRejected;
+  }
 }
\ No newline at end of file
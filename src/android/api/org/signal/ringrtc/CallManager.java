@@ -83,6 +83,9 @@ public class CallManager {
   @Nullable
   private PeerConnectionFactory               groupFactory;
 
+  @Nullable
+  private LocalPreview                        localPreview;
+
   static {
     Log.d(TAG, "Loading ringrtc library");
     System.loadLibrary("ringrtc");
@@ -142,6 +145,96 @@ public class CallManager {
 
   }
 
+  /**
+   *
+   * Changes how much RingRTC logs, such as when the user turns on debug logging.
+   *
+   * <p>Trace messages are never logged on Android, and release builds of
+   * RingRTC log Info at most.
+   *
+   * @param level  The most verbose level to log
+   */
+  public static void setMaxLogLevel(@NonNull LogLevel level) {
+    checkInitializeHasBeenCalled();
+    Log.i(TAG, "setMaxLogLevel(): " + level);
+
+    try {
+      ringrtcSetMaxLogLevel(level.ordinal());
+    } catch (CallException e) {
+      Log.w(TAG, "Unable to set the max log level", e);
+    }
+  }
+
+  /**
+   *
+   * Overrides how much one part of RingRTC logs, such as "group_call" or
+   * "http", so that support can turn on targeted debugging.
+   *
+   * <p>The override stays in effect across {@link #setMaxLogLevel} until
+   * {@link #clearModuleLogLevels} is called. Like that, it is limited to
+   * Info in release builds of RingRTC.
+   *
+   * @param module  The module, or a path of modules such as "core::group_call"
+   * @param level   The most verbose level to log for the module
+   */
+  public static void setModuleLogLevel(@NonNull String module, @NonNull LogLevel level) {
+    checkInitializeHasBeenCalled();
+    Log.i(TAG, "setModuleLogLevel(): " + module + " " + level);
+
+    try {
+      ringrtcSetModuleLogLevel(module, level.ordinal());
+    } catch (CallException e) {
+      Log.w(TAG, "Unable to set the module log level", e);
+    }
+  }
+
+  /**
+   *
+   * Removes every override from {@link #setModuleLogLevel}.
+   */
+  public static void clearModuleLogLevels() {
+    checkInitializeHasBeenCalled();
+    Log.i(TAG, "clearModuleLogLevels()");
+
+    ringrtcClearModuleLogLevels();
+  }
+
+  /**
+   *
+   * Returns recent call events as JSON, for attaching to bug reports.
+   *
+   * <p>This includes state transitions, ICE connection changes, and HTTP and
+   * decryption failures, even if they were never logged.
+   *
+   * @return the events, as UTF-8 JSON
+   */
+  @NonNull
+  public static byte[] exportDiagnostics() {
+    checkInitializeHasBeenCalled();
+    return ringrtcExportDiagnostics();
+  }
+
+  /**
+   *
+   * Sets the file that a dump of call state is written to if RingRTC
+   * crashes, for postmortem analysis.
+   *
+   * <p>The dump includes the state of every call, in-flight HTTP requests,
+   * and the most recent events from {@link #exportDiagnostics()}. The file
+   * is created (or truncated) right away.
+   *
+   * @param path  the file to write the dump to
+   *
+   * @throws CallException for native code failures, such as if the file
+   *                       cannot be created
+   */
+  public static void setCrashDumpPath(@NonNull String path)
+    throws CallException
+  {
+    checkInitializeHasBeenCalled();
+    ringrtcSetCrashDumpPath(path);
+  }
+
   private static void checkInitializeHasBeenCalled() {
     if (!CallManager.isInitialized) {
       throw new IllegalStateException("CallManager.initialize has not been called");
@@ -335,6 +428,8 @@ public class CallManager {
       this.groupFactory.dispose();
     }
 
+    stopLocalPreview();
+
     ringrtcClose(nativeCallManager);
     nativeCallManager = 0;
   }
@@ -381,6 +476,34 @@ public class CallManager {
     ringrtcCall(nativeCallManager, remote, callMediaType.ordinal(), localDeviceId);
   }
 
+  /**
+   *
+   * Indication from application to take over an accepted call from
+   * another device of the local user, in response to
+   * Observer.onHandoffRequested(). Continues like an outgoing call,
+   * without ringing the remote peer.
+   *
+   * @param remote         remote side of the call
+   * @param callId         callId of the call being taken over
+   * @param callMediaType  the media type of the call being taken over
+   * @param localDeviceId  the local deviceId of the client
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void takeOverCall(         Remote        remote,
+                           @NonNull CallId        callId,
+                           @NonNull CallMediaType callMediaType,
+                           @NonNull Integer       localDeviceId)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "takeOverCall(): " + callId);
+
+    ringrtcTakeOverCall(nativeCallManager, remote, callId.longValue(), callMediaType.ordinal(), localDeviceId);
+  }
+
   /**
    *
    * Indication from application to proceed with call
@@ -416,6 +539,145 @@ public class CallManager {
                       @Nullable Integer                        audioLevelsIntervalMs,
                                 boolean                        enableCamera)
     throws CallException
+  {
+    proceed(callId, context, eglBase, audioProcessingMethod, useOboe, localSink, remoteSink, camera, iceServers, hideIp, dataMode, audioLevelsIntervalMs, enableCamera, null, null);
+  }
+
+  /**
+   *
+   * Indication from application to proceed with call, with custom timeouts
+   *
+   * @param callId                 callId for the call
+   * @param context                Call service context
+   * @param eglBase                eglBase to use for this Call
+   * @param audioProcessingMethod  the method to use for audio processing
+   * @param useOboe                whether to use the oboe-based audio device module, otherwise use java
+   * @param localSink              local video sink to use for this Call
+   * @param remoteSink             remote video sink to use for this Call
+   * @param camera                 camera control to use for this Call
+   * @param iceServers             list of ICE servers to use for this Call
+   * @param hideIp                 if true hide caller's IP by using a TURN server
+   * @param dataMode               desired data mode to start the session with
+   * @param audioLevelsIntervalMs  if greater than 0, enable audio levels with this interval (in milliseconds)
+   * @param enableCamera           if true, enable the local camera video track when created
+   * @param ringTimeoutMs          if greater than 0, how long the call may go unaccepted, from when it was created (1 second to 5 minutes)
+   * @param connectTimeoutMs       if greater than 0, how long the call may take to connect after proceeding (1 second to 2 minutes)
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void proceed(@NonNull  CallId                         callId,
+                      @NonNull  Context                        context,
+                      @NonNull  EglBase                        eglBase,
+                                AudioProcessingMethod          audioProcessingMethod,
+                                boolean                        useOboe,
+                      @NonNull  VideoSink                      localSink,
+                      @NonNull  VideoSink                      remoteSink,
+                      @NonNull  CameraControl                  camera,
+                      @NonNull  List<PeerConnection.IceServer> iceServers,
+                                boolean                        hideIp,
+                                DataMode                       dataMode,
+                      @Nullable Integer                        audioLevelsIntervalMs,
+                                boolean                        enableCamera,
+                      @Nullable Integer                        ringTimeoutMs,
+                      @Nullable Integer                        connectTimeoutMs)
+    throws CallException
+  {
+    proceed(callId, context, eglBase, audioProcessingMethod, useOboe, localSink, remoteSink, camera, iceServers, hideIp, dataMode, audioLevelsIntervalMs, enableCamera, ringTimeoutMs, connectTimeoutMs, false);
+  }
+
+  /**
+   *
+   * Indication from application to proceed with call, with custom timeouts
+   * and early media
+   *
+   * @param callId                 callId for the call
+   * @param context                Call service context
+   * @param eglBase                eglBase to use for this Call
+   * @param audioProcessingMethod  the method to use for audio processing
+   * @param useOboe                whether to use the oboe-based audio device module, otherwise use java
+   * @param localSink              local video sink to use for this Call
+   * @param remoteSink             remote video sink to use for this Call
+   * @param camera                 camera control to use for this Call
+   * @param iceServers             list of ICE servers to use for this Call
+   * @param hideIp                 if true hide caller's IP by using a TURN server
+   * @param dataMode               desired data mode to start the session with
+   * @param audioLevelsIntervalMs  if greater than 0, enable audio levels with this interval (in milliseconds)
+   * @param enableCamera           if true, enable the local camera video track when created
+   * @param ringTimeoutMs          if greater than 0, how long the call may go unaccepted, from when it was created (1 second to 5 minutes)
+   * @param connectTimeoutMs       if greater than 0, how long the call may take to connect after proceeding (1 second to 2 minutes)
+   * @param earlyMedia             if true, play audio the callee sends before accepting, such as a custom ringback tone (outgoing calls only)
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void proceed(@NonNull  CallId                         callId,
+                      @NonNull  Context                        context,
+                      @NonNull  EglBase                        eglBase,
+                                AudioProcessingMethod          audioProcessingMethod,
+                                boolean                        useOboe,
+                      @NonNull  VideoSink                      localSink,
+                      @NonNull  VideoSink                      remoteSink,
+                      @NonNull  CameraControl                  camera,
+                      @NonNull  List<PeerConnection.IceServer> iceServers,
+                                boolean                        hideIp,
+                                DataMode                       dataMode,
+                      @Nullable Integer                        audioLevelsIntervalMs,
+                                boolean                        enableCamera,
+                      @Nullable Integer                        ringTimeoutMs,
+                      @Nullable Integer                        connectTimeoutMs,
+                                boolean                        earlyMedia)
+    throws CallException
+  {
+    proceed(callId, context, eglBase, audioProcessingMethod, useOboe, localSink, remoteSink, camera, iceServers, hideIp, dataMode, audioLevelsIntervalMs, enableCamera, ringTimeoutMs, connectTimeoutMs, earlyMedia, null, false);
+  }
+
+  /**
+   *
+   * Indication from application to proceed with call, with custom timeouts,
+   * early media, and a media stall watchdog
+   *
+   * @param callId                 callId for the call
+   * @param context                Call service context
+   * @param eglBase                eglBase to use for this Call
+   * @param audioProcessingMethod  the method to use for audio processing
+   * @param useOboe                whether to use the oboe-based audio device module, otherwise use java
+   * @param localSink              local video sink to use for this Call
+   * @param remoteSink             remote video sink to use for this Call
+   * @param camera                 camera control to use for this Call
+   * @param iceServers             list of ICE servers to use for this Call
+   * @param hideIp                 if true hide caller's IP by using a TURN server
+   * @param dataMode               desired data mode to start the session with
+   * @param audioLevelsIntervalMs  if greater than 0, enable audio levels with this interval (in milliseconds)
+   * @param enableCamera           if true, enable the local camera video track when created
+   * @param ringTimeoutMs          if greater than 0, how long the call may go unaccepted, from when it was created (1 second to 5 minutes)
+   * @param connectTimeoutMs       if greater than 0, how long the call may take to connect after proceeding (1 second to 2 minutes)
+   * @param earlyMedia             if true, play audio the callee sends before accepting, such as a custom ringback tone (outgoing calls only)
+   * @param mediaStallTimeoutMs    if not null, report MEDIA_STALLED when nothing is received for this long while connected (3 seconds to 2 minutes)
+   * @param hangUpOnMediaStall     if true, end the call with ENDED_MEDIA_STALLED once its media stalls
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void proceed(@NonNull  CallId                         callId,
+                      @NonNull  Context                        context,
+                      @NonNull  EglBase                        eglBase,
+                                AudioProcessingMethod          audioProcessingMethod,
+                                boolean                        useOboe,
+                      @NonNull  VideoSink                      localSink,
+                      @NonNull  VideoSink                      remoteSink,
+                      @NonNull  CameraControl                  camera,
+                      @NonNull  List<PeerConnection.IceServer> iceServers,
+                                boolean                        hideIp,
+                                DataMode                       dataMode,
+                      @Nullable Integer                        audioLevelsIntervalMs,
+                                boolean                        enableCamera,
+                      @Nullable Integer                        ringTimeoutMs,
+                      @Nullable Integer                        connectTimeoutMs,
+                                boolean                        earlyMedia,
+                      @Nullable Integer                        mediaStallTimeoutMs,
+                                boolean                        hangUpOnMediaStall)
+    throws CallException
   {
     checkCallManagerExists();
 
@@ -426,7 +688,9 @@ public class CallManager {
       }
     }
 
-    PeerConnectionFactory factory = this.createPeerConnectionFactory(eglBase, audioProcessingMethod, useOboe);
+    // Hand over the local preview, if any, so the camera keeps running.
+    LocalPreview preview = takeLocalPreview(camera);
+    PeerConnectionFactory factory = preview != null ? preview.factory : this.createPeerConnectionFactory(eglBase, audioProcessingMethod, useOboe);
 
     CallContext callContext = new CallContext(callId,
                                               context,
@@ -435,16 +699,98 @@ public class CallManager {
                                               remoteSink,
                                               camera,
                                               iceServers,
-                                              hideIp);
+                                              hideIp,
+                                              preview);
 
     callContext.setVideoEnabled(enableCamera);
 
     int audioLevelsIntervalMillis = audioLevelsIntervalMs == null ? 0 : audioLevelsIntervalMs.intValue();
+    int ringTimeoutMillis = ringTimeoutMs == null ? 0 : ringTimeoutMs.intValue();
+    int connectTimeoutMillis = connectTimeoutMs == null ? 0 : connectTimeoutMs.intValue();
+    int mediaStallTimeoutMillis = mediaStallTimeoutMs == null ? 0 : mediaStallTimeoutMs.intValue();
     ringrtcProceed(nativeCallManager,
                    callId.longValue(),
                    callContext,
                    dataMode.ordinal(),
-                   audioLevelsIntervalMillis);
+                   audioLevelsIntervalMillis,
+                   hideIp,
+                   ringTimeoutMillis,
+                   connectTimeoutMillis,
+                   earlyMedia,
+                   mediaStallTimeoutMillis,
+                   hangUpOnMediaStall);
+  }
+
+  /**
+   *
+   * Starts capturing from the local camera before a call is accepted,
+   * for example to show a self-view on the incoming call screen.
+   *
+   * <p>Frames are delivered to the given localSink. No peer connection
+   * is created. If proceed() is later called with the same camera, the
+   * capturer is handed over to the call without restarting it.
+   * Otherwise the preview must be stopped with stopLocalPreview().
+   *
+   * @param eglBase                eglBase to use for the preview and the Call
+   * @param audioProcessingMethod  the method to use for audio processing
+   * @param useOboe                whether to use the oboe-based audio device module, otherwise use java
+   * @param localSink              local video sink to receive the preview
+   * @param camera                 camera control to capture from
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void startLocalPreview(@NonNull EglBase               eglBase,
+                                         AudioProcessingMethod audioProcessingMethod,
+                                         boolean               useOboe,
+                                @NonNull VideoSink             localSink,
+                                @NonNull CameraControl         camera)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "startLocalPreview():");
+
+    stopLocalPreview();
+
+    if (!camera.hasCapturer()) {
+      Log.w(TAG, "startLocalPreview(): camera has no capturer");
+      return;
+    }
+
+    PeerConnectionFactory factory = this.createPeerConnectionFactory(eglBase, audioProcessingMethod, useOboe);
+    this.localPreview = new LocalPreview(factory, localSink, camera);
+  }
+
+  /**
+   *
+   * Stops the local preview started with startLocalPreview(), if it
+   * was not handed over to a call.
+   *
+   */
+  public void stopLocalPreview() {
+    if (this.localPreview != null) {
+      Log.i(TAG, "stopLocalPreview():");
+      this.localPreview.dispose();
+      this.localPreview = null;
+    }
+  }
+
+  @Nullable
+  private LocalPreview takeLocalPreview(@NonNull CameraControl camera) {
+    if (this.localPreview == null) {
+      return null;
+    }
+
+    if (this.localPreview.cameraControl != camera) {
+      Log.w(TAG, "takeLocalPreview(): preview uses a different camera, discarding it");
+      stopLocalPreview();
+      return null;
+    }
+
+    LocalPreview preview = this.localPreview;
+    this.localPreview = null;
+    return preview;
   }
 
   /**
@@ -763,11 +1109,385 @@ public class CallManager {
    */
   public void acceptCall(@NonNull CallId callId)
     throws CallException
+  {
+    acceptCall(callId, false, false);
+  }
+
+  /**
+   *
+   * Indication from application to accept the active call with the
+   * microphone muted and/or the camera off from the start.
+   *
+   * The local media is disabled before the call is accepted and the
+   * remote peer is told about it along with the accept, so there is
+   * no need to call setAudioEnable() or setVideoEnable() afterwards.
+   *
+   * @param callId         callId for the call
+   * @param audioMuted     if true, join with the microphone muted
+   * @param videoDisabled  if true, join with the camera off
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void acceptCall(@NonNull CallId callId, boolean audioMuted, boolean videoDisabled)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "accept(): " + callId + ", audioMuted: " + audioMuted + ", videoDisabled: " + videoDisabled);
+    if (audioMuted) {
+      Connection connection = ringrtcGetActiveConnection(nativeCallManager);
+      connection.setAudioEnabled(false);
+    }
+    if (videoDisabled) {
+      CallContext callContext = ringrtcGetActiveCallContext(nativeCallManager);
+      callContext.setVideoEnabled(false);
+    }
+    ringrtcAcceptCall(nativeCallManager, callId.longValue(), audioMuted, videoDisabled);
+  }
+
+  /**
+   *
+   * Indication from application to put the active call on hold or
+   * take it off hold.
+   *
+   * While on hold, no media is sent and the remote peer receives
+   * a REMOTE_HELD event. Has no effect until the call is accepted.
+   *
+   * @param callId  callId for the call
+   * @param onHold  if true, put the call on hold, otherwise resume it
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setOnHold(@NonNull CallId callId, boolean onHold)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "setOnHold(): " + callId + ", onHold: " + onHold);
+    ringrtcSetOnHold(nativeCallManager, callId.longValue(), onHold);
+  }
+
+  /**
+   *
+   * Indication from application to move the accepted call to another
+   * device of the local user, which is then asked to take it over.
+   *
+   * The call continues on this device until the target device has
+   * connected to the remote peer, and then ends with ENDED_HANDED_OFF.
+   *
+   * @param callId          callId for the call
+   * @param remoteUserId    the UUID of the remote user of the call
+   * @param targetDeviceId  the local user's device that should take over
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void handOff(@NonNull CallId callId, @NonNull UUID remoteUserId, @NonNull Integer targetDeviceId)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "handOff(): " + callId + ", targetDeviceId: " + targetDeviceId);
+    ringrtcHandOff(nativeCallManager, callId.longValue(), Util.getBytesFromUuid(remoteUserId), targetDeviceId);
+  }
+
+  /**
+   *
+   * Restarts ICE on the active call with new credentials, e.g. after
+   * the network changed. Only takes effect once the call is accepted.
+   *
+   * @param callId  callId for the call
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void restartIce(@NonNull CallId callId)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "restartIce(): " + callId);
+    ringrtcRestartIce(nativeCallManager, callId.longValue());
+  }
+
+  /**
+   *
+   * Asks the remote peer to agree to the local side sending video on
+   * the accepted call. The answer arrives as LOCAL_VIDEO_ADD_ACCEPTED
+   * or LOCAL_VIDEO_ADD_DECLINED, and video should only be sent once
+   * accepted.
+   *
+   * @param callId  callId for the call
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void enableVideo(@NonNull CallId callId)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "enableVideo(): " + callId);
+    ringrtcEnableVideo(nativeCallManager, callId.longValue());
+  }
+
+  /**
+   *
+   * Stops sending video on the accepted call. The remote peer is told,
+   * but doesn't need to agree.
+   *
+   * @param callId  callId for the call
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void disableVideo(@NonNull CallId callId)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "disableVideo(): " + callId);
+    ringrtcDisableVideo(nativeCallManager, callId.longValue());
+  }
+
+  /**
+   *
+   * Answers the remote peer's request to send video, received as
+   * REMOTE_VIDEO_ADD_REQUESTED.
+   *
+   * @param callId    callId for the call
+   * @param accepted  if true, let the remote peer send video
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void respondToVideoRequest(@NonNull CallId callId, boolean accepted)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "respondToVideoRequest(): " + callId + ", accepted: " + accepted);
+    ringrtcRespondToVideoRequest(nativeCallManager, callId.longValue(), accepted);
+  }
+
+  /**
+   *
+   * Returns a short code derived from the keys negotiated for the call.
+   * Both parties see the same code unless someone interfered with the
+   * signaling, so users can read it aloud to each other to verify the
+   * call.
+   *
+   * @param callId  callId for the call
+   *
+   * @return the code as a string of decimal digits, or null if the call
+   *         is not active or not yet connected
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  @Nullable
+  public String getVerificationCode(@NonNull CallId callId)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "getVerificationCode(): " + callId);
+    return ringrtcGetVerificationCode(nativeCallManager, callId.longValue());
+  }
+
+  /**
+   *
+   * Starts sending audio to the caller of a ringing incoming call
+   * before accepting it, such as a custom ringback tone or IVR prompts.
+   * Ignored unless the caller enabled early media when proceeding.
+   *
+   * @param callId  callId for the call
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void startEarlyMedia(@NonNull CallId callId)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "startEarlyMedia(): " + callId);
+    ringrtcStartEarlyMedia(nativeCallManager, callId.longValue());
+  }
+
+  /**
+   *
+   * Enables or disables restarting ICE automatically when the
+   * connection of an accepted call drops, as when switching between
+   * Wi-Fi and cellular.
+   *
+   * @param enabled  if true, restart ICE when the connection drops
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setRestartIceOnDisconnect(boolean enabled)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "setRestartIceOnDisconnect(): " + enabled);
+    ringrtcSetRestartIceOnDisconnect(nativeCallManager, enabled);
+  }
+
+  /**
+   *
+   * Tells RingRTC whether the app has audio focus, as granted by
+   * AudioManager or by Telecom once the call's Connection is active.
+   * Without focus, audio playout and recording stay stopped, even
+   * after a call is accepted or a group call is joined, and they start
+   * as soon as focus is gained. Apps that never call this get audio
+   * as soon as media starts flowing.
+   *
+   * @param hasFocus  true if the app has audio focus
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setAudioFocus(boolean hasFocus)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "setAudioFocus(): " + hasFocus);
+    ringrtcSetAudioSessionActive(nativeCallManager, hasFocus);
+  }
+
+  /**
+   *
+   * Enables or disables relay-only (TURN-only) ICE for all calls, so
+   * the remote peer never learns the user's IP address, even for
+   * calls that don't set hideIp in proceed().
+   *
+   * @param enabled  if true, only use relay candidates
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setRelayOnlyByDefault(boolean enabled)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "setRelayOnlyByDefault(): " + enabled);
+    ringrtcSetRelayOnlyByDefault(nativeCallManager, enabled);
+  }
+
+  /**
+   *
+   * Limits the size of the opaque offers and answers passed to
+   * onSendOffer() and onSendAnswer(), so they fit in a push payload.
+   * Messages over the limit are compressed, and then have optional
+   * fields trimmed until they fit.
+   *
+   * @param maxSize  the maximum size in bytes, or 0 for no limit
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setMaxSignalingMessageSize(int maxSize)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "setMaxSignalingMessageSize(): " + maxSize);
+    ringrtcSetMaxSignalingMessageSize(nativeCallManager, maxSize);
+  }
+
+  /**
+   *
+   * Enables or disables call waiting.
+   *
+   * When enabled, an offer received during an accepted call is
+   * reported with {@link CallEvent#INCOMING_CALL_WAITING} instead
+   * of being declined as busy.
+   *
+   * @param enabled  if true, let a second incoming call wait
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void setCallWaitingEnabled(boolean enabled)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "setCallWaitingEnabled(): " + enabled);
+    ringrtcSetCallWaitingEnabled(nativeCallManager, enabled);
+  }
+
+  /**
+   *
+   * Puts the active call on hold and accepts the waiting call.
+   *
+   * The accepted call starts like any other incoming call and is
+   * accepted automatically once it connects.
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void holdAndAcceptWaitingCall()
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "holdAndAcceptWaitingCall():");
+    ringrtcHoldAndAcceptWaitingCall(nativeCallManager);
+  }
+
+  /**
+   *
+   * Declines the waiting call, which is reported to the caller as busy.
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void declineWaitingCall()
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "declineWaitingCall():");
+    ringrtcDeclineWaitingCall(nativeCallManager);
+  }
+
+  /**
+   *
+   * Resumes the held call. Only allowed once no other call is active.
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void resumeHeldCall()
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "resumeHeldCall():");
+    ringrtcResumeHeldCall(nativeCallManager);
+  }
+
+  /**
+   *
+   * Hangs up the held call.
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void hangupHeldCall()
+    throws CallException
   {
     checkCallManagerExists();
 
-    Log.i(TAG, "accept(): " + callId);
-    ringrtcAcceptCall(nativeCallManager, callId.longValue());
+    Log.i(TAG, "hangupHeldCall():");
+    ringrtcHangupHeldCall(nativeCallManager);
   }
 
   /**
@@ -829,6 +1549,27 @@ public class CallManager {
     ringrtcUpdateDataMode(nativeCallManager, dataMode.ordinal());
   }
 
+  /**
+   *
+   * Caps the bitrates sent in the active direct call, on top of the
+   * data mode. The remote peer applies the caps to what it sends as
+   * well.
+   *
+   * @param maxAudioBitrateBps  max audio bitrate, or 0 for no cap
+   * @param maxVideoBitrateBps  max video bitrate on top of the audio, or 0 for no cap
+   * @param maxTotalBitrateBps  max bitrate for all media, or 0 for no cap
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void updateBitrateConfig(long maxAudioBitrateBps, long maxVideoBitrateBps, long maxTotalBitrateBps)
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    ringrtcUpdateBitrateConfig(nativeCallManager, maxAudioBitrateBps, maxVideoBitrateBps, maxTotalBitrateBps);
+  }
+
   /**
    *
    * Notification from application to hangup the active call.
@@ -836,13 +1577,45 @@ public class CallManager {
    * @throws CallException for native code failures
    *
    */
-  public void hangup()
+  public void hangup()
+    throws CallException
+  {
+    checkCallManagerExists();
+
+    Log.i(TAG, "hangup():");
+    ringrtcHangup(nativeCallManager);
+  }
+
+  /**
+   *
+   * Notification from application to decline the active incoming call,
+   * telling the caller why instead of sending a normal hangup.
+   *
+   * @param callId  callId for the call
+   * @param reason  why the call is being declined
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void decline(@NonNull CallId callId, @NonNull DeclineReason reason)
     throws CallException
   {
     checkCallManagerExists();
 
-    Log.i(TAG, "hangup():");
-    ringrtcHangup(nativeCallManager);
+    Log.i(TAG, "decline(): " + callId + ", reason: " + reason);
+    ringrtcDecline(nativeCallManager, callId.longValue(), reason.ordinal());
+  }
+
+  /** Describes why the callee declined a call. */
+  public enum DeclineReason {
+    /** The callee is busy. */
+    BUSY,
+    /** The callee does not want to be disturbed. */
+    DO_NOT_DISTURB,
+    /** The callee sent a text reply instead. */
+    TEXT_REPLY_SENT,
+    /** The application declined the call, such as for a blocked contact. */
+    POLICY
   }
 
   /** Describes why a ring was cancelled. */
@@ -1105,6 +1878,86 @@ public class CallManager {
     ringrtcUpdateCallLink(nativeCallManager, sfuUrl, authCredentialPresentation, linkRootKey.getKeyBytes(), adminPasskey, null, restrictions.ordinal(), -1, requestId);
   }
 
+  /**
+   *
+   * Asynchronous request to switch a call link to a new admin passkey, so that
+   * a leaked one can be retired without recreating the link.
+   *
+   * Keep both passkeys until this succeeds, since a lost response leaves it
+   * unclear which one is in effect. If the old passkey is rejected because an
+   * earlier attempt went through, the new one is checked instead, and
+   * {@link CallLinkState#wasOldAdminPasskeyRejected} is set.
+   * <ul>
+   *   <li>On success, the new passkey is in effect and the old one can be dropped.
+   *   <li>403: neither passkey is valid any more.
+   *   <li>On any other failure, retry with the same pair of passkeys.
+   * </ul>
+   *
+   * @param sfuUrl                     the URL to use when accessing the SFU
+   * @param authCredentialPresentation a serialized CallLinkAuthCredentialPresentation
+   * @param linkRootKey                the root key for the call link
+   * @param adminPasskey               the passkey currently in effect
+   * @param newAdminPasskey            the passkey to switch to, from {@link CallLinkRootKey#generateAdminPasskey}
+   * @param handler                    a handler function which is invoked with the room's updated state, or an error status code
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void rotateCallLinkAdminPasskey(
+    @NonNull String                                     sfuUrl,
+    @NonNull byte[]                                     authCredentialPresentation,
+    @NonNull CallLinkRootKey                            linkRootKey,
+    @NonNull byte[]                                     adminPasskey,
+    @NonNull byte[]                                     newAdminPasskey,
+    @NonNull ResponseHandler<HttpResult<CallLinkState>> handler)
+    throws CallException
+  {
+    checkCallManagerExists();
+    Log.i(TAG, "rotateCallLinkAdminPasskey():");
+
+    long requestId = this.callLinkRequests.add(handler);
+    ringrtcRotateCallLinkAdminPasskey(nativeCallManager, sfuUrl, authCredentialPresentation, linkRootKey.getKeyBytes(), adminPasskey, newAdminPasskey, requestId);
+  }
+
+  /**
+   *
+   * Asynchronous request to move a call link to a newer version of its root
+   * key, by re-encrypting its name and details.
+   *
+   * Links shared with the old key keep working, since the room and key bytes
+   * don't change. Once this succeeds, store and share {@code linkRootKey} in
+   * place of the old key.
+   *
+   * Possible failure codes include:
+   * <ul>
+   *   <li>403: the admin passkey is incorrect
+   *   <li>404: the room doesn't exist (or expired so long ago that it has been removed from the server)
+   * </ul>
+   *
+   * @param sfuUrl                     the URL to use when accessing the SFU
+   * @param authCredentialPresentation a serialized CallLinkAuthCredentialPresentation
+   * @param linkRootKey                the root key for the call link, from {@link CallLinkRootKey#withLatestVersion}
+   * @param adminPasskey               the passkey specified when the link was created
+   * @param handler                    a handler function which is invoked with the room's updated state, or an error status code
+   *
+   * @throws CallException for native code failures
+   *
+   */
+  public void migrateCallLink(
+    @NonNull String                                     sfuUrl,
+    @NonNull byte[]                                     authCredentialPresentation,
+    @NonNull CallLinkRootKey                            linkRootKey,
+    @NonNull byte[]                                     adminPasskey,
+    @NonNull ResponseHandler<HttpResult<CallLinkState>> handler)
+    throws CallException
+  {
+    checkCallManagerExists();
+    Log.i(TAG, "migrateCallLink():");
+
+    long requestId = this.callLinkRequests.add(handler);
+    ringrtcMigrateCallLink(nativeCallManager, sfuUrl, authCredentialPresentation, linkRootKey.getKeyBytes(), adminPasskey, requestId);
+  }
+
   /**
    *
    * Asynchronous request to delete a call link.
@@ -1234,6 +2087,39 @@ public class CallManager {
                                              AudioProcessingMethod audioProcessingMethod,
                                              boolean               useOboe,
                                    @NonNull  GroupCall.Observer    observer)
+  {
+    return createGroupCall(groupId, sfuUrl, hkdfExtraInfo, audioLevelsIntervalMs, audioProcessingMethod, useOboe, null, false, observer);
+  }
+
+  /**
+   *
+   * Creates and returns a GroupCall object that can exchange video
+   * frames with the application through direct buffers.
+   *
+   * If there is any error when allocating resources for the object,
+   * null is returned.
+   *
+   * @param groupId                the unique identifier for the group
+   * @param sfuUrl                 the URL to use when accessing the SFU
+   * @param hkdfExtraInfo          additional entropy to use for the connection with the SFU (it's okay if this is empty)
+   * @param audioLevelsIntervalMs  if provided, the observer will receive audio level callbacks at this interval
+   * @param audioProcessingMethod  the method to use for audio processing
+   * @param useOboe                whether to use the oboe-based audio device module, otherwise use java
+   * @param directVideoSink        if provided, receives decoded remote video frames
+   * @param useDirectVideoSource   whether outgoing video is sent with GroupCall.pushVideoFrame() rather than the camera
+   * @param observer               the observer that the group call object will use for callback notifications
+   *
+   */
+  @Nullable
+  public GroupCall createGroupCall(@NonNull  byte[]                    groupId,
+                                   @NonNull  String                    sfuUrl,
+                                   @NonNull  byte[]                    hkdfExtraInfo,
+                                   @Nullable Integer                   audioLevelsIntervalMs,
+                                             AudioProcessingMethod     audioProcessingMethod,
+                                             boolean                   useOboe,
+                                   @Nullable GroupCall.DirectVideoSink directVideoSink,
+                                             boolean                   useDirectVideoSource,
+                                   @NonNull  GroupCall.Observer        observer)
   {
     checkCallManagerExists();
 
@@ -1246,7 +2132,7 @@ public class CallManager {
       }
     }
 
-    GroupCall groupCall = GroupCall.create(nativeCallManager, groupId, sfuUrl, hkdfExtraInfo, audioLevelsIntervalMs, this.groupFactory, observer);
+    GroupCall groupCall = GroupCall.create(nativeCallManager, groupId, sfuUrl, hkdfExtraInfo, audioLevelsIntervalMs, this.groupFactory, directVideoSink, useDirectVideoSource, observer);
 
     if (groupCall != null) {
       // Add the groupCall to the map.
@@ -1286,6 +2172,44 @@ public class CallManager {
                                                 AudioProcessingMethod audioProcessingMethod,
                                                 boolean               useOboe,
                                       @NonNull  GroupCall.Observer    observer)
+  {
+    return createCallLinkCall(sfuUrl, authCredentialPresentation, linkRootKey, adminPasskey, hkdfExtraInfo, audioLevelsIntervalMs, audioProcessingMethod, useOboe, null, false, observer);
+  }
+
+  /**
+   *
+   * Creates and returns a GroupCall object for a call link call that
+   * can exchange video frames with the application through direct
+   * buffers.
+   *
+   * If there is any error when allocating resources for the object,
+   * null is returned.
+   *
+   * @param sfuUrl                     the URL to use when accessing the SFU
+   * @param authCredentialPresentation a serialized CallLinkAuthCredentialPresentation
+   * @param linkRootKey                the root key for the call link
+   * @param adminPasskey               if present, the opaque passkey authorizing this user as an admin for the call link
+   * @param hkdfExtraInfo              additional entropy to use for the connection with the SFU (it's okay if this is empty)
+   * @param audioLevelsIntervalMs      if provided, the observer will receive audio level callbacks at this interval
+   * @param audioProcessingMethod      the method to use for audio processing
+   * @param useOboe                    whether to use the oboe-based audio device module, otherwise use java
+   * @param directVideoSink            if provided, receives decoded remote video frames
+   * @param useDirectVideoSource       whether outgoing video is sent with GroupCall.pushVideoFrame() rather than the camera
+   * @param observer                   the observer that the group call object will use for callback notifications
+   *
+   */
+  @Nullable
+  public GroupCall createCallLinkCall(@NonNull  String                    sfuUrl,
+                                      @NonNull  byte[]                    authCredentialPresentation,
+                                      @NonNull  CallLinkRootKey           linkRootKey,
+                                      @Nullable byte[]                    adminPasskey,
+                                      @NonNull  byte[]                    hkdfExtraInfo,
+                                      @Nullable Integer                   audioLevelsIntervalMs,
+                                                AudioProcessingMethod     audioProcessingMethod,
+                                                boolean                   useOboe,
+                                      @Nullable GroupCall.DirectVideoSink directVideoSink,
+                                                boolean                   useDirectVideoSource,
+                                      @NonNull  GroupCall.Observer        observer)
   {
     checkCallManagerExists();
 
@@ -1298,7 +2222,7 @@ public class CallManager {
       }
     }
 
-    GroupCall groupCall = GroupCall.create(nativeCallManager, sfuUrl, authCredentialPresentation, linkRootKey, adminPasskey, hkdfExtraInfo, audioLevelsIntervalMs, this.groupFactory, observer);
+    GroupCall groupCall = GroupCall.create(nativeCallManager, sfuUrl, authCredentialPresentation, linkRootKey, adminPasskey, hkdfExtraInfo, audioLevelsIntervalMs, this.groupFactory, directVideoSink, useDirectVideoSource, observer);
 
     if (groupCall != null) {
       // Add the groupCall to the map.
@@ -1326,7 +2250,8 @@ public class CallManager {
                                       int         remoteDeviceId,
                                       CallContext callContext,
                                       int         audioJitterBufferMaxPackets,
-                                      int         audioJitterBufferMaxTargetDelayMs) {
+                                      int         audioJitterBufferMaxTargetDelayMs,
+                                      boolean     relayOnly) {
 
     CallId callId = new CallId(nativeCallId);
 
@@ -1341,7 +2266,7 @@ public class CallManager {
     configuration.tcpCandidatePolicy = PeerConnection.TcpCandidatePolicy.DISABLED;
     configuration.continualGatheringPolicy = PeerConnection.ContinualGatheringPolicy.GATHER_CONTINUALLY;
 
-    if (callContext.hideIp) {
+    if (callContext.hideIp || relayOnly) {
       configuration.iceTransportsType = PeerConnection.IceTransportsType.RELAY;
     }
 
@@ -1472,10 +2397,12 @@ public class CallManager {
   }
 
   @CalledByNative
-  private void onNetworkRouteChanged(Remote remote, int localNetworkAdapterType) {
+  private void onNetworkRouteChanged(Remote remote, int localNetworkAdapterType, int localNetworkAdapterTypeUnderVpn, boolean relayed) {
     Log.i(TAG, "onNetworkRouteChange():");
 
-    NetworkRoute networkRoute = new NetworkRoute(NetworkAdapterTypeFromRawValue(localNetworkAdapterType));
+    NetworkRoute networkRoute = new NetworkRoute(NetworkAdapterTypeFromRawValue(localNetworkAdapterType),
+                                                 NetworkAdapterTypeFromRawValue(localNetworkAdapterTypeUnderVpn),
+                                                 relayed);
 
     observer.onNetworkRouteChanged(remote, networkRoute);
   }
@@ -1527,6 +2454,12 @@ public class CallManager {
     observer.onCallConcluded(remote);
   }
 
+  @CalledByNative
+  private void handoffRequested(@NonNull byte[] remoteUserId, long callId, int remoteDeviceId, CallMediaType callMediaType) {
+    Log.i(TAG, "handoffRequested():");
+    observer.onHandoffRequested(Util.getUuidFromBytes(remoteUserId), new CallId(callId), remoteDeviceId, callMediaType);
+  }
+
   @CalledByNative
   private void onSendOffer(long callId, Remote remote, int remoteDeviceId, boolean broadcast, @NonNull byte[] opaque, CallMediaType callMediaType) {
     Log.i(TAG, "onSendOffer():");
@@ -1770,6 +2703,19 @@ public class CallManager {
     groupCall.handlePeekChanged(info);
   }
 
+  @CalledByNative
+  private void handleCallFull(long clientId, @Nullable Long maxDevices, @Nullable Long deviceCount) {
+    Log.i(TAG, "handleCallFull():");
+
+    GroupCall groupCall = this.groupCallByClientId.get(clientId);
+    if (groupCall == null) {
+      Log.w(TAG, "groupCall not found by clientId: " + clientId);
+      return;
+    }
+
+    groupCall.handleCallFull(maxDevices, deviceCount);
+  }
+
   @CalledByNative
   private void handleEnded(long clientId, GroupCall.GroupCallEndReason reason) {
     Log.i(TAG, "handleEnded():");
@@ -1828,7 +2774,8 @@ public class CallManager {
                        @NonNull VideoSink                      remoteSink,
                        @NonNull CameraControl                  camera,
                        @NonNull List<PeerConnection.IceServer> iceServers,
-                                boolean                        hideIp) {
+                                boolean                        hideIp,
+                       @Nullable LocalPreview                  preview) {
 
       Log.i(TAG, "ctor(): " + callId);
 
@@ -1841,7 +2788,17 @@ public class CallManager {
 
       // Create a video track that will be shared across all
       // connection objects.  It must be disposed manually.
-      if (cameraControl.hasCapturer()) {
+      if (preview != null) {
+        // Take over the already running preview capturer.
+        this.videoSource = preview.videoSource;
+        this.videoTrack  = preview.videoTrack;
+        videoTrack.setEnabled(false);
+
+        if (preview.localSink != localSink) {
+          videoTrack.removeSink(preview.localSink);
+          videoTrack.addSink(localSink);
+        }
+      } else if (cameraControl.hasCapturer()) {
         this.videoSource = factory.createVideoSource(false);
         // Note: This must stay "video1" to stay in sync with V4 signaling.
         this.videoTrack  = factory.createVideoTrack("video1", videoSource);
@@ -1884,6 +2841,53 @@ public class CallManager {
     }
   }
 
+  /**
+   *
+   * A local camera preview running before a call is accepted.
+   *
+   */
+  static class LocalPreview {
+
+    @NonNull  private final String TAG = CallManager.LocalPreview.class.getSimpleName();
+    /** Connection factory, handed over to the call */
+    @NonNull  public final  PeerConnectionFactory factory;
+    /** Local camera surface renderer */
+    @NonNull  public final  VideoSink             localSink;
+    /** Camera controller */
+    @NonNull  public final  CameraControl         cameraControl;
+    @NonNull  public final  VideoSource           videoSource;
+    @NonNull  public final  VideoTrack            videoTrack;
+
+    public LocalPreview(@NonNull PeerConnectionFactory factory,
+                        @NonNull VideoSink             localSink,
+                        @NonNull CameraControl         camera) {
+
+      Log.i(TAG, "ctor():");
+
+      this.factory       = factory;
+      this.localSink     = localSink;
+      this.cameraControl = camera;
+
+      this.videoSource = factory.createVideoSource(false);
+      // Note: This must stay "video1" to stay in sync with V4 signaling.
+      this.videoTrack  = factory.createVideoTrack("video1", videoSource);
+      videoTrack.setEnabled(true);
+
+      cameraControl.initCapturer(videoSource.getCapturerObserver());
+      videoTrack.addSink(localSink);
+      cameraControl.setEnabled(true);
+    }
+
+    void dispose() {
+      Log.i(TAG, "dispose():");
+
+      cameraControl.setEnabled(false);
+      videoSource.dispose();
+      videoTrack.dispose();
+      factory.dispose();
+    }
+  }
+
   /**
    *
    * Enumeration of simple call status events
@@ -1981,7 +2985,64 @@ public class CallManager {
     RECEIVED_OFFER_WHILE_ACTIVE,
 
     /** Received an offer while already handling an active call and glare was detected. */
-    RECEIVED_OFFER_WITH_GLARE;
+    RECEIVED_OFFER_WITH_GLARE,
+
+    /** The remote peer has put the call on hold and stopped sending media. */
+    REMOTE_HELD,
+
+    /** The remote peer has taken the call off hold. */
+    REMOTE_RESUMED,
+
+    /** Received an offer while in an accepted call with call waiting enabled. */
+    INCOMING_CALL_WAITING,
+
+    /** The call ended because the callee declined it as busy. */
+    ENDED_REMOTE_DECLINED_BUSY,
+
+    /** The call ended because the callee declined it as do-not-disturb. */
+    ENDED_REMOTE_DECLINED_DO_NOT_DISTURB,
+
+    /** The call ended because the callee declined it and sent a text reply. */
+    ENDED_REMOTE_DECLINED_TEXT_REPLY_SENT,
+
+    /** The remote peer moved the call to another of its devices. */
+    REMOTE_HANDED_OFF,
+
+    /** The call ended because another device of the local user took it over. */
+    ENDED_HANDED_OFF,
+
+    /** The callee started sending early media, which is played while ringing. */
+    REMOTE_EARLY_MEDIA,
+
+    /** The remote peer asks to send video; answer with respondToVideoRequest(). */
+    REMOTE_VIDEO_ADD_REQUESTED,
+
+    /** The remote peer started sending video after the local side agreed. */
+    REMOTE_VIDEO_ADDED,
+
+    /** The remote peer stopped sending video. */
+    REMOTE_VIDEO_REMOVED,
+
+    /** The remote peer agreed to receive video, which can now be sent. */
+    LOCAL_VIDEO_ADD_ACCEPTED,
+
+    /** The remote peer declined to receive video. */
+    LOCAL_VIDEO_ADD_DECLINED,
+
+    /** Nothing has been received from the remote peer for the media stall timeout. */
+    MEDIA_STALLED,
+
+    /** The call ended because nothing was received for the media stall timeout. */
+    ENDED_MEDIA_STALLED,
+
+    /** The call ended because the remote peer lost its connection. */
+    ENDED_REMOTE_HANGUP_NETWORK_FAILURE,
+
+    /** The call ended because the callee's application declined it by policy. */
+    ENDED_REMOTE_DECLINED_BY_POLICY,
+
+    /** The call ended because the remote peer stopped ringing without an answer. */
+    ENDED_REMOTE_HANGUP_RING_TIMEOUT;
 
     @CalledByNative
     static CallEvent fromNativeIndex(int nativeIndex) {
@@ -2028,7 +3089,28 @@ public class CallManager {
     BUSY,
 
     /** Call needed permission on a different device. */
-    NEED_PERMISSION;
+    NEED_PERMISSION,
+
+    /** Call was declined by the callee as busy. */
+    DECLINED_BUSY,
+
+    /** Call was declined by the callee as do-not-disturb. */
+    DECLINED_DO_NOT_DISTURB,
+
+    /** Call was declined by the callee, who sent a text reply. */
+    DECLINED_TEXT_REPLY_SENT,
+
+    /** Call was taken over by another device of the same user. */
+    HANDED_OFF,
+
+    /** Call ended because the sender lost its connection. */
+    NETWORK_FAILURE,
+
+    /** Call was declined by the callee's application by policy. */
+    DECLINED_BY_POLICY,
+
+    /** Call ended because nobody answered before the ring timeout. */
+    RING_TIMEOUT;
 
     @CalledByNative
     static HangupType fromNativeIndex(int nativeIndex) {
@@ -2084,6 +3166,20 @@ public class CallManager {
     }
   }
 
+  /**
+   *
+   * How much RingRTC logs; see {@link #setMaxLogLevel}
+   *
+   */
+  public enum LogLevel {
+    OFF,
+    ERROR,
+    WARN,
+    INFO,
+    DEBUG,
+    TRACE,
+  }
+
   public enum CallMessageUrgency {
     DROPPABLE,
     HANDLE_IMMEDIATELY,
@@ -2177,6 +3273,19 @@ public class CallManager {
      */
     void onCallConcluded(Remote remote);
 
+    /**
+     *
+     * Another device of the local user asked this device to take over
+     * its accepted call, using takeOverCall().
+     *
+     * @param remoteUserId    the UUID of the remote user of the call
+     * @param callId          callId of the call to take over
+     * @param remoteDeviceId  the device the remote user is on
+     * @param callMediaType   the media type of the call
+     *
+     */
+    void onHandoffRequested(@NonNull UUID remoteUserId, @NonNull CallId callId, int remoteDeviceId, @NonNull CallMediaType callMediaType);
+
     /**
      *
      * Notification that an offer is ready to be sent
@@ -2315,6 +3424,24 @@ public class CallManager {
     void ringrtcInitialize()
     throws CallException;
 
+  private static native
+    void ringrtcSetMaxLogLevel(int level)
+    throws CallException;
+
+  private static native
+    void ringrtcSetModuleLogLevel(String module, int level)
+    throws CallException;
+
+  private static native
+    void ringrtcClearModuleLogLevels();
+
+  private static native
+    byte[] ringrtcExportDiagnostics();
+
+  private static native
+    void ringrtcSetCrashDumpPath(String path)
+    throws CallException;
+
   private static native
     long ringrtcCreateCallManager(CallManager callManager)
     throws CallException;
@@ -2334,12 +3461,22 @@ public class CallManager {
     void ringrtcCall(long nativeCallManager, Remote remote, int callMediaType, int localDeviceId)
     throws CallException;
 
+  private native
+    void ringrtcTakeOverCall(long nativeCallManager, Remote remote, long callId, int callMediaType, int localDeviceId)
+    throws CallException;
+
   private native
     void ringrtcProceed(long        nativeCallManager,
                         long        callId,
                         CallContext callContext,
                         int         dataMode,
-                        int         audioLevelsIntervalMillis)
+                        int         audioLevelsIntervalMillis,
+                        boolean     hideIp,
+                        int         ringTimeoutMillis,
+                        int         connectTimeoutMillis,
+                        boolean     earlyMedia,
+                        int         mediaStallTimeoutMillis,
+                        boolean     hangUpOnMediaStall)
     throws CallException;
 
   private native
@@ -2354,6 +3491,10 @@ public class CallManager {
     void ringrtcHangup(long nativeCallManager)
     throws CallException;
 
+  private native
+    void ringrtcDecline(long nativeCallManager, long callId, int reason)
+    throws CallException;
+
   private native
     void ringrtcCancelGroupRing(long nativeCallManager, byte[] groupId, long ringId, int reason)
     throws CallException;
@@ -2423,7 +3564,75 @@ public class CallManager {
     throws CallException;
 
   private native
-    void ringrtcAcceptCall(long nativeCallManager, long callId)
+    void ringrtcAcceptCall(long nativeCallManager, long callId, boolean audioMuted, boolean videoDisabled)
+    throws CallException;
+
+  private native
+    void ringrtcSetOnHold(long nativeCallManager, long callId, boolean onHold)
+    throws CallException;
+
+  private native
+    void ringrtcHandOff(long nativeCallManager, long callId, byte[] remoteUserId, int targetDeviceId)
+    throws CallException;
+
+  private native
+    void ringrtcRestartIce(long nativeCallManager, long callId)
+    throws CallException;
+
+  private native
+    void ringrtcStartEarlyMedia(long nativeCallManager, long callId)
+    throws CallException;
+
+  private native
+    void ringrtcEnableVideo(long nativeCallManager, long callId)
+    throws CallException;
+
+  private native
+    void ringrtcDisableVideo(long nativeCallManager, long callId)
+    throws CallException;
+
+  private native
+    void ringrtcRespondToVideoRequest(long nativeCallManager, long callId, boolean accepted)
+    throws CallException;
+
+  private native
+    String ringrtcGetVerificationCode(long nativeCallManager, long callId)
+    throws CallException;
+
+  private native
+    void ringrtcSetRestartIceOnDisconnect(long nativeCallManager, boolean enabled)
+    throws CallException;
+
+  private native
+    void ringrtcSetAudioSessionActive(long nativeCallManager, boolean active)
+    throws CallException;
+
+  private native
+    void ringrtcSetRelayOnlyByDefault(long nativeCallManager, boolean enabled)
+    throws CallException;
+
+  private native
+    void ringrtcSetMaxSignalingMessageSize(long nativeCallManager, int maxSize)
+    throws CallException;
+
+  private native
+    void ringrtcSetCallWaitingEnabled(long nativeCallManager, boolean enabled)
+    throws CallException;
+
+  private native
+    void ringrtcHoldAndAcceptWaitingCall(long nativeCallManager)
+    throws CallException;
+
+  private native
+    void ringrtcDeclineWaitingCall(long nativeCallManager)
+    throws CallException;
+
+  private native
+    void ringrtcResumeHeldCall(long nativeCallManager)
+    throws CallException;
+
+  private native
+    void ringrtcHangupHeldCall(long nativeCallManager)
     throws CallException;
 
   private native
@@ -2446,6 +3655,13 @@ public class CallManager {
     void ringrtcUpdateDataMode(long nativeCallManager, int dataMode)
     throws CallException;
 
+  private native
+    void ringrtcUpdateBitrateConfig(long nativeCallManager,
+                                    long maxAudioBitrateBps,
+                                    long maxVideoBitrateBps,
+                                    long maxTotalBitrateBps)
+    throws CallException;
+
   private native
     void ringrtcDrop(long nativeCallManager, long callId)
     throws CallException;
@@ -2506,6 +3722,25 @@ public class CallManager {
                                long   requestId)
     throws CallException;
 
+  private native
+    void ringrtcRotateCallLinkAdminPasskey(long   nativeCallManager,
+                                           String sfuUrl,
+                                           byte[] authCredentialPresentation,
+                                           byte[] rootKeyBytes,
+                                           byte[] adminPasskey,
+                                           byte[] newAdminPasskey,
+                                           long   requestId)
+    throws CallException;
+
+  private native
+    void ringrtcMigrateCallLink(long   nativeCallManager,
+                                String sfuUrl,
+                                byte[] authCredentialPresentation,
+                                byte[] rootKeyBytes,
+                                byte[] adminPasskey,
+                                long   requestId)
+    throws CallException;
+
   private native
     void ringrtcPeekCallLinkCall(long   nativeCallManager,
                                  long   requestId,
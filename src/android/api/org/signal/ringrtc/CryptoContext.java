@@ -0,0 +1,108 @@
+/*
+ * Copyright 2024 Signal Messenger, LLC
+ * SPDX-License-Identifier: AGPL-3.0-only
+ */
+
+package org.signal.ringrtc;
+
+import androidx.annotation.NonNull;
+
+/**
+ * The frame encryption used by group calls, for media that doesn't go through
+ * RingRTC's own pipeline.
+ *
+ * <p>Each instance owns native state, released by {@link #close()}. Methods
+ * may be called from any thread and are serialized natively, but must not be
+ * called concurrently with or after {@link #close()}.
+ */
+public final class CryptoContext implements AutoCloseable {
+  /** The size of send and receive secrets, in bytes. */
+  public static final int SECRET_SIZE_BYTES = 32;
+  /** The size of the MAC sent with each frame, in bytes. */
+  public static final int MAC_SIZE_BYTES = 16;
+
+  /** What has to be sent along with an encrypted frame. */
+  public static final class EncryptedFrame {
+    public final byte   ratchetCounter;
+    public final long   frameCounter;
+    @NonNull
+    public final byte[] mac;
+
+    @CalledByNative
+    EncryptedFrame(byte ratchetCounter, long frameCounter, @NonNull byte[] mac) {
+      this.ratchetCounter = ratchetCounter;
+      this.frameCounter   = frameCounter;
+      this.mac            = mac;
+    }
+  }
+
+  /** The secret receivers need to decrypt frames, as of a ratchet counter. */
+  public static final class SendState {
+    public final byte   ratchetCounter;
+    @NonNull
+    public final byte[] secret;
+
+    @CalledByNative
+    SendState(byte ratchetCounter, @NonNull byte[] secret) {
+      this.ratchetCounter = ratchetCounter;
+      this.secret         = secret;
+    }
+  }
+
+  private long nativeContext;
+
+  public CryptoContext(@NonNull byte[] initialSendSecret) throws CallException {
+    this.nativeContext = nativeCreate(initialSendSecret);
+  }
+
+  /** Encrypts {@code data} in place. */
+  @NonNull
+  public EncryptedFrame encrypt(@NonNull byte[] data) throws CallException {
+    return nativeEncrypt(nativeContext, data);
+  }
+
+  /**
+   * Decrypts {@code data} in place, returning false (and leaving it untouched)
+   * if no secret from {@code senderId} matches.
+   */
+  public boolean decrypt(long senderId, byte ratchetCounter, long frameCounter, @NonNull byte[] data, @NonNull byte[] mac) throws CallException {
+    return nativeDecrypt(nativeContext, senderId, ratchetCounter, frameCounter, data, mac);
+  }
+
+  public void addReceiveSecret(long senderId, byte ratchetCounter, @NonNull byte[] secret) throws CallException {
+    nativeAddReceiveSecret(nativeContext, senderId, ratchetCounter, secret);
+  }
+
+  @NonNull
+  public SendState getSendState() throws CallException {
+    return nativeGetSendState(nativeContext);
+  }
+
+  /** Ratchets the send secret forward, for when a receiver is added. */
+  @NonNull
+  public SendState advanceSendRatchet() throws CallException {
+    return nativeAdvanceSendRatchet(nativeContext);
+  }
+
+  /** Replaces the send secret, for when a receiver is removed. */
+  public void resetSendRatchet(@NonNull byte[] secret) throws CallException {
+    nativeResetSendRatchet(nativeContext, secret);
+  }
+
+  @Override
+  public void close() {
+    long context = nativeContext;
+    nativeContext = 0;
+    nativeDestroy(context);
+  }
+
+  // Native-only methods.
+  private static native long nativeCreate(byte[] initialSendSecret) throws CallException;
+  private static native void nativeDestroy(long context);
+  private static native EncryptedFrame nativeEncrypt(long context, byte[] data) throws CallException;
+  private static native boolean nativeDecrypt(long context, long senderId, byte ratchetCounter, long frameCounter, byte[] data, byte[] mac) throws CallException;
+  private static native void nativeAddReceiveSecret(long context, long senderId, byte ratchetCounter, byte[] secret) throws CallException;
+  private static native SendState nativeGetSendState(long context) throws CallException;
+  private static native SendState nativeAdvanceSendRatchet(long context) throws CallException;
+  private static native void nativeResetSendRatchet(long context, byte[] secret) throws CallException;
+}
@@ -5,9 +5,12 @@
 
 package org.signal.ringrtc;
 
+import android.hardware.HardwareBuffer;
+import android.os.Build;
 import android.util.LongSparseArray;
 import androidx.annotation.NonNull;
 import androidx.annotation.Nullable;
+import androidx.annotation.RequiresApi;
 
 import org.webrtc.AudioSource;
 import org.webrtc.AudioTrack;
@@ -22,6 +25,7 @@ import org.webrtc.VideoSink;
 import org.webrtc.VideoSource;
 import org.webrtc.VideoTrack;
 
+import java.nio.ByteBuffer;
 import java.util.ArrayList;
 import java.util.Collection;
 import java.util.List;
@@ -70,6 +74,10 @@ public final class GroupCall {
     @Nullable private VideoTrack                         outgoingVideoTrack;
     @NonNull  private ArrayList<VideoTrack>              incomingVideoTracks;
 
+    // Owned pointer to the RingRTC video source fed by pushVideoFrame(),
+    // or 0 if the call sends video from outgoingVideoSource.
+              private long                               nativeDirectVideoSource;
+
     private GroupCall(@NonNull  Kind                  kind,
                                 long                  nativeCallManager,
                       @NonNull  PeerConnectionFactory factory,
@@ -131,6 +139,8 @@ public final class GroupCall {
                             @NonNull  byte[]                hkdfExtraInfo,
                             @Nullable Integer               audioLevelsIntervalMs,
                             @NonNull  PeerConnectionFactory factory,
+                            @Nullable DirectVideoSink       directVideoSink,
+                                      boolean               useDirectVideoSource,
                             @NonNull  Observer              observer) {
         Log.i(TAG, "create():");
 
@@ -138,6 +148,7 @@ public final class GroupCall {
 
         int audioLevelsIntervalMillis = audioLevelsIntervalMs == null ? 0 : audioLevelsIntervalMs.intValue();
         try {
+            long nativeVideoTrack = call.getNativeOutgoingVideoTrack(useDirectVideoSource);
             call.clientId = ringrtcCreateGroupCallClient(
                 nativeCallManager,
                 groupId,
@@ -148,8 +159,9 @@ public final class GroupCall {
                 factory.getNativePeerConnectionFactory(),
                 // Returns a borrowed RC.
                 call.outgoingAudioTrack.getNativeAudioTrack(),
-                // Returns a borrowed RC.
-                call.outgoingVideoTrack.getNativeVideoTrack());
+                // A borrowed RC.
+                nativeVideoTrack,
+                directVideoSink);
             if (call.clientId == INVALID_CLIENT_ID) {
                 call.dispose();
                 return null;
@@ -175,6 +187,8 @@ public final class GroupCall {
                             @NonNull  byte[]                hkdfExtraInfo,
                             @Nullable Integer               audioLevelsIntervalMs,
                             @NonNull  PeerConnectionFactory factory,
+                            @Nullable DirectVideoSink       directVideoSink,
+                                      boolean               useDirectVideoSource,
                             @NonNull  Observer              observer) {
         Log.i(TAG, "create():");
 
@@ -182,6 +196,7 @@ public final class GroupCall {
 
         int audioLevelsIntervalMillis = audioLevelsIntervalMs == null ? 0 : audioLevelsIntervalMs.intValue();
         try {
+            long nativeVideoTrack = call.getNativeOutgoingVideoTrack(useDirectVideoSource);
             call.clientId = ringrtcCreateCallLinkCallClient(
                 nativeCallManager,
                 sfuUrl,
@@ -194,8 +209,9 @@ public final class GroupCall {
                 factory.getNativePeerConnectionFactory(),
                 // Returns a borrowed RC.
                 call.outgoingAudioTrack.getNativeAudioTrack(),
-                // Returns a borrowed RC.
-                call.outgoingVideoTrack.getNativeVideoTrack());
+                // A borrowed RC.
+                nativeVideoTrack,
+                directVideoSink);
             if (call.clientId == INVALID_CLIENT_ID) {
                 call.dispose();
                 return null;
@@ -208,6 +224,20 @@ public final class GroupCall {
         return call;
     }
 
+    /**
+     * Returns a borrowed RC to the video track to send, creating the
+     * RingRTC video source for pushVideoFrame() if asked to.
+     */
+    private long getNativeOutgoingVideoTrack(boolean useDirectVideoSource)
+        throws CallException
+    {
+        if (!useDirectVideoSource) {
+            return this.outgoingVideoTrack.getNativeVideoTrack();
+        }
+        this.nativeDirectVideoSource = ringrtcCreateDirectVideoSource(factory.getNativePeerConnectionFactory());
+        return ringrtcGetDirectVideoTrack(this.nativeDirectVideoSource);
+    }
+
     /**
      * Releases native resources belonging to the object.
      */
@@ -231,6 +261,11 @@ public final class GroupCall {
             this.outgoingVideoTrack = null;
         }
 
+        if (this.nativeDirectVideoSource != 0) {
+            ringrtcDeleteDirectVideoSource(this.nativeDirectVideoSource);
+            this.nativeDirectVideoSource = 0;
+        }
+
         for (VideoTrack incomingTrack : incomingVideoTracks) {
             incomingTrack.dispose();
         }
@@ -287,7 +322,7 @@ public final class GroupCall {
 
         // When leaving, make sure outgoing media is stopped as soon as possible.
         this.outgoingAudioTrack.setEnabled(false);
-        this.outgoingVideoTrack.setEnabled(false);
+        this.setOutgoingVideoEnabled(false);
 
         ringrtcLeave(nativeCallManager, this.clientId);
     }
@@ -316,7 +351,7 @@ public final class GroupCall {
             } else {
                 // When disconnecting, make sure outgoing media is stopped as soon as possible.
                 this.outgoingAudioTrack.setEnabled(false);
-                this.outgoingVideoTrack.setEnabled(false);
+                this.setOutgoingVideoEnabled(false);
 
                 // The handleEnded() callback has not been called, so we can invoke
                 // the RingRTC API to handle the disconnect, and resources will be
@@ -396,11 +431,80 @@ public final class GroupCall {
         Log.i(TAG, "setOutgoingVideoMuted():");
 
         this.localDeviceState.videoMuted = muted;
-        this.outgoingVideoTrack.setEnabled(!this.localDeviceState.videoMuted);
+        this.setOutgoingVideoEnabled(!this.localDeviceState.videoMuted);
 
         ringrtcSetOutgoingVideoMuted(nativeCallManager, this.clientId, muted);
     }
 
+    private void setOutgoingVideoEnabled(boolean enabled)
+        throws CallException
+    {
+        this.outgoingVideoTrack.setEnabled(enabled);
+        if (this.nativeDirectVideoSource != 0) {
+            ringrtcSetDirectVideoEnabled(this.nativeDirectVideoSource, enabled);
+        }
+    }
+
+    /**
+     *
+     * Sends a video frame read from a direct ByteBuffer of tightly
+     * packed pixels. Only for calls created to use a direct video
+     * source. The buffer can be reused once this returns.
+     *
+     * @param buffer         a direct ByteBuffer holding the frame
+     * @param width          the width of the frame in pixels
+     * @param height         the height of the frame in pixels
+     * @param format         the layout of the pixels in the buffer
+     * @param rotation       the clockwise rotation of the frame in degrees
+     *
+     * @throws CallException for native code failures
+     *
+     */
+    public void pushVideoFrame(@NonNull ByteBuffer  buffer,
+                                        int         width,
+                                        int         height,
+                               @NonNull PixelFormat format,
+                                        int         rotation)
+        throws CallException
+    {
+        if (!buffer.isDirect()) {
+            throw new CallException("pushVideoFrame() needs a direct ByteBuffer");
+        }
+        ringrtcPushVideoFrameBuffer(this.checkDirectVideoSource(), buffer, width, height, format.ordinal(), rotation);
+    }
+
+    /**
+     *
+     * Sends a video frame read from an RGBA HardwareBuffer that can be
+     * read by the CPU. Only for calls created to use a direct video
+     * source. The buffer can be reused once this returns.
+     *
+     * @param buffer         the HardwareBuffer holding the frame
+     * @param rotation       the clockwise rotation of the frame in degrees
+     *
+     * @throws CallException for native code failures
+     *
+     */
+    @RequiresApi(Build.VERSION_CODES.O)
+    public void pushVideoFrame(@NonNull HardwareBuffer buffer,
+                                        int            rotation)
+        throws CallException
+    {
+        if (Build.VERSION.SDK_INT < Build.VERSION_CODES.O) {
+            throw new CallException("HardwareBuffer frames need API level 26 or later");
+        }
+        ringrtcPushVideoFrameHardwareBuffer(this.checkDirectVideoSource(), buffer, rotation);
+    }
+
+    private long checkDirectVideoSource()
+        throws CallException
+    {
+        if (this.nativeDirectVideoSource == 0) {
+            throw new CallException("The group call was not created with a direct video source");
+        }
+        return this.nativeDirectVideoSource;
+    }
+
     /**	
      *
      * Links the camera to the outgoing video track.
@@ -826,6 +930,18 @@ public final class GroupCall {
         this.observer.onPeekChanged(this);
     }
 
+    /**
+     *
+     * Callback from RingRTC when joining failed because the call is full,
+     * right before the group call ends. Called via the CallManager.
+     *
+     */
+    void handleCallFull(@Nullable Long maxDevices, @Nullable Long deviceCount) {
+        Log.i(TAG, "handleCallFull():");
+
+        this.observer.onCallFull(this, maxDevices, deviceCount);
+    }
+
     /**
      *
      * Callback from RingRTC when the group call ends. Called via the
@@ -907,7 +1023,9 @@ public final class GroupCall {
         /** The user was speaking, and is not anymore. */
         STOPPED_SPEAKING,
         /** The user has been speaking for long enough that they may want to lower their hand. */
-        LOWER_HAND_SUGGESTION;
+        LOWER_HAND_SUGGESTION,
+        /** The user appears to be speaking while muted. */
+        SPEAKING_WHILE_MUTED;
 
         @CalledByNative
         static SpeechEvent fromNativeIndex(int nativeIndex) { return values()[nativeIndex]; }
@@ -1038,6 +1156,18 @@ public final class GroupCall {
         }
     }
 
+    /**
+     * How a remote device is connected to the call.
+     */
+    public enum ParticipantKind {
+        /** A device running the app. */
+        APP_USER,
+        /** Bridged in by a gateway, such as a phone dialing in. */
+        BRIDGED;
+
+        static ParticipantKind fromNativeIndex(int nativeIndex) { return values()[nativeIndex]; }
+    }
+
     /**
      * The state of each remote member in a group call.
      */
@@ -1049,6 +1179,7 @@ public final class GroupCall {
         // array.
         @Nullable UUID       userId;
         @NonNull  byte[]     userIdByteArray;
+        @NonNull  ParticipantKind participantKind;
 
                   boolean    mediaKeysReceived;
 
@@ -1066,6 +1197,7 @@ public final class GroupCall {
 
         public RemoteDeviceState(          long    demuxId,
                                  @NonNull  byte[]  userIdByteArray,
+                                           int     participantKind,
                                            boolean mediaKeysReceived,
                                  @Nullable Boolean audioMuted,
                                  @Nullable Boolean videoMuted,
@@ -1077,6 +1209,7 @@ public final class GroupCall {
                                            boolean isHigherResolutionPending) {
             this.demuxId = demuxId;
             this.userIdByteArray = userIdByteArray;
+            this.participantKind = ParticipantKind.fromNativeIndex(participantKind);
             this.mediaKeysReceived = mediaKeysReceived;
 
             this.audioMuted = audioMuted;
@@ -1100,6 +1233,10 @@ public final class GroupCall {
             return userId;
         }
 
+        public @NonNull ParticipantKind getParticipantKind() {
+            return participantKind;
+        }
+
         public boolean getMediaKeysReceived() {
             return mediaKeysReceived;
         }
@@ -1276,12 +1413,52 @@ public final class GroupCall {
          */
         void onPeekChanged(GroupCall groupCall);
 
+        /**
+         * Notification that the group call could not be joined because it has
+         * reached its maximum number of devices. This is followed by onEnded()
+         * with HAS_MAX_DEVICES.
+         *
+         * Either count may be null if it isn't known.
+         */
+        void onCallFull(GroupCall groupCall, @Nullable Long maxDevices, @Nullable Long deviceCount);
+
         /**
          * Notification that the group call has ended.
          */
         void onEnded(GroupCall groupCall, GroupCallEndReason reason);
     }
 
+    /**
+     * Receives decoded remote video frames without copying them, for
+     * calls created with one.
+     */
+    public interface DirectVideoSink {
+
+        /**
+         * Called on a WebRTC thread for each decoded frame.
+         *
+         * The buffer refers to the decoder's output and is only valid
+         * until this returns, so the frame must be copied or rendered
+         * before then, and quickly, so as not to stall the decoder.
+         *
+         * @param demuxId   the remote device the frame is from
+         * @param rotation  the clockwise rotation of the frame in degrees
+         * @param i420      a direct ByteBuffer holding the I420 planes
+         */
+        void onVideoFrame(long demuxId, int width, int height, int rotation, @NonNull ByteBuffer i420);
+    }
+
+    /**
+     * The layouts of the pixels given to pushVideoFrame().
+     *
+     * The order must stay in sync with VideoPixelFormat in RingRTC.
+     */
+    public enum PixelFormat {
+        I420,
+        NV12,
+        RGBA
+    }
+
     /* Native methods below here. */
 
     private static native
@@ -1292,7 +1469,8 @@ public final class GroupCall {
                                           int audioLevelsIntervalMillis,
                                           long nativePeerConnectionFactory,
                                           long nativeAudioTrack,
-                                          long nativeVideoTrack)
+                                          long nativeVideoTrack,
+                                          DirectVideoSink directVideoSink)
         throws CallException;
 
     private static native
@@ -1305,7 +1483,40 @@ public final class GroupCall {
                                              int audioLevelsIntervalMillis,
                                              long nativePeerConnectionFactory,
                                              long nativeAudioTrack,
-                                             long nativeVideoTrack)
+                                             long nativeVideoTrack,
+                                             DirectVideoSink directVideoSink)
+        throws CallException;
+
+    private static native
+        long ringrtcCreateDirectVideoSource(long nativePeerConnectionFactory)
+        throws CallException;
+
+    private static native
+        long ringrtcGetDirectVideoTrack(long nativeDirectVideoSource)
+        throws CallException;
+
+    private static native
+        void ringrtcSetDirectVideoEnabled(long nativeDirectVideoSource,
+                                          boolean enabled)
+        throws CallException;
+
+    private static native
+        void ringrtcPushVideoFrameBuffer(long nativeDirectVideoSource,
+                                         ByteBuffer buffer,
+                                         int width,
+                                         int height,
+                                         int pixelFormat,
+                                         int rotation)
+        throws CallException;
+
+    private static native
+        void ringrtcPushVideoFrameHardwareBuffer(long nativeDirectVideoSource,
+                                                 HardwareBuffer hardwareBuffer,
+                                                 int rotation)
+        throws CallException;
+
+    private static native
+        void ringrtcDeleteDirectVideoSource(long nativeDirectVideoSource)
         throws CallException;
 
     private native
@@ -14,16 +14,41 @@ import org.webrtc.PeerConnection;
  */
 public class NetworkRoute {
   PeerConnection.AdapterType localAdapterType;
+  PeerConnection.AdapterType localAdapterTypeUnderVpn;
+  boolean                    relayed;
 
   public NetworkRoute() {
-    this.localAdapterType = PeerConnection.AdapterType.UNKNOWN;
+    this(PeerConnection.AdapterType.UNKNOWN);
   }
 
   public NetworkRoute(PeerConnection.AdapterType localAdapterType) {
-    this.localAdapterType = localAdapterType;
+    this(localAdapterType, PeerConnection.AdapterType.UNKNOWN, false);
+  }
+
+  public NetworkRoute(PeerConnection.AdapterType localAdapterType,
+                      PeerConnection.AdapterType localAdapterTypeUnderVpn,
+                      boolean                    relayed)
+  {
+    this.localAdapterType         = localAdapterType;
+    this.localAdapterTypeUnderVpn = localAdapterTypeUnderVpn;
+    this.relayed                  = relayed;
   }
 
   public PeerConnection.AdapterType getLocalAdapterType() {
     return this.localAdapterType;
   }
-}
\ No newline at end of file
+
+  /**
+   * The adapter the VPN runs over, if the local adapter type is VPN.
+   */
+  public PeerConnection.AdapterType getLocalAdapterTypeUnderVpn() {
+    return this.localAdapterTypeUnderVpn;
+  }
+
+  /**
+   * Whether media goes through a relay (TURN) server on either side.
+   */
+  public boolean isRelayed() {
+    return this.relayed;
+  }
+}
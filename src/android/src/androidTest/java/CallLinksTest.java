@@ -52,6 +52,16 @@ public class CallLinksTest extends CallTestBase {
         assertFalse(Arrays.equals(EXAMPLE_KEY.deriveRoomId(), anotherKey.deriveRoomId()));
     }
 
+    @Test
+    public void testLatestVersion() throws Exception {
+        final CallLinkRootKey latestKey = EXAMPLE_KEY.withLatestVersion();
+        assertFalse(Arrays.equals(EXAMPLE_KEY.getKeyBytes(), latestKey.getKeyBytes()));
+        assertArrayEquals(EXAMPLE_KEY.deriveRoomId(), latestKey.deriveRoomId());
+
+        final CallLinkRootKey parsedKey = new CallLinkRootKey(latestKey.toString());
+        assertArrayEquals(latestKey.getKeyBytes(), parsedKey.getKeyBytes());
+    }
+
     @Test
     public void testFormatting() throws Exception {
         assertEquals("bcdf-ghkm-npqr-stxz-bcdf-ghkm-npqr-stxz", EXAMPLE_KEY.toString());
@@ -209,6 +219,45 @@ public class CallLinksTest extends CallTestBase {
         latch.await();
     }
 
+    @Test
+    public void testRotateAdminPasskeySuccess() throws Exception {
+        CallManager.Observer observer = mock();
+        CallManager callManager = CallManager.createCallManager(observer);
+
+        CountDownLatch latch = new CountDownLatch(1);
+        callManager.rotateCallLinkAdminPasskey("sfu.example", new byte[] { 1, 2, 3 }, EXAMPLE_KEY, CallLinkRootKey.generateAdminPasskey(), CallLinkRootKey.generateAdminPasskey(), result -> {
+            errors.checkThat(result.isSuccess(), is(true));
+            latch.countDown();
+        });
+
+        ArgumentCaptor<Long> requestId = ArgumentCaptor.forClass(Long.class);
+        verify(observer).onSendHttpRequest(requestId.capture(), startsWith("sfu.example"), eq(CallManager.HttpMethod.PUT), any(), any());
+
+        callManager.receivedHttpResponse(requestId.getValue(), 200, EXAMPLE_STATE_JSON.getBytes("UTF-8"));
+        latch.await();
+    }
+
+    @Test
+    public void testMigrateSuccess() throws Exception {
+        CallManager.Observer observer = mock();
+        CallManager callManager = CallManager.createCallManager(observer);
+
+        CountDownLatch latch = new CountDownLatch(1);
+        callManager.migrateCallLink("sfu.example", new byte[] { 1, 2, 3 }, EXAMPLE_KEY.withLatestVersion(), CallLinkRootKey.generateAdminPasskey(), result -> {
+            errors.checkThat(result.isSuccess(), is(true));
+            latch.countDown();
+        });
+
+        // The link is read first, and then updated.
+        ArgumentCaptor<Long> requestId = ArgumentCaptor.forClass(Long.class);
+        verify(observer).onSendHttpRequest(requestId.capture(), startsWith("sfu.example"), eq(CallManager.HttpMethod.GET), any(), any());
+        callManager.receivedHttpResponse(requestId.getValue(), 200, EXAMPLE_STATE_JSON.getBytes("UTF-8"));
+
+        verify(observer).onSendHttpRequest(requestId.capture(), startsWith("sfu.example"), eq(CallManager.HttpMethod.PUT), any(), any());
+        callManager.receivedHttpResponse(requestId.getValue(), 200, EXAMPLE_STATE_JSON.getBytes("UTF-8"));
+        latch.await();
+    }
+
     @Test
     public void testDeleteSuccess() throws Exception {
         CallManager.Observer observer = mock();
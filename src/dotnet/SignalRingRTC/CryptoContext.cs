@@ -0,0 +1,140 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+using System;
+using SignalRingRTC.Interop;
+
+namespace SignalRingRTC
+{
+    /// <summary>What has to be sent along with an encrypted frame.</summary>
+    public readonly record struct EncryptedFrame(byte RatchetCounter, ulong FrameCounter, byte[] Mac);
+
+    /// <summary>The secret receivers need to decrypt frames, as of a ratchet counter.</summary>
+    public readonly record struct SendState(byte RatchetCounter, byte[] Secret);
+
+    /// <summary>
+    /// The frame encryption used by group calls, for media that doesn't go
+    /// through RingRTC's own pipeline.
+    ///
+    /// Methods may be called from any thread and are serialized natively,
+    /// but must not be called concurrently with or after <see cref="Dispose"/>.
+    /// </summary>
+    public sealed class CryptoContext : IDisposable
+    {
+        /// <summary>The size of send and receive secrets, in bytes.</summary>
+        public const int SecretSizeBytes = 32;
+        /// <summary>The size of the MAC sent with each frame, in bytes.</summary>
+        public const int MacSizeBytes = 16;
+
+        private IntPtr handle;
+
+        public unsafe CryptoContext(ReadOnlySpan<byte> initialSendSecret)
+        {
+            CheckSecret(initialSendSecret);
+            fixed (byte* secretPtr = initialSendSecret)
+            {
+                handle = Native.rtc_crypto_Context_create(secretPtr);
+            }
+            if (handle == IntPtr.Zero)
+            {
+                throw new InvalidOperationException("failed to create crypto context");
+            }
+        }
+
+        private IntPtr Handle => handle != IntPtr.Zero ? handle : throw new ObjectDisposedException(nameof(CryptoContext));
+
+        /// <summary>Encrypts <paramref name="data"/> in place.</summary>
+        public unsafe EncryptedFrame Encrypt(Span<byte> data)
+        {
+            var mac = new byte[MacSizeBytes];
+            byte ratchetCounter;
+            ulong frameCounter;
+            fixed (byte* dataPtr = data)
+            fixed (byte* macPtr = mac)
+            {
+                if (!Native.rtc_crypto_Context_encrypt(Handle, dataPtr, (nuint)data.Length, macPtr, &ratchetCounter, &frameCounter))
+                {
+                    throw new InvalidOperationException("failed to encrypt frame");
+                }
+            }
+            return new EncryptedFrame(ratchetCounter, frameCounter, mac);
+        }
+
+        /// <summary>
+        /// Decrypts <paramref name="data"/> in place, returning false (and
+        /// leaving it untouched) if no secret from <paramref name="senderId"/>
+        /// matches.
+        /// </summary>
+        public unsafe bool Decrypt(uint senderId, byte ratchetCounter, ulong frameCounter, Span<byte> data, ReadOnlySpan<byte> mac)
+        {
+            if (mac.Length != MacSizeBytes)
+            {
+                throw new ArgumentException($"MAC must be {MacSizeBytes} bytes", nameof(mac));
+            }
+            fixed (byte* dataPtr = data)
+            fixed (byte* macPtr = mac)
+            {
+                return Native.rtc_crypto_Context_decrypt(Handle, senderId, ratchetCounter, frameCounter, dataPtr, (nuint)data.Length, macPtr);
+            }
+        }
+
+        public unsafe void AddReceiveSecret(uint senderId, byte ratchetCounter, ReadOnlySpan<byte> secret)
+        {
+            CheckSecret(secret);
+            fixed (byte* secretPtr = secret)
+            {
+                Native.rtc_crypto_Context_addReceiveSecret(Handle, senderId, ratchetCounter, secretPtr);
+            }
+        }
+
+        public unsafe SendState GetSendState()
+        {
+            var secret = new byte[SecretSizeBytes];
+            byte ratchetCounter;
+            fixed (byte* secretPtr = secret)
+            {
+                Native.rtc_crypto_Context_getSendState(Handle, &ratchetCounter, secretPtr);
+            }
+            return new SendState(ratchetCounter, secret);
+        }
+
+        /// <summary>Ratchets the send secret forward, for when a receiver is added.</summary>
+        public unsafe SendState AdvanceSendRatchet()
+        {
+            var secret = new byte[SecretSizeBytes];
+            byte ratchetCounter;
+            fixed (byte* secretPtr = secret)
+            {
+                Native.rtc_crypto_Context_advanceSendRatchet(Handle, &ratchetCounter, secretPtr);
+            }
+            return new SendState(ratchetCounter, secret);
+        }
+
+        /// <summary>Replaces the send secret, for when a receiver is removed.</summary>
+        public unsafe void ResetSendRatchet(ReadOnlySpan<byte> secret)
+        {
+            CheckSecret(secret);
+            fixed (byte* secretPtr = secret)
+            {
+                Native.rtc_crypto_Context_resetSendRatchet(Handle, secretPtr);
+            }
+        }
+
+        public void Dispose()
+        {
+            var disposed = handle;
+            handle = IntPtr.Zero;
+            Native.rtc_crypto_Context_destroy(disposed);
+        }
+
+        private static void CheckSecret(ReadOnlySpan<byte> secret)
+        {
+            if (secret.Length != SecretSizeBytes)
+            {
+                throw new ArgumentException($"secrets must be {SecretSizeBytes} bytes", nameof(secret));
+            }
+        }
+    }
+}
@@ -0,0 +1,365 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+using System;
+using System.Collections.Generic;
+using System.Runtime.CompilerServices;
+using System.Runtime.InteropServices;
+using System.Text;
+using SignalRingRTC.Interop;
+
+namespace SignalRingRTC
+{
+    // Matches ConnectionState in core/group_call.rs.
+    public enum ConnectionState
+    {
+        NotConnected = 0,
+        Connecting = 1,
+        Connected = 2,
+        Reconnecting = 3,
+    }
+
+    // Matches JoinState in core/group_call.rs.
+    public enum JoinState
+    {
+        NotJoined = 0,
+        Joining = 1,
+        Pending = 2,
+        Joined = 3,
+    }
+
+    // Matches EndReason in core/group_call.rs.
+    public enum GroupCallEndReason
+    {
+        DeviceExplicitlyDisconnected = 0,
+        ServerExplicitlyDisconnected = 1,
+        DeniedRequestToJoinCall = 2,
+        RemovedFromCall = 3,
+        CallManagerIsBusy = 4,
+        SfuClientFailedToJoin = 5,
+        FailedToCreatePeerConnectionFactory = 6,
+        FailedToNegotiateSrtpKeys = 7,
+        FailedToCreatePeerConnection = 8,
+        FailedToStartPeerConnection = 9,
+        FailedToUpdatePeerConnection = 10,
+        FailedToSetMaxSendBitrate = 11,
+        IceFailedWhileConnecting = 12,
+        IceFailedAfterConnected = 13,
+        ServerChangedDemuxId = 14,
+        HasMaxDevices = 15,
+    }
+
+    // Matches SignalingMessageUrgency in core/group_call.rs.
+    public enum CallMessageUrgency
+    {
+        Droppable = 0,
+        HandleImmediately = 1,
+    }
+
+    public sealed class GroupCallConfig
+    {
+        public required string SfuUrl { get; init; }
+        public required byte[] GroupId { get; init; }
+        /// <summary>The ACI of the local account, needed to exchange media keys.</summary>
+        public required byte[] SelfUuid { get; init; }
+        public byte[] HkdfExtraInfo { get; init; } = Array.Empty<byte>();
+    }
+
+    public sealed record RemoteDevice(uint DemuxId, byte[] UserId, bool MediaKeysReceived);
+
+    /// <summary>A member of the group, with its UUID ciphertext.</summary>
+    public sealed record GroupMember(byte[] UserId, byte[] MemberId);
+
+    /// <summary>
+    /// Receives the events of a <see cref="GroupCall"/>. Methods are called on
+    /// RingRTC's threads, so they should return quickly. Exceptions are
+    /// swallowed.
+    /// </summary>
+    public interface IGroupCallObserver
+    {
+        /// <summary>Respond with <see cref="GroupCall.SetMembershipProof"/>.</summary>
+        void RequestMembershipProof();
+
+        /// <summary>Respond with <see cref="GroupCall.SetGroupMembers"/>.</summary>
+        void RequestGroupMembers();
+
+        /// <summary>Send a serialized CallMessage to the given user.</summary>
+        void SendCallMessage(byte[] recipientId, byte[] message, CallMessageUrgency urgency);
+
+        /// <summary>
+        /// Send a serialized CallMessage to the group, or only to
+        /// <paramref name="recipientsOverride"/> if it isn't empty.
+        /// </summary>
+        void SendCallMessageToGroup(byte[] groupId, byte[] message, CallMessageUrgency urgency, IReadOnlyList<byte[]> recipientsOverride);
+
+        void OnConnectionStateChanged(ConnectionState connectionState);
+
+        /// <summary>The local demux ID is known once pending or joined.</summary>
+        void OnJoinStateChanged(JoinState joinState, uint? demuxId);
+
+        void OnRemoteDevicesChanged(IReadOnlyList<RemoteDevice> remoteDevices);
+
+        void OnEnded(GroupCallEndReason reason);
+    }
+
+    /// <summary>
+    /// A group call joined without a UI, sending only audio. Outgoing
+    /// audio is pushed with <see cref="SendAudio"/>; received media isn't
+    /// exposed.
+    ///
+    /// Media keys are exchanged over the application's signaling channel:
+    /// messages to send are handed to the observer, and received messages
+    /// must be passed to <see cref="ReceivedCallMessage"/>.
+    /// </summary>
+    public sealed class GroupCall : IDisposable
+    {
+        private IntPtr handle;
+
+        private GroupCall(IntPtr handle)
+        {
+            this.handle = handle;
+        }
+
+        /// <summary>
+        /// Starts connecting to the call's SFU. Call <see cref="Join"/> to
+        /// start exchanging media.
+        /// </summary>
+        public static unsafe GroupCall Create(SfuHttpClient httpClient, GroupCallConfig config, IGroupCallObserver observer)
+        {
+            var sfuUrl = Encoding.UTF8.GetBytes(config.SfuUrl);
+            var retained = GCHandle.Alloc(observer);
+            var groupCallDelegate = new rtc_groupcall_Delegate
+            {
+                retained = (void*)GCHandle.ToIntPtr(retained),
+                release = &Release,
+                request_membership_proof = &RequestMembershipProof,
+                request_group_members = &RequestGroupMembers,
+                send_call_message = &SendCallMessage,
+                send_call_message_to_group = &SendCallMessageToGroup,
+                handle_connection_state_changed = &HandleConnectionStateChanged,
+                handle_join_state_changed = &HandleJoinStateChanged,
+                handle_remote_devices_changed = &HandleRemoteDevicesChanged,
+                handle_ended = &HandleEnded,
+            };
+
+            IntPtr handle;
+            fixed (byte* sfuUrlPtr = sfuUrl)
+            fixed (byte* groupIdPtr = config.GroupId)
+            fixed (byte* selfUuidPtr = config.SelfUuid)
+            fixed (byte* hkdfExtraInfoPtr = config.HkdfExtraInfo)
+            {
+                var rtcConfig = new rtc_groupcall_ClientConfig
+                {
+                    sfu_url = new rtc_String { ptr = sfuUrlPtr, count = (nuint)sfuUrl.Length },
+                    group_id = new rtc_Bytes { ptr = groupIdPtr, count = (nuint)config.GroupId.Length },
+                    self_uuid = new rtc_Bytes { ptr = selfUuidPtr, count = (nuint)config.SelfUuid.Length },
+                    hkdf_extra_info = new rtc_Bytes { ptr = hkdfExtraInfoPtr, count = (nuint)config.HkdfExtraInfo.Length },
+                };
+                // On failure, the delegate has already been released.
+                handle = Native.rtc_groupcall_Client_create(httpClient.Handle, rtcConfig, groupCallDelegate);
+            }
+            if (handle == IntPtr.Zero)
+            {
+                throw new InvalidOperationException("failed to create group call");
+            }
+            return new GroupCall(handle);
+        }
+
+        private IntPtr Handle => handle != IntPtr.Zero ? handle : throw new ObjectDisposedException(nameof(GroupCall));
+
+        public unsafe void SetMembershipProof(byte[] membershipProof)
+        {
+            fixed (byte* ptr = membershipProof)
+            {
+                Native.rtc_groupcall_Client_setMembershipProof(Handle, new rtc_Bytes { ptr = ptr, count = (nuint)membershipProof.Length });
+            }
+        }
+
+        public unsafe void SetGroupMembers(IReadOnlyList<GroupMember> groupMembers)
+        {
+            var pins = new List<GCHandle>(2 * groupMembers.Count);
+            try
+            {
+                var members = new rtc_sfu_GroupMember[groupMembers.Count];
+                for (var i = 0; i < members.Length; i++)
+                {
+                    members[i] = new rtc_sfu_GroupMember
+                    {
+                        user_id = Pin(groupMembers[i].UserId, pins),
+                        member_id = Pin(groupMembers[i].MemberId, pins),
+                    };
+                }
+                fixed (rtc_sfu_GroupMember* ptr = members)
+                {
+                    Native.rtc_groupcall_Client_setGroupMembers(Handle, new rtc_sfu_GroupMembers { ptr = ptr, count = (nuint)members.Length });
+                }
+            }
+            finally
+            {
+                foreach (var pin in pins)
+                {
+                    pin.Free();
+                }
+            }
+        }
+
+        public void Join()
+        {
+            Native.rtc_groupcall_Client_join(Handle);
+        }
+
+        public void Leave()
+        {
+            Native.rtc_groupcall_Client_leave(Handle);
+        }
+
+        /// <summary>
+        /// Passes a CallMessage received over the signaling channel. Returns
+        /// false if it could not be parsed.
+        /// </summary>
+        public unsafe bool ReceivedCallMessage(byte[] senderUserId, byte[] message)
+        {
+            fixed (byte* senderPtr = senderUserId)
+            fixed (byte* messagePtr = message)
+            {
+                return Native.rtc_groupcall_Client_receivedCallMessage(
+                    Handle,
+                    new rtc_Bytes { ptr = senderPtr, count = (nuint)senderUserId.Length },
+                    new rtc_Bytes { ptr = messagePtr, count = (nuint)message.Length });
+            }
+        }
+
+        /// <summary>Sends the local media key to everyone in the call again.</summary>
+        public void ResendMediaKeys()
+        {
+            Native.rtc_groupcall_Client_resendMediaKeys(Handle);
+        }
+
+        public void SetOutgoingAudioMuted(bool muted)
+        {
+            Native.rtc_groupcall_Client_setOutgoingAudioMuted(Handle, muted);
+        }
+
+        /// <summary>
+        /// Queues interleaved 16-bit PCM audio to be sent, at any sample rate.
+        /// Silence is sent when nothing is queued. Returns false if the audio
+        /// could not be queued.
+        /// </summary>
+        public unsafe bool SendAudio(ReadOnlySpan<short> samples, int channels, uint sampleRateHz)
+        {
+            if (channels <= 0 || samples.Length % channels != 0)
+            {
+                throw new ArgumentException("samples must be whole frames of interleaved channels");
+            }
+            fixed (short* ptr = samples)
+            {
+                return Native.rtc_groupcall_Client_sendAudio(Handle, ptr, (nuint)(samples.Length / channels), (nuint)channels, sampleRateHz);
+            }
+        }
+
+        /// <summary>
+        /// Leaves the call and disconnects. The observer isn't called once
+        /// this returns.
+        /// </summary>
+        public void Dispose()
+        {
+            var disposed = handle;
+            handle = IntPtr.Zero;
+            if (disposed != IntPtr.Zero)
+            {
+                Native.rtc_groupcall_Client_destroy(disposed);
+            }
+        }
+
+        private static unsafe rtc_Bytes Pin(byte[] bytes, List<GCHandle> pins)
+        {
+            var pin = GCHandle.Alloc(bytes, GCHandleType.Pinned);
+            pins.Add(pin);
+            return new rtc_Bytes { ptr = (byte*)pin.AddrOfPinnedObject(), count = (nuint)bytes.Length };
+        }
+
+        private static unsafe void Notify(void* unretained, Action<IGroupCallObserver> action)
+        {
+            try
+            {
+                action((IGroupCallObserver)GCHandle.FromIntPtr((IntPtr)unretained).Target!);
+            }
+            catch (Exception)
+            {
+                // Exceptions must not unwind into RingRTC.
+            }
+        }
+
+        [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+        private static unsafe void Release(void* retained)
+        {
+            GCHandle.FromIntPtr((IntPtr)retained).Free();
+        }
+
+        [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+        private static unsafe void RequestMembershipProof(void* unretained)
+        {
+            Notify(unretained, observer => observer.RequestMembershipProof());
+        }
+
+        [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+        private static unsafe void RequestGroupMembers(void* unretained)
+        {
+            Notify(unretained, observer => observer.RequestGroupMembers());
+        }
+
+        [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+        private static unsafe void SendCallMessage(void* unretained, rtc_Bytes recipientId, rtc_Bytes message, int urgency)
+        {
+            var recipientIdBytes = recipientId.ToArray();
+            var messageBytes = message.ToArray();
+            Notify(unretained, observer => observer.SendCallMessage(recipientIdBytes, messageBytes, (CallMessageUrgency)urgency));
+        }
+
+        [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+        private static unsafe void SendCallMessageToGroup(void* unretained, rtc_Bytes groupId, rtc_Bytes message, int urgency, rtc_UserIds recipientsOverride)
+        {
+            var groupIdBytes = groupId.ToArray();
+            var messageBytes = message.ToArray();
+            var recipients = new byte[(int)recipientsOverride.count][];
+            for (var i = 0; i < recipients.Length; i++)
+            {
+                recipients[i] = recipientsOverride.ptr[i].ToArray();
+            }
+            Notify(unretained, observer => observer.SendCallMessageToGroup(groupIdBytes, messageBytes, (CallMessageUrgency)urgency, recipients));
+        }
+
+        [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+        private static unsafe void HandleConnectionStateChanged(void* unretained, int connectionState)
+        {
+            Notify(unretained, observer => observer.OnConnectionStateChanged((ConnectionState)connectionState));
+        }
+
+        [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+        private static unsafe void HandleJoinStateChanged(void* unretained, int joinState, rtc_OptionalU32 demuxId)
+        {
+            var localDemuxId = demuxId.ToNullable();
+            Notify(unretained, observer => observer.OnJoinStateChanged((JoinState)joinState, localDemuxId));
+        }
+
+        [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+        private static unsafe void HandleRemoteDevicesChanged(void* unretained, rtc_groupcall_RemoteDevices remoteDevices)
+        {
+            var devices = new RemoteDevice[(int)remoteDevices.count];
+            for (var i = 0; i < devices.Length; i++)
+            {
+                var device = remoteDevices.ptr[i];
+                devices[i] = new RemoteDevice(device.demux_id, device.user_id.ToArray(), device.media_keys_received != 0);
+            }
+            Notify(unretained, observer => observer.OnRemoteDevicesChanged(devices));
+        }
+
+        [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+        private static unsafe void HandleEnded(void* unretained, int reason)
+        {
+            Notify(unretained, observer => observer.OnEnded((GroupCallEndReason)reason));
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+using System;
+using System.Net.Http;
+using System.Runtime.CompilerServices;
+using System.Runtime.InteropServices;
+using System.Threading.Tasks;
+using SignalRingRTC.Interop;
+
+namespace SignalRingRTC
+{
+    /// <summary>
+    /// Makes the HTTP requests RingRTC needs, such as to the SFU, using a
+    /// <see cref="System.Net.Http.HttpClient"/>.
+    ///
+    /// Dispose it only after the calls using it, since responses that arrive
+    /// afterwards are dropped.
+    /// </summary>
+    public sealed class SfuHttpClient : IDisposable
+    {
+        private readonly Transport transport;
+
+        public SfuHttpClient() : this(new HttpClient())
+        {
+        }
+
+        public unsafe SfuHttpClient(HttpClient httpClient)
+        {
+            transport = new Transport(httpClient);
+            var retained = GCHandle.Alloc(transport);
+            var httpDelegate = new rtc_http_Delegate
+            {
+                retained = (void*)GCHandle.ToIntPtr(retained),
+                release = &Release,
+                send_request = &SendRequest,
+            };
+            transport.Open(Native.rtc_http_Client_create(httpDelegate));
+        }
+
+        internal IntPtr Handle => transport.Handle;
+
+        public void Dispose()
+        {
+            transport.Close();
+        }
+
+        private sealed class Transport
+        {
+            private readonly HttpClient httpClient;
+            private readonly object gate = new();
+            private IntPtr handle;
+
+            public Transport(HttpClient httpClient)
+            {
+                this.httpClient = httpClient;
+            }
+
+            public IntPtr Handle
+            {
+                get
+                {
+                    lock (gate)
+                    {
+                        if (handle == IntPtr.Zero)
+                        {
+                            throw new ObjectDisposedException(nameof(SfuHttpClient));
+                        }
+                        return handle;
+                    }
+                }
+            }
+
+            public void Open(IntPtr handle)
+            {
+                lock (gate)
+                {
+                    this.handle = handle;
+                }
+            }
+
+            public void Close()
+            {
+                IntPtr closed;
+                lock (gate)
+                {
+                    closed = handle;
+                    handle = IntPtr.Zero;
+                }
+                if (closed != IntPtr.Zero)
+                {
+                    Native.rtc_http_Client_destroy(closed);
+                }
+            }
+
+            /// <summary>A null request is reported as failed.</summary>
+            public async Task SendAsync(uint requestId, HttpRequestMessage? request)
+            {
+                ushort statusCode = 0;
+                byte[]? body = null;
+                if (request != null)
+                {
+                    try
+                    {
+                        using (request)
+                        using (var response = await httpClient.SendAsync(request).ConfigureAwait(false))
+                        {
+                            statusCode = (ushort)response.StatusCode;
+                            body = await response.Content.ReadAsByteArrayAsync().ConfigureAwait(false);
+                        }
+                    }
+                    catch (Exception)
+                    {
+                        // Reported to RingRTC as a failed request below.
+                    }
+                }
+                Deliver(requestId, statusCode, body);
+            }
+
+            private unsafe void Deliver(uint requestId, ushort statusCode, byte[]? body)
+            {
+                lock (gate)
+                {
+                    if (handle == IntPtr.Zero)
+                    {
+                        return;
+                    }
+                    if (body == null)
+                    {
+                        Native.rtc_http_Client_request_failed(handle, requestId);
+                        return;
+                    }
+                    fixed (byte* bodyPtr = body)
+                    {
+                        var response = new rtc_http_Response
+                        {
+                            status_code = statusCode,
+                            body = new rtc_Bytes { ptr = bodyPtr, count = (nuint)body.Length },
+                        };
+                        Native.rtc_http_Client_received_response(handle, requestId, response);
+                    }
+                }
+            }
+        }
+
+        private static unsafe HttpRequestMessage ToRequestMessage(in rtc_http_Request request)
+        {
+            var method = request.method switch
+            {
+                0 => HttpMethod.Get,
+                1 => HttpMethod.Put,
+                2 => HttpMethod.Post,
+                3 => HttpMethod.Delete,
+                _ => throw new ArgumentException($"unknown HTTP method {request.method}"),
+            };
+            var message = new HttpRequestMessage(method, request.url.ToString());
+            if (request.body.ptr != null)
+            {
+                message.Content = new ByteArrayContent(request.body.ToArray());
+            }
+            for (nuint i = 0; i < request.headers.count; i++)
+            {
+                var header = request.headers.ptr[i];
+                var name = header.name.ToString();
+                var value = header.value.ToString();
+                // Content headers such as Content-Type can't be set on the request itself.
+                if (!message.Headers.TryAddWithoutValidation(name, value))
+                {
+                    message.Content ??= new ByteArrayContent(Array.Empty<byte>());
+                    message.Content.Headers.TryAddWithoutValidation(name, value);
+                }
+            }
+            return message;
+        }
+
+        [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+        private static unsafe void Release(void* retained)
+        {
+            GCHandle.FromIntPtr((IntPtr)retained).Free();
+        }
+
+        [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+        private static unsafe void SendRequest(void* unretained, uint requestId, rtc_http_Request request)
+        {
+            var transport = (Transport)GCHandle.FromIntPtr((IntPtr)unretained).Target!;
+            HttpRequestMessage? message = null;
+            try
+            {
+                // The request is only borrowed for this call, so copy it first.
+                message = ToRequestMessage(request);
+            }
+            catch (Exception)
+            {
+                // Fails the request, but not from within this callback.
+            }
+            _ = Task.Run(() => transport.SendAsync(requestId, message));
+        }
+    }
+}
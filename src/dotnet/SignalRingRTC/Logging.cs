@@ -0,0 +1,65 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+using System;
+using System.Runtime.CompilerServices;
+using System.Runtime.InteropServices;
+using SignalRingRTC.Interop;
+
+namespace SignalRingRTC
+{
+    /// <summary>Matches log::Level; Off only applies as a maximum.</summary>
+    public enum LogLevel : byte
+    {
+        Off = 0,
+        Error = 1,
+        Warn = 2,
+        Info = 3,
+        Debug = 4,
+        Trace = 5,
+    }
+
+    public delegate void LogHandler(LogLevel level, string file, uint? line, string message);
+
+    public static class Logging
+    {
+        private static LogHandler? handler;
+
+        /// <summary>
+        /// Sends RingRTC's logs to <paramref name="logHandler"/>, which is
+        /// called from RingRTC's threads. Returns false if logging was
+        /// already initialized, which can only be done once per process.
+        /// </summary>
+        public static unsafe bool Initialize(LogHandler logHandler, LogLevel maxLevel)
+        {
+            handler = logHandler;
+            var logDelegate = new rtc_log_Delegate
+            {
+                ctx = null,
+                log = &Log,
+                flush = &Flush,
+            };
+            return Native.rtc_log_init(logDelegate, (byte)maxLevel);
+        }
+
+        [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+        private static unsafe void Log(void* ctx, rtc_log_Record record)
+        {
+            try
+            {
+                handler?.Invoke((LogLevel)record.level, record.file.ToString(), record.line.ToNullable(), record.message.ToString());
+            }
+            catch (Exception)
+            {
+                // There is nowhere left to report it, and it must not unwind into Rust.
+            }
+        }
+
+        [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+        private static unsafe void Flush(void* ctx)
+        {
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+using System;
+using System.Runtime.InteropServices;
+using System.Text;
+
+namespace SignalRingRTC.Interop
+{
+    // The declarations below mirror the rtc_* definitions in src/rust, whose
+    // layouts are pinned there with const_assert_eq!. Booleans inside structs
+    // are declared as bytes so that the structs stay blittable.
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe struct rtc_Bytes
+    {
+        public byte* ptr;
+        public nuint count;
+
+        public byte[] ToArray()
+        {
+            return ptr == null ? Array.Empty<byte>() : new ReadOnlySpan<byte>(ptr, checked((int)count)).ToArray();
+        }
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe struct rtc_String
+    {
+        public byte* ptr;
+        public nuint count;
+
+        public override string ToString()
+        {
+            return ptr == null ? string.Empty : Encoding.UTF8.GetString(ptr, checked((int)count));
+        }
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct rtc_OptionalU32
+    {
+        public uint value;
+        public byte valid;
+
+        public uint? ToNullable()
+        {
+            return valid != 0 ? value : null;
+        }
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe struct rtc_UserIds
+    {
+        public rtc_Bytes* ptr;
+        public nuint count;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct rtc_sfu_GroupMember
+    {
+        public rtc_Bytes user_id;
+        public rtc_Bytes member_id;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe struct rtc_sfu_GroupMembers
+    {
+        public rtc_sfu_GroupMember* ptr;
+        public nuint count;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct rtc_log_Record
+    {
+        public rtc_String message;
+        public rtc_String file;
+        public rtc_OptionalU32 line;
+        public byte level;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe struct rtc_log_Delegate
+    {
+        public void* ctx;
+        public delegate* unmanaged[Cdecl]<void*, rtc_log_Record, void> log;
+        public delegate* unmanaged[Cdecl]<void*, void> flush;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe struct rtc_http_Header
+    {
+        public rtc_String name;
+        public rtc_String value;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe struct rtc_http_Headers
+    {
+        public rtc_http_Header* ptr;
+        public nuint count;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct rtc_http_Request
+    {
+        public rtc_String url;
+        public int method;
+        public rtc_http_Headers headers;
+        public rtc_Bytes body;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct rtc_http_Response
+    {
+        public ushort status_code;
+        public rtc_Bytes body;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe struct rtc_http_Delegate
+    {
+        public void* retained;
+        public delegate* unmanaged[Cdecl]<void*, void> release;
+        public delegate* unmanaged[Cdecl]<void*, uint, rtc_http_Request, void> send_request;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct rtc_groupcall_ClientConfig
+    {
+        public rtc_String sfu_url;
+        public rtc_Bytes group_id;
+        public rtc_Bytes self_uuid;
+        public rtc_Bytes hkdf_extra_info;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct rtc_groupcall_RemoteDevice
+    {
+        public uint demux_id;
+        public rtc_Bytes user_id;
+        public byte media_keys_received;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe struct rtc_groupcall_RemoteDevices
+    {
+        public rtc_groupcall_RemoteDevice* ptr;
+        public nuint count;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe struct rtc_groupcall_Delegate
+    {
+        public void* retained;
+        public delegate* unmanaged[Cdecl]<void*, void> release;
+        public delegate* unmanaged[Cdecl]<void*, void> request_membership_proof;
+        public delegate* unmanaged[Cdecl]<void*, void> request_group_members;
+        public delegate* unmanaged[Cdecl]<void*, rtc_Bytes, rtc_Bytes, int, void> send_call_message;
+        public delegate* unmanaged[Cdecl]<void*, rtc_Bytes, rtc_Bytes, int, rtc_UserIds, void> send_call_message_to_group;
+        public delegate* unmanaged[Cdecl]<void*, int, void> handle_connection_state_changed;
+        public delegate* unmanaged[Cdecl]<void*, int, rtc_OptionalU32, void> handle_join_state_changed;
+        public delegate* unmanaged[Cdecl]<void*, rtc_groupcall_RemoteDevices, void> handle_remote_devices_changed;
+        public delegate* unmanaged[Cdecl]<void*, int, void> handle_ended;
+    }
+
+    internal static unsafe class Native
+    {
+        private const string Library = "ringrtc";
+
+        // lite/logging.rs
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        [return: MarshalAs(UnmanagedType.U1)]
+        public static extern bool rtc_log_init(rtc_log_Delegate @delegate, byte max_level);
+
+        // lite/http.rs
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern IntPtr rtc_http_Client_create(rtc_http_Delegate @delegate);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern void rtc_http_Client_destroy(IntPtr client);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern void rtc_http_Client_received_response(IntPtr client, uint request_id, rtc_http_Response response);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern void rtc_http_Client_request_failed(IntPtr client, uint request_id);
+
+        // headless.rs
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern IntPtr rtc_groupcall_Client_create(IntPtr http_client, rtc_groupcall_ClientConfig config, rtc_groupcall_Delegate @delegate);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern void rtc_groupcall_Client_destroy(IntPtr client);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern void rtc_groupcall_Client_setMembershipProof(IntPtr client, rtc_Bytes membership_proof);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern void rtc_groupcall_Client_setGroupMembers(IntPtr client, rtc_sfu_GroupMembers group_members);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern void rtc_groupcall_Client_join(IntPtr client);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern void rtc_groupcall_Client_leave(IntPtr client);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        [return: MarshalAs(UnmanagedType.U1)]
+        public static extern bool rtc_groupcall_Client_receivedCallMessage(IntPtr client, rtc_Bytes sender_user_id, rtc_Bytes message);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern void rtc_groupcall_Client_resendMediaKeys(IntPtr client);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern void rtc_groupcall_Client_setOutgoingAudioMuted(IntPtr client, [MarshalAs(UnmanagedType.U1)] bool muted);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        [return: MarshalAs(UnmanagedType.U1)]
+        public static extern bool rtc_groupcall_Client_sendAudio(IntPtr client, short* samples, nuint samples_per_channel, nuint channels, uint sample_rate_hz);
+
+        // frame_crypto.rs
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern IntPtr rtc_crypto_Context_create(byte* initial_send_secret);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern void rtc_crypto_Context_destroy(IntPtr context);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        [return: MarshalAs(UnmanagedType.U1)]
+        public static extern bool rtc_crypto_Context_encrypt(IntPtr context, byte* data, nuint data_len, byte* mac_out, byte* ratchet_counter_out, ulong* frame_counter_out);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        [return: MarshalAs(UnmanagedType.U1)]
+        public static extern bool rtc_crypto_Context_decrypt(IntPtr context, uint sender_id, byte ratchet_counter, ulong frame_counter, byte* data, nuint data_len, byte* mac);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern void rtc_crypto_Context_addReceiveSecret(IntPtr context, uint sender_id, byte ratchet_counter, byte* secret);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        [return: MarshalAs(UnmanagedType.U1)]
+        public static extern bool rtc_crypto_Context_getSendState(IntPtr context, byte* ratchet_counter_out, byte* secret_out);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        [return: MarshalAs(UnmanagedType.U1)]
+        public static extern bool rtc_crypto_Context_advanceSendRatchet(IntPtr context, byte* ratchet_counter_out, byte* secret_out);
+
+        [DllImport(Library, CallingConvention = CallingConvention.Cdecl)]
+        public static extern void rtc_crypto_Context_resetSendRatchet(IntPtr context, byte* secret);
+    }
+}
@@ -63,6 +63,20 @@ public struct CallLinkRootKey: CustomStringConvertible {
         return result!
     }
 
+    /// The same key at the newest version, to pass to ``SFUClient/migrateCallLink(sfuUrl:authCredentialPresentation:linkRootKey:adminPasskey:)``.
+    public func withLatestVersion() -> Self {
+        var result: Self? = nil
+        let errorCStr = bytes.withRtcBytes { bytes in
+            rtc_calllinks_CallLinkRootKey_withLatestVersion(bytes, &result) { resultOpaquePtr, versionedBytes in
+                resultOpaquePtr!.assumingMemoryBound(to: Optional<Self>.self).pointee = Self(validatedBytes: versionedBytes)
+            }
+        }
+        if let errorCStr {
+            fail(String(cString: errorCStr))
+        }
+        return result!
+    }
+
     public var description: String {
         var result: String? = nil
         let errorCStr = bytes.withRtcBytes { bytes in
@@ -98,12 +112,24 @@ public struct CallLinkState {
     public var restrictions: Restrictions
     public var revoked: Bool
     public var expiration: Date
+    /// Is never null, but may be empty.
+    public var description: String
+    /// An RGB color, as 0xRRGGBB.
+    public var accentColor: UInt32?
+    /// An app-defined reference to an avatar stored elsewhere.
+    public var avatar: String?
+    /// Set when rotating the admin passkey found that an earlier attempt had already gone through.
+    public var oldAdminPasskeyRejected: Bool
 
-    public init(name: String, restrictions: Restrictions, revoked: Bool, expiration: Date) {
+    public init(name: String, restrictions: Restrictions, revoked: Bool, expiration: Date, description: String = "", accentColor: UInt32? = nil, avatar: String? = nil, oldAdminPasskeyRejected: Bool = false) {
         self.name = name
         self.restrictions = restrictions
         self.revoked = revoked
         self.expiration = expiration
+        self.description = description
+        self.accentColor = accentColor
+        self.avatar = avatar
+        self.oldAdminPasskeyRejected = oldAdminPasskeyRejected
     }
 
     static func fromRtc(_ rtcResponse: rtc_calllinks_CallLinkState) -> Self {
@@ -118,6 +144,15 @@ public struct CallLinkState {
             restrictions = .unknown
         }
         let expiration = Date(timeIntervalSince1970: TimeInterval(rtcResponse.expiration_epoch_seconds))
-        return Self(name: name, restrictions: restrictions, revoked: rtcResponse.revoked, expiration: expiration)
+        return Self(
+            name: name,
+            restrictions: restrictions,
+            revoked: rtcResponse.revoked,
+            expiration: expiration,
+            description: rtcResponse.description.toString() ?? "",
+            accentColor: rtcResponse.accent_color.asUInt32(),
+            avatar: rtcResponse.avatar.toString(),
+            oldAdminPasskeyRejected: rtcResponse.old_admin_passkey_rejected
+        )
     }
 }
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: AGPL-3.0-only
 //
 
+import AVFoundation
 import SignalRingRTC.RingRTC
 import WebRTC
 
@@ -77,6 +78,44 @@ public enum CallManagerEvent: Int32 {
     case receivedOfferWhileActive
     /// Received an offer while already handling an active call and glare was detected.
     case receivedOfferWithGlare
+    /// The remote side has put the call on hold and stopped sending media.
+    case remoteHeld
+    /// The remote side has taken the call off hold.
+    case remoteResumed
+    /// Received an offer while in an accepted call with call waiting enabled.
+    case incomingCallWaiting
+    /// The call ended because the callee declined it as busy.
+    case endedRemoteDeclinedBusy
+    /// The call ended because the callee declined it as do-not-disturb.
+    case endedRemoteDeclinedDoNotDisturb
+    /// The call ended because the callee declined it and sent a text reply.
+    case endedRemoteDeclinedTextReplySent
+    /// The remote side has handed the call off to another of its devices, which is now connected.
+    case remoteHandedOff
+    /// The call ended because it was handed off to another of the local user's devices.
+    case endedHandedOff
+    /// The callee has started sending early media, which is played while ringing.
+    case remoteEarlyMedia
+    /// The remote side asks to send video; answer with respondToVideoRequest().
+    case remoteVideoAddRequested
+    /// The remote side started sending video after the local side agreed.
+    case remoteVideoAdded
+    /// The remote side stopped sending video.
+    case remoteVideoRemoved
+    /// The remote side agreed to receive video, which can now be sent.
+    case localVideoAddAccepted
+    /// The remote side declined to receive video.
+    case localVideoAddDeclined
+    /// Nothing has been received from the remote side for the media stall timeout.
+    case mediaStalled
+    /// The call ended because nothing was received for the media stall timeout.
+    case endedMediaStalled
+    /// The call ended because the remote side lost its connection.
+    case endedRemoteHangupNetworkFailure
+    /// The call ended because the callee's application declined it by policy.
+    case endedRemoteDeclinedByPolicy
+    /// The call ended because the remote side stopped ringing without an answer.
+    case endedRemoteHangupRingTimeout
 }
 
 // In sync with WebRTC's PeerConnection.AdapterType.
@@ -110,9 +149,15 @@ public enum NetworkAdapterType: Int32 {
 @available(iOSApplicationExtension, unavailable)
 public struct NetworkRoute {
     public let localAdapterType: NetworkAdapterType
+    /// The adapter the VPN runs over, if localAdapterType is .vpn.
+    public let localAdapterTypeUnderVpn: NetworkAdapterType
+    /// Whether media goes through a relay (TURN) server on either side.
+    public let relayed: Bool
 
-    public init(localAdapterType: NetworkAdapterType) {
+    public init(localAdapterType: NetworkAdapterType, localAdapterTypeUnderVpn: NetworkAdapterType = .unknown, relayed: Bool = false) {
         self.localAdapterType = localAdapterType
+        self.localAdapterTypeUnderVpn = localAdapterTypeUnderVpn
+        self.relayed = relayed
     }
 }
 
@@ -140,6 +185,33 @@ public enum HangupType: Int32 {
     case busy = 3
     /// Call needed permission on a different device.
     case needPermission = 4
+    /// Call was declined by the callee as busy.
+    case declinedBusy = 5
+    /// Call was declined by the callee as do-not-disturb.
+    case declinedDoNotDisturb = 6
+    /// Call was declined by the callee, who sent a text reply.
+    case declinedTextReplySent = 7
+    /// Call was handed off to a different device of the same user.
+    case handedOff = 8
+    /// Call ended because the sender lost its connection.
+    case networkFailure = 9
+    /// Call was declined by the callee's application by policy.
+    case declinedByPolicy = 10
+    /// Call ended because nobody answered before the ring timeout.
+    case ringTimeout = 11
+}
+
+/// Describes why the callee declined a call.
+@available(iOSApplicationExtension, unavailable)
+public enum DeclineReason: Int32 {
+    /// The callee is busy.
+    case busy = 0
+    /// The callee does not want to be disturbed.
+    case doNotDisturb = 1
+    /// The callee sent a text reply instead.
+    case textReplySent = 2
+    /// The application declined the call, such as for a blocked contact.
+    case policy = 3
 }
 
 @available(iOSApplicationExtension, unavailable)
@@ -307,6 +379,14 @@ public protocol CallManagerDelegate: AnyObject {
      */
     @MainActor
     func callManager(_ callManager: CallManager<CallManagerDelegateCallType, Self>, didUpdateRingForGroup groupId: Data, ringId: Int64, sender: UUID, update: RingUpdate)
+
+    /**
+     * Another of the current user's devices has asked this device to take over its
+     * accepted call with `remoteUserId`. To do so, create a call reference and invoke
+     * the takeOverCall() API with the given `callId`.
+     */
+    @MainActor
+    func callManager(_ callManager: CallManager<CallManagerDelegateCallType, Self>, didRequestHandoffFrom remoteUserId: UUID, callId: UInt64, remoteDeviceId: UInt32, callMediaType: CallMediaType)
 }
 
 @available(iOSApplicationExtension, unavailable)
@@ -335,6 +415,9 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
 
     private var videoCaptureController: VideoCaptureController?
 
+    // A local camera preview started before proceed(), if any.
+    private var localPreview: (videoSource: RTCVideoSource, videoTrack: RTCVideoTrack, videoCaptureController: VideoCaptureController)?
+
     public init(httpClient: HTTPClient, fieldTrials: [String: String] = [:], audioDevice: RTCAudioDevice? = nil) {
         // Initialize the global object (mainly for logging).
         CallManagerGlobal.initialize(fieldTrials: fieldTrials)
@@ -380,6 +463,58 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
         }
     }
 
+    /// Changes how much RingRTC logs, such as when the user turns on debug logging.
+    /// Release builds of RingRTC log Info at most.
+    public static func setMaxLogLevel(_ level: RingRTCLogLevel) {
+        Logger.debug("setMaxLogLevel")
+
+        if !rtc_log_setMaxLevel(level.rawValue) {
+            failDebug("setMaxLogLevel had an error")
+        }
+    }
+
+    /// Overrides how much one part of RingRTC logs, such as "group_call" or "http", so that
+    /// support can turn on targeted debugging. Stays in effect across ``setMaxLogLevel(_:)``
+    /// until ``clearModuleLogLevels()`` is called, and is likewise limited to Info in release
+    /// builds.
+    public static func setModuleLogLevel(_ module: String, _ level: RingRTCLogLevel) {
+        Logger.debug("setModuleLogLevel")
+
+        let rtcModule = rtc_String.allocate(from: module)
+        defer { rtcModule.deallocate() }
+        if !rtc_log_setModuleLevel(rtcModule, level.rawValue) {
+            failDebug("setModuleLogLevel had an error")
+        }
+    }
+
+    public static func clearModuleLogLevels() {
+        Logger.debug("clearModuleLogLevels")
+
+        rtc_log_clearModuleLevels()
+    }
+
+    /// Returns recent state transitions, ICE connection changes, and HTTP and decryption failures
+    /// as UTF-8 JSON, for attaching to bug reports.
+    public static func exportDiagnostics() -> Data {
+        var result: Data? = nil
+        rtc_diagnostics_export(&result) { resultOpaquePtr, exported in
+            resultOpaquePtr!.assumingMemoryBound(to: Optional<Data>.self).pointee = exported.toData()
+        }
+        return result!
+    }
+
+    /// Sets the file that the state of every call, in-flight HTTP requests, and the most recent
+    /// events from ``exportDiagnostics()`` are written to if RingRTC crashes. The file is created
+    /// (or truncated) right away; returns false if that fails.
+    @discardableResult
+    public static func setCrashDumpPath(_ path: String) -> Bool {
+        Logger.debug("setCrashDumpPath")
+
+        let rtcPath = rtc_String.allocate(from: path)
+        defer { rtcPath.deallocate() }
+        return rtc_crashDump_setPath(rtcPath)
+    }
+
     deinit {
         // Close the RingRTC Call Manager.
         let retPtr = ringrtcClose(self.ringRtcCallManager)
@@ -413,16 +548,303 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
         _ = unmanagedCall.retain()
     }
 
+    /// Takes over an accepted call that another of the user's devices is handing off.
+    ///
+    /// - Parameter call: The application's reference for the remote peer of the call.
+    /// - Parameter callId: The call ID from the handoff request.
+    /// - Parameter callMediaType: The media type from the handoff request.
+    /// - Parameter localDevice: The local device ID of the client.
+    @MainActor
+    public func takeOverCall(call: CallType, callId: UInt64, callMediaType: CallMediaType, localDevice: UInt32) throws {
+        Logger.debug("takeOverCall")
+
+        let unmanagedCall: Unmanaged<CallType> = Unmanaged.passUnretained(call)
+
+        let retPtr = ringrtcTakeOverCall(ringRtcCallManager, unmanagedCall.toOpaque(), callId, callMediaType.rawValue, localDevice)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "takeOverCall() function failure")
+        }
+
+        // Keep the call reference around until rust says we're done with the call.
+        _ = unmanagedCall.retain()
+    }
+
+    /// Accepts the incoming call. With `audioMuted` and/or `videoDisabled`, the
+    /// microphone and/or camera start out off, and the remote side is told so along
+    /// with the accept, without racing a separate call afterwards.
     @MainActor
-    public func accept(callId: UInt64) throws {
+    public func accept(callId: UInt64, audioMuted: Bool = false, videoDisabled: Bool = false) throws {
         Logger.debug("accept")
 
-        let retPtr = ringrtcAccept(ringRtcCallManager, callId)
+        if audioMuted || videoDisabled, let callContext = ringrtcGetActiveCallContext(ringRtcCallManager) {
+            let appCallContext: CallContext = Unmanaged.fromOpaque(callContext).takeUnretainedValue()
+            if audioMuted {
+                appCallContext.setAudioEnabled(enabled: false)
+                isAudioEnabled = false
+            }
+            if videoDisabled && appCallContext.setVideoEnabled(enabled: false) {
+                appCallContext.setCameraEnabled(enabled: false)
+            }
+        }
+
+        let retPtr = ringrtcAccept(ringRtcCallManager, callId, audioMuted, videoDisabled)
         if retPtr == nil {
             throw CallManagerError.apiFailed(description: "accept() function failure")
         }
     }
 
+    /// Puts the call on hold or takes it off hold, e.g. from a CallKit hold action.
+    ///
+    /// While on hold, no media is sent and the remote side gets a `remoteHeld` event.
+    /// Has no effect until the call has been accepted.
+    @MainActor
+    public func setOnHold(callId: UInt64, onHold: Bool) throws {
+        Logger.debug("setOnHold")
+
+        let retPtr = ringrtcSetOnHold(ringRtcCallManager, callId, onHold)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "setOnHold() function failure")
+        }
+    }
+
+    /// Restarts ICE on the active call with new credentials, e.g. after the network changed.
+    ///
+    /// Has no effect until the call has been accepted.
+    @MainActor
+    public func restartIce(callId: UInt64) throws {
+        Logger.debug("restartIce")
+
+        let retPtr = ringrtcRestartIce(ringRtcCallManager, callId)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "restartIce() function failure")
+        }
+    }
+
+    /// Starts sending audio to the caller of a ringing incoming call before accepting it,
+    /// such as a custom ringback tone or IVR prompts.
+    ///
+    /// Ignored unless the caller enabled early media when proceeding.
+    @MainActor
+    public func startEarlyMedia(callId: UInt64) throws {
+        Logger.debug("startEarlyMedia")
+
+        let retPtr = ringrtcStartEarlyMedia(ringRtcCallManager, callId)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "startEarlyMedia() function failure")
+        }
+    }
+
+    /// Asks the remote side to agree to the local side sending video on the accepted call.
+    ///
+    /// The answer arrives as localVideoAddAccepted or localVideoAddDeclined, and video
+    /// should only be sent once accepted.
+    @MainActor
+    public func enableVideo(callId: UInt64) throws {
+        Logger.debug("enableVideo")
+
+        let retPtr = ringrtcEnableVideo(ringRtcCallManager, callId)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "enableVideo() function failure")
+        }
+    }
+
+    /// Stops sending video on the accepted call. The remote side is told, but doesn't
+    /// need to agree.
+    @MainActor
+    public func disableVideo(callId: UInt64) throws {
+        Logger.debug("disableVideo")
+
+        let retPtr = ringrtcDisableVideo(ringRtcCallManager, callId)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "disableVideo() function failure")
+        }
+    }
+
+    /// Answers the remote side's request to send video, received as remoteVideoAddRequested.
+    @MainActor
+    public func respondToVideoRequest(callId: UInt64, accepted: Bool) throws {
+        Logger.debug("respondToVideoRequest")
+
+        let retPtr = ringrtcRespondToVideoRequest(ringRtcCallManager, callId, accepted)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "respondToVideoRequest() function failure")
+        }
+    }
+
+    /// Returns a short code derived from the keys negotiated for the call, or nil if
+    /// the call is not active or not yet connected. Both parties see the same code
+    /// unless someone interfered with the signaling, so users can read it aloud to
+    /// each other to verify the call.
+    @MainActor
+    public func verificationCode(callId: UInt64) -> String? {
+        Logger.debug("verificationCode")
+
+        let code = ringrtcGetVerificationCode(ringRtcCallManager, callId)
+        guard code >= 0 else {
+            return nil
+        }
+        return String(format: "%06d", code)
+    }
+
+    /// Hands the accepted call off to another of the user's devices, e.g. to move
+    /// the call from a phone to a desktop. The call ends locally once the target
+    /// device has connected.
+    @MainActor
+    public func handOff(callId: UInt64, remoteUserId: UUID, targetDeviceId: UInt32) throws {
+        Logger.debug("handOff")
+
+        let remoteUserIdSlice = allocatedAppByteSliceFromData(maybe_data: remoteUserId.data)
+        defer { remoteUserIdSlice.bytes?.deallocate() }
+
+        let retPtr = ringrtcHandOff(ringRtcCallManager, callId, remoteUserIdSlice, targetDeviceId)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "handOff() function failure")
+        }
+    }
+
+    /// Enables or disables restarting ICE automatically when the connection of an
+    /// accepted call drops, as when switching between Wi-Fi and cellular.
+    @MainActor
+    public func setRestartIceOnDisconnect(_ enabled: Bool) throws {
+        Logger.debug("setRestartIceOnDisconnect")
+
+        let retPtr = ringrtcSetRestartIceOnDisconnect(ringRtcCallManager, enabled)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "setRestartIceOnDisconnect() function failure")
+        }
+    }
+
+    /// Lets CallKit decide when audio starts. Until `audioSessionDidActivate(_:)`,
+    /// audio units stay stopped, even once a call is accepted or a group call is
+    /// joined, rather than starting before CallKit has activated the audio session
+    /// (which leaves the call without audio). Call this before the first call.
+    @MainActor
+    public func useManualAudioSession() throws {
+        Logger.debug("useManualAudioSession")
+
+        let rtcAudioSession = RTCAudioSession.sharedInstance()
+        rtcAudioSession.useManualAudio = true
+        rtcAudioSession.isAudioEnabled = false
+        try setAudioSessionActive(false)
+    }
+
+    /// Starts audio once CallKit has activated the audio session.
+    /// Call from `CXProviderDelegate.provider(_:didActivate:)`.
+    @MainActor
+    public func audioSessionDidActivate(_ audioSession: AVAudioSession) throws {
+        Logger.debug("audioSessionDidActivate")
+
+        let rtcAudioSession = RTCAudioSession.sharedInstance()
+        rtcAudioSession.audioSessionDidActivate(audioSession)
+        rtcAudioSession.isAudioEnabled = true
+        try setAudioSessionActive(true)
+    }
+
+    /// Stops audio when CallKit deactivates the audio session.
+    /// Call from `CXProviderDelegate.provider(_:didDeactivate:)`.
+    @MainActor
+    public func audioSessionDidDeactivate(_ audioSession: AVAudioSession) throws {
+        Logger.debug("audioSessionDidDeactivate")
+
+        try setAudioSessionActive(false)
+        let rtcAudioSession = RTCAudioSession.sharedInstance()
+        rtcAudioSession.isAudioEnabled = false
+        rtcAudioSession.audioSessionDidDeactivate(audioSession)
+    }
+
+    private func setAudioSessionActive(_ active: Bool) throws {
+        let retPtr = ringrtcSetAudioSessionActive(ringRtcCallManager, active)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "setAudioSessionActive() function failure")
+        }
+    }
+
+    /// Enables or disables relay-only (TURN-only) ICE for all calls, so the remote
+    /// peer never learns the user's IP address, even when proceed() doesn't set hideIp.
+    @MainActor
+    public func setRelayOnlyByDefault(_ enabled: Bool) throws {
+        Logger.debug("setRelayOnlyByDefault")
+
+        let retPtr = ringrtcSetRelayOnlyByDefault(ringRtcCallManager, enabled)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "setRelayOnlyByDefault() function failure")
+        }
+    }
+
+    /// Limits the size of the opaque offers and answers passed to the delegate for
+    /// sending, so they fit in a push payload. Messages over the limit are compressed,
+    /// and then have optional fields trimmed until they fit. Pass 0 for no limit.
+    @MainActor
+    public func setMaxSignalingMessageSize(_ maxSize: UInt32) throws {
+        Logger.debug("setMaxSignalingMessageSize")
+
+        let retPtr = ringrtcSetMaxSignalingMessageSize(ringRtcCallManager, maxSize)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "setMaxSignalingMessageSize() function failure")
+        }
+    }
+
+    /// Enables or disables call waiting.
+    ///
+    /// When enabled, an offer received during an accepted call is reported
+    /// with `incomingCallWaiting` instead of being declined as busy.
+    @MainActor
+    public func setCallWaitingEnabled(_ enabled: Bool) throws {
+        Logger.debug("setCallWaitingEnabled")
+
+        let retPtr = ringrtcSetCallWaitingEnabled(ringRtcCallManager, enabled)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "setCallWaitingEnabled() function failure")
+        }
+    }
+
+    /// Puts the active call on hold and accepts the waiting call.
+    ///
+    /// The accepted call starts like any other incoming call and is accepted
+    /// automatically once it connects.
+    @MainActor
+    public func holdAndAcceptWaitingCall() throws {
+        Logger.debug("holdAndAcceptWaitingCall")
+
+        let retPtr = ringrtcHoldAndAcceptWaitingCall(ringRtcCallManager)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "holdAndAcceptWaitingCall() function failure")
+        }
+    }
+
+    /// Declines the waiting call, which is reported to the caller as busy.
+    @MainActor
+    public func declineWaitingCall() throws {
+        Logger.debug("declineWaitingCall")
+
+        let retPtr = ringrtcDeclineWaitingCall(ringRtcCallManager)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "declineWaitingCall() function failure")
+        }
+    }
+
+    /// Resumes the held call. Only allowed once no other call is active.
+    @MainActor
+    public func resumeHeldCall() throws {
+        Logger.debug("resumeHeldCall")
+
+        let retPtr = ringrtcResumeHeldCall(ringRtcCallManager)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "resumeHeldCall() function failure")
+        }
+    }
+
+    /// Hangs up the held call.
+    @MainActor
+    public func hangupHeldCall() throws {
+        Logger.debug("hangupHeldCall")
+
+        let retPtr = ringrtcHangupHeldCall(ringRtcCallManager)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "hangupHeldCall() function failure")
+        }
+    }
+
     @MainActor
     public func hangup() throws {
         Logger.debug("hangup")
@@ -433,6 +855,18 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
         }
     }
 
+    /// Declines the active incoming call, telling the caller why instead
+    /// of sending a normal hangup.
+    @MainActor
+    public func decline(callId: UInt64, reason: DeclineReason) throws {
+        Logger.debug("decline")
+
+        let retPtr = ringrtcDecline(ringRtcCallManager, callId, reason.rawValue)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "decline() function failure")
+        }
+    }
+
     @MainActor
     public func cancelGroupRing(groupId: Data, ringId: Int64, reason: RingCancelReason?) throws {
         Logger.debug("cancelGroupRing")
@@ -457,8 +891,13 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
     ///   - videoCaptureController: UI provided capturer interface
     ///   - dataMode: The desired data mode to start the session with
     ///   - audioLevelsIntervalMillis: If non-zero, the desired interval between audio level events (in milliseconds)
+    ///   - ringTimeoutMillis: If non-zero, how long the call may go unaccepted, from when it was created (1 second to 5 minutes)
+    ///   - connectTimeoutMillis: If non-zero, how long the call may take to connect after proceeding (1 second to 2 minutes)
+    ///   - earlyMedia: Whether to play audio the callee sends before accepting, such as a custom ringback tone (outgoing calls only)
+    ///   - mediaStallTimeoutMillis: If non-zero, report mediaStalled when nothing is received for this long while connected (3 seconds to 2 minutes)
+    ///   - hangUpOnMediaStall: Whether to end the call with endedMediaStalled once its media stalls
     @MainActor
-    public func proceed(callId: UInt64, iceServers: [RTCIceServer], hideIp: Bool, videoCaptureController: VideoCaptureController, dataMode: DataMode, audioLevelsIntervalMillis: UInt64?) throws {
+    public func proceed(callId: UInt64, iceServers: [RTCIceServer], hideIp: Bool, videoCaptureController: VideoCaptureController, dataMode: DataMode, audioLevelsIntervalMillis: UInt64?, ringTimeoutMillis: UInt64? = nil, connectTimeoutMillis: UInt64? = nil, earlyMedia: Bool = false, mediaStallTimeoutMillis: UInt64? = nil, hangUpOnMediaStall: Bool = false) throws {
         Logger.info("proceed(): callId: 0x\(String(callId, radix: 16)), hideIp: \(hideIp)")
         for iceServer in iceServers {
             for url in iceServer.urlStrings {
@@ -473,10 +912,75 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
         let audioTrack = self.factory!.audioTrack(with: audioSource, trackId: "audio1")
         audioTrack.isEnabled = false
 
+        let videoSource: RTCVideoSource
+        let videoTrack: RTCVideoTrack
+        if let localPreview = self.localPreview, localPreview.videoCaptureController === videoCaptureController {
+            // Hand over the local preview so the camera keeps running.
+            Logger.info("proceed(): using local preview")
+            videoSource = localPreview.videoSource
+            videoTrack = localPreview.videoTrack
+            videoTrack.isEnabled = false
+            self.localPreview = nil
+        } else {
+            stopLocalPreview()
+            (videoSource, videoTrack) = createLocalVideo(videoCaptureController: videoCaptureController)
+            videoTrack.isEnabled = false
+        }
+
+        // Create a call context object to hold on to some of
+        // the settings needed by the application when actually
+        // creating the connection.
+        let appCallContext = CallContext(iceServers: iceServers, hideIp: hideIp, audioSource: audioSource, audioTrack: audioTrack, videoSource: videoSource, videoTrack: videoTrack, videoCaptureController: videoCaptureController)
+
+        let retPtr = ringrtcProceed(ringRtcCallManager, callId, appCallContext.getWrapper(), dataMode.rawValue, audioLevelsIntervalMillis ?? 0, hideIp, ringTimeoutMillis ?? 0, connectTimeoutMillis ?? 0, earlyMedia, mediaStallTimeoutMillis ?? 0, hangUpOnMediaStall)
+        if retPtr == nil {
+            throw CallManagerError.apiFailed(description: "proceed() function failure")
+        }
+    }
+
+    /// Starts capturing from the local camera before a call is accepted,
+    /// for example to show a self-view on the incoming call screen.
+    ///
+    /// No peer connection is created. Add a renderer to the returned track
+    /// to show the preview. If proceed() is later called with the same
+    /// videoCaptureController, the capturer is handed over to the call
+    /// without restarting it; the track is then disabled until the call
+    /// enables local video. Otherwise, call stopLocalPreview().
+    ///
+    /// - Parameters:
+    ///   - videoCaptureController: UI provided capturer interface
+    @MainActor
+    public func startLocalPreview(videoCaptureController: VideoCaptureController) -> RTCVideoTrack {
+        Logger.info("startLocalPreview():")
+
+        stopLocalPreview()
+
+        let (videoSource, videoTrack) = createLocalVideo(videoCaptureController: videoCaptureController)
+        videoTrack.isEnabled = true
+        videoCaptureController.startCapture()
+
+        self.localPreview = (videoSource: videoSource, videoTrack: videoTrack, videoCaptureController: videoCaptureController)
+        return videoTrack
+    }
+
+    /// Stops the local preview started with startLocalPreview(), if it
+    /// was not handed over to a call.
+    @MainActor
+    public func stopLocalPreview() {
+        guard let localPreview = self.localPreview else {
+            return
+        }
+
+        Logger.info("stopLocalPreview():")
+        localPreview.videoTrack.isEnabled = false
+        localPreview.videoCaptureController.stopCapture()
+        self.localPreview = nil
+    }
+
+    private func createLocalVideo(videoCaptureController: VideoCaptureController) -> (RTCVideoSource, RTCVideoTrack) {
         let videoSource = self.factory!.videoSource()
         // Note: This must stay "video1" to stay in sync with V4 signaling.
         let videoTrack = self.factory!.videoTrack(with: videoSource, trackId: "video1")
-        videoTrack.isEnabled = false
 
         // Define maximum output video format for 1:1 calls.
         videoSource.adaptOutputFormat(
@@ -487,15 +991,7 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
 
         videoCaptureController.capturerDelegate = videoSource
 
-        // Create a call context object to hold on to some of
-        // the settings needed by the application when actually
-        // creating the connection.
-        let appCallContext = CallContext(iceServers: iceServers, hideIp: hideIp, audioSource: audioSource, audioTrack: audioTrack, videoSource: videoSource, videoTrack: videoTrack, videoCaptureController: videoCaptureController)
-
-        let retPtr = ringrtcProceed(ringRtcCallManager, callId, appCallContext.getWrapper(), dataMode.rawValue, audioLevelsIntervalMillis ?? 0)
-        if retPtr == nil {
-            throw CallManagerError.apiFailed(description: "proceed() function failure")
-        }
+        return (videoSource, videoTrack)
     }
 
     @MainActor
@@ -606,6 +1102,20 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
         ringrtcUpdateDataMode(ringRtcCallManager, dataMode.rawValue)
     }
 
+    /// Caps the bitrates sent in the active direct call, on top of the data mode.
+    /// The remote side applies the caps to what it sends as well.
+    ///
+    /// - Parameters:
+    ///   - maxAudioBitrateBps: The max audio bitrate, or nil for no cap
+    ///   - maxVideoBitrateBps: The max video bitrate on top of the audio, or nil for no cap
+    ///   - maxTotalBitrateBps: The max bitrate for all media, or nil for no cap
+    @MainActor
+    public func updateBitrateConfig(maxAudioBitrateBps: UInt64? = nil, maxVideoBitrateBps: UInt64? = nil, maxTotalBitrateBps: UInt64? = nil) {
+        Logger.debug("updateBitrateConfig")
+
+        ringrtcUpdateBitrateConfig(ringRtcCallManager, maxAudioBitrateBps ?? 0, maxVideoBitrateBps ?? 0, maxTotalBitrateBps ?? 0)
+    }
+
     // MARK: - Signaling API
 
     @MainActor
@@ -944,7 +1454,7 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
 
     // MARK: - Utility Observers
 
-    func onCreateConnection(pcObserverOwned: UnsafeMutableRawPointer?, deviceId: UInt32, appCallContext: CallContext, audioJitterBufferMaxPackets: Int32, audioJitterBufferMaxTargetDelayMs: Int32) -> (connection: Connection, pc: UnsafeMutableRawPointer?) {
+    func onCreateConnection(pcObserverOwned: UnsafeMutableRawPointer?, deviceId: UInt32, appCallContext: CallContext, audioJitterBufferMaxPackets: Int32, audioJitterBufferMaxTargetDelayMs: Int32, relayOnly: Bool) -> (connection: Connection, pc: UnsafeMutableRawPointer?) {
         Logger.debug("onCreateConnection")
 
         // We create default configuration settings here as per
@@ -962,7 +1472,7 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
         configuration.tcpCandidatePolicy = .disabled
         configuration.continualGatheringPolicy = .gatherContinually
 
-        if appCallContext.hideIp {
+        if appCallContext.hideIp || relayOnly {
             configuration.iceTransportPolicy = .relay
         }
 
@@ -1038,6 +1548,16 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
         }
     }
 
+    func onHandoffRequested(remoteUserId: UUID, callId: UInt64, remoteDeviceId: UInt32, callMediaType: CallMediaType) {
+        Logger.debug("onHandoffRequested")
+
+        Task { @MainActor in
+            Logger.debug("onHandoffRequested - main.async")
+
+            self.delegate?.callManager(self, didRequestHandoffFrom: remoteUserId, callId: callId, remoteDeviceId: remoteDeviceId, callMediaType: callMediaType)
+        }
+    }
+
     // MARK: - Group Call Observers
 
     func requestMembershipProof(clientId: UInt32) {
@@ -1244,6 +1764,20 @@ public class CallManager<CallType, CallManagerDelegateType>: CallManagerInterfac
             groupCall.handleSpeakingNotification(event: event)
         }
     }
+
+    func handleCallFull(clientId: UInt32, maxDevices: UInt32?, deviceCount: UInt32?) {
+        Logger.debug("handleCallFull")
+
+        Task { @MainActor in
+            Logger.debug("handleCallFull - main.async")
+
+            guard let groupCall = self.groupCallByClientId[clientId] else {
+                return
+            }
+
+            groupCall.handleCallFull(maxDevices: maxDevices, deviceCount: deviceCount)
+        }
+    }
 }
 
 @available(iOSApplicationExtension, unavailable)
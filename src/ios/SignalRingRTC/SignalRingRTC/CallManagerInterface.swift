@@ -20,10 +20,11 @@ protocol CallManagerInterfaceDelegate: AnyObject {
     func onSendBusy(callId: UInt64, remote: UnsafeRawPointer, destinationDeviceId: UInt32?)
     func sendCallMessage(recipientUuid: UUID, message: Data, urgency: CallMessageUrgency)
     func sendCallMessageToGroup(groupId: Data, message: Data, urgency: CallMessageUrgency, overrideRecipients: [UUID])
-    func onCreateConnection(pcObserverOwned: UnsafeMutableRawPointer?, deviceId: UInt32, appCallContext: CallContext, audioJitterBufferMaxPackets: Int32, audioJitterBufferMaxTargetDelayMs: Int32) -> (connection: Connection, pc: UnsafeMutableRawPointer?)
+    func onCreateConnection(pcObserverOwned: UnsafeMutableRawPointer?, deviceId: UInt32, appCallContext: CallContext, audioJitterBufferMaxPackets: Int32, audioJitterBufferMaxTargetDelayMs: Int32, relayOnly: Bool) -> (connection: Connection, pc: UnsafeMutableRawPointer?)
     func onConnectMedia(remote: UnsafeRawPointer, appCallContext: CallContext, stream: RTCMediaStream)
     func onCompareRemotes(remote1: UnsafeRawPointer, remote2: UnsafeRawPointer) -> Bool
     func onCallConcluded(remote: UnsafeRawPointer)
+    func onHandoffRequested(remoteUserId: UUID, callId: UInt64, remoteDeviceId: UInt32, callMediaType: CallMediaType)
 
     // Group Calls
 
@@ -43,6 +44,7 @@ protocol CallManagerInterfaceDelegate: AnyObject {
     func handlePeekChanged(clientId: UInt32, peekInfo: PeekInfo)
     func handleEnded(clientId: UInt32, reason: GroupCallEndReason)
     func handleSpeakingNotification(clientId: UInt32, event: SpeechEvent)
+    func handleCallFull(clientId: UInt32, maxDevices: UInt32?, deviceCount: UInt32?)
 }
 
 @available(iOSApplicationExtension, unavailable)
@@ -83,6 +85,7 @@ class CallManagerInterface {
             onConnectMedia: callManagerInterfaceOnConnectMedia,
             onCompareRemotes: callManagerInterfaceOnCompareRemotes,
             onCallConcluded: callManagerInterfaceOnCallConcluded,
+            onHandoffRequested: callManagerInterfaceOnHandoffRequested,
 
             // Group Calls
 
@@ -101,7 +104,8 @@ class CallManagerInterface {
             handleIncomingVideoTrack: callManagerInterfaceHandleIncomingVideoTrack,
             handlePeekChanged: callManagerInterfaceHandlePeekChanged,
             handleEnded: callManagerInterfaceHandleEnded,
-            handleSpeakingNotification: callManagerInterfaceHandleSpeakingNotification
+            handleSpeakingNotification: callManagerInterfaceHandleSpeakingNotification,
+            handleCallFull: callManagerInterfaceHandleCallFull
         )
     }
 
@@ -127,7 +131,7 @@ class CallManagerInterface {
         }
     }
 
-    func onNetworkRouteChangedFor(remote: UnsafeRawPointer, localNetworkAdapterType: Int32) {
+    func onNetworkRouteChangedFor(remote: UnsafeRawPointer, localNetworkAdapterType: Int32, localNetworkAdapterTypeUnderVpn: Int32, relayed: Bool) {
         guard let delegate = self.callManagerObserverDelegate else {
             return
         }
@@ -137,7 +141,14 @@ class CallManagerInterface {
             return
         }
 
-        let networkRoute = NetworkRoute(localAdapterType: validLocalNetworkAdapterType)
+        guard let validLocalNetworkAdapterTypeUnderVpn = NetworkAdapterType(rawValue: localNetworkAdapterTypeUnderVpn) else {
+            failDebug("invalid network adapter type: \(localNetworkAdapterTypeUnderVpn)")
+            return
+        }
+
+        let networkRoute = NetworkRoute(localAdapterType: validLocalNetworkAdapterType,
+                                        localAdapterTypeUnderVpn: validLocalNetworkAdapterTypeUnderVpn,
+                                        relayed: relayed)
         delegate.onNetworkRouteChangedFor(remote: remote, networkRoute: networkRoute)
     }
 
@@ -213,12 +224,12 @@ class CallManagerInterface {
         delegate.sendCallMessageToGroup(groupId: groupId, message: message, urgency: urgency, overrideRecipients: overrideRecipients)
     }
 
-    func onCreateConnection(pcObserverOwned: UnsafeMutableRawPointer?, deviceId: UInt32, appCallContext: CallContext, audioJitterBufferMaxPackets: Int32, audioJitterBufferMaxTargetDelayMs: Int32) -> (connection: Connection, pc: UnsafeMutableRawPointer?)? {
+    func onCreateConnection(pcObserverOwned: UnsafeMutableRawPointer?, deviceId: UInt32, appCallContext: CallContext, audioJitterBufferMaxPackets: Int32, audioJitterBufferMaxTargetDelayMs: Int32, relayOnly: Bool) -> (connection: Connection, pc: UnsafeMutableRawPointer?)? {
         guard let delegate = self.callManagerObserverDelegate else {
             return nil
         }
 
-        return delegate.onCreateConnection(pcObserverOwned: pcObserverOwned, deviceId: deviceId, appCallContext: appCallContext, audioJitterBufferMaxPackets: audioJitterBufferMaxPackets, audioJitterBufferMaxTargetDelayMs: audioJitterBufferMaxTargetDelayMs)
+        return delegate.onCreateConnection(pcObserverOwned: pcObserverOwned, deviceId: deviceId, appCallContext: appCallContext, audioJitterBufferMaxPackets: audioJitterBufferMaxPackets, audioJitterBufferMaxTargetDelayMs: audioJitterBufferMaxTargetDelayMs, relayOnly: relayOnly)
     }
 
     func onConnectedMedia(remote: UnsafeRawPointer, appCallContext: CallContext, stream: RTCMediaStream) {
@@ -245,6 +256,14 @@ class CallManagerInterface {
         delegate.onCallConcluded(remote: remote)
     }
 
+    func onHandoffRequested(remoteUserId: UUID, callId: UInt64, remoteDeviceId: UInt32, callMediaType: CallMediaType) {
+        guard let delegate = self.callManagerObserverDelegate else {
+            return
+        }
+
+        delegate.onHandoffRequested(remoteUserId: remoteUserId, callId: callId, remoteDeviceId: remoteDeviceId, callMediaType: callMediaType)
+    }
+
     // Group Calls
 
     func groupCallRingUpdate(groupId: Data, ringId: Int64, sender: UUID, update: RingUpdate) {
@@ -366,6 +385,14 @@ class CallManagerInterface {
 
         delegate.handleSpeakingNotification(clientId: clientId, event: event)
     }
+
+    func handleCallFull(clientId: UInt32, maxDevices: UInt32?, deviceCount: UInt32?) {
+        guard let delegate = self.callManagerObserverDelegate else {
+            return
+        }
+
+        delegate.handleCallFull(clientId: clientId, maxDevices: maxDevices, deviceCount: deviceCount)
+    }
 }
 
 @available(iOSApplicationExtension, unavailable)
@@ -421,7 +448,7 @@ func callManagerInterfaceOnCallEvent(object: UnsafeMutableRawPointer?, remote: U
 }
 
 @available(iOSApplicationExtension, unavailable)
-func callManagerInterfaceOnNetworkRouteChanged(object: UnsafeMutableRawPointer?, remote: UnsafeRawPointer?, localNetworkAdapterType: Int32) {
+func callManagerInterfaceOnNetworkRouteChanged(object: UnsafeMutableRawPointer?, remote: UnsafeRawPointer?, localNetworkAdapterType: Int32, localNetworkAdapterTypeUnderVpn: Int32, relayed: Bool) {
     guard let object = object else {
         failDebug("object was unexpectedly nil")
         return
@@ -433,7 +460,7 @@ func callManagerInterfaceOnNetworkRouteChanged(object: UnsafeMutableRawPointer?,
         return
     }
 
-    obj.onNetworkRouteChangedFor(remote: remote, localNetworkAdapterType: localNetworkAdapterType)
+    obj.onNetworkRouteChangedFor(remote: remote, localNetworkAdapterType: localNetworkAdapterType, localNetworkAdapterTypeUnderVpn: localNetworkAdapterTypeUnderVpn, relayed: relayed)
 }
 
 @available(iOSApplicationExtension, unavailable)
@@ -680,7 +707,7 @@ func callManagerInterfaceSendCallMessageToGroup(object: UnsafeMutableRawPointer?
 }
 
 @available(iOSApplicationExtension, unavailable)
-func callManagerInterfaceOnCreateConnectionInterface(object: UnsafeMutableRawPointer?, pcObserverOwned: UnsafeMutableRawPointer?, deviceId: UInt32, context: UnsafeMutableRawPointer?, audioJitterBufferMaxPackets: Int32, audioJitterBufferMaxTargetDelayMs: Int32) -> AppConnectionInterface {
+func callManagerInterfaceOnCreateConnectionInterface(object: UnsafeMutableRawPointer?, pcObserverOwned: UnsafeMutableRawPointer?, deviceId: UInt32, context: UnsafeMutableRawPointer?, audioJitterBufferMaxPackets: Int32, audioJitterBufferMaxTargetDelayMs: Int32, relayOnly: Bool) -> AppConnectionInterface {
     guard let object = object else {
         failDebug("object was unexpectedly nil")
 
@@ -707,7 +734,7 @@ func callManagerInterfaceOnCreateConnectionInterface(object: UnsafeMutableRawPoi
 
     let appCallContext: CallContext = Unmanaged.fromOpaque(callContext).takeUnretainedValue()
 
-    if let connectionDetails = obj.onCreateConnection(pcObserverOwned: pcObserverOwned, deviceId: deviceId, appCallContext: appCallContext, audioJitterBufferMaxPackets: audioJitterBufferMaxPackets, audioJitterBufferMaxTargetDelayMs: audioJitterBufferMaxTargetDelayMs) {
+    if let connectionDetails = obj.onCreateConnection(pcObserverOwned: pcObserverOwned, deviceId: deviceId, appCallContext: appCallContext, audioJitterBufferMaxPackets: audioJitterBufferMaxPackets, audioJitterBufferMaxTargetDelayMs: audioJitterBufferMaxTargetDelayMs, relayOnly: relayOnly) {
         return connectionDetails.connection.getWrapper(pc: connectionDetails.pc)
     } else {
         // Swift was problematic to pass back some nullable structure, so we
@@ -822,6 +849,30 @@ func callManagerInterfaceOnCallConcluded(object: UnsafeMutableRawPointer?, remot
     obj.onCallConcluded(remote: remote)
 }
 
+@available(iOSApplicationExtension, unavailable)
+func callManagerInterfaceOnHandoffRequested(object: UnsafeMutableRawPointer?, remoteUserId: AppByteSlice, callId: UInt64, remoteDeviceId: UInt32, mediaType: Int32) {
+    guard let object = object else {
+        failDebug("object was unexpectedly nil")
+        return
+    }
+    let obj: CallManagerInterface = Unmanaged.fromOpaque(object).takeUnretainedValue()
+
+    guard let remoteUserId = remoteUserId.toUUID() else {
+        failDebug("remoteUserId was unexpectedly empty")
+        return
+    }
+
+    let callMediaType: CallMediaType
+    if let validMediaType = CallMediaType(rawValue: mediaType) {
+        callMediaType = validMediaType
+    } else {
+        failDebug("unexpected call media type")
+        return
+    }
+
+    obj.onHandoffRequested(remoteUserId: remoteUserId, callId: callId, remoteDeviceId: remoteDeviceId, callMediaType: callMediaType)
+}
+
 // Group Calls
 
 @available(iOSApplicationExtension, unavailable)
@@ -994,6 +1045,7 @@ func callManagerInterfaceHandleRemoteDevicesChanged(object: UnsafeMutableRawPoin
         let deviceState = RemoteDeviceState(
             demuxId: remoteDeviceState.demuxId,
             userId: userId,
+            participantKind: ParticipantKind(rawValue: remoteDeviceState.participantKind) ?? .appUser,
             mediaKeysReceived: remoteDeviceState.mediaKeysReceived,
             addedTime: remoteDeviceState.addedTime,
             speakerTime: remoteDeviceState.speakerTime,
@@ -1139,3 +1191,24 @@ func callManagerInterfaceHandleSpeakingNotification(object: UnsafeMutableRawPoin
 
     obj.handleSpeakingNotification(clientId: clientId, event: _event)
 }
+
+@available(iOSApplicationExtension, unavailable)
+func callManagerInterfaceHandleCallFull(object: UnsafeMutableRawPointer?, clientId: UInt32, maxDevices: AppOptionalUInt32, deviceCount: AppOptionalUInt32) {
+    guard let object = object else {
+        failDebug("object was unexpectedly nil")
+        return
+    }
+    let obj: CallManagerInterface = Unmanaged.fromOpaque(object).takeUnretainedValue()
+
+    var finalMaxDevices: UInt32?
+    if maxDevices.valid {
+        finalMaxDevices = maxDevices.value
+    }
+
+    var finalDeviceCount: UInt32?
+    if deviceCount.valid {
+        finalDeviceCount = deviceCount.value
+    }
+
+    obj.handleCallFull(clientId: clientId, maxDevices: finalMaxDevices, deviceCount: finalDeviceCount)
+}
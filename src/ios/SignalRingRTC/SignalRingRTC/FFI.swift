@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: AGPL-3.0-only
 //
 
+import Foundation
 import SignalRingRTC.RingRTC
 
 // Here is the new lite/modularized pattern for FFI for a module called X
@@ -154,6 +155,12 @@ class Requests<T> {
         return id
     }
 
+    /// Adds a continuation for a request identified by RingRTC, such as by the
+    /// token returned from `rtc_sfu_peek_async`.
+    func add(_ continuation: CheckedContinuation<T, Never>, id: UInt32) {
+        self.continuationById[id] = continuation
+    }
+
     func resolve(id: UInt32, response: T) -> Bool {
         if let continuation = self.continuationById.removeValue(forKey: id) {
             continuation.resume(returning: response)
@@ -162,3 +169,31 @@ class Requests<T> {
         return false
     }
 }
+
+/// The token of a request started by one of the `_async` FFI functions, which
+/// can be cancelled from any thread, even before the token is known.
+final class RequestToken: @unchecked Sendable {
+    private let lock = NSLock()
+    private var token: UInt32 = 0
+    private var cancelled = false
+
+    func set(_ token: UInt32) {
+        let cancelled = lock.withLock {
+            self.token = token
+            return self.cancelled
+        }
+        if cancelled {
+            _ = rtc_sfu_cancel(token)
+        }
+    }
+
+    func cancel() {
+        let token = lock.withLock {
+            self.cancelled = true
+            return self.token
+        }
+        if token != 0 {
+            _ = rtc_sfu_cancel(token)
+        }
+    }
+}
@@ -53,6 +53,7 @@ public enum GroupCallEndReason: Int32 {
 public enum SpeechEvent: Int32 {
     case StoppedSpeaking = 0
     case LowerHandSuggestion
+    case SpeakingWhileMuted
 }
 
 /// The local device state for a group call.
@@ -94,11 +95,20 @@ public class Reaction {
     }
 }
 
+/// How a remote device is connected to the call.
+public enum ParticipantKind: Int32 {
+    /// A device running the app.
+    case appUser = 0
+    /// Bridged in by a gateway, such as a phone dialing in.
+    case bridged
+}
+
 /// All remote devices in a group call and their associated state.
 @available(iOSApplicationExtension, unavailable)
 public class RemoteDeviceState: Hashable {
     public let demuxId: UInt32
     public var userId: UUID
+    public internal(set) var participantKind: ParticipantKind
     public var mediaKeysReceived: Bool
 
     public internal(set) var audioMuted: Bool?
@@ -113,9 +123,10 @@ public class RemoteDeviceState: Hashable {
 
     public internal(set) var videoTrack: RTCVideoTrack?
 
-    init(demuxId: UInt32, userId: UUID, mediaKeysReceived: Bool, addedTime: UInt64, speakerTime: UInt64, isHigherResolutionPending: Bool) {
+    init(demuxId: UInt32, userId: UUID, participantKind: ParticipantKind = .appUser, mediaKeysReceived: Bool, addedTime: UInt64, speakerTime: UInt64, isHigherResolutionPending: Bool) {
         self.demuxId = demuxId
         self.userId = userId
+        self.participantKind = participantKind
         self.mediaKeysReceived = mediaKeysReceived
         self.addedTime = addedTime
         self.speakerTime = speakerTime
@@ -245,6 +256,15 @@ public protocol GroupCallDelegate: AnyObject {
     @MainActor
     func groupCall(onPeekChanged groupCall: GroupCall)
 
+    /**
+     * Indication that the group call could not be joined because it has reached its
+     * maximum number of devices. This is followed by onEnded with hasMaxDevices.
+     *
+     * Either count may be nil if it isn't known.
+     */
+    @MainActor
+    func groupCall(onCallFull groupCall: GroupCall, maxDevices: UInt32?, deviceCount: UInt32?)
+
     /**
      * Indication that group call ended due to a reason other than the user choosing
      * to disconnect from it.
@@ -877,4 +897,9 @@ public class GroupCall {
     func handleSpeakingNotification(event: SpeechEvent) {
         self.delegate?.groupCall(onSpeakingNotification: self, event: event)
     }
+
+    @MainActor
+    func handleCallFull(maxDevices: UInt32?, deviceCount: UInt32?) {
+        self.delegate?.groupCall(onCallFull: self, maxDevices: maxDevices, deviceCount: deviceCount)
+    }
 }
@@ -99,6 +99,21 @@ public struct PeekResponse {
             peekInfo: PeekInfo.fromRtc(rtcPeekResponse.value)
         )
     }
+
+    static func failure(_ errorStatusCode: UInt16) -> Self {
+        return PeekResponse(
+            errorStatusCode: errorStatusCode,
+            peekInfo: PeekInfo(
+                joinedMembers: [],
+                creator: nil,
+                eraId: nil,
+                maxDevices: nil,
+                deviceCountIncludingPendingDevices: 0,
+                deviceCountExcludingPendingDevices: 0,
+                pendingUsers: []
+            )
+        )
+    }
 }
 
 // Same as rust sfu::PeekInfo (nicer version of rtc_sfu_PeekInfo)
@@ -155,6 +170,12 @@ public enum SFUResult<Value> {
 }
 
 public class SFUClient {
+    /// A failure code for requests that couldn't be started, such as due to invalid arguments.
+    public static let requestFailedStatus: UInt16 = 602
+
+    /// A failure code for requests whose task was cancelled before they completed.
+    public static let requestCancelledStatus: UInt16 = 603
+
     private let httpClient: HTTPClient
     private let peekRequests: Requests<PeekResponse> = Requests()
     private let callLinkRequests: Requests<SFUResult<CallLinkState>> = Requests()
@@ -164,18 +185,38 @@ public class SFUClient {
         self.httpClient = httpClient
     }
 
+    /// Runs a request started by one of the `_async` FFI functions, whose
+    /// response is delivered to `requests`, cancelling it if the task is.
+    @MainActor
+    private func withCancellableRequest<T>(_ requests: Requests<T>, failure: T, start: @MainActor () -> UInt32) async -> T {
+        let requestToken = RequestToken()
+        return await withTaskCancellationHandler(operation: { @MainActor in
+            await withCheckedContinuation { continuation in
+                let token = start()
+                guard token != 0 else {
+                    continuation.resume(returning: failure)
+                    return
+                }
+                // The response is delivered on the main actor, so it can't arrive before this.
+                requests.add(continuation, id: token)
+                requestToken.set(token)
+            }
+        }, onCancel: {
+            requestToken.cancel()
+        })
+    }
+
     @MainActor
     public func peek(request: PeekRequest) async -> PeekResponse {
-        return await withCheckedContinuation { continuation in
+        return await withCancellableRequest(self.peekRequests, failure: .failure(SFUClient.requestFailedStatus)) {
             Logger.debug("peekGroupCall")
 
-            let requestId = self.peekRequests.add(continuation)
             let rtcRequest: rtc_sfu_PeekRequest = rtc_sfu_PeekRequest.allocate(request)
             defer {
                 rtcRequest.deallocate()
             }
             let delegateWrapper = SFUDelegateWrapper(self)
-            rtc_sfu_peek(self.httpClient.rtcClient, requestId, rtcRequest, delegateWrapper.asRtc())
+            return rtc_sfu_peek_async(self.httpClient.rtcClient, rtcRequest, delegateWrapper.asRtc())
         }
     }
 
@@ -193,14 +234,13 @@ public class SFUClient {
     /// - Parameter linkRootKey: The root key for the call link
     @MainActor
     public func peek(sfuUrl: String, authCredentialPresentation: [UInt8], linkRootKey: CallLinkRootKey) async -> PeekResponse {
-        return await withCheckedContinuation { continuation in
+        return await withCancellableRequest(self.peekRequests, failure: .failure(SFUClient.requestFailedStatus)) {
             Logger.debug("peekCallLinkCall")
 
-            let requestId = self.peekRequests.add(continuation)
             let delegateWrapper = SFUDelegateWrapper(self)
-            authCredentialPresentation.withRtcBytes { authCredentialPresentation in
+            return authCredentialPresentation.withRtcBytes { authCredentialPresentation in
                 linkRootKey.bytes.withRtcBytes { linkRootKey in
-                    rtc_sfu_peekCallLink(self.httpClient.rtcClient, requestId, sfuUrl, authCredentialPresentation, linkRootKey, delegateWrapper.asRtc())
+                    rtc_sfu_peekCallLink_async(self.httpClient.rtcClient, sfuUrl, authCredentialPresentation, linkRootKey, delegateWrapper.asRtc())
                 }
             }
         }
@@ -240,14 +280,13 @@ public class SFUClient {
     /// - 404: the room does not exist (or expired so long ago that it has been removed from the server)
     @MainActor
     public func readCallLink(sfuUrl: String, authCredentialPresentation: [UInt8], linkRootKey: CallLinkRootKey) async -> SFUResult<CallLinkState> {
-        return await withCheckedContinuation { continuation in
-            Logger.debug("createCallLink")
+        return await withCancellableRequest(self.callLinkRequests, failure: .failure(SFUClient.requestFailedStatus)) {
+            Logger.debug("readCallLink")
 
-            let requestId = self.callLinkRequests.add(continuation)
             let delegateWrapper = SFUDelegateWrapper(self)
-            authCredentialPresentation.withRtcBytes { authCredentialPresentation in
+            return authCredentialPresentation.withRtcBytes { authCredentialPresentation in
                 linkRootKey.bytes.withRtcBytes { linkRootKey in
-                    rtc_sfu_readCallLink(self.httpClient.rtcClient, requestId, sfuUrl, authCredentialPresentation, linkRootKey, delegateWrapper.asRtc())
+                    rtc_sfu_readCallLink_async(self.httpClient.rtcClient, sfuUrl, authCredentialPresentation, linkRootKey, delegateWrapper.asRtc())
                 }
             }
         }
@@ -296,12 +335,11 @@ public class SFUClient {
     /// - Parameter restrictions: the restrictions for joining the room. Restrictions.unknown is invalid for creation
     @MainActor
     public func createCallLink(sfuUrl: String, createCredentialPresentation: [UInt8], linkRootKey: CallLinkRootKey, adminPasskey: Data, callLinkPublicParams: [UInt8], restrictions: CallLinkState.Restrictions) async -> SFUResult<CallLinkState> {
-        return await withCheckedContinuation { continuation in
+        return await withCancellableRequest(self.callLinkRequests, failure: .failure(SFUClient.requestFailedStatus)) {
             Logger.debug("createCallLink")
 
-            let requestId = self.callLinkRequests.add(continuation)
             let delegateWrapper = SFUDelegateWrapper(self)
-            createCredentialPresentation.withRtcBytes { createCredentialPresentation in
+            return createCredentialPresentation.withRtcBytes { createCredentialPresentation in
                 linkRootKey.bytes.withRtcBytes { linkRootKey in
                     adminPasskey.withRtcBytes { adminPasskey in
                         callLinkPublicParams.withRtcBytes { callLinkPublicParams in
@@ -309,7 +347,7 @@ public class SFUClient {
                             if rawRestrictions < 0 {
                                 preconditionFailure("cannot create call link with restrictions 'unknown'")
                             }
-                            rtc_sfu_createCallLink(self.httpClient.rtcClient, requestId, sfuUrl, createCredentialPresentation, linkRootKey, adminPasskey, callLinkPublicParams, rawRestrictions, delegateWrapper.asRtc())
+                            return rtc_sfu_createCallLink_async(self.httpClient.rtcClient, sfuUrl, createCredentialPresentation, linkRootKey, adminPasskey, callLinkPublicParams, rawRestrictions, delegateWrapper.asRtc())
                         }
                     }
                 }
@@ -332,15 +370,14 @@ public class SFUClient {
     /// - Parameter newName: the new name to use
     @MainActor
     public func updateCallLinkName(sfuUrl: String, authCredentialPresentation: [UInt8], linkRootKey: CallLinkRootKey, adminPasskey: Data, newName: String) async -> SFUResult<CallLinkState> {
-        return await withCheckedContinuation { continuation in
+        return await withCancellableRequest(self.callLinkRequests, failure: .failure(SFUClient.requestFailedStatus)) {
             Logger.debug("updateCallLinkName")
 
-            let requestId = self.callLinkRequests.add(continuation)
             let delegateWrapper = SFUDelegateWrapper(self)
-            authCredentialPresentation.withRtcBytes { createCredentialPresentation in
+            return authCredentialPresentation.withRtcBytes { createCredentialPresentation in
                 linkRootKey.bytes.withRtcBytes { linkRootKey in
                     adminPasskey.withRtcBytes { adminPasskey in
-                        rtc_sfu_updateCallLink(self.httpClient.rtcClient, requestId, sfuUrl, createCredentialPresentation, linkRootKey, adminPasskey, newName, -1, -1, delegateWrapper.asRtc())
+                        rtc_sfu_updateCallLink_async(self.httpClient.rtcClient, sfuUrl, createCredentialPresentation, linkRootKey, adminPasskey, newName, -1, -1, delegateWrapper.asRtc())
                     }
                 }
             }
@@ -363,19 +400,81 @@ public class SFUClient {
     /// - Parameter restrictions: the new restrictions
     @MainActor
     public func updateCallLinkRestrictions(sfuUrl: String, authCredentialPresentation: [UInt8], linkRootKey: CallLinkRootKey, adminPasskey: Data, restrictions: CallLinkState.Restrictions) async -> SFUResult<CallLinkState> {
-        return await withCheckedContinuation { continuation in
+        return await withCancellableRequest(self.callLinkRequests, failure: .failure(SFUClient.requestFailedStatus)) {
             Logger.debug("updateCallLinkRestrictions")
 
-            let requestId = self.callLinkRequests.add(continuation)
             let delegateWrapper = SFUDelegateWrapper(self)
-            authCredentialPresentation.withRtcBytes { createCredentialPresentation in
+            return authCredentialPresentation.withRtcBytes { createCredentialPresentation in
                 linkRootKey.bytes.withRtcBytes { linkRootKey in
                     adminPasskey.withRtcBytes { adminPasskey in
                         let rawRestrictions = restrictions.toOrdinal()
                         if rawRestrictions < 0 {
                             preconditionFailure("cannot update restrictions to 'unknown'")
                         }
-                        rtc_sfu_updateCallLink(self.httpClient.rtcClient, requestId, sfuUrl, createCredentialPresentation, linkRootKey, adminPasskey, nil, rawRestrictions, -1, delegateWrapper.asRtc())
+                        return rtc_sfu_updateCallLink_async(self.httpClient.rtcClient, sfuUrl, createCredentialPresentation, linkRootKey, adminPasskey, nil, rawRestrictions, -1, delegateWrapper.asRtc())
+                    }
+                }
+            }
+        }
+    }
+
+    /// Asynchronous request to switch a call link to a new admin passkey, so that a leaked one
+    /// can be retired without recreating the link.
+    ///
+    /// Keep both passkeys until this succeeds, since a lost response leaves it unclear which one
+    /// is in effect. If the old passkey is rejected because an earlier attempt went through, the
+    /// new one is checked instead, and ``CallLinkState/oldAdminPasskeyRejected`` is set.
+    /// - On success, the new passkey is in effect and the old one can be dropped.
+    /// - 403: neither passkey is valid any more.
+    /// - On any other failure, retry with the same pair of passkeys.
+    ///
+    /// - Parameter sfuUrl: the URL to use when accessing the SFU
+    /// - Parameter authCredentialPresentation: a serialized CallLinkAuthCredentialPresentation
+    /// - Parameter linkRootKey: the root key for the call link
+    /// - Parameter adminPasskey: the passkey currently in effect
+    /// - Parameter newAdminPasskey: the passkey to switch to, from ``CallLinkRootKey/generateAdminPasskey()``
+    @MainActor
+    public func rotateCallLinkAdminPasskey(sfuUrl: String, authCredentialPresentation: [UInt8], linkRootKey: CallLinkRootKey, adminPasskey: Data, newAdminPasskey: Data) async -> SFUResult<CallLinkState> {
+        return await withCancellableRequest(self.callLinkRequests, failure: .failure(SFUClient.requestFailedStatus)) {
+            Logger.debug("rotateCallLinkAdminPasskey")
+
+            let delegateWrapper = SFUDelegateWrapper(self)
+            return authCredentialPresentation.withRtcBytes { createCredentialPresentation in
+                linkRootKey.bytes.withRtcBytes { linkRootKey in
+                    adminPasskey.withRtcBytes { adminPasskey in
+                        newAdminPasskey.withRtcBytes { newAdminPasskey in
+                            rtc_sfu_rotateCallLinkAdminPasskey_async(self.httpClient.rtcClient, sfuUrl, createCredentialPresentation, linkRootKey, adminPasskey, newAdminPasskey, delegateWrapper.asRtc())
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Asynchronous request to move a call link to a newer version of its root key, by
+    /// re-encrypting its name and details.
+    ///
+    /// Links shared with the old key keep working, since the room and key bytes don't change.
+    /// Once this succeeds, store and share `linkRootKey` in place of the old key.
+    ///
+    /// Possible failure codes include:
+    /// - 403: the admin passkey is incorrect
+    /// - 404: the room doesn't exist (or expired so long ago that it has been removed from the server)
+    ///
+    /// - Parameter sfuUrl: the URL to use when accessing the SFU
+    /// - Parameter authCredentialPresentation: a serialized CallLinkAuthCredentialPresentation
+    /// - Parameter linkRootKey: the root key for the call link, from ``CallLinkRootKey/withLatestVersion()``
+    /// - Parameter adminPasskey: the passkey specified when the link was created
+    @MainActor
+    public func migrateCallLink(sfuUrl: String, authCredentialPresentation: [UInt8], linkRootKey: CallLinkRootKey, adminPasskey: Data) async -> SFUResult<CallLinkState> {
+        return await withCancellableRequest(self.callLinkRequests, failure: .failure(SFUClient.requestFailedStatus)) {
+            Logger.debug("migrateCallLink")
+
+            let delegateWrapper = SFUDelegateWrapper(self)
+            return authCredentialPresentation.withRtcBytes { authCredentialPresentation in
+                linkRootKey.bytes.withRtcBytes { linkRootKey in
+                    adminPasskey.withRtcBytes { adminPasskey in
+                        rtc_sfu_migrateCallLink_async(self.httpClient.rtcClient, sfuUrl, authCredentialPresentation, linkRootKey, adminPasskey, delegateWrapper.asRtc())
                     }
                 }
             }
@@ -396,15 +495,14 @@ public class SFUClient {
     /// - Parameter adminPasskey: the passkey specified when the link was created
     @MainActor
     public func deleteCallLink(sfuUrl: String, authCredentialPresentation: [UInt8], linkRootKey: CallLinkRootKey, adminPasskey: Data) async -> SFUResult<()> {
-        return await withCheckedContinuation { continuation in
+        return await withCancellableRequest(self.emptyRequests, failure: .failure(SFUClient.requestFailedStatus)) {
             Logger.debug("deleteCallLink")
 
-            let requestId = self.emptyRequests.add(continuation)
             let delegateWrapper = SFUDelegateWrapper(self)
-            authCredentialPresentation.withRtcBytes { createCredentialPresentation in
+            return authCredentialPresentation.withRtcBytes { createCredentialPresentation in
                 linkRootKey.bytes.withRtcBytes { linkRootKey in
                     adminPasskey.withRtcBytes { adminPasskey in
-                        rtc_sfu_deleteCallLink(self.httpClient.rtcClient, requestId, sfuUrl, createCredentialPresentation, linkRootKey, adminPasskey, delegateWrapper.asEmptyRtc())
+                        rtc_sfu_deleteCallLink_async(self.httpClient.rtcClient, sfuUrl, createCredentialPresentation, linkRootKey, adminPasskey, delegateWrapper.asEmptyRtc())
                     }
                 }
             }
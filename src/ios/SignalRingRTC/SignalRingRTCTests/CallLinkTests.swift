@@ -20,6 +20,13 @@ final class CallLinkTests: XCTestCase {
         XCTAssertNotEqual(Self.EXAMPLE_KEY.deriveRoomId(), anotherKey.deriveRoomId())
     }
 
+    func testLatestVersion() throws {
+        let latestKey = Self.EXAMPLE_KEY.withLatestVersion()
+        XCTAssertNotEqual(Self.EXAMPLE_KEY.bytes, latestKey.bytes)
+        XCTAssertEqual(Self.EXAMPLE_KEY.deriveRoomId(), latestKey.deriveRoomId())
+        XCTAssertEqual(try CallLinkRootKey(String(describing: latestKey)).bytes, latestKey.bytes)
+    }
+
     func testFormatting() throws {
         XCTAssertEqual(String(describing: Self.EXAMPLE_KEY), "bcdf-ghkm-npqr-stxz-bcdf-ghkm-npqr-stxz")
     }
@@ -196,6 +203,53 @@ final class CallLinkTests: XCTestCase {
         }
     }
 
+    @MainActor
+    func testRotateAdminPasskeySuccess() async throws {
+        let delegate = TestDelegate()
+        let httpClient = HTTPClient(delegate: delegate)
+        let sfu = SFUClient(httpClient: httpClient)
+
+        delegate.onSendRequest { id, request in
+            XCTAssert(request.url.starts(with: "sfu.example"))
+            XCTAssertEqual(request.method, .put)
+            httpClient.receivedResponse(requestId: id, response: HTTPResponse(statusCode: 200, body: Self.EXAMPLE_STATE_JSON.data(using: .utf8)))
+        }
+
+        let result = await sfu.rotateCallLinkAdminPasskey(sfuUrl: "sfu.example", authCredentialPresentation: [1, 2, 3], linkRootKey: Self.EXAMPLE_KEY, adminPasskey: CallLinkRootKey.generateAdminPasskey(), newAdminPasskey: CallLinkRootKey.generateAdminPasskey())
+        switch result {
+        case .success(_):
+            // Don't bother checking anything here, since we are mocking the SFU's responses anyway.
+            break
+        case .failure(let code):
+            XCTFail("unexpected failure: \(code)")
+        }
+    }
+
+    @MainActor
+    func testMigrateSuccess() async throws {
+        let delegate = TestDelegate()
+        let httpClient = HTTPClient(delegate: delegate)
+        let sfu = SFUClient(httpClient: httpClient)
+
+        // The link is read first, and then updated.
+        for method in [HTTPMethod.get, .put] {
+            delegate.onSendRequest { id, request in
+                XCTAssert(request.url.starts(with: "sfu.example"))
+                XCTAssertEqual(request.method, method)
+                httpClient.receivedResponse(requestId: id, response: HTTPResponse(statusCode: 200, body: Self.EXAMPLE_STATE_JSON.data(using: .utf8)))
+            }
+        }
+
+        let result = await sfu.migrateCallLink(sfuUrl: "sfu.example", authCredentialPresentation: [1, 2, 3], linkRootKey: Self.EXAMPLE_KEY.withLatestVersion(), adminPasskey: CallLinkRootKey.generateAdminPasskey())
+        switch result {
+        case .success(_):
+            // Don't bother checking anything here, since we are mocking the SFU's responses anyway.
+            break
+        case .failure(let code):
+            XCTFail("unexpected failure: \(code)")
+        }
+    }
+
     @MainActor
     func testDeleteCallLinkSuccess() async throws {
         let delegate = TestDelegate()
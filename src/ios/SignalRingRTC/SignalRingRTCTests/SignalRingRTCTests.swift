@@ -336,6 +336,55 @@ final class TestDelegate: CallManagerDelegate & HTTPDelegate {
         case .receivedOfferWithGlare:
             Logger.debug("TestDelegate:receivedOfferWithGlare")
             eventReceivedOfferWithGlare = true
+
+        case .remoteHeld:
+            Logger.debug("TestDelegate:remoteHeld")
+
+        case .remoteResumed:
+            Logger.debug("TestDelegate:remoteResumed")
+
+        case .incomingCallWaiting:
+            Logger.debug("TestDelegate:incomingCallWaiting")
+        case .endedRemoteDeclinedBusy:
+            Logger.debug("TestDelegate:endedRemoteDeclinedBusy")
+            eventGeneralEnded = true
+        case .endedRemoteDeclinedDoNotDisturb:
+            Logger.debug("TestDelegate:endedRemoteDeclinedDoNotDisturb")
+            eventGeneralEnded = true
+        case .endedRemoteDeclinedTextReplySent:
+            Logger.debug("TestDelegate:endedRemoteDeclinedTextReplySent")
+            eventGeneralEnded = true
+        case .remoteHandedOff:
+            Logger.debug("TestDelegate:remoteHandedOff")
+        case .endedHandedOff:
+            Logger.debug("TestDelegate:endedHandedOff")
+            eventGeneralEnded = true
+        case .remoteEarlyMedia:
+            Logger.debug("TestDelegate:remoteEarlyMedia")
+        case .remoteVideoAddRequested:
+            Logger.debug("TestDelegate:remoteVideoAddRequested")
+        case .remoteVideoAdded:
+            Logger.debug("TestDelegate:remoteVideoAdded")
+        case .remoteVideoRemoved:
+            Logger.debug("TestDelegate:remoteVideoRemoved")
+        case .localVideoAddAccepted:
+            Logger.debug("TestDelegate:localVideoAddAccepted")
+        case .localVideoAddDeclined:
+            Logger.debug("TestDelegate:localVideoAddDeclined")
+        case .mediaStalled:
+            Logger.debug("TestDelegate:mediaStalled")
+        case .endedMediaStalled:
+            Logger.debug("TestDelegate:endedMediaStalled")
+            eventGeneralEnded = true
+        case .endedRemoteHangupNetworkFailure:
+            Logger.debug("TestDelegate:endedRemoteHangupNetworkFailure")
+            eventGeneralEnded = true
+        case .endedRemoteDeclinedByPolicy:
+            Logger.debug("TestDelegate:endedRemoteDeclinedByPolicy")
+            eventGeneralEnded = true
+        case .endedRemoteHangupRingTimeout:
+            Logger.debug("TestDelegate:endedRemoteHangupRingTimeout")
+            eventGeneralEnded = true
         }
     }
 
@@ -622,6 +671,11 @@ final class TestDelegate: CallManagerDelegate & HTTPDelegate {
         didUpdateRingForGroupUpdate = update
     }
 
+    func callManager(_ callManager: CallManager<OpaqueCallData, TestDelegate>, didRequestHandoffFrom remoteUserId: UUID, callId: UInt64, remoteDeviceId: UInt32, callMediaType: CallMediaType) {
+        Logger.debug("TestDelegate:didRequestHandoffFrom")
+        generalInvocationDetected = true
+    }
+
     func callManager(_ callManager: CallManager<OpaqueCallData, TestDelegate>, shouldCompareCalls call1: OpaqueCallData, call2: OpaqueCallData) -> Bool {
         Logger.debug("TestDelegate:shouldCompareCalls")
         generalInvocationDetected = true
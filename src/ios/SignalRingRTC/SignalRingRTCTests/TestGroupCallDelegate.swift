@@ -16,6 +16,7 @@ class TestGroupCallDelegate: GroupCallDelegate {
     var onReactionsCount = 0
     var onRaisedHandsCount = 0
     var onPeekChangedCount = 0
+    var onCallFullCount = 0
     var onEndedCount = 0
     var onSpeakingCount = 0
     var lastOnEndedReason: GroupCallEndReason? = nil
@@ -57,6 +58,10 @@ class TestGroupCallDelegate: GroupCallDelegate {
         onPeekChangedCount += 1
     }
 
+    func groupCall(onCallFull groupCall: GroupCall, maxDevices: UInt32?, deviceCount: UInt32?) {
+        onCallFullCount += 1
+    }
+
     func groupCall(onEnded groupCall: GroupCall, reason: GroupCallEndReason) {
         onEndedCount += 1
         lastOnEndedReason = reason
@@ -6,48 +6,82 @@
 import { RingRTCType } from './ringrtc/Service';
 
 export {
+  AcceptOptions,
   AnswerMessage,
   AudioDevice,
+  AvSyncStats,
+  AudioInjectionMode,
+  AudioLossProtection,
+  AudioLossProtectionMode,
+  AudioProfile,
   DataMode,
   BusyMessage,
   Call,
+  CallBitrateConfig,
   CallEndedReason,
   CallId,
   CallLogLevel,
   CallMessageUrgency,
   CallSettings,
   CallState,
+  CallStats,
+  CallSummary,
   CallingMessage,
+  CandidatePairStats,
+  CandidateType,
+  ConnectionQuality,
   ConnectionState,
+  DeclineReason,
   DeviceId,
+  E2eeHealth,
+  GlareResolution,
   GroupCall,
   GroupCallEndReason,
+  GroupCallIntervals,
   GroupCallKind,
   GroupCallObserver,
   GroupMemberInfo,
+  HandoffRequest,
   HangupMessage,
   HangupType,
   HttpMethod,
   HttpResult,
   IceCandidateMessage,
+  InboundStreamStats,
+  JitterBufferConfig,
   JoinState,
+  KeyRotationPolicy,
   LocalDeviceState,
+  MaxSendBitrate,
   OfferMessage,
   OfferType,
   OpaqueMessage,
+  OutboundStreamStats,
+  ParticipantKind,
   PeekDeviceInfo,
   PeekInfo,
   PeekStatusCodes,
+  QualityLimitationReason,
   Reaction,
+  RemoteAvSyncSkew,
+  RemoteConnectionQuality,
   RemoteDeviceState,
   RingCancelReason,
   RingRTCType,
   RingUpdate,
+  SetupLatencies,
+  SetupLatencyHistograms,
   SpeechEvent,
   UserId,
   VideoCapturer,
+  VideoContentHint,
+  VideoLimits,
   VideoRenderer,
+  VideoNegotiationEvent,
   VideoRequest,
+  LOW_LATENCY_JITTER_BUFFER,
+  MAX_APP_DATA_LENGTH,
+  SMOOTH_JITTER_BUFFER,
   callIdFromEra,
   callIdFromRingId,
 } from './ringrtc/Service';
@@ -60,6 +94,8 @@ export {
   MAX_VIDEO_CAPTURE_BUFFER_SIZE,
   MAX_VIDEO_CAPTURE_HEIGHT,
   MAX_VIDEO_CAPTURE_WIDTH,
+  VideoPixelFormatEnum,
+  VideoRotation,
 } from './ringrtc/VideoSupport';
 
 export {
@@ -68,4 +104,10 @@ export {
   CallLinkState,
 } from './ringrtc/CallLinks';
 
+export {
+  CryptoContext,
+  EncryptedFrame,
+  SendState,
+} from './ringrtc/CryptoContext';
+
 export const RingRTC = new RingRTCType();
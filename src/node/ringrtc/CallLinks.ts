@@ -35,6 +35,16 @@ export class CallLinkRootKey {
     return Native.CallLinkRootKey_deriveRoomId(this.bytes);
   }
 
+  /**
+   * The same key at the newest version, to pass to
+   * {@link RingRTCType.migrateCallLink}.
+   */
+  withLatestVersion(): CallLinkRootKey {
+    return new CallLinkRootKey(
+      Native.CallLinkRootKey_withLatestVersion(this.bytes)
+    );
+  }
+
   toString(): string {
     // eslint-disable-next-line @typescript-eslint/no-unsafe-return
     return Native.CallLinkRootKey_toFormattedString(this.bytes);
@@ -46,7 +56,14 @@ export class CallLinkState {
     public name: string,
     public restrictions: CallLinkRestrictions,
     public revoked: boolean,
-    public expiration: Date
+    public expiration: Date,
+    public description = '',
+    /** An RGB color, as 0xRRGGBB. */
+    public accentColor?: number,
+    /** An app-defined reference to an avatar stored elsewhere. */
+    public avatar?: string,
+    /** Set when rotating the admin passkey found that an earlier attempt had already gone through. */
+    public oldAdminPasskeyRejected = false
   ) {}
 }
 
@@ -0,0 +1,86 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+import Native from './Native';
+
+/** What has to be sent along with an encrypted frame. */
+export type EncryptedFrame = {
+  ratchetCounter: number;
+  frameCounter: number;
+  mac: Buffer;
+};
+
+/** The secret receivers need to decrypt frames, as of a ratchet counter. */
+export type SendState = {
+  ratchetCounter: number;
+  secret: Buffer;
+};
+
+/**
+ * The frame encryption used by group calls, for media that doesn't go through
+ * RingRTC's own pipeline.
+ *
+ * The native state is owned by this object and freed when it is garbage
+ * collected. Frames are encrypted and decrypted in place, synchronously.
+ */
+export class CryptoContext {
+  private readonly native: unknown;
+
+  constructor(initialSendSecret: Buffer) {
+    this.native = Native.CryptoContext_new(initialSendSecret);
+  }
+
+  encrypt(data: Buffer): EncryptedFrame {
+    return Native.CryptoContext_encrypt(this.native, data);
+  }
+
+  /**
+   * Returns false, leaving `data` untouched, if no secret from `senderId`
+   * matches.
+   */
+  decrypt(
+    senderId: number,
+    ratchetCounter: number,
+    frameCounter: number,
+    data: Buffer,
+    mac: Buffer
+  ): boolean {
+    return Native.CryptoContext_decrypt(
+      this.native,
+      senderId,
+      ratchetCounter,
+      frameCounter,
+      data,
+      mac
+    );
+  }
+
+  addReceiveSecret(
+    senderId: number,
+    ratchetCounter: number,
+    secret: Buffer
+  ): void {
+    Native.CryptoContext_addReceiveSecret(
+      this.native,
+      senderId,
+      ratchetCounter,
+      secret
+    );
+  }
+
+  getSendState(): SendState {
+    return Native.CryptoContext_getSendState(this.native);
+  }
+
+  /** Ratchets the send secret forward, for when a receiver is added. */
+  advanceSendRatchet(): SendState {
+    return Native.CryptoContext_advanceSendRatchet(this.native);
+  }
+
+  /** Replaces the send secret, for when a receiver is removed. */
+  resetSendRatchet(secret: Buffer): void {
+    Native.CryptoContext_resetSendRatchet(this.native, secret);
+  }
+}
@@ -0,0 +1,149 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+// Generated by src/rust/build.rs from the Rust definitions; do not edit.
+// Regenerate by building with RINGRTC_TS_ENUMS_PATH set, as bin/build-electron does.
+
+// Matches ParticipantKind in lite/sfu.rs.
+export enum ParticipantKind {
+  AppUser = 0,
+  Bridged = 1,
+}
+
+// Matches Method in lite/http.rs.
+export enum HttpMethod {
+  Get = 0,
+  Put = 1,
+  Post = 2,
+  Delete = 3,
+}
+
+// Matches ConnectionState in core/group_call.rs.
+export enum ConnectionState {
+  NotConnected = 0,
+  Connecting = 1,
+  Connected = 2,
+  Reconnecting = 3,
+}
+
+// Matches JoinState in core/group_call.rs.
+export enum JoinState {
+  NotJoined = 0,
+  Joining = 1,
+  Pending = 2,
+  Joined = 3,
+}
+
+// Matches EndReason in core/group_call.rs.
+export enum GroupCallEndReason {
+  DeviceExplicitlyDisconnected = 0,
+  ServerExplicitlyDisconnected = 1,
+  DeniedRequestToJoinCall = 2,
+  RemovedFromCall = 3,
+  CallManagerIsBusy = 4,
+  SfuClientFailedToJoin = 5,
+  FailedToCreatePeerConnectionFactory = 6,
+  FailedToNegotiateSrtpKeys = 7,
+  FailedToCreatePeerConnection = 8,
+  FailedToStartPeerConnection = 9,
+  FailedToUpdatePeerConnection = 10,
+  FailedToSetMaxSendBitrate = 11,
+  IceFailedWhileConnecting = 12,
+  IceFailedAfterConnected = 13,
+  ServerChangedDemuxId = 14,
+  HasMaxDevices = 15,
+}
+
+// Matches SpeechEvent in core/group_call.rs.
+export enum SpeechEvent {
+  StoppedSpeaking = 0,
+  LowerHandSuggestion = 1,
+  SpeakingWhileMuted = 2,
+}
+
+// Matches ConnectionQuality in core/group_call.rs.
+export enum ConnectionQuality {
+  Good = 0,
+  Fair = 1,
+  Poor = 2,
+}
+
+// Matches SignalingMessageUrgency in core/group_call.rs.
+export enum CallMessageUrgency {
+  Droppable = 0,
+  HandleImmediately = 1,
+}
+
+// Matches RingUpdate in core/group_call.rs.
+export enum RingUpdate {
+  Requested = 0,
+  ExpiredRequest = 1,
+  AcceptedOnAnotherDevice = 2,
+  DeclinedOnAnotherDevice = 3,
+  BusyLocally = 4,
+  BusyOnAnotherDevice = 5,
+  CancelledByRinger = 6,
+}
+
+// Matches RingCancelReason in core/group_call.rs.
+export enum RingCancelReason {
+  DeclinedByUser = 0,
+  Busy = 1,
+}
+
+// Matches EndReason in native.rs.
+export enum CallEndedReason {
+  LocalHangup = 'LocalHangup',
+  RemoteHangup = 'RemoteHangup',
+  RemoteHangupNeedPermission = 'RemoteHangupNeedPermission',
+  Declined = 'Declined',
+  Busy = 'Busy',
+  Glare = 'Glare',
+  ReCall = 'ReCall',
+  ReceivedOfferExpired = 'ReceivedOfferExpired',
+  ReceivedOfferWhileActive = 'ReceivedOfferWhileActive',
+  ReceivedOfferWithGlare = 'ReceivedOfferWithGlare',
+  SignalingFailure = 'SignalingFailure',
+  GlareFailure = 'GlareFailure',
+  ConnectionFailure = 'ConnectionFailure',
+  InternalFailure = 'InternalFailure',
+  Timeout = 'Timeout',
+  AcceptedOnAnotherDevice = 'AcceptedOnAnotherDevice',
+  DeclinedOnAnotherDevice = 'DeclinedOnAnotherDevice',
+  BusyOnAnotherDevice = 'BusyOnAnotherDevice',
+  DeclinedBusy = 'DeclinedBusy',
+  DeclinedDoNotDisturb = 'DeclinedDoNotDisturb',
+  DeclinedTextReplySent = 'DeclinedTextReplySent',
+  HandedOff = 'HandedOff',
+  MediaStalled = 'MediaStalled',
+  RemoteHangupNetworkFailure = 'RemoteHangupNetworkFailure',
+  DeclinedByPolicy = 'DeclinedByPolicy',
+  RemoteHangupRingTimeout = 'RemoteHangupRingTimeout',
+}
+
+// Matches VideoNegotiationEvent in native.rs.
+export enum VideoNegotiationEvent {
+  RemoteAddRequested = 'RemoteAddRequested',
+  RemoteAdded = 'RemoteAdded',
+  RemoteRemoved = 'RemoteRemoved',
+  LocalAddAccepted = 'LocalAddAccepted',
+  LocalAddDeclined = 'LocalAddDeclined',
+}
+
+// Matches the constants of ResponseStatus in lite/http.rs.
+export enum HttpResponseStatus {
+  GROUP_CALL_NOT_STARTED = 404,
+  GROUP_CALL_FULL = 413,
+  CALL_LINK_IN_USE = 409,
+  CALL_LINK_REVISION_CONFLICT = 412,
+  INVALID_CLIENT_AUTH = 601,
+  REQUEST_FAILED = 602,
+  REQUEST_CANCELLED = 603,
+  INVALID_RESPONSE_BODY_UTF8 = 701,
+  INVALID_RESPONSE_BODY_JSON = 702,
+  CALL_LINK_EXPIRED = 703,
+  CALL_LINK_INVALID = 704,
+  CALL_LINK_EPOCH_EXPIRED = 705,
+}
@@ -5,16 +5,58 @@
 
 /* eslint-disable max-classes-per-file */
 
-import { GumVideoCaptureOptions, VideoPixelFormatEnum } from './VideoSupport';
+import {
+  GumVideoCaptureOptions,
+  VideoPixelFormatEnum,
+  VideoRotation,
+} from './VideoSupport';
 import {
   CallLinkState,
   CallLinkRestrictions,
   CallLinkRootKey,
 } from './CallLinks';
+import {
+  CallEndedReason,
+  CallMessageUrgency,
+  ConnectionQuality,
+  ConnectionState,
+  GroupCallEndReason,
+  HttpMethod,
+  HttpResponseStatus,
+  JoinState,
+  ParticipantKind,
+  RingCancelReason,
+  RingUpdate,
+  SpeechEvent,
+  VideoNegotiationEvent,
+} from './NativeEnums';
 import Native from './Native';
 
+export {
+  CallEndedReason,
+  CallMessageUrgency,
+  ConnectionQuality,
+  ConnectionState,
+  GroupCallEndReason,
+  HttpMethod,
+  HttpResponseStatus,
+  JoinState,
+  ParticipantKind,
+  RingCancelReason,
+  RingUpdate,
+  SpeechEvent,
+  VideoNegotiationEvent,
+};
+
 const INVALID_CLIENT_ID = 0;
 
+// Defaults for AudioLossProtection in Auto mode.
+const DEFAULT_ENABLE_LOSS_PERCENT = 10;
+const DEFAULT_DISABLE_LOSS_PERCENT = 5;
+
+// The most data that can be sent at once with sendAppData.
+export const MAX_APP_DATA_LENGTH = 1024;
+
 export const callIdFromEra: (era: string) => CallId = Native.callIdFromEra;
 
 export function callIdFromRingId(ringId: bigint): CallId {
@@ -31,7 +73,9 @@ class Config {
 }
 
 class NativeCallManager {
-  // Read by Rust
+  // Read by Rust, which calls it on the JavaScript thread whenever events
+  // arrive. Events are delivered in order, one call at a time; any caused by a
+  // callback calling back into RingRTC are delivered after it returns.
   private readonly observer: CallManagerCallbacks;
 
   constructor(observer: CallManagerCallbacks) {
@@ -86,12 +130,57 @@ class NativeCallManager {
 // Mirror methods onto NativeCallManager.
 // This is done through direct assignment rather than wrapper methods to avoid indirection.
 (NativeCallManager.prototype as any).setSelfUuid = Native.cm_setSelfUuid;
+(NativeCallManager.prototype as any).setMaxLogLevel =
+  Native.cm_setMaxLogLevel;
+(NativeCallManager.prototype as any).setModuleLogLevel =
+  Native.cm_setModuleLogLevel;
+(NativeCallManager.prototype as any).clearModuleLogLevels =
+  Native.cm_clearModuleLogLevels;
+(NativeCallManager.prototype as any).exportDiagnostics =
+  Native.cm_exportDiagnostics;
+(NativeCallManager.prototype as any).setCrashDumpPath =
+  Native.cm_setCrashDumpPath;
+(NativeCallManager.prototype as any).getSetupLatencyHistograms =
+  Native.cm_getSetupLatencyHistograms;
+(NativeCallManager.prototype as any).setGroupCallIntervals =
+  Native.cm_setGroupCallIntervals;
 (NativeCallManager.prototype as any).createOutgoingCall =
   Native.cm_createOutgoingCall;
+(NativeCallManager.prototype as any).takeOverCall = Native.cm_takeOverCall;
 (NativeCallManager.prototype as any).proceed = Native.cm_proceed;
+(NativeCallManager.prototype as any).preGatherIce = Native.cm_preGatherIce;
 (NativeCallManager.prototype as any).accept = Native.cm_accept;
+(NativeCallManager.prototype as any).setOnHold = Native.cm_setOnHold;
+(NativeCallManager.prototype as any).handOff = Native.cm_handOff;
+(NativeCallManager.prototype as any).restartIce = Native.cm_restartIce;
+(NativeCallManager.prototype as any).startEarlyMedia =
+  Native.cm_startEarlyMedia;
+(NativeCallManager.prototype as any).enableVideo = Native.cm_enableVideo;
+(NativeCallManager.prototype as any).disableVideo = Native.cm_disableVideo;
+(NativeCallManager.prototype as any).respondToVideoRequest =
+  Native.cm_respondToVideoRequest;
+(NativeCallManager.prototype as any).sendAppData = Native.cm_sendAppData;
+(NativeCallManager.prototype as any).getVerificationCode =
+  Native.cm_getVerificationCode;
+(NativeCallManager.prototype as any).getWideCallId = Native.cm_getWideCallId;
+(NativeCallManager.prototype as any).setCallAppData = Native.cm_setCallAppData;
+(NativeCallManager.prototype as any).setRestartIceOnDisconnect =
+  Native.cm_setRestartIceOnDisconnect;
+(NativeCallManager.prototype as any).setRelayOnlyByDefault =
+  Native.cm_setRelayOnlyByDefault;
+(NativeCallManager.prototype as any).setMaxSignalingMessageSize =
+  Native.cm_setMaxSignalingMessageSize;
 (NativeCallManager.prototype as any).ignore = Native.cm_ignore;
 (NativeCallManager.prototype as any).hangup = Native.cm_hangup;
+(NativeCallManager.prototype as any).decline = Native.cm_decline;
+(NativeCallManager.prototype as any).setCallWaitingEnabled =
+  Native.cm_setCallWaitingEnabled;
+(NativeCallManager.prototype as any).holdAndAcceptWaitingCall =
+  Native.cm_holdAndAcceptWaitingCall;
+(NativeCallManager.prototype as any).declineWaitingCall =
+  Native.cm_declineWaitingCall;
+(NativeCallManager.prototype as any).resumeHeldCall = Native.cm_resumeHeldCall;
+(NativeCallManager.prototype as any).hangupHeldCall = Native.cm_hangupHeldCall;
 (NativeCallManager.prototype as any).cancelGroupRing =
   Native.cm_cancelGroupRing;
 (NativeCallManager.prototype as any).signalingMessageSent =
@@ -99,6 +188,16 @@ class NativeCallManager {
 (NativeCallManager.prototype as any).signalingMessageSendFailed =
   Native.cm_signalingMessageSendFailed;
 (NativeCallManager.prototype as any).updateDataMode = Native.cm_updateDataMode;
+(NativeCallManager.prototype as any).updateAudioProfile =
+  Native.cm_updateAudioProfile;
+(NativeCallManager.prototype as any).setAudioLossProtection =
+  Native.cm_setAudioLossProtection;
+(NativeCallManager.prototype as any).setAudioLevelsInterval =
+  Native.cm_setAudioLevelsInterval;
+(NativeCallManager.prototype as any).updateBitrateConfig =
+  Native.cm_updateBitrateConfig;
+(NativeCallManager.prototype as any).setMaxSendBitrate =
+  Native.cm_setMaxSendBitrate;
 (NativeCallManager.prototype as any).receivedOffer = Native.cm_receivedOffer;
 (NativeCallManager.prototype as any).receivedAnswer = Native.cm_receivedAnswer;
 (NativeCallManager.prototype as any).receivedIceCandidates =
@@ -118,6 +217,14 @@ class NativeCallManager {
 (NativeCallManager.prototype as any).setOutgoingVideoIsScreenShare =
   Native.cm_setOutgoingVideoIsScreenShare;
 (NativeCallManager.prototype as any).sendVideoFrame = Native.cm_sendVideoFrame;
+(NativeCallManager.prototype as any).sendAudioSamples =
+  Native.cm_sendAudioSamples;
+(NativeCallManager.prototype as any).setAudioInjectionMode =
+  Native.cm_setAudioInjectionMode;
+(NativeCallManager.prototype as any).clearInjectedAudio =
+  Native.cm_clearInjectedAudio;
+(NativeCallManager.prototype as any).shareSystemAudio =
+  Native.cm_shareSystemAudio;
 (NativeCallManager.prototype as any).receiveVideoFrame =
   Native.cm_receiveVideoFrame;
 (NativeCallManager.prototype as any).receiveGroupCallVideoFrame =
@@ -135,17 +242,54 @@ class NativeCallManager {
 (NativeCallManager.prototype as any).groupRing = Native.cm_groupRing;
 (NativeCallManager.prototype as any).groupReact = Native.cm_groupReact;
 (NativeCallManager.prototype as any).groupRaiseHand = Native.cm_groupRaiseHand;
+(NativeCallManager.prototype as any).groupSendAppData =
+  Native.cm_groupSendAppData;
+(NativeCallManager.prototype as any).groupLowerAllHands =
+  Native.cm_groupLowerAllHands;
+(NativeCallManager.prototype as any).groupAssignBreakoutGroups =
+  Native.cm_groupAssignBreakoutGroups;
+(NativeCallManager.prototype as any).groupEndBreakoutGroups =
+  Native.cm_groupEndBreakoutGroups;
+(NativeCallManager.prototype as any).setLowerHandWhenSpeaking =
+  Native.cm_setLowerHandWhenSpeaking;
+(NativeCallManager.prototype as any).setDetectSpeakingWhileMuted =
+  Native.cm_setDetectSpeakingWhileMuted;
+(NativeCallManager.prototype as any).setSkipMediaKeysForBridgedParticipants =
+  Native.cm_setSkipMediaKeysForBridgedParticipants;
+(NativeCallManager.prototype as any).setLeaveWhenOwnDeviceJoins =
+  Native.cm_setLeaveWhenOwnDeviceJoins;
+(NativeCallManager.prototype as any).setKeyRotationPolicy =
+  Native.cm_setKeyRotationPolicy;
 (NativeCallManager.prototype as any).setOutgoingAudioMuted =
   Native.cm_setOutgoingAudioMuted;
 (NativeCallManager.prototype as any).setOutgoingVideoMuted =
   Native.cm_setOutgoingVideoMuted;
 (NativeCallManager.prototype as any).setOutgoingGroupCallVideoIsScreenShare =
   Native.cm_setOutgoingGroupCallVideoIsScreenShare;
+(NativeCallManager.prototype as any).setOutgoingVideoLimits =
+  Native.cm_setOutgoingVideoLimits;
 (NativeCallManager.prototype as any).setPresenting = Native.cm_setPresenting;
 (NativeCallManager.prototype as any).resendMediaKeys =
   Native.cm_resendMediaKeys;
 (NativeCallManager.prototype as any).setDataMode = Native.cm_setDataMode;
+(NativeCallManager.prototype as any).setGroupCallAudioProfile =
+  Native.cm_setGroupCallAudioProfile;
+(NativeCallManager.prototype as any).setGroupCallAudioLossProtection =
+  Native.cm_setGroupCallAudioLossProtection;
+(NativeCallManager.prototype as any).setGroupCallAudioLevelsInterval =
+  Native.cm_setGroupCallAudioLevelsInterval;
+(NativeCallManager.prototype as any).getStats = Native.cm_getStats;
+(NativeCallManager.prototype as any).getCandidatePairs =
+  Native.cm_getCandidatePairs;
+(NativeCallManager.prototype as any).getGroupCallStats =
+  Native.cm_getGroupCallStats;
+(NativeCallManager.prototype as any).getGroupCallCandidatePairs =
+  Native.cm_getGroupCallCandidatePairs;
 (NativeCallManager.prototype as any).requestVideo = Native.cm_requestVideo;
+(NativeCallManager.prototype as any).setRenderingPaused =
+  Native.cm_setRenderingPaused;
+(NativeCallManager.prototype as any).setAllRenderingPaused =
+  Native.cm_setAllRenderingPaused;
 (NativeCallManager.prototype as any).approveUser = Native.cm_approveUser;
 (NativeCallManager.prototype as any).denyUser = Native.cm_denyUser;
 (NativeCallManager.prototype as any).removeClient = Native.cm_removeClient;
@@ -158,17 +302,27 @@ class NativeCallManager {
 (NativeCallManager.prototype as any).createCallLink = Native.cm_createCallLink;
 (NativeCallManager.prototype as any).updateCallLink = Native.cm_updateCallLink;
 (NativeCallManager.prototype as any).deleteCallLink = Native.cm_deleteCallLink;
+(NativeCallManager.prototype as any).rotateCallLinkAdminPasskey =
+  Native.cm_rotateCallLinkAdminPasskey;
+(NativeCallManager.prototype as any).migrateCallLink =
+  Native.cm_migrateCallLink;
 (NativeCallManager.prototype as any).peekGroupCall = Native.cm_peekGroupCall;
 (NativeCallManager.prototype as any).peekCallLinkCall =
   Native.cm_peekCallLinkCall;
 (NativeCallManager.prototype as any).getAudioInputs = Native.cm_getAudioInputs;
 (NativeCallManager.prototype as any).setAudioInput = Native.cm_setAudioInput;
+(NativeCallManager.prototype as any).setAudioInputById =
+  Native.cm_setAudioInputById;
+
 (NativeCallManager.prototype as any).getAudioOutputs =
   Native.cm_getAudioOutputs;
 (NativeCallManager.prototype as any).setAudioOutput = Native.cm_setAudioOutput;
-(NativeCallManager.prototype as any).processEvents = Native.cm_processEvents;
+(NativeCallManager.prototype as any).setAudioOutputById =
+  Native.cm_setAudioOutputById;
 (NativeCallManager.prototype as any).setRtcStatsInterval =
   Native.cm_setRtcStatsInterval;
+(NativeCallManager.prototype as any).setGroupCallIceServers =
+  Native.cm_setGroupCallIceServers;
 
 type GroupId = Buffer;
 type GroupCallUserId = Buffer;
@@ -176,6 +330,7 @@ type GroupCallUserId = Buffer;
 export interface PeekDeviceInfo {
   demuxId: number;
   userId?: GroupCallUserId;
+  participantKind: ParticipantKind;
 }
 
 export interface Reaction {
@@ -212,8 +367,8 @@ export interface PeekInfo {
 }
 
 export enum PeekStatusCodes {
-  EXPIRED_CALL_LINK = 703,
-  INVALID_CALL_LINK = 704,
+  EXPIRED_CALL_LINK = HttpResponseStatus.CALL_LINK_EXPIRED,
+  INVALID_CALL_LINK = HttpResponseStatus.CALL_LINK_INVALID,
 }
 
 // In sync with WebRTC's PeerConnection.AdapterType.
@@ -245,12 +400,27 @@ enum NetworkAdapterType {
 // Information about the network route being used for sending audio/video/data
 export class NetworkRoute {
   localAdapterType: NetworkAdapterType;
+  // The adapter the VPN runs over, if localAdapterType is Vpn.
+  localAdapterTypeUnderVpn: NetworkAdapterType;
+  // Whether media goes through a relay (TURN) server on either side.
+  relayed: boolean;
 
   constructor() {
     this.localAdapterType = NetworkAdapterType.Unknown;
+    this.localAdapterTypeUnderVpn = NetworkAdapterType.Unknown;
+    this.relayed = false;
   }
 }
 
+// The type of an ICE candidate.
+export enum CandidateType {
+  Unknown = 0,
+  Host = 1,
+  ServerReflexive = 2,
+  PeerReflexive = 3,
+  Relay = 4,
+}
+
 // Range of 0-32767 where 0 is silence.
 export type RawAudioLevel = number;
 // Range of 0-1 where 0 is silence.
@@ -271,6 +441,10 @@ interface RawCallLinkState {
   rawRestrictions: number;
   revoked: boolean;
   expiration: Date;
+  description: string;
+  accentColor: number | undefined;
+  avatar: string | undefined;
+  oldAdminPasskeyRejected: boolean | undefined;
 }
 
 function normalizeAudioLevel(raw: RawAudioLevel): NormalizedAudioLevel {
@@ -297,7 +471,11 @@ function rawCallLinkStateToCallLinkState(
       raw.name,
       restrictions,
       raw.revoked,
-      raw.expiration
+      raw.expiration,
+      raw.description,
+      raw.accentColor,
+      raw.avatar,
+      raw.oldAdminPasskeyRejected ?? false
     );
   } else {
     return undefined;
@@ -359,6 +537,8 @@ export type HttpResult<T> =
 export class RingRTCType {
   private readonly callManager: CallManager;
   private _call: Call | null;
+  // The 1:1 call put on hold to accept a waiting call, if any.
+  private _heldCall: Call | null;
   private _groupCallByClientId: Map<GroupCallClientId, GroupCall>;
   private _peekRequests: Requests<HttpResult<PeekInfo>>;
   private _callLinkRequests: Requests<HttpResult<CallLinkState>>;
@@ -384,6 +564,16 @@ export class RingRTCType {
 
   handleStartCall: ((call: Call) => Promise<boolean>) | null = null;
 
+  handleIncomingCallWaiting:
+    | ((remoteUserId: UserId, callId: CallId) => void)
+    | null = null;
+
+  handleGlareResolved:
+    | ((remoteUserId: UserId, resolution: GlareResolution) => void)
+    | null = null;
+
+  handleHandoffRequested: ((request: HandoffRequest) => void) | null = null;
+
   handleAutoEndedIncomingCallRequest:
     | ((
         callId: CallId,
@@ -392,7 +582,8 @@ export class RingRTCType {
         ageSec: number,
         wasVideoCall: boolean,
         receivedAtCounter: number | undefined,
-        receivedAtDate: number | undefined
+        receivedAtDate: number | undefined,
+        appData: Buffer
       ) => void)
     | null = null;
 
@@ -443,9 +634,26 @@ export class RingRTCType {
 
   handleRtcStatsReport: ((reportJson: string) => void) | null = null;
 
+  // Called with the new device lists whenever an audio device is added or
+  // removed. Only available when using the RingRTC audio device module.
+  handleAudioDevicesChanged:
+    | ((inputs: Array<AudioDevice>, outputs: Array<AudioDevice>) => void)
+    | null = null;
+
+  handleCallSummary:
+    | ((
+        remoteUserId: UserId,
+        callId: CallId,
+        summary: CallSummary,
+        reason: CallEndedReason,
+        appData: Buffer
+      ) => void)
+    | null = null;
+
   constructor() {
     this.callManager = new NativeCallManager(this) as unknown as CallManager;
     this._call = null;
+    this._heldCall = null;
     this._groupCallByClientId = new Map();
     this._peekRequests = new Requests();
     this._callLinkRequests = new Requests();
@@ -463,14 +671,101 @@ export class RingRTCType {
   }
 
   // Called by UX
+  // Applies to all of RingRTC, such as when the user turns on debug logging.
+  // Trace messages are never logged, and release builds log Info at most.
+  setMaxLogLevel(level: CallLogLevel): void {
+    this.callManager.setMaxLogLevel(level);
+  }
+
+  // Called by UX
+  // Overrides the max log level for one part of RingRTC, such as 'group_call'
+  // or 'http', so that support can turn on targeted debugging. Stays in effect
+  // across setMaxLogLevel until cleared. Release builds log Info at most.
+  setModuleLogLevel(module: string, level: CallLogLevel): void {
+    this.callManager.setModuleLogLevel(module, level);
+  }
+
+  // Called by UX
+  clearModuleLogLevels(): void {
+    this.callManager.clearModuleLogLevels();
+  }
+
+  // Called by UX
+  // Returns recent state transitions, ICE connection changes, and HTTP and
+  // decryption failures as UTF-8 JSON, for attaching to bug reports.
+  exportDiagnostics(): Buffer {
+    return this.callManager.exportDiagnostics();
+  }
+
+  // Called by UX
+  // Creates (or truncates) the file at path, which the state of every call,
+  // in-flight HTTP requests, and recent events are written to if RingRTC
+  // crashes. Throws if the file can't be created.
+  setCrashDumpPath(path: string): void {
+    this.callManager.setCrashDumpPath(path);
+  }
+
+  // Called by UX
+  // Returns how long call setup took for every call so far, such as for
+  // periodic telemetry. Pass reset to start counting again afterwards.
+  getSetupLatencyHistograms(reset: boolean): SetupLatencyHistograms {
+    return this.callManager.getSetupLatencyHistograms(reset);
+  }
+
+  // Called by UX
+  // Only affects group calls created after this is called. Omitted intervals
+  // use the defaults.
+  setGroupCallIntervals(intervals: GroupCallIntervals): void {
+    this.callManager.setGroupCallIntervals(
+      intervals.heartbeatMillis ?? 0,
+      intervals.statsMillis ?? 0,
+      intervals.peekPollMillis ?? 0
+    );
+  }
+
+  // Called by UX
+  // The optional appData is passed back with every notification for the
+  // call, such as to handleCallSummary, and is available as call.appData.
   startOutgoingCall(
     remoteUserId: UserId,
     isVideoCall: boolean,
-    localDeviceId: DeviceId
+    localDeviceId: DeviceId,
+    appData: Buffer = Buffer.alloc(0)
   ): Call {
     const callId = this.callManager.createOutgoingCall(
       remoteUserId,
       isVideoCall,
+      localDeviceId,
+      appData
+    );
+    const isIncoming = false;
+    const call = new Call(
+      this.callManager,
+      remoteUserId,
+      callId,
+      isIncoming,
+      isVideoCall,
+      CallState.Prering
+    );
+    this._call = call;
+    call.outgoingVideoEnabled = isVideoCall;
+    call.appData = appData;
+    return call;
+  }
+
+  // Called by UX
+  // Takes over an accepted call from another device of the local user,
+  // in response to handleHandoffRequested.
+  takeOverCall(
+    remoteUserId: UserId,
+    callId: CallId,
+    isVideoCall: boolean,
+    localDeviceId: DeviceId
+  ): Call {
+    this.callManager.takeOverCall(
+      remoteUserId,
+      callId,
+      isVideoCall,
       localDeviceId
     );
     const isIncoming = false;
@@ -600,6 +895,48 @@ export class RingRTCType {
       });
   }
 
+  // Called by Rust
+  onIncomingCallWaiting(remoteUserId: UserId, callId: CallId): void {
+    if (this.handleIncomingCallWaiting) {
+      this.handleIncomingCallWaiting(remoteUserId, callId);
+    }
+  }
+
+  // Called by Rust
+  onGlareResolved(
+    remoteUserId: UserId,
+    localCallId: CallId,
+    remoteCallId: CallId,
+    winningCallId: CallId | null,
+    convertedToIncoming: boolean
+  ): void {
+    if (this.handleGlareResolved) {
+      this.handleGlareResolved(remoteUserId, {
+        localCallId,
+        remoteCallId,
+        winningCallId,
+        convertedToIncoming,
+      });
+    }
+  }
+
+  // Called by Rust
+  onHandoffRequested(
+    remoteUserId: Buffer,
+    callId: CallId,
+    remoteDeviceId: DeviceId,
+    isVideoCall: boolean
+  ): void {
+    if (this.handleHandoffRequested) {
+      this.handleHandoffRequested({
+        remoteUserId,
+        callId,
+        remoteDeviceId,
+        isVideoCall,
+      });
+    }
+  }
+
   proceed(callId: CallId, settings: CallSettings): void {
     sillyDeadlockProtection(() => {
       this.callManager.proceed(
@@ -607,11 +944,28 @@ export class RingRTCType {
         settings.iceServers,
         settings.hideIp,
         settings.dataMode,
-        settings.audioLevelsIntervalMillis || 0
+        settings.audioLevelsIntervalMillis || 0,
+        settings.ringTimeoutMillis || 0,
+        settings.connectTimeoutMillis || 0,
+        settings.earlyMedia || false,
+        settings.mediaStallTimeoutMillis || 0,
+        settings.hangUpOnMediaStall || false,
+        settings.audioProfile || AudioProfile.Voice,
+        settings.jitterBuffer?.targetDelayMillis ?? -1,
+        settings.jitterBuffer?.maxDelayMillis ?? -1
       );
     });
   }
 
+  // Starts gathering relay ICE candidates for an incoming call before
+  // proceeding, so it connects sooner once it does. Nothing is sent to the
+  // caller until proceed, and the call then only uses relay candidates.
+  preGatherIce(callId: CallId, iceServers: Array<IceServer>): void {
+    sillyDeadlockProtection(() => {
+      this.callManager.preGatherIce(callId, iceServers);
+    });
+  }
+
   // Called by Rust
   onCallState(remoteUserId: UserId, state: CallState): void {
     const call = this._call;
@@ -626,7 +980,8 @@ export class RingRTCType {
     remoteUserId: UserId,
     callId: CallId,
     reason: CallEndedReason,
-    ageSec: number
+    ageSec: number,
+    appData: Buffer
   ): void {
     const callInfo = this._callInfoByCallId.get(this.getCallInfoKey(callId));
     const { isVideoCall, receivedAtCounter, receivedAtDate } = callInfo || {
@@ -636,6 +991,18 @@ export class RingRTCType {
     };
     this._callInfoByCallId.delete(this.getCallInfoKey(callId));
 
+    const heldCall = this._heldCall;
+    if (
+      heldCall &&
+      heldCall.callId.high === callId.high &&
+      heldCall.callId.low === callId.low
+    ) {
+      this._heldCall = null;
+      heldCall.endedReason = reason;
+      heldCall.state = CallState.Ended;
+      return;
+    }
+
     const call = this._call;
     if (call && reason == CallEndedReason.ReceivedOfferWithGlare) {
       // The current call is the outgoing call.
@@ -675,7 +1042,8 @@ export class RingRTCType {
           ageSec,
           isVideoCall,
           receivedAtCounter,
-          receivedAtDate
+          receivedAtDate,
+          appData
         );
       }
 
@@ -695,6 +1063,19 @@ export class RingRTCType {
     call.state = CallState.Ended;
   }
 
+  // Called by Rust
+  onCallSummary(
+    remoteUserId: UserId,
+    callId: CallId,
+    summary: CallSummary,
+    reason: CallEndedReason,
+    appData: Buffer
+  ): void {
+    if (this.handleCallSummary) {
+      this.handleCallSummary(remoteUserId, callId, summary, reason, appData);
+    }
+  }
+
   onRemoteAudioEnabled(remoteUserId: UserId, enabled: boolean): void {
     const call = this._call;
     if (!call || call.remoteUserId !== remoteUserId) {
@@ -707,6 +1088,87 @@ export class RingRTCType {
     }
   }
 
+  onRemoteHoldChanged(remoteUserId: UserId, onHold: boolean): void {
+    const call = this._call;
+    if (!call || call.remoteUserId !== remoteUserId) {
+      return;
+    }
+
+    call.remoteOnHold = onHold;
+    if (call.handleRemoteHoldChanged) {
+      call.handleRemoteHoldChanged();
+    }
+  }
+
+  onRemoteHandedOff(remoteUserId: UserId): void {
+    const call = this._call;
+    if (!call || call.remoteUserId !== remoteUserId) {
+      return;
+    }
+
+    if (call.handleRemoteHandedOff) {
+      call.handleRemoteHandedOff();
+    }
+  }
+
+  onRemoteEarlyMedia(remoteUserId: UserId): void {
+    const call = this._call;
+    if (!call || call.remoteUserId !== remoteUserId) {
+      return;
+    }
+
+    if (call.handleRemoteEarlyMedia) {
+      call.handleRemoteEarlyMedia();
+    }
+  }
+
+  onMediaStalled(remoteUserId: UserId): void {
+    const call = this._call;
+    if (!call || call.remoteUserId !== remoteUserId) {
+      return;
+    }
+
+    if (call.handleMediaStalled) {
+      call.handleMediaStalled();
+    }
+  }
+
+  onAppData(remoteUserId: UserId, data: Buffer): void {
+    const call = this._call;
+    if (!call || call.remoteUserId !== remoteUserId) {
+      return;
+    }
+
+    if (call.handleAppData) {
+      call.handleAppData(data);
+    }
+  }
+
+  onVideoAdaptationChanged(
+    remoteUserId: UserId,
+    reason: QualityLimitationReason | undefined
+  ): void {
+    const call = this._call;
+    if (!call || call.remoteUserId !== remoteUserId) {
+      return;
+    }
+
+    if (call.handleVideoAdaptationChanged) {
+      call.handleVideoAdaptationChanged(reason);
+    }
+  }
+
+  onVideoNegotiation(remoteUserId: UserId, event: VideoNegotiationEvent): void {
+    const call = this._call;
+    if (!call || call.remoteUserId !== remoteUserId) {
+      return;
+    }
+
+    if (call.handleVideoNegotiation) {
+      call.handleVideoNegotiation(event);
+    }
+  }
+
   onRemoteVideoEnabled(remoteUserId: UserId, enabled: boolean): void {
     const call = this._call;
     if (!call || call.remoteUserId !== remoteUserId) {
@@ -733,7 +1195,9 @@ export class RingRTCType {
 
   onNetworkRouteChanged(
     remoteUserId: UserId,
-    localNetworkAdapterType: NetworkAdapterType
+    localNetworkAdapterType: NetworkAdapterType,
+    localNetworkAdapterTypeUnderVpn: NetworkAdapterType,
+    relayed: boolean
   ): void {
     const call = this._call;
     if (!call || call.remoteUserId !== remoteUserId) {
@@ -741,6 +1205,9 @@ export class RingRTCType {
     }
 
     call.networkRoute.localAdapterType = localNetworkAdapterType;
+    call.networkRoute.localAdapterTypeUnderVpn =
+      localNetworkAdapterTypeUnderVpn;
+    call.networkRoute.relayed = relayed;
     if (call.handleNetworkRouteChanged) {
       call.handleNetworkRouteChanged();
     }
@@ -1082,6 +1549,84 @@ export class RingRTCType {
     return promise;
   }
 
+  /**
+   * Asynchronous request to switch a call link to a new admin passkey, so that
+   * a leaked one can be retired without recreating the link.
+   *
+   * Keep both passkeys until this succeeds, since a lost response leaves it
+   * unclear which one is in effect. If the old passkey is rejected because an
+   * earlier attempt went through, the new one is checked instead, and
+   * {@link CallLinkState.oldAdminPasskeyRejected} is set.
+   * - On success, the new passkey is in effect and the old one can be dropped.
+   * - 403: neither passkey is valid any more.
+   * - On any other failure, retry with the same pair of passkeys.
+   *
+   * @param sfuUrl - the URL to use when accessing the SFU
+   * @param authCredentialPresentation - a serialized CallLinkAuthCredentialPresentation
+   * @param linkRootKey - the root key for the call link
+   * @param adminPasskey - the passkey currently in effect
+   * @param newAdminPasskey - the passkey to switch to, from {@link CallLinkRootKey.generateAdminPassKey}
+   */
+  rotateCallLinkAdminPasskey(
+    sfuUrl: string,
+    authCredentialPresentation: Buffer,
+    linkRootKey: CallLinkRootKey,
+    adminPasskey: Buffer,
+    newAdminPasskey: Buffer
+  ): Promise<HttpResult<CallLinkState>> {
+    const [requestId, promise] = this._callLinkRequests.add();
+    // Response comes back via handleCallLinkResponse
+    sillyDeadlockProtection(() => {
+      this.callManager.rotateCallLinkAdminPasskey(
+        requestId,
+        sfuUrl,
+        authCredentialPresentation,
+        linkRootKey.bytes,
+        adminPasskey,
+        newAdminPasskey
+      );
+    });
+    return promise;
+  }
+
+  /**
+   * Asynchronous request to move a call link to a newer version of its root
+   * key, by re-encrypting its name and details.
+   *
+   * Links shared with the old key keep working, since the room and key bytes
+   * don't change. Once this succeeds, store and share `linkRootKey` in place
+   * of the old key.
+   *
+   * Possible failure codes include:
+   * - 403: the admin passkey is incorrect
+   * - 404: the room doesn't exist (or expired so long ago that it has been
+   *   removed from the server)
+   *
+   * @param sfuUrl - the URL to use when accessing the SFU
+   * @param authCredentialPresentation - a serialized CallLinkAuthCredentialPresentation
+   * @param linkRootKey - the root key for the call link, from {@link CallLinkRootKey.withLatestVersion}
+   * @param adminPasskey - the passkey specified when the link was created
+   */
+  migrateCallLink(
+    sfuUrl: string,
+    authCredentialPresentation: Buffer,
+    linkRootKey: CallLinkRootKey,
+    adminPasskey: Buffer
+  ): Promise<HttpResult<CallLinkState>> {
+    const [requestId, promise] = this._callLinkRequests.add();
+    // Response comes back via handleCallLinkResponse
+    sillyDeadlockProtection(() => {
+      this.callManager.migrateCallLink(
+        requestId,
+        sfuUrl,
+        authCredentialPresentation,
+        linkRootKey.bytes,
+        adminPasskey
+      );
+    });
+    return promise;
+  }
+
   /**
    * Asynchronous request to delete a call link.
    *
@@ -1148,13 +1693,16 @@ export class RingRTCType {
     sfuUrl: string,
     hkdfExtraInfo: Buffer,
     audioLevelsIntervalMillis: number | undefined,
-    observer: GroupCallObserver
+    observer: GroupCallObserver,
+    // Relay servers to use instead of connecting to the SFU directly.
+    iceServers: Array<IceServer> = []
   ): GroupCall | undefined {
     const clientId = this.callManager.createGroupCallClient(
       groupId,
       sfuUrl,
       hkdfExtraInfo,
-      audioLevelsIntervalMillis || 0
+      audioLevelsIntervalMillis || 0,
+      iceServers
     );
     if (clientId === INVALID_CLIENT_ID) {
       // Return undefined since the group call client creation failed.
@@ -1181,7 +1729,9 @@ export class RingRTCType {
     adminPasskey: Buffer | undefined,
     hkdfExtraInfo: Buffer,
     audioLevelsIntervalMillis: number | undefined,
-    observer: GroupCallObserver
+    observer: GroupCallObserver,
+    // Relay servers to use instead of connecting to the SFU directly.
+    iceServers: Array<IceServer> = []
   ): GroupCall | undefined {
     const clientId = this.callManager.createCallLinkCallClient(
       sfuUrl,
@@ -1189,7 +1739,8 @@ export class RingRTCType {
       rootKey.bytes,
       adminPasskey,
       hkdfExtraInfo,
-      audioLevelsIntervalMillis || 0
+      audioLevelsIntervalMillis || 0,
+      iceServers
     );
     if (clientId === INVALID_CLIENT_ID) {
       // Return undefined since the call link client creation failed.
@@ -1392,49 +1943,112 @@ export class RingRTCType {
   }
 
   // Called by Rust
-  handleRemoteDevicesChanged(
+  handleAppData(
     clientId: GroupCallClientId,
-    remoteDeviceStates: Array<RemoteDeviceState>
+    demuxId: number,
+    data: Buffer
   ): void {
     sillyDeadlockProtection(() => {
       const groupCall = this._groupCallByClientId.get(clientId);
-      if (!groupCall) {
-        this.logError(
-          'handleRemoteDevicesChanged(): GroupCall not found in map!'
-        );
-        return;
+      if (groupCall) {
+        groupCall.handleAppData(demuxId, data);
       }
-
-      groupCall.handleRemoteDevicesChanged(remoteDeviceStates);
     });
   }
 
   // Called by Rust
-  handlePeekChanged(clientId: GroupCallClientId, rawInfo: RawPeekInfo): void {
+  handleRemoteConnectionQualities(
+    clientId: GroupCallClientId,
+    qualities: Array<RemoteConnectionQuality>
+  ): void {
     sillyDeadlockProtection(() => {
       const groupCall = this._groupCallByClientId.get(clientId);
-      if (!groupCall) {
-        this.logError('handlePeekChanged(): GroupCall not found in map!');
-        return;
+      if (groupCall) {
+        groupCall.handleRemoteConnectionQualities(qualities);
       }
-
-      const info = rawPeekInfoToPeekInfo(rawInfo);
-      groupCall.handlePeekChanged(info);
     });
   }
 
   // Called by Rust
-  handlePeekResponse(
-    requestId: number,
-    statusCode: number,
-    rawInfo: RawPeekInfo | undefined
+  handleAvSyncSkew(
+    clientId: GroupCallClientId,
+    skews: Array<RemoteAvSyncSkew>
   ): void {
     sillyDeadlockProtection(() => {
-      let result: HttpResult<PeekInfo>;
-      if (rawInfo) {
-        const info = rawPeekInfoToPeekInfo(rawInfo);
-        result = { success: true, value: info };
-      } else {
+      const groupCall = this._groupCallByClientId.get(clientId);
+      if (groupCall) {
+        groupCall.handleAvSyncSkew(skews);
+      }
+    });
+  }
+
+  // Called by Rust
+  handleE2eeHealth(clientId: GroupCallClientId, health: E2eeHealth): void {
+    sillyDeadlockProtection(() => {
+      const groupCall = this._groupCallByClientId.get(clientId);
+      if (groupCall) {
+        groupCall.handleE2eeHealth(health);
+      }
+    });
+  }
+
+  // Called by Rust
+  handleVideoAdaptationChanged(
+    clientId: GroupCallClientId,
+    reason: QualityLimitationReason | undefined
+  ): void {
+    sillyDeadlockProtection(() => {
+      const groupCall = this._groupCallByClientId.get(clientId);
+      if (groupCall) {
+        groupCall.handleVideoAdaptationChanged(reason);
+      }
+    });
+  }
+
+  // Called by Rust
+  handleRemoteDevicesChanged(
+    clientId: GroupCallClientId,
+    remoteDeviceStates: Array<RemoteDeviceState>
+  ): void {
+    sillyDeadlockProtection(() => {
+      const groupCall = this._groupCallByClientId.get(clientId);
+      if (!groupCall) {
+        this.logError(
+          'handleRemoteDevicesChanged(): GroupCall not found in map!'
+        );
+        return;
+      }
+
+      groupCall.handleRemoteDevicesChanged(remoteDeviceStates);
+    });
+  }
+
+  // Called by Rust
+  handlePeekChanged(clientId: GroupCallClientId, rawInfo: RawPeekInfo): void {
+    sillyDeadlockProtection(() => {
+      const groupCall = this._groupCallByClientId.get(clientId);
+      if (!groupCall) {
+        this.logError('handlePeekChanged(): GroupCall not found in map!');
+        return;
+      }
+
+      const info = rawPeekInfoToPeekInfo(rawInfo);
+      groupCall.handlePeekChanged(info);
+    });
+  }
+
+  // Called by Rust
+  handlePeekResponse(
+    requestId: number,
+    statusCode: number,
+    rawInfo: RawPeekInfo | undefined
+  ): void {
+    sillyDeadlockProtection(() => {
+      let result: HttpResult<PeekInfo>;
+      if (rawInfo) {
+        const info = rawPeekInfoToPeekInfo(rawInfo);
+        result = { success: true, value: info };
+      } else {
         result = { success: false, errorStatusCode: statusCode };
       }
       if (!this._peekRequests.resolve(requestId, result)) {
@@ -1490,6 +2104,77 @@ export class RingRTCType {
     });
   }
 
+  // Called by Rust
+  handleBreakoutGroupChanged(
+    clientId: GroupCallClientId,
+    breakoutGroupId: number | undefined
+  ): void {
+    sillyDeadlockProtection(() => {
+      const groupCall = this._groupCallByClientId.get(clientId);
+      if (groupCall) {
+        groupCall.handleBreakoutGroupChanged(breakoutGroupId);
+      }
+    });
+  }
+
+  // Called by Rust
+  handleOwnDeviceJoined(clientId: GroupCallClientId, demuxId: number): void {
+    sillyDeadlockProtection(() => {
+      const groupCall = this._groupCallByClientId.get(clientId);
+      if (groupCall) {
+        groupCall.handleOwnDeviceJoined(demuxId);
+      }
+    });
+  }
+
+  // Called by Rust
+  requestIceServers(clientId: GroupCallClientId): void {
+    sillyDeadlockProtection(() => {
+      const groupCall = this._groupCallByClientId.get(clientId);
+      if (groupCall) {
+        groupCall.requestIceServers();
+      }
+    });
+  }
+
+  // Called by Rust
+  handleIceServersRefreshFailed(clientId: GroupCallClientId): void {
+    sillyDeadlockProtection(() => {
+      const groupCall = this._groupCallByClientId.get(clientId);
+      if (groupCall) {
+        groupCall.handleIceServersRefreshFailed();
+      }
+    });
+  }
+
+  // Called by Rust
+  handleCallFull(
+    clientId: GroupCallClientId,
+    maxDevices: number | undefined,
+    deviceCount: number | undefined
+  ): void {
+    sillyDeadlockProtection(() => {
+      const groupCall = this._groupCallByClientId.get(clientId);
+      if (groupCall) {
+        groupCall.handleCallFull(maxDevices, deviceCount);
+      }
+    });
+  }
+
+  // Called by Rust
+  handleGroupCallSummary(
+    clientId: GroupCallClientId,
+    summary: CallSummary,
+    reason: GroupCallEndReason
+  ): void {
+    sillyDeadlockProtection(() => {
+      const groupCall = this._groupCallByClientId.get(clientId);
+      if (groupCall) {
+        groupCall.handleCallSummary(summary, reason);
+      }
+    });
+  }
+
   // Called by Rust
   handleEnded(clientId: GroupCallClientId, reason: GroupCallEndReason): void {
     sillyDeadlockProtection(() => {
@@ -1528,6 +2213,16 @@ export class RingRTCType {
     }
   }
 
+  // Called by Rust
+  onAudioDevicesChanged(
+    inputs: Array<AudioDevice>,
+    outputs: Array<AudioDevice>
+  ): void {
+    if (this.handleAudioDevicesChanged) {
+      this.handleAudioDevicesChanged(inputs, outputs);
+    }
+  }
+
   // Called by Rust
   handleSpeechEvent(clientId: GroupCallClientId, event: SpeechEvent): void {
     sillyDeadlockProtection(() => {
@@ -1791,24 +2486,28 @@ export class RingRTCType {
     return null;
   }
 
-  accept(callId: CallId, asVideoCall: boolean): void {
+  // With options, the microphone and/or camera start out off without
+  // racing a separate call after accepting.
+  accept(callId: CallId, asVideoCall: boolean, options?: AcceptOptions): void {
     const call = this.getCall(callId);
     if (!call) {
       return;
     }
 
-    call.accept();
-    call.outgoingAudioEnabled = true;
-    call.outgoingVideoEnabled = asVideoCall;
+    call.accept(options);
+    call.outgoingAudioEnabled = !options?.audioMuted;
+    call.outgoingVideoEnabled = asVideoCall && !options?.videoDisabled;
   }
 
-  decline(callId: CallId): void {
+  // If a reason is given, the caller is told why the call was declined
+  // rather than seeing a normal hangup.
+  decline(callId: CallId, reason?: DeclineReason): void {
     const call = this.getCall(callId);
     if (!call) {
       return;
     }
 
-    call.decline();
+    call.decline(reason);
   }
 
   ignore(callId: CallId): void {
@@ -1820,6 +2519,27 @@ export class RingRTCType {
     call.ignore();
   }
 
+  setOnHold(callId: CallId, onHold: boolean): void {
+    const call = this.getCall(callId);
+    if (!call) {
+      return;
+    }
+
+    call.onHold = onHold;
+  }
+
+  // Asks another device of the local user to take over the accepted call.
+  // The call ends here with CallEndedReason.HandedOff once it has.
+  handOff(
+    callId: CallId,
+    remoteUserId: Buffer,
+    targetDeviceId: DeviceId
+  ): void {
+    sillyDeadlockProtection(() => {
+      this.callManager.handOff(callId, remoteUserId, targetDeviceId);
+    });
+  }
+
   hangup(callId: CallId): void {
     const call = this.getCall(callId);
     if (!call) {
@@ -1829,6 +2549,69 @@ export class RingRTCType {
     call.hangup();
   }
 
+  setCallWaitingEnabled(enabled: boolean): void {
+    this.callManager.setCallWaitingEnabled(enabled);
+  }
+
+  // Restart ICE automatically when the connection of an accepted call drops,
+  // as when switching between Wi-Fi and cellular.
+  setRestartIceOnDisconnect(enabled: boolean): void {
+    this.callManager.setRestartIceOnDisconnect(enabled);
+  }
+
+  // Only use relay (TURN) candidates for every 1:1 call, as if hideIp were
+  // passed to proceed(), so the remote peer never learns the user's IP address.
+  setRelayOnlyByDefault(enabled: boolean): void {
+    this.callManager.setRelayOnlyByDefault(enabled);
+  }
+
+  // Limits the size of the opaque offers and answers handed to
+  // handleOutgoingSignaling, compressing and trimming them as needed so they
+  // fit in a push payload. Pass 0 to remove the limit.
+  setMaxSignalingMessageSize(maxSize: number): void {
+    this.callManager.setMaxSignalingMessageSize(maxSize);
+  }
+
+  // Puts the current call on hold and accepts the waiting call.
+  // The new call is delivered through handleIncomingCall as usual.
+  holdAndAcceptWaitingCall(): void {
+    const call = this._call;
+    if (call) {
+      call.setHeldLocally();
+      this._heldCall = call;
+      this._call = null;
+    }
+    sillyDeadlockProtection(() => {
+      this.callManager.holdAndAcceptWaitingCall();
+    });
+  }
+
+  declineWaitingCall(): void {
+    sillyDeadlockProtection(() => {
+      this.callManager.declineWaitingCall();
+    });
+  }
+
+  // Only takes effect once the current call has ended.
+  resumeHeldCall(): void {
+    const heldCall = this._heldCall;
+    if (!heldCall || (this._call && this._call.state !== CallState.Ended)) {
+      return;
+    }
+    this._heldCall = null;
+    this._call = heldCall;
+    heldCall.setHeldLocally(false);
+    sillyDeadlockProtection(() => {
+      this.callManager.resumeHeldCall();
+    });
+  }
+
+  hangupHeldCall(): void {
+    sillyDeadlockProtection(() => {
+      this.callManager.hangupHeldCall();
+    });
+  }
+
   setOutgoingAudio(callId: CallId, enabled: boolean): void {
     const call = this.getCall(callId);
     if (!call) {
@@ -1847,12 +2630,17 @@ export class RingRTCType {
     call.outgoingVideoEnabled = enabled;
   }
 
-  setOutgoingVideoIsScreenShare(callId: CallId, isScreenShare: boolean): void {
+  setOutgoingVideoIsScreenShare(
+    callId: CallId,
+    isScreenShare: boolean,
+    contentHint?: VideoContentHint
+  ): void {
     const call = this.getCall(callId);
     if (!call) {
       return;
     }
 
+    call.outgoingVideoContentHint = contentHint;
     call.outgoingVideoIsScreenShare = isScreenShare;
   }
 
@@ -1882,6 +2670,12 @@ export class RingRTCType {
     this.callManager.setAudioInput(index);
   }
 
+  // Unlike indices, unique IDs stay valid when devices are added or removed.
+  // Returns false if no device has the given ID.
+  setAudioInputById(uniqueId: string): boolean {
+    return this.callManager.setAudioInputById(uniqueId);
+  }
+
   getAudioOutputs(): Array<AudioDevice> {
     return this.callManager.getAudioOutputs();
   }
@@ -1889,6 +2683,10 @@ export class RingRTCType {
   setAudioOutput(index: number): void {
     this.callManager.setAudioOutput(index);
   }
+
+  setAudioOutputById(uniqueId: string): boolean {
+    return this.callManager.setAudioOutputById(uniqueId);
+  }
 }
 
 export interface CallSettings {
@@ -1896,6 +2694,33 @@ export interface CallSettings {
   hideIp: boolean;
   dataMode: DataMode;
   audioLevelsIntervalMillis?: number;
+  // How long the call may go unaccepted, from when it was created.
+  // Bounded to between 1 second and 5 minutes; defaults to 1 minute.
+  ringTimeoutMillis?: number;
+  // How long the call may take to connect after proceeding.
+  // Bounded to between 1 second and 2 minutes; defaults to 1 minute.
+  connectTimeoutMillis?: number;
+  // Whether to play audio the callee sends before accepting, such as a
+  // custom ringback tone. Only used for outgoing calls.
+  earlyMedia?: boolean;
+  // If set, how long the call may go without receiving anything while
+  // connected before handleMediaStalled is called.
+  // Bounded to between 3 seconds and 2 minutes.
+  mediaStallTimeoutMillis?: number;
+  // Whether to end the call with CallEndedReason.MediaStalled once its media
+  // stalls.
+  hangUpOnMediaStall?: boolean;
+  // The audio profile to start the call with. Defaults to Voice.
+  audioProfile?: AudioProfile;
+  // The jitter buffer delays for incoming audio. They can't be changed once
+  // the call has started.
+  jitterBuffer?: JitterBufferConfig;
+}
+
+// How the callee's media starts out when accepting a call.
+export interface AcceptOptions {
+  audioMuted?: boolean;
+  videoDisabled?: boolean;
 }
 
 interface IceServer {
@@ -1943,21 +2768,39 @@ export class Call {
   private _outgoingAudioEnabled = false;
   private _outgoingVideoEnabled = false;
   private _outgoingVideoIsScreenShare = false;
+  // Unset means Detailed for screenshare and None otherwise.
+  outgoingVideoContentHint: VideoContentHint | undefined;
+  private _onHold = false;
   private _remoteAudioEnabled = false;
   private _remoteVideoEnabled = false;
   outgoingAudioLevel: NormalizedAudioLevel = 0;
   remoteAudioLevel: NormalizedAudioLevel = 0;
   remoteSharingScreen = false;
+  remoteOnHold = false;
   networkRoute: NetworkRoute = new NetworkRoute();
   private _videoCapturer: VideoCapturer | null = null;
   private _videoRenderer: VideoRenderer | null = null;
   endedReason?: CallEndedReason;
+  // Opaque data the UX attached to the call, passed back with notifications
+  // that don't come with the Call itself. Empty unless set.
+  appData: Buffer = Buffer.alloc(0);
 
   // These callbacks should be set by the UX code.
   handleStateChanged?: () => void;
   handleRemoteAudioEnabled?: () => void;
   handleRemoteVideoEnabled?: () => void;
   handleRemoteSharingScreen?: () => void;
+  handleRemoteHoldChanged?: () => void;
+  handleRemoteHandedOff?: () => void;
+  handleRemoteEarlyMedia?: () => void;
+  handleVideoNegotiation?: (event: VideoNegotiationEvent) => void;
+  handleMediaStalled?: () => void;
+  handleAppData?: (data: Buffer) => void;
+  // Outgoing video started or stopped being scaled down; undefined once it
+  // stops.
+  handleVideoAdaptationChanged?: (
+    reason: QualityLimitationReason | undefined
+  ) => void;
   handleNetworkRouteChanged?: () => void;
   handleAudioLevels?: () => void;
 
@@ -2038,18 +2881,98 @@ export class Call {
     this.enableOrDisableRenderer();
   }
 
-  accept(): void {
-    this._callManager.accept(this.callId);
+  accept(options?: AcceptOptions): void {
+    this._callManager.accept(
+      this.callId,
+      options?.audioMuted ?? false,
+      options?.videoDisabled ?? false
+    );
   }
 
-  decline(): void {
-    this.hangup();
+  decline(reason?: DeclineReason): void {
+    if (reason === undefined) {
+      this.hangup();
+      return;
+    }
+
+    sillyDeadlockProtection(() => {
+      this._callManager.decline(this.callId, reason);
+    });
   }
 
   ignore(): void {
     this._callManager.ignore(this.callId);
   }
 
+  // Restarts ICE with new credentials, e.g. after a network change.
+  // Only takes effect once the call has been accepted.
+  restartIce(): void {
+    sillyDeadlockProtection(() => {
+      this._callManager.restartIce(this.callId);
+    });
+  }
+
+  // Asks the remote peer to agree to this side sending video. Video should
+  // only be sent after handleVideoNegotiation gets LocalAddAccepted.
+  enableVideo(): void {
+    sillyDeadlockProtection(() => {
+      this._callManager.enableVideo(this.callId);
+    });
+  }
+
+  // Stops sending video, which the remote peer doesn't need to agree to.
+  disableVideo(): void {
+    sillyDeadlockProtection(() => {
+      this._callManager.disableVideo(this.callId);
+    });
+  }
+
+  // Answers the remote peer's request to send video (RemoteAddRequested).
+  respondToVideoRequest(accepted: boolean): void {
+    sillyDeadlockProtection(() => {
+      this._callManager.respondToVideoRequest(this.callId, accepted);
+    });
+  }
+
+  // Sends data to the remote peer once the call is accepted, where it arrives
+  // with handleAppData. Reliable data is resent until received and arrives in
+  // order, while unreliable data may be lost. Throws if the data is larger
+  // than MAX_APP_DATA_LENGTH.
+  sendAppData(data: Buffer, reliable: boolean): void {
+    sillyDeadlockProtection(() => {
+      this._callManager.sendAppData(this.callId, data, reliable);
+    });
+  }
+
+  // A 6-digit code derived from the keys negotiated for this call. Users can
+  // read it to each other to check that nobody tampered with the signaling.
+  // Null until the call has connected.
+  getVerificationCode(): string | null {
+    return this._callManager.getVerificationCode(this.callId);
+  }
+
+  // Attaches opaque data to the call, such as which account it belongs to.
+  // For an incoming call, set this before proceeding.
+  setAppData(appData: Buffer): void {
+    this.appData = appData;
+    this._callManager.setCallAppData(this.callId, appData);
+  }
+
+  // A 128-bit ID for this call as 32 hex digits, which unlike callId is
+  // practically unique across all users. Null for an incoming call from a
+  // client that doesn't send one.
+  getWideCallId(): string | null {
+    return this._callManager.getWideCallId(this.callId);
+  }
+
+  // Starts sending audio to the caller while ringing, before accepting.
+  // Ignored unless the caller enabled earlyMedia in its CallSettings.
+  startEarlyMedia(): void {
+    sillyDeadlockProtection(() => {
+      this._callManager.startEarlyMedia(this.callId);
+    });
+  }
+
   hangup(): void {
     // This is a little faster than waiting for the
     // change in call state to come back.
@@ -2090,10 +3013,30 @@ export class Call {
     // This assumes we only have one active call.
     this._outgoingVideoIsScreenShare = isScreenShare;
     sillyDeadlockProtection(() => {
-      this._callManager.setOutgoingVideoIsScreenShare(isScreenShare);
+      this._callManager.setOutgoingVideoIsScreenShare(
+        isScreenShare,
+        this.outgoingVideoContentHint
+      );
+    });
+  }
+
+  get onHold(): boolean {
+    return this._onHold;
+  }
+
+  // Only takes effect once the call has been accepted.
+  set onHold(onHold: boolean) {
+    this._onHold = onHold;
+    sillyDeadlockProtection(() => {
+      this._callManager.setOnHold(this.callId, onHold);
     });
   }
 
+  // Updates the hold state when RingRTC holds or resumes the call itself.
+  setHeldLocally(onHold = true): void {
+    this._onHold = onHold;
+  }
+
   get remoteAudioEnabled(): boolean {
     return this._remoteAudioEnabled;
   }
@@ -2116,20 +3059,53 @@ export class Call {
     width: number,
     height: number,
     format: VideoPixelFormatEnum,
-    buffer: Buffer
+    buffer: Buffer,
+    rotation?: VideoRotation
   ): void {
     // This assumes we only have one active call.
-    this._callManager.sendVideoFrame(width, height, format, buffer);
+    this._callManager.sendVideoFrame(width, height, format, buffer, rotation);
+  }
+
+  // Queues interleaved 16-bit PCM to be sent with (or instead of) the
+  // microphone, for example a text-to-speech announcement. Any sample rate is
+  // accepted and resampled as needed.
+  sendAudioSamples(
+    samples: Int16Array,
+    sampleRateHz: number,
+    channels: number
+  ): void {
+    this._callManager.sendAudioSamples(samples, sampleRateHz, channels);
+  }
+
+  setAudioInjectionMode(mode: AudioInjectionMode): void {
+    this._callManager.setAudioInjectionMode(mode);
+  }
+
+  // Drops any queued audio that hasn't been sent yet.
+  clearInjectedAudio(): void {
+    this._callManager.clearInjectedAudio();
+  }
+
+  // Sends the audio the system is playing along with the microphone, such as
+  // while sharing the screen. Only supported on Windows.
+  shareSystemAudio(enabled: boolean): void {
+    this._callManager.shareSystemAudio(enabled);
   }
 
   // With this method, a Call is a VideoFrameSource
   receiveVideoFrame(
     buffer: Buffer,
     maxWidth: number,
-    maxHeight: number
-  ): [number, number] | undefined {
+    maxHeight: number,
+    applyRotation?: boolean
+  ): [number, number, VideoRotation] | undefined {
     // This assumes we only have one active call.
-    return this._callManager.receiveVideoFrame(buffer, maxWidth, maxHeight);
+    return this._callManager.receiveVideoFrame(
+      buffer,
+      maxWidth,
+      maxHeight,
+      applyRotation
+    );
   }
 
   private enableOrDisableCapturer(): void {
@@ -2178,6 +3154,82 @@ export class Call {
     });
   }
 
+  // Changes how often handleAudioLevels is called, or stops it with 0.
+  setAudioLevelsInterval(intervalMillis: number): void {
+    sillyDeadlockProtection(() => {
+      try {
+        this._callManager.setAudioLevelsInterval(intervalMillis);
+      } catch {
+        // We may not have an active connection any more.
+        // In which case it doesn't matter
+      }
+    });
+  }
+
+  // Returns the stats from the most recent stats report, or undefined if
+  // there is no active connection or no report has arrived yet.
+  getStats(): CallStats | undefined {
+    try {
+      return this._callManager.getStats();
+    } catch {
+      // We may not have an active connection any more.
+      return undefined;
+    }
+  }
+
+  // Returns every ICE candidate pair from the most recent stats report, to
+  // diagnose connectivity problems. Empty if there is no active connection or
+  // no report has arrived yet.
+  getCandidatePairs(): Array<CandidatePairStats> {
+    try {
+      return this._callManager.getCandidatePairs();
+    } catch {
+      // We may not have an active connection any more.
+      return [];
+    }
+  }
+
+  // Caps the outgoing camera video, or screen share if isScreenShare is true.
+  // Captured frames are scaled down and dropped to fit, without restarting
+  // the capturer. The limits stay in effect until changed.
+  setOutgoingVideoLimits(limits: VideoLimits, isScreenShare = false): void {
+    this._callManager.setOutgoingVideoLimits(
+      isScreenShare,
+      limits.maxWidth ?? 0,
+      limits.maxHeight ?? 0,
+      limits.maxFramerate ?? 0
+    );
+  }
+
+  // Switches the outgoing audio between speech and music, such as when
+  // starting to share music.
+  updateAudioProfile(audioProfile: AudioProfile): void {
+    sillyDeadlockProtection(() => {
+      try {
+        this._callManager.updateAudioProfile(audioProfile);
+      } catch {
+        // We may not have an active connection any more.
+        // In which case it doesn't matter
+      }
+    });
+  }
+
+  // Changes how outgoing audio is protected against packet loss.
+  setAudioLossProtection(protection: AudioLossProtection): void {
+    sillyDeadlockProtection(() => {
+      try {
+        this._callManager.setAudioLossProtection(
+          protection.fec,
+          protection.enableLossPercent ?? DEFAULT_ENABLE_LOSS_PERCENT,
+          protection.disableLossPercent ?? DEFAULT_DISABLE_LOSS_PERCENT
+        );
+      } catch {
+        // We may not have an active connection any more.
+        // In which case it doesn't matter
+      }
+    });
+  }
+
   updateDataMode(dataMode: DataMode): void {
     sillyDeadlockProtection(() => {
       try {
@@ -2189,6 +3241,37 @@ export class Call {
     });
   }
 
+  // Caps the bitrates on top of the data mode. The remote peer applies the
+  // caps to what it sends as well.
+  updateBitrateConfig(config: CallBitrateConfig): void {
+    sillyDeadlockProtection(() => {
+      try {
+        this._callManager.updateBitrateConfig(
+          config.maxAudioBitrateBps ?? 0,
+          config.maxVideoBitrateBps ?? 0,
+          config.maxTotalBitrateBps ?? 0
+        );
+      } catch {
+        // We may not have an active connection any more.
+        // In which case it doesn't matter
+      }
+    });
+  }
+
+  // Overrides the max send bitrates of the data mode, e.g. to stay within a
+  // strict egress budget. Unlike updateBitrateConfig, this isn't shared with
+  // the remote peer. Only takes effect once the call is connected.
+  setMaxSendBitrate(max: MaxSendBitrate): void {
+    sillyDeadlockProtection(() => {
+      this._callManager.setMaxSendBitrate(
+        this.callId,
+        max.audioBps ?? 0,
+        max.cameraVideoBps ?? 0,
+        max.screenShareBps ?? 0
+      );
+    });
+  }
+
   private enableOrDisableRenderer(): void {
     if (!this._videoRenderer) {
       return;
@@ -2214,85 +3297,208 @@ export class Call {
   }
 }
 
-// Group Calls
+// Group Calls
+
+export type GroupCallClientId = number;
+
+export interface RemoteConnectionQuality {
+  demuxId: number; // UInt32
+  quality: ConnectionQuality;
+}
+
+// A remote device whose audio and video are being played out noticeably out
+// of sync. The skew is how far video trails audio; negative if video is ahead.
+export interface RemoteAvSyncSkew {
+  demuxId: number; // UInt32
+  skewMillis: number;
+}
+
+// How well incoming media and data decrypted since the previous report, so
+// that apps can (with consent) report decryption failures in aggregate.
+export interface E2eeHealth {
+  windowMillis: number;
+  framesDecrypted: number;
+  framesFailed: number;
+  // Only devices that had any failures, ordered by demux ID.
+  failuresBySender: Array<{ demuxId: number; framesFailed: number }>;
+}
+
+// How often group calls do their periodic work. Omit a field to keep the default.
+export interface GroupCallIntervals {
+  // How often to send heartbeats and video requests to the SFU (default 1s).
+  heartbeatMillis?: number;
+  // How often to get and log stats (default 10s).
+  statsMillis?: number;
+  // How often to request an updated membership list from the SFU (default 10s).
+  peekPollMillis?: number;
+}
+
+// Totals for a call that has ended, for call history and telemetry.
+export interface CallSummary {
+  // How long the local device was connected to the call.
+  durationMillis: number;
+  audioBytesSent: number;
+  audioBytesReceived: number;
+  videoBytesSent: number;
+  videoBytesReceived: number;
+  // Undefined if the round trip time was never measured.
+  averageRoundTripTimeMillis: number | undefined;
+  // The estimated audio quality, as a mean opinion score from 1 (bad) to 4.5
+  // (excellent). Below about 3.5, most people would rate the call as poor.
+  // Undefined if no audio ever flowed.
+  averageMos: number | undefined;
+  // The lowest per-minute estimate, which catches a bad stretch in a long
+  // call. Undefined if the call never had a full minute of audio.
+  worstMinuteMos: number | undefined;
+  // Includes the local device.
+  maxParticipantCount: number;
+  // CSV with a row for every stats report, for offline analysis: elapsed_ms,
+  // send_kbps, receive_kbps, loss_pct, remote_loss_pct, rtt_ms, and freezes.
+  // Rows are as far apart as the stats interval.
+  statsTimeSeries: string;
+  setupLatencies: SetupLatencies;
+}
 
-export type GroupCallClientId = number;
+// How long after the start of a call each setup milestone was reached, or
+// undefined if it wasn't. 1:1 calls start when created and group calls when
+// joined. Group calls never ring.
+export interface SetupLatencies {
+  timeToRingMillis?: number;
+  // Accepted and connected for a 1:1 call, or connected to the SFU for a
+  // group call.
+  timeToConnectMillis?: number;
+  timeToFirstFrameMillis?: number;
+}
+
+// Counts of setup latencies across calls. Each array has a count for every
+// bucket bound (inclusive), followed by one for everything slower.
+export interface SetupLatencyHistograms {
+  bucketBoundsMillis: Array<number>;
+  timeToRing: Array<number>;
+  timeToConnect: Array<number>;
+  timeToFirstFrame: Array<number>;
+}
+
+// An outgoing stream over the most recent stats interval. Loss, jitter, and
+// round trip time are as reported back by the receiver. The frame fields are
+// only set for video.
+export interface OutboundStreamStats {
+  ssrc: number;
+  bitrateBps: number;
+  packetsPerSecond: number;
+  remotePacketLossPercent: number;
+  remoteJitterMillis: number;
+  remoteRoundTripTimeMillis: number;
+  framerate: number;
+  frameWidth: number;
+  frameHeight: number;
+  // Undefined for audio, which is always Opus.
+  codec: 'VP8' | 'VP9' | undefined;
+  // Undefined if the stream isn't being scaled down, and always for audio.
+  qualityLimitationReason: QualityLimitationReason | undefined;
+}
+
+// Matches QualityLimitationReason in rust.
+export enum QualityLimitationReason {
+  Cpu = 1,
+  Bandwidth,
+  Other,
+}
+
+// An incoming stream over the most recent stats interval. Jitter is only set
+// for audio and the frame fields only for video. Frame counts are totals for
+// the life of the stream.
+export interface InboundStreamStats {
+  ssrc: number;
+  bitrateBps: number;
+  packetsPerSecond: number;
+  packetLossPercent: number;
+  jitterMillis: number;
+  // The average time audio spent in the jitter buffer over the interval.
+  // Always 0 for video.
+  jitterBufferDelayMillis: number;
+  framerate: number;
+  framesDecoded: number;
+  framesDropped: number;
+  freezeCount: number;
+  frameWidth: number;
+  frameHeight: number;
+  // Undefined for audio, which is always Opus.
+  codec: 'VP8' | 'VP9' | undefined;
+}
+
+// A snapshot of the most recent stats report for a call.
+export interface CallStats {
+  timestampMillis: number;
+  roundTripTimeMillis: number;
+  availableOutgoingBitrateBps: number;
+  audioSend: Array<OutboundStreamStats>;
+  videoSend: Array<OutboundStreamStats>;
+  audioReceive: Array<InboundStreamStats>;
+  videoReceive: Array<InboundStreamStats>;
+  // The jitter buffer delays in effect, as set on the call.
+  jitterBuffer: JitterBufferConfig;
+  avSync: Array<AvSyncStats>;
+  // The estimated audio quality over the interval, as in CallSummary, scoring
+  // the worst audio stream in either direction. Undefined if no audio is
+  // flowing.
+  mos: number | undefined;
+  // The average over the most recent full minute of audio.
+  minuteMos: number | undefined;
+  candidatePairs: Array<CandidatePairStats>;
+}
+
+// An ICE candidate pair being checked or used to send media.
+export interface CandidatePairStats {
+  localCandidateType: CandidateType;
+  // Undefined if not known.
+  localProtocol: 'udp' | 'tcp' | 'tls' | undefined;
+  // How the local relay candidate reaches the TURN server; undefined unless
+  // localCandidateType is Relay.
+  localRelayProtocol: 'udp' | 'tcp' | 'tls' | undefined;
+  remoteCandidateType: CandidateType;
+  state: 'frozen' | 'waiting' | 'in-progress' | 'failed' | 'succeeded';
+  nominated: boolean;
+  // Whether media is being sent over this pair.
+  selected: boolean;
+  // Zero if not measured yet.
+  roundTripTimeMillis: number;
+  // Totals for the life of the pair.
+  bytesSent: number;
+  bytesReceived: number;
+}
+
+// How far apart the audio and video from a single remote sender are being
+// played out. The skew is how far video trails audio; negative if video is
+// ahead.
+export interface AvSyncStats {
+  audioSsrc: number;
+  videoSsrc: number;
+  skewMillis: number;
+}
+
+// How a glare was resolved, where both sides called each other at the same time.
+export interface GlareResolution {
+  // The call that was active locally when the remote's offer arrived.
+  localCallId: CallId;
+  // The call offered by the remote.
+  remoteCallId: CallId;
+  // The call that continues, or null if both calls were ended.
+  winningCallId: CallId | null;
+  // True if the local outgoing call was ended and the remote's call continues
+  // in its place as a regular incoming call.
+  convertedToIncoming: boolean;
+}
 
-// Represents the connection state to a media server for a group call.
-export enum ConnectionState {
-  NotConnected = 0,
-  Connecting,
-  Connected,
-  Reconnecting,
-}
-
-// Represents whether or not a user is joined to a group call and can exchange media.
-export enum JoinState {
-  NotJoined = 0,
-  Joining,
-  Pending,
-  Joined,
-}
-
-// If not ended purposely by the user, gives the reason why a group call ended.
-export enum GroupCallEndReason {
-  // Normal events
-  DeviceExplicitlyDisconnected = 0,
-  ServerExplicitlyDisconnected,
-  DeniedRequestToJoinCall,
-  RemovedFromCall,
-
-  // Things that can go wrong
-  CallManagerIsBusy,
-  SfuClientFailedToJoin,
-  FailedToCreatePeerConnectionFactory,
-  FailedToNegotiateSrtpKeys,
-  FailedToCreatePeerConnection,
-  FailedToStartPeerConnection,
-  FailedToUpdatePeerConnection,
-  FailedToSetMaxSendBitrate,
-  IceFailedWhileConnecting,
-  IceFailedAfterConnected,
-  ServerChangedDemuxId,
-  HasMaxDevices,
-}
-
-// Matches SpeechEvent in rust.
-export enum SpeechEvent {
-  // User was speaking but stopped.
-  StoppedSpeaking = 0,
-  // User has been speaking for a while -- maybe lower hand?
-  LowerHandSuggestion,
-}
-
-export enum CallMessageUrgency {
-  Droppable = 0,
-  HandleImmediately,
-}
-
-export enum RingUpdate {
-  /// The sender is trying to ring this user.
-  Requested = 0,
-  /// The sender tried to ring this user, but it's been too long.
-  ExpiredRequest,
-  /// Call was accepted elsewhere by a different device.
-  AcceptedOnAnotherDevice,
-  /// Call was declined elsewhere by a different device.
-  DeclinedOnAnotherDevice,
-  /// This device is currently on a different call.
-  BusyLocally,
-  /// A different device is currently on a different call.
-  BusyOnAnotherDevice,
-  /// The sender cancelled the ring request.
-  CancelledByRinger,
-}
-
-// HTTP request methods.
-export enum HttpMethod {
-  Get = 0,
-  Put = 1,
-  Post = 2,
-  Delete = 3,
+// Sent by another device of the local user that wants this one to take over
+// its accepted call, using takeOverCall().
+export interface HandoffRequest {
+  // The service ID of the other party of the call.
+  remoteUserId: Buffer;
+  callId: CallId;
+  // The device the other party is using for the call.
+  remoteDeviceId: DeviceId;
+  isVideoCall: boolean;
 }
 
 // The local device state for a group call.
@@ -2325,6 +3531,7 @@ export class LocalDeviceState {
 export class RemoteDeviceState {
   demuxId: number; // UInt32
   userId: Buffer;
+  participantKind: ParticipantKind;
   mediaKeysReceived: boolean;
   audioMuted: boolean | undefined;
   videoMuted: boolean | undefined;
@@ -2336,6 +3543,7 @@ export class RemoteDeviceState {
   speakerTime: string; // unix millis; 0 if they've never spoken (to be converted to a numeric type)
   forwardingVideo: boolean | undefined;
   isHigherResolutionPending: boolean;
+  connectionQuality: ConnectionQuality | undefined;
 
   constructor(
     demuxId: number,
@@ -2346,6 +3554,7 @@ export class RemoteDeviceState {
   ) {
     this.demuxId = demuxId;
     this.userId = userId;
+    this.participantKind = ParticipantKind.AppUser;
     this.mediaKeysReceived = mediaKeysReceived;
     this.audioLevel = 0;
     this.addedTime = addedTime;
@@ -2400,7 +3609,48 @@ export interface GroupCallObserver {
   onLowBandwidthForVideo(groupCall: GroupCall, recovered: boolean): void;
   onReactions(groupCall: GroupCall, reactions: Array<Reaction>): void;
   onRaisedHands(groupCall: GroupCall, raisedHands: Array<number>): void;
+  // Data another device's application sent with sendAppData.
+  onAppData(groupCall: GroupCall, demuxId: number, data: Buffer): void;
+  onRemoteConnectionQualities(groupCall: GroupCall): void;
+  onAvSyncSkew(groupCall: GroupCall, skews: Array<RemoteAvSyncSkew>): void;
+  // Every minute in which any frames were received.
+  onE2eeHealth(groupCall: GroupCall, health: E2eeHealth): void;
+  // Outgoing video started or stopped being scaled down; undefined once it
+  // stops.
+  onVideoAdaptationChanged(
+    groupCall: GroupCall,
+    reason: QualityLimitationReason | undefined
+  ): void;
+  onCallSummary(
+    groupCall: GroupCall,
+    summary: CallSummary,
+    reason: GroupCallEndReason
+  ): void;
   onPeekChanged(groupCall: GroupCall): void;
+  // Another device on the same account joined after the local device did.
+  // If setLeaveWhenOwnDeviceJoins was enabled, the local device leaves right after.
+  onOwnDeviceJoined(groupCall: GroupCall, demuxId: number): void;
+  // The ICE servers passed to setIceServers expire soon; fetch fresh ones and
+  // pass them to setIceServers again.
+  requestIceServers(groupCall: GroupCall): void;
+  // Fresh ICE servers couldn't be applied, or the current ones expired without
+  // being refreshed. The call goes on, but may not reconnect through a relay.
+  onIceServersRefreshFailed(groupCall: GroupCall): void;
+  // The local device was moved into a breakout group, or back to the main call
+  // if breakoutGroupId is undefined. Only devices in the same group can see each
+  // other's media.
+  onBreakoutGroupChanged(
+    groupCall: GroupCall,
+    breakoutGroupId: number | undefined
+  ): void;
+  // Joining failed because the call has reached its maximum number of devices.
+  // Followed by onEnded with GroupCallEndReason.HasMaxDevices.
+  // Either count is undefined if it isn't known.
+  onCallFull(
+    groupCall: GroupCall,
+    maxDevices: number | undefined,
+    deviceCount: number | undefined
+  ): void;
   onEnded(groupCall: GroupCall, reason: GroupCallEndReason): void;
   onSpeechEvent(groupCall: GroupCall, event: SpeechEvent): void;
 }
@@ -2502,6 +3752,76 @@ export class GroupCall {
     this._callManager.groupRaiseHand(this._clientId, raise);
   }
 
+  // Called by UI
+  // Sends the data to all other devices in the call. It isn't resent, so it
+  // may be lost. Data larger than MAX_APP_DATA_LENGTH is dropped.
+  sendAppData(data: Buffer): void {
+    this._callManager.groupSendAppData(this._clientId, data);
+  }
+
+  // Called by UI
+  lowerAllHands(): void {
+    this._callManager.groupLowerAllHands(this._clientId);
+  }
+
+  // Called by UI
+  // Only honored for admins. Devices assigned to breakout group 0 are moved back
+  // to the main call.
+  assignBreakoutGroups(
+    assignments: Array<{ demuxId: number; breakoutGroupId: number }>
+  ): void {
+    this._callManager.groupAssignBreakoutGroups(
+      this._clientId,
+      assignments.map(assignment => assignment.demuxId),
+      assignments.map(assignment => assignment.breakoutGroupId)
+    );
+  }
+
+  // Called by UI
+  // Only honored for admins.
+  endBreakoutGroups(): void {
+    this._callManager.groupEndBreakoutGroups(this._clientId);
+  }
+
+  // Called by UI
+  setLowerHandWhenSpeaking(enabled: boolean): void {
+    this._callManager.setLowerHandWhenSpeaking(this._clientId, enabled);
+  }
+
+  // Called by UI
+  // When enabled, SpeechEvent.SpeakingWhileMuted is reported if the user appears
+  // to be talking while muted. This requires the microphone to keep capturing.
+  setDetectSpeakingWhileMuted(enabled: boolean): void {
+    this._callManager.setDetectSpeakingWhileMuted(this._clientId, enabled);
+  }
+
+  // Called by UI
+  // When enabled, media keys aren't sent to users who are only in the call
+  // through a bridge (ParticipantKind.Bridged).
+  setSkipMediaKeysForBridgedParticipants(enabled: boolean): void {
+    this._callManager.setSkipMediaKeysForBridgedParticipants(
+      this._clientId,
+      enabled
+    );
+  }
+
+  // Called by UI
+  // When enabled, the call is left automatically if another device on the same
+  // account joins, e.g. to move the call to that device.
+  setLeaveWhenOwnDeviceJoins(enabled: boolean): void {
+    this._callManager.setLeaveWhenOwnDeviceJoins(this._clientId, enabled);
+  }
+
+  // Called by UI
+  // The delay is ignored for KeyRotationPolicy.Immediate.
+  setKeyRotationPolicy(policy: KeyRotationPolicy, delayMillis = 0): void {
+    this._callManager.setKeyRotationPolicy(
+      this._clientId,
+      policy,
+      delayMillis
+    );
+  }
+
   // Called by UI
   setOutgoingVideoMuted(muted: boolean): void {
     this._localDeviceState.videoMuted = muted;
@@ -2517,11 +3837,15 @@ export class GroupCall {
   }
 
   // Called by UI
-  setOutgoingVideoIsScreenShare(isScreenShare: boolean): void {
+  setOutgoingVideoIsScreenShare(
+    isScreenShare: boolean,
+    contentHint?: VideoContentHint
+  ): void {
     this._localDeviceState.sharingScreen = isScreenShare;
     this._callManager.setOutgoingGroupCallVideoIsScreenShare(
       this._clientId,
-      isScreenShare
+      isScreenShare,
+      contentHint
     );
     this._observer.onLocalDeviceStateChanged(this);
   }
@@ -2541,6 +3865,57 @@ export class GroupCall {
     this._callManager.setDataMode(this._clientId, dataMode);
   }
 
+  // Called by UI
+  // Changes how often onAudioLevels is called, or stops it with 0.
+  setAudioLevelsInterval(intervalMillis: number): void {
+    this._callManager.setGroupCallAudioLevelsInterval(
+      this._clientId,
+      intervalMillis
+    );
+  }
+
+  // Called by UI
+  // Returns the stats from the most recent stats report, or undefined if the
+  // call has not been joined long enough to have any.
+  getStats(): CallStats | undefined {
+    return this._callManager.getGroupCallStats(this._clientId);
+  }
+
+  // Called by UI
+  // Returns every ICE candidate pair to the SFU from the most recent stats
+  // report, or an empty array if there is no report yet.
+  getCandidatePairs(): Array<CandidatePairStats> {
+    return this._callManager.getGroupCallCandidatePairs(this._clientId);
+  }
+
+  // Called by UI
+  // Caps the outgoing camera video, or screen share if isScreenShare is true.
+  // The limits stay in effect until changed.
+  setOutgoingVideoLimits(limits: VideoLimits, isScreenShare = false): void {
+    this._callManager.setOutgoingVideoLimits(
+      isScreenShare,
+      limits.maxWidth ?? 0,
+      limits.maxHeight ?? 0,
+      limits.maxFramerate ?? 0
+    );
+  }
+
+  // Called by UI
+  setAudioProfile(audioProfile: AudioProfile): void {
+    this._callManager.setGroupCallAudioProfile(this._clientId, audioProfile);
+  }
+
+  // Called by UI
+  // Changes how outgoing audio is protected against packet loss.
+  setAudioLossProtection(protection: AudioLossProtection): void {
+    this._callManager.setGroupCallAudioLossProtection(
+      this._clientId,
+      protection.fec,
+      protection.enableLossPercent ?? DEFAULT_ENABLE_LOSS_PERCENT,
+      protection.disableLossPercent ?? DEFAULT_DISABLE_LOSS_PERCENT
+    );
+  }
+
   // Called by UI
   requestVideo(
     resolutions: Array<VideoRequest>,
@@ -2553,6 +3928,18 @@ export class GroupCall {
     );
   }
 
+  // Called by UI
+  // Stops receiving and rendering video from the given devices (e.g. when the
+  // app is in the background) until it's resumed.
+  setRenderingPaused(demuxIds: Array<number>, paused: boolean): void {
+    this._callManager.setRenderingPaused(this._clientId, demuxIds, paused);
+  }
+
+  // Called by UI
+  setAllRenderingPaused(paused: boolean): void {
+    this._callManager.setAllRenderingPaused(this._clientId, paused);
+  }
+
   // Called by UI
   approveUser(otherUserId: Buffer): void {
     this._callManager.approveUser(this._clientId, otherUserId);
@@ -2653,16 +4040,52 @@ export class GroupCall {
     this._observer.onRaisedHands(this, raisedHands);
   }
 
+  handleAppData(demuxId: number, data: Buffer): void {
+    this._observer.onAppData(this, demuxId, data);
+  }
+
+  handleRemoteConnectionQualities(
+    qualities: Array<RemoteConnectionQuality>
+  ): void {
+    if (this._remoteDeviceStates != undefined) {
+      for (const received of qualities) {
+        for (const remoteDeviceState of this._remoteDeviceStates) {
+          if (remoteDeviceState.demuxId == received.demuxId) {
+            remoteDeviceState.connectionQuality = received.quality;
+          }
+        }
+      }
+    }
+
+    this._observer.onRemoteConnectionQualities(this);
+  }
+
+  handleAvSyncSkew(skews: Array<RemoteAvSyncSkew>): void {
+    this._observer.onAvSyncSkew(this, skews);
+  }
+
+  handleVideoAdaptationChanged(
+    reason: QualityLimitationReason | undefined
+  ): void {
+    this._observer.onVideoAdaptationChanged(this, reason);
+  }
+
+  handleE2eeHealth(health: E2eeHealth): void {
+    this._observer.onE2eeHealth(this, health);
+  }
+
   // Called by Rust via RingRTC object
   handleRemoteDevicesChanged(
     remoteDeviceStates: Array<RemoteDeviceState>
   ): void {
-    // We don't get aspect ratios from RingRTC, so make sure to copy them over.
+    // We don't get aspect ratios or connection qualities along with the device
+    // states from RingRTC, so make sure to copy them over.
     for (const noo of remoteDeviceStates) {
       const old = this._remoteDeviceStates?.find(
         old => old.demuxId == noo.demuxId
       );
       noo.videoAspectRatio = old?.videoAspectRatio;
+      noo.connectionQuality = old?.connectionQuality;
     }
 
     this._remoteDeviceStates = remoteDeviceStates;
@@ -2677,6 +4100,39 @@ export class GroupCall {
     this._observer.onPeekChanged(this);
   }
 
+  // Called by Rust via RingRTC object
+  handleOwnDeviceJoined(demuxId: number): void {
+    this._observer.onOwnDeviceJoined(this, demuxId);
+  }
+
+  // Called by Rust via RingRTC object
+  requestIceServers(): void {
+    this._observer.requestIceServers(this);
+  }
+
+  // Called by Rust via RingRTC object
+  handleIceServersRefreshFailed(): void {
+    this._observer.onIceServersRefreshFailed(this);
+  }
+
+  // Called by Rust via RingRTC object
+  handleBreakoutGroupChanged(breakoutGroupId: number | undefined): void {
+    this._observer.onBreakoutGroupChanged(this, breakoutGroupId);
+  }
+
+  // Called by Rust via RingRTC object
+  handleCallFull(
+    maxDevices: number | undefined,
+    deviceCount: number | undefined
+  ): void {
+    this._observer.onCallFull(this, maxDevices, deviceCount);
+  }
+
+  // Called by Rust via RingRTC object
+  handleCallSummary(summary: CallSummary, reason: GroupCallEndReason): void {
+    this._observer.onCallSummary(this, summary, reason);
+  }
+
   // Called by Rust via RingRTC object
   handleEnded(reason: GroupCallEndReason): void {
     this._callManager.deleteGroupCallClient(this._clientId);
@@ -2689,10 +4145,40 @@ export class GroupCall {
     width: number,
     height: number,
     format: VideoPixelFormatEnum,
-    buffer: Buffer
+    buffer: Buffer,
+    rotation?: VideoRotation
   ): void {
     // This assumes we only have one active call.
-    this._callManager.sendVideoFrame(width, height, format, buffer);
+    this._callManager.sendVideoFrame(width, height, format, buffer, rotation);
+  }
+
+  // Called by UI
+  // Queues interleaved 16-bit PCM to be sent with (or instead of) the
+  // microphone. Any sample rate is accepted and resampled as needed.
+  sendAudioSamples(
+    samples: Int16Array,
+    sampleRateHz: number,
+    channels: number
+  ): void {
+    this._callManager.sendAudioSamples(samples, sampleRateHz, channels);
+  }
+
+  // Called by UI
+  setAudioInjectionMode(mode: AudioInjectionMode): void {
+    this._callManager.setAudioInjectionMode(mode);
+  }
+
+  // Called by UI
+  // Drops any queued audio that hasn't been sent yet.
+  clearInjectedAudio(): void {
+    this._callManager.clearInjectedAudio();
+  }
+
+  // Called by UI
+  // Sends the audio the system is playing along with the microphone, such as
+  // while sharing the screen. Only supported on Windows.
+  shareSystemAudio(enabled: boolean): void {
+    this._callManager.shareSystemAudio(enabled);
   }
 
   // With this, a GroupCall can provide a VideoFrameSource for each remote device.
@@ -2719,6 +4205,17 @@ export class GroupCall {
     this._callManager.setRtcStatsInterval(this._clientId, intervalMillis);
   }
 
+  // Called by UI
+  // Switches to gathering relay candidates from iceServers without reconnecting.
+  // If expiresInMillis is given, requestIceServers is called shortly before then.
+  setIceServers(iceServers: Array<IceServer>, expiresInMillis?: number): void {
+    this._callManager.setGroupCallIceServers(
+      this._clientId,
+      iceServers,
+      expiresInMillis
+    );
+  }
+
   handleSpeechEvent(event: SpeechEvent): void {
     this._observer.onSpeechEvent(this, event);
   }
@@ -2743,19 +4240,28 @@ class GroupCallVideoFrameSource {
   receiveVideoFrame(
     buffer: Buffer,
     maxWidth: number,
-    maxHeight: number
-  ): [number, number] | undefined {
+    maxHeight: number,
+    applyRotation?: boolean
+  ): [number, number, VideoRotation] | undefined {
     // This assumes we only have one active call.
     const frame = this._callManager.receiveGroupCallVideoFrame(
       this._groupCall.clientId,
       this._remoteDemuxId,
       buffer,
       maxWidth,
-      maxHeight
+      maxHeight,
+      applyRotation
     );
     if (frame) {
-      const [width, height] = frame;
-      this._groupCall.setRemoteAspectRatio(this._remoteDemuxId, width / height);
+      const [width, height, rotation] = frame;
+      // The aspect ratio is of the frame as displayed, after any rotation.
+      const sideways =
+        rotation === VideoRotation.Clockwise90 ||
+        rotation === VideoRotation.Clockwise270;
+      this._groupCall.setRemoteAspectRatio(
+        this._remoteDemuxId,
+        sideways ? height / width : width / height
+      );
     }
     return frame;
   }
@@ -2865,6 +4371,23 @@ export enum HangupType {
   Declined = 2,
   Busy = 3,
   NeedPermission = 4,
+  DeclinedBusy = 5,
+  DeclinedDoNotDisturb = 6,
+  DeclinedTextReplySent = 7,
+  HandedOff = 8,
+  NetworkFailure = 9,
+  DeclinedByPolicy = 10,
+  RingTimeout = 11,
+}
+
+// Why the callee declined a call.
+export enum DeclineReason {
+  Busy = 0,
+  DoNotDisturb = 1,
+  TextReplySent = 2,
+  // Declined by the application, such as for a blocked contact, rather than
+  // by the user.
+  Policy = 3,
 }
 
 export enum DataMode {
@@ -2872,32 +4395,168 @@ export enum DataMode {
   Normal = 1,
 }
 
-/// Describes why a ring was cancelled.
-export enum RingCancelReason {
-  /// The user explicitly clicked "Decline".
-  DeclinedByUser = 0,
-  /// The device is busy with another call.
-  Busy,
+// What the outgoing audio is tuned for. Music sends full-band audio at a
+// higher bitrate.
+export enum AudioProfile {
+  Voice = 0,
+  Music = 1,
+}
+
+// How audio passed to sendAudioSamples is combined with the microphone.
+// Replace sends silence whenever nothing is queued.
+export enum AudioInjectionMode {
+  Mix = 0,
+  Replace = 1,
+}
+
+// Whether a kind of audio loss protection is used. Auto turns it on while
+// the receiver reports enough packet loss.
+export enum AudioLossProtectionMode {
+  Off = 0,
+  On = 1,
+  Auto = 2,
+}
+
+// How outgoing audio is protected against packet loss, with Opus FEC, which
+// recovers isolated losses. In Auto mode, protection turns on at
+// enableLossPercent (default 10) and off below disableLossPercent (default 5).
+export interface AudioLossProtection {
+  fec: AudioLossProtectionMode;
+  enableLossPercent?: number;
+  disableLossPercent?: number;
+}
+
+// How long received audio is held to smooth out jitter. A shorter
+// delay suits interactive use, while a longer one rides out poor networks.
+// Leave a field out for WebRTC's adaptive default.
+export interface JitterBufferConfig {
+  targetDelayMillis?: number;
+  maxDelayMillis?: number;
+}
+
+export const LOW_LATENCY_JITTER_BUFFER: JitterBufferConfig = {
+  maxDelayMillis: 150,
+};
+
+export const SMOOTH_JITTER_BUFFER: JitterBufferConfig = {
+  targetDelayMillis: 250,
+};
+
+// Caps on outgoing video, for low-end devices or to save data. They can only
+// lower what a call would send anyway. Leave a field out for no limit.
+export interface VideoLimits {
+  maxWidth?: number;
+  maxHeight?: number;
+  maxFramerate?: number;
+}
+
+// What the outgoing video contains, so the encoder knows what to preserve.
+// Detailed and Text are screen content, which keeps its resolution at the
+// cost of framerate; Text is also captured at a lower framerate.
+export enum VideoContentHint {
+  None = 0,
+  Fluid = 1,
+  Detailed = 2,
+  Text = 3,
+}
+
+// Explicit caps on the bitrates sent in a direct call. Unset means no cap.
+export interface CallBitrateConfig {
+  maxAudioBitrateBps?: number;
+  // The max bitrate for video, on top of the audio.
+  maxVideoBitrateBps?: number;
+  maxTotalBitrateBps?: number;
+}
+
+// Local overrides of the max send bitrates of a direct call. Unset leaves the
+// bitrate to the data mode.
+export interface MaxSendBitrate {
+  audioBps?: number;
+  // The max bitrates for video, on top of the audio.
+  cameraVideoBps?: number;
+  screenShareBps?: number;
+}
+
+/// Controls how soon the media send key is rotated after someone leaves a group call.
+export enum KeyRotationPolicy {
+  // Rotate as soon as someone leaves.
+  Immediate = 0,
+  // Rotate once no one else has left for the given delay.
+  Debounced = 1,
+  // Rotate the given delay after the first departure, covering everyone who
+  // left in the meantime.
+  Batched = 2,
 }
 
 export interface CallManager {
   setConfig(config: Config): void;
   setSelfUuid(uuid: Buffer): void;
+  setMaxLogLevel(level: CallLogLevel): void;
+  setModuleLogLevel(module: string, level: CallLogLevel): void;
+  clearModuleLogLevels(): void;
+  exportDiagnostics(): Buffer;
+  setCrashDumpPath(path: string): void;
+  getSetupLatencyHistograms(reset: boolean): SetupLatencyHistograms;
+  setGroupCallIntervals(
+    heartbeatMillis: number,
+    statsMillis: number,
+    peekPollMillis: number
+  ): void;
   createOutgoingCall(
     remoteUserId: UserId,
     isVideoCall: boolean,
-    localDeviceId: DeviceId
+    localDeviceId: DeviceId,
+    appData: Buffer
   ): CallId;
+  takeOverCall(
+    remoteUserId: UserId,
+    callId: CallId,
+    isVideoCall: boolean,
+    localDeviceId: DeviceId
+  ): void;
+  preGatherIce(callId: CallId, iceServers: Array<IceServer>): void;
   proceed(
     callId: CallId,
     iceServers: Array<IceServer>,
     hideIp: boolean,
     dataMode: DataMode,
-    audioLevelsIntervalMillis: number
+    audioLevelsIntervalMillis: number,
+    ringTimeoutMillis: number,
+    connectTimeoutMillis: number,
+    earlyMedia: boolean,
+    mediaStallTimeoutMillis: number,
+    hangUpOnMediaStall: boolean,
+    audioProfile: AudioProfile,
+    jitterBufferTargetDelayMillis: number,
+    jitterBufferMaxDelayMillis: number
+  ): void;
+  accept(callId: CallId, audioMuted: boolean, videoDisabled: boolean): void;
+  setOnHold(callId: CallId, onHold: boolean): void;
+  handOff(
+    callId: CallId,
+    remoteUserId: Buffer,
+    targetDeviceId: DeviceId
   ): void;
-  accept(callId: CallId): void;
+  restartIce(callId: CallId): void;
+  startEarlyMedia(callId: CallId): void;
+  enableVideo(callId: CallId): void;
+  disableVideo(callId: CallId): void;
+  respondToVideoRequest(callId: CallId, accepted: boolean): void;
+  sendAppData(callId: CallId, data: Buffer, reliable: boolean): void;
+  getVerificationCode(callId: CallId): string | null;
+  getWideCallId(callId: CallId): string | null;
+  setCallAppData(callId: CallId, appData: Buffer): void;
+  setRestartIceOnDisconnect(enabled: boolean): void;
+  setRelayOnlyByDefault(enabled: boolean): void;
+  setMaxSignalingMessageSize(maxSize: number): void;
   ignore(callId: CallId): void;
   hangup(): void;
+  decline(callId: CallId, reason: DeclineReason): void;
+  setCallWaitingEnabled(enabled: boolean): void;
+  holdAndAcceptWaitingCall(): void;
+  declineWaitingCall(): void;
+  resumeHeldCall(): void;
+  hangupHeldCall(): void;
   cancelGroupRing(
     groupId: GroupId,
     ringId: string,
@@ -2907,19 +4566,58 @@ export interface CallManager {
   signalingMessageSendFailed(callId: CallId): void;
   setOutgoingAudioEnabled(enabled: boolean): void;
   setOutgoingVideoEnabled(enabled: boolean): void;
-  setOutgoingVideoIsScreenShare(enabled: boolean): void;
+  setOutgoingVideoIsScreenShare(
+    enabled: boolean,
+    contentHint: VideoContentHint | undefined
+  ): void;
   updateDataMode(dataMode: DataMode): void;
+  updateAudioProfile(audioProfile: AudioProfile): void;
+  setAudioLossProtection(
+    fec: AudioLossProtectionMode,
+    enableLossPercent: number,
+    disableLossPercent: number
+  ): void;
+  setAudioLevelsInterval(intervalMillis: number): void;
+  setOutgoingVideoLimits(
+    isScreenShare: boolean,
+    maxWidth: number,
+    maxHeight: number,
+    maxFramerate: number
+  ): void;
+  getStats(): CallStats | undefined;
+  getCandidatePairs(): Array<CandidatePairStats>;
+  updateBitrateConfig(
+    maxAudioBitrateBps: number,
+    maxVideoBitrateBps: number,
+    maxTotalBitrateBps: number
+  ): void;
+  setMaxSendBitrate(
+    callId: CallId,
+    audioBps: number,
+    cameraVideoBps: number,
+    screenShareBps: number
+  ): void;
   sendVideoFrame(
     width: number,
     height: number,
     format: VideoPixelFormatEnum,
-    buffer: Buffer
+    buffer: Buffer,
+    rotation?: VideoRotation
+  ): void;
+  sendAudioSamples(
+    samples: Int16Array,
+    sampleRateHz: number,
+    channels: number
   ): void;
+  setAudioInjectionMode(mode: AudioInjectionMode): void;
+  clearInjectedAudio(): void;
+  shareSystemAudio(enabled: boolean): void;
   receiveVideoFrame(
     buffer: Buffer,
     maxWidth: number,
-    maxHeight: number
-  ): [number, number] | undefined;
+    maxHeight: number,
+    applyRotation?: boolean
+  ): [number, number, VideoRotation] | undefined;
   receivedOffer(
     remoteUserId: UserId,
     remoteDeviceId: DeviceId,
@@ -2974,7 +4672,8 @@ export interface CallManager {
     groupId: Buffer,
     sfuUrl: string,
     hkdfExtraInfo: Buffer,
-    audioLevelsIntervalMillis: number
+    audioLevelsIntervalMillis: number,
+    iceServers: Array<IceServer>
   ): GroupCallClientId;
   createCallLinkCallClient(
     sfuUrl: string,
@@ -2982,7 +4681,8 @@ export interface CallManager {
     linkRootKey: Buffer,
     adminPasskey: Buffer | undefined,
     hkdfExtraInfo: Buffer,
-    audioLevelsIntervalMillis: number
+    audioLevelsIntervalMillis: number,
+    iceServers: Array<IceServer>
   ): GroupCallClientId;
   deleteGroupCallClient(clientId: GroupCallClientId): void;
   connect(clientId: GroupCallClientId): void;
@@ -2994,18 +4694,69 @@ export interface CallManager {
   setPresenting(clientId: GroupCallClientId, presenting: boolean): void;
   setOutgoingGroupCallVideoIsScreenShare(
     clientId: GroupCallClientId,
-    isScreenShare: boolean
+    isScreenShare: boolean,
+    contentHint: VideoContentHint | undefined
   ): void;
   groupRing(clientId: GroupCallClientId, recipient: Buffer | undefined): void;
   groupReact(clientId: GroupCallClientId, value: string): void;
   groupRaiseHand(clientId: GroupCallClientId, raise: boolean): void;
+  groupSendAppData(clientId: GroupCallClientId, data: Buffer): void;
+  groupLowerAllHands(clientId: GroupCallClientId): void;
+  groupAssignBreakoutGroups(
+    clientId: GroupCallClientId,
+    demuxIds: Array<number>,
+    breakoutGroupIds: Array<number>
+  ): void;
+  groupEndBreakoutGroups(clientId: GroupCallClientId): void;
+  setLowerHandWhenSpeaking(clientId: GroupCallClientId, enabled: boolean): void;
+  setDetectSpeakingWhileMuted(
+    clientId: GroupCallClientId,
+    enabled: boolean
+  ): void;
+  setSkipMediaKeysForBridgedParticipants(
+    clientId: GroupCallClientId,
+    enabled: boolean
+  ): void;
+  setLeaveWhenOwnDeviceJoins(
+    clientId: GroupCallClientId,
+    enabled: boolean
+  ): void;
+  setKeyRotationPolicy(
+    clientId: GroupCallClientId,
+    policy: KeyRotationPolicy,
+    delayMillis: number
+  ): void;
   resendMediaKeys(clientId: GroupCallClientId): void;
   setDataMode(clientId: GroupCallClientId, dataMode: DataMode): void;
+  setGroupCallAudioProfile(
+    clientId: GroupCallClientId,
+    audioProfile: AudioProfile
+  ): void;
+  setGroupCallAudioLossProtection(
+    clientId: GroupCallClientId,
+    fec: AudioLossProtectionMode,
+    enableLossPercent: number,
+    disableLossPercent: number
+  ): void;
+  setGroupCallAudioLevelsInterval(
+    clientId: GroupCallClientId,
+    intervalMillis: number
+  ): void;
+  getGroupCallStats(clientId: GroupCallClientId): CallStats | undefined;
+  getGroupCallCandidatePairs(
+    clientId: GroupCallClientId
+  ): Array<CandidatePairStats>;
   requestVideo(
     clientId: GroupCallClientId,
     resolutions: Array<VideoRequest>,
     activeSpeakerHeight: number
   ): void;
+  setRenderingPaused(
+    clientId: GroupCallClientId,
+    demuxIds: Array<number>,
+    paused: boolean
+  ): void;
+  setAllRenderingPaused(clientId: GroupCallClientId, paused: boolean): void;
   approveUser(clientId: GroupCallClientId, otherUserId: Buffer): void;
   denyUser(clientId: GroupCallClientId, otherUserId: Buffer): void;
   removeClient(clientId: GroupCallClientId, otherClientDemuxId: number): void;
@@ -3021,12 +4772,18 @@ export interface CallManager {
     remoteDemuxId: number,
     buffer: Buffer,
     maxWidth: number,
-    maxHeight: number
-  ): [number, number] | undefined;
+    maxHeight: number,
+    applyRotation?: boolean
+  ): [number, number, VideoRotation] | undefined;
   setRtcStatsInterval(
     clientId: GroupCallClientId,
     intervalMillis: number
   ): void;
+  setGroupCallIceServers(
+    clientId: GroupCallClientId,
+    iceServers: Array<IceServer>,
+    expiresInMillis: number | undefined
+  ): void;
   // Responses come back via handleCallLinkResponse
   readCallLink(
     requestId: number,
@@ -3060,6 +4817,21 @@ export interface CallManager {
     linkRootKey: Buffer,
     adminPasskey: Buffer
   ): void;
+  rotateCallLinkAdminPasskey(
+    requestId: number,
+    sfuUrl: string,
+    authCredentialPresentation: Buffer,
+    linkRootKey: Buffer,
+    adminPasskey: Buffer,
+    newAdminPasskey: Buffer
+  ): void;
+  migrateCallLink(
+    requestId: number,
+    sfuUrl: string,
+    authCredentialPresentation: Buffer,
+    linkRootKey: Buffer,
+    adminPasskey: Buffer
+  ): void;
   // Response comes back via handlePeekResponse
   peekGroupCall(
     requestId: number,
@@ -3077,8 +4849,10 @@ export interface CallManager {
 
   getAudioInputs(): Array<AudioDevice>;
   setAudioInput(index: number): void;
+  setAudioInputById(uniqueId: string): boolean;
   getAudioOutputs(): Array<AudioDevice>;
   setAudioOutput(index: number): void;
+  setAudioOutputById(uniqueId: string): boolean;
 }
 
 export interface CallManagerCallbacks {
@@ -3088,16 +4862,48 @@ export interface CallManagerCallbacks {
     callId: CallId,
     isVideoCall: boolean
   ): void;
+  onIncomingCallWaiting(remoteUserId: UserId, callId: CallId): void;
+  onGlareResolved(
+    remoteUserId: UserId,
+    localCallId: CallId,
+    remoteCallId: CallId,
+    winningCallId: CallId | null,
+    convertedToIncoming: boolean
+  ): void;
+  onHandoffRequested(
+    remoteUserId: Buffer,
+    callId: CallId,
+    remoteDeviceId: DeviceId,
+    isVideoCall: boolean
+  ): void;
   onCallState(remoteUserId: UserId, state: CallState): void;
   onCallEnded(
     remoteUserId: UserId,
     callId: CallId,
     endedReason: CallEndedReason,
-    ageSec: number
+    ageSec: number,
+    appData: Buffer
+  ): void;
+  onCallSummary(
+    remoteUserId: UserId,
+    callId: CallId,
+    summary: CallSummary,
+    endedReason: CallEndedReason,
+    appData: Buffer
   ): void;
   onRemoteAudioEnabled(remoteUserId: UserId, enabled: boolean): void;
   onRemoteVideoEnabled(remoteUserId: UserId, enabled: boolean): void;
   onRemoteSharingScreen(remoteUserId: UserId, enabled: boolean): void;
+  onRemoteHoldChanged(remoteUserId: UserId, onHold: boolean): void;
+  onRemoteHandedOff(remoteUserId: UserId): void;
+  onRemoteEarlyMedia(remoteUserId: UserId): void;
+  onVideoNegotiation(remoteUserId: UserId, event: VideoNegotiationEvent): void;
+  onMediaStalled(remoteUserId: UserId): void;
+  onAppData(remoteUserId: UserId, data: Buffer): void;
+  onVideoAdaptationChanged(
+    remoteUserId: UserId,
+    reason: QualityLimitationReason | undefined
+  ): void;
   onSendOffer(
     remoteUserId: UserId,
     remoteDeviceId: DeviceId,
@@ -3176,8 +4982,30 @@ export interface CallManagerCallbacks {
     statusCode: number,
     rawInfo: RawPeekInfo | undefined
   ): void;
+  handleOwnDeviceJoined(clientId: GroupCallClientId, demuxId: number): void;
+  requestIceServers(clientId: GroupCallClientId): void;
+  handleIceServersRefreshFailed(clientId: GroupCallClientId): void;
+  handleBreakoutGroupChanged(
+    clientId: GroupCallClientId,
+    breakoutGroupId: number | undefined
+  ): void;
+  handleCallFull(
+    clientId: GroupCallClientId,
+    maxDevices: number | undefined,
+    deviceCount: number | undefined
+  ): void;
+  handleGroupCallSummary(
+    clientId: GroupCallClientId,
+    summary: CallSummary,
+    reason: GroupCallEndReason
+  ): void;
   handleEnded(clientId: GroupCallClientId, reason: GroupCallEndReason): void;
 
+  onAudioDevicesChanged(
+    inputs: Array<AudioDevice>,
+    outputs: Array<AudioDevice>
+  ): void;
+
   onLogMessage(
     level: number,
     fileName: string,
@@ -3194,27 +5022,6 @@ export enum CallState {
   Ended = 'ended',
 }
 
-export enum CallEndedReason {
-  LocalHangup = 'LocalHangup',
-  RemoteHangup = 'RemoteHangup',
-  RemoteHangupNeedPermission = 'RemoteHangupNeedPermission',
-  Declined = 'Declined',
-  Busy = 'Busy',
-  Glare = 'Glare',
-  ReCall = 'ReCall',
-  ReceivedOfferExpired = 'ReceivedOfferExpired',
-  ReceivedOfferWhileActive = 'ReceivedOfferWhileActive',
-  ReceivedOfferWithGlare = 'ReceivedOfferWithGlare',
-  SignalingFailure = 'SignalingFailure',
-  GlareFailure = 'GlareFailure',
-  ConnectionFailure = 'ConnectionFailure',
-  InternalFailure = 'InternalFailure',
-  Timeout = 'Timeout',
-  AcceptedOnAnotherDevice = 'AcceptedOnAnotherDevice',
-  DeclinedOnAnotherDevice = 'DeclinedOnAnotherDevice',
-  BusyOnAnotherDevice = 'BusyOnAnotherDevice',
-}
-
 export enum CallLogLevel {
   Off,
   Error,
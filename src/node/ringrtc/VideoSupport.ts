@@ -17,6 +17,14 @@ export enum VideoPixelFormatEnum {
   Rgba = 2,
 }
 
+// How far a sent frame must be rotated clockwise to be displayed upright.
+export enum VideoRotation {
+  None = 0,
+  Clockwise90 = 90,
+  Clockwise180 = 180,
+  Clockwise270 = 270,
+}
+
 // eslint-disable-next-line @typescript-eslint/no-unused-vars
 function videoPixelFormatFromEnum(
   format: VideoPixelFormatEnum
@@ -59,23 +67,32 @@ export interface VideoFrameSource {
    * but allow for rotation, i.e. a maximum of 1920x1080 will also allow
    * portrait-mode 1080x1920.
    *
-   * Returns a `[width, height]` pair for the resulting frame,
+   * Frames are rotated upright before being copied, unless `applyRotation`
+   * is false, in which case they are copied as received and it's up to the
+   * renderer to rotate them clockwise by the returned rotation.
+   *
+   * Returns a `[width, height, rotation]` triple for the resulting frame,
    * or `undefined` if there's no new frame ready to be displayed.
    */
   receiveVideoFrame(
     buffer: Buffer,
     maxWidth: number,
-    maxHeight: number
-  ): [number, number] | undefined;
+    maxHeight: number,
+    applyRotation?: boolean
+  ): [number, number, VideoRotation?] | undefined;
 }
 
 // Sends frames (after getting them from something like GumVideoCapturer, for example).
+//
+// Anything can send frames this way, not just a camera: a virtual camera or a
+// game capture, for example. Frames are treated as captured when sent.
 interface VideoFrameSender {
   sendVideoFrame(
     width: number,
     height: number,
     format: VideoPixelFormatEnum,
-    buffer: Buffer
+    buffer: Buffer,
+    rotation?: VideoRotation
   ): void;
 }
 
@@ -115,6 +132,7 @@ export class GumVideoCapturer {
   private spawnedSenderRunning = false;
   private preferredDeviceId?: string;
   private updateLocalPreviewIntervalId?: any;
+  private localPreviewMirrored = false;
 
   constructor(defaultCaptureOptions: GumVideoCaptureOptions) {
     this.defaultCaptureOptions = defaultCaptureOptions;
@@ -133,6 +151,7 @@ export class GumVideoCapturer {
     this.localPreview = localPreview;
 
     this.updateLocalPreviewSourceObject();
+    this.updateLocalPreviewMirroring();
 
     // This is a dumb hack around the fact that sometimes the
     // this.localPreview.current is updated without a call
@@ -147,6 +166,13 @@ export class GumVideoCapturer {
     );
   }
 
+  // Mirrors the local preview horizontally, as people expect of a self-view.
+  // Only the preview is mirrored; the video sent is left as captured.
+  setLocalPreviewMirrored(mirrored: boolean): void {
+    this.localPreviewMirrored = mirrored;
+    this.updateLocalPreviewMirroring();
+  }
+
   async enableCapture(): Promise<void> {
     return this.startCapturing(this.defaultCaptureOptions);
   }
@@ -434,6 +460,7 @@ export class GumVideoCapturer {
 
     if (mediaStream && this.captureOptions) {
       localPreview.srcObject = mediaStream;
+      this.updateLocalPreviewMirroring();
       if (localPreview.width === 0) {
         localPreview.width = this.captureOptions.maxWidth;
       }
@@ -444,6 +471,16 @@ export class GumVideoCapturer {
       localPreview.srcObject = null;
     }
   }
+
+  private updateLocalPreviewMirroring(): void {
+    const localPreview = this.localPreview?.current;
+    if (!localPreview) {
+      return;
+    }
+    localPreview.style.transform = this.localPreviewMirrored
+      ? 'scaleX(-1)'
+      : '';
+  }
 }
 
 // We add 10% in each dimension to allow for things that are slightly wider or taller than 1080p.
@@ -74,6 +74,11 @@ export class CallingClass {
     call.handleRemoteSharingScreen = () => {
       log('handleRemoteSharingScreen');
     };
+
+    // eslint-disable-next-line no-param-reassign
+    call.handleRemoteHoldChanged = () => {
+      log('handleRemoteHoldChanged');
+    };
   }
 
   ////////////////////////////////////////////////////////////////////////////////
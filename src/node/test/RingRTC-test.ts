@@ -13,15 +13,20 @@ import {
   CallLinkRestrictions,
   CallLinkRootKey,
   CallState,
+  CallSummary,
   CallingMessage,
+  E2eeHealth,
   GroupCall,
   GroupCallEndReason,
   GroupCallKind,
   GroupMemberInfo,
   HttpMethod,
   OfferType,
+  ParticipantKind,
   PeekStatusCodes,
+  QualityLimitationReason,
   Reaction,
+  RemoteAvSyncSkew,
   SpeechEvent,
   RingRTC,
   callIdFromEra,
@@ -405,7 +410,7 @@ describe('RingRTC', () => {
         "participants":[
           {"opaqueUserId":"${sha256Hex('11')}","demuxId":${32 * 1}},
           {"opaqueUserId":"${sha256Hex('22')}","demuxId":${32 * 2}},
-          {"opaqueUserId":"${sha256Hex('44')}","demuxId":${32 * 3}}
+          {"opaqueUserId":"${sha256Hex('44')}","demuxId":${32 * 3},"participantKind":"bridged"}
         ],
         "pendingClients":[
           {"opaqueUserId":"${sha256Hex('33')}","demuxId":${32 * 4}},
@@ -424,9 +429,17 @@ describe('RingRTC', () => {
     assert.equal(peekInfo.maxDevices, 20);
     assert.isTrue(peekInfo.creator?.equals(Buffer.of(0x11, 0x11, 0x11, 0x11)));
     assert.deepEqual(peekInfo.devices, [
-      { demuxId: 32 * 1, userId: Buffer.of(0x11, 0x11, 0x11, 0x11) },
-      { demuxId: 32 * 2, userId: Buffer.of(0x22, 0x22, 0x22, 0x22) },
-      { demuxId: 32 * 3 },
+      {
+        demuxId: 32 * 1,
+        userId: Buffer.of(0x11, 0x11, 0x11, 0x11),
+        participantKind: ParticipantKind.AppUser,
+      },
+      {
+        demuxId: 32 * 2,
+        userId: Buffer.of(0x22, 0x22, 0x22, 0x22),
+        participantKind: ParticipantKind.AppUser,
+      },
+      { demuxId: 32 * 3, participantKind: ParticipantKind.Bridged },
     ]);
     assert.deepEqual(peekInfo.pendingUsers, [
       Buffer.of(0x33, 0x33, 0x33, 0x33),
@@ -457,6 +470,17 @@ describe('RingRTC', () => {
       assert.equal(`${EXAMPLE_KEY}`, 'bcdf-ghkm-npqr-stxz-bcdf-ghkm-npqr-stxz');
     });
 
+    it('can move to the latest version', () => {
+      const latestKey = EXAMPLE_KEY.withLatestVersion();
+      assert.isFalse(EXAMPLE_KEY.bytes.equals(latestKey.bytes));
+      assert.isTrue(
+        EXAMPLE_KEY.deriveRoomId().equals(latestKey.deriveRoomId())
+      );
+      assert.isTrue(
+        CallLinkRootKey.parse(`${latestKey}`).bytes.equals(latestKey.bytes)
+      );
+    });
+
     it('can create call links', async () => {
       const requestIdPromise = new Promise<number>((resolve, reject) => {
         RingRTC.handleSendHttpRequest = (
@@ -752,6 +776,94 @@ describe('RingRTC', () => {
       assert.isTrue(state.success);
     });
 
+    it('can rotate call link admin passkey', async () => {
+      const newAdminPasskey = CallLinkRootKey.generateAdminPassKey();
+      const requestIdPromise = new Promise<number>((resolve, reject) => {
+        RingRTC.handleSendHttpRequest = (
+          requestId,
+          url,
+          method,
+          _headers,
+          body
+        ) => {
+          try {
+            assert.isTrue(url.startsWith('sfu.example'));
+            assert.equal(method, HttpMethod.Put);
+            assert.equal(
+              JSON.parse(body?.toString() ?? '{}').newAdminPasskey,
+              newAdminPasskey.toString('base64')
+            );
+            resolve(requestId);
+          } catch (e) {
+            reject(e);
+          }
+        };
+      });
+      const callLinkResponse = RingRTC.rotateCallLinkAdminPasskey(
+        'sfu.example',
+        Buffer.of(1, 2, 3),
+        EXAMPLE_KEY,
+        CallLinkRootKey.generateAdminPassKey(),
+        newAdminPasskey
+      );
+      const requestId = await requestIdPromise;
+      RingRTC.receivedHttpResponse(
+        requestId,
+        200,
+        Buffer.from(EXAMPLE_STATE_JSON)
+      );
+      const state = await callLinkResponse;
+      assert.isTrue(state.success);
+      if (state.success) {
+        assert.isFalse(state.value.oldAdminPasskeyRejected);
+      }
+    });
+
+    it('can migrate call link', async () => {
+      const nextRequestId = (expectedMethod: HttpMethod) =>
+        new Promise<number>((resolve, reject) => {
+          RingRTC.handleSendHttpRequest = (
+            requestId,
+            url,
+            method,
+            _headers,
+            _body
+          ) => {
+            try {
+              assert.isTrue(url.startsWith('sfu.example'));
+              assert.equal(method, expectedMethod);
+              resolve(requestId);
+            } catch (e) {
+              reject(e);
+            }
+          };
+        });
+
+      // The link is read first, and then updated.
+      let requestIdPromise = nextRequestId(HttpMethod.Get);
+      const callLinkResponse = RingRTC.migrateCallLink(
+        'sfu.example',
+        Buffer.of(1, 2, 3),
+        EXAMPLE_KEY.withLatestVersion(),
+        CallLinkRootKey.generateAdminPassKey()
+      );
+      let requestId = await requestIdPromise;
+      requestIdPromise = nextRequestId(HttpMethod.Put);
+      RingRTC.receivedHttpResponse(
+        requestId,
+        200,
+        Buffer.from(EXAMPLE_STATE_JSON)
+      );
+      requestId = await requestIdPromise;
+      RingRTC.receivedHttpResponse(
+        requestId,
+        200,
+        Buffer.from(EXAMPLE_STATE_JSON)
+      );
+      const state = await callLinkResponse;
+      assert.isTrue(state.success);
+    });
+
     it('can delete call link', async () => {
       const requestIdPromise = new Promise<number>((resolve, reject) => {
         RingRTC.handleSendHttpRequest = (
@@ -906,7 +1018,32 @@ describe('RingRTC', () => {
       onLowBandwidthForVideo(_call: GroupCall, _recovered: boolean) {}
       onReactions(_call: GroupCall, _reactions: Array<Reaction>) {}
       onRaisedHands(_call: GroupCall, _raisedHands: Array<number>) {}
+      onAppData(_call: GroupCall, _demuxId: number, _data: Buffer) {}
+      onRemoteConnectionQualities(_call: GroupCall) {}
+      onAvSyncSkew(_call: GroupCall, _skews: Array<RemoteAvSyncSkew>) {}
+      onE2eeHealth(_call: GroupCall, _health: E2eeHealth) {}
+      onVideoAdaptationChanged(
+        _call: GroupCall,
+        _reason: QualityLimitationReason | undefined
+      ) {}
+      onCallSummary(
+        _call: GroupCall,
+        _summary: CallSummary,
+        _reason: GroupCallEndReason
+      ) {}
       onPeekChanged(_call: GroupCall) {}
+      onOwnDeviceJoined(_call: GroupCall, _demuxId: number) {}
+      requestIceServers(_call: GroupCall) {}
+      onIceServersRefreshFailed(_call: GroupCall) {}
+      onBreakoutGroupChanged(
+        _call: GroupCall,
+        _breakoutGroupId: number | undefined
+      ) {}
+      onCallFull(
+        _call: GroupCall,
+        _maxDevices: number | undefined,
+        _deviceCount: number | undefined
+      ) {}
       onEnded(_call: GroupCall, _reason: GroupCallEndReason) {}
       onSpeechEvent(_call: GroupCall, _event: SpeechEvent) {}
       /* eslint-enable @typescript-eslint/no-empty-function */
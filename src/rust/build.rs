@@ -35,6 +35,30 @@ fn main() {
             .expect("unable to generate bindings")
             .write_to_file("tringlib.h");
     }
+    // The canonical header for the rtc_* FFI used by iOS, generated from the
+    // Rust definitions so that it can't drift from them.
+    println!("cargo:rerun-if-env-changed=RINGRTC_HEADER_PATH");
+    if let Some(header_path) = env::var_os("RINGRTC_HEADER_PATH") {
+        let crate_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let config_path = format!("{}/cbindgen.toml", crate_dir);
+        println!("cargo:rerun-if-changed={}", config_path);
+        println!("cargo:rerun-if-changed={}/src", crate_dir);
+        let config =
+            cbindgen::Config::from_file(&config_path).expect("unable to read cbindgen.toml");
+        cbindgen::Builder::new()
+            .with_crate(crate_dir)
+            .with_config(config)
+            .generate()
+            .expect("unable to generate ringrtc.h")
+            .write_to_file(header_path);
+    }
+    // The TypeScript enums passed across the Electron bindings, generated from
+    // the Rust definitions so that new variants can't be missed.
+    println!("cargo:rerun-if-env-changed=RINGRTC_TS_ENUMS_PATH");
+    if let Some(ts_path) = env::var_os("RINGRTC_TS_ENUMS_PATH") {
+        let crate_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        fs::write(ts_path, generate_ts_enums(&crate_dir)).expect("unable to write TS enums");
+    }
     let target = env::var("TARGET").unwrap();
     let profile = env::var("PROFILE").unwrap();
     let out_dir = env::var("OUTPUT_DIR")
@@ -61,7 +85,7 @@ fn main() {
         target, profile, target_os, target_arch, out_dir
     );
 
-    // We only depend on environment variables, not any files.
+    // Apart from the header above, we only depend on environment variables, not any files.
     // Explicitly state that by depending on build.rs itself, as recommended.
     println!("cargo:rerun-if-changed=build.rs");
 
@@ -152,6 +176,210 @@ fn macos_link_search_path() -> Option<String> {
     None
 }
 
+/// How the values of a generated TypeScript enum are derived from a Rust enum.
+enum TsEnumValues {
+    /// The discriminant of each variant, whether or not it has fields.
+    Ordinal,
+    /// The name of each variant, for enums passed as strings.
+    VariantName,
+}
+
+/// A Rust enum (in a file relative to src/) and the TypeScript enum made from it.
+struct TsEnum {
+    path: &'static str,
+    rust_name: &'static str,
+    ts_name: &'static str,
+    values: TsEnumValues,
+}
+
+const TS_ENUMS: &[TsEnum] = &[
+    TsEnum {
+        path: "lite/sfu.rs",
+        rust_name: "ParticipantKind",
+        ts_name: "ParticipantKind",
+        values: TsEnumValues::Ordinal,
+    },
+    TsEnum {
+        path: "lite/http.rs",
+        rust_name: "Method",
+        ts_name: "HttpMethod",
+        values: TsEnumValues::Ordinal,
+    },
+    TsEnum {
+        path: "core/group_call.rs",
+        rust_name: "ConnectionState",
+        ts_name: "ConnectionState",
+        values: TsEnumValues::Ordinal,
+    },
+    TsEnum {
+        path: "core/group_call.rs",
+        rust_name: "JoinState",
+        ts_name: "JoinState",
+        values: TsEnumValues::Ordinal,
+    },
+    TsEnum {
+        path: "core/group_call.rs",
+        rust_name: "EndReason",
+        ts_name: "GroupCallEndReason",
+        values: TsEnumValues::Ordinal,
+    },
+    TsEnum {
+        path: "core/group_call.rs",
+        rust_name: "SpeechEvent",
+        ts_name: "SpeechEvent",
+        values: TsEnumValues::Ordinal,
+    },
+    TsEnum {
+        path: "core/group_call.rs",
+        rust_name: "ConnectionQuality",
+        ts_name: "ConnectionQuality",
+        values: TsEnumValues::Ordinal,
+    },
+    TsEnum {
+        path: "core/group_call.rs",
+        rust_name: "SignalingMessageUrgency",
+        ts_name: "CallMessageUrgency",
+        values: TsEnumValues::Ordinal,
+    },
+    TsEnum {
+        path: "core/group_call.rs",
+        rust_name: "RingUpdate",
+        ts_name: "RingUpdate",
+        values: TsEnumValues::Ordinal,
+    },
+    TsEnum {
+        path: "core/group_call.rs",
+        rust_name: "RingCancelReason",
+        ts_name: "RingCancelReason",
+        values: TsEnumValues::Ordinal,
+    },
+    TsEnum {
+        path: "native.rs",
+        rust_name: "EndReason",
+        ts_name: "CallEndedReason",
+        values: TsEnumValues::VariantName,
+    },
+    TsEnum {
+        path: "native.rs",
+        rust_name: "VideoNegotiationEvent",
+        ts_name: "VideoNegotiationEvent",
+        values: TsEnumValues::VariantName,
+    },
+];
+
+fn parse_source_file(crate_dir: &str, path: &str) -> syn::File {
+    let full_path = format!("{}/src/{}", crate_dir, path);
+    println!("cargo:rerun-if-changed={}", full_path);
+    let source = fs::read_to_string(&full_path)
+        .unwrap_or_else(|e| panic!("unable to read {}: {}", full_path, e));
+    syn::parse_file(&source).unwrap_or_else(|e| panic!("unable to parse {}: {}", full_path, e))
+}
+
+fn int_literal(expr: &syn::Expr) -> i64 {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(value),
+            ..
+        }) => value.base10_parse().expect("integer literal"),
+        _ => panic!("only integer literals are supported in generated TS enums"),
+    }
+}
+
+fn generate_ts_enum(file: &syn::File, ts_enum: &TsEnum) -> String {
+    let item = file
+        .items
+        .iter()
+        .find_map(|item| match item {
+            syn::Item::Enum(item) if item.ident == ts_enum.rust_name => Some(item),
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("no enum {} in {}", ts_enum.rust_name, ts_enum.path));
+
+    let mut out = format!(
+        "// Matches {} in {}.\nexport enum {} {{\n",
+        ts_enum.rust_name, ts_enum.path, ts_enum.ts_name
+    );
+    let mut next_ordinal = 0;
+    for variant in &item.variants {
+        let name = variant.ident.to_string();
+        match ts_enum.values {
+            TsEnumValues::Ordinal => {
+                let ordinal = variant
+                    .discriminant
+                    .as_ref()
+                    .map_or(next_ordinal, |(_, expr)| int_literal(expr));
+                out.push_str(&format!("  {} = {},\n", name, ordinal));
+                next_ordinal = ordinal + 1;
+            }
+            TsEnumValues::VariantName => {
+                out.push_str(&format!("  {} = '{}',\n", name, name));
+            }
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Generates an enum from the associated constants of http::ResponseStatus.
+fn generate_ts_response_status(file: &syn::File) -> String {
+    let mut out = String::from(
+        "// Matches the constants of ResponseStatus in lite/http.rs.\nexport enum HttpResponseStatus {\n",
+    );
+    for item in &file.items {
+        let syn::Item::Impl(item) = item else {
+            continue;
+        };
+        if !matches!(&*item.self_ty, syn::Type::Path(ty) if ty.path.is_ident("ResponseStatus"))
+            || item.trait_.is_some()
+        {
+            continue;
+        }
+        for impl_item in &item.items {
+            let syn::ImplItem::Const(constant) = impl_item else {
+                continue;
+            };
+            let syn::Expr::Struct(value) = &constant.expr else {
+                continue;
+            };
+            let code = value
+                .fields
+                .iter()
+                .find(|field| matches!(&field.member, syn::Member::Named(name) if name == "code"))
+                .expect("ResponseStatus constant without a code");
+            out.push_str(&format!(
+                "  {} = {},\n",
+                constant.ident,
+                int_literal(&code.expr)
+            ));
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn generate_ts_enums(crate_dir: &str) -> String {
+    let mut out = String::from(
+        "//\n\
+         // Copyright 2024 Signal Messenger, LLC\n\
+         // SPDX-License-Identifier: AGPL-3.0-only\n\
+         //\n\
+         \n\
+         // Generated by src/rust/build.rs from the Rust definitions; do not edit.\n\
+         // Regenerate by building with RINGRTC_TS_ENUMS_PATH set, as bin/build-electron does.\n",
+    );
+    for ts_enum in TS_ENUMS {
+        let file = parse_source_file(crate_dir, ts_enum.path);
+        out.push('\n');
+        out.push_str(&generate_ts_enum(&file, ts_enum));
+    }
+    out.push('\n');
+    out.push_str(&generate_ts_response_status(&parse_source_file(
+        crate_dir,
+        "lite/http.rs",
+    )));
+    out
+}
+
 fn fetch_webrtc_artifact(
     target_os: &str,
     target_arch: &str,
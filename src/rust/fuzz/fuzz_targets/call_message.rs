@@ -0,0 +1,33 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Decodes the bytes as each kind of signaling message that arrives from the network: a
+//! CallMessage from another client, and group call messages from other devices and the SFU.
+//!
+//! Anything that decodes must encode to something that decodes to the same message.
+
+#![no_main]
+
+use std::fmt::Debug;
+
+use libfuzzer_sys::fuzz_target;
+use prost::Message;
+use ringrtc::protobuf::{
+    group_call::{DeviceToDevice, SfuToDevice},
+    signaling::CallMessage,
+};
+
+fn check_round_trip<M: Message + Default + PartialEq + Debug>(data: &[u8]) {
+    if let Ok(message) = M::decode(data) {
+        let reencoded = message.encode_to_vec();
+        assert_eq!(message, M::decode(reencoded.as_slice()).expect("re-decode"));
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    check_round_trip::<CallMessage>(data);
+    check_round_trip::<DeviceToDevice>(data);
+    check_round_trip::<SfuToDevice>(data);
+});
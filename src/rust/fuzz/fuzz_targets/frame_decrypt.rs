@@ -0,0 +1,37 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Decrypts a group call frame from a sender whose keys are known, with an arbitrary header
+//! (ratchet counter, frame counter, and MAC) taken from the start of the bytes.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ringrtc::core::crypto::{Context, FrameCounter, Mac, MAC_SIZE_BYTES};
+
+const SENDER_ID: u32 = 1;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&ratchet_counter, rest)) = data.split_first() else {
+        return;
+    };
+    let Some((frame_counter, rest)) = rest.split_first_chunk::<8>() else {
+        return;
+    };
+    let Some((mac, payload)) = rest.split_first_chunk::<MAC_SIZE_BYTES>() else {
+        return;
+    };
+    let frame_counter = FrameCounter::from_be_bytes(*frame_counter);
+    let mac: &Mac = mac;
+
+    let mut context = Context::new([0; 32]);
+    // One key that has been ratcheted and one that hasn't, so that the header can match
+    // either, or need ratcheting.
+    context.add_receive_secret(SENDER_ID, 0, [1; 32]);
+    context.add_receive_secret(SENDER_ID, 3, [2; 32]);
+
+    let mut payload = payload.to_vec();
+    let _ = context.decrypt(SENDER_ID, ratchet_counter, frame_counter, &mut payload, mac);
+});
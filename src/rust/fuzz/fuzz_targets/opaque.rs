@@ -0,0 +1,29 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Parses the bytes as the opaque payload of each 1:1 signaling message the app passes in,
+//! which may be compressed.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ringrtc::{
+    common::CallMediaType,
+    core::signaling::{Answer, IceCandidate, Offer},
+};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(offer) = Offer::new(CallMediaType::Video, data.to_vec()) {
+        let _ = offer.to_v4();
+    }
+    if let Ok(answer) = Answer::new(data.to_vec()) {
+        let _ = answer.to_v4();
+    }
+
+    let candidate = IceCandidate::new(data.to_vec());
+    let _ = candidate.v3_sdp();
+    let _ = candidate.removed_address();
+    let _ = candidate.ice_restart();
+});
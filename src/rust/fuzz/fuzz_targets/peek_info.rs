@@ -0,0 +1,71 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Parses the bytes as PeekInfo from the SFU: as the JSON body of a peek response (successful
+//! or not), and as the protobuf sent over RTP data during a call.
+
+#![no_main]
+
+use std::sync::Arc;
+
+use libfuzzer_sys::fuzz_target;
+use prost::Message;
+use ringrtc::{
+    lite::{
+        call_links::CallLinkRootKey,
+        http,
+        sfu::{self, GroupMember, MemberMap, ObfuscatedResolver, PeekInfo},
+    },
+    protobuf::group_call::sfu_to_device::PeekInfo as ProtoPeekInfo,
+};
+
+/// Responds to every request right away with the same status and body.
+struct FixedResponse {
+    status: u16,
+    body: Vec<u8>,
+}
+
+impl http::Client for FixedResponse {
+    fn send_request(&self, _request: http::Request, callback: http::ResponseCallback) {
+        callback(Some(http::Response {
+            status: self.status.into(),
+            body: self.body.clone(),
+        }))
+    }
+}
+
+fn root_key() -> CallLinkRootKey {
+    CallLinkRootKey::try_from(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16][..])
+        .expect("valid root key")
+}
+
+fn member_map() -> Arc<MemberMap> {
+    Arc::new(MemberMap::new(&[GroupMember {
+        user_id: b"user".to_vec(),
+        member_id: b"member".to_vec(),
+    }]))
+}
+
+fuzz_target!(|data: &[u8]| {
+    for status in [200, 404] {
+        sfu::peek(
+            &FixedResponse {
+                status,
+                body: data.to_vec(),
+            },
+            "https://sfu.example",
+            None,
+            "auth".to_string(),
+            member_map(),
+            Some(root_key()),
+            Box::new(|_result| {}),
+        );
+    }
+
+    if let Ok(proto) = ProtoPeekInfo::decode(data) {
+        let resolver = ObfuscatedResolver::new(member_map(), Some(root_key()));
+        let _ = PeekInfo::deobfuscate_proto(proto, &resolver);
+    }
+});
@@ -262,6 +262,7 @@ impl Platform for AndroidPlatform {
         let audio_jitter_buffer_max_packets = call_config.audio_jitter_buffer_config.max_packets;
         let audio_jitter_buffer_max_target_delay_ms =
             call_config.audio_jitter_buffer_config.max_target_delay_ms;
+        let relay_only = call_config.relay_only;
 
         let connection = Connection::new(
             call.clone(),
@@ -291,6 +292,7 @@ impl Platform for AndroidPlatform {
                 jni_call_context.as_obj() => org.signal.ringrtc.CallManager::CallContext,
                 audio_jitter_buffer_max_packets => int,
                 audio_jitter_buffer_max_target_delay_ms => int,
+                relay_only => boolean,
             ) -> org.signal.ringrtc.Connection),
         )?;
 
@@ -311,6 +313,7 @@ impl Platform for AndroidPlatform {
         call_id: CallId,
         direction: CallDirection,
         call_media_type: CallMediaType,
+        _app_data: &[u8],
     ) -> Result<()> {
         info!(
             "on_start_call(): call_id: {}, direction: {}",
@@ -358,6 +361,7 @@ impl Platform for AndroidPlatform {
         remote_peer: &Self::AppRemotePeer,
         _call_id: CallId,
         event: ApplicationEvent,
+        _app_data: &[u8],
     ) -> Result<()> {
         info!("on_event(): {}", event);
 
@@ -404,6 +408,8 @@ impl Platform for AndroidPlatform {
             jni_args!((
                 remote_peer.as_obj() => org.signal.ringrtc.Remote,
                 network_route.local_adapter_type as i32 => int,
+                network_route.local_adapter_type_under_vpn as i32 => int,
+                network_route.relayed() => boolean,
             ) -> void),
         )?;
         Ok(())
@@ -884,12 +890,23 @@ impl Platform for AndroidPlatform {
         remote_peer: &Self::AppRemotePeer,
         call_id: CallId,
         _age: Duration,
+        app_data: &[u8],
     ) -> Result<()> {
         // Android already keeps track of the offer timestamp, so no need to pass the age through.
-        self.on_event(remote_peer, call_id, ApplicationEvent::ReceivedOfferExpired)
+        self.on_event(
+            remote_peer,
+            call_id,
+            ApplicationEvent::ReceivedOfferExpired,
+            app_data,
+        )
     }
 
-    fn on_call_concluded(&self, remote_peer: &Self::AppRemotePeer, _call_id: CallId) -> Result<()> {
+    fn on_call_concluded(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        _call_id: CallId,
+        _app_data: &[u8],
+    ) -> Result<()> {
         info!("on_call_concluded():");
 
         let env = &mut self.java_env()?;
@@ -909,6 +926,42 @@ impl Platform for AndroidPlatform {
         Ok(())
     }
 
+    fn on_handoff_requested(&self, request: signaling::HandoffRequest) -> Result<()> {
+        info!("on_handoff_requested(): call_id: {}", request.call_id);
+
+        let env = &mut self.java_env()?;
+        let jni_call_manager = self.jni_call_manager.as_obj();
+
+        let remote_user_id = JObject::from(env.byte_array_from_slice(&request.remote_user_id)?);
+        let call_id_jlong = u64::from(request.call_id) as jlong;
+        let remote_device_id = request.remote_device_id as jint;
+        let jni_call_media_type = match self.java_enum(
+            env,
+            CALL_MANAGER_CLASS,
+            "CallMediaType",
+            request.call_media_type as i32,
+        ) {
+            Ok(v) => AutoLocal::new(v, env),
+            Err(error) => {
+                return Err(error);
+            }
+        };
+
+        jni_call_method(
+            env,
+            jni_call_manager,
+            "handoffRequested",
+            jni_args!((
+                remote_user_id => [byte],
+                call_id_jlong => long,
+                remote_device_id => int,
+                jni_call_media_type => org.signal.ringrtc.CallManager::CallMediaType,
+            ) -> void),
+        )?;
+
+        Ok(())
+    }
+
     // Group Calls
 
     fn group_call_ring_update(
@@ -1395,6 +1448,7 @@ impl Platform for AndroidPlatform {
                     let args = jni_args!((
                         jni_demux_id => long,
                         jni_user_id_byte_array => [byte],
+                        remote_device_state.participant_kind as i32 => int,
                         remote_device_state.media_keys_received => boolean,
                         jni_audio_muted => java.lang.Boolean,
                         jni_video_muted => java.lang.Boolean,
@@ -1513,6 +1567,41 @@ impl Platform for AndroidPlatform {
         }
     }
 
+    fn handle_call_full(
+        &self,
+        client_id: group_call::ClientId,
+        max_devices: Option<u32>,
+        device_count: Option<u32>,
+    ) {
+        info!(
+            "handle_call_full(): max_devices: {:?}, device_count: {:?}",
+            max_devices, device_count
+        );
+
+        if let Ok(mut env) = self.java_env() {
+            // Set a frame capacity of min (5) + objects (2).
+            let capacity = 7;
+            if let Err(e) = env.with_local_frame(capacity, |env| -> Result<()> {
+                let jni_max_devices = self.get_optional_u32_long_object(env, max_devices)?;
+                let jni_device_count = self.get_optional_u32_long_object(env, device_count)?;
+
+                jni_call_method(
+                    env,
+                    self.jni_call_manager.as_obj(),
+                    "handleCallFull",
+                    jni_args!((
+                        client_id as jlong => long,
+                        jni_max_devices => java.lang.Long,
+                        jni_device_count => java.lang.Long,
+                    ) -> void),
+                )?;
+                Ok(())
+            }) {
+                error!("handle_call_full: {:?}", e);
+            }
+        }
+    }
+
     fn handle_ended(&self, client_id: group_call::ClientId, reason: group_call::EndReason) {
         info!("handle_ended():");
 
@@ -1946,11 +2035,24 @@ impl AndroidPlatform {
                     .unwrap_or_default()
                     .as_secs();
 
+                let description_object =
+                    JObject::from(env.new_string(state.metadata.description.clone())?);
+                let avatar_object = match &state.metadata.avatar {
+                    Some(avatar) => JObject::from(env.new_string(avatar)?),
+                    None => JObject::null(),
+                };
+                // -1 is never a valid 0xRRGGBB color.
+                let raw_accent_color: jlong = state.metadata.accent_color.map_or(-1, Into::into);
+
                 let args = jni_args!((
                     name_object => java.lang.String,
                     raw_restrictions => int,
                     state.revoked => boolean,
                     expiration_in_epoch_seconds as jlong => long,
+                    description_object => java.lang.String,
+                    raw_accent_color => long,
+                    avatar_object => java.lang.String,
+                    state.old_admin_passkey_rejected => boolean,
                 ) -> void);
 
                 let object = env.new_object(call_link_state_class, args.sig, &args.args);
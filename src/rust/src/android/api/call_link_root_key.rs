@@ -13,7 +13,7 @@ use jni::{
 use crate::{
     android::{error, jni_util::*},
     core::util::try_scoped,
-    lite::call_links::CallLinkRootKey,
+    lite::call_links::{CallLinkRootKey, CallLinkRootKeyVersion},
 };
 
 #[no_mangle]
@@ -26,7 +26,7 @@ pub unsafe extern "C" fn Java_org_signal_ringrtc_CallLinkRootKey_nativeParseKeyS
     try_scoped(|| {
         let string = env.get_string(&string)?;
         let key = CallLinkRootKey::try_from(Cow::from(&string).as_ref())?;
-        Ok(env.byte_array_from_slice(&key.bytes())?)
+        Ok(env.byte_array_from_slice(&key.to_versioned_bytes())?)
     })
     .unwrap_or_else(|e| {
         error::throw_error(&mut env, e);
@@ -57,7 +57,7 @@ pub unsafe extern "C" fn Java_org_signal_ringrtc_CallLinkRootKey_generate<'local
 ) -> JObject<'local> {
     try_scoped(|| {
         let key = CallLinkRootKey::generate(rand::rngs::OsRng);
-        let bytes = env.byte_array_from_slice(&key.bytes())?;
+        let bytes = env.byte_array_from_slice(&key.to_versioned_bytes())?;
         let object = jni_new_object(
             &mut env,
             jni_class_name!(org.signal.ringrtc.CallLinkRootKey),
@@ -105,6 +105,25 @@ pub unsafe extern "C" fn Java_org_signal_ringrtc_CallLinkRootKey_nativeDeriveRoo
     })
 }
 
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallLinkRootKey_nativeWithLatestVersion<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass,
+    key_bytes: JByteArray,
+) -> JByteArray<'local> {
+    try_scoped(|| {
+        let key_bytes = env.convert_byte_array(key_bytes)?;
+        let key = CallLinkRootKey::try_from(key_bytes.as_slice())?
+            .with_version(CallLinkRootKeyVersion::LATEST);
+        Ok(env.byte_array_from_slice(&key.to_versioned_bytes())?)
+    })
+    .unwrap_or_else(|e| {
+        error::throw_error(&mut env, e);
+        JByteArray::default()
+    })
+}
+
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallLinkRootKey_nativeToFormattedString<'local>(
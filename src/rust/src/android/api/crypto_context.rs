@@ -0,0 +1,230 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! JNI bindings for org.signal.ringrtc.CryptoContext.
+//!
+//! The Java object owns a boxed [`CryptoContext`] through its `long` handle
+//! and frees it in `close()`. The context's lock makes the other calls safe
+//! from any thread, but Java must not close the handle while they run.
+
+use anyhow::anyhow;
+use jni::{
+    objects::{JByteArray, JClass, JObject},
+    sys::{jboolean, jbyte, jlong, JNI_FALSE},
+    JNIEnv,
+};
+
+use crate::{
+    android::{error, jni_util::*},
+    core::{
+        crypto::{Mac, RatchetCounter, Secret},
+        util::try_scoped,
+    },
+    frame_crypto::CryptoContext,
+};
+
+const ENCRYPTED_FRAME_CLASS: &str =
+    jni_class_name!(org.signal.ringrtc.CryptoContext::EncryptedFrame);
+const SEND_STATE_CLASS: &str = jni_class_name!(org.signal.ringrtc.CryptoContext::SendState);
+
+/// # Safety
+///
+/// `handle` must come from `nativeCreate` and not have been passed to `nativeDestroy`.
+unsafe fn context_from_handle(handle: jlong) -> anyhow::Result<&'static CryptoContext> {
+    (handle as *const CryptoContext)
+        .as_ref()
+        .ok_or_else(|| anyhow!("CryptoContext used after close()"))
+}
+
+fn byte_array_to_i8(data: &[u8]) -> Vec<jbyte> {
+    data.iter().map(|b| *b as jbyte).collect()
+}
+
+fn new_send_state<'local>(
+    env: &mut JNIEnv<'local>,
+    (ratchet_counter, secret): (RatchetCounter, Secret),
+) -> anyhow::Result<JObject<'local>> {
+    let secret = env.byte_array_from_slice(&secret)?;
+    jni_new_object(
+        env,
+        SEND_STATE_CLASS,
+        jni_args!((ratchet_counter as jbyte => byte, secret => [byte]) -> void),
+    )
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CryptoContext_nativeCreate(
+    mut env: JNIEnv,
+    _class: JClass,
+    initial_send_secret: JByteArray,
+) -> jlong {
+    try_scoped(|| {
+        let secret = env.convert_byte_array(initial_send_secret)?;
+        let secret = Secret::try_from(secret.as_slice())?;
+        Ok(Box::into_raw(Box::new(CryptoContext::new(secret))) as jlong)
+    })
+    .unwrap_or_else(|e| {
+        error::throw_error(&mut env, e);
+        0
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CryptoContext_nativeDestroy(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) {
+    if handle != 0 {
+        drop(Box::from_raw(handle as *mut CryptoContext));
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CryptoContext_nativeEncrypt<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass,
+    handle: jlong,
+    data: JByteArray,
+) -> JObject<'local> {
+    try_scoped(|| {
+        let context = context_from_handle(handle)?;
+        let mut plaintext = env.convert_byte_array(&data)?;
+        let mut mac = Mac::default();
+        let (ratchet_counter, frame_counter) = context.lock().encrypt(&mut plaintext, &mut mac)?;
+        env.set_byte_array_region(&data, 0, &byte_array_to_i8(&plaintext))?;
+        let mac = env.byte_array_from_slice(&mac)?;
+        jni_new_object(
+            &mut env,
+            ENCRYPTED_FRAME_CLASS,
+            jni_args!((
+                ratchet_counter as jbyte => byte,
+                frame_counter as jlong => long,
+                mac => [byte],
+            ) -> void),
+        )
+    })
+    .unwrap_or_else(|e| {
+        error::throw_error(&mut env, e);
+        JObject::default()
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CryptoContext_nativeDecrypt(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    sender_id: jlong,
+    ratchet_counter: jbyte,
+    frame_counter: jlong,
+    data: JByteArray,
+    mac: JByteArray,
+) -> jboolean {
+    try_scoped(|| {
+        let context = context_from_handle(handle)?;
+        let mut ciphertext = env.convert_byte_array(&data)?;
+        let mac = env.convert_byte_array(mac)?;
+        let mac = Mac::try_from(mac.as_slice())?;
+        let decrypted = context
+            .lock()
+            .decrypt(
+                sender_id as u32,
+                ratchet_counter as RatchetCounter,
+                frame_counter as u64,
+                &mut ciphertext,
+                &mac,
+            )
+            .is_ok();
+        if decrypted {
+            env.set_byte_array_region(&data, 0, &byte_array_to_i8(&ciphertext))?;
+        }
+        Ok(jboolean::from(decrypted))
+    })
+    .unwrap_or_else(|e| {
+        error::throw_error(&mut env, e);
+        JNI_FALSE
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CryptoContext_nativeAddReceiveSecret(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    sender_id: jlong,
+    ratchet_counter: jbyte,
+    secret: JByteArray,
+) {
+    try_scoped(|| {
+        let context = context_from_handle(handle)?;
+        let secret = env.convert_byte_array(secret)?;
+        let secret = Secret::try_from(secret.as_slice())?;
+        context.lock().add_receive_secret(
+            sender_id as u32,
+            ratchet_counter as RatchetCounter,
+            secret,
+        );
+        Ok(())
+    })
+    .unwrap_or_else(|e| error::throw_error(&mut env, e))
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CryptoContext_nativeGetSendState<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass,
+    handle: jlong,
+) -> JObject<'local> {
+    try_scoped(|| {
+        let state = context_from_handle(handle)?.lock().send_state();
+        new_send_state(&mut env, state)
+    })
+    .unwrap_or_else(|e| {
+        error::throw_error(&mut env, e);
+        JObject::default()
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CryptoContext_nativeAdvanceSendRatchet<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass,
+    handle: jlong,
+) -> JObject<'local> {
+    try_scoped(|| {
+        let state = context_from_handle(handle)?.lock().advance_send_ratchet();
+        new_send_state(&mut env, state)
+    })
+    .unwrap_or_else(|e| {
+        error::throw_error(&mut env, e);
+        JObject::default()
+    })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CryptoContext_nativeResetSendRatchet(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    secret: JByteArray,
+) {
+    try_scoped(|| {
+        let context = context_from_handle(handle)?;
+        let secret = env.convert_byte_array(secret)?;
+        let secret = Secret::try_from(secret.as_slice())?;
+        context.lock().reset_send_ratchet(secret);
+        Ok(())
+    })
+    .unwrap_or_else(|e| error::throw_error(&mut env, e))
+}
@@ -11,18 +11,20 @@
 use std::{borrow::Cow, time::Duration};
 
 use jni::{
-    objects::{JByteArray, JClass, JObject, JString},
+    objects::{JByteArray, JByteBuffer, JClass, JObject, JString},
     strings::JavaStr,
-    sys::{jboolean, jint, jlong, jobject},
+    sys::{jboolean, jint, jlong, jobject, jstring},
     JNIEnv,
 };
 
 use crate::{
     android::{
-        android_platform::AndroidPlatform, call_manager, call_manager::AndroidCallManager, error,
+        android_platform::AndroidPlatform, call_manager, call_manager::AndroidCallManager,
+        direct_video, error,
     },
-    common::{CallConfig, CallMediaType, DataMode, DeviceId},
+    common::{AcceptOptions, CallBitrateConfig, CallConfig, CallMediaType, DataMode, DeviceId},
     core::{connection::Connection, group_call, signaling, util::try_scoped},
+    lite::diagnostics,
     webrtc,
 };
 
@@ -52,6 +54,65 @@ pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcInitialize(
     }
 }
 
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetMaxLogLevel(
+    mut env: JNIEnv,
+    _class: JClass,
+    level: jint,
+) {
+    if let Err(e) = call_manager::set_max_log_level(level) {
+        error::throw_error(&mut env, e);
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetModuleLogLevel(
+    mut env: JNIEnv,
+    _class: JClass,
+    module: JString,
+    level: jint,
+) {
+    if let Err(e) = call_manager::set_module_log_level(&mut env, module, level) {
+        error::throw_error(&mut env, e);
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcClearModuleLogLevels(
+    _env: JNIEnv,
+    _class: JClass,
+) {
+    crate::core::logging::clear_module_log_levels();
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcExportDiagnostics<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass,
+) -> JByteArray<'local> {
+    env.byte_array_from_slice(&diagnostics::export())
+        .unwrap_or_else(|e| {
+            error::throw_error(&mut env, e.into());
+            JByteArray::default()
+        })
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetCrashDumpPath(
+    mut env: JNIEnv,
+    _class: JClass,
+    path: JString,
+) {
+    if let Err(e) = call_manager::set_crash_dump_path(&mut env, path) {
+        error::throw_error(&mut env, e);
+    }
+}
+
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcCreateCallManager(
@@ -135,6 +196,32 @@ pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcCall(
     }
 }
 
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcTakeOverCall(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    jni_remote: JObject,
+    call_id: jlong,
+    call_media_type: jint,
+    local_device: jint,
+) {
+    match call_manager::take_over_call(
+        &env,
+        call_manager as *mut AndroidCallManager,
+        jni_remote,
+        call_id,
+        CallMediaType::from_i32(call_media_type),
+        local_device as DeviceId,
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcProceed(
@@ -145,6 +232,12 @@ pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcProceed(
     jni_call_context: JObject,
     data_mode: jint,
     audio_levels_interval_millis: jint,
+    hide_ip: jboolean,
+    ring_timeout_millis: jint,
+    connect_timeout_millis: jint,
+    early_media: jboolean,
+    media_stall_timeout_millis: jint,
+    hang_up_on_media_stall: jboolean,
 ) {
     let audio_levels_interval = if audio_levels_interval_millis <= 0 {
         None
@@ -152,12 +245,31 @@ pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcProceed(
         Some(Duration::from_millis(audio_levels_interval_millis as u64))
     };
 
+    let mut call_config = CallConfig::default()
+        .with_data_mode(DataMode::from_i32(data_mode))
+        .with_relay_only(hide_ip != 0)
+        .with_early_media(early_media != 0);
+    if ring_timeout_millis > 0 {
+        call_config =
+            call_config.with_ring_timeout(Duration::from_millis(ring_timeout_millis as u64));
+    }
+    if connect_timeout_millis > 0 {
+        call_config =
+            call_config.with_connect_timeout(Duration::from_millis(connect_timeout_millis as u64));
+    }
+    if media_stall_timeout_millis > 0 {
+        call_config = call_config.with_media_stall_timeout(
+            Duration::from_millis(media_stall_timeout_millis as u64),
+            hang_up_on_media_stall != 0,
+        );
+    }
+
     match call_manager::proceed(
         &env,
         call_manager as *mut AndroidCallManager,
         call_id,
         jni_call_context,
-        CallConfig::default().with_data_mode(DataMode::from_i32(data_mode)),
+        call_config,
         audio_levels_interval,
     ) {
         Ok(v) => v,
@@ -214,6 +326,23 @@ pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcHangup(
     }
 }
 
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcDecline(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+    reason: jint,
+) {
+    match call_manager::decline(call_manager as *mut AndroidCallManager, call_id, reason) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcCancelGroupRing(
@@ -447,8 +576,325 @@ pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcAcceptCall(
     _object: JObject,
     call_manager: jlong,
     call_id: jlong,
+    audio_muted: jboolean,
+    video_disabled: jboolean,
+) {
+    match call_manager::accept_call(
+        call_manager as *mut AndroidCallManager,
+        call_id,
+        AcceptOptions {
+            audio_muted: audio_muted != 0,
+            video_disabled: video_disabled != 0,
+        },
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetOnHold(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+    on_hold: jboolean,
+) {
+    match call_manager::set_on_hold(
+        call_manager as *mut AndroidCallManager,
+        call_id,
+        on_hold != 0,
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcHandOff(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+    remote_user_id: JByteArray,
+    target_device_id: jint,
+) {
+    match call_manager::hand_off(
+        &env,
+        call_manager as *mut AndroidCallManager,
+        call_id,
+        remote_user_id,
+        target_device_id as DeviceId,
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcRestartIce(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+) {
+    match call_manager::restart_ice(call_manager as *mut AndroidCallManager, call_id) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcEnableVideo(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+) {
+    match call_manager::enable_video(call_manager as *mut AndroidCallManager, call_id) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcDisableVideo(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+) {
+    match call_manager::disable_video(call_manager as *mut AndroidCallManager, call_id) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcRespondToVideoRequest(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+    accepted: jboolean,
+) {
+    match call_manager::respond_to_video_request(
+        call_manager as *mut AndroidCallManager,
+        call_id,
+        accepted != 0,
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcGetVerificationCode(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+) -> jstring {
+    let result =
+        call_manager::get_verification_code(call_manager as *mut AndroidCallManager, call_id)
+            .and_then(|code| match code {
+                Some(code) => Ok(env.new_string(code)?.into_raw()),
+                None => Ok(std::ptr::null_mut()),
+            });
+    match result {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+            std::ptr::null_mut() as jstring
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcStartEarlyMedia(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    call_id: jlong,
+) {
+    match call_manager::start_early_media(call_manager as *mut AndroidCallManager, call_id) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetRestartIceOnDisconnect(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    enabled: jboolean,
+) {
+    match call_manager::set_restart_ice_on_disconnect(
+        call_manager as *mut AndroidCallManager,
+        enabled != 0,
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetAudioSessionActive(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    active: jboolean,
+) {
+    match call_manager::set_audio_session_active(
+        call_manager as *mut AndroidCallManager,
+        active != 0,
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetRelayOnlyByDefault(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    enabled: jboolean,
+) {
+    match call_manager::set_relay_only_by_default(
+        call_manager as *mut AndroidCallManager,
+        enabled != 0,
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetMaxSignalingMessageSize(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    max_size: jint,
+) {
+    match call_manager::set_max_signaling_message_size(
+        call_manager as *mut AndroidCallManager,
+        (max_size > 0).then_some(max_size as usize),
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcSetCallWaitingEnabled(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    enabled: jboolean,
+) {
+    match call_manager::set_call_waiting_enabled(
+        call_manager as *mut AndroidCallManager,
+        enabled != 0,
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcHoldAndAcceptWaitingCall(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+) {
+    match call_manager::hold_and_accept_waiting_call(call_manager as *mut AndroidCallManager) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcDeclineWaitingCall(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+) {
+    match call_manager::decline_waiting_call(call_manager as *mut AndroidCallManager) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcResumeHeldCall(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+) {
+    match call_manager::resume_held_call(call_manager as *mut AndroidCallManager) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcHangupHeldCall(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
 ) {
-    match call_manager::accept_call(call_manager as *mut AndroidCallManager, call_id) {
+    match call_manager::hangup_held_call(call_manager as *mut AndroidCallManager) {
         Ok(v) => v,
         Err(e) => {
             error::throw_error(&mut env, e);
@@ -543,6 +989,31 @@ pub extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcUpdateDataMode(
     }
 }
 
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcUpdateBitrateConfig(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    max_audio_bitrate_bps: jlong,
+    max_video_bitrate_bps: jlong,
+    max_total_bitrate_bps: jlong,
+) {
+    match call_manager::update_bitrate_config(
+        call_manager as *mut AndroidCallManager,
+        CallBitrateConfig::from_bps(
+            max_audio_bitrate_bps.max(0) as u64,
+            max_video_bitrate_bps.max(0) as u64,
+            max_total_bitrate_bps.max(0) as u64,
+        ),
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcDrop(
@@ -711,6 +1182,64 @@ pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcDeleteCallLi
     }
 }
 
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcRotateCallLinkAdminPasskey(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    sfu_url: JString,
+    auth_credential_presentation: JByteArray,
+    root_key: JByteArray,
+    admin_passkey: JByteArray,
+    new_admin_passkey: JByteArray,
+    request_id: jlong,
+) {
+    match call_manager::rotate_call_link_admin_passkey(
+        &mut env,
+        call_manager as *mut AndroidCallManager,
+        sfu_url,
+        auth_credential_presentation,
+        root_key,
+        admin_passkey,
+        new_admin_passkey,
+        request_id,
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_CallManager_ringrtcMigrateCallLink(
+    mut env: JNIEnv,
+    _object: JObject,
+    call_manager: jlong,
+    sfu_url: JString,
+    auth_credential_presentation: JByteArray,
+    root_key: JByteArray,
+    admin_passkey: JByteArray,
+    request_id: jlong,
+) {
+    match call_manager::migrate_call_link(
+        &mut env,
+        call_manager as *mut AndroidCallManager,
+        sfu_url,
+        auth_credential_presentation,
+        root_key,
+        admin_passkey,
+        request_id,
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
 // Group Calls
 
 #[no_mangle]
@@ -778,6 +1307,7 @@ pub unsafe extern "C" fn Java_org_signal_ringrtc_GroupCall_ringrtcCreateGroupCal
     native_peer_connection_factory_borrowed_rc: jlong,
     native_audio_track_borrowed_rc: jlong,
     native_video_track_borrowed_rc: jlong,
+    direct_video_sink: JObject,
 ) -> jlong {
     match call_manager::create_group_call_client(
         &mut env,
@@ -789,6 +1319,7 @@ pub unsafe extern "C" fn Java_org_signal_ringrtc_GroupCall_ringrtcCreateGroupCal
         native_peer_connection_factory_borrowed_rc,
         native_audio_track_borrowed_rc,
         native_video_track_borrowed_rc,
+        direct_video_sink,
     ) {
         Ok(v) => v as i64,
         Err(e) => {
@@ -813,6 +1344,7 @@ pub unsafe extern "C" fn Java_org_signal_ringrtc_GroupCall_ringrtcCreateCallLink
     native_peer_connection_factory_borrowed_rc: jlong,
     native_audio_track_borrowed_rc: jlong,
     native_video_track_borrowed_rc: jlong,
+    direct_video_sink: JObject,
 ) -> jlong {
     match call_manager::create_call_link_call_client(
         &mut env,
@@ -826,6 +1358,7 @@ pub unsafe extern "C" fn Java_org_signal_ringrtc_GroupCall_ringrtcCreateCallLink
         native_peer_connection_factory_borrowed_rc,
         native_audio_track_borrowed_rc,
         native_video_track_borrowed_rc,
+        direct_video_sink,
     ) {
         Ok(v) => v as i64,
         Err(e) => {
@@ -835,6 +1368,119 @@ pub unsafe extern "C" fn Java_org_signal_ringrtc_GroupCall_ringrtcCreateCallLink
     }
 }
 
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_GroupCall_ringrtcCreateDirectVideoSource(
+    mut env: JNIEnv,
+    _cls: JClass,
+    native_peer_connection_factory_borrowed_rc: jlong,
+) -> jlong {
+    match direct_video::create_direct_video_source(native_peer_connection_factory_borrowed_rc) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+            0
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_GroupCall_ringrtcGetDirectVideoTrack(
+    mut env: JNIEnv,
+    _cls: JClass,
+    direct_video_source: jlong,
+) -> jlong {
+    match direct_video::get_direct_video_track(direct_video_source) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+            0
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_GroupCall_ringrtcSetDirectVideoEnabled(
+    mut env: JNIEnv,
+    _cls: JClass,
+    direct_video_source: jlong,
+    enabled: bool,
+) {
+    match direct_video::set_direct_video_enabled(direct_video_source, enabled) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_GroupCall_ringrtcPushVideoFrameBuffer(
+    mut env: JNIEnv,
+    _cls: JClass,
+    direct_video_source: jlong,
+    buffer: JByteBuffer,
+    width: jint,
+    height: jint,
+    pixel_format: jint,
+    rotation: jint,
+) {
+    match direct_video::push_video_frame_from_buffer(
+        &env,
+        direct_video_source,
+        buffer,
+        width,
+        height,
+        pixel_format,
+        rotation,
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_GroupCall_ringrtcPushVideoFrameHardwareBuffer(
+    mut env: JNIEnv,
+    _cls: JClass,
+    direct_video_source: jlong,
+    hardware_buffer: JObject,
+    rotation: jint,
+) {
+    match direct_video::push_video_frame_from_hardware_buffer(
+        &env,
+        direct_video_source,
+        hardware_buffer,
+        rotation,
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn Java_org_signal_ringrtc_GroupCall_ringrtcDeleteDirectVideoSource(
+    mut env: JNIEnv,
+    _cls: JClass,
+    direct_video_source: jlong,
+) {
+    match direct_video::delete_direct_video_source(direct_video_source) {
+        Ok(v) => v,
+        Err(e) => {
+            error::throw_error(&mut env, e);
+        }
+    }
+}
+
 #[no_mangle]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn Java_org_signal_ringrtc_GroupCall_ringrtcDeleteGroupCallClient(
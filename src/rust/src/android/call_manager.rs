@@ -5,7 +5,7 @@
 
 //! Android CallManager Interface.
 
-use std::{borrow::Cow, convert::TryFrom, panic, sync::Arc, time::Duration};
+use std::{borrow::Cow, convert::TryFrom, fs::File, panic, sync::Arc, time::Duration};
 
 use jni::{
     objects::{GlobalRef, JByteArray, JClass, JObject, JString},
@@ -17,12 +17,16 @@ use log::Level;
 use crate::{
     android::{
         android_platform::{AndroidCallContext, AndroidPlatform},
+        direct_video::JavaVideoSink,
         error::AndroidError,
         jni_util::*,
         logging::init_logging,
         webrtc_peer_connection_factory::*,
     },
-    common::{CallConfig, CallId, CallMediaType, DataMode, DeviceId, Result},
+    common::{
+        AcceptOptions, CallBitrateConfig, CallConfig, CallId, CallMediaType, DataMode, DeviceId,
+        Result,
+    },
     core::{
         call_manager::CallManager,
         connection::Connection,
@@ -35,12 +39,12 @@ use crate::{
             self, CallLinkDeleteRequest, CallLinkMemberResolver, CallLinkRestrictions,
             CallLinkUpdateRequest,
         },
-        http,
+        crash_dump, http,
         sfu::{self, Delegate, GroupMember},
     },
     webrtc,
     webrtc::{
-        media,
+        media::{self, VideoSink},
         peer_connection::PeerConnection,
         peer_connection_factory::{self as pcf, PeerConnectionFactory},
         peer_connection_observer::PeerConnectionObserver,
@@ -81,6 +85,35 @@ pub fn initialize(env: &mut JNIEnv) -> Result<()> {
     Ok(())
 }
 
+/// Overrides the max log level for one module, numbered as in `log::LevelFilter`.
+pub fn set_module_log_level(env: &mut JNIEnv, module: JString, level: jint) -> Result<()> {
+    let module: String = env.get_string(&module)?.into();
+    let level_filter = u8::try_from(level)
+        .ok()
+        .and_then(crate::core::logging::level_filter_from_raw)
+        .ok_or(RingRtcError::InvalidLogLevel(level))?;
+    crate::core::logging::set_module_log_level(&module, level_filter);
+    Ok(())
+}
+
+/// Creates (or truncates) the file at the path, to be written with the state of every call if
+/// the process panics.
+pub fn set_crash_dump_path(env: &mut JNIEnv, path: JString) -> Result<()> {
+    let path: String = env.get_string(&path)?.into();
+    crash_dump::set_dump_file(File::create(path)?);
+    Ok(())
+}
+
+/// Changes the max log level, numbered as in `log::LevelFilter`.
+pub fn set_max_log_level(level: jint) -> Result<()> {
+    let level_filter = u8::try_from(level)
+        .ok()
+        .and_then(crate::core::logging::level_filter_from_raw)
+        .ok_or(RingRtcError::InvalidLogLevel(level))?;
+    crate::core::logging::set_max_log_level(level_filter);
+    Ok(())
+}
+
 /// Creates a new AndroidCallManager object.
 pub fn create_call_manager(env: &mut JNIEnv, jni_call_manager: JObject) -> Result<jlong> {
     let platform = AndroidPlatform::new(env, env.new_global_ref(jni_call_manager)?)?;
@@ -110,6 +143,12 @@ pub fn create_peer_connection(
 
     // native_connection is an un-boxed Connection<AndroidPlatform> on the heap.
     // pass ownership of it to the PeerConnectionObserver.
+    //
+    // Video frames of 1:1 calls never pass through RingRTC on Android.
+    // Decoded frames go straight from the org.webrtc VideoTrack to the app's
+    // VideoSink, and captured frames from the CapturerObserver to the
+    // VideoSource, both keeping their native or texture buffers. Group calls
+    // can instead use the direct buffers of android::direct_video.
     let pc_observer = PeerConnectionObserver::new(
         native_connection,
         false, /* enable_frame_encryption */
@@ -181,6 +220,25 @@ pub fn call(
     call_manager.call(app_remote_peer, call_media_type, local_device_id)
 }
 
+/// Application notification to take over a call from another device of the local user
+pub fn take_over_call(
+    env: &JNIEnv,
+    call_manager: *mut AndroidCallManager,
+    jni_remote: JObject,
+    call_id: jlong,
+    call_media_type: CallMediaType,
+    local_device_id: DeviceId,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let app_remote_peer = env.new_global_ref(jni_remote)?;
+    call_manager.take_over_call(
+        app_remote_peer,
+        CallId::from(call_id),
+        call_media_type,
+        local_device_id,
+    )
+}
+
 /// Application notification to proceed with a new call
 pub fn proceed(
     env: &JNIEnv,
@@ -224,6 +282,14 @@ pub fn hangup(call_manager: *mut AndroidCallManager) -> Result<()> {
     call_manager.hangup()
 }
 
+/// Application notification to decline the active call with a reason
+pub fn decline(call_manager: *mut AndroidCallManager, call_id: jlong, reason: jint) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let reason = signaling::DeclineReason::from_i32(reason)
+        .ok_or(RingRtcError::InvalidDeclineReason(reason))?;
+    call_manager.decline(CallId::from(call_id), reason)
+}
+
 /// Application notification cancelling a group call ring
 pub fn cancel_group_ring(
     env: &JNIEnv,
@@ -459,10 +525,159 @@ pub fn http_request_failed(call_manager: *mut AndroidCallManager, request_id: jl
 }
 
 /// Application notification to accept the incoming call
-pub fn accept_call(call_manager: *mut AndroidCallManager, call_id: jlong) -> Result<()> {
+pub fn accept_call(
+    call_manager: *mut AndroidCallManager,
+    call_id: jlong,
+    options: AcceptOptions,
+) -> Result<()> {
+    let call_id = CallId::from(call_id);
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.accept_call_with_options(call_id, options)
+}
+
+/// Application request to put the call on hold or take it off hold
+pub fn set_on_hold(
+    call_manager: *mut AndroidCallManager,
+    call_id: jlong,
+    on_hold: bool,
+) -> Result<()> {
     let call_id = CallId::from(call_id);
     let call_manager = unsafe { ptr_as_mut(call_manager)? };
-    call_manager.accept_call(call_id)
+    call_manager.set_on_hold(call_id, on_hold)
+}
+
+/// Application request to have another device of the local user take over the call
+pub fn hand_off(
+    env: &JNIEnv,
+    call_manager: *mut AndroidCallManager,
+    call_id: jlong,
+    remote_user_id: JByteArray,
+    target_device_id: DeviceId,
+) -> Result<()> {
+    let call_id = CallId::from(call_id);
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.hand_off(
+        call_id,
+        env.convert_byte_array(remote_user_id)?,
+        target_device_id,
+    )
+}
+
+/// Application request to restart ICE on the active call
+pub fn restart_ice(call_manager: *mut AndroidCallManager, call_id: jlong) -> Result<()> {
+    let call_id = CallId::from(call_id);
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.restart_ice(call_id)
+}
+
+/// Application request to start sending video on the active call
+pub fn enable_video(call_manager: *mut AndroidCallManager, call_id: jlong) -> Result<()> {
+    let call_id = CallId::from(call_id);
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.enable_video(call_id)
+}
+
+/// Application request to stop sending video on the active call
+pub fn disable_video(call_manager: *mut AndroidCallManager, call_id: jlong) -> Result<()> {
+    let call_id = CallId::from(call_id);
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.disable_video(call_id)
+}
+
+/// Application response to the remote peer's request to send video
+pub fn respond_to_video_request(
+    call_manager: *mut AndroidCallManager,
+    call_id: jlong,
+    accepted: bool,
+) -> Result<()> {
+    let call_id = CallId::from(call_id);
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.respond_to_video_request(call_id, accepted)
+}
+
+/// Application request for the verification code of an active call
+pub fn get_verification_code(
+    call_manager: *mut AndroidCallManager,
+    call_id: jlong,
+) -> Result<Option<String>> {
+    let call_id = CallId::from(call_id);
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    Ok(call_manager
+        .verification_code(call_id)?
+        .map(|code| code.to_string()))
+}
+
+/// Application request to send early media on a ringing incoming call
+pub fn start_early_media(call_manager: *mut AndroidCallManager, call_id: jlong) -> Result<()> {
+    let call_id = CallId::from(call_id);
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.start_early_media(call_id)
+}
+
+/// Application request to enable or disable restarting ICE when the connection drops
+pub fn set_restart_ice_on_disconnect(
+    call_manager: *mut AndroidCallManager,
+    enabled: bool,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_restart_ice_on_disconnect(enabled)
+}
+
+/// Application notification that it gained or lost audio focus
+pub fn set_audio_session_active(call_manager: *mut AndroidCallManager, active: bool) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_audio_session_active(active)
+}
+
+/// Application request to enable or disable relay-only ICE for all calls
+pub fn set_relay_only_by_default(
+    call_manager: *mut AndroidCallManager,
+    enabled: bool,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_relay_only_by_default(enabled)
+}
+
+/// Application request to limit the size of outgoing offers and answers
+pub fn set_max_signaling_message_size(
+    call_manager: *mut AndroidCallManager,
+    max_size: Option<usize>,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_max_signaling_message_size(max_size)
+}
+
+/// Application request to enable or disable call waiting
+pub fn set_call_waiting_enabled(
+    call_manager: *mut AndroidCallManager,
+    enabled: bool,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_call_waiting_enabled(enabled)
+}
+
+/// Application request to put the active call on hold and accept the waiting call
+pub fn hold_and_accept_waiting_call(call_manager: *mut AndroidCallManager) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.hold_and_accept_waiting_call()
+}
+
+/// Application request to decline the waiting call as busy
+pub fn decline_waiting_call(call_manager: *mut AndroidCallManager) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.decline_waiting_call()
+}
+
+/// Application request to resume the held call once no other call is active
+pub fn resume_held_call(call_manager: *mut AndroidCallManager) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.resume_held_call()
+}
+
+/// Application request to hang up the held call
+pub fn hangup_held_call(call_manager: *mut AndroidCallManager) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.hangup_held_call()
 }
 
 /// CMI request for the active Connection object
@@ -518,6 +733,16 @@ pub fn update_data_mode(call_manager: *mut AndroidCallManager, data_mode: DataMo
     active_connection.inject_update_data_mode(data_mode)
 }
 
+/// Request to update the bitrate caps on the direct connection
+pub fn update_bitrate_config(
+    call_manager: *mut AndroidCallManager,
+    bitrate_config: CallBitrateConfig,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let mut active_connection = call_manager.active_connection()?;
+    active_connection.inject_update_bitrate_config(bitrate_config)
+}
+
 /// CMI request to drop the active call
 pub fn drop_call(call_manager: *mut AndroidCallManager, call_id: jlong) -> Result<()> {
     let call_id = CallId::from(call_id);
@@ -562,6 +787,7 @@ pub fn read_call_link(
         call_manager.http_client(),
         &Cow::from(&sfu_url),
         root_key,
+        None,
         &auth_credential_presentation,
         Box::new(move |result| {
             platform.handle_call_link_result(request_id as u32, result);
@@ -601,6 +827,7 @@ pub fn create_call_link(
         &admin_passkey,
         &call_link_public_params,
         restrictions,
+        None,
         Box::new(move |result| {
             platform.handle_call_link_result(request_id as u32, result);
         }),
@@ -659,6 +886,9 @@ pub fn update_call_link(
             encrypted_name: encrypted_name.as_deref(),
             restrictions: new_restrictions,
             revoked: new_revoked,
+            reset_epoch: false,
+            encrypted_metadata: None,
+            new_admin_passkey: None,
         },
         Box::new(move |result| {
             platform.handle_call_link_result(request_id as u32, result);
@@ -702,6 +932,72 @@ pub fn delete_call_link(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
+pub fn rotate_call_link_admin_passkey(
+    env: &mut JNIEnv,
+    call_manager: *mut AndroidCallManager,
+    sfu_url: JString,
+    auth_credential_presentation: JByteArray,
+    root_key: JByteArray,
+    admin_passkey: JByteArray,
+    new_admin_passkey: JByteArray,
+    request_id: jlong,
+) -> Result<()> {
+    let sfu_url = env.get_string(&sfu_url)?;
+    let auth_credential_presentation = env.convert_byte_array(auth_credential_presentation)?;
+    let root_key =
+        call_links::CallLinkRootKey::try_from(env.convert_byte_array(root_key)?.as_slice())?;
+    let admin_passkey = env.convert_byte_array(admin_passkey)?;
+    let new_admin_passkey = env.convert_byte_array(new_admin_passkey)?;
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let platform = call_manager.platform()?.try_clone()?;
+    call_links::rotate_admin_passkey(
+        call_manager.shared_http_client(),
+        &Cow::from(&sfu_url),
+        root_key,
+        &auth_credential_presentation,
+        &admin_passkey,
+        &new_admin_passkey,
+        Box::new(move |result| {
+            platform.handle_call_link_result(request_id as u32, result);
+        }),
+    );
+
+    Ok(())
+}
+
+pub fn migrate_call_link(
+    env: &mut JNIEnv,
+    call_manager: *mut AndroidCallManager,
+    sfu_url: JString,
+    auth_credential_presentation: JByteArray,
+    root_key: JByteArray,
+    admin_passkey: JByteArray,
+    request_id: jlong,
+) -> Result<()> {
+    let sfu_url = env.get_string(&sfu_url)?;
+    let auth_credential_presentation = env.convert_byte_array(auth_credential_presentation)?;
+    let root_key =
+        call_links::CallLinkRootKey::try_from(env.convert_byte_array(root_key)?.as_slice())?;
+    let admin_passkey = env.convert_byte_array(admin_passkey)?;
+
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let platform = call_manager.platform()?.try_clone()?;
+    call_links::read_and_migrate_call_link(
+        call_manager.shared_http_client(),
+        &Cow::from(&sfu_url),
+        root_key,
+        &auth_credential_presentation,
+        &admin_passkey,
+        Box::new(move |result| {
+            platform.handle_call_link_result(request_id as u32, result);
+        }),
+    );
+
+    Ok(())
+}
+
 // Group Calls
 
 /// Convert a byte[] with 32-byte chunks in to a GroupMember struct vector.
@@ -790,6 +1086,7 @@ pub fn create_group_call_client(
     native_pcf_borrowed_rc: jlong,
     native_audio_track_borrowed_rc: jlong,
     native_video_track_borrowed_rc: jlong,
+    direct_video_sink: JObject,
 ) -> Result<group_call::ClientId> {
     let group_id = env.convert_byte_array(group_id)?;
     let sfu_url = env.get_string(&sfu_url)?.into();
@@ -823,6 +1120,12 @@ pub fn create_group_call_client(
         Some(peer_connection_factory.rffi().clone()),
     );
 
+    let incoming_video_sink = if direct_video_sink.is_null() {
+        None
+    } else {
+        Some(Box::new(JavaVideoSink::new(env, &direct_video_sink)?) as Box<dyn VideoSink>)
+    };
+
     let audio_levels_interval = if audio_levels_interval_millis <= 0 {
         None
     } else {
@@ -838,7 +1141,8 @@ pub fn create_group_call_client(
         Some(peer_connection_factory),
         outgoing_audio_track,
         outgoing_video_track,
-        None,
+        incoming_video_sink,
+        vec![],
     )
 }
 
@@ -855,6 +1159,7 @@ pub fn create_call_link_call_client(
     native_pcf_borrowed_rc: jlong,
     native_audio_track_borrowed_rc: jlong,
     native_video_track_borrowed_rc: jlong,
+    direct_video_sink: JObject,
 ) -> Result<group_call::ClientId> {
     let sfu_url = env.get_string(&sfu_url)?.into();
     let auth_presentation = env.convert_byte_array(auth_presentation)?;
@@ -895,6 +1200,12 @@ pub fn create_call_link_call_client(
         Some(peer_connection_factory.rffi().clone()),
     );
 
+    let incoming_video_sink = if direct_video_sink.is_null() {
+        None
+    } else {
+        Some(Box::new(JavaVideoSink::new(env, &direct_video_sink)?) as Box<dyn VideoSink>)
+    };
+
     let audio_levels_interval = if audio_levels_interval_millis <= 0 {
         None
     } else {
@@ -912,7 +1223,8 @@ pub fn create_call_link_call_client(
         Some(peer_connection_factory),
         outgoing_audio_track,
         outgoing_video_track,
-        None,
+        incoming_video_sink,
+        vec![],
     )
 }
 
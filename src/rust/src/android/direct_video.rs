@@ -0,0 +1,319 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Video frames passed between Java and WebRTC without Java arrays.
+//!
+//! Decoded remote frames are handed to a Java GroupCall.DirectVideoSink as
+//! direct ByteBuffers that point at the decoder's I420 output. Frames to send
+//! are read straight out of direct ByteBuffers, or out of HardwareBuffers on
+//! API level 26 and later, and pushed to a RingRTC VideoSource that replaces
+//! the org.webrtc VideoSource of the call.
+
+use std::{
+    ffi::{c_void, CStr},
+    mem, ptr,
+    sync::{Arc, OnceLock},
+};
+
+use anyhow::anyhow;
+use jni::{
+    objects::{GlobalRef, JByteBuffer, JObject},
+    sys::{jint, jlong},
+    JNIEnv, JavaVM,
+};
+
+use crate::{
+    android::jni_util::*,
+    common::Result,
+    core::util::ptr_as_box,
+    lite::sfu::DemuxId,
+    webrtc::{
+        self,
+        media::{VideoFrame, VideoPixelFormat, VideoRotation, VideoSink, VideoSource, VideoTrack},
+        peer_connection_factory::{self as pcf, PeerConnectionFactory},
+    },
+};
+
+/// Hands decoded remote video frames to a Java GroupCall.DirectVideoSink.
+#[derive(Clone)]
+pub struct JavaVideoSink {
+    jvm: Arc<JavaVM>,
+    sink: GlobalRef,
+}
+
+impl JavaVideoSink {
+    pub fn new(env: &JNIEnv, sink: &JObject) -> Result<Self> {
+        Ok(Self {
+            jvm: Arc::new(env.get_java_vm()?),
+            sink: env.new_global_ref(sink)?,
+        })
+    }
+
+    fn deliver(&self, demux_id: DemuxId, frame: &VideoFrame) -> Result<()> {
+        let Some(i420) = frame.as_i420() else {
+            return Err(anyhow!("video frame has no I420 buffer"));
+        };
+        let env = &mut ExceptionCheckingJNIEnv::from(self.jvm.attach_current_thread_as_daemon()?);
+        // The ByteBuffer is a view of the decoder's output rather than a copy,
+        // so it's only valid until onVideoFrame() returns.
+        let buffer = unsafe { env.new_direct_byte_buffer(i420.as_ptr() as *mut u8, i420.len())? };
+        let result = jni_call_method(
+            env,
+            self.sink.as_obj(),
+            "onVideoFrame",
+            jni_args!((
+                (demux_id as jlong) => long,
+                (frame.width() as jint) => int,
+                (frame.height() as jint) => int,
+                frame.rotation().degrees() => int,
+                buffer => java.nio.ByteBuffer,
+            ) -> void),
+        );
+        env.delete_local_ref(buffer)?;
+        result
+    }
+}
+
+impl VideoSink for JavaVideoSink {
+    fn on_video_frame(&self, demux_id: DemuxId, frame: VideoFrame) {
+        if let Err(e) = self.deliver(demux_id, &frame) {
+            error!("Failed to deliver video frame for {}: {:?}", demux_id, e);
+        }
+    }
+
+    fn box_clone(&self) -> Box<dyn VideoSink> {
+        Box::new(self.clone())
+    }
+}
+
+/// An outgoing video track fed with frames pushed from Java, used by a group
+/// call in place of an org.webrtc VideoSource and VideoTrack.
+pub struct DirectVideoSource {
+    source: VideoSource,
+    track: VideoTrack,
+}
+
+/// Create a DirectVideoSource, returning it boxed for Java to own.
+pub fn create_direct_video_source(native_pcf_borrowed_rc: jlong) -> Result<jlong> {
+    // This is safe because the factory given to us should still be alive.
+    let peer_connection_factory = unsafe {
+        PeerConnectionFactory::from_native_factory(webrtc::Arc::from_borrowed(
+            webrtc::ptr::BorrowedRc::from_ptr(
+                native_pcf_borrowed_rc as *const pcf::RffiPeerConnectionFactoryInterface,
+            ),
+        ))
+    };
+    let source = peer_connection_factory.create_outgoing_video_source()?;
+    let track = peer_connection_factory.create_outgoing_video_track(&source)?;
+    // Like the org.webrtc track it replaces, the track starts out disabled.
+    track.set_enabled(false);
+    Ok(Box::into_raw(Box::new(DirectVideoSource { source, track })) as jlong)
+}
+
+/// Return a borrowed RC to the track of a DirectVideoSource, to create a
+/// group call with.
+pub fn get_direct_video_track(direct_video_source: jlong) -> Result<jlong> {
+    let direct_video_source = source_from_jlong(direct_video_source)?;
+    Ok(direct_video_source.track.rffi().as_borrowed().as_ptr() as jlong)
+}
+
+pub fn set_direct_video_enabled(direct_video_source: jlong, enabled: bool) -> Result<()> {
+    source_from_jlong(direct_video_source)?
+        .track
+        .set_enabled(enabled);
+    Ok(())
+}
+
+pub fn delete_direct_video_source(direct_video_source: jlong) -> Result<()> {
+    let _ = unsafe { ptr_as_box(direct_video_source as *mut DirectVideoSource)? };
+    Ok(())
+}
+
+fn source_from_jlong(direct_video_source: jlong) -> Result<&'static DirectVideoSource> {
+    // Frames may be pushed from any thread, so only shared references are
+    // handed out.
+    unsafe { (direct_video_source as *const DirectVideoSource).as_ref() }
+        .ok_or_else(|| anyhow!("null DirectVideoSource"))
+}
+
+fn rotation_from_jint(rotation: jint) -> Result<VideoRotation> {
+    VideoRotation::from_degrees(rotation)
+        .ok_or_else(|| anyhow!("invalid video rotation: {}", rotation))
+}
+
+/// Send a frame read from a direct ByteBuffer holding tightly packed pixels
+/// in the given format.
+pub fn push_video_frame_from_buffer(
+    env: &JNIEnv,
+    direct_video_source: jlong,
+    buffer: JByteBuffer,
+    width: jint,
+    height: jint,
+    pixel_format: jint,
+    rotation: jint,
+) -> Result<()> {
+    let direct_video_source = source_from_jlong(direct_video_source)?;
+    let pixel_format = VideoPixelFormat::from_i32(pixel_format)
+        .ok_or_else(|| anyhow!("invalid video pixel format: {}", pixel_format))?;
+    let rotation = rotation_from_jint(rotation)?;
+    let (width, height) = (u32::try_from(width)?, u32::try_from(height)?);
+
+    let address = env.get_direct_buffer_address(&buffer)?;
+    let capacity = env.get_direct_buffer_capacity(&buffer)?;
+    let size = frame_size(width, height, pixel_format);
+    if capacity < size {
+        return Err(anyhow!(
+            "{}x{} {:?} frame needs {} bytes but the buffer has {}",
+            width,
+            height,
+            pixel_format,
+            size,
+            capacity
+        ));
+    }
+    // This is safe because the ByteBuffer stays alive for this call.
+    let pixels = unsafe { std::slice::from_raw_parts(address, size) };
+    let frame =
+        VideoFrame::copy_from_slice(width, height, pixel_format, pixels).with_rotation(rotation);
+    direct_video_source.source.push_frame(frame);
+    Ok(())
+}
+
+fn frame_size(width: u32, height: u32, pixel_format: VideoPixelFormat) -> usize {
+    let pixels = width as usize * height as usize;
+    match pixel_format {
+        VideoPixelFormat::I420 | VideoPixelFormat::Nv12 => pixels * 3 / 2,
+        VideoPixelFormat::Rgba => pixels * 4,
+    }
+}
+
+/// AHardwareBuffer_Desc from the NDK's android/hardware_buffer.h.
+#[repr(C)]
+#[derive(Default)]
+#[allow(dead_code)]
+struct AHardwareBufferDesc {
+    width: u32,
+    height: u32,
+    layers: u32,
+    format: u32,
+    usage: u64,
+    stride: u32,
+    rfu0: u32,
+    rfu1: u64,
+}
+
+const AHARDWAREBUFFER_FORMAT_R8G8B8A8_UNORM: u32 = 1;
+const AHARDWAREBUFFER_FORMAT_R8G8B8X8_UNORM: u32 = 2;
+const AHARDWAREBUFFER_USAGE_CPU_READ_OFTEN: u64 = 3;
+
+/// The NDK's AHardwareBuffer functions. They only exist from API level 26, so
+/// they are looked up when first needed rather than linked, which would keep
+/// the library from loading on older versions of Android.
+struct HardwareBufferApi {
+    from_hardware_buffer:
+        unsafe extern "C" fn(*mut jni::sys::JNIEnv, jni::sys::jobject) -> *mut c_void,
+    describe: unsafe extern "C" fn(*const c_void, *mut AHardwareBufferDesc),
+    lock: unsafe extern "C" fn(*mut c_void, u64, i32, *const c_void, *mut *mut c_void) -> i32,
+    unlock: unsafe extern "C" fn(*mut c_void, *mut i32) -> i32,
+}
+
+impl HardwareBufferApi {
+    fn get() -> Option<&'static Self> {
+        static API: OnceLock<Option<HardwareBufferApi>> = OnceLock::new();
+        API.get_or_init(|| unsafe { Self::load() }).as_ref()
+    }
+
+    unsafe fn load() -> Option<Self> {
+        let library = libc::dlopen(c"libandroid.so".as_ptr(), libc::RTLD_NOW);
+        if library.is_null() {
+            return None;
+        }
+        let symbol = |name: &CStr| {
+            let symbol = libc::dlsym(library, name.as_ptr());
+            (!symbol.is_null()).then_some(symbol)
+        };
+        Some(Self {
+            from_hardware_buffer: mem::transmute::<*mut c_void, _>(symbol(
+                c"AHardwareBuffer_fromHardwareBuffer",
+            )?),
+            describe: mem::transmute::<*mut c_void, _>(symbol(c"AHardwareBuffer_describe")?),
+            lock: mem::transmute::<*mut c_void, _>(symbol(c"AHardwareBuffer_lock")?),
+            unlock: mem::transmute::<*mut c_void, _>(symbol(c"AHardwareBuffer_unlock")?),
+        })
+    }
+}
+
+/// Send a frame read from an android.hardware.HardwareBuffer in an RGBA
+/// format. Only supported from API level 26.
+pub fn push_video_frame_from_hardware_buffer(
+    env: &JNIEnv,
+    direct_video_source: jlong,
+    hardware_buffer: JObject,
+    rotation: jint,
+) -> Result<()> {
+    let direct_video_source = source_from_jlong(direct_video_source)?;
+    let rotation = rotation_from_jint(rotation)?;
+    let api = HardwareBufferApi::get()
+        .ok_or_else(|| anyhow!("HardwareBuffer frames need API level 26 or later"))?;
+
+    // The AHardwareBuffer is owned by the Java object, which stays alive for
+    // this call.
+    let buffer = unsafe { (api.from_hardware_buffer)(env.get_raw(), hardware_buffer.as_raw()) };
+    if buffer.is_null() {
+        return Err(anyhow!("not a HardwareBuffer"));
+    }
+    let mut desc = AHardwareBufferDesc::default();
+    unsafe { (api.describe)(buffer, &mut desc) };
+    if !matches!(
+        desc.format,
+        AHARDWAREBUFFER_FORMAT_R8G8B8A8_UNORM | AHARDWAREBUFFER_FORMAT_R8G8B8X8_UNORM
+    ) {
+        return Err(anyhow!(
+            "unsupported HardwareBuffer format: {}",
+            desc.format
+        ));
+    }
+
+    let mut address = ptr::null_mut();
+    let rc = unsafe {
+        (api.lock)(
+            buffer,
+            AHARDWAREBUFFER_USAGE_CPU_READ_OFTEN,
+            -1,
+            ptr::null(),
+            &mut address,
+        )
+    };
+    if rc != 0 || address.is_null() {
+        return Err(anyhow!("failed to lock HardwareBuffer: {}", rc));
+    }
+    let (width, height, stride) = (
+        desc.width as usize,
+        desc.height as usize,
+        desc.stride as usize,
+    );
+    // This is safe because the buffer stays locked until after the copy.
+    let locked = unsafe { std::slice::from_raw_parts(address as *const u8, stride * height * 4) };
+    let frame = if stride == width {
+        VideoFrame::copy_from_slice(desc.width, desc.height, VideoPixelFormat::Rgba, locked)
+    } else {
+        // WebRTC wants tightly packed rows.
+        let packed: Vec<u8> = locked
+            .chunks(stride * 4)
+            .flat_map(|row| &row[..width * 4])
+            .copied()
+            .collect();
+        VideoFrame::copy_from_slice(desc.width, desc.height, VideoPixelFormat::Rgba, &packed)
+    };
+    let rc = unsafe { (api.unlock)(buffer, ptr::null_mut()) };
+    if rc != 0 {
+        warn!("failed to unlock HardwareBuffer: {}", rc);
+    }
+
+    direct_video_source
+        .source
+        .push_frame(frame.with_rotation(rotation));
+    Ok(())
+}
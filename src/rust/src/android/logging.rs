@@ -14,6 +14,7 @@ use log::{Level, Log, Metadata, Record};
 use crate::{
     android::{error::AndroidError, jni_util::*},
     common::Result,
+    core::logging::RedactingLogger,
 };
 
 /// Log object for interfacing with existing Android logger.
@@ -146,7 +147,7 @@ pub fn init_logging(env: &mut JNIEnv, level: Level) -> Result<()> {
         logger_class,
     };
 
-    log::set_boxed_logger(Box::new(logger))?;
+    log::set_boxed_logger(Box::new(RedactingLogger::new(logger)))?;
     log::set_max_level(level.to_level_filter());
 
     Ok(())
@@ -14,7 +14,8 @@ use base64::{engine::general_purpose::STANDARD as base64, Engine};
 use rand::SeedableRng;
 use ringrtc::lite::{
     call_links::{
-        CallLinkDeleteRequest, CallLinkRestrictions, CallLinkRootKey, CallLinkUpdateRequest,
+        CallLinkDeleteRequest, CallLinkEpoch, CallLinkRestrictions, CallLinkRootKey,
+        CallLinkUpdateRequest,
     },
     http::{self, sim as sim_http, Client},
 };
@@ -130,6 +131,7 @@ fn make_testing_request(
                     &http_client_inner,
                     url,
                     root_key,
+                    None,
                     &bincode::serialize(&auth_credential_presentation).unwrap(),
                     Box::new(show_result),
                 )
@@ -190,11 +192,15 @@ Available commands are:
 
 help                         - show this message
 create <id>                  - create a new link
-read <id>                    - fetch the current state of a link
+read <id> [<epoch>]          - fetch the current state of a link, as shared with the given epoch
 set-title <id> <new-title>   - change the title of a link
 admin-approval <id> (on|off) - turn on/off admin approval for a link
+members-can-add <id> <revision> (on|off)
+                             - let members add people, if the link is still at <revision>
 reset-approvals <id>         - reset a link's list of approved users (if the server has this enabled)
 reset-expiration <id>        - reset a link's expiration (if the server has this enabled)
+reset-epoch <id>             - invalidate previously shared links by bumping the epoch
+usage <id>                   - show how much a link has been used
 root-key <id>                - print the root key for a link
 exit                         - quit
 
@@ -239,10 +245,20 @@ The admin passkey for any created links is a constant {ADMIN_PASSKEY:?}.
                     ADMIN_PASSKEY,
                     &bincode::serialize(&call_link_zkparams.get_public_params()).unwrap(),
                     None,
+                    None,
                     Box::new(show_result),
                 );
             }
-            ["read", id] => {
+            ["read", id, epoch @ ..] if epoch.len() <= 1 => {
+                let epoch = match epoch.first().map(|epoch| CallLinkEpoch::try_from(*epoch)) {
+                    None => None,
+                    Some(Ok(epoch)) => Some(epoch),
+                    Some(Err(_)) => {
+                        println!("Couldn't parse the epoch.\n");
+                        prompt("> ");
+                        continue;
+                    }
+                };
                 let root_key = root_key_from_id(id);
                 let auth_credential_presentation = issue_and_present_auth_credential(
                     &server_zkparams,
@@ -253,6 +269,7 @@ The admin passkey for any created links is a constant {ADMIN_PASSKEY:?}.
                     &http_client,
                     url,
                     root_key,
+                    epoch,
                     &bincode::serialize(&auth_credential_presentation).unwrap(),
                     Box::new(show_result),
                 );
@@ -321,6 +338,66 @@ The admin passkey for any created links is a constant {ADMIN_PASSKEY:?}.
                     Box::new(show_result),
                 );
             }
+            ["members-can-add", id, revision, on_or_off @ ("on" | "off")] => {
+                let Ok(revision) = revision.parse() else {
+                    println!("Couldn't parse the revision.\n");
+                    prompt("> ");
+                    continue;
+                };
+                let root_key = root_key_from_id(id);
+                let auth_credential_presentation = issue_and_present_auth_credential(
+                    &server_zkparams,
+                    &public_zkparams,
+                    &root_key,
+                );
+                ringrtc::lite::call_links::update_call_link_restrictions(
+                    &http_client,
+                    url,
+                    root_key,
+                    &bincode::serialize(&auth_credential_presentation).unwrap(),
+                    ADMIN_PASSKEY,
+                    None,
+                    Some(*on_or_off == "on"),
+                    revision,
+                    Box::new(show_result),
+                );
+            }
+            ["reset-epoch", id] => {
+                let root_key = root_key_from_id(id);
+                let auth_credential_presentation = issue_and_present_auth_credential(
+                    &server_zkparams,
+                    &public_zkparams,
+                    &root_key,
+                );
+                ringrtc::lite::call_links::update_call_link(
+                    &http_client,
+                    url,
+                    root_key,
+                    &bincode::serialize(&auth_credential_presentation).unwrap(),
+                    &CallLinkUpdateRequest {
+                        admin_passkey: ADMIN_PASSKEY,
+                        reset_epoch: true,
+                        ..CallLinkUpdateRequest::default()
+                    },
+                    Box::new(show_result),
+                );
+            }
+            ["usage", id] => {
+                let root_key = root_key_from_id(id);
+                let auth_credential_presentation = issue_and_present_auth_credential(
+                    &server_zkparams,
+                    &public_zkparams,
+                    &root_key,
+                );
+                ringrtc::lite::call_links::read_call_link_usage(
+                    &http_client,
+                    url,
+                    &root_key,
+                    &bincode::serialize(&auth_credential_presentation).unwrap(),
+                    ADMIN_PASSKEY,
+                    Box::new(show_result),
+                );
+            }
             ["reset-approvals", id] => {
                 make_testing_request(
                     id,
@@ -38,6 +38,7 @@ use ringrtc::{
 };
 
 use crate::{
+    impairment::{ImpairedHttpClient, ImpairmentSchedule},
     network::DeterministicLossNetwork,
     relay::SignalingRelay,
     util,
@@ -90,7 +91,7 @@ struct CallEndpointState {
 
     // connection related resources
     peer_connection_factory: PeerConnectionFactory,
-    delegate_http_client: HttpClient,
+    delegate_http_client: Box<dyn Client + Send>,
     network: Option<DeterministicLossNetwork>,
 
     direct_call: Option<DirectCall>,
@@ -111,6 +112,7 @@ impl CallEndpoint {
         event_sync: EventSync,
         incoming_video_sink: Option<Box<dyn VideoSink>>,
         use_injectable_network: bool,
+        http_impairments: Option<ImpairmentSchedule>,
     ) -> Result<Self> {
         let audio_config = audio_config.clone();
         let name = name.to_owned();
@@ -146,7 +148,12 @@ impl CallEndpoint {
                 let state_handler = Box::new(endpoint.clone());
 
                 // Fill in group call things
-                let delegate_http_client = HttpClient::start();
+                let delegate_http_client: Box<dyn Client + Send> = match http_impairments {
+                    Some(schedule) => {
+                        Box::new(ImpairedHttpClient::start(HttpClient::start(), schedule))
+                    }
+                    None => Box::new(HttpClient::start()),
+                };
                 let http_client = http::DelegatingClient::new(endpoint.clone());
                 let group_handler = Box::new(endpoint);
 
@@ -262,11 +269,17 @@ impl CallEndpoint {
         });
     }
 
-    pub fn add_deterministic_loss_network(&self, ip: &str, loss_rate: u8, packet_size_ms: i32) {
+    pub fn add_network(
+        &self,
+        ip: &str,
+        loss_rate: Option<u8>,
+        packet_size_ms: i32,
+        impairments: Option<ImpairmentSchedule>,
+    ) {
         let ip = ip.to_owned();
         self.actor.send(move |state| {
             if let Some(ref mut network) = state.network {
-                network.add_deterministic_loss(ip, loss_rate, packet_size_ms);
+                network.add_interface(ip, loss_rate, packet_size_ms, impairments);
             } else {
                 error!("Error: Injectable network not set properly!");
             }
@@ -34,6 +34,7 @@ impl CallStateHandler for CallEndpoint {
         remote_peer_id: &str,
         call_id: CallId,
         call_state: CallState,
+        _app_data: &[u8],
     ) -> Result<()> {
         info!(
             "State change in call from {}.{} to {}: now {:?}",
@@ -111,6 +111,7 @@ impl CallEndpoint {
                     state.outgoing_audio_track.clone(),
                     state.outgoing_video_track.clone(),
                     Some(state.incoming_video_sink.clone()),
+                    vec![],
                 )
                 .expect("create group call client");
 
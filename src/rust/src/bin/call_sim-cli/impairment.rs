@@ -0,0 +1,307 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Network impairments applied inside the client, to incoming packets and to HTTP requests.
+//!
+//! Unlike the emulation applied to the container's network, these change at precise offsets
+//! from the start of the client, and can be given per client on the command line, such as
+//! `--impairments "0:loss=15;10:loss=100;20:latency_ms=80,jitter_ms=20,rate_kbps=300"`.
+
+use std::{
+    cmp::Ordering,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use log::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use ringrtc::{
+    common::{
+        actor::{Actor, Stopper},
+        units::{DataRate, DataSize},
+    },
+    lite::http::{self, sim::HttpClient},
+};
+
+/// How long packets can wait for the rate limit before they are dropped instead, like a
+/// router's queue filling up.
+const MAX_QUEUE_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Impairment {
+    /// The percent of packets dropped at random. 100 is an outage.
+    pub loss_percent: u8,
+    pub latency: Duration,
+    /// Up to this much more latency, chosen at random for each packet.
+    pub jitter: Duration,
+    /// The most that can get through, or None for no limit.
+    pub rate: Option<DataRate>,
+}
+
+impl FromStr for Impairment {
+    type Err = String;
+
+    /// Parses comma-separated settings, such as `loss=15,latency_ms=80`. Anything not given
+    /// is unimpaired.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut impairment = Self::default();
+        for setting in s.split(',').filter(|setting| !setting.is_empty()) {
+            let (key, value) = setting
+                .split_once('=')
+                .ok_or_else(|| format!("expected key=value, got \"{setting}\""))?;
+            let value: u64 = value
+                .parse()
+                .map_err(|_| format!("invalid value for {key}: \"{value}\""))?;
+            match key {
+                "loss" if value <= 100 => impairment.loss_percent = value as u8,
+                "loss" => return Err(format!("loss must be a percent, got {value}")),
+                "latency_ms" => impairment.latency = Duration::from_millis(value),
+                "jitter_ms" => impairment.jitter = Duration::from_millis(value),
+                "rate_kbps" if value > 0 => impairment.rate = Some(DataRate::from_kbps(value)),
+                "rate_kbps" => return Err("rate_kbps must be more than 0".to_string()),
+                _ => return Err(format!("unknown impairment \"{key}\"")),
+            }
+        }
+        Ok(impairment)
+    }
+}
+
+/// Impairments that change over time, each starting at an offset from when the client started
+/// and lasting until the next.
+#[derive(Clone, Debug, Default)]
+pub struct ImpairmentSchedule {
+    steps: Vec<(Duration, Impairment)>,
+}
+
+impl ImpairmentSchedule {
+    /// The impairment at the time, or none before the first one starts.
+    pub fn at(&self, elapsed: Duration) -> Impairment {
+        self.steps
+            .iter()
+            .rev()
+            .find(|(offset, _)| *offset <= elapsed)
+            .map(|(_, impairment)| *impairment)
+            .unwrap_or_default()
+    }
+}
+
+impl FromStr for ImpairmentSchedule {
+    type Err = String;
+
+    /// Parses semicolon-separated steps of `{offset in seconds}:{impairment}`, such as
+    /// `0:loss=15;10:loss=100;20:` for 15% loss, then a 10 second outage, then nothing.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut steps = s
+            .split(';')
+            .filter(|step| !step.is_empty())
+            .map(|step| {
+                let (offset, impairment) = step
+                    .split_once(':')
+                    .ok_or_else(|| format!("expected offset:impairment, got \"{step}\""))?;
+                let offset = offset
+                    .parse()
+                    .map_err(|_| format!("invalid offset \"{offset}\""))?;
+                Ok((Duration::from_secs(offset), impairment.parse()?))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        steps.sort_by_key(|(offset, _)| *offset);
+        Ok(Self { steps })
+    }
+}
+
+/// Decides what happens to each packet according to a schedule.
+pub struct Impairer {
+    schedule: ImpairmentSchedule,
+    started: Instant,
+    rng: StdRng,
+    /// When everything already let through will have been transmitted at the rate limit.
+    rate_limited_until: Instant,
+}
+
+impl Impairer {
+    /// Starts following the schedule from now.
+    pub fn new(schedule: ImpairmentSchedule) -> Self {
+        let now = Instant::now();
+        Self {
+            schedule,
+            started: now,
+            rng: StdRng::from_entropy(),
+            rate_limited_until: now,
+        }
+    }
+
+    /// Returns when a packet of the size received now should be delivered, or None if it's
+    /// dropped.
+    pub fn deliver_at(&mut self, size: DataSize) -> Option<Instant> {
+        let now = Instant::now();
+        let impairment = self.schedule.at(now - self.started);
+
+        if self.rng.gen_range(0..100) < impairment.loss_percent {
+            return None;
+        }
+
+        let mut transmitted = now;
+        if let Some(rate) = impairment.rate {
+            let queued_until = self.rate_limited_until.max(now);
+            if queued_until - now > MAX_QUEUE_DELAY {
+                return None;
+            }
+            self.rate_limited_until = queued_until + size / rate;
+            transmitted = self.rate_limited_until;
+        }
+
+        let jitter = if impairment.jitter.is_zero() {
+            Duration::ZERO
+        } else {
+            self.rng.gen_range(Duration::ZERO..=impairment.jitter)
+        };
+        Some(transmitted + impairment.latency + jitter)
+    }
+}
+
+/// A packet held until it's due. Ordered by when it's due, and then by when it was received.
+pub struct DelayedPacket<T> {
+    pub deliver_at: Instant,
+    pub sequence: u64,
+    pub packet: T,
+}
+
+impl<T> PartialEq for DelayedPacket<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for DelayedPacket<T> {}
+
+impl<T> PartialOrd for DelayedPacket<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for DelayedPacket<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.deliver_at, self.sequence).cmp(&(other.deliver_at, other.sequence))
+    }
+}
+
+/// Wraps the sim HTTP client so that requests, such as to the SFU, are impaired too.
+///
+/// Each request is treated like a single packet: one that would be lost fails as if the
+/// connection had, and the rest are sent once they would have arrived.
+#[derive(Clone)]
+pub struct ImpairedHttpClient {
+    http_client: HttpClient,
+    impairer: Arc<Mutex<Impairer>>,
+    actor: Actor<()>,
+}
+
+impl ImpairedHttpClient {
+    pub fn start(http_client: HttpClient, schedule: ImpairmentSchedule) -> Self {
+        Self {
+            http_client,
+            impairer: Arc::new(Mutex::new(Impairer::new(schedule))),
+            actor: Actor::start("ImpairedHttpClient", Stopper::new(), |_| Ok(())).unwrap(),
+        }
+    }
+}
+
+impl http::Client for ImpairedHttpClient {
+    fn send_request(&self, request: http::Request, response_callback: http::ResponseCallback) {
+        let size = DataSize::from_bytes(request.body.as_ref().map_or(0, Vec::len) as u64);
+        let deliver_at = self
+            .impairer
+            .lock()
+            .expect("lock impairer")
+            .deliver_at(size);
+        match deliver_at {
+            None => {
+                info!("Dropped HTTP request to {}", request.url);
+                response_callback(None);
+            }
+            Some(deliver_at) => {
+                let http_client = self.http_client.clone();
+                self.actor.send_delayed(
+                    deliver_at.saturating_duration_since(Instant::now()),
+                    move |_| http_client.send_request(request, response_callback),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_impairment() {
+        assert_eq!(Ok(Impairment::default()), "".parse());
+        assert_eq!(
+            Ok(Impairment {
+                loss_percent: 15,
+                latency: Duration::from_millis(80),
+                jitter: Duration::from_millis(20),
+                rate: Some(DataRate::from_kbps(300)),
+            }),
+            "loss=15,latency_ms=80,jitter_ms=20,rate_kbps=300".parse()
+        );
+
+        for invalid in [
+            "loss",
+            "loss=",
+            "loss=-1",
+            "loss=101",
+            "latency_ms=fast",
+            "rate_kbps=0",
+            "bandwidth=300",
+        ] {
+            assert!(
+                invalid.parse::<Impairment>().is_err(),
+                "{invalid} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_schedule() {
+        let schedule: ImpairmentSchedule =
+            "0:loss=15;10:loss=100;20:latency_ms=80,jitter_ms=20,rate_kbps=300"
+                .parse()
+                .unwrap();
+        assert_eq!(15, schedule.at(Duration::ZERO).loss_percent);
+        assert_eq!(15, schedule.at(Duration::from_millis(9_999)).loss_percent);
+        assert_eq!(100, schedule.at(Duration::from_secs(10)).loss_percent);
+        assert_eq!(
+            Impairment {
+                loss_percent: 0,
+                latency: Duration::from_millis(80),
+                jitter: Duration::from_millis(20),
+                rate: Some(DataRate::from_kbps(300)),
+            },
+            schedule.at(Duration::from_secs(60))
+        );
+
+        // Steps can be given out of order, and nothing is impaired before the first.
+        let schedule: ImpairmentSchedule = "20:;5:loss=100".parse().unwrap();
+        assert_eq!(Impairment::default(), schedule.at(Duration::from_secs(1)));
+        assert_eq!(100, schedule.at(Duration::from_secs(5)).loss_percent);
+        assert_eq!(Impairment::default(), schedule.at(Duration::from_secs(20)));
+
+        for invalid in [
+            "loss=15",
+            "soon:loss=15",
+            "-1:loss=15",
+            "0:loss=15;10:rate_kbps=0",
+        ] {
+            assert!(
+                invalid.parse::<ImpairmentSchedule>().is_err(),
+                "{invalid} should be rejected"
+            );
+        }
+    }
+}
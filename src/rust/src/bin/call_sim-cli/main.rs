@@ -4,6 +4,7 @@
 //
 
 mod endpoint;
+mod impairment;
 mod network;
 mod relay;
 mod scenario;
@@ -22,7 +23,7 @@ use ringrtc::{
     core::group_call::GroupId,
     lite::sfu::{GroupMember, MembershipProof, UserId},
     webrtc::{
-        media::{AudioBandwidth, AudioEncoderConfig},
+        media::{AudioBandwidth, AudioEncoderConfig, AudioProfile},
         peer_connection_factory::{
             AudioConfig, AudioJitterBufferConfig, FileBasedAdmConfig, IceServer,
             RffiAudioDeviceModuleType,
@@ -31,7 +32,7 @@ use ringrtc::{
 };
 use scenario::ScenarioCallTypeConfig;
 
-use crate::scenario::ScenarioManager;
+use crate::{impairment::ImpairmentSchedule, scenario::ScenarioManager};
 
 #[derive(Parser, Debug)]
 struct Args {
@@ -132,6 +133,11 @@ struct Args {
     #[arg(long, action = clap::ArgAction::Set, default_value = "true")]
     fec: bool,
 
+    /// The audio profile to start with. The music profile ignores the other
+    /// audio encoding options.
+    #[arg(long, default_value_t = AudioProfile::Voice, value_enum)]
+    audio_profile: AudioProfile,
+
     /// Whether to use adaptation when encoding audio. Set to 0 to disable (default).
     #[arg(long, default_value_t = 0)]
     adaptation: i32,
@@ -205,6 +211,12 @@ struct Args {
     #[arg(long)]
     deterministic_loss: Option<u8>,
 
+    /// Loss, latency, jitter, and rate limits to apply to incoming packets and HTTP requests,
+    /// each from an offset in seconds, such as `0:loss=15;10:loss=100;20:latency_ms=80`.
+    /// This will turn on the injectable network using a UDP socket.
+    #[arg(long)]
+    impairments: Option<ImpairmentSchedule>,
+
     #[arg(short = 'g', long)]
     is_group_call: bool,
 
@@ -315,6 +327,7 @@ fn main() -> Result<()> {
             enable_dtx: args.dtx,
             enable_fec: args.fec,
         },
+        audio_profile: args.audio_profile,
         enable_tcc_audio: args.tcc,
         audio_jitter_buffer_config: AudioJitterBufferConfig {
             max_packets: args.audio_jitter_buffer_max_packets,
@@ -324,6 +337,7 @@ fn main() -> Result<()> {
         },
         audio_rtcp_report_interval_ms: args.audio_rtcp_report_interval_ms,
         enable_vp9: args.vp9,
+        ..Default::default()
     };
 
     let mut scenario = ScenarioManager::new()?;
@@ -358,6 +372,7 @@ fn main() -> Result<()> {
             output_video_height: args.output_video_height,
             video_output: args.output_video_file.map(Into::into),
             deterministic_loss: args.deterministic_loss,
+            impairments: args.impairments,
             call_type_config,
         },
     );
@@ -4,10 +4,14 @@
 //
 
 use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
     io,
     iter::{Cycle, StepBy},
     net::{SocketAddr, UdpSocket},
+    sync::mpsc,
     thread,
+    time::Instant,
 };
 
 use anyhow::anyhow;
@@ -17,13 +21,15 @@ use bitvec::{
 };
 use log::*;
 use ringrtc::{
-    common::Result,
+    common::{units::DataSize, Result},
     webrtc::{
         injectable_network::{self, InjectableNetwork},
         network::NetworkInterfaceType,
     },
 };
 
+use crate::impairment::{DelayedPacket, Impairer, ImpairmentSchedule};
+
 pub struct DeterministicLoss {
     pre_delay: u8,
     ignore_last_n: u8,
@@ -119,7 +125,7 @@ impl DeterministicLoss {
     }
 }
 
-/// Wrapper around InjectableNetwork that allows adding DeterministicLoss
+/// Wrapper around InjectableNetwork that allows adding DeterministicLoss and impairments
 pub struct DeterministicLossNetwork {
     injectable_network: InjectableNetwork,
     socket: Option<UdpSocket>,
@@ -133,12 +139,27 @@ impl DeterministicLossNetwork {
         }
     }
 
-    pub fn add_deterministic_loss(&mut self, ip: String, loss_rate: u8, packet_size_ms: i32) {
+    /// Adds the interface that all packets go through, dropping received packets according
+    /// to `loss_rate` (if given) and then impairing the rest according to `impairments`.
+    pub fn add_interface(
+        &mut self,
+        ip: String,
+        loss_rate: Option<u8>,
+        packet_size_ms: i32,
+        impairments: Option<ImpairmentSchedule>,
+    ) {
         let ip = ip.parse().expect("parse IP address");
-        let mut deterministic_loss = DeterministicLoss::new(loss_rate, packet_size_ms, 10)
-            .expect("parameters should be valid");
+        let mut deterministic_loss = loss_rate.map(|loss_rate| {
+            DeterministicLoss::new(loss_rate, packet_size_ms, 10)
+                .expect("parameters should be valid")
+        });
+        let mut impairer = impairments.map(Impairer::new);
 
         let network = self.injectable_network.clone();
+        let delayed_packet_sender = impairer
+            .as_ref()
+            .map(|_| Self::start_delayed_delivery(network.clone()));
+        let mut sequence = 0;
 
         // The injectable network currently makes an assumption that socket ports will
         // start from 2001. We also make that assumption, and we are only going to give
@@ -177,12 +198,31 @@ impl DeterministicLossNetwork {
                             );
                         }
 
-                        if !deterministic_loss.next_is_loss() {
-                            network.receive_udp(injectable_network::Packet {
-                                source: src_addr,
-                                dest: local_socket_addr,
-                                data: buf[..number_of_bytes].to_vec(),
-                            });
+                        if deterministic_loss
+                            .as_mut()
+                            .is_some_and(|deterministic_loss| deterministic_loss.next_is_loss())
+                        {
+                            continue;
+                        }
+
+                        let packet = injectable_network::Packet {
+                            source: src_addr,
+                            dest: local_socket_addr,
+                            data: buf[..number_of_bytes].to_vec(),
+                        };
+                        match (&mut impairer, &delayed_packet_sender) {
+                            (Some(impairer), Some(delayed_packet_sender)) => {
+                                let size = DataSize::from_bytes(number_of_bytes as u64);
+                                if let Some(deliver_at) = impairer.deliver_at(size) {
+                                    sequence += 1;
+                                    let _ = delayed_packet_sender.send(DelayedPacket {
+                                        deliver_at,
+                                        sequence,
+                                        packet,
+                                    });
+                                }
+                            }
+                            _ => network.receive_udp(packet),
                         }
                     }
                     Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
@@ -194,6 +234,51 @@ impl DeterministicLossNetwork {
         });
     }
 
+    /// Spawns a thread that delivers packets when they're due, which with jitter may not be
+    /// the order they were received in. Once the returned sender is dropped, it delivers
+    /// whatever is still queued when it's due, and then exits.
+    fn start_delayed_delivery(
+        network: InjectableNetwork,
+    ) -> mpsc::Sender<DelayedPacket<injectable_network::Packet>> {
+        let (sender, receiver) = mpsc::channel::<DelayedPacket<injectable_network::Packet>>();
+        thread::spawn(move || {
+            let mut queue: BinaryHeap<Reverse<DelayedPacket<injectable_network::Packet>>> =
+                BinaryHeap::new();
+            let mut disconnected = false;
+            loop {
+                let next_due = queue
+                    .peek()
+                    .map(|Reverse(next)| next.deliver_at.saturating_duration_since(Instant::now()));
+                if disconnected {
+                    match next_due {
+                        Some(next_due) => thread::sleep(next_due),
+                        None => break,
+                    }
+                } else {
+                    let received = match next_due {
+                        Some(next_due) => receiver.recv_timeout(next_due),
+                        None => receiver.recv().map_err(mpsc::RecvTimeoutError::from),
+                    };
+                    match received {
+                        Ok(delayed_packet) => queue.push(Reverse(delayed_packet)),
+                        Err(mpsc::RecvTimeoutError::Timeout) => {}
+                        Err(mpsc::RecvTimeoutError::Disconnected) => disconnected = true,
+                    }
+                }
+
+                let now = Instant::now();
+                while queue
+                    .peek()
+                    .is_some_and(|Reverse(next)| next.deliver_at <= now)
+                {
+                    let Reverse(due) = queue.pop().expect("peeked");
+                    network.receive_udp(due.packet);
+                }
+            }
+        });
+        sender
+    }
+
     pub fn stop_network(&self) {
         if let Some(socket) = &self.socket {
             socket
@@ -27,6 +27,7 @@ use tower::timeout::Timeout;
 
 use crate::{
     endpoint::{CallEndpoint, EventSync},
+    impairment::ImpairmentSchedule,
     relay::CallSimSignalingRelayClient,
     video::{self, I420Source},
 };
@@ -57,6 +58,7 @@ pub struct ScenarioConfig {
     pub output_video_height: u32,
     pub video_output: Option<PathBuf>,
     pub deterministic_loss: Option<u8>,
+    pub impairments: Option<ImpairmentSchedule>,
     pub call_type_config: ScenarioCallTypeConfig,
 }
 
@@ -136,6 +138,8 @@ impl ScenarioManager {
         });
 
         let packet_size_ms = call_config.audio_encoder_config.initial_packet_size_ms;
+        let use_injectable_network =
+            scenario_config.deterministic_loss.is_some() || scenario_config.impairments.is_some();
 
         let mut client = CallEndpoint::new(
             name,
@@ -146,12 +150,18 @@ impl ScenarioManager {
             &stopper,
             event_sync,
             video_sink,
-            scenario_config.deterministic_loss.is_some(),
+            use_injectable_network,
+            scenario_config.impairments.clone(),
         )
         .expect("Start client");
 
-        if let Some(loss_rate) = scenario_config.deterministic_loss {
-            client.add_deterministic_loss_network(ip, loss_rate, packet_size_ms);
+        if use_injectable_network {
+            client.add_network(
+                ip,
+                scenario_config.deterministic_loss,
+                packet_size_ms,
+                scenario_config.impairments.clone(),
+            );
         }
 
         let client_sync = ClientSync {
@@ -241,6 +241,8 @@ fn main() {
         incoming_video_sink: Some(Box::new(observer.clone())),
         ring_id: None,
         audio_levels_interval: None,
+        intervals: Default::default(),
+        ice_servers: vec![],
     })
     .unwrap();
 
@@ -0,0 +1,10 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Generates the Kotlin and Swift bindings for `lite::bindings`.
+
+fn main() {
+    uniffi::uniffi_bindgen_main()
+}
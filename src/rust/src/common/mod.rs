@@ -9,11 +9,18 @@ pub mod actor;
 pub mod jni_signature;
 pub mod units;
 
-use std::fmt;
+use std::{fmt, time::Duration};
 
-use crate::webrtc::{
-    media::AudioEncoderConfig,
-    peer_connection_factory::{AudioConfig, AudioJitterBufferConfig},
+use sha2::{Digest, Sha256};
+
+use crate::{
+    core::setup_latency::SetupLatencies,
+    webrtc::{
+        media::{AudioEncoderConfig, AudioLossProtectionConfig, AudioProfile},
+        peer_connection::JitterBufferConfig,
+        peer_connection_factory::{AudioConfig, AudioJitterBufferConfig},
+        stats_observer::{MediaUsage, StatsTimeSeries},
+    },
 };
 
 /// Common Result type, using `anyhow::Error` for Error.
@@ -70,6 +77,71 @@ impl From<u64> for CallId {
     }
 }
 
+/// A 128-bit call identifier, for telling calls apart across users.
+///
+/// The low half is the legacy CallId, which is still what routes signaling
+/// and keys calls locally, so older clients interoperate unchanged. The high
+/// half is an extension carried in the offer. It is derived from the sender's
+/// identity and fresh randomness, so two senders picking the same 64-bit
+/// CallId still end up with distinct wide IDs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WideCallId {
+    call_id: CallId,
+    extension: u64,
+}
+
+impl WideCallId {
+    const GENERATION_DOMAIN: &'static [u8] = b"RingRTC-WideCallId-v1";
+
+    pub fn new(call_id: CallId, extension: u64) -> Self {
+        Self { call_id, extension }
+    }
+
+    /// Generates the extension for an outgoing call with the given CallId,
+    /// mixing in the local user's identity and device.
+    pub fn generate(call_id: CallId, sender: &[u8], sender_device_id: DeviceId) -> Self {
+        let salt: [u8; 16] = rand::random();
+
+        let mut hash = Sha256::new();
+        hash.update(Self::GENERATION_DOMAIN);
+        hash.update((sender.len() as u32).to_be_bytes());
+        hash.update(sender);
+        hash.update(sender_device_id.to_be_bytes());
+        hash.update(call_id.as_u64().to_be_bytes());
+        hash.update(salt);
+        let digest = hash.finalize();
+
+        let mut extension = [0u8; 8];
+        extension.copy_from_slice(&digest[..8]);
+        Self::new(call_id, u64::from_be_bytes(extension))
+    }
+
+    /// The legacy 64-bit CallId.
+    pub fn call_id(&self) -> CallId {
+        self.call_id
+    }
+
+    /// The high 64 bits, as carried in the offer.
+    pub fn extension(&self) -> u64 {
+        self.extension
+    }
+
+    pub fn as_u128(&self) -> u128 {
+        ((self.extension as u128) << 64) | self.call_id.as_u64() as u128
+    }
+
+    /// Big-endian bytes, high half first.
+    pub fn to_bytes(&self) -> [u8; 16] {
+        self.as_u128().to_be_bytes()
+    }
+}
+
+impl fmt::Display for WideCallId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:032x}", self.as_u128())
+    }
+}
+
 /// Unique remote device identification number.
 pub type DeviceId = u32;
 
@@ -401,6 +473,72 @@ pub enum ApplicationEvent {
     /// Received an offer while already handling an active call and glare
     /// was detected.
     ReceivedOfferWithGlare,
+
+    /// The remote side has put the call on hold and stopped sending media.
+    RemoteHeld,
+
+    /// The remote side has taken the call off hold.
+    RemoteResumed,
+
+    /// Received an offer while in an accepted call with call waiting enabled.
+    /// The call waits until the application accepts or declines it.
+    IncomingCallWaiting,
+
+    /// The call ended because the callee declined it as busy.
+    EndedRemoteDeclinedBusy,
+
+    /// The call ended because the callee declined it as do-not-disturb.
+    EndedRemoteDeclinedDoNotDisturb,
+
+    /// The call ended because the callee declined it and sent a text reply.
+    EndedRemoteDeclinedTextReplySent,
+
+    /// The remote side moved the call to another of its devices, which is now
+    /// the one sending and receiving media.
+    RemoteHandedOff,
+
+    /// The call ended because another device of the local user took it over.
+    EndedHandedOff,
+
+    /// The callee started sending early media (a custom ringback tone or IVR
+    /// prompts), which is played out while the call is still ringing.
+    RemoteEarlyMedia,
+
+    /// The remote side asked to start sending video. The application should
+    /// answer with CallManager::respond_to_video_request().
+    RemoteVideoAddRequested,
+
+    /// The remote side started sending video after the local side agreed.
+    RemoteVideoAdded,
+
+    /// The remote side stopped sending video.
+    RemoteVideoRemoved,
+
+    /// The remote side agreed to the local request to send video, which
+    /// can now be started.
+    LocalVideoAddAccepted,
+
+    /// The remote side declined the local request to send video.
+    LocalVideoAddDeclined,
+
+    /// Nothing has been received from the remote side for the media stall
+    /// timeout, even though the connection appears to be up.
+    MediaStalled,
+
+    /// The call ended because nothing was received from the remote side for
+    /// the media stall timeout.
+    EndedMediaStalled,
+
+    /// The call ended because the remote side lost its connection.
+    EndedRemoteHangupNetworkFailure,
+
+    /// The call ended because the callee's application declined it by policy,
+    /// such as for a blocked contact.
+    EndedRemoteDeclinedByPolicy,
+
+    /// The call ended because the remote side stopped ringing after the ring
+    /// timeout, without anyone answering.
+    EndedRemoteHangupRingTimeout,
 }
 
 impl fmt::Display for ApplicationEvent {
@@ -762,35 +900,176 @@ impl DataMode {
     }
 }
 
+/// Explicit caps on the bitrates sent in a direct call, on top of the DataMode.
+///
+/// The caps are shared with the remote peer, which applies them to what it
+/// sends as well. A cap of None leaves that bitrate to the DataMode.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CallBitrateConfig {
+    /// The max bitrate of the audio encoder.
+    pub max_audio_bitrate: Option<units::DataRate>,
+    /// The max bitrate for video, on top of the audio.
+    pub max_video_bitrate: Option<units::DataRate>,
+    /// The max bitrate for all media.
+    pub max_total_bitrate: Option<units::DataRate>,
+}
+
+impl CallBitrateConfig {
+    /// Creates the caps from bits per second, where 0 means no cap.
+    pub fn from_bps(
+        max_audio_bitrate_bps: u64,
+        max_video_bitrate_bps: u64,
+        max_total_bitrate_bps: u64,
+    ) -> Self {
+        let cap = |bps| (bps > 0).then(|| units::DataRate::from_bps(bps));
+        Self {
+            max_audio_bitrate: cap(max_audio_bitrate_bps),
+            max_video_bitrate: cap(max_video_bitrate_bps),
+            max_total_bitrate: cap(max_total_bitrate_bps),
+        }
+    }
+
+    /// The lower of the two caps for each bitrate.
+    pub fn min(&self, other: &Self) -> Self {
+        let min = |a: Option<units::DataRate>, b: Option<units::DataRate>| match (a, b) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        Self {
+            max_audio_bitrate: min(self.max_audio_bitrate, other.max_audio_bitrate),
+            max_video_bitrate: min(self.max_video_bitrate, other.max_video_bitrate),
+            max_total_bitrate: min(self.max_total_bitrate, other.max_total_bitrate),
+        }
+    }
+}
+
+/// Local overrides of the max send bitrates of a direct call, for deployments
+/// with strict egress budgets.
+///
+/// Unlike CallBitrateConfig, these aren't shared with the remote peer, and a
+/// video override replaces the DataMode max rather than capping it. None
+/// leaves that bitrate to the DataMode and caps.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct MaxSendBitrate {
+    /// The max bitrate of the audio encoder.
+    pub audio: Option<units::DataRate>,
+    /// The max bitrate for camera video, on top of the audio.
+    pub camera_video: Option<units::DataRate>,
+    /// The max bitrate for screen sharing, on top of the audio.
+    pub screen_share: Option<units::DataRate>,
+}
+
+impl MaxSendBitrate {
+    /// Creates the overrides from bits per second, where 0 means none.
+    pub fn from_bps(audio_bps: u64, camera_video_bps: u64, screen_share_bps: u64) -> Self {
+        let max = |bps| (bps > 0).then(|| units::DataRate::from_bps(bps));
+        Self {
+            audio: max(audio_bps),
+            camera_video: max(camera_video_bps),
+            screen_share: max(screen_share_bps),
+        }
+    }
+}
+
+/// How the callee's media starts out when accepting a direct call.
+///
+/// The remote peer is told about the initial state along with the accept,
+/// so it never sees media that was meant to be off.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct AcceptOptions {
+    /// Join with the microphone muted.
+    pub audio_muted: bool,
+    /// Join with the camera off.
+    pub video_disabled: bool,
+}
+
+/// How long a direct call may go without being accepted before it times out.
+pub const DEFAULT_RING_TIMEOUT: Duration = Duration::from_secs(60);
+pub const MIN_RING_TIMEOUT: Duration = Duration::from_secs(1);
+pub const MAX_RING_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How long a direct call may take to connect after proceeding before it fails.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(60);
+pub const MIN_CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+pub const MAX_CONNECT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How long a connected direct call may go without receiving anything before
+/// its media is considered stalled. The remote side sends RTP data at least
+/// once a second, so shorter timeouts would be too eager.
+pub const MIN_MEDIA_STALL_TIMEOUT: Duration = Duration::from_secs(3);
+pub const MAX_MEDIA_STALL_TIMEOUT: Duration = Duration::from_secs(120);
+
 /// Low-level media configuration.
 #[derive(Clone, Debug)]
 pub struct CallConfig {
     /// The DataMode at the time of call initialization.
     pub data_mode: DataMode,
+    /// The bitrate caps at the time of call initialization.
+    pub bitrate_config: CallBitrateConfig,
     pub stats_interval_secs: u16,
     pub stats_initial_offset_secs: u16,
 
     pub audio_config: AudioConfig,
     pub audio_encoder_config: AudioEncoderConfig,
+    /// The profile the call starts with. Voice uses audio_encoder_config,
+    /// while Music uses the defaults for music. Can be changed mid-call.
+    pub audio_profile: AudioProfile,
     pub enable_tcc_audio: bool,
     pub audio_jitter_buffer_config: AudioJitterBufferConfig,
     pub audio_rtcp_report_interval_ms: i32,
+    /// How outgoing audio is protected against packet loss. Can be changed
+    /// mid-call.
+    pub audio_loss_protection: AudioLossProtectionConfig,
+    /// The delays for the call's incoming audio, which override those in
+    /// audio_jitter_buffer_config where set.
+    pub jitter_buffer: JitterBufferConfig,
 
     pub enable_vp9: bool,
+
+    /// If true, only relay (TURN) candidates are used so the remote peer never
+    /// learns the local IP address.
+    pub relay_only: bool,
+
+    /// If true, the callee may send audio before accepting the call (a custom
+    /// ringback tone or IVR prompts), which is played while ringing.
+    pub early_media: bool,
+
+    /// How long the call may go without being accepted, measured from when
+    /// the call was created. Clamped to [MIN_RING_TIMEOUT, MAX_RING_TIMEOUT].
+    pub ring_timeout: Duration,
+    /// How long the connection may take to be established, measured from
+    /// proceeding. Clamped to [MIN_CONNECT_TIMEOUT, MAX_CONNECT_TIMEOUT].
+    pub connect_timeout: Duration,
+    /// If set, how long the call may go without receiving anything while
+    /// connected before MediaStalled is reported. Clamped to
+    /// [MIN_MEDIA_STALL_TIMEOUT, MAX_MEDIA_STALL_TIMEOUT].
+    pub media_stall_timeout: Option<Duration>,
+    /// If true, the call is ended with EndedMediaStalled once its media stalls.
+    pub hang_up_on_media_stall: bool,
 }
 
 impl Default for CallConfig {
     fn default() -> Self {
         Self {
             data_mode: DataMode::Normal,
+            bitrate_config: Default::default(),
             stats_interval_secs: 10,
             stats_initial_offset_secs: 2,
             audio_config: Default::default(),
             audio_encoder_config: Default::default(),
+            audio_profile: AudioProfile::Voice,
             enable_tcc_audio: false,
             audio_jitter_buffer_config: Default::default(),
             audio_rtcp_report_interval_ms: 5000,
+            audio_loss_protection: Default::default(),
+            jitter_buffer: Default::default(),
             enable_vp9: true,
+            relay_only: false,
+            early_media: false,
+            ring_timeout: DEFAULT_RING_TIMEOUT,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            media_stall_timeout: None,
+            hang_up_on_media_stall: false,
         }
     }
 }
@@ -800,6 +1079,138 @@ impl CallConfig {
         self.data_mode = data_mode;
         self
     }
+
+    pub fn with_audio_profile(mut self, audio_profile: AudioProfile) -> Self {
+        self.audio_profile = audio_profile;
+        self
+    }
+
+    /// The audio encoder config to use for the given profile.
+    pub fn audio_encoder_config_for(&self, profile: AudioProfile) -> AudioEncoderConfig {
+        match profile {
+            AudioProfile::Voice => self.audio_encoder_config.clone(),
+            AudioProfile::Music => AudioEncoderConfig::for_profile(profile),
+        }
+    }
+
+    pub fn with_bitrate_config(mut self, bitrate_config: CallBitrateConfig) -> Self {
+        self.bitrate_config = bitrate_config;
+        self
+    }
+
+    pub fn with_relay_only(mut self, relay_only: bool) -> Self {
+        self.relay_only = relay_only;
+        self
+    }
+
+    pub fn with_early_media(mut self, early_media: bool) -> Self {
+        self.early_media = early_media;
+        self
+    }
+
+    pub fn with_audio_loss_protection(
+        mut self,
+        audio_loss_protection: AudioLossProtectionConfig,
+    ) -> Self {
+        self.audio_loss_protection = audio_loss_protection;
+        self
+    }
+
+    pub fn with_jitter_buffer(mut self, jitter_buffer: JitterBufferConfig) -> Self {
+        self.jitter_buffer = jitter_buffer;
+        self
+    }
+
+    pub fn with_ring_timeout(mut self, ring_timeout: Duration) -> Self {
+        self.ring_timeout = ring_timeout;
+        self
+    }
+
+    pub fn with_connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = connect_timeout;
+        self
+    }
+
+    pub fn with_media_stall_timeout(mut self, timeout: Duration, hang_up: bool) -> Self {
+        self.media_stall_timeout = Some(timeout);
+        self.hang_up_on_media_stall = hang_up;
+        self
+    }
+
+    /// The ring timeout, within bounds.
+    pub fn bounded_ring_timeout(&self) -> Duration {
+        self.ring_timeout.clamp(MIN_RING_TIMEOUT, MAX_RING_TIMEOUT)
+    }
+
+    /// The connect timeout, within bounds.
+    pub fn bounded_connect_timeout(&self) -> Duration {
+        self.connect_timeout
+            .clamp(MIN_CONNECT_TIMEOUT, MAX_CONNECT_TIMEOUT)
+    }
+
+    /// The media stall timeout, within bounds, if the watchdog is enabled.
+    pub fn bounded_media_stall_timeout(&self) -> Option<Duration> {
+        self.media_stall_timeout
+            .map(|timeout| timeout.clamp(MIN_MEDIA_STALL_TIMEOUT, MAX_MEDIA_STALL_TIMEOUT))
+    }
+}
+
+/// A short code derived from the keys negotiated for a 1:1 call.
+///
+/// Both sides compute the same code unless someone tampered with the
+/// signaling, so users can read it to each other to verify the call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct VerificationCode(u32);
+
+impl VerificationCode {
+    pub const DIGITS: usize = 6;
+    const MODULUS: u64 = 1_000_000;
+
+    /// Reduces derived key material to a code.
+    pub fn from_key_material(bytes: [u8; 8]) -> Self {
+        Self((u64::from_be_bytes(bytes) % Self::MODULUS) as u32)
+    }
+
+    /// The code as a number below 10^DIGITS.
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for VerificationCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:01$}", self.0, Self::DIGITS)
+    }
+}
+
+/// Totals for a call that has ended, so applications can populate call
+/// history and telemetry without keeping their own counters.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CallSummary {
+    /// How long the local device was connected to the call, or zero if it
+    /// never got that far.
+    pub duration: Duration,
+    pub media_usage: MediaUsage,
+    /// The largest number of devices in the call at once, including the local
+    /// device. Always 2 for a 1:1 call that connected.
+    pub max_participant_count: u32,
+    pub stats_time_series: StatsTimeSeries,
+    pub setup_latencies: SetupLatencies,
+}
+
+/// How a glare was resolved, where both sides called each other at about the
+/// same time and each received the other's offer while its own call was active.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GlareResolution {
+    /// The call that was active locally when the remote offer arrived.
+    pub local_call_id: CallId,
+    /// The call offered by the remote.
+    pub remote_call_id: CallId,
+    /// The call that continues, or None if both calls were ended.
+    pub winning_call_id: Option<CallId>,
+    /// True if the local outgoing call was ended and the remote's call continues
+    /// in its place as a regular incoming call.
+    pub converted_to_incoming: bool,
 }
 
 // Benchmarking component list.
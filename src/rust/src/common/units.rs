@@ -55,6 +55,14 @@ impl DataRate {
     }
 }
 
+impl Add<DataRate> for DataRate {
+    type Output = DataRate;
+
+    fn add(self, other: DataRate) -> DataRate {
+        DataRate::per_second(self.size_per_second + other.size_per_second)
+    }
+}
+
 impl Mul<Duration> for DataRate {
     type Output = DataSize;
 
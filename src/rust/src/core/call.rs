@@ -14,7 +14,7 @@ use std::{
         Arc, Condvar, Mutex, MutexGuard,
     },
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use x25519_dalek::StaticSecret;
@@ -22,8 +22,8 @@ use x25519_dalek::StaticSecret;
 use crate::{
     common::{
         actor::{Actor, Stopper},
-        ApplicationEvent, CallConfig, CallDirection, CallId, CallMediaType, CallState, DeviceId,
-        Result,
+        AcceptOptions, ApplicationEvent, CallConfig, CallDirection, CallId, CallMediaType,
+        CallState, CallSummary, DeviceId, Result, WideCallId,
     },
     core::{
         call_fsm::{CallEvent, CallStateMachine},
@@ -31,12 +31,17 @@ use crate::{
         call_mutex::CallMutex,
         connection::{Connection, ConnectionObserverEvent, ConnectionType},
         platform::Platform,
+        setup_latency::{SetupLatencyTracker, SetupMilestone},
         signaling,
     },
     error::RingRtcError,
+    lite::{
+        crash_dump,
+        diagnostics::{self, DiagnosticEventKind},
+    },
     webrtc::{
         ice_gatherer::IceGatherer, media::MediaStream, peer_connection::AudioLevel,
-        peer_connection_observer::NetworkRoute,
+        peer_connection_observer::NetworkRoute, stats_observer::QualityLimitationReason,
     },
 };
 
@@ -63,6 +68,17 @@ where
     offer: signaling::Offer,
 }
 
+/// ICE gathering started for an incoming call before the application
+/// proceeded with it.
+struct PreGatheringState<T>
+where
+    T: Platform,
+{
+    // Kept alive with the call, like the parent when forking.
+    connection: Connection<T>,
+    ice_gatherer: IceGatherer,
+}
+
 /// Represents the set of connections between a local client and
 /// 1-to-many remote peer devices for the same call recipient.
 pub struct Call<T>
@@ -93,6 +109,10 @@ where
     fsm_sender: SyncSender<(Call<T>, CallEvent)>,
     /// Allows stopping the call timeout early.
     timeout_stopper: Stopper,
+    /// When the call was created, from which the ring timeout is measured.
+    created: Instant,
+    /// How long the call may go without being accepted.
+    ring_timeout: Arc<CallMutex<Duration>>,
     /// Collection of connections for this call
     connection_map: Arc<CallMutex<HashMap<DeviceId, Connection<T>>>>,
     /// Condition variable used at termination to quiesce and synchronize the FSM.
@@ -103,10 +123,30 @@ where
     /// It's a little ugly, but we need to handle the case where we get ConnectionState::ConnectedBeforeAccepted
     /// before a ConnectionState::ConnectingAfterAccepted and not notify the application twice.
     did_notify_application_of_remote_ringing: Arc<AtomicBool>,
+    /// Whether the application already accepted this incoming call, so it should be
+    /// accepted as soon as it connects instead of ringing.
+    accept_when_connected: Arc<AtomicBool>,
+    /// Whether this outgoing call takes over an accepted call with the same
+    /// CallId from another device of the local user.
+    handoff: Arc<AtomicBool>,
+    /// The remote device taking over this accepted call, while the connection
+    /// to it is being set up alongside the active connection.
+    handoff_device_id: Arc<CallMutex<Option<DeviceId>>>,
+    /// The high half of the 128-bit call ID, generated locally for outgoing
+    /// calls and taken from the offer for incoming ones. None if the caller
+    /// is a client that doesn't send one.
+    call_id_extension: Arc<CallMutex<Option<u64>>>,
     /// When doing call forking, the parent that must be kept alive to keep
     /// ICE candidates and signaling alive.
     /// And we also need to keep around that parent's offer that it created.
     forking: Arc<CallMutex<Option<ForkingState<T>>>>,
+    /// The ICE gathering started before proceeding, if any.  Incoming calls only.
+    pre_gathering: Arc<CallMutex<Option<PreGatheringState<T>>>>,
+    /// Whether the application call context was set for pre-gathering, so
+    /// the one passed to proceed may replace it.
+    pre_gathering_call_context: Arc<AtomicBool>,
+    /// How long it took to ring, connect, and get the first video frame.
+    setup_latency: Arc<SetupLatencyTracker>,
 }
 
 impl<T> fmt::Display for Call<T>
@@ -181,13 +221,22 @@ where
             pending_call: Arc::clone(&self.pending_call),
             fsm_sender: self.fsm_sender.clone(),
             timeout_stopper: self.timeout_stopper.clone(),
+            created: self.created,
+            ring_timeout: Arc::clone(&self.ring_timeout),
             connection_map: Arc::clone(&self.connection_map),
             terminate_condvar: Arc::clone(&self.terminate_condvar),
             did_send_offer: Arc::clone(&self.did_send_offer),
             did_notify_application_of_remote_ringing: Arc::clone(
                 &self.did_notify_application_of_remote_ringing,
             ),
+            accept_when_connected: Arc::clone(&self.accept_when_connected),
+            handoff: Arc::clone(&self.handoff),
+            handoff_device_id: Arc::clone(&self.handoff_device_id),
+            call_id_extension: Arc::clone(&self.call_id_extension),
             forking: Arc::clone(&self.forking),
+            pre_gathering: Arc::clone(&self.pre_gathering),
+            pre_gathering_call_context: Arc::clone(&self.pre_gathering_call_context),
+            setup_latency: Arc::clone(&self.setup_latency),
         }
     }
 }
@@ -214,6 +263,9 @@ where
             .name("fsm-worker".to_string())
             .spawn(move || call_fsm.run())?;
 
+        let setup_latency = SetupLatencyTracker::default();
+        setup_latency.start();
+
         let call = Self {
             call_manager: Arc::new(CallMutex::new(call_manager, "call_manager")),
             call_id,
@@ -227,26 +279,42 @@ where
             pending_call: Arc::new(CallMutex::new(None, "pending_call")),
             fsm_sender,
             timeout_stopper: Stopper::new(),
+            created: Instant::now(),
+            ring_timeout: Arc::new(CallMutex::new(Duration::ZERO, "ring_timeout")),
             connection_map: Arc::new(CallMutex::new(HashMap::new(), "connection_map")),
             terminate_condvar: Arc::new((Mutex::new(false), Condvar::new())),
             did_send_offer: Arc::new(AtomicBool::new(false)),
             did_notify_application_of_remote_ringing: Arc::new(AtomicBool::new(false)),
+            accept_when_connected: Arc::new(AtomicBool::new(false)),
+            handoff: Arc::new(AtomicBool::new(false)),
+            handoff_device_id: Arc::new(CallMutex::new(None, "handoff_device_id")),
+            call_id_extension: Arc::new(CallMutex::new(None, "call_id_extension")),
             forking: Arc::new(CallMutex::new(None, "forking")),
+            pre_gathering: Arc::new(CallMutex::new(None, "pre_gathering")),
+            pre_gathering_call_context: Arc::new(AtomicBool::new(false)),
+            setup_latency: Arc::new(setup_latency),
         };
 
         Ok(call)
     }
 
     /// Start a timer to terminate the call if setup takes too long.
+    ///
+    /// The period is measured from when the call was created. Starting the
+    /// timer again moves the deadline, and earlier timers firing before it
+    /// are ignored.
     pub fn start_timeout_timer(&self, time_out_period: Duration) -> Result<()> {
+        *self.ring_timeout.lock()? = time_out_period;
         if !time_out_period.is_zero() {
             let mut call_clone = self.clone();
             Actor::start("fsm-timeout", self.timeout_stopper.clone(), |_| Ok(()))?.send_delayed(
-                time_out_period,
+                time_out_period.saturating_sub(self.created.elapsed()),
                 move |_| {
-                    call_clone
-                        .inject_call_timeout()
-                        .unwrap_or_else(|e| error!("Inject call timeout failed: {:?}", e))
+                    if call_clone.ring_timeout_expired().unwrap_or(true) {
+                        call_clone
+                            .inject_call_timeout()
+                            .unwrap_or_else(|e| error!("Inject call timeout failed: {:?}", e))
+                    }
                 },
             )
         }
@@ -254,16 +322,83 @@ where
         Ok(())
     }
 
+    fn ring_timeout_expired(&self) -> Result<bool> {
+        let ring_timeout = *self.ring_timeout.lock()?;
+        Ok(!ring_timeout.is_zero() && self.created.elapsed() >= ring_timeout)
+    }
+
+    /// Start a timer to fail the call if it has not connected in time.
+    pub fn start_connect_timer(&self, connect_timeout: Duration) -> Result<()> {
+        let mut call_clone = self.clone();
+        Actor::start("fsm-connect-timeout", self.timeout_stopper.clone(), |_| {
+            Ok(())
+        })?
+        .send_delayed(connect_timeout, move |_| {
+            call_clone
+                .inject_connect_timeout()
+                .unwrap_or_else(|e| error!("Inject connect timeout failed: {:?}", e))
+        });
+
+        Ok(())
+    }
+
     /// Return the Call identifier.
     pub fn call_id(&self) -> CallId {
         self.call_id
     }
 
+    /// Accept the call as soon as it connects rather than ringing.
+    pub fn set_accept_when_connected(&self) {
+        self.accept_when_connected.store(true, Ordering::Release);
+    }
+
+    /// Whether the call should be accepted as soon as it connects.
+    pub fn accept_when_connected(&self) -> bool {
+        self.accept_when_connected.load(Ordering::Acquire)
+    }
+
+    /// Mark this outgoing call as taking over an accepted call from another
+    /// device of the local user.
+    pub fn set_handoff(&self) {
+        self.handoff.store(true, Ordering::Release);
+    }
+
+    /// Whether this outgoing call takes over an accepted call from another
+    /// device of the local user.
+    pub fn is_handoff(&self) -> bool {
+        self.handoff.load(Ordering::Acquire)
+    }
+
+    /// Set the high half of the 128-bit call ID.
+    pub fn set_call_id_extension(&self, extension: u64) -> Result<()> {
+        *self.call_id_extension.lock()? = Some(extension);
+        Ok(())
+    }
+
+    /// Return the 128-bit call ID, or None if it is not known, such as for an
+    /// incoming call from a client that doesn't send one.
+    pub fn wide_call_id(&self) -> Result<Option<WideCallId>> {
+        Ok(self
+            .call_id_extension
+            .lock()?
+            .map(|extension| WideCallId::new(self.call_id, extension)))
+    }
+
     /// Return the Call direction.
     pub fn direction(&self) -> CallDirection {
         self.direction
     }
 
+    /// Return the media type the call was started with.
+    pub fn media_type(&self) -> CallMediaType {
+        self.media_type
+    }
+
+    /// Return the tracker of setup milestones, shared by the call's connections.
+    pub fn setup_latency(&self) -> Arc<SetupLatencyTracker> {
+        Arc::clone(&self.setup_latency)
+    }
+
     /// Return the current Call state.
     pub fn state(&self) -> Result<CallState> {
         let state = self.state.lock()?;
@@ -273,6 +408,24 @@ where
     /// Update the current Call state.
     pub fn set_state(&self, new_state: CallState) -> Result<()> {
         let mut state = self.state.lock()?;
+        if *state != new_state {
+            diagnostics::record(
+                DiagnosticEventKind::StateTransition,
+                format!("call: {:?}", new_state),
+            );
+            if new_state == CallState::ConnectedAndAccepted {
+                self.setup_latency.record(SetupMilestone::Connected);
+            }
+            if new_state == CallState::Terminated {
+                crash_dump::remove_call_state("direct", self.call_id.as_u64());
+            } else {
+                crash_dump::set_call_state(
+                    "direct",
+                    self.call_id.as_u64(),
+                    format_args!("{:?}", new_state),
+                );
+            }
+        }
         *state = new_state;
         Ok(())
     }
@@ -298,6 +451,11 @@ where
         )
     }
 
+    /// Return the remote device taking over this call, if a handoff is in progress.
+    pub fn handoff_device_id(&self) -> Result<Option<DeviceId>> {
+        Ok(*self.handoff_device_id.lock()?)
+    }
+
     /// Return the active Connection this call is associated with.
     pub fn active_connection(&self) -> Result<Connection<T>> {
         let connection_map = self.connection_map.lock()?;
@@ -307,6 +465,24 @@ where
         }
     }
 
+    /// Return the totals for the call so far, for reporting when it ends.
+    ///
+    /// A call that never got an active connection only ever had the local
+    /// device in it.
+    pub fn call_summary(&self) -> CallSummary {
+        let summary = self
+            .active_connection()
+            .and_then(|connection| connection.call_summary())
+            .unwrap_or_else(|_| CallSummary {
+                max_participant_count: 1,
+                ..Default::default()
+            });
+        CallSummary {
+            setup_latencies: self.setup_latency.latencies(),
+            ..summary
+        }
+    }
+
     /// For an incoming call, create a PendingCall structure for
     /// holding the offer and ICE candidates sent by the remote side
     /// *before* the application has formally decided to accept the
@@ -330,6 +506,88 @@ where
         Ok(())
     }
 
+    /// For an accepted call, start a connection to another device of the
+    /// remote user that is taking the call over. The active connection keeps
+    /// going until the new one is connected.
+    pub fn handle_received_handoff_offer(&self, received: signaling::ReceivedOffer) -> Result<()> {
+        let remote_device_id = received.sender_device_id;
+        if let Some(handoff_device_id) = self.handoff_device_id()? {
+            return Err(RingRtcError::HandoffAlreadyInProgress(handoff_device_id).into());
+        }
+        info!(
+            "handle_received_handoff_offer(): id: {}",
+            self.call_id().format(remote_device_id)
+        );
+
+        let active_connection = self.active_connection()?;
+        let mut call_manager = self.call_manager()?;
+        let mut connection = call_manager.create_connection(
+            self,
+            remote_device_id,
+            ConnectionType::Incoming,
+            received.offer.latest_version(),
+            active_connection.call_config().clone(),
+            active_connection.audio_levels_interval()?,
+        )?;
+        let answer = connection.start_incoming(received, Vec::new(), None)?;
+        call_manager.send_answer(
+            self.clone(),
+            connection.clone(),
+            signaling::SendAnswer {
+                receiver_device_id: remote_device_id,
+                answer,
+            },
+        )?;
+
+        self.connection_map
+            .lock()?
+            .insert(remote_device_id, connection);
+        *self.handoff_device_id.lock()? = Some(remote_device_id);
+        Ok(())
+    }
+
+    /// Make the connection to the device taking over this call the active one.
+    pub fn hand_off_to(&self, remote_device_id: DeviceId) -> Result<()> {
+        *self.handoff_device_id.lock()? = None;
+        *self.active_device_id.lock()? = Some(remote_device_id);
+        Ok(())
+    }
+
+    /// Enable media through the connection that took over the call and notify
+    /// the application.
+    pub fn activate_handed_off_connection(&self) -> Result<()> {
+        let mut connection = self.active_connection()?;
+        connection.inject_accept(AcceptOptions::default())?;
+        connection.enable_media()?;
+        connection.start_tick()?;
+        self.notify_application(ApplicationEvent::RemoteHandedOff)?;
+        self.notify_network_route_changed(connection.network_route()?)?;
+        Ok(())
+    }
+
+    /// Give up on a handoff to the given device, leaving the active
+    /// connection as it was.
+    pub fn abandon_handoff(&self, remote_device_id: DeviceId) -> Result<()> {
+        {
+            let mut handoff_device_id = self.handoff_device_id.lock()?;
+            if *handoff_device_id != Some(remote_device_id) {
+                return Ok(());
+            }
+            *handoff_device_id = None;
+        }
+        info!(
+            "abandon_handoff(): id: {}",
+            self.call_id().format(remote_device_id)
+        );
+
+        let connection = self.connection_map.lock()?.remove(&remote_device_id);
+        if let Some(mut connection) = connection {
+            // blocks as connection FSM shutsdown
+            connection.terminate()?;
+        }
+        Ok(())
+    }
+
     /// Return the local Device Id associated with this call.
     pub fn local_device_id(&self) -> DeviceId {
         self.local_device_id
@@ -338,10 +596,26 @@ where
     /// Store the application specific CallContext associated with this call.
     pub fn set_call_context(&self, call_context: <T as Platform>::AppCallContext) -> Result<()> {
         let mut app_call_context = self.app_call_context.lock()?;
-        match *app_call_context {
-            Some(_) => return Err(RingRtcError::AppCallContextAlreadySet(self.call_id).into()),
-            None => *app_call_context = Some(call_context),
+        // Only the context used for pre-gathering may be replaced.
+        let replaceable = self
+            .pre_gathering_call_context
+            .swap(false, Ordering::AcqRel);
+        if app_call_context.is_some() && !replaceable {
+            return Err(RingRtcError::AppCallContextAlreadySet(self.call_id).into());
         }
+        *app_call_context = Some(call_context);
+        Ok(())
+    }
+
+    /// Set the application call context used to pre-gather ICE candidates,
+    /// which the one passed to proceed replaces.
+    pub fn set_pre_gathering_call_context(
+        &self,
+        call_context: <T as Platform>::AppCallContext,
+    ) -> Result<()> {
+        self.set_call_context(call_context)?;
+        self.pre_gathering_call_context
+            .store(true, Ordering::Release);
         Ok(())
     }
 
@@ -393,15 +667,67 @@ where
     }
 
     /// Enable media flowing through the active connection and notify the application.
-    pub fn accept_locally(&self) -> Result<()> {
+    pub fn accept_locally(&self, options: AcceptOptions) -> Result<()> {
         let mut connection = self.active_connection()?;
-        connection.inject_accept()?;
+        connection.inject_accept(options)?;
         connection.enable_media()?;
         connection.start_tick()?;
         self.notify_application(ApplicationEvent::LocalAccepted)?;
         Ok(())
     }
 
+    /// Restart ICE on the active connection.
+    pub fn restart_ice(&self) -> Result<()> {
+        self.active_connection()?.restart_ice()
+    }
+
+    /// Send application data to the remote peer on the active connection.
+    pub fn send_app_data(&self, data: Vec<u8>, reliable: bool) -> Result<()> {
+        self.active_connection()?.send_app_data(data, reliable)
+    }
+
+    /// Ask the remote peer to agree to starting to send video on the active
+    /// connection, or stop sending it.
+    pub fn request_video(&self, add: bool) -> Result<()> {
+        self.active_connection()?.request_video(add)
+    }
+
+    /// Answer the remote peer's request to start sending video, and notify
+    /// the application once it is allowed.
+    pub fn respond_to_video_request(&self, accepted: bool) -> Result<()> {
+        if !self
+            .active_connection()?
+            .respond_to_video_request(accepted)?
+        {
+            warn!("respond_to_video_request(): no request to respond to");
+            return Ok(());
+        }
+        if accepted {
+            self.notify_application(ApplicationEvent::RemoteVideoAdded)?;
+        }
+        Ok(())
+    }
+
+    /// Start sending early media on the active connection, if the caller
+    /// offered to play it.
+    pub fn start_early_media(&self) -> Result<()> {
+        let connection = self.active_connection()?;
+        if !connection.remote_allows_early_media()? {
+            warn!("start_early_media(): ignoring since the caller didn't offer early media");
+            return Ok(());
+        }
+        connection.start_early_media()
+    }
+
+    /// Restart ICE after the active connection dropped, if enabled in the CallManager.
+    pub fn handle_reconnecting(&self) -> Result<()> {
+        if self.call_manager()?.restart_ice_on_disconnect()? {
+            info!("handle_reconnecting(): restarting ICE");
+            self.restart_ice()?;
+        }
+        Ok(())
+    }
+
     /// Enable media flowing through the active connection and notify the application.
     pub fn accept_remotely(&self) -> Result<()> {
         let connection = self.active_connection()?;
@@ -418,6 +744,12 @@ where
     ///
     /// This is a pass through to the CallManager.
     pub fn notify_application(&self, event: ApplicationEvent) -> Result<()> {
+        if matches!(
+            event,
+            ApplicationEvent::LocalRinging | ApplicationEvent::RemoteRinging
+        ) {
+            self.setup_latency.record(SetupMilestone::Ringing);
+        }
         if event == ApplicationEvent::RemoteRinging {
             let did_notify = self
                 .did_notify_application_of_remote_ringing
@@ -468,6 +800,29 @@ where
         call_manager.notify_low_bandwidth_for_video(&remote_peer, recovered)
     }
 
+    /// Notify application that outgoing video started or stopped being adapted
+    ///
+    /// This is a pass through to the CallManager.
+    pub fn notify_video_adaptation_changed(
+        &self,
+        reason: Option<QualityLimitationReason>,
+    ) -> Result<()> {
+        let call_manager = self.call_manager()?;
+        let remote_peer = self.remote_peer()?;
+
+        call_manager.notify_video_adaptation_changed(&remote_peer, reason)
+    }
+
+    /// Notify application of data sent by the remote peer
+    ///
+    /// This is a pass through to the CallManager.
+    pub fn notify_app_data(&self, data: Vec<u8>) -> Result<()> {
+        let call_manager = self.call_manager()?;
+        let remote_peer = self.remote_peer()?;
+
+        call_manager.notify_app_data(&remote_peer, data)
+    }
+
     /// Notify call manager of an internal error.
     ///
     pub fn internal_error(&self, error: anyhow::Error) -> Result<()> {
@@ -541,7 +896,7 @@ where
     /// - handle the previously stored pending Offer and ICE Candidates
     pub fn proceed(
         &mut self,
-        call_config: CallConfig,
+        mut call_config: CallConfig,
         audio_levels_interval: Option<Duration>,
     ) -> Result<()> {
         info!("proceed():");
@@ -556,6 +911,24 @@ where
                     let remote_device_id = pending_call.received.sender_device_id;
                     info!("proceed(): incoming: remote_device: {}", remote_device_id);
 
+                    // Match the caller so neither side reveals its IP address.
+                    let remote_relay_only = pending_call
+                        .received
+                        .offer
+                        .to_v4()
+                        .and_then(|v4| v4.relay_only)
+                        .unwrap_or(false);
+                    if remote_relay_only && !call_config.relay_only {
+                        info!("proceed(): remote asked for relay only, so using relay only too");
+                        call_config.relay_only = true;
+                    }
+
+                    let pre_gathering = self.pre_gathering.lock()?;
+                    if pre_gathering.is_some() && !call_config.relay_only {
+                        info!("proceed(): ICE was pre-gathered, so using relay only");
+                        call_config.relay_only = true;
+                    }
+
                     let mut connection = call_manager.create_connection(
                         self,
                         remote_device_id,
@@ -564,8 +937,13 @@ where
                         call_config,
                         audio_levels_interval,
                     )?;
-                    let answer = connection
-                        .start_incoming(pending_call.received, pending_call.ice_candidates)?;
+                    let answer = connection.start_incoming(
+                        pending_call.received,
+                        pending_call.ice_candidates,
+                        pre_gathering
+                            .as_ref()
+                            .map(|pre_gathering| &pre_gathering.ice_gatherer),
+                    )?;
                     call_manager.send_answer(
                         self.clone(),
                         connection.clone(),
@@ -574,6 +952,14 @@ where
                             answer,
                         },
                     )?;
+                    // Send the candidates gathered so far after the answer.
+                    if let Some(pre_gathering) = pre_gathering.as_ref() {
+                        connection.buffer_local_ice_candidates(
+                            pre_gathering
+                                .connection
+                                .take_buffered_local_ice_candidates()?,
+                        )?;
+                    }
 
                     let mut connection_map = self.connection_map.lock()?;
                     connection_map.insert(remote_device_id, connection);
@@ -597,8 +983,14 @@ where
                     call_config,
                     audio_levels_interval,
                 )?;
-                let (local_secret, ice_gatherer, offer) =
+                let (local_secret, ice_gatherer, mut offer) =
                     parent_connection.start_outgoing_parent(self.media_type)?;
+                if self.is_handoff() {
+                    offer = offer.to_handoff()?;
+                }
+                if let Some(extension) = *self.call_id_extension.lock()? {
+                    offer = offer.with_call_id_extension(extension)?;
+                }
 
                 // Keep around so that it's not closed until all the connections are closed.
                 *(self.forking.lock()?) = Some(ForkingState {
@@ -616,6 +1008,50 @@ where
         Ok(())
     }
 
+    /// Start gathering relay ICE candidates for an incoming call before
+    /// proceeding, so that TURN allocation doesn't hold up connecting.
+    ///
+    /// Nothing is signaled until proceed(), which answers using the gathered
+    /// candidates.
+    pub fn pre_gather_ice(&self) -> Result<()> {
+        info!("pre_gather_ice():");
+
+        let (remote_device_id, version) = match self.pending_call.lock()?.as_ref() {
+            Some(pending_call) => (
+                pending_call.received.sender_device_id,
+                pending_call.received.offer.latest_version(),
+            ),
+            None => {
+                return Err(RingRtcError::OptionValueNotSet(
+                    "pre_gather_ice()".to_owned(),
+                    "pending_offer".to_owned(),
+                )
+                .into());
+            }
+        };
+
+        let mut pre_gathering = self.pre_gathering.lock()?;
+        if pre_gathering.is_some() {
+            warn!("pre_gather_ice(): already pre-gathering");
+            return Ok(());
+        }
+
+        let mut connection = self.call_manager()?.create_connection(
+            self,
+            remote_device_id,
+            ConnectionType::IncomingPreGather,
+            version,
+            CallConfig::default().with_relay_only(true),
+            None,
+        )?;
+        let ice_gatherer = connection.start_incoming_pre_gather()?;
+        *pre_gathering = Some(PreGatheringState {
+            connection,
+            ice_gatherer,
+        });
+        Ok(())
+    }
+
     /// Handle the received answer.
     pub fn received_answer(&self, received: signaling::ReceivedAnswer) -> Result<()> {
         let sender_device_id = received.sender_device_id;
@@ -635,7 +1071,7 @@ where
                 info!("received_answer from device {}; forking enabled, so inject into connection_map", sender_device_id);
                 let call_manager = self.call_manager()?;
                 let call_config = forking.parent_connection.call_config();
-                let audio_levels_interval = forking.parent_connection.audio_levels_interval();
+                let audio_levels_interval = forking.parent_connection.audio_levels_interval()?;
                 let mut child_connection = call_manager.create_connection(
                     self,
                     sender_device_id,
@@ -783,6 +1219,13 @@ where
     pub fn handle_ice_failed(&mut self, remote_device: DeviceId) -> Result<()> {
         info!("ice_failed(): id: {}", self.call_id().format(remote_device));
 
+        if self.handoff_device_id()? == Some(remote_device) {
+            // The device taking over the call couldn't connect, so keep the call
+            // going with the active connection.
+            info!("ice_failed(): handoff connection");
+            return self.abandon_handoff(remote_device);
+        }
+
         if let Ok(active_device_id) = self.active_device_id() {
             // There is an active connection.
             if active_device_id == remote_device {
@@ -849,6 +1292,15 @@ where
             }
         }
 
+        if let Some(mut pre_gathering) = self.pre_gathering.lock()?.take() {
+            if let Err(e) = pre_gathering.connection.terminate() {
+                error!(
+                    "terminate_connections(): call_id: {} failed to terminate pre-gathering connection: {}",
+                    self.call_id(),
+                    e
+                );
+            }
+        }
         if let Some(mut forking) = self.forking.lock()?.take() {
             if let Err(e) = forking.parent_connection.terminate() {
                 error!(
@@ -1005,9 +1457,14 @@ where
         self.inject_event(event)
     }
 
+    /// Inject a PreGatherIce event into the FSM.
+    pub fn inject_pre_gather_ice(&mut self) -> Result<()> {
+        self.inject_event(CallEvent::PreGatherIce)
+    }
+
     /// Inject an Accept Call event into the FSM.
-    pub fn inject_accept_call(&mut self) -> Result<()> {
-        self.inject_event(CallEvent::AcceptCall)
+    pub fn inject_accept_call(&mut self, options: AcceptOptions) -> Result<()> {
+        self.inject_event(CallEvent::AcceptCall(options))
     }
 
     /// Inject a local `SendHangupViaRtpDataToAll` event into the FSM.
@@ -1075,6 +1532,15 @@ where
         self.inject_event(event)
     }
 
+    /// Inject a local `ConnectTimeout` event into the FSM.
+    ///
+    /// `Called By:` Local timeout thread.
+    ///
+    pub fn inject_connect_timeout(&mut self) -> Result<()> {
+        let event = CallEvent::ConnectTimeout;
+        self.inject_event(event)
+    }
+
     /// Inject a synchronizing event into the FSM.
     ///
     /// Blocks the caller while the event flushes through the FSM.
@@ -1149,6 +1615,19 @@ where
             // blocks as connection FSM synchronizes
             parent_connection.synchronize()?;
         }
+        if let Some(pre_gathering_connection) = self
+            .pre_gathering
+            .lock()?
+            .as_mut()
+            .map(|p| &mut p.connection)
+        {
+            info!(
+                "synchronize(): call_id: {} pre-gathering connection",
+                self.call_id(),
+            );
+            // blocks as connection FSM synchronizes
+            pre_gathering_connection.synchronize()?;
+        }
         if let Ok(mut connection_map) = self.connection_map.lock() {
             for (_, connection) in connection_map.iter_mut() {
                 info!(
@@ -1187,4 +1666,15 @@ where
         let parent_connection = forking.as_ref().unwrap().parent_connection.clone();
         Ok(parent_connection)
     }
+
+    /// Return the connection pre-gathering ICE candidates, if any.
+    ///
+    /// `Called By:` Test infrastructure
+    #[cfg(feature = "sim")]
+    pub fn get_pre_gathering_connection(&self) -> Result<Option<Connection<T>>> {
+        let pre_gathering = self.pre_gathering.lock()?;
+        Ok(pre_gathering
+            .as_ref()
+            .map(|pre_gathering| pre_gathering.connection.clone()))
+    }
 }
@@ -33,6 +33,7 @@
 //! - IceFailed
 //! - Timeout
 //! - Reconnecting
+//! - MediaStalled
 //!
 //! ## Signaling events from client application
 //! - ReceivedAnswer
@@ -41,6 +42,7 @@
 //! ## Internally-generated events
 //!
 //! - CallTimeout
+//! - ConnectTimeout
 //! - InternalError
 
 use std::{
@@ -53,7 +55,8 @@ use std::{
 use crate::{
     common::{
         actor::{Actor, Stopper},
-        ApplicationEvent, CallConfig, CallDirection, CallState, ConnectionState, DeviceId, Result,
+        AcceptOptions, ApplicationEvent, CallConfig, CallDirection, CallState, ConnectionState,
+        DeviceId, Result,
     },
     core::{
         call::{Call, EventStream},
@@ -63,7 +66,10 @@ use crate::{
         util::try_scoped,
     },
     error::RingRtcError,
-    webrtc::{peer_connection::AudioLevel, peer_connection_observer::NetworkRoute},
+    webrtc::{
+        peer_connection::AudioLevel, peer_connection_observer::NetworkRoute,
+        stats_observer::QualityLimitationReason,
+    },
 };
 
 /// The different types of CallEvents.
@@ -72,11 +78,13 @@ pub enum CallEvent {
     /// Start a call (call struct has the direction attribute).
     StartCall,
     /// Accept incoming call (callee only).
-    AcceptCall,
+    AcceptCall(AcceptOptions),
     /// Send Hangup
     SendHangupViaRtpDataToAll(signaling::Hangup),
 
     // Flow events from client application
+    /// Start gathering ICE candidates before proceeding (callee only).
+    PreGatherIce,
     /// OK to proceed with call setup including user options.
     Proceed {
         call_config: CallConfig,
@@ -100,6 +108,8 @@ pub enum CallEvent {
     InternalError(anyhow::Error),
     /// The call timed out while establishing a connection.
     CallTimeout,
+    /// The call did not connect in time after proceeding.
+    ConnectTimeout,
     /// Synchronize the FSM.
     Synchronize(Arc<(Mutex<bool>, Condvar)>),
     /// Terminate the call.
@@ -110,10 +120,11 @@ impl fmt::Display for CallEvent {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let display = match self {
             CallEvent::StartCall => "StartCall".to_string(),
-            CallEvent::AcceptCall => "AcceptCall".to_string(),
+            CallEvent::AcceptCall(options) => format!("AcceptCall, options: {:?}", options),
             CallEvent::SendHangupViaRtpDataToAll(hangup) => {
                 format!("SendHangupViaRtpDataToAll, hangup: {}", hangup)
             }
+            CallEvent::PreGatherIce => "PreGatherIce".to_string(),
             CallEvent::Proceed {
                 call_config,
                 audio_levels_interval,
@@ -141,6 +152,7 @@ impl fmt::Display for CallEvent {
             }
             CallEvent::InternalError(e) => format!("InternalError: {}", e),
             CallEvent::CallTimeout => "CallTimeout".to_string(),
+            CallEvent::ConnectTimeout => "ConnectTimeout".to_string(),
             CallEvent::Synchronize(_) => "Synchronize".to_string(),
             CallEvent::Terminate => "Terminate".to_string(),
         };
@@ -345,6 +357,42 @@ where
         Ok(())
     }
 
+    // For accepted calls being taken over by another remote device: switch to its connection, and then
+    // hang up the original one, which the remote device learns about via the HandedOff hangup.
+    fn handle_handoff_connected(
+        &mut self,
+        call: Call<T>,
+        state: CallState,
+        handoff_device_id: DeviceId,
+    ) -> Result<()> {
+        info!(
+            "handle_handoff_connected(): Handing off to {}",
+            handoff_device_id
+        );
+        call.hand_off_to(handoff_device_id)?;
+
+        let hangup = signaling::Hangup::HandedOff(handoff_device_id);
+        call.send_hangup_via_rtp_data_to_all_except(hangup, handoff_device_id)?;
+
+        self.schedule_work_until_terminating(
+            call.clone(),
+            "handle_handoff_connected failed",
+            move |call| {
+                call.activate_handed_off_connection()?;
+                call.send_hangup_via_signaling_to_all(hangup)?;
+                // This blocks.
+                call.terminate_connections_except_accepted(handoff_device_id)?;
+                Ok(())
+            },
+        );
+
+        if state == CallState::ReconnectingAfterAccepted {
+            call.set_state(CallState::ConnectedAndAccepted)?;
+            self.notify_application(call, ApplicationEvent::Reconnected);
+        }
+        Ok(())
+    }
+
     // For remotely-accepted (outgoing) calls, this is the second step: enable media and notify the application.
     fn activate_remotely_accepted_connection(&mut self, call: Call<T>) {
         self.schedule_work_until_terminating(
@@ -378,11 +426,12 @@ where
         }
         match event {
             CallEvent::StartCall => self.handle_start_call(call, state),
+            CallEvent::PreGatherIce => self.handle_pre_gather_ice(call, state),
             CallEvent::Proceed {
                 call_config,
                 audio_levels_interval,
             } => self.handle_proceed(call, state, call_config, audio_levels_interval),
-            CallEvent::AcceptCall => self.handle_accept_call(call, state),
+            CallEvent::AcceptCall(options) => self.handle_accept_call(call, state, options),
             CallEvent::ReceivedAnswer(received) => {
                 self.handle_received_answer(call, state, received)
             }
@@ -398,6 +447,7 @@ where
             }
             CallEvent::InternalError(error) => self.handle_internal_error(call, error),
             CallEvent::CallTimeout => self.handle_call_timeout(call, state),
+            CallEvent::ConnectTimeout => self.handle_connect_timeout(call, state),
             // Handled above
             CallEvent::SendHangupViaRtpDataToAll(_) => Ok(()),
             CallEvent::Synchronize(_) => Ok(()),
@@ -470,6 +520,40 @@ where
         });
     }
 
+    fn notify_video_adaptation_changed(
+        &mut self,
+        mut call: Call<T>,
+        reason: Option<QualityLimitationReason>,
+    ) {
+        self.notify_spawn(move || {
+            let result = try_scoped(|| {
+                if call.terminating()? {
+                    Ok(())
+                } else {
+                    call.notify_video_adaptation_changed(reason)
+                }
+            });
+            if let Err(err) = result {
+                call.inject_internal_error(err, "Notify Video Adaptation failed");
+            }
+        });
+    }
+
+    fn notify_app_data(&mut self, mut call: Call<T>, data: Vec<u8>) {
+        self.notify_spawn(move || {
+            let result = try_scoped(|| {
+                if call.terminating()? {
+                    Ok(())
+                } else {
+                    call.notify_app_data(data)
+                }
+            });
+            if let Err(err) = result {
+                call.inject_internal_error(err, "Notify App Data failed");
+            }
+        });
+    }
+
     fn handle_start_call(&mut self, call: Call<T>, state: CallState) -> Result<()> {
         info!("handle_start_call():");
 
@@ -483,6 +567,20 @@ where
         }
     }
 
+    fn handle_pre_gather_ice(&mut self, call: Call<T>, state: CallState) -> Result<()> {
+        info!("handle_pre_gather_ice():");
+
+        if state == CallState::WaitingToProceed && call.direction() == CallDirection::Incoming {
+            self.schedule_work_until_terminating(call, "Pre-gathering ICE failed", move |call| {
+                call.pre_gather_ice()
+            });
+        } else {
+            self.unexpected_state(state, "PreGatherIce");
+        }
+
+        Ok(())
+    }
+
     fn handle_proceed(
         &mut self,
         call: Call<T>,
@@ -565,6 +663,23 @@ where
             return Ok(());
         }
 
+        // If this device took over the call, ignore the hangup that ends the leg it replaced.
+        if hangup_type == signaling::HangupType::HandedOff
+            && Some(call.local_device_id()) == hangup_device_id
+        {
+            info!("handle_received_hangup(): Ignoring handoff hangup for this device");
+            return Ok(());
+        }
+
+        // If the device taking over the call hangs up, keep the call going as it was.
+        if call.handoff_device_id()? == Some(sender_device_id) {
+            info!("handle_received_hangup(): Abandoning handoff");
+            self.schedule_work_until_terminating(call, "Abandoning handoff failed", move |call| {
+                call.abandon_handoff(sender_device_id)
+            });
+            return Ok(());
+        }
+
         // If already connected to device A, ignore hangup messages from device B.
         if let Ok(active_device_id) = call.active_device_id() {
             if sender_device_id != active_device_id {
@@ -606,6 +721,33 @@ where
                 ))
             }
 
+            // Caller gets declined with a reason: propagate it as Declined with
+            // specific app event.
+            (signaling::HangupType::DeclinedBusy, CallDirection::Outgoing) => {
+                propagate_with_app_event(
+                    signaling::Hangup::DeclinedOnAnotherDevice(sender_device_id),
+                    ApplicationEvent::EndedRemoteDeclinedBusy,
+                )
+            }
+            (signaling::HangupType::DeclinedDoNotDisturb, CallDirection::Outgoing) => {
+                propagate_with_app_event(
+                    signaling::Hangup::DeclinedOnAnotherDevice(sender_device_id),
+                    ApplicationEvent::EndedRemoteDeclinedDoNotDisturb,
+                )
+            }
+            (signaling::HangupType::DeclinedTextReplySent, CallDirection::Outgoing) => {
+                propagate_with_app_event(
+                    signaling::Hangup::DeclinedOnAnotherDevice(sender_device_id),
+                    ApplicationEvent::EndedRemoteDeclinedTextReplySent,
+                )
+            }
+            (signaling::HangupType::DeclinedByPolicy, CallDirection::Outgoing) => {
+                propagate_with_app_event(
+                    signaling::Hangup::DeclinedOnAnotherDevice(sender_device_id),
+                    ApplicationEvent::EndedRemoteDeclinedByPolicy,
+                )
+            }
+
             // Callee gets propagated hangup: use specific app event.
             (signaling::HangupType::AcceptedOnAnotherDevice, CallDirection::Incoming) => {
                 app_event_without_propagation(ApplicationEvent::EndedRemoteHangupAccepted)
@@ -617,6 +759,29 @@ where
                 app_event_without_propagation(ApplicationEvent::EndedRemoteHangupBusy)
             }
 
+            // Either side gets told the remote user moved the call to another device.
+            (signaling::HangupType::HandedOff, _) => {
+                app_event_without_propagation(ApplicationEvent::EndedHandedOff)
+            }
+
+            // Either side gets told the remote side lost its connection.
+            (signaling::HangupType::NetworkFailure, _) => {
+                app_event_without_propagation(ApplicationEvent::EndedRemoteHangupNetworkFailure)
+            }
+
+            // Callee gets told the caller gave up ringing.
+            (signaling::HangupType::RingTimeout, CallDirection::Incoming) => {
+                app_event_without_propagation(ApplicationEvent::EndedRemoteHangupRingTimeout)
+            }
+
+            // Caller gets told a callee stopped ringing: the other callees stop too.
+            (signaling::HangupType::RingTimeout, CallDirection::Outgoing) => {
+                propagate_with_app_event(
+                    signaling::Hangup::RingTimeout,
+                    ApplicationEvent::EndedRemoteHangupRingTimeout,
+                )
+            }
+
             // Everything else is unexpected: warn, and mostly treat like normal, no propagation.
             // TODO: Isn't NeedPermission for incoming normal because it's propagated above?
             // Should we make this no_app_event_and_no_propagation?
@@ -624,6 +789,13 @@ where
             (signaling::HangupType::AcceptedOnAnotherDevice, CallDirection::Outgoing) => unexpected,
             (signaling::HangupType::DeclinedOnAnotherDevice, CallDirection::Outgoing) => unexpected,
             (signaling::HangupType::BusyOnAnotherDevice, CallDirection::Outgoing) => unexpected,
+            (
+                signaling::HangupType::DeclinedBusy
+                | signaling::HangupType::DeclinedDoNotDisturb
+                | signaling::HangupType::DeclinedTextReplySent
+                | signaling::HangupType::DeclinedByPolicy,
+                CallDirection::Incoming,
+            ) => unexpected,
         };
 
         if !expected {
@@ -662,14 +834,19 @@ where
         Ok(())
     }
 
-    fn handle_accept_call(&mut self, call: Call<T>, state: CallState) -> Result<()> {
+    fn handle_accept_call(
+        &mut self,
+        call: Call<T>,
+        state: CallState,
+        options: AcceptOptions,
+    ) -> Result<()> {
         info!("handle_accept_call():");
         if state.can_be_accepted_locally() {
             call.set_state(CallState::ConnectedAndAccepted)?;
             self.schedule_work_until_terminating(
                 call,
                 "Processing local accept request failed",
-                move |call| call.accept_locally(),
+                move |call| call.accept_locally(options),
             );
         } else {
             self.unexpected_state(state, "AcceptCall");
@@ -709,6 +886,13 @@ where
         match event {
             ConnectionObserverEvent::StateChanged(connection_state) => {
                 match (direction, state, connection_state) {
+                    (
+                        _,
+                        CallState::ConnectedAndAccepted | CallState::ReconnectingAfterAccepted,
+                        ConnectionState::ConnectedBeforeAccepted,
+                    ) if call.handoff_device_id()? == Some(remote_device_id) => {
+                        self.handle_handoff_connected(call, state, remote_device_id)?;
+                    }
                     (
                         CallDirection::Incoming,
                         CallState::ConnectingBeforeAccepted,
@@ -717,7 +901,12 @@ where
                         // We use the fact that we are connected via ICE
                         // as a signal that the application should ring.
                         call.set_state(CallState::ConnectedBeforeAccepted)?;
-                        self.notify_application(call, ApplicationEvent::LocalRinging)
+                        if call.accept_when_connected() {
+                            // The application already accepted it, e.g. as a waiting call.
+                            self.handle_accept_call(call, CallState::ConnectedBeforeAccepted)
+                        } else {
+                            self.notify_application(call, ApplicationEvent::LocalRinging)
+                        }
                     }
                     (
                         CallDirection::Outgoing,
@@ -725,7 +914,12 @@ where
                         ConnectionState::ConnectedBeforeAccepted,
                     ) => {
                         call.set_state(CallState::ConnectedBeforeAccepted)?;
-                        self.notify_application(call, ApplicationEvent::RemoteRinging)
+                        if call.is_handoff() {
+                            // The remote user already accepted the call on another of our devices.
+                            Ok(())
+                        } else {
+                            self.notify_application(call, ApplicationEvent::RemoteRinging)
+                        }
                     }
                     | (
                         CallDirection::Outgoing,
@@ -792,6 +986,11 @@ where
                     ) => {
                         if call.active_device_id()? == remote_device_id {
                             call.set_state(CallState::ReconnectingAfterAccepted)?;
+                            self.schedule_work_until_terminating(
+                                call.clone(),
+                                "Processing reconnecting request failed",
+                                move |call| call.handle_reconnecting(),
+                            );
                             self.notify_application(call, ApplicationEvent::Reconnecting)
                         } else {
                             info!(
@@ -948,9 +1147,22 @@ where
                     }
                     if let Some(audio_enabled) = status.audio_enabled {
                         if audio_enabled {
-                            self.notify_application(call, ApplicationEvent::RemoteAudioEnable)
+                            self.notify_application(
+                                call.clone(),
+                                ApplicationEvent::RemoteAudioEnable,
+                            )
+                        } else {
+                            self.notify_application(
+                                call.clone(),
+                                ApplicationEvent::RemoteAudioDisable,
+                            )
+                        }
+                    }
+                    if let Some(on_hold) = status.on_hold {
+                        if on_hold {
+                            self.notify_application(call, ApplicationEvent::RemoteHeld)
                         } else {
-                            self.notify_application(call, ApplicationEvent::RemoteAudioDisable)
+                            self.notify_application(call, ApplicationEvent::RemoteResumed)
                         }
                     }
                 } else {
@@ -990,6 +1202,87 @@ where
                 self.notify_low_bandwidth_for_video(call, recovered);
                 Ok(())
             }
+            ConnectionObserverEvent::VideoAdaptationChanged { reason } => {
+                self.notify_video_adaptation_changed(call, reason);
+                Ok(())
+            }
+            ConnectionObserverEvent::RemoteVideoRequest { add } => {
+                if state.active() && call.active_device_id()? == remote_device_id {
+                    let app_event = if add {
+                        ApplicationEvent::RemoteVideoAddRequested
+                    } else {
+                        ApplicationEvent::RemoteVideoRemoved
+                    };
+                    self.notify_application(call, app_event);
+                } else {
+                    info!(
+                        "call_id: {} remote_device_id: {} Ignoring event: {}, from inactive connection.",
+                        call_id, remote_device_id, event
+                    );
+                }
+                Ok(())
+            }
+            ConnectionObserverEvent::VideoRequestResponse { accepted } => {
+                if state.active() && call.active_device_id()? == remote_device_id {
+                    let app_event = if accepted {
+                        ApplicationEvent::LocalVideoAddAccepted
+                    } else {
+                        ApplicationEvent::LocalVideoAddDeclined
+                    };
+                    self.notify_application(call, app_event);
+                } else {
+                    info!(
+                        "call_id: {} remote_device_id: {} Ignoring event: {}, from inactive connection.",
+                        call_id, remote_device_id, event
+                    );
+                }
+                Ok(())
+            }
+            ConnectionObserverEvent::MediaStalled { hang_up } => {
+                if state.active() && call.active_device_id()? == remote_device_id {
+                    self.notify_application(call.clone(), ApplicationEvent::MediaStalled);
+                    if hang_up {
+                        self.schedule_work_even_when_terminating(
+                            call,
+                            "Processing media stall failed",
+                            move |call| call.call_manager()?.media_stalled(call.call_id()),
+                        );
+                    }
+                } else {
+                    info!(
+                        "call_id: {} remote_device_id: {} Ignoring event: {}, from inactive connection.",
+                        call_id, remote_device_id, event
+                    );
+                }
+                Ok(())
+            }
+            ConnectionObserverEvent::AppData(data) => {
+                if state.active() && call.active_device_id()? == remote_device_id {
+                    self.notify_app_data(call, data);
+                } else {
+                    info!(
+                        "call_id: {} remote_device_id: {} Ignoring app data from inactive connection.",
+                        call_id, remote_device_id
+                    );
+                }
+                Ok(())
+            }
+            ConnectionObserverEvent::RemoteEarlyMedia => {
+                if direction == CallDirection::Outgoing
+                    && matches!(
+                        state,
+                        CallState::ConnectingBeforeAccepted | CallState::ConnectedBeforeAccepted
+                    )
+                {
+                    self.notify_application(call, ApplicationEvent::RemoteEarlyMedia)
+                } else {
+                    info!(
+                        "call_id: {} remote_device_id: {} Ignoring event: {}, in state: {}",
+                        call_id, remote_device_id, event, state
+                    );
+                }
+                Ok(())
+            }
         }
     }
 
@@ -1033,6 +1326,24 @@ where
         Ok(())
     }
 
+    fn handle_connect_timeout(&mut self, call: Call<T>, state: CallState) -> Result<()> {
+        info!("handle_connect_timeout(): state: {}", state);
+
+        match state {
+            CallState::WaitingToProceed
+            | CallState::ConnectingBeforeAccepted
+            | CallState::ConnectingAfterAccepted => {
+                self.schedule_work_even_when_terminating(
+                    call,
+                    "Processing connect timeout failed",
+                    move |call| call.call_manager()?.connection_failure(call.call_id()),
+                );
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
     fn handle_synchronize(
         &mut self,
         mut call: Call<T>,
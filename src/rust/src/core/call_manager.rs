@@ -23,18 +23,24 @@ use prost::Message;
 use crate::{
     common::{
         actor::{Actor, Stopper},
-        ApplicationEvent, CallConfig, CallDirection, CallId, CallMediaType, CallState, DataMode,
-        DeviceId, Result, RingBench,
+        AcceptOptions, ApplicationEvent, CallConfig, CallDirection, CallId, CallMediaType,
+        CallState, CallSummary, DataMode, DeviceId, GlareResolution, MaxSendBitrate, Result,
+        RingBench, VerificationCode, WideCallId, DEFAULT_RING_TIMEOUT,
     },
     core::{
         call::Call,
         call_mutex::CallMutex,
-        connection::{Connection, ConnectionType},
+        connection::{Connection, ConnectionType, MAX_APP_DATA_LEN},
         group_call,
-        group_call::{Client, ClientStartParams, GroupCallKind, HttpSfuClient, Observer, Reaction},
+        group_call::{
+            Client, ClientStartParams, GroupCallIntervals, GroupCallKind, HttpSfuClient, Observer,
+            Reaction,
+        },
         platform::Platform,
         signaling,
         signaling::ReceivedOffer,
+        signaling_store::SignalingStore,
+        signaling_transport::SignalingTransport,
         util::{try_scoped, uuid_to_string},
     },
     error::RingRtcError,
@@ -48,15 +54,18 @@ use crate::{
     },
     protobuf,
     webrtc::{
-        media::{AudioTrack, MediaStream, VideoSink, VideoTrack},
+        media::{
+            AudioLossProtectionConfig, AudioProfile, AudioTrack, MediaStream, VideoSink, VideoTrack,
+        },
         peer_connection::{AudioLevel, ReceivedAudioLevel},
-        peer_connection_factory::PeerConnectionFactory,
+        peer_connection_factory::{IceServer, PeerConnectionFactory},
         peer_connection_observer::NetworkRoute,
+        stats_observer::{QualityLimitationReason, StatsSnapshot},
     },
 };
 
 pub const MAX_MESSAGE_AGE: Duration = Duration::from_secs(60);
-const TIME_OUT_PERIOD: Duration = Duration::from_secs(60);
+const TIME_OUT_PERIOD: Duration = DEFAULT_RING_TIMEOUT;
 
 lazy_static! {
     static ref INCOMING_GROUP_CALL_RING_TIME: Duration =
@@ -211,6 +220,9 @@ enum ReceivedOfferCollision {
     None,
     /// An active call with a different user, so act busy
     Busy,
+    /// An active call with a different user, but call waiting is enabled,
+    /// so let the application decide
+    Waiting,
     /// An active call with the same user, but we win so ignore the incoming call
     GlareWinner,
     /// An active call with the same user, but we lose so drop our call
@@ -222,6 +234,16 @@ enum ReceivedOfferCollision {
     ReCall,
 }
 
+/// An incoming 1:1 call that arrived during an active call and is waiting for the
+/// application to accept or decline it.
+struct WaitingCall<T>
+where
+    T: Platform,
+{
+    call: Call<T>,
+    received: signaling::ReceivedOffer,
+}
+
 /// Management of 1:1 call messages that arrive before the offer for a particular call.
 ///
 /// We don't save all message kinds here, only the ones that can affect an incoming call.
@@ -356,6 +378,18 @@ pub fn validate_call_message_as_opaque_ring(
     }
 }
 
+/// The hangup to send when a call ends locally for the given reason, so that
+/// the remote side can tell why.
+fn hangup_for_ended_event(event: ApplicationEvent) -> signaling::Hangup {
+    match event {
+        ApplicationEvent::EndedTimeout => signaling::Hangup::RingTimeout,
+        ApplicationEvent::EndedConnectionFailure | ApplicationEvent::EndedMediaStalled => {
+            signaling::Hangup::NetworkFailure
+        }
+        _ => signaling::Hangup::Normal,
+    }
+}
+
 /// A wrapper for group call and call link clients.
 #[derive(Clone)]
 struct GroupCallClient {
@@ -373,12 +407,35 @@ where
 {
     /// Interface to platform specific methods.
     platform: Arc<CallMutex<T>>,
+    /// Transport for outgoing signaling, used instead of the platform if set.
+    signaling_transport: Arc<CallMutex<Option<Box<dyn SignalingTransport<T>>>>>,
+    /// Where the 1:1 signaling message in flight is persisted, if set.
+    signaling_store: Arc<CallMutex<Option<Box<dyn SignalingStore<T>>>>>,
     /// The current user's UUID, or None if it's unknown.
     self_uuid: Arc<CallMutex<Option<UserId>>>,
     /// Map of all 1:1 calls.
     call_by_call_id: Arc<CallMutex<HashMap<CallId, Call<T>>>>,
+    /// Opaque application data for 1:1 calls, passed along with every
+    /// notification for the call until it concludes.
+    call_app_data: Arc<CallMutex<HashMap<CallId, Vec<u8>>>>,
     /// CallId of the active call.
     active_call_id: Arc<CallMutex<Option<CallId>>>,
+    /// Whether an incoming call during an active call waits for the application
+    /// instead of being rejected as busy.
+    call_waiting_enabled: Arc<CallMutex<bool>>,
+    /// The incoming call waiting for the application, if any.
+    waiting_call: Arc<CallMutex<Option<WaitingCall<T>>>>,
+    /// Whether to restart ICE when the connection of an accepted call drops.
+    restart_ice_on_disconnect: Arc<CallMutex<bool>>,
+    /// Whether calls use relay candidates only, even if proceed() didn't ask for it.
+    relay_only_by_default: Arc<CallMutex<bool>>,
+    /// Whether the OS has given the app audio, see set_audio_session_active().
+    audio_session_active: Arc<CallMutex<bool>>,
+    /// The maximum size of the opaque value of outgoing offers and answers, if any.
+    max_signaling_opaque_size: Arc<CallMutex<Option<usize>>>,
+    /// CallId of the call put on hold to accept a waiting call. It stays in
+    /// call_by_call_id but is not the active call.
+    held_call_id: Arc<CallMutex<Option<CallId>>>,
     /// 1:1 call messages that arrived before the Offer for a particular call.
     pending_call_messages: Arc<CallMutex<PendingCallMessages>>,
     /// Map of all group calls.
@@ -389,6 +446,8 @@ where
     outstanding_group_rings: Arc<CallMutex<HashMap<group_call::GroupId, OutstandingGroupRing>>>,
     /// Busy indication if in either a direct or group call.
     busy: Arc<CallMutex<bool>>,
+    /// Cadences of periodic work for group calls started from now on.
+    group_call_intervals: Arc<CallMutex<GroupCallIntervals>>,
     /// Dedicated actor for background task execution.
     worker: Actor<()>,
     /// Signaling message queue.
@@ -449,14 +508,25 @@ where
     fn clone(&self) -> Self {
         Self {
             platform: Arc::clone(&self.platform),
+            signaling_transport: Arc::clone(&self.signaling_transport),
+            signaling_store: Arc::clone(&self.signaling_store),
             self_uuid: Arc::clone(&self.self_uuid),
             call_by_call_id: Arc::clone(&self.call_by_call_id),
+            call_app_data: Arc::clone(&self.call_app_data),
             active_call_id: Arc::clone(&self.active_call_id),
+            call_waiting_enabled: Arc::clone(&self.call_waiting_enabled),
+            restart_ice_on_disconnect: Arc::clone(&self.restart_ice_on_disconnect),
+            relay_only_by_default: Arc::clone(&self.relay_only_by_default),
+            audio_session_active: Arc::clone(&self.audio_session_active),
+            max_signaling_opaque_size: Arc::clone(&self.max_signaling_opaque_size),
+            waiting_call: Arc::clone(&self.waiting_call),
+            held_call_id: Arc::clone(&self.held_call_id),
             pending_call_messages: Arc::clone(&self.pending_call_messages),
             group_call_by_client_id: Arc::clone(&self.group_call_by_client_id),
             next_group_call_client_id: Arc::clone(&self.next_group_call_client_id),
             outstanding_group_rings: Arc::clone(&self.outstanding_group_rings),
             busy: Arc::clone(&self.busy),
+            group_call_intervals: Arc::clone(&self.group_call_intervals),
             worker: self.worker.clone(),
             message_queue: Arc::clone(&self.message_queue),
             http_client: self.http_client.clone(),
@@ -484,9 +554,19 @@ where
 
         Ok(Self {
             platform: Arc::new(CallMutex::new(platform, "platform")),
+            signaling_transport: Arc::new(CallMutex::new(None, "signaling_transport")),
+            signaling_store: Arc::new(CallMutex::new(None, "signaling_store")),
             self_uuid: Arc::new(CallMutex::new(None, "self_uuid")),
             call_by_call_id: Arc::new(CallMutex::new(HashMap::new(), "call_by_call_id")),
+            call_app_data: Arc::new(CallMutex::new(HashMap::new(), "call_app_data")),
             active_call_id: Arc::new(CallMutex::new(None, "active_call_id")),
+            call_waiting_enabled: Arc::new(CallMutex::new(false, "call_waiting_enabled")),
+            restart_ice_on_disconnect: Arc::new(CallMutex::new(false, "restart_ice_on_disconnect")),
+            relay_only_by_default: Arc::new(CallMutex::new(false, "relay_only_by_default")),
+            audio_session_active: Arc::new(CallMutex::new(true, "audio_session_active")),
+            max_signaling_opaque_size: Arc::new(CallMutex::new(None, "max_signaling_opaque_size")),
+            waiting_call: Arc::new(CallMutex::new(None, "waiting_call")),
+            held_call_id: Arc::new(CallMutex::new(None, "held_call_id")),
             pending_call_messages: Arc::new(CallMutex::new(
                 PendingCallMessages::None,
                 "pending_individual_call_messages",
@@ -501,6 +581,10 @@ where
                 "outstanding_group_rings",
             )),
             busy: Arc::new(CallMutex::new(false, "busy")),
+            group_call_intervals: Arc::new(CallMutex::new(
+                GroupCallIntervals::default(),
+                "group_call_intervals",
+            )),
             worker: Actor::start("call-manager-worker", worker_stopper, |_| Ok(()))?,
             message_queue: Arc::new(CallMutex::new(
                 SignalingMessageQueue::new()?,
@@ -514,6 +598,12 @@ where
         &self.http_client
     }
 
+    /// The same client as [`Self::http_client`], for requests that make more requests once
+    /// they finish.
+    pub fn shared_http_client(&self) -> Arc<dyn http::Client + Send + Sync> {
+        Arc::new(self.http_client.clone())
+    }
+
     /// Updates the current user's UUID.
     pub fn set_self_uuid(&mut self, uuid: UserId) -> Result<()> {
         info!("set_self_uuid():");
@@ -521,6 +611,134 @@ where
         Ok(())
     }
 
+    /// Attaches opaque application data to a 1:1 call, which is passed back
+    /// with every subsequent notification for it, up to and including
+    /// on_call_concluded().
+    ///
+    /// This takes effect immediately, so for an outgoing call it can be set
+    /// before create_outgoing_call() to be included from on_start_call() on.
+    /// For an incoming call, on_start_call() comes before the application can
+    /// attach anything, so it is typically set before calling proceed().
+    pub fn set_call_app_data(&mut self, call_id: CallId, app_data: Vec<u8>) -> Result<()> {
+        info!("set_call_app_data(): call_id: {}", call_id);
+        self.call_app_data.lock()?.insert(call_id, app_data);
+        Ok(())
+    }
+
+    /// Sends all outgoing signaling over the given transport instead of
+    /// through the platform.
+    ///
+    /// Signaling received over the transport must still be passed in with
+    /// the received_*() APIs.
+    pub fn set_signaling_transport(
+        &mut self,
+        transport: Box<dyn SignalingTransport<T>>,
+    ) -> Result<()> {
+        info!("set_signaling_transport():");
+        *self.signaling_transport.lock()? = Some(transport);
+        Ok(())
+    }
+
+    /// Persists each 1:1 signaling message in the given store until the
+    /// application confirms it as sent, so it survives a process restart.
+    pub fn set_signaling_store(&mut self, store: Box<dyn SignalingStore<T>>) -> Result<()> {
+        info!("set_signaling_store():");
+        *self.signaling_store.lock()? = Some(store);
+        Ok(())
+    }
+
+    /// Sends a message that was still pending in the signaling store when
+    /// the process last stopped.
+    pub fn resend_pending_message(
+        &mut self,
+        remote_peer: <T as Platform>::AppRemotePeer,
+        pending: Vec<u8>,
+    ) -> Result<()> {
+        info!("API:resend_pending_message():");
+
+        let pending = signaling::PendingMessage::from_bytes(&pending)?;
+        let call_id = pending.call_id;
+        let message_type = pending.message.typ();
+
+        let resend_closure = Box::new(move |cm: &CallManager<T>| {
+            ringbench!(
+                RingBench::Cm,
+                RingBench::App,
+                format!("resend_pending_message({})\t{}", pending.message, call_id)
+            );
+
+            cm.save_pending_message(&remote_peer, pending.clone());
+
+            let transport = cm.signaling_transport.lock()?;
+            let platform = cm.platform.lock()?;
+            let receiver_device_id = pending.receiver_device_id;
+            match (transport.as_ref(), pending.message) {
+                (Some(transport), signaling::Message::Offer(offer)) => {
+                    transport.send_offer(&remote_peer, call_id, offer)?
+                }
+                (None, signaling::Message::Offer(offer)) => {
+                    platform.on_send_offer(&remote_peer, call_id, offer)?
+                }
+                (transport, signaling::Message::Answer(answer)) => {
+                    let send = signaling::SendAnswer {
+                        answer,
+                        receiver_device_id: receiver_device_id
+                            .ok_or(RingRtcError::InvalidPendingMessage("receiver_device_id"))?,
+                    };
+                    match transport {
+                        Some(transport) => transport.send_answer(&remote_peer, call_id, send)?,
+                        None => platform.on_send_answer(&remote_peer, call_id, send)?,
+                    }
+                }
+                (transport, signaling::Message::Ice(ice)) => {
+                    let send = signaling::SendIce {
+                        ice,
+                        receiver_device_id,
+                    };
+                    match transport {
+                        Some(transport) => transport.send_ice(&remote_peer, call_id, send)?,
+                        None => platform.on_send_ice(&remote_peer, call_id, send)?,
+                    }
+                }
+                (transport, signaling::Message::Hangup(hangup)) => {
+                    let send = signaling::SendHangup { hangup };
+                    match transport {
+                        Some(transport) => transport.send_hangup(&remote_peer, call_id, send)?,
+                        None => platform.on_send_hangup(&remote_peer, call_id, send)?,
+                    }
+                }
+                (Some(transport), signaling::Message::Busy) => {
+                    transport.send_busy(&remote_peer, call_id)?
+                }
+                (None, signaling::Message::Busy) => platform.on_send_busy(&remote_peer, call_id)?,
+            }
+
+            Ok(MessageSendResult::Sent)
+        });
+
+        let message_item = SignalingMessageItem {
+            call_id,
+            message_type,
+            message_closure: resend_closure,
+        };
+
+        let mut call_manager = self.clone();
+        self.worker_spawn(move || {
+            if let Err(err) = call_manager.send_next_message(Some(message_item)) {
+                error!("Resending pending message failed: {}", err);
+            }
+        })
+    }
+
+    /// Updates the heartbeat, stats, and peek poll intervals used by group calls.
+    ///
+    /// Only affects group calls created after this is called.
+    pub fn set_group_call_intervals(&mut self, intervals: GroupCallIntervals) -> Result<()> {
+        info!("set_group_call_intervals(): {:?}", intervals);
+        *self.group_call_intervals.lock()? = intervals;
+        Ok(())
+    }
+
     /// Create an outgoing call.
     pub fn call(
         &mut self,
@@ -546,18 +764,244 @@ where
         let mut call_manager = self.clone();
         self.worker_spawn(move || {
             let remote_peer_error = remote_peer.clone();
-            if let Err(err) =
-                call_manager.handle_call(remote_peer, call_id, call_media_type, local_device_id)
-            {
+            if let Err(err) = call_manager.handle_call(
+                remote_peer,
+                call_id,
+                call_media_type,
+                local_device_id,
+                false,
+            ) {
                 error!("Handle call failed: {}", err);
                 call_manager.internal_create_api_error(&remote_peer_error, call_id, err);
             }
         })
     }
 
+    /// Take over an accepted call with the given CallId from another device
+    /// of the local user, after that device sent a handoff request.
+    pub fn take_over_call(
+        &mut self,
+        remote_peer: <T as Platform>::AppRemotePeer,
+        call_id: CallId,
+        call_media_type: CallMediaType,
+        local_device_id: DeviceId,
+    ) -> Result<()> {
+        info!("API:take_over_call({}):", call_id);
+
+        let mut call_manager = self.clone();
+        self.worker_spawn(move || {
+            let remote_peer_error = remote_peer.clone();
+            if let Err(err) = call_manager.handle_call(
+                remote_peer,
+                call_id,
+                call_media_type,
+                local_device_id,
+                true,
+            ) {
+                error!("Handle take over call failed: {}", err);
+                call_manager.internal_create_api_error(&remote_peer_error, call_id, err);
+            }
+        })
+    }
+
+    /// Ask another device of the local user to take over the accepted call.
+    ///
+    /// The call continues on this device until the target device has
+    /// connected to the remote peer, at which point it ends with
+    /// [`ApplicationEvent::EndedHandedOff`].
+    pub fn hand_off(
+        &mut self,
+        call_id: CallId,
+        remote_user_id: UserId,
+        target_device_id: DeviceId,
+    ) -> Result<()> {
+        handle_active_call_api!(
+            self,
+            CallManager::handle_hand_off,
+            call_id,
+            remote_user_id,
+            target_device_id
+        )
+    }
+
     /// Accept an incoming call.
     pub fn accept_call(&mut self, call_id: CallId) -> Result<()> {
-        handle_active_call_api!(self, CallManager::handle_accept_call, call_id)
+        self.accept_call_with_options(call_id, AcceptOptions::default())
+    }
+
+    /// Accept an incoming call with the microphone muted and/or the camera
+    /// off from the start.
+    ///
+    /// The remote peer learns the initial state along with the accept. The
+    /// application must still disable its own tracks before calling this.
+    pub fn accept_call_with_options(
+        &mut self,
+        call_id: CallId,
+        options: AcceptOptions,
+    ) -> Result<()> {
+        handle_active_call_api!(self, CallManager::handle_accept_call, call_id, options)
+    }
+
+    /// Put the active call on hold, or take it off hold.
+    ///
+    /// While on hold, no media is sent and the remote side is told
+    /// so it can show the call as held.
+    pub fn set_on_hold(&mut self, call_id: CallId, on_hold: bool) -> Result<()> {
+        handle_active_call_api!(self, CallManager::handle_set_on_hold, call_id, on_hold)
+    }
+
+    /// Restart ICE on the active call with new credentials, e.g. after the
+    /// platform reports a network change.
+    ///
+    /// Both sides gather candidates again without renegotiating the call,
+    /// so media resumes as soon as a new candidate pair is found.
+    pub fn restart_ice(&mut self, call_id: CallId) -> Result<()> {
+        handle_active_call_api!(self, CallManager::handle_restart_ice, call_id)
+    }
+
+    /// Override the max send bitrates of the call's data mode, separately for
+    /// audio, camera video and screen sharing, e.g. to stay within a strict
+    /// egress budget. The override is local and takes effect right away on
+    /// the connected call.
+    pub fn set_max_send_bitrate(
+        &mut self,
+        call_id: CallId,
+        max_send_bitrate: MaxSendBitrate,
+    ) -> Result<()> {
+        handle_active_call_api!(
+            self,
+            CallManager::handle_set_max_send_bitrate,
+            call_id,
+            max_send_bitrate
+        )
+    }
+
+    /// Ask the remote peer to agree to the local side starting to send video
+    /// on the accepted call. The application is told whether it agreed with
+    /// [`ApplicationEvent::LocalVideoAddAccepted`] or
+    /// [`ApplicationEvent::LocalVideoAddDeclined`], and should only start
+    /// sending video once accepted.
+    pub fn enable_video(&mut self, call_id: CallId) -> Result<()> {
+        handle_active_call_api!(self, CallManager::handle_request_video, call_id, true)
+    }
+
+    /// Stop sending video on the accepted call, which the remote peer is
+    /// told about but doesn't need to agree to.
+    pub fn disable_video(&mut self, call_id: CallId) -> Result<()> {
+        handle_active_call_api!(self, CallManager::handle_request_video, call_id, false)
+    }
+
+    /// Answer the remote peer's request to start sending video, received as
+    /// [`ApplicationEvent::RemoteVideoAddRequested`].
+    pub fn respond_to_video_request(&mut self, call_id: CallId, accepted: bool) -> Result<()> {
+        handle_active_call_api!(
+            self,
+            CallManager::handle_respond_to_video_request,
+            call_id,
+            accepted
+        )
+    }
+
+    /// Send application data to the remote peer of the accepted call, which
+    /// receives it with [`Platform::on_app_data`]. Reliable data is resent
+    /// until received and arrives in order, while unreliable data may be lost.
+    pub fn send_app_data(&mut self, call_id: CallId, data: Vec<u8>, reliable: bool) -> Result<()> {
+        if data.len() > MAX_APP_DATA_LEN {
+            return Err(RingRtcError::AppDataTooLarge(data.len(), MAX_APP_DATA_LEN).into());
+        }
+        handle_active_call_api!(
+            self,
+            CallManager::handle_send_app_data,
+            call_id,
+            data,
+            reliable
+        )
+    }
+
+    /// Start sending audio to the caller of a ringing incoming call before
+    /// accepting it, such as a custom ringback tone or IVR prompts.
+    ///
+    /// This does nothing unless the caller enabled early media in its
+    /// CallConfig. The rest of the media starts when the call is accepted.
+    pub fn start_early_media(&mut self, call_id: CallId) -> Result<()> {
+        handle_active_call_api!(self, CallManager::handle_start_early_media, call_id)
+    }
+
+    /// Enables or disables restarting ICE automatically when the connection
+    /// of an accepted call drops, as when switching between Wi-Fi and cellular.
+    pub fn set_restart_ice_on_disconnect(&mut self, enabled: bool) -> Result<()> {
+        info!("set_restart_ice_on_disconnect(): {}", enabled);
+        *self.restart_ice_on_disconnect.lock()? = enabled;
+        Ok(())
+    }
+
+    /// Enables or disables relay-only (TURN-only) ICE for all 1:1 calls,
+    /// hiding the local IP address from the remote peer.
+    ///
+    /// This applies to calls that proceed after it is set, in addition to
+    /// any call_config that already asks for relay only.
+    pub fn set_relay_only_by_default(&mut self, enabled: bool) -> Result<()> {
+        info!("set_relay_only_by_default(): {}", enabled);
+        *self.relay_only_by_default.lock()? = enabled;
+        Ok(())
+    }
+
+    /// Tells RingRTC whether the OS has given the app audio, as when CallKit
+    /// activates the audio session or Telecom grants audio focus.
+    ///
+    /// While inactive, audio playout and recording stay stopped, even once a
+    /// call is accepted or a group call is joined, and they start when the
+    /// session becomes active. Apps that never call this keep the default of
+    /// starting audio along with the rest of the media.
+    pub fn set_audio_session_active(&mut self, active: bool) -> Result<()> {
+        handle_active_call_api!(self, CallManager::handle_set_audio_session_active, active)
+    }
+
+    /// Limits the size of the opaque value of outgoing offers and answers, as
+    /// when they must fit in a push payload, or removes the limit with None.
+    ///
+    /// Messages over the limit are compressed, and then have optional fields
+    /// trimmed until they fit. A call whose message still doesn't fit fails.
+    pub fn set_max_signaling_message_size(&mut self, max_size: Option<usize>) -> Result<()> {
+        info!("set_max_signaling_message_size(): {:?}", max_size);
+        *self.max_signaling_opaque_size.lock()? = max_size;
+        Ok(())
+    }
+
+    /// Enables or disables call waiting.
+    ///
+    /// When enabled, an incoming call that arrives while an accepted 1:1 call is
+    /// active is reported to the application with [`ApplicationEvent::IncomingCallWaiting`]
+    /// instead of being rejected as busy. Only one call can be waiting at a time.
+    pub fn set_call_waiting_enabled(&mut self, enabled: bool) -> Result<()> {
+        info!("set_call_waiting_enabled(): {}", enabled);
+        *self.call_waiting_enabled.lock()? = enabled;
+        Ok(())
+    }
+
+    /// Put the active call on hold and accept the waiting call.
+    ///
+    /// The waiting call becomes the active call and starts like any other incoming
+    /// call, except that it is accepted as soon as it connects instead of ringing.
+    pub fn hold_and_accept_waiting_call(&mut self) -> Result<()> {
+        handle_active_call_api!(self, CallManager::handle_hold_and_accept_waiting_call)
+    }
+
+    /// Reject the waiting call as busy.
+    pub fn decline_waiting_call(&mut self) -> Result<()> {
+        handle_active_call_api!(self, CallManager::handle_decline_waiting_call)
+    }
+
+    /// Make the held call the active call again and take it off hold.
+    ///
+    /// Does nothing while another call is active.
+    pub fn resume_held_call(&mut self) -> Result<()> {
+        handle_active_call_api!(self, CallManager::handle_resume_held_call)
+    }
+
+    /// Local hangup of the held call.
+    pub fn hangup_held_call(&mut self) -> Result<()> {
+        handle_active_call_api!(self, CallManager::handle_hangup_held_call)
     }
 
     /// Drop the active call.
@@ -583,6 +1027,26 @@ where
         )
     }
 
+    /// Start gathering ICE candidates for an incoming call before proceeding
+    /// with it, such as while the application is still deciding whether to
+    /// ring.
+    ///
+    /// Only relay candidates are gathered, and nothing is sent to the caller
+    /// until proceed(). The call then uses only relay candidates, whatever
+    /// the CallConfig passed to proceed() says.
+    pub fn pre_gather_ice(
+        &mut self,
+        call_id: CallId,
+        app_call_context: <T as Platform>::AppCallContext,
+    ) -> Result<()> {
+        handle_active_call_api!(
+            self,
+            CallManager::handle_pre_gather_ice,
+            call_id,
+            app_call_context
+        )
+    }
+
     /// OK for the library to continue to send signaling messages.
     pub fn message_sent(&mut self, call_id: CallId) -> Result<()> {
         handle_active_call_api!(self, CallManager::handle_message_sent, call_id)
@@ -598,6 +1062,13 @@ where
         handle_active_call_api!(self, CallManager::handle_hangup)
     }
 
+    /// Local decline of the active incoming call, telling the caller why.
+    ///
+    /// Only an incoming call that has not been accepted yet can be declined.
+    pub fn decline(&mut self, call_id: CallId, reason: signaling::DeclineReason) -> Result<()> {
+        handle_active_call_api!(self, CallManager::handle_decline, call_id, reason)
+    }
+
     fn remove_outstanding_group_ring(
         &mut self,
         group_id: group_call::GroupIdRef,
@@ -794,6 +1265,32 @@ where
         active_call.active_connection()
     }
 
+    /// Returns the verification code for the active call, for users to
+    /// compare with the remote user to detect tampering with the signaling.
+    ///
+    /// This is None until the call has settled on a connection, since an
+    /// outgoing call negotiates different keys with each callee device.
+    pub fn verification_code(&self, call_id: CallId) -> Result<Option<VerificationCode>> {
+        if !self.call_is_active(call_id)? {
+            return Ok(None);
+        }
+        match self.active_connection() {
+            Ok(connection) => connection.verification_code(),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Returns the 128-bit call ID for the active call, for telling it apart
+    /// from calls between other users that picked the same CallId.
+    ///
+    /// This is None for an incoming call from a client that doesn't send one.
+    pub fn wide_call_id(&self, call_id: CallId) -> Result<Option<WideCallId>> {
+        if !self.call_is_active(call_id)? {
+            return Ok(None);
+        }
+        self.active_call()?.wide_call_id()
+    }
+
     /// Checks if a call is active.
     pub fn call_active(&self) -> Result<bool> {
         Ok(self.active_call_id.lock()?.is_some())
@@ -843,6 +1340,13 @@ where
                 info!("synchronize(): syncing call: {}", call.call_id());
                 call.synchronize()?;
             }
+            if let Some(mut waiting_call) = self.waiting_call()? {
+                info!(
+                    "synchronize(): syncing waiting call: {}",
+                    waiting_call.call_id()
+                );
+                waiting_call.synchronize()?;
+            }
 
             let mut group_calls = self.group_call_by_client_id.lock()?.clone();
             for (client_id, group_call) in group_calls.iter_mut() {
@@ -936,12 +1440,74 @@ where
 
     /// Releases busy so another call can begin
     fn release_busy(&mut self) -> Result<()> {
+        if self.held_call_id.lock()?.is_some() {
+            // The held call still keeps us busy.
+            return Ok(());
+        }
+
         let mut busy = self.busy.lock()?;
         *busy = false;
 
         Ok(())
     }
 
+    /// Returns the call waiting for the application to accept or decline it, if any.
+    pub fn waiting_call(&self) -> Result<Option<Call<T>>> {
+        Ok(self
+            .waiting_call
+            .lock()?
+            .as_ref()
+            .map(|waiting| waiting.call.clone()))
+    }
+
+    /// Returns the held call, if any.
+    fn held_call(&self) -> Result<Option<Call<T>>> {
+        match *self.held_call_id.lock()? {
+            Some(call_id) => Ok(self.call_by_call_id.lock()?.get(&call_id).cloned()),
+            None => Ok(None),
+        }
+    }
+
+    /// Check if call_id refers to the held call.
+    fn call_is_held(&self, call_id: CallId) -> Result<bool> {
+        Ok(*self.held_call_id.lock()? == Some(call_id))
+    }
+
+    /// Check if call_id refers to the waiting call.
+    fn call_is_waiting(&self, call_id: CallId) -> Result<bool> {
+        Ok(self
+            .waiting_call
+            .lock()?
+            .as_ref()
+            .map_or(false, |waiting| waiting.call.call_id() == call_id))
+    }
+
+    /// Terminates the held call and notifies the application of the reason why.
+    fn terminate_held_call(&mut self, send_hangup: bool, event: ApplicationEvent) -> Result<()> {
+        info!("terminate_held_call():");
+
+        let call = self.held_call()?;
+        self.held_call_id.lock()?.take();
+        let call = match call {
+            Some(call) => call,
+            None => {
+                info!("terminate_held_call(): skipping, no held call");
+                return Ok(());
+            }
+        };
+        if !self.call_active()? {
+            self.release_busy()?;
+        }
+
+        let hangup = if send_hangup {
+            Some(hangup_for_ended_event(event))
+        } else {
+            None
+        };
+
+        self.terminate_call(call, hangup, Some(event))
+    }
+
     /// Terminates Call and optionally notifies application of the reason why.
     /// Also removes/drops it from the map.
     fn terminate_and_drop_call(&mut self, call_id: CallId) -> Result<()> {
@@ -974,8 +1540,21 @@ where
 
             let remote_peer = call.remote_peer()?;
 
-            let platform = cm.platform.lock()?;
-            platform.on_send_hangup(&remote_peer, call_id, send)?;
+            cm.save_pending_message(
+                &remote_peer,
+                signaling::PendingMessage {
+                    call_id,
+                    receiver_device_id: None,
+                    message: signaling::Message::Hangup(send.hangup),
+                },
+            );
+
+            if let Some(transport) = cm.signaling_transport.lock()?.as_ref() {
+                transport.send_hangup(&remote_peer, call_id, send)?;
+            } else {
+                let platform = cm.platform.lock()?;
+                platform.on_send_hangup(&remote_peer, call_id, send)?;
+            }
 
             Ok(MessageSendResult::Sent)
         });
@@ -1011,6 +1590,12 @@ where
 
         if let Some(event) = event {
             let remote_peer = call.remote_peer()?;
+            // The summary is only informational; don't let it keep the call from ending.
+            if let Err(err) =
+                self.notify_call_summary(&remote_peer, call_id, call.call_summary(), event)
+            {
+                warn!("terminate_call(): notifying call summary: {}", err);
+            }
             self.notify_application(&remote_peer, call_id, event)?;
         }
 
@@ -1058,7 +1643,7 @@ where
         self.release_busy()?;
 
         let hangup = if send_hangup {
-            Some(signaling::Hangup::Normal)
+            Some(hangup_for_ended_event(event))
         } else {
             None
         };
@@ -1073,13 +1658,14 @@ where
         call_id: CallId,
         call_media_type: CallMediaType,
         local_device_id: DeviceId,
+        handoff: bool,
     ) -> Result<()> {
         ringbench!(
             RingBench::App,
             RingBench::Cm,
             format!(
-                "call()\t{}\t{}\t{}",
-                call_id, call_media_type, local_device_id
+                "call()\t{}\t{}\t{}\thandoff: {}",
+                call_id, call_media_type, local_device_id, handoff
             )
         );
 
@@ -1100,6 +1686,19 @@ where
                         local_device_id,
                         self.clone(),
                     )?;
+                    if handoff {
+                        // The call ID extension belongs to the device that
+                        // started the call, so a handoff doesn't send one.
+                        call.set_handoff();
+                    } else {
+                        // If the local user isn't known yet, the randomness
+                        // alone still makes collisions unlikely.
+                        let self_uuid = self.self_uuid.lock()?.clone().unwrap_or_default();
+                        let wide_call_id =
+                            WideCallId::generate(call_id, &self_uuid, local_device_id);
+                        info!("handle_call(): wide_call_id: {}", wide_call_id);
+                        call.set_call_id_extension(wide_call_id.extension())?;
+                    }
 
                     // Whenever there is a new call, ensure that messages can flow.
                     self.reset_messages_in_flight()?;
@@ -1117,11 +1716,11 @@ where
     }
 
     /// Handle accept_call() API from application.
-    fn handle_accept_call(&mut self, call_id: CallId) -> Result<()> {
+    fn handle_accept_call(&mut self, call_id: CallId, options: AcceptOptions) -> Result<()> {
         ringbench!(
             RingBench::App,
             RingBench::Cm,
-            format!("accept()\t{}", call_id)
+            format!("accept({:?})\t{}", options, call_id)
         );
 
         let mut active_call = check_active_call!(self, "handle_accept_call");
@@ -1130,7 +1729,391 @@ where
             return Ok(());
         }
 
-        active_call.inject_accept_call()
+        active_call.inject_accept_call(options)
+    }
+
+    /// Handle hand_off() API from application.
+    fn handle_hand_off(
+        &mut self,
+        call_id: CallId,
+        remote_user_id: UserId,
+        target_device_id: DeviceId,
+    ) -> Result<()> {
+        ringbench!(
+            RingBench::App,
+            RingBench::Cm,
+            format!("hand_off({})\t{}", target_device_id, call_id)
+        );
+
+        let active_call = check_active_call!(self, "handle_hand_off");
+        if active_call.call_id() != call_id {
+            ringbenchx!(RingBench::Cm, RingBench::App, "inactive call_id");
+            return Ok(());
+        }
+
+        match active_call.state()? {
+            CallState::ConnectedAndAccepted | CallState::ReconnectingAfterAccepted => {}
+            state => {
+                warn!(
+                    "handle_hand_off(): ignoring for call that isn't accepted, state: {}",
+                    state
+                );
+                return Ok(());
+            }
+        }
+
+        let self_uuid = self.self_uuid.lock()?.clone();
+        let Some(self_uuid) = self_uuid else {
+            error!("self UUID unknown; cannot hand off the call to another device");
+            return Ok(());
+        };
+        let remote_device_id = active_call.active_device_id()?;
+
+        let message = protobuf::signaling::CallMessage {
+            handoff_request: Some(protobuf::signaling::call_message::HandoffRequest {
+                call_id: Some(call_id.as_u64()),
+                target_device_id: Some(target_device_id),
+                remote_user_id: Some(remote_user_id),
+                remote_device_id: Some(remote_device_id),
+                video: Some(active_call.media_type() == CallMediaType::Video),
+            }),
+            ..Default::default()
+        };
+        self.send_signaling_message(
+            self_uuid,
+            message,
+            group_call::SignalingMessageUrgency::HandleImmediately,
+        );
+        Ok(())
+    }
+
+    /// Handle set_on_hold() API from application.
+    fn handle_set_on_hold(&mut self, call_id: CallId, on_hold: bool) -> Result<()> {
+        ringbench!(
+            RingBench::App,
+            RingBench::Cm,
+            format!("set_on_hold({})\t{}", on_hold, call_id)
+        );
+
+        let active_call = check_active_call!(self, "handle_set_on_hold");
+        if active_call.call_id() != call_id {
+            ringbenchx!(RingBench::Cm, RingBench::App, "inactive call_id");
+            return Ok(());
+        }
+
+        match active_call.state()? {
+            CallState::ConnectedAndAccepted | CallState::ReconnectingAfterAccepted => active_call
+                .active_connection()?
+                .update_sender_status(signaling::SenderStatus {
+                    on_hold: Some(on_hold),
+                    ..Default::default()
+                }),
+            state => {
+                warn!(
+                    "handle_set_on_hold(): ignoring for call that isn't accepted, state: {}",
+                    state
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Handle set_audio_session_active() API from application.
+    fn handle_set_audio_session_active(&mut self, active: bool) -> Result<()> {
+        ringbench!(
+            RingBench::App,
+            RingBench::Cm,
+            format!("set_audio_session_active({})", active)
+        );
+
+        *self.audio_session_active.lock()? = active;
+
+        if let Ok(active_call) = self.active_call() {
+            match active_call.state()? {
+                CallState::ConnectedAndAccepted | CallState::ReconnectingAfterAccepted => {
+                    active_call.active_connection()?.set_audio_enabled(active)?;
+                }
+                state => {
+                    info!(
+                        "handle_set_audio_session_active(): applying when the call is accepted, state: {}",
+                        state
+                    );
+                }
+            }
+        }
+
+        let group_calls = self.group_call_by_client_id.lock()?.clone();
+        for group_call in group_calls.values() {
+            group_call.client.set_audio_session_active(active);
+        }
+        Ok(())
+    }
+
+    /// Handle restart_ice() API from application.
+    fn handle_set_max_send_bitrate(
+        &mut self,
+        call_id: CallId,
+        max_send_bitrate: MaxSendBitrate,
+    ) -> Result<()> {
+        ringbench!(
+            RingBench::App,
+            RingBench::Cm,
+            format!("set_max_send_bitrate({:?})\t{}", max_send_bitrate, call_id)
+        );
+
+        let active_call = check_active_call!(self, "handle_set_max_send_bitrate");
+        if active_call.call_id() != call_id {
+            ringbenchx!(RingBench::Cm, RingBench::App, "inactive call_id");
+            return Ok(());
+        }
+
+        match active_call.active_connection() {
+            Ok(active_connection) => active_connection.set_max_send_bitrate(max_send_bitrate),
+            Err(_) => {
+                warn!("handle_set_max_send_bitrate(): ignoring for call that isn't connected");
+                Ok(())
+            }
+        }
+    }
+
+    fn handle_restart_ice(&mut self, call_id: CallId) -> Result<()> {
+        ringbench!(
+            RingBench::App,
+            RingBench::Cm,
+            format!("restart_ice()\t{}", call_id)
+        );
+
+        let active_call = check_active_call!(self, "handle_restart_ice");
+        if active_call.call_id() != call_id {
+            ringbenchx!(RingBench::Cm, RingBench::App, "inactive call_id");
+            return Ok(());
+        }
+
+        match active_call.state()? {
+            CallState::ConnectedAndAccepted | CallState::ReconnectingAfterAccepted => {
+                active_call.restart_ice()
+            }
+            state => {
+                warn!(
+                    "handle_restart_ice(): ignoring for call that isn't accepted, state: {}",
+                    state
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Handle enable_video() and disable_video() APIs from application.
+    fn handle_request_video(&mut self, call_id: CallId, add: bool) -> Result<()> {
+        ringbench!(
+            RingBench::App,
+            RingBench::Cm,
+            format!("request_video({})\t{}", add, call_id)
+        );
+
+        let active_call = check_active_call!(self, "handle_request_video");
+        if active_call.call_id() != call_id {
+            ringbenchx!(RingBench::Cm, RingBench::App, "inactive call_id");
+            return Ok(());
+        }
+
+        match active_call.state()? {
+            CallState::ConnectedAndAccepted | CallState::ReconnectingAfterAccepted => {
+                active_call.request_video(add)
+            }
+            state => {
+                warn!(
+                    "handle_request_video(): ignoring for call that isn't accepted, state: {}",
+                    state
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Handle respond_to_video_request() API from application.
+    fn handle_respond_to_video_request(&mut self, call_id: CallId, accepted: bool) -> Result<()> {
+        ringbench!(
+            RingBench::App,
+            RingBench::Cm,
+            format!("respond_to_video_request({})\t{}", accepted, call_id)
+        );
+
+        let active_call = check_active_call!(self, "handle_respond_to_video_request");
+        if active_call.call_id() != call_id {
+            ringbenchx!(RingBench::Cm, RingBench::App, "inactive call_id");
+            return Ok(());
+        }
+
+        match active_call.state()? {
+            CallState::ConnectedAndAccepted | CallState::ReconnectingAfterAccepted => {
+                active_call.respond_to_video_request(accepted)
+            }
+            state => {
+                warn!(
+                    "handle_respond_to_video_request(): ignoring for call that isn't accepted, state: {}",
+                    state
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Handle send_app_data() API from application.
+    fn handle_send_app_data(
+        &mut self,
+        call_id: CallId,
+        data: Vec<u8>,
+        reliable: bool,
+    ) -> Result<()> {
+        ringbench!(
+            RingBench::App,
+            RingBench::Cm,
+            format!("send_app_data({})\t{}", reliable, call_id)
+        );
+
+        let active_call = check_active_call!(self, "handle_send_app_data");
+        if active_call.call_id() != call_id {
+            ringbenchx!(RingBench::Cm, RingBench::App, "inactive call_id");
+            return Ok(());
+        }
+
+        match active_call.state()? {
+            CallState::ConnectedAndAccepted | CallState::ReconnectingAfterAccepted => {
+                // Too much unacknowledged data shouldn't end the call.
+                if let Err(err) = active_call.send_app_data(data, reliable) {
+                    warn!("handle_send_app_data(): {}", err);
+                }
+                Ok(())
+            }
+            state => {
+                warn!(
+                    "handle_send_app_data(): ignoring for call that isn't accepted, state: {}",
+                    state
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Handle start_early_media() API from application.
+    fn handle_start_early_media(&mut self, call_id: CallId) -> Result<()> {
+        ringbench!(
+            RingBench::App,
+            RingBench::Cm,
+            format!("start_early_media()\t{}", call_id)
+        );
+
+        let active_call = check_active_call!(self, "handle_start_early_media");
+        if active_call.call_id() != call_id {
+            ringbenchx!(RingBench::Cm, RingBench::App, "inactive call_id");
+            return Ok(());
+        }
+        if active_call.direction() != CallDirection::Incoming {
+            warn!("handle_start_early_media(): ignoring for outgoing call");
+            return Ok(());
+        }
+
+        match active_call.state()? {
+            CallState::ConnectedBeforeAccepted => active_call.start_early_media(),
+            state => {
+                warn!(
+                    "handle_start_early_media(): ignoring for call that isn't ringing, state: {}",
+                    state
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Handle hold_and_accept_waiting_call() API from application.
+    fn handle_hold_and_accept_waiting_call(&mut self) -> Result<()> {
+        ringbench!(
+            RingBench::App,
+            RingBench::Cm,
+            "hold_and_accept_waiting_call()"
+        );
+
+        let WaitingCall { call, received } = match self.waiting_call.lock()?.take() {
+            Some(waiting) => waiting,
+            None => {
+                ringbenchx!(RingBench::Cm, RingBench::App, "no waiting call");
+                return Ok(());
+            }
+        };
+
+        let cm_clone = self.clone();
+        let mut busy = cm_clone.busy.lock()?;
+
+        if let Ok(active_call) = self.active_call() {
+            info!(
+                "handle_hold_and_accept_waiting_call(): holding call_id: {}",
+                active_call.call_id()
+            );
+            // Tells the remote side and stops sending and playing media.
+            active_call
+                .active_connection()?
+                .update_sender_status(signaling::SenderStatus {
+                    on_hold: Some(true),
+                    ..Default::default()
+                })?;
+            *self.held_call_id.lock()? = Some(active_call.call_id());
+            self.clear_active_call()?;
+        }
+
+        call.set_accept_when_connected();
+        self.start_incoming_call(call, received)?;
+        *busy = true;
+        Ok(())
+    }
+
+    /// Handle decline_waiting_call() API from application.
+    fn handle_decline_waiting_call(&mut self) -> Result<()> {
+        ringbench!(RingBench::App, RingBench::Cm, "decline_waiting_call()");
+
+        let waiting_call = self.waiting_call.lock()?.take();
+        match waiting_call {
+            Some(WaitingCall { call, .. }) => self.send_busy(call),
+            None => {
+                ringbenchx!(RingBench::Cm, RingBench::App, "no waiting call");
+                Ok(())
+            }
+        }
+    }
+
+    /// Handle resume_held_call() API from application.
+    fn handle_resume_held_call(&mut self) -> Result<()> {
+        ringbench!(RingBench::App, RingBench::Cm, "resume_held_call()");
+
+        if self.call_active()? {
+            warn!("handle_resume_held_call(): ignoring while another call is active");
+            return Ok(());
+        }
+
+        let held_call = match self.held_call()? {
+            Some(call) => call,
+            None => {
+                ringbenchx!(RingBench::Cm, RingBench::App, "no held call");
+                return Ok(());
+            }
+        };
+        self.held_call_id.lock()?.take();
+        *self.active_call_id.lock()? = Some(held_call.call_id());
+        *self.busy.lock()? = true;
+
+        held_call
+            .active_connection()?
+            .update_sender_status(signaling::SenderStatus {
+                on_hold: Some(false),
+                ..Default::default()
+            })
+    }
+
+    /// Handle hangup_held_call() API from application.
+    fn handle_hangup_held_call(&mut self) -> Result<()> {
+        ringbench!(RingBench::App, RingBench::Cm, "hangup_held_call()");
+
+        self.terminate_held_call(true, ApplicationEvent::EndedLocalHangup)
     }
 
     fn handle_terminate_active_call(
@@ -1166,7 +2149,7 @@ where
         &mut self,
         call_id: CallId,
         app_call_context: <T as Platform>::AppCallContext,
-        call_config: CallConfig,
+        mut call_config: CallConfig,
         audio_levels_interval: Option<Duration>,
     ) -> Result<()> {
         ringbench!(
@@ -1175,16 +2158,44 @@ where
             format!("proceed()\t{}", call_id)
         );
 
+        if *self.relay_only_by_default.lock()? {
+            call_config.relay_only = true;
+        }
+
         let mut active_call = check_active_call!(self, "handle_proceed");
         if active_call.call_id() != call_id {
             ringbenchx!(RingBench::Cm, RingBench::App, "inactive call_id");
             Ok(())
         } else {
             active_call.set_call_context(app_call_context)?;
+            active_call.start_timeout_timer(call_config.bounded_ring_timeout())?;
+            active_call.start_connect_timer(call_config.bounded_connect_timeout())?;
             active_call.inject_proceed(call_config, audio_levels_interval)
         }
     }
 
+    /// Handle pre_gather_ice() API from application.
+    fn handle_pre_gather_ice(
+        &mut self,
+        call_id: CallId,
+        app_call_context: <T as Platform>::AppCallContext,
+    ) -> Result<()> {
+        ringbench!(
+            RingBench::App,
+            RingBench::Cm,
+            format!("pre_gather_ice()\t{}", call_id)
+        );
+
+        let mut active_call = check_active_call!(self, "handle_pre_gather_ice");
+        if active_call.call_id() != call_id {
+            ringbenchx!(RingBench::Cm, RingBench::App, "inactive call_id");
+            Ok(())
+        } else {
+            active_call.set_pre_gathering_call_context(app_call_context)?;
+            active_call.inject_pre_gather_ice()
+        }
+    }
+
     /// Handle message_sent() API from application.
     fn handle_message_sent(&mut self, call_id: CallId) -> Result<()> {
         ringbench!(
@@ -1285,6 +2296,32 @@ where
         )
     }
 
+    /// Handle decline() API from application.
+    fn handle_decline(&mut self, call_id: CallId, reason: signaling::DeclineReason) -> Result<()> {
+        ringbench!(
+            RingBench::App,
+            RingBench::Cm,
+            format!("decline({:?})\t{}", reason, call_id)
+        );
+
+        let active_call = check_active_call!(self, "handle_decline");
+        if active_call.call_id() != call_id {
+            ringbenchx!(RingBench::Cm, RingBench::App, "inactive call_id");
+            return Ok(());
+        }
+
+        if active_call.direction() != CallDirection::Incoming || active_call.state()?.active() {
+            warn!("handle_decline(): only an incoming call that was not accepted can be declined");
+            return Ok(());
+        }
+
+        self.handle_terminate_active_call(
+            active_call,
+            Some(signaling::Hangup::Declined(reason)),
+            ApplicationEvent::EndedLocalHangup,
+        )
+    }
+
     /// Handle received_offer() API from application.
     fn handle_received_offer(
         &mut self,
@@ -1333,6 +2370,17 @@ where
             }
         };
 
+        // A handoff offer continues an accepted call on another remote device,
+        // so it never rings and is never rejected as busy.
+        if received.offer.is_handoff() {
+            return self.handle_received_handoff_offer(
+                remote_peer,
+                incoming_call_id,
+                received,
+                active_call,
+            );
+        }
+
         // Create the call object so that it will either be used as the
         // active call or properly concluded if dropped.
         let mut incoming_call = Call::new(
@@ -1343,8 +2391,11 @@ where
             received.receiver_device_id,
             self.clone(),
         )?;
+        if let Some(extension) = received.offer.call_id_extension() {
+            incoming_call.set_call_id_extension(extension)?;
+        }
 
-        let collision = match (active_call_id, &active_call, *busy) {
+        let mut collision = match (active_call_id, &active_call, *busy) {
             (None, None, false) => ReceivedOfferCollision::None,
             (None, None, true) => {
                 info!("Group call exists, sending busy for received offer");
@@ -1358,6 +2409,12 @@ where
                 ReceivedOfferCollision::Busy
             }
         };
+        if matches!(collision, ReceivedOfferCollision::Busy)
+            && self.can_wait(active_call.as_ref(), &remote_peer)?
+        {
+            info!("Call waiting is enabled, letting the application decide on the received offer");
+            collision = ReceivedOfferCollision::Waiting;
+        }
 
         enum ActiveCallAction {
             DontTerminate,
@@ -1368,6 +2425,7 @@ where
         enum IncomingCallAction {
             Ignore(ApplicationEvent),
             RejectAsBusy(ApplicationEvent),
+            Wait,
             Start,
         }
 
@@ -1379,6 +2437,9 @@ where
                 ActiveCallAction::DontTerminate,
                 IncomingCallAction::RejectAsBusy(ApplicationEvent::ReceivedOfferWhileActive),
             ),
+            ReceivedOfferCollision::Waiting => {
+                (ActiveCallAction::DontTerminate, IncomingCallAction::Wait)
+            }
             ReceivedOfferCollision::GlareWinner => (
                 ActiveCallAction::DontTerminate,
                 IncomingCallAction::Ignore(ApplicationEvent::ReceivedOfferWithGlare),
@@ -1399,6 +2460,27 @@ where
             ),
         };
 
+        if let Some(active_call) = &active_call {
+            let winning_call_id = match collision {
+                ReceivedOfferCollision::GlareWinner => Some(Some(active_call.call_id())),
+                ReceivedOfferCollision::GlareLoser => Some(Some(incoming_call_id)),
+                ReceivedOfferCollision::GlareDoubleLoser => Some(None),
+                _ => None,
+            };
+            if let Some(winning_call_id) = winning_call_id {
+                self.notify_glare_resolved(
+                    &remote_peer,
+                    GlareResolution {
+                        local_call_id: active_call.call_id(),
+                        remote_call_id: incoming_call_id,
+                        winning_call_id,
+                        converted_to_incoming: winning_call_id == Some(incoming_call_id)
+                            && active_call.direction() == CallDirection::Outgoing,
+                    },
+                )?;
+            }
+        }
+
         match active_call_action {
             ActiveCallAction::DontTerminate => {}
             ActiveCallAction::TerminateAndSendHangup(app_event) => {
@@ -1425,44 +2507,116 @@ where
                 self.notify_application(&remote_peer, incoming_call_id, app_event)?;
                 self.send_busy(incoming_call)?;
             }
+            IncomingCallAction::Wait => {
+                // A waiting call rings for as long as any other incoming call would.
+                incoming_call.start_timeout_timer(TIME_OUT_PERIOD)?;
+                self.notify_application(
+                    &remote_peer,
+                    incoming_call_id,
+                    ApplicationEvent::IncomingCallWaiting,
+                )?;
+                *self.waiting_call.lock()? = Some(WaitingCall {
+                    call: incoming_call,
+                    received,
+                });
+            }
             IncomingCallAction::Start => {
-                let mut active_call_id = self.active_call_id.lock()?;
-                if let Some(active_call_id) = *active_call_id {
-                    return Err(RingRtcError::CallAlreadyInProgress(active_call_id).into());
-                }
+                self.start_incoming_call(incoming_call, received)?;
+                *busy = true;
+            }
+        }
+        Ok(())
+    }
 
-                // Whenever there is a new call, ensure that messages can flow.
-                self.reset_messages_in_flight()?;
+    /// Handle an offer from another device of the remote user that is taking
+    /// over the active call.
+    fn handle_received_handoff_offer(
+        &mut self,
+        remote_peer: <T as Platform>::AppRemotePeer,
+        incoming_call_id: CallId,
+        received: signaling::ReceivedOffer,
+        active_call: Option<Call<T>>,
+    ) -> Result<()> {
+        if let Some(active_call) = active_call {
+            if active_call.call_id() == incoming_call_id
+                && self.remote_peer_equals_active(&active_call, &remote_peer)
+                && matches!(
+                    active_call.state()?,
+                    CallState::ConnectedAndAccepted | CallState::ReconnectingAfterAccepted
+                )
+                && active_call.active_device_id()? != received.sender_device_id
+                && active_call.handoff_device_id()?.is_none()
+            {
+                return active_call.handle_received_handoff_offer(received);
+            }
+        }
 
-                let mut call_map = self.call_by_call_id.lock()?;
-                call_map.insert(incoming_call_id, incoming_call.clone());
+        ringbenchx!(RingBench::Cm, RingBench::App, "unexpected handoff offer");
+        // Notify application we are completely done with this remote.
+        self.notify_call_concluded(&remote_peer, incoming_call_id)
+    }
 
-                *busy = true;
-                *active_call_id = Some(incoming_call_id);
-                incoming_call.start_timeout_timer(TIME_OUT_PERIOD)?;
-                incoming_call.handle_received_offer(received)?;
-                incoming_call.inject_start_call()?;
-
-                match std::mem::take(&mut *self.pending_call_messages.lock()?) {
-                    PendingCallMessages::None => {}
-                    PendingCallMessages::IceCandidates { call_id, received }
-                        if call_id == incoming_call_id =>
-                    {
-                        for received in received {
-                            incoming_call.inject_received_ice(received)?;
-                        }
-                    }
-                    PendingCallMessages::Hangup { call_id, received }
-                        if call_id == incoming_call_id =>
-                    {
-                        incoming_call.inject_received_hangup(received)?;
-                    }
-                    PendingCallMessages::IceCandidates { call_id, .. }
-                    | PendingCallMessages::Hangup { call_id, .. } => {
-                        info!("dropping pending messages for {}", call_id);
-                    }
+    /// Whether a received offer from remote_peer can wait for the application
+    /// instead of being rejected as busy while the given call is active.
+    fn can_wait(
+        &self,
+        active_call: Option<&Call<T>>,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+    ) -> Result<bool> {
+        let active_call_accepted = match active_call {
+            Some(active_call) => {
+                matches!(
+                    active_call.state()?,
+                    CallState::ConnectedAndAccepted | CallState::ReconnectingAfterAccepted
+                ) && !self.remote_peer_equals_active(active_call, remote_peer)
+            }
+            None => false,
+        };
+        Ok(active_call_accepted
+            && *self.call_waiting_enabled.lock()?
+            && self.waiting_call.lock()?.is_none()
+            && self.held_call_id.lock()?.is_none())
+    }
+
+    /// Makes the incoming call the active call and starts it.
+    fn start_incoming_call(
+        &mut self,
+        mut incoming_call: Call<T>,
+        received: signaling::ReceivedOffer,
+    ) -> Result<()> {
+        let incoming_call_id = incoming_call.call_id();
+        let mut active_call_id = self.active_call_id.lock()?;
+        if let Some(active_call_id) = *active_call_id {
+            return Err(RingRtcError::CallAlreadyInProgress(active_call_id).into());
+        }
+
+        // Whenever there is a new call, ensure that messages can flow.
+        self.reset_messages_in_flight()?;
+
+        let mut call_map = self.call_by_call_id.lock()?;
+        call_map.insert(incoming_call_id, incoming_call.clone());
+
+        *active_call_id = Some(incoming_call_id);
+        incoming_call.start_timeout_timer(TIME_OUT_PERIOD)?;
+        incoming_call.handle_received_offer(received)?;
+        incoming_call.inject_start_call()?;
+
+        match std::mem::take(&mut *self.pending_call_messages.lock()?) {
+            PendingCallMessages::None => {}
+            PendingCallMessages::IceCandidates { call_id, received }
+                if call_id == incoming_call_id =>
+            {
+                for received in received {
+                    incoming_call.inject_received_ice(received)?;
                 }
             }
+            PendingCallMessages::Hangup { call_id, received } if call_id == incoming_call_id => {
+                incoming_call.inject_received_hangup(received)?;
+            }
+            PendingCallMessages::IceCandidates { call_id, .. }
+            | PendingCallMessages::Hangup { call_id, .. } => {
+                info!("dropping pending messages for {}", call_id);
+            }
         }
         Ok(())
     }
@@ -1510,13 +2664,20 @@ where
             )
         );
 
+        if let Some(mut held_call) = self.held_call()?.filter(|call| call.call_id() == call_id) {
+            return held_call.inject_received_ice(received);
+        }
+
         match self.active_call() {
             Ok(mut active_call) if active_call.call_id() == call_id => {
                 active_call.inject_received_ice(received)?;
             }
             Ok(active_call) => {
-                if active_call.direction() == CallDirection::Outgoing {
-                    // Save the ICE candidates anyway, in case we have a glare scenario.
+                if active_call.direction() == CallDirection::Outgoing
+                    || self.call_is_waiting(call_id)?
+                {
+                    // Save the ICE candidates anyway, in case we have a glare scenario
+                    // or the waiting call gets accepted.
                     self.pending_call_messages
                         .lock()?
                         .save_ice_candidates(call_id, received);
@@ -1552,6 +2713,23 @@ where
             )
         );
 
+        if let Some(mut held_call) = self.held_call()?.filter(|call| call.call_id() == call_id) {
+            return held_call.inject_received_hangup(received);
+        }
+
+        if self.call_is_waiting(call_id)? {
+            if let Some(WaitingCall { call, .. }) = self.waiting_call.lock()?.take() {
+                info!("received_hangup(): the waiting call ended");
+                let remote_peer = call.remote_peer()?;
+                self.notify_application(
+                    &remote_peer,
+                    call_id,
+                    ApplicationEvent::EndedRemoteHangup,
+                )?;
+            }
+            return Ok(());
+        }
+
         match self.active_call() {
             Ok(mut active_call) if active_call.call_id() == call_id => {
                 active_call.inject_received_hangup(received)?;
@@ -1613,7 +2791,7 @@ where
         &mut self,
         sender_uuid: Vec<u8>,
         _sender_device_id: DeviceId,
-        _local_device_id: DeviceId,
+        local_device_id: DeviceId,
         message: Vec<u8>,
         message_age: Duration,
     ) -> Result<()> {
@@ -1731,6 +2909,49 @@ where
                     }
                 }
             }
+            protobuf::signaling::CallMessage {
+                handoff_request: Some(handoff_request),
+                ..
+            } => {
+                if self.self_uuid.lock()?.as_ref() != Some(&sender_uuid) {
+                    info!("Discarding handoff request from another user");
+                    return Ok(());
+                }
+                match handoff_request {
+                    protobuf::signaling::call_message::HandoffRequest {
+                        call_id: Some(call_id),
+                        target_device_id: Some(target_device_id),
+                        remote_user_id: Some(remote_user_id),
+                        remote_device_id: Some(remote_device_id),
+                        video,
+                    } => {
+                        if target_device_id != local_device_id {
+                            info!("Ignoring handoff request for another device");
+                        } else if message_age > MAX_MESSAGE_AGE {
+                            info!("Ignoring expired handoff request");
+                        } else if *self.busy.lock()? {
+                            info!("Ignoring handoff request while busy");
+                        } else {
+                            let call_media_type = if video.unwrap_or(false) {
+                                CallMediaType::Video
+                            } else {
+                                CallMediaType::Audio
+                            };
+                            self.platform.lock()?.on_handoff_requested(
+                                signaling::HandoffRequest {
+                                    call_id: CallId::new(call_id),
+                                    remote_user_id,
+                                    remote_device_id,
+                                    call_media_type,
+                                },
+                            )?;
+                        }
+                    }
+                    _ => {
+                        warn!("Received malformed HandoffRequest: {:?}", handoff_request);
+                    }
+                }
+            }
             protobuf::signaling::CallMessage {
                 group_call_message: Some(group_call_message),
                 ..
@@ -1855,6 +3076,8 @@ where
             let _ = self.terminate_call(call, Some(signaling::Hangup::Normal), None);
         }
 
+        self.waiting_call.lock()?.take();
+        self.held_call_id.lock()?.take();
         self.clear_active_call()?;
         self.release_busy()?;
 
@@ -1880,8 +3103,21 @@ where
 
             let remote_peer = call.remote_peer()?;
 
-            let platform = cm.platform.lock()?;
-            platform.on_send_busy(&remote_peer, call_id)?;
+            cm.save_pending_message(
+                &remote_peer,
+                signaling::PendingMessage {
+                    call_id,
+                    receiver_device_id: None,
+                    message: signaling::Message::Busy,
+                },
+            );
+
+            if let Some(transport) = cm.signaling_transport.lock()?.as_ref() {
+                transport.send_busy(&remote_peer, call_id)?;
+            } else {
+                let platform = cm.platform.lock()?;
+                platform.on_send_busy(&remote_peer, call_id)?;
+            }
 
             Ok(MessageSendResult::Sent)
         });
@@ -2016,10 +3252,38 @@ where
         }
     }
 
+    /// Persists the message about to be handed to the application in the
+    /// signaling store, if any. The message is only in flight until the
+    /// application confirms it, so nothing is saved if the platform assumes
+    /// messages are sent right away.
+    fn save_pending_message(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        pending: signaling::PendingMessage,
+    ) {
+        let result = try_scoped(|| {
+            let store = self.signaling_store.lock()?;
+            if let Some(store) = store.as_ref() {
+                if !self.platform.lock()?.assume_messages_sent() {
+                    store.save(remote_peer, pending.to_bytes()?)?;
+                }
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            warn!("save_pending_message(): {}", e);
+        }
+    }
+
     fn reset_messages_in_flight(&self) -> Result<()> {
         match self.message_queue.lock() {
             Ok(mut message_queue) => {
                 message_queue.messages_in_flight = false;
+                if let Some(store) = self.signaling_store.lock()?.as_ref() {
+                    if let Err(e) = store.clear() {
+                        warn!("reset_messages_in_flight(): clearing store: {}", e);
+                    }
+                }
                 Ok(())
             }
             Err(e) => {
@@ -2164,8 +3428,9 @@ where
             format!("start()\t{}", call_id)
         );
 
+        let app_data = self.call_app_data(call_id)?;
         let platform = self.platform.lock()?;
-        platform.on_start_call(remote_peer, call_id, direction, call_media_type)
+        platform.on_start_call(remote_peer, call_id, direction, call_media_type, &app_data)
     }
 
     /// Notify application of an event.
@@ -2177,8 +3442,54 @@ where
     ) -> Result<()> {
         ringbench!(RingBench::Cm, RingBench::App, format!("event({})", event));
 
+        let app_data = self.call_app_data(call_id)?;
+        let platform = self.platform.lock()?;
+        platform.on_event(remote_peer, call_id, event, &app_data)
+    }
+
+    /// Notify application of the totals for a call that is ending
+    pub(super) fn notify_call_summary(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        call_id: CallId,
+        summary: CallSummary,
+        event: ApplicationEvent,
+    ) -> Result<()> {
+        ringbench!(
+            RingBench::Cm,
+            RingBench::App,
+            format!("call_summary({:?}, {})", summary, event)
+        );
+
+        let app_data = self.call_app_data(call_id)?;
         let platform = self.platform.lock()?;
-        platform.on_event(remote_peer, call_id, event)
+        platform.on_call_summary(remote_peer, call_id, summary, event, &app_data)
+    }
+
+    /// Notify application of how a glare with the remote peer was resolved
+    fn notify_glare_resolved(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        resolution: GlareResolution,
+    ) -> Result<()> {
+        ringbench!(
+            RingBench::Cm,
+            RingBench::App,
+            format!("glare_resolved({:?})", resolution)
+        );
+
+        let platform = self.platform.lock()?;
+        platform.on_glare_resolved(remote_peer, resolution)
+    }
+
+    /// Whether ICE should be restarted when the connection of an accepted call drops.
+    pub(super) fn restart_ice_on_disconnect(&self) -> Result<bool> {
+        Ok(*self.restart_ice_on_disconnect.lock()?)
+    }
+
+    /// Whether audio may be played out and recorded, see set_audio_session_active().
+    pub(super) fn audio_session_active(&self) -> Result<bool> {
+        Ok(*self.audio_session_active.lock()?)
     }
 
     /// Notify application that the network route changed
@@ -2221,6 +3532,26 @@ where
         platform.on_low_bandwidth_for_video(remote_peer, recovered)
     }
 
+    /// Notify application that outgoing video started or stopped being adapted
+    pub(super) fn notify_video_adaptation_changed(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        reason: Option<QualityLimitationReason>,
+    ) -> Result<()> {
+        let platform = self.platform.lock()?;
+        platform.on_video_adaptation_changed(remote_peer, reason)
+    }
+
+    /// Notify application of data sent by the remote peer
+    pub(super) fn notify_app_data(
+        &self,
+        remote_peer: &<T as Platform>::AppRemotePeer,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        let platform = self.platform.lock()?;
+        platform.on_app_data(remote_peer, data)
+    }
+
     /// Create a new connection to a remote device
     pub(super) fn create_connection(
         &self,
@@ -2280,11 +3611,11 @@ where
     ) -> Result<()> {
         info!("remote_hangup(): call_id: {}", call_id);
 
+        let event = app_event_override.unwrap_or(ApplicationEvent::EndedRemoteHangup);
         if self.call_is_active(call_id)? {
-            match app_event_override {
-                Some(event) => self.terminate_active_call(false, event),
-                None => self.terminate_active_call(false, ApplicationEvent::EndedRemoteHangup),
-            }
+            self.terminate_active_call(false, event)
+        } else if self.call_is_held(call_id)? {
+            self.terminate_held_call(false, event)
         } else {
             info!("remote_hangup(): ignoring for inactive call");
             Ok(())
@@ -2304,8 +3635,9 @@ where
             format!("offer_expired()\t{}", call_id)
         );
 
+        let app_data = self.call_app_data(call_id)?;
         let platform = self.platform.lock()?;
-        platform.on_offer_expired(remote_peer, call_id, age)
+        platform.on_offer_expired(remote_peer, call_id, age, &app_data)
     }
 
     /// Notify application that the call is concluded.
@@ -2320,8 +3652,24 @@ where
             format!("call_concluded()\t{}", call_id)
         );
 
+        // Nothing else is reported for the call after this.
+        let app_data = self
+            .call_app_data
+            .lock()?
+            .remove(&call_id)
+            .unwrap_or_default();
         let platform = self.platform.lock()?;
-        platform.on_call_concluded(remote_peer, call_id)
+        platform.on_call_concluded(remote_peer, call_id, &app_data)
+    }
+
+    /// The application data attached to a 1:1 call, or empty if none was.
+    fn call_app_data(&self, call_id: CallId) -> Result<Vec<u8>> {
+        Ok(self
+            .call_app_data
+            .lock()?
+            .get(&call_id)
+            .cloned()
+            .unwrap_or_default())
     }
 
     /// Local timeout of the active call.
@@ -2330,6 +3678,16 @@ where
 
         if self.call_is_active(call_id)? {
             self.terminate_active_call(true, ApplicationEvent::EndedTimeout)
+        } else if self.call_is_waiting(call_id)? {
+            let waiting_call = self.waiting_call.lock()?.take();
+            match waiting_call {
+                Some(WaitingCall { call, .. }) => {
+                    let remote_peer = call.remote_peer()?;
+                    self.notify_application(&remote_peer, call_id, ApplicationEvent::EndedTimeout)?;
+                    self.send_busy(call)
+                }
+                None => Ok(()),
+            }
         } else {
             info!("timeout(): ignoring for inactive call");
             Ok(())
@@ -2342,12 +3700,29 @@ where
 
         if self.call_is_active(call_id)? {
             self.terminate_active_call(true, ApplicationEvent::EndedConnectionFailure)
+        } else if self.call_is_held(call_id)? {
+            self.terminate_held_call(true, ApplicationEvent::EndedConnectionFailure)
         } else {
             info!("call_failed(): ignoring for inactive call");
             Ok(())
         }
     }
 
+    /// Nothing was received on the active call for the media stall timeout,
+    /// and the call was configured to hang up when that happens.
+    pub(super) fn media_stalled(&mut self, call_id: CallId) -> Result<()> {
+        info!("media_stalled(): call_id: {}", call_id);
+
+        if self.call_is_active(call_id)? {
+            self.terminate_active_call(true, ApplicationEvent::EndedMediaStalled)
+        } else if self.call_is_held(call_id)? {
+            self.terminate_held_call(true, ApplicationEvent::EndedMediaStalled)
+        } else {
+            info!("media_stalled(): ignoring for inactive call");
+            Ok(())
+        }
+    }
+
     /// Internal error occurred on the active call.
     ///
     /// This shuts down the specified call if active and notifies the
@@ -2357,6 +3732,8 @@ where
 
         if self.call_is_active(call_id)? {
             self.terminate_active_call(true, ApplicationEvent::EndedInternalFailure)
+        } else if self.call_is_held(call_id)? {
+            self.terminate_held_call(true, ApplicationEvent::EndedInternalFailure)
         } else {
             info!("internal_error(): ignoring for inactive call");
             Ok(())
@@ -2373,6 +3750,11 @@ where
         let call_id = call.call_id();
         info!("send_offer(): call_id: {}", call_id);
 
+        let offer = match *self.max_signaling_opaque_size.lock()? {
+            Some(max_size) => offer.fit_to_size(max_size)?,
+            None => offer,
+        };
+
         let offer_closure = Box::new(move |cm: &CallManager<T>| {
             ringbench!(
                 RingBench::Cm,
@@ -2383,8 +3765,21 @@ where
             let remote_peer = call.remote_peer()?;
 
             if connection.can_send_messages() {
-                let platform = cm.platform.lock()?;
-                platform.on_send_offer(&remote_peer, call_id, offer)?;
+                cm.save_pending_message(
+                    &remote_peer,
+                    signaling::PendingMessage {
+                        call_id,
+                        receiver_device_id: None,
+                        message: signaling::Message::Offer(offer.clone()),
+                    },
+                );
+
+                if let Some(transport) = cm.signaling_transport.lock()?.as_ref() {
+                    transport.send_offer(&remote_peer, call_id, offer)?;
+                } else {
+                    let platform = cm.platform.lock()?;
+                    platform.on_send_offer(&remote_peer, call_id, offer)?;
+                }
                 Ok(MessageSendResult::Sent)
             } else {
                 Ok(MessageSendResult::NotSent)
@@ -2405,11 +3800,15 @@ where
         &mut self,
         call: Call<T>,
         connection: Connection<T>,
-        send: signaling::SendAnswer,
+        mut send: signaling::SendAnswer,
     ) -> Result<()> {
         let call_id = call.call_id();
         info!("send_answer(): call_id: {}", call_id);
 
+        if let Some(max_size) = *self.max_signaling_opaque_size.lock()? {
+            send.answer = send.answer.fit_to_size(max_size)?;
+        }
+
         let answer_closure = Box::new(move |cm: &CallManager<T>| {
             ringbench!(
                 RingBench::Cm,
@@ -2424,8 +3823,21 @@ where
             let remote_peer = call.remote_peer()?;
 
             if connection.can_send_messages() {
-                let platform = cm.platform.lock()?;
-                platform.on_send_answer(&remote_peer, call_id, send)?;
+                cm.save_pending_message(
+                    &remote_peer,
+                    signaling::PendingMessage {
+                        call_id,
+                        receiver_device_id: Some(send.receiver_device_id),
+                        message: signaling::Message::Answer(send.answer.clone()),
+                    },
+                );
+
+                if let Some(transport) = cm.signaling_transport.lock()?.as_ref() {
+                    transport.send_answer(&remote_peer, call_id, send)?;
+                } else {
+                    let platform = cm.platform.lock()?;
+                    platform.on_send_answer(&remote_peer, call_id, send)?;
+                }
                 Ok(MessageSendResult::Sent)
             } else {
                 Ok(MessageSendResult::NotSent)
@@ -2470,21 +3882,30 @@ where
 
             let remote_peer = call.remote_peer()?;
 
-            let platform = cm.platform.lock()?;
-            platform.on_send_ice(
+            let send = signaling::SendIce {
+                receiver_device_id: if broadcast {
+                    None
+                } else {
+                    Some(connection.remote_device_id())
+                },
+                ice: signaling::Ice {
+                    candidates: local_candidates,
+                },
+            };
+            cm.save_pending_message(
                 &remote_peer,
-                call_id,
-                signaling::SendIce {
-                    receiver_device_id: if broadcast {
-                        None
-                    } else {
-                        Some(connection.remote_device_id())
-                    },
-                    ice: signaling::Ice {
-                        candidates: local_candidates,
-                    },
+                signaling::PendingMessage {
+                    call_id,
+                    receiver_device_id: send.receiver_device_id,
+                    message: signaling::Message::Ice(send.ice.clone()),
                 },
-            )?;
+            );
+            if let Some(transport) = cm.signaling_transport.lock()?.as_ref() {
+                transport.send_ice(&remote_peer, call_id, send)?;
+            } else {
+                let platform = cm.platform.lock()?;
+                platform.on_send_ice(&remote_peer, call_id, send)?;
+            }
             Ok(MessageSendResult::Sent)
         });
 
@@ -2655,10 +4076,111 @@ where
         platform_handler!(self, handle_raised_hands, client_id, raised_hands);
     }
 
+    fn handle_remote_connection_qualities(
+        &self,
+        client_id: group_call::ClientId,
+        qualities: Vec<group_call::RemoteConnectionQuality>,
+    ) {
+        info!("handle_remote_connection_qualities(): {:?}", qualities);
+        platform_handler!(
+            self,
+            handle_remote_connection_qualities,
+            client_id,
+            qualities
+        );
+    }
+
+    fn handle_av_sync_skew(
+        &self,
+        client_id: group_call::ClientId,
+        skews: Vec<group_call::RemoteAvSyncSkew>,
+    ) {
+        warn!("handle_av_sync_skew(): {:?}", skews);
+        platform_handler!(self, handle_av_sync_skew, client_id, skews);
+    }
+
+    fn handle_video_adaptation_changed(
+        &self,
+        client_id: group_call::ClientId,
+        reason: Option<QualityLimitationReason>,
+    ) {
+        info!("handle_video_adaptation_changed(): {:?}", reason);
+        platform_handler!(self, handle_video_adaptation_changed, client_id, reason);
+    }
+
+    fn handle_e2ee_health(&self, client_id: group_call::ClientId, health: group_call::E2eeHealth) {
+        debug!("handle_e2ee_health(): {:?}", health);
+        platform_handler!(self, handle_e2ee_health, client_id, health);
+    }
+
+    fn handle_app_data(&self, client_id: group_call::ClientId, demux_id: DemuxId, data: Vec<u8>) {
+        debug!(
+            "handle_app_data(): demux_id: {}, len: {}",
+            demux_id,
+            data.len()
+        );
+        platform_handler!(self, handle_app_data, client_id, demux_id, data);
+    }
+
     fn handle_rtc_stats_report(&self, report_json: String) {
         platform_handler!(self, handle_rtc_stats_report, report_json);
     }
 
+    fn handle_own_device_joined(&self, client_id: group_call::ClientId, demux_id: DemuxId) {
+        info!("handle_own_device_joined(): demux_id: {}", demux_id);
+        platform_handler!(self, handle_own_device_joined, client_id, demux_id);
+    }
+
+    fn request_ice_servers(&self, client_id: group_call::ClientId) {
+        info!("request_ice_servers():");
+        platform_handler!(self, request_ice_servers, client_id);
+    }
+
+    fn handle_ice_servers_refresh_failed(&self, client_id: group_call::ClientId) {
+        info!("handle_ice_servers_refresh_failed():");
+        platform_handler!(self, handle_ice_servers_refresh_failed, client_id);
+    }
+
+    fn handle_breakout_group_changed(
+        &self,
+        client_id: group_call::ClientId,
+        breakout_group_id: Option<group_call::BreakoutGroupId>,
+    ) {
+        info!(
+            "handle_breakout_group_changed(): breakout_group_id: {:?}",
+            breakout_group_id
+        );
+        platform_handler!(
+            self,
+            handle_breakout_group_changed,
+            client_id,
+            breakout_group_id
+        );
+    }
+
+    fn handle_call_full(
+        &self,
+        client_id: group_call::ClientId,
+        max_devices: Option<u32>,
+        device_count: Option<u32>,
+    ) {
+        info!(
+            "handle_call_full(): max_devices: {:?}, device_count: {:?}",
+            max_devices, device_count
+        );
+        platform_handler!(self, handle_call_full, client_id, max_devices, device_count);
+    }
+
+    fn handle_call_summary(
+        &self,
+        client_id: group_call::ClientId,
+        summary: CallSummary,
+        reason: group_call::EndReason,
+    ) {
+        info!("handle_call_summary(): {:?}", summary);
+        platform_handler!(self, handle_call_summary, client_id, summary, reason);
+    }
+
     fn handle_ended(&self, client_id: group_call::ClientId, reason: group_call::EndReason) {
         info!("handle_ended({:?}):", reason);
         platform_handler!(self, handle_ended, client_id, reason);
@@ -2673,16 +4195,23 @@ where
         info!("send_signaling_message():");
         info!("  recipient: {}", uuid_to_string(&recipient_id));
 
+        let transport = self
+            .signaling_transport
+            .lock()
+            .expect("signaling_transport.lock()");
         let platform = self.platform.lock().expect("platform.lock()");
         let mut bytes = BytesMut::with_capacity(call_message.encoded_len());
         let result = call_message.encode(&mut bytes);
         match result {
             Ok(()) => {
-                platform
-                    .send_call_message(recipient_id, bytes.to_vec(), urgency)
-                    .unwrap_or_else(|_| {
-                        error!("failed to send signaling message",);
-                    });
+                let result = if let Some(transport) = transport.as_ref() {
+                    transport.send_call_message(recipient_id, bytes.to_vec(), urgency)
+                } else {
+                    platform.send_call_message(recipient_id, bytes.to_vec(), urgency)
+                };
+                result.unwrap_or_else(|_| {
+                    error!("failed to send signaling message",);
+                });
             }
             Err(_) => {
                 error!("Failed to encode signaling message");
@@ -2700,21 +4229,33 @@ where
         info!("send_signaling_message_to_group():");
         info!("  group ID: {}", uuid_to_string(&group_id));
 
+        let transport = self
+            .signaling_transport
+            .lock()
+            .expect("signaling_transport.lock()");
         let platform = self.platform.lock().expect("platform.lock()");
         let mut bytes = BytesMut::with_capacity(call_message.encoded_len());
         let result = call_message.encode(&mut bytes);
         match result {
             Ok(()) => {
-                platform
-                    .send_call_message_to_group(
+                let result = if let Some(transport) = transport.as_ref() {
+                    transport.send_call_message_to_group(
+                        group_id,
+                        bytes.to_vec(),
+                        urgency,
+                        recipients_override,
+                    )
+                } else {
+                    platform.send_call_message_to_group(
                         group_id,
                         bytes.to_vec(),
                         urgency,
                         recipients_override,
                     )
-                    .unwrap_or_else(|_| {
-                        error!("failed to send signaling message",);
-                    });
+                };
+                result.unwrap_or_else(|_| {
+                    error!("failed to send signaling message",);
+                });
             }
             Err(_) => {
                 error!("Failed to encode signaling message");
@@ -2767,6 +4308,7 @@ where
         outgoing_audio_track: AudioTrack,
         outgoing_video_track: VideoTrack,
         incoming_video_sink: Option<Box<dyn VideoSink>>,
+        ice_servers: Vec<IceServer>,
     ) -> Result<group_call::ClientId> {
         info!("create_group_call_client():");
         debug!(
@@ -2831,7 +4373,12 @@ where
             incoming_video_sink,
             ring_id,
             audio_levels_interval,
+            intervals: *self.group_call_intervals.lock()?,
+            ice_servers,
         })?;
+        if !*self.audio_session_active.lock()? {
+            client.set_audio_session_active(false);
+        }
 
         client_by_id.insert(
             client_id,
@@ -2859,6 +4406,7 @@ where
         outgoing_audio_track: AudioTrack,
         outgoing_video_track: VideoTrack,
         incoming_video_sink: Option<Box<dyn VideoSink>>,
+        ice_servers: Vec<IceServer>,
     ) -> Result<group_call::ClientId> {
         info!("create_call_link_call_client():");
         let room_id: group_call::GroupId = root_key.derive_room_id();
@@ -2922,7 +4470,12 @@ where
             incoming_video_sink,
             ring_id: None,
             audio_levels_interval,
+            intervals: *self.group_call_intervals.lock()?,
+            ice_servers,
         })?;
+        if !*self.audio_session_active.lock()? {
+            client.set_audio_session_active(false);
+        }
 
         client_by_id.insert(
             client_id,
@@ -3015,6 +4568,17 @@ where
     forward_group_call_api!(leave());
     forward_group_call_api!(react(value: String));
     forward_group_call_api!(raise_hand(raise: bool));
+    forward_group_call_api!(send_app_data(data: Vec<u8>));
+    forward_group_call_api!(lower_all_hands());
+    forward_group_call_api!(assign_breakout_groups(
+        assignments: Vec<(DemuxId, group_call::BreakoutGroupId)>
+    ));
+    forward_group_call_api!(end_breakout_groups());
+    forward_group_call_api!(set_lower_hand_when_speaking(enabled: bool));
+    forward_group_call_api!(set_detect_speaking_while_muted(enabled: bool));
+    forward_group_call_api!(set_leave_when_own_device_joins(enabled: bool));
+    forward_group_call_api!(set_skip_media_keys_for_bridged_participants(enabled: bool));
+    forward_group_call_api!(set_key_rotation_policy(policy: group_call::KeyRotationPolicy));
     forward_group_call_api!(group_ring => ring(recipient: Option<UserId>));
     forward_group_call_api!(set_outgoing_audio_muted(muted: bool));
     forward_group_call_api!(set_outgoing_video_muted(muted: bool));
@@ -3022,10 +4586,15 @@ where
     forward_group_call_api!(set_sharing_screen(sharing_screen: bool));
     forward_group_call_api!(resend_media_keys());
     forward_group_call_api!(set_data_mode(data_mode: DataMode));
+    forward_group_call_api!(set_audio_profile(audio_profile: AudioProfile));
+    forward_group_call_api!(set_audio_loss_protection(config: AudioLossProtectionConfig));
+    forward_group_call_api!(set_audio_levels_interval(audio_levels_interval: Option<Duration>));
     forward_group_call_api!(request_video(
         rendered_resolutions: Vec<group_call::VideoRequest>,
         active_speaker_height: u16,
     ), false);
+    forward_group_call_api!(set_rendering_paused(demux_ids: Vec<DemuxId>, paused: bool));
+    forward_group_call_api!(set_all_rendering_paused(paused: bool));
     forward_group_call_api!(approve_user(user_id: UserId));
     forward_group_call_api!(deny_user(user_id: UserId));
     forward_group_call_api!(remove_client(other_client_id: DemuxId));
@@ -3033,6 +4602,21 @@ where
     forward_group_call_api!(set_group_members(members: Vec<GroupMember>));
     forward_group_call_api!(set_membership_proof(proof: Vec<u8>));
     forward_group_call_api!(set_rtc_stats_interval(interval: Duration));
+    forward_group_call_api!(set_group_call_ice_servers => set_ice_servers(
+        ice_servers: Vec<IceServer>,
+        expires_in: Option<Duration>
+    ));
+
+    pub fn group_call_stats_snapshot(
+        &mut self,
+        client_id: group_call::ClientId,
+    ) -> Option<StatsSnapshot> {
+        let mut stats_snapshot = None;
+        self.with_group_call(client_id, |group_call| {
+            stats_snapshot = group_call.stats_snapshot();
+        });
+        stats_snapshot
+    }
 
     pub fn disconnect(&mut self, client_id: group_call::ClientId) {
         info!("disconnect(): id: {}", client_id);
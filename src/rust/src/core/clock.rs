@@ -0,0 +1,56 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! The time that a group call's periodic work, such as key rotation and polling, is scheduled by.
+//!
+//! This is real time, except that with the "sim" feature, tests can move a clock forward to run
+//! timers deterministically instead of sleeping until they fire.
+
+use std::time::Instant;
+#[cfg(feature = "sim")]
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct Clock {
+    /// How far ahead of real time the clock has been moved, in nanoseconds. Shared by clones.
+    #[cfg(feature = "sim")]
+    offset_nanos: Arc<AtomicU64>,
+}
+
+impl Clock {
+    pub fn now(&self) -> Instant {
+        #[cfg(feature = "sim")]
+        return Instant::now() + Duration::from_nanos(self.offset_nanos.load(Ordering::Relaxed));
+        #[cfg(not(feature = "sim"))]
+        Instant::now()
+    }
+
+    /// Moves the clock (and all of its clones) forward.
+    #[cfg(feature = "sim")]
+    pub fn advance(&self, by: Duration) {
+        self.offset_nanos
+            .fetch_add(by.as_nanos() as u64, Ordering::Relaxed);
+    }
+}
+
+#[cfg(all(test, feature = "sim"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advance_is_shared() {
+        let clock = Clock::default();
+        let clone = clock.clone();
+        let before = clock.now();
+        clone.advance(Duration::from_secs(60));
+        assert!(clock.now() >= before + Duration::from_secs(60));
+    }
+}
@@ -6,14 +6,16 @@
 //! A peer-to-peer connection interface.
 
 use std::{
+    collections::VecDeque,
     fmt,
     net::SocketAddr,
     sync::{
+        atomic::{AtomicBool, Ordering},
         mpsc::{Receiver, SyncSender},
         Arc, Condvar, Mutex, MutexGuard,
     },
     thread,
-    time::{Duration, SystemTime},
+    time::{Duration, Instant, SystemTime},
 };
 
 use bytes::{BufMut, BytesMut};
@@ -27,23 +29,32 @@ use crate::{
     common::{
         actor::{Actor, Stopper},
         units::DataRate,
-        CallConfig, CallDirection, CallId, CallMediaType, ConnectionState, DataMode, DeviceId,
-        Result, RingBench,
+        AcceptOptions, CallBitrateConfig, CallConfig, CallDirection, CallId, CallMediaType,
+        CallSummary, ConnectionState, DataMode, DeviceId, MaxSendBitrate, Result, RingBench,
+        VerificationCode,
     },
     core::{
         call::Call,
         call_mutex::CallMutex,
         connection_fsm::{ConnectionEvent, ConnectionStateMachine},
         platform::Platform,
+        setup_latency::SetupLatencyTracker,
         signaling,
         util::{ptr_as_box, redact_string},
     },
     error::RingRtcError,
-    lite::sfu::DemuxId,
+    lite::{
+        diagnostics::{self, DiagnosticEventKind},
+        metrics,
+        sfu::DemuxId,
+    },
     protobuf, webrtc,
     webrtc::{
         ice_gatherer::IceGatherer,
-        media::{MediaStream, VideoFrame, VideoFrameMetadata, VideoSink},
+        media::{
+            AudioEncoderConfig, AudioLossProtection, AudioLossProtectionConfig, AudioProfile,
+            MediaStream, VideoFrame, VideoFrameMetadata, VideoSink,
+        },
         peer_connection::{AudioLevel, PeerConnection, SendRates},
         peer_connection_observer::{
             IceConnectionState, NetworkAdapterType, NetworkRoute, PeerConnectionObserverTrait,
@@ -53,7 +64,10 @@ use crate::{
         sdp_observer::{
             create_csd_observer, create_ssd_observer, SessionDescription, SrtpCryptoSuite, SrtpKey,
         },
-        stats_observer::{create_stats_observer, StatsObserver},
+        stats_observer::{
+            create_stats_observer, AvSyncStats, QualityLimitationReason, StatsObserver,
+            StatsSnapshot,
+        },
     },
 };
 
@@ -81,15 +95,26 @@ const DELAY_FOR_RECOVERED_BWE_CALLBACK_MILLIS: u64 = 6000;
 const DELAY_FOR_RECOVERED_BWE_CALLBACK_TICKS: u64 =
     DELAY_FOR_RECOVERED_BWE_CALLBACK_MILLIS / TICK_INTERVAL_MILLIS;
 
+/// How often to check whether inbound media has stalled.
+const CHECK_MEDIA_STALL_INTERVAL_MILLIS: u64 = 1000;
+const CHECK_MEDIA_STALL_INTERVAL_TICKS: u64 =
+    CHECK_MEDIA_STALL_INTERVAL_MILLIS / TICK_INTERVAL_MILLIS;
+
 pub const RTP_DATA_PAYLOAD_TYPE: rtp::PayloadType = 101;
 pub const OLD_RTP_DATA_SSRC_FOR_OUTGOING: rtp::Ssrc = 1001;
 pub const OLD_RTP_DATA_SSRC_FOR_INCOMING: rtp::Ssrc = 2001;
 pub const OLD_RTP_DATA_RESERVED: [u8; 4] = [0, 0, 0, 0];
 pub const NEW_RTP_DATA_SSRC: rtp::Ssrc = 0xD;
 
+/// The most application data that can be sent at once, so that it fits
+/// in a single RTP data packet.
+pub const MAX_APP_DATA_LEN: usize = 1024;
+/// How much reliable application data can wait to be acknowledged.
+const MAX_UNACKED_APP_DATA: usize = 32;
+
 /// Connection observer status notification types
 /// Sent from the Connection to the parent Call object
-#[derive(Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum ConnectionObserverEvent {
     StateChanged(ConnectionState),
 
@@ -112,6 +137,34 @@ pub enum ConnectionObserverEvent {
     LowBandwidthForVideo {
         recovered: bool,
     },
+
+    /// Outgoing video started or stopped being adapted, or the reason for it
+    /// changed. None once it is no longer being adapted.
+    VideoAdaptationChanged {
+        reason: Option<QualityLimitationReason>,
+    },
+
+    /// The remote callee started sending early media via RTP data, which
+    /// is now being played out.
+    RemoteEarlyMedia,
+
+    /// The remote side asked to start sending video (add), or stopped it.
+    RemoteVideoRequest {
+        add: bool,
+    },
+
+    /// The remote side answered the local request to start sending video.
+    VideoRequestResponse {
+        accepted: bool,
+    },
+
+    /// No RTP has been received for the media stall timeout while connected.
+    MediaStalled {
+        hang_up: bool,
+    },
+
+    /// The remote side sent application data via RTP data.
+    AppData(Vec<u8>),
 }
 
 impl ConnectionObserverEvent {
@@ -121,15 +174,12 @@ impl ConnectionObserverEvent {
     }
 }
 
-impl Clone for ConnectionObserverEvent {
-    fn clone(&self) -> Self {
-        *self
-    }
-}
-
 impl fmt::Display for ConnectionObserverEvent {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            ConnectionObserverEvent::AppData(data) => write!(f, "AppData({} bytes)", data.len()),
+            _ => write!(f, "{:?}", self),
+        }
     }
 }
 
@@ -150,6 +200,25 @@ where
     app_connection: Option<<T as Platform>::AppConnection>,
     /// Boxed copy of the stats collector object shared for callbacks.
     stats_observer: Option<Box<StatsObserver>>,
+    /// The offer and answer as applied to the PeerConnection, for ICE restarts.
+    negotiated: Option<NegotiatedParameters>,
+    /// The ICE gatherer created by the last local ICE restart, if any.
+    ice_gatherer: Option<IceGatherer>,
+}
+
+/// The parameters that went into the local and remote descriptions.
+///
+/// An ICE restart only swaps out ICE credentials, so the descriptions
+/// are rebuilt from these rather than renegotiated.
+#[derive(Clone)]
+struct NegotiatedParameters {
+    offer: protobuf::signaling::ConnectionParametersV4,
+    answer: protobuf::signaling::ConnectionParametersV4,
+    srtp_keys: Option<NegotiatedSrtpKeys>,
+    /// Whether the remote peer understands ICE restart messages.
+    remote_supports_ice_restart: bool,
+    /// Set after restarting ICE locally, until the remote credentials arrive.
+    ice_restart_pending: bool,
 }
 
 // Send and Sync needed to share *const pointer types across threads.
@@ -208,6 +277,9 @@ pub enum ConnectionType {
     // As a callee, the connection signals to one remote device.
     Incoming,
     // This is like "signaling mode == unicast".
+    // As a callee, the pre-gathering connection gathers ICE candidates for
+    // the incoming connection before it exists, but doesn't signal anything.
+    IncomingPreGather,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -248,6 +320,8 @@ struct TickState {
 const MIN_SEND_RATE: DataRate = DataRate::from_kbps(30);
 // When a network route is relayed, don't send more than this.
 const RELAYED_MAX_SEND_RATE: DataRate = DataRate::from_mbps(1);
+// Opus can't go below this, even if the audio is capped lower.
+const MIN_AUDIO_BITRATE_BPS: i32 = 6_000;
 
 /// State that decides the max send bitrate and audio configuration.
 /// It's decided by a combination of the local settings, the remote settings,
@@ -260,19 +334,61 @@ pub struct BandwidthController {
     pub remote_max: Option<DataRate>,
     // The current network route
     pub network_route: NetworkRoute,
+    /// The audio profile currently used for sending.
+    pub audio_profile: AudioProfile,
+    /// The max bitrate of the configured audio encoder, before any caps.
+    pub audio_encoder_max: DataRate,
+    /// The bitrate caps set by the local user.
+    pub local_caps: CallBitrateConfig,
+    /// The bitrate caps set by the remote user, which apply locally as well.
+    pub remote_caps: CallBitrateConfig,
+    /// Which audio loss protection is in use.
+    pub audio_loss_protection: AudioLossProtection,
+    /// The max send bitrates overriding the local data mode.
+    pub max_send_bitrate: MaxSendBitrate,
+    /// Whether the local side is sharing its screen, which decides the
+    /// video override that applies.
+    pub sharing_screen: bool,
 }
 
 impl BandwidthController {
-    // Min of local, remote, and relay maxs, but can't go below MIN_SEND_RATE
+    // Min of local, remote, relay, and capped maxs, but can't go below MIN_SEND_RATE
     pub fn max_send_rate(&self) -> DataRate {
+        let caps = self.caps();
+        // The video cap leaves room for the audio.
+        let video_max = caps
+            .max_video_bitrate
+            .map(|video_max| video_max + self.max_audio_rate());
         self.local_max()
             .min_opt(self.remote_max)
             .min_opt(self.relay_max())
+            .min_opt(caps.max_total_bitrate)
+            .min_opt(video_max)
             .max(MIN_SEND_RATE)
     }
 
+    /// The max bitrate for the audio encoder.
+    pub fn max_audio_rate(&self) -> DataRate {
+        self.audio_encoder_max
+            .min_opt(self.caps().max_audio_bitrate)
+            .min_opt(self.max_send_bitrate.audio)
+    }
+
+    fn caps(&self) -> CallBitrateConfig {
+        self.local_caps.min(&self.remote_caps)
+    }
+
     fn local_max(&self) -> DataRate {
-        self.local_mode.max_bitrate()
+        let video_override = if self.sharing_screen {
+            self.max_send_bitrate.screen_share
+        } else {
+            self.max_send_bitrate.camera_video
+        };
+        match video_override {
+            // The video override leaves room for the audio.
+            Some(video_max) => video_max + self.max_audio_rate(),
+            None => self.local_mode.max_bitrate(),
+        }
     }
 
     fn relay_max(&self) -> Option<DataRate> {
@@ -284,6 +400,138 @@ impl BandwidthController {
     }
 }
 
+/// Mid-call video negotiation with the remote peer.
+///
+/// Starting to send video needs the remote peer to agree, while stopping
+/// doesn't. Only the agreement is tracked here; the application still
+/// turns its camera on and off, which the remote peer sees through the
+/// video status messages as before.
+#[derive(Debug, Default)]
+struct VideoNegotiation {
+    /// Whether video is being sent, as agreed with the remote peer.
+    local_sending: bool,
+    /// The ID of the last request sent.
+    last_request_id: u32,
+    /// Whether the last request sent is to add video and waits for a response.
+    request_pending: bool,
+    /// The ID of the last request received.
+    last_remote_request_id: u32,
+    /// A received request to add video that the application hasn't answered.
+    remote_request_pending: Option<u32>,
+}
+
+impl VideoNegotiation {
+    fn new(video_call: bool) -> Self {
+        Self {
+            local_sending: video_call,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the ID of the request to send, if anything changes.
+    /// Stopping takes effect right away.
+    fn request(&mut self, add: bool) -> Option<u32> {
+        if add == (self.local_sending || self.request_pending) {
+            return None;
+        }
+        self.last_request_id += 1;
+        self.request_pending = add;
+        if !add {
+            self.local_sending = false;
+        }
+        Some(self.last_request_id)
+    }
+
+    /// Returns whether a request was answered, ignoring stale responses.
+    fn handle_response(&mut self, request_id: u32, accepted: bool) -> bool {
+        if !self.request_pending || request_id != self.last_request_id {
+            return false;
+        }
+        self.request_pending = false;
+        self.local_sending = accepted;
+        true
+    }
+
+    /// Returns whether the request is new, ignoring retransmissions.
+    fn handle_request(&mut self, request_id: u32, add: bool) -> bool {
+        if request_id <= self.last_remote_request_id {
+            return false;
+        }
+        self.last_remote_request_id = request_id;
+        self.remote_request_pending = if add { Some(request_id) } else { None };
+        true
+    }
+
+    /// Returns the ID of the received request being answered, if any.
+    fn respond(&mut self) -> Option<u32> {
+        self.remote_request_pending.take()
+    }
+}
+
+/// Application data exchanged with the remote peer via RTP data.
+///
+/// Reliable data is numbered, resent until the remote peer acknowledges it,
+/// and delivered in order. Unreliable data is sent once and may be lost.
+#[derive(Debug, Default)]
+struct AppDataChannel {
+    /// The sequence of the last reliable data sent.
+    last_sequence: u32,
+    /// Reliable data not acknowledged yet, oldest first.
+    unacked: VecDeque<protobuf::rtp_data::AppData>,
+    /// The sequence of the last reliable data received, with none missing.
+    last_received_sequence: u32,
+}
+
+impl AppDataChannel {
+    /// Returns the reliable data to send, or None if too much is waiting
+    /// to be acknowledged.
+    fn push_reliable(
+        &mut self,
+        call_id: CallId,
+        data: Vec<u8>,
+    ) -> Option<protobuf::rtp_data::AppData> {
+        if self.unacked.len() >= MAX_UNACKED_APP_DATA {
+            return None;
+        }
+        self.last_sequence += 1;
+        let app_data = protobuf::rtp_data::AppData {
+            id: Some(u64::from(call_id)),
+            sequence: Some(self.last_sequence),
+            data: Some(data),
+        };
+        self.unacked.push_back(app_data.clone());
+        Some(app_data)
+    }
+
+    /// Forgets the reliable data the remote peer has received.
+    fn handle_ack(&mut self, sequence: u32) {
+        self.unacked
+            .retain(|app_data| app_data.sequence() > sequence);
+    }
+
+    /// Returns whether the reliable data is next in order, ignoring
+    /// retransmissions. Data after a gap is dropped, since it is resent.
+    fn handle_received(&mut self, sequence: u32) -> bool {
+        if sequence != self.last_received_sequence + 1 {
+            return false;
+        }
+        self.last_received_sequence = sequence;
+        true
+    }
+}
+
+/// Lowers the audio encoder bitrates to at most `max`, which must not be above
+/// the configured max, but not below what Opus supports.
+fn capped_audio_encoder_config(config: &AudioEncoderConfig, max: DataRate) -> AudioEncoderConfig {
+    let max_bitrate_bps = (max.as_bps() as i32).max(MIN_AUDIO_BITRATE_BPS);
+    AudioEncoderConfig {
+        initial_bitrate_bps: config.initial_bitrate_bps.min(max_bitrate_bps),
+        min_bitrate_bps: config.min_bitrate_bps.min(max_bitrate_bps),
+        max_bitrate_bps,
+        ..config.clone()
+    }
+}
+
 /// Configuration of the polling stats. The initial offset is disabled if 0 seconds.
 #[derive(Clone, Copy, Debug)]
 pub struct PollStatsConfig {
@@ -340,6 +588,60 @@ enum BweCallbackState {
     Done,
 }
 
+/// Watches inbound RTP while connected and accepted, so that a call whose
+/// media silently stopped arriving is reported instead of hanging.
+///
+/// The remote side sends RTP data at least once a second while connected,
+/// so that alone keeps the watchdog fed even when no audio or video is sent.
+#[derive(Debug, Clone, Copy)]
+struct MediaWatchdog {
+    timeout: Duration,
+    /// When RTP was last received, or None when not watching.
+    last_received: Option<Instant>,
+    /// True once a stall has been reported, until RTP is received again.
+    stalled: bool,
+}
+
+impl MediaWatchdog {
+    fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            last_received: None,
+            stalled: false,
+        }
+    }
+
+    /// Stops watching, such as while reconnecting.
+    fn reset(&mut self) {
+        self.last_received = None;
+        self.stalled = false;
+    }
+
+    /// Returns true if the media just stalled. Watching starts with the first
+    /// check after a reset.
+    fn check(&mut self, now: Instant, received: bool) -> bool {
+        match self.last_received {
+            Some(_) if received => {
+                self.last_received = Some(now);
+                self.stalled = false;
+                false
+            }
+            Some(last_received) => {
+                if !self.stalled && now.saturating_duration_since(last_received) >= self.timeout {
+                    self.stalled = true;
+                    true
+                } else {
+                    false
+                }
+            }
+            None => {
+                self.last_received = Some(now);
+                false
+            }
+        }
+    }
+}
+
 /// Represents the connection between a local client and one remote peer.
 ///
 /// This object is thread-safe.
@@ -362,14 +664,20 @@ where
     direction: CallDirection,
     /// The current state of the call connection
     state: Arc<CallMutex<ConnectionState>>,
+    /// When the connection first reached ConnectedAndAccepted, if it has.
+    connected_at: Arc<CallMutex<Option<Instant>>>,
     /// Ancillary WebRTC data.
     webrtc: Arc<CallMutex<WebRtcData<T>>>,
     /// State that decides what bandwidth to use for sending.
     bandwidth_controller: Arc<CallMutex<BandwidthController>>,
+    /// Mid-call video negotiation with the remote peer.
+    video_negotiation: Arc<CallMutex<VideoNegotiation>>,
+    /// Application data exchanged with the remote peer.
+    app_data_channel: Arc<CallMutex<AppDataChannel>>,
     /// The media configuration for the call (includes bandwidth and audio encoding settings).
     call_config: CallConfig,
-    /// The interval for audio level polling
-    audio_levels_interval: Option<Duration>,
+    /// The interval for audio level polling, which can change mid-call.
+    audio_levels_interval: Arc<CallMutex<Option<Duration>>>,
     /// Polling stats configuration.
     poll_stats_config: PollStatsConfig,
     /// Local ICE candidates waiting to be sent over signaling.
@@ -389,6 +697,14 @@ where
     incoming_video_sink: Option<Box<dyn VideoSink>>,
     /// Tracks when to send `ConnectionObserverEvent::LowBandwidthForVideo`.
     bwe_callback_state: BweCallbackState,
+    /// The last reason sent with `ConnectionObserverEvent::VideoAdaptationChanged`.
+    video_send_limitation: Option<QualityLimitationReason>,
+    /// Set whenever RTP is received, and cleared by each media stall check.
+    rtp_received: Arc<AtomicBool>,
+    /// Tracks when to send `ConnectionObserverEvent::MediaStalled`, if enabled.
+    media_watchdog: Option<MediaWatchdog>,
+    /// Shared with the parent Call, to record when the first video frame arrives.
+    setup_latency: Arc<SetupLatencyTracker>,
 }
 
 impl<T> fmt::Display for Connection<T>
@@ -461,10 +777,13 @@ where
             connection_id: self.connection_id,
             direction: self.direction,
             state: Arc::clone(&self.state),
+            connected_at: Arc::clone(&self.connected_at),
             webrtc: Arc::clone(&self.webrtc),
             bandwidth_controller: Arc::clone(&self.bandwidth_controller),
+            video_negotiation: Arc::clone(&self.video_negotiation),
+            app_data_channel: Arc::clone(&self.app_data_channel),
             call_config: self.call_config.clone(),
-            audio_levels_interval: self.audio_levels_interval,
+            audio_levels_interval: Arc::clone(&self.audio_levels_interval),
             poll_stats_config: self.poll_stats_config,
             buffered_local_ice_candidates: Arc::clone(&self.buffered_local_ice_candidates),
             terminate_condvar: Arc::clone(&self.terminate_condvar),
@@ -474,6 +793,10 @@ where
             last_received_rtp_data_timestamp: Arc::clone(&self.last_received_rtp_data_timestamp),
             incoming_video_sink: self.incoming_video_sink.clone(),
             bwe_callback_state: self.bwe_callback_state,
+            video_send_limitation: self.video_send_limitation,
+            rtp_received: Arc::clone(&self.rtp_received),
+            media_watchdog: self.media_watchdog,
+            setup_latency: Arc::clone(&self.setup_latency),
         }
     }
 }
@@ -496,6 +819,8 @@ where
 
         let call_id = call.call_id();
         let direction = call.direction();
+        let video_call = call.media_type() == CallMediaType::Video;
+        let setup_latency = call.setup_latency();
 
         let webrtc = WebRtcData {
             peer_connection: None,
@@ -504,12 +829,17 @@ where
             incoming_media: None,
             app_connection: None,
             stats_observer: None,
+            negotiated: None,
+            ice_gatherer: None,
         };
 
         let poll_stats_config = PollStatsConfig::new(
             call_config.stats_interval_secs,
             call_config.stats_initial_offset_secs,
         );
+        let media_watchdog = call_config
+            .bounded_media_stall_timeout()
+            .map(MediaWatchdog::new);
 
         let connection = Self {
             fsm_sender,
@@ -519,6 +849,7 @@ where
             direction,
             call: Arc::new(CallMutex::new(call, "call")),
             state: Arc::new(CallMutex::new(ConnectionState::NotYetStarted, "state")),
+            connected_at: Arc::new(CallMutex::new(None, "connected_at")),
             webrtc: Arc::new(CallMutex::new(webrtc, "webrtc")),
             bandwidth_controller: Arc::new(CallMutex::new(
                 BandwidthController {
@@ -531,11 +862,35 @@ where
                         local_relay_protocol: TransportProtocol::Unknown,
                         remote_relayed: false,
                     },
+                    audio_profile: call_config.audio_profile,
+                    audio_encoder_max: DataRate::from_bps(
+                        call_config
+                            .audio_encoder_config_for(call_config.audio_profile)
+                            .max_bitrate_bps as u64,
+                    ),
+                    local_caps: call_config.bitrate_config,
+                    remote_caps: CallBitrateConfig::default(),
+                    max_send_bitrate: MaxSendBitrate::default(),
+                    sharing_screen: false,
+                    audio_loss_protection: AudioLossProtection::new(
+                        call_config.audio_loss_protection.clone(),
+                    ),
                 },
                 "webrtc",
             )),
+            video_negotiation: Arc::new(CallMutex::new(
+                VideoNegotiation::new(video_call),
+                "video_negotiation",
+            )),
+            app_data_channel: Arc::new(CallMutex::new(
+                AppDataChannel::default(),
+                "app_data_channel",
+            )),
             call_config,
-            audio_levels_interval,
+            audio_levels_interval: Arc::new(CallMutex::new(
+                audio_levels_interval,
+                "audio_levels_interval",
+            )),
             poll_stats_config,
             buffered_local_ice_candidates: Arc::new(CallMutex::new(
                 Vec::new(),
@@ -561,6 +916,10 @@ where
             bwe_callback_state: BweCallbackState::CheckIfLow {
                 delayed_check_tick: 0,
             },
+            video_send_limitation: None,
+            rtp_received: Arc::new(AtomicBool::new(false)),
+            media_watchdog,
+            setup_latency,
         };
 
         connection.init_connection_ptr()?;
@@ -682,7 +1041,7 @@ where
             peer_connection.create_offer(observer.as_ref());
             let _ = observer.get_result()?;
 
-            let (mut offer, mut answer, remote_public_key) =
+            let (mut offer, mut answer, v4_offer, v4_answer) =
                 if let (Some(v4_offer), Some(v4_answer)) = (offer.to_v4(), received.answer.to_v4())
                 {
                     // Set the remote max based on the bitrate in the answer.
@@ -696,27 +1055,30 @@ where
                     "Incoming answer codecs: {:?}, max_bitrate: {:?}, bandwidth_controller: {:?}",
                     v4_answer.receive_video_codecs, v4_answer.max_bitrate_bps, bandwidth_controller
                 );
+                    if v4_answer.relay_only == Some(true) && !self.call_config.relay_only {
+                        info!("Remote is using relay only");
+                    }
 
-                    (offer, answer, v4_answer.public_key)
+                    (offer, answer, v4_offer, v4_answer)
                 } else {
                     return Err(RingRtcError::UnknownSignaledProtocolVersion.into());
                 };
 
-            if let Some(remote_public_key) = remote_public_key {
+            let srtp_keys = if let Some(remote_public_key) = &v4_answer.public_key {
                 let callee_identity_key = &received.sender_identity_key;
                 let caller_identity_key = &received.receiver_identity_key;
-                let NegotiatedSrtpKeys {
-                    offer_key,
-                    answer_key,
-                } = negotiate_srtp_keys(
+                let srtp_keys = negotiate_srtp_keys(
                     local_secret,
-                    &remote_public_key,
+                    remote_public_key,
                     caller_identity_key,
                     callee_identity_key,
                 )?;
-                offer.disable_dtls_and_set_srtp_key(&offer_key)?;
-                answer.disable_dtls_and_set_srtp_key(&answer_key)?;
-            }
+                offer.disable_dtls_and_set_srtp_key(&srtp_keys.offer_key)?;
+                answer.disable_dtls_and_set_srtp_key(&srtp_keys.answer_key)?;
+                Some(srtp_keys)
+            } else {
+                None
+            };
 
             let observer = create_ssd_observer();
             peer_connection.set_local_description(observer.as_ref(), offer);
@@ -736,7 +1098,19 @@ where
             // is happening. But none of those will be processed until start_fsm() is called below.
             observer.get_result()?;
 
-            peer_connection.configure_audio_encoders(&self.call_config.audio_encoder_config);
+            peer_connection.configure_audio_encoders(
+                &self
+                    .call_config
+                    .audio_encoder_config_for(bandwidth_controller.audio_profile),
+            );
+
+            webrtc.negotiated = Some(NegotiatedParameters {
+                remote_supports_ice_restart: v4_answer.ice_restart_supported.unwrap_or(false),
+                offer: v4_offer,
+                answer: v4_answer,
+                srtp_keys,
+                ice_restart_pending: false,
+            });
 
             self.apply_bandwidth_controller(&mut bandwidth_controller, &mut webrtc)?;
 
@@ -753,15 +1127,50 @@ where
         result
     }
 
+    // An incoming pre-gathering connection is responsible for:
+    // 1. Creating an ICE gatherer for the incoming connection to use once the
+    //    application proceeds, so candidates are ready by the time it answers.
+    // 2. Holding on to the gathered candidates until then.
+    pub fn start_incoming_pre_gather(&mut self) -> Result<IceGatherer> {
+        let result = (|| {
+            self.set_state(ConnectionState::Starting)?;
+
+            let webrtc = self.webrtc.lock()?;
+            let peer_connection = webrtc.peer_connection()?;
+
+            let ice_gatherer = peer_connection.create_shared_ice_gatherer()?;
+            peer_connection.use_shared_ice_gatherer(&ice_gatherer)?;
+
+            // As for the outgoing parent, the only purpose of the offer is
+            // to start gathering ICE candidates. It is never sent.
+            let observer = create_csd_observer();
+            peer_connection.create_offer(observer.as_ref());
+            let offer = observer.get_result()?;
+            let observer = create_ssd_observer();
+            peer_connection.set_local_description(observer.as_ref(), offer);
+            observer.get_result()?;
+
+            self.set_state(ConnectionState::IceGathering)?;
+            Ok(ice_gatherer)
+        })();
+
+        // Always start the FSM no matter what happened above because
+        // close() relies on it running.
+        self.start_fsm()?;
+        result
+    }
+
     // An incoming connection is responsible for:
     // 1. Creating an answer to send back to the caller
     // 2. Configuring the PeerConnection with the offer and the answer,
     //    and any remote ICE candidates that came that have arrived.
     // 3. Make sure no media can flow until the user has explicitly accepted.
+    // 4. Using the ICE gatherer from the pre-gathering connection, if any.
     pub fn start_incoming(
         &mut self,
         received: signaling::ReceivedOffer,
         remote_ice_candidates: Vec<signaling::IceCandidate>,
+        pre_gathered: Option<&IceGatherer>,
     ) -> Result<signaling::Answer> {
         let result = (|| {
             self.set_state(ConnectionState::Starting)?;
@@ -777,6 +1186,12 @@ where
 
             let peer_connection = webrtc.peer_connection()?;
 
+            // This must happen before creating the answer so that it carries
+            // the ICE parameters of the gatherer.
+            if let Some(ice_gatherer) = pre_gathered {
+                peer_connection.use_shared_ice_gatherer(ice_gatherer)?;
+            }
+
             let v4_offer = received.offer.to_v4();
             let (mut offer, remote_public_key) = if let Some(v4_offer) = v4_offer.as_ref() {
                 // Set the remote mode based on the bitrate in the offer.
@@ -795,24 +1210,22 @@ where
             };
 
             let (local_secret, local_public_key) = generate_local_secret_and_public_key()?;
-            let answer_key = match remote_public_key {
+            let srtp_keys = match remote_public_key {
                 None => None,
                 Some(remote_public_key) => {
                     let caller_identity_key = &received.sender_identity_key;
                     let callee_identity_key = &received.receiver_identity_key;
-                    let NegotiatedSrtpKeys {
-                        offer_key,
-                        answer_key,
-                    } = negotiate_srtp_keys(
+                    let srtp_keys = negotiate_srtp_keys(
                         &local_secret,
                         &remote_public_key,
                         caller_identity_key,
                         callee_identity_key,
                     )?;
-                    offer.disable_dtls_and_set_srtp_key(&offer_key)?;
-                    Some(answer_key)
+                    offer.disable_dtls_and_set_srtp_key(&srtp_keys.offer_key)?;
+                    Some(srtp_keys)
                 }
             };
+            let answer_key = srtp_keys.as_ref().map(|srtp_keys| &srtp_keys.answer_key);
 
             let observer = create_ssd_observer();
             peer_connection.set_remote_description(observer.as_ref(), offer);
@@ -823,11 +1236,11 @@ where
             let observer = create_csd_observer();
             peer_connection.create_answer(observer.as_ref());
             let mut answer = observer.get_result()?;
-            if let Some(answer_key) = &answer_key {
+            if let Some(answer_key) = answer_key {
                 answer.disable_dtls_and_set_srtp_key(answer_key)?;
             }
 
-            let answer_to_send = if v4_offer.is_some() {
+            let (answer_to_send, negotiated) = if let Some(v4_offer) = v4_offer {
                 let v4_answer = answer.to_v4(
                     local_public_key.as_bytes().to_vec(),
                     &self.call_config,
@@ -842,10 +1255,17 @@ where
                 // We have to change the local answer to match what we send back
                 answer = SessionDescription::answer_from_v4(&v4_answer, &self.call_config)?;
                 // And we have to make sure to do this again since answer_from_v4 doesn't do it.
-                if let Some(answer_key) = &answer_key {
+                if let Some(answer_key) = answer_key {
                     answer.disable_dtls_and_set_srtp_key(answer_key)?;
                 }
-                signaling::Answer::from_v4(v4_answer)?
+                let negotiated = NegotiatedParameters {
+                    remote_supports_ice_restart: v4_offer.ice_restart_supported.unwrap_or(false),
+                    offer: v4_offer,
+                    answer: v4_answer.clone(),
+                    srtp_keys,
+                    ice_restart_pending: false,
+                };
+                (signaling::Answer::from_v4(v4_answer)?, negotiated)
             } else {
                 return Err(RingRtcError::UnknownSignaledProtocolVersion.into());
             };
@@ -862,7 +1282,13 @@ where
             // But it won't be processed until start_fsm() is called below.
             observer.get_result()?;
 
-            peer_connection.configure_audio_encoders(&self.call_config.audio_encoder_config);
+            peer_connection.configure_audio_encoders(
+                &self
+                    .call_config
+                    .audio_encoder_config_for(bandwidth_controller.audio_profile),
+            );
+
+            webrtc.negotiated = Some(negotiated);
 
             self.apply_bandwidth_controller(&mut bandwidth_controller, &mut webrtc)?;
 
@@ -927,11 +1353,58 @@ where
         let mut state = self.state.lock()?;
         if *state != new_state {
             *state = new_state;
+            if new_state == ConnectionState::ConnectedAndAccepted {
+                self.connected_at.lock()?.get_or_insert_with(Instant::now);
+            }
             self.notify_observer(ConnectionObserverEvent::StateChanged(new_state))?;
         }
         Ok(())
     }
 
+    /// Return the totals for the connection so far, for reporting when the
+    /// call ends.
+    pub fn call_summary(&self) -> Result<CallSummary> {
+        let connected_at = *self.connected_at.lock()?;
+        let webrtc = self.webrtc.lock()?;
+        let (media_usage, stats_time_series) = webrtc
+            .stats_observer
+            .as_ref()
+            .map(|stats_observer| (stats_observer.media_usage(), stats_observer.time_series()))
+            .unwrap_or_default();
+        Ok(CallSummary {
+            duration: connected_at.map_or(Duration::ZERO, |connected_at| connected_at.elapsed()),
+            media_usage,
+            max_participant_count: if connected_at.is_some() { 2 } else { 1 },
+            stats_time_series,
+        })
+    }
+
+    /// Return the stats from the most recent stats report, or None if
+    /// none has arrived yet.
+    pub fn stats_snapshot(&self) -> Result<Option<StatsSnapshot>> {
+        let jitter_buffer = self.call_config.jitter_buffer;
+        Ok(self
+            .webrtc
+            .lock()?
+            .stats_observer
+            .as_ref()
+            .and_then(|stats_observer| stats_observer.stats_snapshot())
+            .map(|snapshot| {
+                // There is only one remote sender, with at most one stream of each.
+                let av_sync = match (snapshot.audio_recv.first(), snapshot.video_recv.first()) {
+                    (Some(audio), Some(video)) => AvSyncStats::from_streams(audio, video)
+                        .into_iter()
+                        .collect(),
+                    _ => vec![],
+                };
+                StatsSnapshot {
+                    jitter_buffer,
+                    av_sync,
+                    ..snapshot
+                }
+            }))
+    }
+
     /// Return the current network route
     pub fn network_route(&self) -> Result<NetworkRoute> {
         let bandwidth_controller = self.bandwidth_controller.lock()?;
@@ -969,8 +1442,16 @@ where
 
     /// Needed for ICE forking (we must copy this value from the parent connection
     /// to the child connection)
-    pub fn audio_levels_interval(&self) -> Option<Duration> {
-        self.audio_levels_interval
+    pub fn audio_levels_interval(&self) -> Result<Option<Duration>> {
+        Ok(*self.audio_levels_interval.lock()?)
+    }
+
+    /// Change how often audio levels are reported, or stop reporting them
+    /// with None. Takes effect on the next tick.
+    pub fn set_audio_levels_interval(&self, interval: Option<Duration>) -> Result<()> {
+        info!("set_audio_levels_interval(): {:?}", interval);
+        *self.audio_levels_interval.lock()? = interval;
+        Ok(())
     }
 
     /// Set the incoming media.
@@ -1109,6 +1590,125 @@ where
         Ok(())
     }
 
+    /// The remote user is updating the bitrate caps via RTP data. They apply to
+    /// what is sent locally too, so only update locally (if changed).
+    pub fn set_remote_bitrate_config(&self, remote_caps: CallBitrateConfig) -> Result<()> {
+        self.update_bandwidth_controller(move |bandwidth_controller| {
+            if bandwidth_controller.remote_caps == remote_caps {
+                // Nothing changed
+                return false;
+            }
+            bandwidth_controller.remote_caps = remote_caps;
+            info!(
+                "set_remote_bitrate_config(): bandwidth_controller: {:?}",
+                bandwidth_controller
+            );
+            true
+        })?;
+        Ok(())
+    }
+
+    /// The local user is switching the audio profile via the API, such as to
+    /// start sharing music. This only affects what is sent locally.
+    pub fn update_audio_profile(&self, audio_profile: AudioProfile) -> Result<()> {
+        let audio_encoder_max = DataRate::from_bps(
+            self.call_config
+                .audio_encoder_config_for(audio_profile)
+                .max_bitrate_bps as u64,
+        );
+        self.update_bandwidth_controller(move |bandwidth_controller| {
+            if bandwidth_controller.audio_profile == audio_profile {
+                // Nothing changed
+                return false;
+            }
+            bandwidth_controller.audio_profile = audio_profile;
+            bandwidth_controller.audio_encoder_max = audio_encoder_max;
+            info!(
+                "update_audio_profile(): bandwidth_controller: {:?}",
+                bandwidth_controller
+            );
+            true
+        })?;
+        Ok(())
+    }
+
+    /// The local user is changing how outgoing audio is protected against
+    /// packet loss.
+    pub fn set_audio_loss_protection(&self, config: AudioLossProtectionConfig) -> Result<()> {
+        self.update_bandwidth_controller(move |bandwidth_controller| {
+            let changed = bandwidth_controller
+                .audio_loss_protection
+                .set_config(config);
+            info!(
+                "set_audio_loss_protection(): audio_loss_protection: {:?}",
+                bandwidth_controller.audio_loss_protection
+            );
+            changed
+        })?;
+        Ok(())
+    }
+
+    /// The local user is updating the bitrate caps via the API. Update locally
+    /// and send the caps to the remote.
+    pub fn update_bitrate_config(&self, local_caps: CallBitrateConfig) -> Result<()> {
+        let changed = self.update_bandwidth_controller(|bandwidth_controller| {
+            if bandwidth_controller.local_caps == local_caps {
+                // Nothing changed
+                return false;
+            }
+            bandwidth_controller.local_caps = local_caps;
+            info!(
+                "update_bitrate_config(): bandwidth_controller: {:?}",
+                bandwidth_controller
+            );
+            true
+        })?;
+
+        if changed {
+            self.send_bitrate_config_via_rtp_data()?;
+        }
+        Ok(())
+    }
+
+    /// Override the max send bitrates of the local data mode. This is local
+    /// only and isn't sent to the remote peer.
+    pub fn set_max_send_bitrate(&self, max_send_bitrate: MaxSendBitrate) -> Result<()> {
+        self.update_bandwidth_controller(|bandwidth_controller| {
+            if bandwidth_controller.max_send_bitrate == max_send_bitrate {
+                // Nothing changed
+                return false;
+            }
+            bandwidth_controller.max_send_bitrate = max_send_bitrate;
+            info!(
+                "set_max_send_bitrate(): bandwidth_controller: {:?}",
+                bandwidth_controller
+            );
+            true
+        })?;
+        Ok(())
+    }
+
+    /// The bitrate caps set by the local user.
+    pub fn local_bitrate_config(&self) -> Result<CallBitrateConfig> {
+        Ok(self.bandwidth_controller.lock()?.local_caps)
+    }
+
+    /// Send the local bitrate caps to the remote peer as part of the sender
+    /// status via RTP data.
+    pub fn send_bitrate_config_via_rtp_data(&self) -> Result<()> {
+        let local_caps = self.local_bitrate_config()?;
+        let bps = |cap: Option<DataRate>| Some(cap.map_or(0, DataRate::as_bps));
+
+        let mut webrtc = self.webrtc.lock()?;
+        self.update_and_send_rtp_data_message(&mut webrtc, move |data| {
+            let sender_status = data.sender_status.get_or_insert_with(Default::default);
+            sender_status.id = Some(u64::from(self.call_id));
+            sender_status.max_audio_bitrate_bps = bps(local_caps.max_audio_bitrate);
+            sender_status.max_video_bitrate_bps = bps(local_caps.max_video_bitrate);
+            sender_status.max_total_bitrate_bps = bps(local_caps.max_total_bitrate);
+        })
+    }
+
     /// Creates a timer that will regularly invoke the [`tick`](Self::tick) method.
     ///
     /// The timer will continue until the call is terminated.
@@ -1140,9 +1740,12 @@ where
 
     pub fn tick(&mut self, ticks_elapsed: u64) -> Result<()> {
         let mut webrtc = self.webrtc.lock()?;
+        let mut audio_send_loss_pct = None;
+        let mut video_send_limitation = None;
 
         if ticks_elapsed % SEND_RTP_DATA_MESSAGE_INTERVAL_TICKS == 0 {
             self.send_latest_rtp_data_message(&mut webrtc)?;
+            self.resend_unacked_app_data(&mut webrtc)?;
         }
 
         if ticks_elapsed % self.poll_stats_config.poll_stats_interval_ticks
@@ -1150,14 +1753,35 @@ where
         {
             if let Some(observer) = webrtc.stats_observer.as_ref() {
                 let _ = webrtc.peer_connection()?.get_stats(observer);
+                // Stats arrive asynchronously, so this is from the last poll.
+                let snapshot = observer.stats_snapshot();
+                audio_send_loss_pct = snapshot.as_ref().and_then(|snapshot| {
+                    snapshot
+                        .audio_send
+                        .iter()
+                        .map(|stream| stream.remote_packets_lost_pct)
+                        .reduce(f32::max)
+                });
+                video_send_limitation = snapshot.map(|snapshot| snapshot.video_send_limitation());
             } else {
                 warn!("tick(): No stats_observer found");
             }
         }
 
-        if let Some(audio_levels_interval) = self.audio_levels_interval {
+        if let Some(limitation) = video_send_limitation {
+            if limitation != self.video_send_limitation {
+                self.video_send_limitation = limitation;
+                let event = ConnectionObserverEvent::VideoAdaptationChanged { reason: limitation };
+                if let Err(err) = self.notify_observer(event) {
+                    warn!("tick(): failed to notify of video adaptation: {:?}", err);
+                }
+            }
+        }
+
+        if let Some(audio_levels_interval) = *self.audio_levels_interval.lock()? {
+            // Intervals shorter than a tick are reported every tick.
             let audio_levels_interval_ticks =
-                (audio_levels_interval.as_millis() as u64) / TICK_INTERVAL_MILLIS;
+                ((audio_levels_interval.as_millis() as u64) / TICK_INTERVAL_MILLIS).max(1);
             if ticks_elapsed % audio_levels_interval_ticks == 0 {
                 let (captured_level, received_levels) =
                     webrtc.peer_connection()?.get_audio_levels();
@@ -1237,46 +1861,231 @@ where
             }
         }
 
-        Ok(())
-    }
+        if ticks_elapsed % CHECK_MEDIA_STALL_INTERVAL_TICKS == 0 {
+            self.check_media_stall()?;
+        }
 
-    /// Check to see if this Connection is able to send messages.
-    /// Once it is terminated it shouldn't be able to.
-    pub fn can_send_messages(&self) -> bool {
-        !matches!(
-            self.state(),
-            Ok(ConnectionState::Terminating) | Ok(ConnectionState::Terminated)
-        )
+        if let Some(loss_pct) = audio_send_loss_pct {
+            // We need to always take the locks in this order. See apply_bandwidth_controller.
+            drop(webrtc);
+            self.update_bandwidth_controller(|bandwidth_controller| {
+                bandwidth_controller
+                    .audio_loss_protection
+                    .on_packet_loss(loss_pct)
+            })?;
+        }
+
+        Ok(())
     }
 
-    /// Buffer local ICE candidates, and maybe send them immediately
-    pub fn buffer_local_ice_candidates(
-        &self,
-        candidates: Vec<signaling::IceCandidate>,
-    ) -> Result<()> {
-        let (buffered_count_before, buffered_count_after) = {
-            let mut buffered_candidates = self.buffered_local_ice_candidates.lock()?;
-            let buffered_count_before = buffered_candidates.len();
-            for candidate in candidates {
-                buffered_candidates.push(candidate);
-            }
-            let buffered_count_after = buffered_candidates.len();
-            (buffered_count_before, buffered_count_after)
+    fn check_media_stall(&mut self) -> Result<()> {
+        let connected = self.state()? == ConnectionState::ConnectedAndAccepted;
+        let Some(watchdog) = self.media_watchdog.as_mut() else {
+            return Ok(());
         };
+        let received = self.rtp_received.swap(false, Ordering::AcqRel);
 
-        // Only when we transition from no candidates to some do we
-        // need to signal the message queue that there is something
-        // to send for this Connection.
-        if buffered_count_before == 0 && buffered_count_after > 0 {
-            let call = self.call()?;
-            let broadcast = self.connection_type == ConnectionType::OutgoingParent;
-            call.send_buffered_local_ice_candidates(self.clone(), broadcast)?
+        if !connected {
+            watchdog.reset();
+            return Ok(());
+        }
+        if watchdog.check(Instant::now(), received) {
+            warn!(
+                "check_media_stall(): nothing received for {:?}, id: {}",
+                watchdog.timeout, self.connection_id
+            );
+            let event = ConnectionObserverEvent::MediaStalled {
+                hang_up: self.call_config.hang_up_on_media_stall,
+            };
+            if let Err(err) = self.notify_observer(event) {
+                warn!("tick(): failed to notify of stalled media: {:?}", err);
+            }
         }
-
         Ok(())
     }
 
-    /// Get the current local ICE candidates to send to the remote peer.
+    /// Ask the remote peer to agree to us starting to send video, or stop
+    /// sending it, which takes effect right away.
+    pub fn request_video(&self, add: bool) -> Result<()> {
+        let mut webrtc = self.webrtc.lock()?;
+        let mut video_negotiation = self.video_negotiation.lock()?;
+        let request_id = match video_negotiation.request(add) {
+            Some(request_id) => request_id,
+            None => {
+                info!("request_video({}): already requested", add);
+                return Ok(());
+            }
+        };
+
+        let video_request = protobuf::rtp_data::VideoRequest {
+            id: Some(u64::from(self.call_id)),
+            request_id: Some(request_id),
+            add: Some(add),
+        };
+        self.update_and_send_rtp_data_message(&mut webrtc, move |data| {
+            data.video_request = Some(video_request)
+        })
+    }
+
+    /// Answer the remote peer's request to start sending video.
+    ///
+    /// Returns false if there was no request to answer.
+    pub fn respond_to_video_request(&self, accepted: bool) -> Result<bool> {
+        let mut webrtc = self.webrtc.lock()?;
+        let mut video_negotiation = self.video_negotiation.lock()?;
+        let request_id = match video_negotiation.respond() {
+            Some(request_id) => request_id,
+            None => return Ok(false),
+        };
+
+        let video_response = protobuf::rtp_data::VideoResponse {
+            id: Some(u64::from(self.call_id)),
+            request_id: Some(request_id),
+            accepted: Some(accepted),
+        };
+        self.update_and_send_rtp_data_message(&mut webrtc, move |data| {
+            data.video_response = Some(video_response)
+        })?;
+        Ok(true)
+    }
+
+    /// Handle a video request from the remote peer.
+    ///
+    /// Returns false for retransmissions of requests already handled.
+    pub fn handle_received_video_request(&self, request_id: u32, add: bool) -> Result<bool> {
+        let mut video_negotiation = self.video_negotiation.lock()?;
+        Ok(video_negotiation.handle_request(request_id, add))
+    }
+
+    /// Handle the remote peer's response to our last video request.
+    ///
+    /// Returns false for stale responses and retransmissions.
+    pub fn handle_received_video_response(&self, request_id: u32, accepted: bool) -> Result<bool> {
+        let mut video_negotiation = self.video_negotiation.lock()?;
+        Ok(video_negotiation.handle_response(request_id, accepted))
+    }
+
+    /// Send application data to the remote peer via RTP data.
+    ///
+    /// Reliable data is resent until acknowledged and delivered in order,
+    /// while unreliable data is sent once.
+    pub fn send_app_data(&self, data: Vec<u8>, reliable: bool) -> Result<()> {
+        if data.len() > MAX_APP_DATA_LEN {
+            return Err(RingRtcError::AppDataTooLarge(data.len(), MAX_APP_DATA_LEN).into());
+        }
+
+        let mut webrtc = self.webrtc.lock()?;
+        let app_data = if reliable {
+            self.app_data_channel
+                .lock()?
+                .push_reliable(self.call_id, data)
+                .ok_or(RingRtcError::AppDataQueueFull)?
+        } else {
+            protobuf::rtp_data::AppData {
+                id: Some(u64::from(self.call_id)),
+                sequence: None,
+                data: Some(data),
+            }
+        };
+        let message = protobuf::rtp_data::Message {
+            app_data: Some(app_data),
+            ..Default::default()
+        };
+        self.send_via_rtp_data(&mut webrtc, &message)
+    }
+
+    /// Resend the reliable application data not acknowledged yet.
+    fn resend_unacked_app_data(
+        &self,
+        webrtc_data: &mut std::sync::MutexGuard<WebRtcData<T>>,
+    ) -> Result<()> {
+        let unacked = self.app_data_channel.lock()?.unacked.clone();
+        for app_data in unacked {
+            let message = protobuf::rtp_data::Message {
+                app_data: Some(app_data),
+                ..Default::default()
+            };
+            self.send_via_rtp_data(webrtc_data, &message)?;
+        }
+        Ok(())
+    }
+
+    /// Handle application data from the remote peer, acknowledging it if
+    /// it is reliable.
+    ///
+    /// Returns false for retransmissions of data already handled.
+    pub fn handle_received_app_data(&self, sequence: Option<u32>) -> Result<bool> {
+        let sequence = match sequence {
+            Some(sequence) => sequence,
+            None => return Ok(true),
+        };
+
+        let mut webrtc = self.webrtc.lock()?;
+        let (is_next, last_received_sequence) = {
+            let mut app_data_channel = self.app_data_channel.lock()?;
+            let is_next = app_data_channel.handle_received(sequence);
+            (is_next, app_data_channel.last_received_sequence)
+        };
+        // Acknowledge retransmissions too, in case the last ack was lost.
+        let app_data_ack = protobuf::rtp_data::AppDataAck {
+            id: Some(u64::from(self.call_id)),
+            sequence: Some(last_received_sequence),
+        };
+        self.update_and_send_rtp_data_message(&mut webrtc, move |data| {
+            data.app_data_ack = Some(app_data_ack)
+        })?;
+        Ok(is_next)
+    }
+
+    /// Handle the remote peer's acknowledgment of reliable application data.
+    pub fn handle_received_app_data_ack(&self, sequence: u32) -> Result<()> {
+        self.app_data_channel.lock()?.handle_ack(sequence);
+        Ok(())
+    }
+
+    /// Check to see if this Connection is able to send messages.
+    /// Once it is terminated it shouldn't be able to.
+    pub fn can_send_messages(&self) -> bool {
+        !matches!(
+            self.state(),
+            Ok(ConnectionState::Terminating) | Ok(ConnectionState::Terminated)
+        )
+    }
+
+    /// Buffer local ICE candidates, and maybe send them immediately
+    pub fn buffer_local_ice_candidates(
+        &self,
+        candidates: Vec<signaling::IceCandidate>,
+    ) -> Result<()> {
+        let (buffered_count_before, buffered_count_after) = {
+            let mut buffered_candidates = self.buffered_local_ice_candidates.lock()?;
+            let buffered_count_before = buffered_candidates.len();
+            for candidate in candidates {
+                buffered_candidates.push(candidate);
+            }
+            let buffered_count_after = buffered_candidates.len();
+            (buffered_count_before, buffered_count_after)
+        };
+
+        // Only when we transition from no candidates to some do we
+        // need to signal the message queue that there is something
+        // to send for this Connection.
+        // The pre-gathering connection keeps its candidates until they are
+        // taken over by the incoming connection.
+        if self.connection_type == ConnectionType::IncomingPreGather {
+            return Ok(());
+        }
+
+        if buffered_count_before == 0 && buffered_count_after > 0 {
+            let call = self.call()?;
+            let broadcast = self.connection_type == ConnectionType::OutgoingParent;
+            call.send_buffered_local_ice_candidates(self.clone(), broadcast)?
+        }
+
+        Ok(())
+    }
+
+    /// Get the current local ICE candidates to send to the remote peer.
     pub fn take_buffered_local_ice_candidates(&self) -> Result<Vec<signaling::IceCandidate>> {
         info!("take_buffered_local_ice_candidates():");
         Ok(std::mem::take(
@@ -1285,6 +2094,15 @@ where
     }
 
     pub fn handle_received_ice(&self, ice: signaling::Ice) -> Result<()> {
+        // New remote credentials must be applied before the candidates that use them.
+        if let Some(restart) = ice
+            .candidates
+            .iter()
+            .find_map(|candidate| candidate.ice_restart())
+        {
+            self.handle_received_ice_restart(restart)?;
+        }
+
         let webrtc = self.webrtc.lock()?;
         let pc = webrtc.peer_connection()?;
 
@@ -1301,6 +2119,10 @@ where
         let mut removed_addresses = vec![];
         let mut removed_ports = vec![];
         for candidate in remote_ice_candidates {
+            if candidate.ice_restart().is_some() {
+                // Handled by handle_received_ice, and the added candidate is a dummy.
+                continue;
+            }
             if let Some(removed_address) = candidate.removed_address() {
                 removed_ports.push(removed_address.port());
                 removed_addresses.push(removed_address);
@@ -1335,6 +2157,151 @@ where
         Ok(())
     }
 
+    /// Restart ICE with new local credentials and send them to the remote peer,
+    /// which answers with its own new credentials.
+    pub fn restart_ice_from_fsm(&self) -> Result<()> {
+        info!("restart_ice_from_fsm(): {}", self.connection_id);
+        if let Some(local) = self.restart_ice_with(None)? {
+            self.send_ice_restart(local)?;
+        }
+        Ok(())
+    }
+
+    fn handle_received_ice_restart(&self, remote: signaling::IceRestart) -> Result<()> {
+        info!("handle_received_ice_restart(): {}", self.connection_id);
+        if let Some(local) = self.restart_ice_with(Some(remote))? {
+            // The remote peer started the restart, so answer with our side of it.
+            self.send_ice_restart(local)?;
+        }
+        Ok(())
+    }
+
+    fn send_ice_restart(&self, local: signaling::IceRestart) -> Result<()> {
+        self.buffer_local_ice_candidates(vec![signaling::IceCandidate::from_ice_restart(local)?])
+    }
+
+    // Applies new ICE credentials to the PeerConnection. New local credentials
+    // are created unless these are the remote ones answering our own restart.
+    // Returns the new local credentials to send, if any.
+    fn restart_ice_with(
+        &self,
+        remote: Option<signaling::IceRestart>,
+    ) -> Result<Option<signaling::IceRestart>> {
+        // We need to always take the locks in this order. See reconfigure_send_bandwidth.
+        let mut bandwidth_controller = self.bandwidth_controller.lock()?;
+        let mut webrtc = self.webrtc.lock()?;
+
+        let mut negotiated = match webrtc.negotiated.clone() {
+            Some(negotiated) if negotiated.remote_supports_ice_restart => negotiated,
+            Some(_) => {
+                warn!("restart_ice_with(): the remote peer doesn't support ICE restarts");
+                return Ok(None);
+            }
+            None => {
+                warn!("restart_ice_with(): nothing negotiated yet");
+                return Ok(None);
+            }
+        };
+
+        let local = if remote.is_some() && negotiated.ice_restart_pending {
+            None
+        } else {
+            Some(self.create_local_ice_credentials(&mut webrtc)?)
+        };
+
+        let (local_v4, remote_v4) = match self.direction {
+            CallDirection::Outgoing => (&mut negotiated.offer, &mut negotiated.answer),
+            CallDirection::Incoming => (&mut negotiated.answer, &mut negotiated.offer),
+        };
+        if let Some(local) = &local {
+            local_v4.ice_ufrag = Some(local.ice_ufrag.clone());
+            local_v4.ice_pwd = Some(local.ice_pwd.clone());
+        }
+        negotiated.ice_restart_pending = remote.is_none();
+        if let Some(remote) = remote {
+            remote_v4.ice_ufrag = Some(remote.ice_ufrag);
+            remote_v4.ice_pwd = Some(remote.ice_pwd);
+        }
+
+        self.apply_negotiated_parameters(
+            webrtc.peer_connection()?,
+            &negotiated,
+            bandwidth_controller.audio_profile,
+        )?;
+        webrtc.negotiated = Some(negotiated);
+
+        self.apply_bandwidth_controller(&mut bandwidth_controller, &mut webrtc)?;
+        if local.is_some() {
+            // Only the side answering its own restart has nothing new to send.
+            metrics::increment_counter(metrics::ICE_RESTARTS, 1);
+        }
+        Ok(local)
+    }
+
+    // A new shared ICE gatherer comes with new ICE credentials,
+    // which we read back from an offer that is otherwise thrown away.
+    fn create_local_ice_credentials(
+        &self,
+        webrtc: &mut WebRtcData<T>,
+    ) -> Result<signaling::IceRestart> {
+        let peer_connection = webrtc.peer_connection()?;
+        let ice_gatherer = peer_connection.create_shared_ice_gatherer()?;
+        peer_connection.use_shared_ice_gatherer(&ice_gatherer)?;
+
+        let observer = create_csd_observer();
+        peer_connection.create_offer(observer.as_ref());
+        let offer = observer.get_result()?;
+        let v4 = offer.to_v4(vec![], &self.call_config, self.call_config.data_mode)?;
+
+        webrtc.ice_gatherer = Some(ice_gatherer);
+        Ok(signaling::IceRestart {
+            ice_ufrag: v4.ice_ufrag.unwrap_or_default(),
+            ice_pwd: v4.ice_pwd.unwrap_or_default(),
+        })
+    }
+
+    // Rebuilds the descriptions and applies them in the same roles as the
+    // original negotiation.
+    fn apply_negotiated_parameters(
+        &self,
+        peer_connection: &PeerConnection,
+        negotiated: &NegotiatedParameters,
+        audio_profile: AudioProfile,
+    ) -> Result<()> {
+        let mut offer = SessionDescription::offer_from_v4(&negotiated.offer, &self.call_config)?;
+        let mut answer = SessionDescription::answer_from_v4(&negotiated.answer, &self.call_config)?;
+        if let Some(srtp_keys) = &negotiated.srtp_keys {
+            offer.disable_dtls_and_set_srtp_key(&srtp_keys.offer_key)?;
+            answer.disable_dtls_and_set_srtp_key(&srtp_keys.answer_key)?;
+        }
+
+        let observer = create_ssd_observer();
+        match self.direction {
+            CallDirection::Outgoing => {
+                peer_connection.set_local_description(observer.as_ref(), offer)
+            }
+            CallDirection::Incoming => {
+                peer_connection.set_remote_description(observer.as_ref(), offer)
+            }
+        }
+        observer.get_result()?;
+
+        let observer = create_ssd_observer();
+        match self.direction {
+            CallDirection::Outgoing => {
+                peer_connection.set_remote_description(observer.as_ref(), answer)
+            }
+            CallDirection::Incoming => {
+                peer_connection.set_local_description(observer.as_ref(), answer)
+            }
+        }
+        observer.get_result()?;
+
+        peer_connection
+            .configure_audio_encoders(&self.call_config.audio_encoder_config_for(audio_profile));
+        Ok(())
+    }
+
     /// Send a hangup message to the remote peer via RTP data.
     pub fn send_hangup_via_rtp_data(&self, hangup: signaling::Hangup) -> Result<()> {
         ringbench!(
@@ -1356,7 +2323,7 @@ where
     }
 
     /// Send an accepted message to the remote peer via RTP data.
-    pub fn send_accepted_via_rtp_data(&self) -> Result<()> {
+    pub fn send_accepted_via_rtp_data(&self, options: AcceptOptions) -> Result<()> {
         ringbench!(
             RingBench::Conn,
             RingBench::WebRtc,
@@ -1369,7 +2336,61 @@ where
 
         let mut webrtc = self.webrtc.lock()?;
         self.update_and_send_rtp_data_message(&mut webrtc, move |data| {
-            data.accepted = Some(accepted)
+            data.accepted = Some(accepted);
+            // Sent in the same message, so the remote peer never sees the
+            // call accepted with media on that is meant to start out off.
+            if options != AcceptOptions::default() {
+                self.merge_sender_status(
+                    data,
+                    signaling::SenderStatus {
+                        audio_enabled: options.audio_muted.then_some(false),
+                        video_enabled: options.video_disabled.then_some(false),
+                        ..Default::default()
+                    },
+                );
+            }
+        })
+    }
+
+    /// Whether the remote caller's offer allows sending early media.
+    pub fn remote_allows_early_media(&self) -> Result<bool> {
+        let webrtc = self.webrtc.lock()?;
+        Ok(webrtc
+            .negotiated
+            .as_ref()
+            .and_then(|negotiated| negotiated.offer.early_media)
+            .unwrap_or(false))
+    }
+
+    /// The code derived from the negotiated SRTP keys, if any were negotiated.
+    pub fn verification_code(&self) -> Result<Option<VerificationCode>> {
+        let webrtc = self.webrtc.lock()?;
+        Ok(webrtc
+            .negotiated
+            .as_ref()
+            .and_then(|negotiated| negotiated.srtp_keys.as_ref())
+            .map(|srtp_keys| srtp_keys.verification_code))
+    }
+
+    /// Start sending audio to the caller before the call is accepted, and
+    /// tell the caller via RTP data so that it starts playing it out.
+    pub fn start_early_media(&self) -> Result<()> {
+        ringbench!(
+            RingBench::Conn,
+            RingBench::WebRtc,
+            format!("dc(early_media)\t{}", self.connection_id)
+        );
+
+        let early_media = protobuf::rtp_data::EarlyMedia {
+            id: Some(u64::from(self.call_id)),
+        };
+
+        let mut webrtc = self.webrtc.lock()?;
+        let pc = webrtc.peer_connection()?;
+        pc.set_outgoing_media_enabled(true);
+        pc.set_audio_recording_enabled(true);
+        self.update_and_send_rtp_data_message(&mut webrtc, move |data| {
+            data.early_media = Some(early_media)
         })
     }
 
@@ -1408,27 +2429,75 @@ where
             max: Some(max_send_rate),
             ..SendRates::default()
         })?;
+        peer_connection.configure_audio_encoders(&capped_audio_encoder_config(
+            &bandwidth_controller.audio_loss_protection.apply(
+                &self
+                    .call_config
+                    .audio_encoder_config_for(bandwidth_controller.audio_profile),
+            ),
+            bandwidth_controller.max_audio_rate(),
+        ));
         Ok(())
     }
 
     /// Send the remote peer the current sender status via RTP data.
     pub fn update_sender_status_from_fsm(&self, updated: signaling::SenderStatus) -> Result<()> {
-        let mut webrtc = self.webrtc.lock()?;
-        self.update_and_send_rtp_data_message(&mut webrtc, move |data| {
-            let previous = data.sender_status.as_ref();
-            let previous_video_enabled =
-                previous.and_then(|sender_status| sender_status.video_enabled);
-            let previous_sharing_screen =
-                previous.and_then(|sender_status| sender_status.sharing_screen);
-            let previous_audio_enabled =
-                previous.and_then(|sender_status| sender_status.audio_enabled);
-            data.sender_status = Some(protobuf::rtp_data::SenderStatus {
-                id: Some(u64::from(self.call_id)),
-                video_enabled: updated.video_enabled.or(previous_video_enabled),
-                sharing_screen: updated.sharing_screen.or(previous_sharing_screen),
-                audio_enabled: updated.audio_enabled.or(previous_audio_enabled),
-            });
-        })
+        {
+            let mut webrtc = self.webrtc.lock()?;
+            if updated.on_hold == Some(false) {
+                let pc = webrtc.peer_connection()?;
+                pc.set_incoming_media_enabled(true);
+                pc.set_outgoing_media_enabled(true);
+            }
+            self.update_and_send_rtp_data_message(&mut webrtc, move |data| {
+                self.merge_sender_status(data, updated)
+            })?;
+            // Stop sending only after the remote side has been told why the media stopped.
+            // Incoming media is paused too, so a held call can't be heard over another call.
+            if updated.on_hold == Some(true) {
+                let pc = webrtc.peer_connection()?;
+                pc.set_outgoing_media_enabled(false);
+                pc.set_incoming_media_enabled(false);
+            }
+        }
+        // The webrtc lock must be released first. See apply_bandwidth_controller.
+        if let Some(sharing_screen) = updated.sharing_screen {
+            self.update_bandwidth_controller(|bandwidth_controller| {
+                let changed = bandwidth_controller.sharing_screen != sharing_screen;
+                bandwidth_controller.sharing_screen = sharing_screen;
+                changed
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Merge the updated fields into the sender status of the accumulated
+    /// RTP data message, keeping the rest.
+    fn merge_sender_status(
+        &self,
+        data: &mut protobuf::rtp_data::Message,
+        updated: signaling::SenderStatus,
+    ) {
+        let previous = data.sender_status.as_ref();
+        let previous_video_enabled = previous.and_then(|sender_status| sender_status.video_enabled);
+        let previous_sharing_screen =
+            previous.and_then(|sender_status| sender_status.sharing_screen);
+        let previous_audio_enabled = previous.and_then(|sender_status| sender_status.audio_enabled);
+        let previous_on_hold = previous.and_then(|sender_status| sender_status.on_hold);
+        data.sender_status = Some(protobuf::rtp_data::SenderStatus {
+            id: Some(u64::from(self.call_id)),
+            video_enabled: updated.video_enabled.or(previous_video_enabled),
+            sharing_screen: updated.sharing_screen.or(previous_sharing_screen),
+            audio_enabled: updated.audio_enabled.or(previous_audio_enabled),
+            on_hold: updated.on_hold.or(previous_on_hold),
+            // The bitrate caps are only updated by send_bitrate_config_via_rtp_data().
+            max_audio_bitrate_bps: previous
+                .and_then(|sender_status| sender_status.max_audio_bitrate_bps),
+            max_video_bitrate_bps: previous
+                .and_then(|sender_status| sender_status.max_video_bitrate_bps),
+            max_total_bitrate_bps: previous
+                .and_then(|sender_status| sender_status.max_total_bitrate_bps),
+        });
     }
 
     /// Populates a message using the supplied closure and sends it via RTP data.
@@ -1552,12 +2621,15 @@ where
         #[cfg(feature = "call_sim")]
         thread::sleep(Duration::from_millis(20));
 
+        // Audio waits for the OS to give it to the app, if it hasn't yet.
+        let audio_enabled = self.call()?.call_manager()?.audio_session_active()?;
+
         let webrtc = self.webrtc.lock()?;
         let pc = webrtc.peer_connection()?;
-        pc.set_audio_playout_enabled(true);
+        pc.set_audio_playout_enabled(audio_enabled);
         pc.set_incoming_media_enabled(true);
         pc.set_outgoing_media_enabled(true);
-        pc.set_audio_recording_enabled(true);
+        pc.set_audio_recording_enabled(audio_enabled);
 
         let incoming_media = match webrtc.incoming_media.as_ref() {
             Some(v) => v,
@@ -1574,6 +2646,33 @@ where
         call.connect_incoming_media(incoming_media)
     }
 
+    /// Start or stop audio playout and recording, as the OS gives audio to the
+    /// app or takes it away, without affecting the rest of the media.
+    pub fn set_audio_enabled(&self, enabled: bool) -> Result<()> {
+        info!(
+            "set_audio_enabled(): id: {} enabled: {}",
+            self.connection_id, enabled
+        );
+
+        let webrtc = self.webrtc.lock()?;
+        let pc = webrtc.peer_connection()?;
+        pc.set_audio_playout_enabled(enabled);
+        pc.set_audio_recording_enabled(enabled);
+        Ok(())
+    }
+
+    /// Play out the audio the callee sends before accepting. The rest of the
+    /// media is enabled as usual by enable_media() once the call is accepted.
+    pub fn enable_early_media_playout(&self) -> Result<()> {
+        info!("enable_early_media_playout(): id: {}", self.connection_id);
+
+        let webrtc = self.webrtc.lock()?;
+        let pc = webrtc.peer_connection()?;
+        pc.set_audio_playout_enabled(true);
+        pc.set_incoming_media_enabled(true);
+        Ok(())
+    }
+
     /// Send a ConnectionEvent to the internal FSM.
     fn inject_event(&mut self, event: ConnectionEvent) -> Result<()> {
         self.fsm_sender
@@ -1715,6 +2814,14 @@ where
             return Ok(());
         }
 
+        if self.call_config.relay_only && relay_protocol.is_none() {
+            info!(
+                "Local ICE candidate not relayed, so not sending it: {}",
+                candidate.to_info_string()
+            );
+            return Ok(());
+        }
+
         if let Some(relay_protocol) = relay_protocol {
             info!(
                 "Local ICE candidate: {}; {}; relay_protocol={:?}",
@@ -1817,7 +2924,8 @@ where
     }
 
     pub fn inject_received_via_rtp_data(&mut self, bytes: &[u8]) {
-        if bytes.len() > (std::mem::size_of::<protobuf::rtp_data::Message>() * 2) {
+        if bytes.len() > (std::mem::size_of::<protobuf::rtp_data::Message>() * 2 + MAX_APP_DATA_LEN)
+        {
             warn!("RTP data message is excessively large: {}", bytes.len());
             return;
         }
@@ -1854,6 +2962,33 @@ where
             };
             message_handled = true;
         };
+        if let Some(early_media) = message.early_media {
+            if let CallDirection::Outgoing = self.direction() {
+                self.inject_received_early_media_via_rtp_data(CallId::new(early_media.id()))
+                    .unwrap_or_else(|e| warn!("unable to inject remote early media event: {}", e));
+            } else {
+                warn!("Unexpected incoming early media message: {:?}", early_media);
+            };
+            message_handled = true;
+        };
+        if let Some(video_request) = message.video_request {
+            self.inject_received_video_request_via_rtp_data(
+                CallId::new(video_request.id()),
+                video_request.request_id(),
+                video_request.add(),
+            )
+            .unwrap_or_else(|e| warn!("unable to inject remote video request event: {}", e));
+            message_handled = true;
+        };
+        if let Some(video_response) = message.video_response {
+            self.inject_received_video_response_via_rtp_data(
+                CallId::new(video_response.id()),
+                video_response.request_id(),
+                video_response.accepted(),
+            )
+            .unwrap_or_else(|e| warn!("unable to inject remote video response event: {}", e));
+            message_handled = true;
+        };
         if let Some(hangup) = message.hangup {
             self.inject_received_hangup(
                 CallId::new(hangup.id()),
@@ -1867,12 +3002,28 @@ where
             message_handled = true;
         };
         if let (Some(sender_status), Some(seqnum)) = (message.sender_status, message.seqnum) {
+            if sender_status.max_audio_bitrate_bps.is_some()
+                || sender_status.max_video_bitrate_bps.is_some()
+                || sender_status.max_total_bitrate_bps.is_some()
+            {
+                self.inject_received_bitrate_config_via_rtp_data(
+                    CallId::new(sender_status.id()),
+                    CallBitrateConfig::from_bps(
+                        sender_status.max_audio_bitrate_bps(),
+                        sender_status.max_video_bitrate_bps(),
+                        sender_status.max_total_bitrate_bps(),
+                    ),
+                    seqnum,
+                )
+                .unwrap_or_else(|e| warn!("unable to inject remote bitrate config event: {}", e));
+            }
             self.inject_received_sender_status_via_rtp_data(
                 CallId::new(sender_status.id()),
                 signaling::SenderStatus {
                     video_enabled: sender_status.video_enabled,
                     sharing_screen: sender_status.sharing_screen,
                     audio_enabled: sender_status.audio_enabled,
+                    on_hold: sender_status.on_hold,
                 },
                 seqnum,
             )
@@ -1888,6 +3039,23 @@ where
             .unwrap_or_else(|e| warn!("unable to inject remote receiver status event: {}", e));
             message_handled = true;
         };
+        if let Some(app_data) = message.app_data {
+            self.inject_received_app_data_via_rtp_data(
+                CallId::new(app_data.id()),
+                app_data.sequence,
+                app_data.data.unwrap_or_default(),
+            )
+            .unwrap_or_else(|e| warn!("unable to inject remote app data event: {}", e));
+            message_handled = true;
+        };
+        if let Some(app_data_ack) = message.app_data_ack {
+            self.inject_received_app_data_ack_via_rtp_data(
+                CallId::new(app_data_ack.id()),
+                app_data_ack.sequence(),
+            )
+            .unwrap_or_else(|e| warn!("unable to inject remote app data ack event: {}", e));
+            message_handled = true;
+        };
         if !message_handled {
             info!("Unhandled RTP data message: {:?}", message);
         }
@@ -1904,6 +3072,95 @@ where
         self.inject_event(ConnectionEvent::ReceivedAcceptedViaRtpData(call_id))
     }
 
+    /// Inject a `ReceivedEarlyMediaViaRtpData` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `PeerConnectionObserver` call back thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `call_id` - Call ID from the remote peer.
+    pub fn inject_received_early_media_via_rtp_data(&mut self, call_id: CallId) -> Result<()> {
+        self.inject_event(ConnectionEvent::ReceivedEarlyMediaViaRtpData(call_id))
+    }
+
+    /// Inject a `ReceivedVideoRequestViaRtpData` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `PeerConnectionObserver` call back thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `call_id` - Call ID from the remote peer.
+    /// * `request_id` - ID of the request, increasing with each one.
+    /// * `add` - Whether the remote peer wants to start sending video.
+    pub fn inject_received_video_request_via_rtp_data(
+        &mut self,
+        call_id: CallId,
+        request_id: u32,
+        add: bool,
+    ) -> Result<()> {
+        self.inject_event(ConnectionEvent::ReceivedVideoRequestViaRtpData(
+            call_id, request_id, add,
+        ))
+    }
+
+    /// Inject a `ReceivedVideoResponseViaRtpData` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `PeerConnectionObserver` call back thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `call_id` - Call ID from the remote peer.
+    /// * `request_id` - ID of the local request being answered.
+    /// * `accepted` - Whether the remote peer agreed to receive video.
+    pub fn inject_received_video_response_via_rtp_data(
+        &mut self,
+        call_id: CallId,
+        request_id: u32,
+        accepted: bool,
+    ) -> Result<()> {
+        self.inject_event(ConnectionEvent::ReceivedVideoResponseViaRtpData(
+            call_id, request_id, accepted,
+        ))
+    }
+
+    /// Inject a `ReceivedAppDataViaRtpData` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `PeerConnectionObserver` call back thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `call_id` - Call ID from the remote peer.
+    /// * `sequence` - Sequence of the data if it was sent reliably.
+    /// * `data` - The application data.
+    pub fn inject_received_app_data_via_rtp_data(
+        &mut self,
+        call_id: CallId,
+        sequence: Option<u32>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        self.inject_event(ConnectionEvent::ReceivedAppDataViaRtpData(
+            call_id, sequence, data,
+        ))
+    }
+
+    /// Inject a `ReceivedAppDataAckViaRtpData` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `PeerConnectionObserver` call back thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `call_id` - Call ID from the remote peer.
+    /// * `sequence` - Sequence of the last reliable data the remote peer received.
+    pub fn inject_received_app_data_ack_via_rtp_data(
+        &mut self,
+        call_id: CallId,
+        sequence: u32,
+    ) -> Result<()> {
+        self.inject_event(ConnectionEvent::ReceivedAppDataAckViaRtpData(
+            call_id, sequence,
+        ))
+    }
+
     /// Inject a `ReceivedHangup` event into the FSM.
     ///
     /// `Called By:` WebRTC `PeerConnectionObserver` call back thread.
@@ -1934,6 +3191,27 @@ where
         ))
     }
 
+    /// Inject a `ReceivedBitrateConfigViaRtpData` event into the FSM.
+    ///
+    /// `Called By:` WebRTC `PeerConnectionObserver` call back thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `call_id` - Call ID from the remote peer.
+    /// * `bitrate_config` - the bitrate caps set by the remote peer.
+    pub fn inject_received_bitrate_config_via_rtp_data(
+        &mut self,
+        call_id: CallId,
+        bitrate_config: CallBitrateConfig,
+        seqnum: u64,
+    ) -> Result<()> {
+        self.inject_event(ConnectionEvent::ReceivedBitrateConfigViaRtpData(
+            call_id,
+            bitrate_config,
+            seqnum,
+        ))
+    }
+
     /// Inject a `ReceivedReceiverStatusViaRtpData` event into the FSM.
     ///
     /// `Called By:` WebRTC `PeerConnectionObserver` call back thread.
@@ -1965,8 +3243,10 @@ where
     /// Inject a local `Accept` event into the FSM.
     ///
     /// `Called By:` Local application.
-    pub fn inject_accept(&mut self) -> Result<()> {
-        self.inject_event(ConnectionEvent::Accept)
+    ///
+    /// * `options` - How the local media starts out.
+    pub fn inject_accept(&mut self, options: AcceptOptions) -> Result<()> {
+        self.inject_event(ConnectionEvent::Accept(options))
     }
 
     /// Inject a `UpdateSenderStatus` event into the FSM.
@@ -1978,6 +3258,13 @@ where
         self.inject_event(ConnectionEvent::UpdateSenderStatus(status))
     }
 
+    /// Inject a `RestartIce` event into the FSM.
+    ///
+    /// `Called By:` Local application, or the Call when the connection drops.
+    pub fn restart_ice(&mut self) -> Result<()> {
+        self.inject_event(ConnectionEvent::RestartIce)
+    }
+
     /// Inject a `UpdateDataMode` event into the FSM.
     ///
     /// `Called By:` Local application.
@@ -1987,6 +3274,18 @@ where
         self.inject_event(ConnectionEvent::UpdateDataMode(data_mode))
     }
 
+    /// Inject a `UpdateBitrateConfig` event into the FSM.
+    ///
+    /// `Called By:` Local application.
+    ///
+    /// * `bitrate_config` - The bitrate caps that should be used
+    pub fn inject_update_bitrate_config(
+        &mut self,
+        bitrate_config: CallBitrateConfig,
+    ) -> Result<()> {
+        self.inject_event(ConnectionEvent::UpdateBitrateConfig(bitrate_config))
+    }
+
     /// Inject a `ReceivedIce` event into the FSM.
     ///
     /// `Called By:` Call object.
@@ -2125,6 +3424,10 @@ where
     }
 
     fn handle_ice_connection_state_changed(&mut self, new_state: IceConnectionState) -> Result<()> {
+        diagnostics::record(
+            DiagnosticEventKind::IceChange,
+            format!("connection: {:?}", new_state),
+        );
         match new_state {
             IceConnectionState::Completed | IceConnectionState::Connected => {
                 self.inject_ice_connected()
@@ -2150,6 +3453,7 @@ where
         video_frame: Option<VideoFrame>,
     ) -> Result<()> {
         info!("Hello, incoming video frame on demuxid {}", demux_id);
+        self.setup_latency.record_video_frame();
         if let (Some(incoming_video_sink), Some(video_frame)) =
             (self.incoming_video_sink.as_ref(), video_frame)
         {
@@ -2159,6 +3463,8 @@ where
     }
 
     fn handle_rtp_received(&mut self, header: rtp::Header, payload: &[u8]) {
+        self.rtp_received.store(true, Ordering::Release);
+
         let data = match (header.pt, header.ssrc) {
             // Old clients send with 4 bytes of reserved data.
             (
@@ -2201,9 +3507,12 @@ fn generate_local_secret_and_public_key() -> Result<(StaticSecret, PublicKey)> {
     Ok((secret, public))
 }
 
+#[derive(Clone)]
 struct NegotiatedSrtpKeys {
     pub offer_key: SrtpKey,
     pub answer_key: SrtpKey,
+    /// Derived from the same shared secret, for users to compare.
+    pub verification_code: VerificationCode,
 }
 
 fn negotiate_srtp_keys(
@@ -2244,6 +3553,18 @@ fn negotiate_srtp_keys(
     let (answer_key, okm) = okm.split_at(KEY_SIZE);
     let (answer_salt, _) = okm.split_at(SALT_SIZE);
 
+    // Use a separate label so the code reveals nothing about the SRTP keys.
+    let verification_info_prefix = "Signal_Calling_20241016_SignallingDH_VerificationCode_KDF";
+    let mut verification_info = Vec::with_capacity(
+        verification_info_prefix.len() + caller_identity_key.len() + callee_identity_key.len(),
+    );
+    verification_info.extend_from_slice(verification_info_prefix.as_bytes());
+    verification_info.extend_from_slice(caller_identity_key);
+    verification_info.extend_from_slice(callee_identity_key);
+    let mut verification_okm = [0u8; 8];
+    hkdf.expand(&verification_info, &mut verification_okm)
+        .map_err(|_| RingRtcError::SrtpKeyNegotiationFailure)?;
+
     Ok(NegotiatedSrtpKeys {
         offer_key: SrtpKey {
             suite: SUITE,
@@ -2255,6 +3576,7 @@ fn negotiate_srtp_keys(
             key: answer_key.to_vec(),
             salt: answer_salt.to_vec(),
         },
+        verification_code: VerificationCode::from_key_material(verification_okm),
     })
 }
 
@@ -2277,6 +3599,13 @@ mod tests {
                 local_relay_protocol: TransportProtocol::Unknown,
                 remote_relayed: false,
             },
+            audio_profile: AudioProfile::Voice,
+            audio_encoder_max: DataRate::from_kbps(32),
+            local_caps: CallBitrateConfig::default(),
+            remote_caps: CallBitrateConfig::default(),
+            audio_loss_protection: AudioLossProtection::new(Default::default()),
+            max_send_bitrate: MaxSendBitrate::default(),
+            sharing_screen: false,
         };
 
         controller.max_send_rate()
@@ -2312,4 +3641,246 @@ mod tests {
         assert_eq!(expect(300_000), compute(Low, 1_000_000, true));
         assert_eq!(expect(300_000), compute(Low, 300_000, true));
     }
+
+    #[test]
+    fn bandwidth_controller_caps() {
+        let kbps = DataRate::from_kbps;
+        let mut controller = BandwidthController {
+            local_mode: DataMode::Normal,
+            remote_max: None,
+            network_route: NetworkRoute {
+                local_adapter_type: NetworkAdapterType::Unknown,
+                local_adapter_type_under_vpn: NetworkAdapterType::Unknown,
+                local_relayed: false,
+                local_relay_protocol: TransportProtocol::Unknown,
+                remote_relayed: false,
+            },
+            audio_profile: AudioProfile::Voice,
+            audio_encoder_max: kbps(32),
+            local_caps: CallBitrateConfig::default(),
+            remote_caps: CallBitrateConfig::default(),
+            audio_loss_protection: AudioLossProtection::new(Default::default()),
+            max_send_bitrate: MaxSendBitrate::default(),
+            sharing_screen: false,
+        };
+        assert_eq!(kbps(2_000), controller.max_send_rate());
+        assert_eq!(kbps(32), controller.max_audio_rate());
+
+        // The video cap leaves room for the audio.
+        controller.local_caps = CallBitrateConfig::from_bps(0, 500_000, 0);
+        assert_eq!(kbps(532), controller.max_send_rate());
+
+        controller.local_caps = CallBitrateConfig::from_bps(16_000, 500_000, 0);
+        assert_eq!(kbps(516), controller.max_send_rate());
+        assert_eq!(kbps(16), controller.max_audio_rate());
+
+        // The lower of the local and remote caps wins.
+        controller.remote_caps = CallBitrateConfig::from_bps(24_000, 0, 400_000);
+        assert_eq!(kbps(400), controller.max_send_rate());
+        assert_eq!(kbps(16), controller.max_audio_rate());
+
+        // The audio encoder max can't be raised.
+        controller.local_caps = CallBitrateConfig::from_bps(64_000, 0, 0);
+        controller.remote_caps = CallBitrateConfig::default();
+        assert_eq!(kbps(32), controller.max_audio_rate());
+
+        // The caps can't go below the min.
+        controller.local_caps = CallBitrateConfig::from_bps(0, 0, 10_000);
+        assert_eq!(MIN_SEND_RATE, controller.max_send_rate());
+        let audio = capped_audio_encoder_config(&AudioEncoderConfig::default(), kbps(1));
+        assert_eq!(MIN_AUDIO_BITRATE_BPS, audio.max_bitrate_bps);
+        assert_eq!(MIN_AUDIO_BITRATE_BPS, audio.initial_bitrate_bps);
+        assert_eq!(MIN_AUDIO_BITRATE_BPS, audio.min_bitrate_bps);
+    }
+
+    #[test]
+    fn bandwidth_controller_max_send_bitrate() {
+        let kbps = DataRate::from_kbps;
+        let mut controller = BandwidthController {
+            local_mode: DataMode::Low,
+            remote_max: None,
+            network_route: NetworkRoute {
+                local_adapter_type: NetworkAdapterType::Unknown,
+                local_adapter_type_under_vpn: NetworkAdapterType::Unknown,
+                local_relayed: false,
+                local_relay_protocol: TransportProtocol::Unknown,
+                remote_relayed: false,
+            },
+            audio_profile: AudioProfile::Voice,
+            audio_encoder_max: kbps(32),
+            local_caps: CallBitrateConfig::default(),
+            remote_caps: CallBitrateConfig::default(),
+            audio_loss_protection: AudioLossProtection::new(Default::default()),
+            max_send_bitrate: MaxSendBitrate::default(),
+            sharing_screen: false,
+        };
+        assert_eq!(kbps(300), controller.max_send_rate());
+
+        // The video override replaces the data mode and leaves room for the audio.
+        controller.max_send_bitrate = MaxSendBitrate::from_bps(0, 500_000, 1_000_000);
+        assert_eq!(kbps(532), controller.max_send_rate());
+        controller.sharing_screen = true;
+        assert_eq!(kbps(1_032), controller.max_send_rate());
+
+        // Without an override for the current video, the data mode applies.
+        controller.max_send_bitrate = MaxSendBitrate::from_bps(16_000, 500_000, 0);
+        assert_eq!(kbps(300), controller.max_send_rate());
+        assert_eq!(kbps(16), controller.max_audio_rate());
+        controller.sharing_screen = false;
+        assert_eq!(kbps(516), controller.max_send_rate());
+
+        // The caps and the remote max still apply.
+        controller.local_caps = CallBitrateConfig::from_bps(0, 0, 400_000);
+        assert_eq!(kbps(400), controller.max_send_rate());
+        controller.remote_max = Some(kbps(200));
+        assert_eq!(kbps(200), controller.max_send_rate());
+    }
+
+    #[test]
+    fn video_negotiation() {
+        let mut local = VideoNegotiation::new(false);
+        let mut remote = VideoNegotiation::new(false);
+
+        // Nothing to stop, and adding only waits for the remote side.
+        assert_eq!(None, local.request(false));
+        assert_eq!(Some(1), local.request(true));
+        assert_eq!(None, local.request(true));
+        assert!(!local.local_sending);
+
+        assert!(remote.handle_request(1, true));
+        assert!(!remote.handle_request(1, true));
+        assert_eq!(Some(1), remote.respond());
+        assert_eq!(None, remote.respond());
+
+        assert!(!local.handle_response(0, true));
+        assert!(local.handle_response(1, true));
+        assert!(!local.handle_response(1, true));
+        assert!(local.local_sending);
+
+        // Stopping takes effect without a response.
+        assert_eq!(Some(2), local.request(false));
+        assert!(!local.local_sending);
+        assert!(remote.handle_request(2, false));
+        assert_eq!(None, remote.respond());
+
+        // A declined request leaves video off.
+        assert_eq!(Some(3), local.request(true));
+        assert!(remote.handle_request(3, true));
+        assert_eq!(Some(3), remote.respond());
+        assert!(local.handle_response(3, false));
+        assert!(!local.local_sending);
+    }
+
+    #[test]
+    fn video_negotiation_video_call() {
+        let mut negotiation = VideoNegotiation::new(true);
+        assert!(negotiation.local_sending);
+        assert_eq!(None, negotiation.request(true));
+        assert_eq!(Some(1), negotiation.request(false));
+        assert!(!negotiation.local_sending);
+    }
+
+    #[test]
+    fn app_data_channel() {
+        let call_id = CallId::new(1);
+        let mut local = AppDataChannel::default();
+        let mut remote = AppDataChannel::default();
+
+        let first = local.push_reliable(call_id, b"a".to_vec()).unwrap();
+        let second = local.push_reliable(call_id, b"b".to_vec()).unwrap();
+        assert_eq!(Some(1), first.sequence);
+        assert_eq!(Some(2), second.sequence);
+
+        // Data after a gap waits for the missing data to be resent.
+        assert!(!remote.handle_received(2));
+        assert!(remote.handle_received(1));
+        assert!(!remote.handle_received(1));
+        assert!(remote.handle_received(2));
+
+        local.handle_ack(1);
+        assert_eq!(vec![second], Vec::from(local.unacked.clone()));
+        local.handle_ack(2);
+        assert!(local.unacked.is_empty());
+
+        for _ in 0..MAX_UNACKED_APP_DATA {
+            assert!(local.push_reliable(call_id, vec![]).is_some());
+        }
+        assert_eq!(None, local.push_reliable(call_id, vec![]));
+    }
+
+    #[test]
+    fn verification_code_matches_on_both_sides() {
+        let caller_secret = StaticSecret::from([1u8; 32]);
+        let callee_secret = StaticSecret::from([2u8; 32]);
+        let caller_public = PublicKey::from(&caller_secret);
+        let callee_public = PublicKey::from(&callee_secret);
+        let caller_identity_key = [3u8; 33];
+        let callee_identity_key = [4u8; 33];
+
+        let caller = negotiate_srtp_keys(
+            &caller_secret,
+            callee_public.as_bytes(),
+            &caller_identity_key,
+            &callee_identity_key,
+        )
+        .unwrap();
+        let callee = negotiate_srtp_keys(
+            &callee_secret,
+            caller_public.as_bytes(),
+            &caller_identity_key,
+            &callee_identity_key,
+        )
+        .unwrap();
+        assert_eq!(caller.verification_code, callee.verification_code);
+        assert_eq!(6, caller.verification_code.to_string().len());
+
+        // Someone in the middle has a different shared secret with each side.
+        let mitm_secret = StaticSecret::from([5u8; 32]);
+        let mitm_public = PublicKey::from(&mitm_secret);
+        let intercepted = negotiate_srtp_keys(
+            &caller_secret,
+            mitm_public.as_bytes(),
+            &caller_identity_key,
+            &callee_identity_key,
+        )
+        .unwrap();
+        assert_ne!(caller.verification_code, intercepted.verification_code);
+    }
+
+    #[test]
+    fn verification_code_display() {
+        assert_eq!(
+            "000042",
+            VerificationCode::from_key_material(42u64.to_be_bytes()).to_string()
+        );
+        assert_eq!(
+            123_456,
+            VerificationCode::from_key_material(7_123_456u64.to_be_bytes()).value()
+        );
+    }
+
+    #[test]
+    fn media_watchdog() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut watchdog = MediaWatchdog::new(Duration::from_secs(5));
+
+        // The first check starts watching, even if nothing was received.
+        assert!(!watchdog.check(at(0), false));
+        assert!(!watchdog.check(at(4), false));
+        assert!(watchdog.check(at(5), false));
+        // A stall is only reported once.
+        assert!(!watchdog.check(at(6), false));
+
+        // Receiving RTP again starts over.
+        assert!(!watchdog.check(at(7), true));
+        assert!(!watchdog.check(at(11), false));
+        assert!(watchdog.check(at(12), false));
+
+        // Nothing is reported right after a reset.
+        watchdog.reset();
+        assert!(!watchdog.check(at(20), false));
+        assert!(!watchdog.check(at(24), false));
+        assert!(watchdog.check(at(25), false));
+    }
 }
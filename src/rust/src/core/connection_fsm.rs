@@ -20,6 +20,7 @@
 //! - AnswerCall
 //! - LocalHangup
 //! - UpdateSenderStatus
+//! - RestartIce
 //! - SendReceiverStatusViaRtpData
 //! - SendBusy
 //! - ReceivedIce
@@ -33,7 +34,13 @@
 //! - IceDisconnected
 //! - ReceivedIncomingMedia
 //! - ReceivedAcceptedViaRtpData
+//! - ReceivedEarlyMediaViaRtpData
+//! - ReceivedVideoRequestViaRtpData
+//! - ReceivedVideoResponseViaRtpData
+//! - ReceivedAppDataViaRtpData
+//! - ReceivedAppDataAckViaRtpData
 //! - ReceivedSenderStatusViaRtpData
+//! - ReceivedBitrateConfigViaRtpData
 //! - ReceivedReceiverStatusViaRtpData
 //! - ReceivedHangup
 //!
@@ -55,7 +62,8 @@ use crate::{
     common::{
         actor::{Actor, Stopper},
         units::DataRate,
-        CallDirection, CallId, ConnectionState, DataMode, Result, RingBench,
+        AcceptOptions, CallBitrateConfig, CallDirection, CallId, ConnectionState, DataMode, Result,
+        RingBench,
     },
     core::{
         connection::{Connection, ConnectionObserverEvent, EventStream},
@@ -83,16 +91,41 @@ pub enum ConnectionEvent {
     SendHangupViaRtpData(signaling::Hangup),
     /// Accept incoming call (callee only).
     /// Source: app (user action)
-    /// Action: got to "accepted" state and send accept message via RTP data.
-    Accept,
+    /// Action: got to "accepted" state and send accept message via RTP data,
+    /// along with the initial sender status if any media starts out off.
+    Accept(AcceptOptions),
     /// Receive accepted message from remote peer.
     /// Source: RTP data
     /// Action: bubble up to Call and transition states
     ReceivedAcceptedViaRtpData(CallId),
+    /// Receive early media message from remote peer (caller only).
+    /// Source: RTP data
+    /// Action: play out the remote audio while ringing and bubble up to Call.
+    ReceivedEarlyMediaViaRtpData(CallId),
+    /// Receive a request to start or stop sending video from remote peer.
+    /// Source: RTP data
+    /// Action: Update the video direction if stopping and bubble up to app.
+    ReceivedVideoRequestViaRtpData(CallId, u32, bool),
+    /// Receive the response to a local request to start sending video.
+    /// Source: RTP data
+    /// Action: Update the video direction if accepted and bubble up to app.
+    ReceivedVideoResponseViaRtpData(CallId, u32, bool),
+    /// Receive application data from remote peer, with a sequence if reliable.
+    /// Source: RTP data
+    /// Action: Acknowledge it if reliable and bubble up to app.
+    ReceivedAppDataViaRtpData(CallId, Option<u32>, Vec<u8>),
+    /// Receive the acknowledgment of reliable application data.
+    /// Source: RTP data
+    /// Action: Stop resending the acknowledged data.
+    ReceivedAppDataAckViaRtpData(CallId, u32),
     /// Receive sender status change from remote peer.
     /// Source: RTP data
     /// Action: Bubble up to app, which should change the "in call" screen.
     ReceivedSenderStatusViaRtpData(CallId, signaling::SenderStatus, u64),
+    /// Receive bitrate caps from remote peer, sent with its sender status.
+    /// Source: RTP data
+    /// Action: Apply the caps to what is sent locally.
+    ReceivedBitrateConfigViaRtpData(CallId, CallBitrateConfig, u64),
     /// Receive receiver status change from remote peer.
     /// Source: RTP data
     /// Action: Make adjustments in connection if necessary.
@@ -105,6 +138,14 @@ pub enum ConnectionEvent {
     /// Source: app (user setting)
     /// Action: Update and send bitrate via a receiver status message via RTP data.
     UpdateDataMode(DataMode),
+    /// Set bitrate caps
+    /// Source: app (user setting)
+    /// Action: Update and send the caps via a sender status message via RTP data.
+    UpdateBitrateConfig(CallBitrateConfig),
+    /// Restart ICE with new credentials
+    /// Source: app (e.g. network change) or the Call (connection dropped)
+    /// Action: Apply new local ICE credentials and send them over signaling.
+    RestartIce,
     /// Local ICE candidates added or removed from PeerConnection
     /// Source: PeerConnection
     /// Action: Send ICE candidate (addition or removal) over signaling.
@@ -146,19 +187,54 @@ pub enum ConnectionEvent {
 impl fmt::Display for ConnectionEvent {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let display = match self {
-            ConnectionEvent::Accept => "Accept".to_string(),
+            ConnectionEvent::Accept(options) => format!("Accept, options: {:?}", options),
             ConnectionEvent::ReceivedHangup(call_id, hangup) => {
                 format!("RemoteHangup, call_id: {} hangup: {}", call_id, hangup)
             }
             ConnectionEvent::ReceivedAcceptedViaRtpData(id) => {
                 format!("ReceivedAcceptedViaRtpData, call_id: {}", id)
             }
+            ConnectionEvent::ReceivedEarlyMediaViaRtpData(id) => {
+                format!("ReceivedEarlyMediaViaRtpData, call_id: {}", id)
+            }
+            ConnectionEvent::ReceivedVideoRequestViaRtpData(id, request_id, add) => {
+                format!(
+                    "ReceivedVideoRequestViaRtpData, call_id: {}, request_id: {}, add: {}",
+                    id, request_id, add
+                )
+            }
+            ConnectionEvent::ReceivedVideoResponseViaRtpData(id, request_id, accepted) => {
+                format!(
+                    "ReceivedVideoResponseViaRtpData, call_id: {}, request_id: {}, accepted: {}",
+                    id, request_id, accepted
+                )
+            }
+            ConnectionEvent::ReceivedAppDataViaRtpData(id, sequence, data) => {
+                format!(
+                    "ReceivedAppDataViaRtpData, call_id: {}, sequence: {:?}, len: {}",
+                    id,
+                    sequence,
+                    data.len()
+                )
+            }
+            ConnectionEvent::ReceivedAppDataAckViaRtpData(id, sequence) => {
+                format!(
+                    "ReceivedAppDataAckViaRtpData, call_id: {}, sequence: {}",
+                    id, sequence
+                )
+            }
             ConnectionEvent::ReceivedSenderStatusViaRtpData(id, status, seqnum) => {
                 format!(
                     "ReceivedSenderStatusViaRtpData, call_id: {}, status: {:?}, seqnum: {:?}",
                     id, status, seqnum
                 )
             }
+            ConnectionEvent::ReceivedBitrateConfigViaRtpData(id, bitrate_config, seqnum) => {
+                format!(
+                    "ReceivedBitrateConfigViaRtpData, call_id: {}, bitrate_config: {:?}, seqnum: {:?}",
+                    id, bitrate_config, seqnum
+                )
+            }
             ConnectionEvent::ReceivedReceiverStatusViaRtpData(id, max_bitrate, seqnum) => {
                 format!(
                     "ReceivedReceiverStatusViaRtpData, call_id: {}, max_bitrate: {:?}, seqnum: {:?}",
@@ -175,6 +251,10 @@ impl fmt::Display for ConnectionEvent {
             ConnectionEvent::UpdateDataMode(mode) => {
                 format!("UpdateDataMode, mode: {:?}", mode)
             }
+            ConnectionEvent::UpdateBitrateConfig(bitrate_config) => {
+                format!("UpdateBitrateConfig, bitrate_config: {:?}", bitrate_config)
+            }
+            ConnectionEvent::RestartIce => "RestartIce".to_string(),
             ConnectionEvent::LocalIceCandidates(_) => "LocalIceCandidates".to_string(),
             ConnectionEvent::IceConnected => "IceConnected".to_string(),
             ConnectionEvent::IceFailed => "IceConnectionFailed".to_string(),
@@ -230,6 +310,12 @@ where
     /// We process remote receiver status messages larger than the seqnum
     /// and use the bitrate when it changes.
     last_remote_receiver_status: Option<(u64, DataRate)>,
+    /// The sequence number of the last received remote bitrate caps.
+    /// We process them like the receiver status, but they come with the
+    /// sender status.
+    last_remote_bitrate_config: Option<(u64, CallBitrateConfig)>,
+    /// Whether early media from the remote callee is being played out.
+    remote_early_media: bool,
 }
 
 impl<T> fmt::Display for ConnectionStateMachine<T>
@@ -253,6 +339,8 @@ where
             notify_thread: Actor::start("connection-fsm-notify", Stopper::new(), |_| Ok(()))?,
             last_remote_sender_status: None,
             last_remote_receiver_status: None,
+            last_remote_bitrate_config: None,
+            remote_early_media: false,
         })
     }
 
@@ -270,6 +358,10 @@ where
                     ConnectionState::ConnectedAndAccepted,
                     ConnectionEvent::ReceivedSenderStatusViaRtpData(_, _, _),
                 )
+                | (
+                    ConnectionState::ConnectedAndAccepted,
+                    ConnectionEvent::ReceivedBitrateConfigViaRtpData(_, _, _),
+                )
                 | (
                     ConnectionState::ConnectedAndAccepted,
                     ConnectionEvent::ReceivedReceiverStatusViaRtpData(_, _, _),
@@ -277,6 +369,22 @@ where
                 | (
                     ConnectionState::ConnectedAndAccepted,
                     ConnectionEvent::ReceivedAcceptedViaRtpData(_),
+                )
+                | (
+                    ConnectionState::ConnectedAndAccepted,
+                    ConnectionEvent::ReceivedEarlyMediaViaRtpData(_),
+                )
+                | (
+                    ConnectionState::ConnectedAndAccepted,
+                    ConnectionEvent::ReceivedVideoRequestViaRtpData(_, _, _),
+                )
+                | (
+                    ConnectionState::ConnectedAndAccepted,
+                    ConnectionEvent::ReceivedVideoResponseViaRtpData(_, _, _),
+                )
+                | (
+                    ConnectionState::ConnectedAndAccepted,
+                    ConnectionEvent::ReceivedAppDataAckViaRtpData(_, _),
                 ) => {
                     // Don't log periodic, ignored events at high verbosity
                     debug!("state: {}, event: {}", state, event)
@@ -361,12 +469,35 @@ where
             ConnectionEvent::ReceivedHangup(call_id, hangup) => {
                 self.handle_received_hangup(connection, state, call_id, hangup)
             }
-            ConnectionEvent::Accept => self.handle_accept(connection, state),
+            ConnectionEvent::Accept(options) => self.handle_accept(connection, state, options),
             ConnectionEvent::ReceivedAcceptedViaRtpData(id) => {
                 self.handle_received_accepted_via_rtp_data(connection, state, id)
             }
+            ConnectionEvent::ReceivedEarlyMediaViaRtpData(id) => {
+                self.handle_received_early_media_via_rtp_data(connection, state, id)
+            }
+            ConnectionEvent::ReceivedVideoRequestViaRtpData(id, request_id, add) => self
+                .handle_received_video_request_via_rtp_data(connection, state, id, request_id, add),
+            ConnectionEvent::ReceivedVideoResponseViaRtpData(id, request_id, accepted) => self
+                .handle_received_video_response_via_rtp_data(
+                    connection, state, id, request_id, accepted,
+                ),
+            ConnectionEvent::ReceivedAppDataViaRtpData(id, sequence, data) => {
+                self.handle_received_app_data_via_rtp_data(connection, state, id, sequence, data)
+            }
+            ConnectionEvent::ReceivedAppDataAckViaRtpData(id, sequence) => {
+                self.handle_received_app_data_ack_via_rtp_data(connection, id, sequence)
+            }
             ConnectionEvent::ReceivedSenderStatusViaRtpData(id, status, seqnum) => self
                 .handle_received_sender_status_via_rtp_data(connection, state, id, status, seqnum),
+            ConnectionEvent::ReceivedBitrateConfigViaRtpData(id, bitrate_config, seqnum) => self
+                .handle_received_bitrate_config_via_rtp_data(
+                    connection,
+                    state,
+                    id,
+                    bitrate_config,
+                    seqnum,
+                ),
             ConnectionEvent::ReceivedReceiverStatusViaRtpData(id, max_bitrate, seqnum) => self
                 .handle_received_receiver_status_via_rtp_data(
                     connection,
@@ -382,6 +513,10 @@ where
             ConnectionEvent::UpdateDataMode(mode) => {
                 self.handle_update_data_mode(connection, state, mode)
             }
+            ConnectionEvent::UpdateBitrateConfig(bitrate_config) => {
+                self.handle_update_bitrate_config(connection, state, bitrate_config)
+            }
+            ConnectionEvent::RestartIce => self.handle_restart_ice(connection, state),
             ConnectionEvent::LocalIceCandidates(candidates) => {
                 self.handle_local_ice_candidates(connection, state, candidates)
             }
@@ -418,6 +553,7 @@ where
     fn handle_connected_and_accepted_for_the_first_time(
         &mut self,
         connection: Connection<T>,
+        accept_options: AcceptOptions,
     ) -> Result<()> {
         connection.set_state(ConnectionState::ConnectedAndAccepted)?;
         // We may have received status messages while ringing, which we now must
@@ -425,11 +561,17 @@ where
         if let Some((_, max_bitrate)) = self.last_remote_receiver_status {
             Self::handle_remote_receiver_status_changed(&connection, max_bitrate)?;
         }
+        if let Some((_, bitrate_config)) = self.last_remote_bitrate_config {
+            connection.set_remote_bitrate_config(bitrate_config)?;
+        }
         if let Some((_, status)) = self.last_remote_sender_status {
             Self::handle_remote_sender_status_changed(&connection, status)?;
         }
         if connection.direction() == CallDirection::Incoming {
-            self.send_accepted_via_rtp_data(connection);
+            self.send_accepted_via_rtp_data(connection.clone(), accept_options);
+        }
+        if connection.local_bitrate_config()? != CallBitrateConfig::default() {
+            self.send_bitrate_config_via_rtp_data(connection);
         }
         Ok(())
     }
@@ -452,13 +594,17 @@ where
         connection.notify_observer(ConnectionObserverEvent::RemoteSenderStatusChanged(status))
     }
 
-    fn send_accepted_via_rtp_data(&mut self, mut connection: Connection<T>) {
+    fn send_accepted_via_rtp_data(
+        &mut self,
+        mut connection: Connection<T>,
+        accept_options: AcceptOptions,
+    ) {
         self.worker_spawn(move || {
             let result = try_scoped(|| {
                 if connection.terminating()? {
                     return Ok(());
                 }
-                connection.send_accepted_via_rtp_data()
+                connection.send_accepted_via_rtp_data(accept_options)
             });
             if let Err(err) = result {
                 connection.inject_internal_error(err, "Sending Accepted failed");
@@ -466,6 +612,20 @@ where
         });
     }
 
+    fn send_bitrate_config_via_rtp_data(&mut self, mut connection: Connection<T>) {
+        self.worker_spawn(move || {
+            let result = try_scoped(|| {
+                if connection.terminating()? {
+                    return Ok(());
+                }
+                connection.send_bitrate_config_via_rtp_data()
+            });
+            if let Err(err) = result {
+                connection.inject_internal_error(err, "Sending bitrate config failed");
+            }
+        });
+    }
+
     fn handle_received_hangup(
         &mut self,
         connection: Connection<T>,
@@ -528,7 +688,10 @@ where
                         connection.connection_id()
                     )
                 );
-                self.handle_connected_and_accepted_for_the_first_time(connection)?;
+                self.handle_connected_and_accepted_for_the_first_time(
+                    connection,
+                    AcceptOptions::default(),
+                )?;
             }
             ConnectionState::ConnectingAfterAccepted
             | ConnectionState::ConnectedAndAccepted
@@ -546,6 +709,150 @@ where
         Ok(())
     }
 
+    fn handle_received_early_media_via_rtp_data(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        call_id: CallId,
+    ) -> Result<()> {
+        if connection.call_id() != call_id {
+            warn!("Remote early media for non-active call");
+            return Ok(());
+        }
+        match state {
+            ConnectionState::ConnectingBeforeAccepted
+            | ConnectionState::ConnectedBeforeAccepted => {
+                if !connection.call_config().early_media {
+                    warn!("Ignoring remote early media, which wasn't offered");
+                    return Ok(());
+                }
+                if self.remote_early_media {
+                    // Ignore retransmissions of the same RTP data message.
+                    return Ok(());
+                }
+                ringbench!(
+                    RingBench::WebRtc,
+                    RingBench::Conn,
+                    format!("dc(early_media)\t{}", connection.connection_id())
+                );
+                self.remote_early_media = true;
+                connection.enable_early_media_playout()?;
+                self.notify_observer(connection, ConnectionObserverEvent::RemoteEarlyMedia);
+            }
+            ConnectionState::ConnectingAfterAccepted
+            | ConnectionState::ConnectedAndAccepted
+            | ConnectionState::ReconnectingAfterAccepted => {
+                // All media is already enabled. The message may still be
+                // retransmitted along with the accepted message.
+            }
+            ConnectionState::NotYetStarted
+            | ConnectionState::Starting
+            | ConnectionState::IceGathering
+            | ConnectionState::IceFailed
+            | ConnectionState::Terminating
+            | ConnectionState::Terminated => {
+                self.unexpected_state(state, "ReceivedEarlyMediaViaRtpData");
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_received_video_request_via_rtp_data(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        call_id: CallId,
+        request_id: u32,
+        add: bool,
+    ) -> Result<()> {
+        if connection.call_id() != call_id {
+            warn!("Remote video request for non-active call");
+            return Ok(());
+        }
+        if !matches!(
+            state,
+            ConnectionState::ConnectedAndAccepted | ConnectionState::ReconnectingAfterAccepted
+        ) {
+            self.unexpected_state(state, "ReceivedVideoRequestViaRtpData");
+            return Ok(());
+        }
+        // Every message carries the last request, so most are retransmissions.
+        if connection.handle_received_video_request(request_id, add)? {
+            self.notify_observer(
+                connection,
+                ConnectionObserverEvent::RemoteVideoRequest { add },
+            );
+        }
+        Ok(())
+    }
+
+    fn handle_received_video_response_via_rtp_data(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        call_id: CallId,
+        request_id: u32,
+        accepted: bool,
+    ) -> Result<()> {
+        if connection.call_id() != call_id {
+            warn!("Remote video response for non-active call");
+            return Ok(());
+        }
+        if !matches!(
+            state,
+            ConnectionState::ConnectedAndAccepted | ConnectionState::ReconnectingAfterAccepted
+        ) {
+            self.unexpected_state(state, "ReceivedVideoResponseViaRtpData");
+            return Ok(());
+        }
+        if connection.handle_received_video_response(request_id, accepted)? {
+            self.notify_observer(
+                connection,
+                ConnectionObserverEvent::VideoRequestResponse { accepted },
+            );
+        }
+        Ok(())
+    }
+
+    fn handle_received_app_data_via_rtp_data(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        call_id: CallId,
+        sequence: Option<u32>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        if connection.call_id() != call_id {
+            warn!("Remote app data for non-active call");
+            return Ok(());
+        }
+        if !matches!(
+            state,
+            ConnectionState::ConnectedAndAccepted | ConnectionState::ReconnectingAfterAccepted
+        ) {
+            self.unexpected_state(state, "ReceivedAppDataViaRtpData");
+            return Ok(());
+        }
+        // Reliable data is resent until acknowledged, so some are retransmissions.
+        if connection.handle_received_app_data(sequence)? {
+            self.notify_observer(connection, ConnectionObserverEvent::AppData(data));
+        }
+        Ok(())
+    }
+
+    fn handle_received_app_data_ack_via_rtp_data(
+        &mut self,
+        connection: Connection<T>,
+        call_id: CallId,
+        sequence: u32,
+    ) -> Result<()> {
+        if connection.call_id() != call_id {
+            warn!("Remote app data ack for non-active call");
+            return Ok(());
+        }
+        connection.handle_received_app_data_ack(sequence)
+    }
+
     fn handle_received_sender_status_via_rtp_data(
         &mut self,
         connection: Connection<T>,
@@ -608,6 +915,62 @@ where
         Ok(())
     }
 
+    fn handle_received_bitrate_config_via_rtp_data(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        call_id: CallId,
+        bitrate_config: CallBitrateConfig,
+        seqnum: u64,
+    ) -> Result<()> {
+        debug!(
+            "handle_received_bitrate_config_via_rtp_data(): bitrate_config: {:?}, seqnum: {:?}",
+            bitrate_config, seqnum
+        );
+
+        if connection.call_id() != call_id {
+            warn!("Remote bitrate config change for non-active call");
+            return Ok(());
+        }
+
+        let changed = match self.last_remote_bitrate_config {
+            // This is the first sequence number
+            None => true,
+            Some((last_seqnum, last_bitrate_config)) => {
+                // Retransmits and out of order messages were already warned
+                // about for the sender status they came with.
+                if seqnum <= last_seqnum {
+                    return Ok(());
+                }
+
+                bitrate_config != last_bitrate_config
+            }
+        };
+        self.last_remote_bitrate_config = Some((seqnum, bitrate_config));
+
+        match state {
+            ConnectionState::ConnectedAndAccepted | ConnectionState::ReconnectingAfterAccepted => {
+                if changed {
+                    connection.set_remote_bitrate_config(bitrate_config)?;
+                }
+            }
+            ConnectionState::ConnectingBeforeAccepted
+            | ConnectionState::ConnectingAfterAccepted
+            | ConnectionState::ConnectedBeforeAccepted => {
+                // Ignore before active
+            }
+            ConnectionState::NotYetStarted
+            | ConnectionState::Starting
+            | ConnectionState::IceGathering
+            | ConnectionState::IceFailed
+            | ConnectionState::Terminating
+            | ConnectionState::Terminated => {
+                self.unexpected_state(state, "ReceivedBitrateConfigViaRtpData");
+            }
+        };
+        Ok(())
+    }
+
     fn handle_received_receiver_status_via_rtp_data(
         &mut self,
         connection: Connection<T>,
@@ -692,9 +1055,14 @@ where
         Ok(())
     }
 
-    fn handle_accept(&mut self, connection: Connection<T>, state: ConnectionState) -> Result<()> {
+    fn handle_accept(
+        &mut self,
+        connection: Connection<T>,
+        state: ConnectionState,
+        options: AcceptOptions,
+    ) -> Result<()> {
         if state.can_be_accepted_locally() {
-            self.handle_connected_and_accepted_for_the_first_time(connection)?;
+            self.handle_connected_and_accepted_for_the_first_time(connection, options)?;
         } else {
             self.unexpected_state(state, "AcceptCall");
         }
@@ -766,6 +1134,51 @@ where
         Ok(())
     }
 
+    fn handle_update_bitrate_config(
+        &mut self,
+        mut connection: Connection<T>,
+        state: ConnectionState,
+        bitrate_config: CallBitrateConfig,
+    ) -> Result<()> {
+        if state.connecting_or_connected() {
+            self.worker_spawn(move || {
+                let result = try_scoped(|| {
+                    if connection.terminating()? {
+                        return Ok(());
+                    }
+                    connection.update_bitrate_config(bitrate_config)
+                });
+                if let Err(err) = result {
+                    connection.inject_internal_error(err, "Updating bitrate config failed");
+                }
+            });
+        };
+        Ok(())
+    }
+
+    fn handle_restart_ice(
+        &mut self,
+        mut connection: Connection<T>,
+        state: ConnectionState,
+    ) -> Result<()> {
+        if state.connected_or_reconnecting() {
+            self.worker_spawn(move || {
+                let result = try_scoped(|| {
+                    if connection.terminating()? {
+                        return Ok(());
+                    }
+                    connection.restart_ice_from_fsm()
+                });
+                if let Err(err) = result {
+                    connection.inject_internal_error(err, "Restarting ICE failed");
+                }
+            });
+        } else {
+            self.unexpected_state(state, "RestartIce");
+        }
+        Ok(())
+    }
+
     fn handle_local_ice_candidates(
         &mut self,
         mut connection: Connection<T>,
@@ -814,7 +1227,10 @@ where
                 connection.set_state(ConnectionState::ConnectedBeforeAccepted)?;
             }
             ConnectionState::ConnectingAfterAccepted => {
-                self.handle_connected_and_accepted_for_the_first_time(connection)?;
+                self.handle_connected_and_accepted_for_the_first_time(
+                    connection,
+                    AcceptOptions::default(),
+                )?;
             }
             ConnectionState::ConnectedBeforeAccepted | ConnectionState::ConnectedAndAccepted => {
                 // Already connected, so this shouldn't happen.
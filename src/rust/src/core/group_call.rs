@@ -4,7 +4,7 @@
 //
 
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     convert::TryInto,
     hash::{Hash, Hasher},
     iter::FromIterator,
@@ -28,26 +28,40 @@ use crate::{
     common::{
         actor::{Actor, Stopper},
         units::DataRate,
-        CallId, DataMode, Result,
+        CallId, CallSummary, DataMode, Result,
+    },
+    core::{
+        call_mutex::CallMutex,
+        clock::Clock,
+        connection::MAX_APP_DATA_LEN,
+        crypto as frame_crypto,
+        setup_latency::{SetupLatencyTracker, SetupMilestone},
+        signaling,
+        util::uuid_to_string,
     },
-    core::{call_mutex::CallMutex, crypto as frame_crypto, signaling, util::uuid_to_string},
     error::RingRtcError,
     lite::{
-        http, sfu,
+        crash_dump,
+        diagnostics::{self, DiagnosticEventKind},
+        http, metrics, sfu,
         sfu::{
             ClientStatus, DemuxId, GroupMember, MemberMap, MembershipProof, ObfuscatedResolver,
-            PeekInfo, PeekResult, PeekResultCallback, UserId,
+            ParticipantKind, PeekInfo, PeekResult, PeekResultCallback, UserId,
         },
     },
     protobuf,
     protobuf::group_call::{DeviceToSfu, SfuToDevice},
     webrtc::{
         self,
+        ice_gatherer::IceGatherer,
         media::{
-            AudioEncoderConfig, AudioTrack, VideoFrame, VideoFrameMetadata, VideoSink, VideoTrack,
+            AudioEncoderConfig, AudioLossProtection, AudioLossProtectionConfig, AudioProfile,
+            AudioTrack, VideoFrame, VideoFrameMetadata, VideoSink, VideoTrack,
         },
         peer_connection::{AudioLevel, PeerConnection, Protocol, ReceivedAudioLevel, SendRates},
-        peer_connection_factory::{self as pcf, AudioJitterBufferConfig, PeerConnectionFactory},
+        peer_connection_factory::{
+            self as pcf, AudioJitterBufferConfig, IceServer, PeerConnectionFactory,
+        },
         peer_connection_observer::{
             IceConnectionState, NetworkRoute, PeerConnectionObserver, PeerConnectionObserverTrait,
         },
@@ -55,7 +69,10 @@ use crate::{
         sdp_observer::{
             create_csd_observer, create_ssd_observer, SessionDescription, SrtpCryptoSuite, SrtpKey,
         },
-        stats_observer::{create_stats_observer, StatsObserver},
+        stats_observer::{
+            create_stats_observer, AvSyncStats, QualityLimitationReason, ReceiveQualitySample,
+            StatsObserver, StatsSnapshot,
+        },
     },
 };
 
@@ -65,6 +82,9 @@ pub type ClientId = u32;
 // Group UUID
 pub type GroupId = Vec<u8>;
 pub type GroupIdRef<'a> = &'a [u8];
+// Breakout groups are defined by the SFU. 0 is never used, since it stands for the
+// main call when assigning devices.
+pub type BreakoutGroupId = u32;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct RingId(i64);
@@ -197,11 +217,21 @@ const STOPPED_SPEAKING_DURATION: Duration = Duration::from_secs(3);
 // Amount of "continuous" speech (i.e., with gaps no longer than `STOPPED_SPEAKING_DURATION`)
 // after which we suggest lowering a raised hand.
 const MIN_SPEAKING_HAND_LOWER: Duration = Duration::from_secs(5);
+// Amount of "continuous" speech while muted (i.e., with gaps no longer than
+// `SPEAKING_WHILE_MUTED_MAX_GAP`) after which we tell the user they may be muted.
+const MIN_SPEAKING_WHILE_MUTED: Duration = Duration::from_secs(2);
+// Shorter than `STOPPED_SPEAKING_DURATION` so that background noise doesn't add up
+// to "speech" as easily.
+const SPEAKING_WHILE_MUTED_MAX_GAP: Duration = Duration::from_secs(1);
+// The minimum time between two "speaking while muted" notifications, so the user
+// isn't nagged continuously if they're muted on purpose.
+const SPEAKING_WHILE_MUTED_NOTIFICATION_INTERVAL: Duration = Duration::from_secs(30);
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum SpeechEvent {
     StoppedSpeaking = 0,
     LowerHandSuggestion,
+    SpeakingWhileMuted,
 }
 
 impl SpeechEvent {
@@ -210,6 +240,7 @@ impl SpeechEvent {
         match self {
             SpeechEvent::StoppedSpeaking => 0,
             SpeechEvent::LowerHandSuggestion => 1,
+            SpeechEvent::SpeakingWhileMuted => 2,
         }
     }
 }
@@ -306,8 +337,74 @@ pub trait Observer {
 
     fn handle_raised_hands(&self, client_id: ClientId, raised_hands: Vec<DemuxId>);
 
+    // Called with data another device's application sent with send_app_data().
+    fn handle_app_data(&self, _client_id: ClientId, _demux_id: DemuxId, _data: Vec<u8>) {}
+
+    // Called after each stats collection with the receive quality of every remote
+    // device we received media from during the interval.
+    fn handle_remote_connection_qualities(
+        &self,
+        _client_id: ClientId,
+        _qualities: Vec<RemoteConnectionQuality>,
+    ) {
+    }
+
+    // Called after a stats collection in which the audio and video of any remote
+    // devices were further out of sync than AV_SYNC_SKEW_WARNING_SECS.
+    fn handle_av_sync_skew(&self, _client_id: ClientId, _skews: Vec<RemoteAvSyncSkew>) {}
+
+    // Called every E2EE_HEALTH_INTERVAL in which any frames were received, with
+    // how many of them decrypted.
+    fn handle_e2ee_health(&self, _client_id: ClientId, _health: E2eeHealth) {}
+
+    // Called when outgoing video starts or stops being scaled down, or the reason
+    // changes. The reason is None once it stops.
+    fn handle_video_adaptation_changed(
+        &self,
+        _client_id: ClientId,
+        _reason: Option<QualityLimitationReason>,
+    ) {
+    }
+
     fn handle_rtc_stats_report(&self, report_json: String);
 
+    // Called shortly before the relay servers given to Client::set_ice_servers() expire,
+    // so that fresh ones can be fetched and passed to Client::set_ice_servers() again.
+    fn request_ice_servers(&self, _client_id: ClientId) {}
+
+    // Called when fresh relay servers couldn't be applied, or when the current ones
+    // expired without being refreshed. The call goes on, but may not be able to
+    // reconnect through a relay until the servers are refreshed.
+    fn handle_ice_servers_refresh_failed(&self, _client_id: ClientId) {}
+
+    // Called when another device on the same account joins the call after the local
+    // device did, identified by its demux ID. If set_leave_when_own_device_joins() is
+    // enabled, the local device leaves right after this.
+    fn handle_own_device_joined(&self, _client_id: ClientId, _demux_id: DemuxId) {}
+
+    // Called when the local device is moved into a breakout group, or back to the main
+    // call (None). Media keys are only shared within the same breakout group.
+    fn handle_breakout_group_changed(
+        &self,
+        _client_id: ClientId,
+        _breakout_group_id: Option<BreakoutGroupId>,
+    ) {
+    }
+
+    // Called right before handle_ended() with EndReason::HasMaxDevices, to tell how full
+    // the call is. Either value may be unknown if the call hasn't been peeked yet.
+    fn handle_call_full(
+        &self,
+        _client_id: ClientId,
+        _max_devices: Option<u32>,
+        _device_count: Option<u32>,
+    ) {
+    }
+
+    // Called once right before handle_ended() with totals for the whole call.
+    fn handle_call_summary(&self, _client_id: ClientId, _summary: CallSummary, _reason: EndReason) {
+    }
+
     // This will be the last callback.
     // The observer can assume the Call is completely shut down and can be deleted.
     fn handle_ended(&self, client_id: ClientId, reason: EndReason);
@@ -500,7 +597,7 @@ pub enum EndReason {
     CallManagerIsBusy,
     SfuClientFailedToJoin,
     FailedToCreatePeerConnectionFactory,
-    FailedToNegotiatedSrtpKeys,
+    FailedToNegotiateSrtpKeys,
     FailedToCreatePeerConnection,
     FailedToStartPeerConnection,
     FailedToUpdatePeerConnection,
@@ -520,6 +617,105 @@ pub struct Reaction {
     pub value: String,
 }
 
+/// A coarse rating of how well media from a remote device is being received,
+/// suitable for showing a "poor connection" indicator.
+// Must be kept in sync with the TypeScript enum.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum ConnectionQuality {
+    Good = 0,
+    Fair,
+    Poor,
+}
+
+impl ConnectionQuality {
+    fn from_receive_quality_sample(sample: &ReceiveQualitySample) -> Self {
+        let packets_lost_pct = sample.packets_lost_pct();
+        if sample.video_frozen
+            || packets_lost_pct >= POOR_CONNECTION_PACKETS_LOST_PCT
+            || sample.jitter >= POOR_CONNECTION_JITTER_SECS
+        {
+            ConnectionQuality::Poor
+        } else if packets_lost_pct >= FAIR_CONNECTION_PACKETS_LOST_PCT
+            || sample.jitter >= FAIR_CONNECTION_JITTER_SECS
+        {
+            ConnectionQuality::Fair
+        } else {
+            ConnectionQuality::Good
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RemoteConnectionQuality {
+    pub demux_id: DemuxId,
+    pub quality: ConnectionQuality,
+}
+
+/// A remote device whose audio and video are being played out noticeably out
+/// of sync.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RemoteAvSyncSkew {
+    pub demux_id: DemuxId,
+    /// How far video playout trails audio playout, in seconds. Negative if
+    /// video is ahead of audio.
+    pub skew: f64,
+}
+
+/// How well incoming media and data decrypted over a window of time, so that
+/// apps can (with consent) report decryption failures in aggregate.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct E2eeHealth {
+    /// How long the window was, since the previous report.
+    pub window: Duration,
+    pub frames_decrypted: u64,
+    pub frames_failed: u64,
+    /// How many frames failed to decrypt from each device that had any
+    /// failures, ordered by demux ID.
+    pub failures_by_sender: Vec<(DemuxId, u64)>,
+}
+
+/// Counts decryption results as frames arrive, until the actor takes them for
+/// an [`E2eeHealth`] report.
+struct DecryptionCounts {
+    window_started: Instant,
+    frames_decrypted: u64,
+    failures_by_sender: BTreeMap<DemuxId, u64>,
+}
+
+impl DecryptionCounts {
+    fn new(now: Instant) -> Self {
+        Self {
+            window_started: now,
+            frames_decrypted: 0,
+            failures_by_sender: BTreeMap::new(),
+        }
+    }
+
+    fn record(&mut self, sender: DemuxId, decrypted: bool) {
+        if decrypted {
+            self.frames_decrypted += 1;
+        } else {
+            *self.failures_by_sender.entry(sender).or_default() += 1;
+        }
+    }
+
+    /// Starts a new window, returning a report of the previous one unless no
+    /// frames arrived during it.
+    fn take(&mut self, now: Instant) -> Option<E2eeHealth> {
+        let counts = std::mem::replace(self, Self::new(now));
+        if counts.frames_decrypted == 0 && counts.failures_by_sender.is_empty() {
+            return None;
+        }
+        Some(E2eeHealth {
+            window: now.saturating_duration_since(counts.window_started),
+            frames_decrypted: counts.frames_decrypted,
+            frames_failed: counts.failures_by_sender.values().sum(),
+            failures_by_sender: counts.failures_by_sender.into_iter().collect(),
+        })
+    }
+}
+
 // The callbacks from the Client to the "SFU client" for the group call.
 pub trait SfuClient {
     // This should call Client.on_sfu_client_joined when the SfuClient has joined.
@@ -720,6 +916,7 @@ impl From<protobuf::group_call::device_to_device::Heartbeat> for HeartbeatState
 pub struct RemoteDeviceState {
     pub demux_id: DemuxId,
     pub user_id: UserId,
+    pub participant_kind: ParticipantKind,
     pub media_keys_received: bool,
     pub heartbeat_state: HeartbeatState,
     // The latest timestamp we received from an update to
@@ -752,10 +949,16 @@ fn as_unix_millis(t: Option<SystemTime>) -> u64 {
 }
 
 impl RemoteDeviceState {
-    fn new(demux_id: DemuxId, user_id: UserId, added_time: SystemTime) -> Self {
+    fn new(
+        demux_id: DemuxId,
+        user_id: UserId,
+        participant_kind: ParticipantKind,
+        added_time: SystemTime,
+    ) -> Self {
         Self {
             demux_id,
             user_id,
+            participant_kind,
             media_keys_received: false,
             heartbeat_state: Default::default(),
             heartbeat_rtp_timestamp: None,
@@ -847,12 +1050,39 @@ const DELAY_FOR_RECOVERED_BWE_CALLBACK: Duration = Duration::from_secs(6);
 // a second rotation is applied.
 const MEDIA_SEND_KEY_ROTATION_DELAY_SECS: u64 = 3;
 
+// The longest a KeyRotationPolicy::Debounced rotation can be put off after the first
+// departure it covers, no matter how many more departures follow it.
+const MAX_DEBOUNCED_KEY_ROTATION_DELAY: Duration = Duration::from_secs(30);
+
+/// Controls how soon the media send key is rotated after someone leaves the call.
+///
+/// Rotating means generating a new random key and sending it to everyone still in
+/// the call, so in a large call with a lot of churn, rotating for every departure
+/// causes a storm of key distribution. Delaying the rotation trades that off against
+/// how long someone who left could still decrypt media. Joins aren't affected; they
+/// only ever advance the current key.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyRotationPolicy {
+    /// Rotate as soon as someone leaves.
+    #[default]
+    Immediate,
+    /// Rotate once no one else has left for the given duration, but no later than
+    /// MAX_DEBOUNCED_KEY_ROTATION_DELAY after the first departure, so steady churn
+    /// can't keep pushing the rotation back.
+    Debounced(Duration),
+    /// Rotate the given duration after the first departure, covering everyone
+    /// who left in the meantime.
+    Batched(Duration),
+}
+
 enum KeyRotationState {
     // A key has been applied.  Nothing is pending.
     Applied,
     // A key has been generated but not yet applied.
     Pending {
         secret: frame_crypto::Secret,
+        // When to apply it, by State::clock.
+        apply_at: Instant,
         // Once it has been applied, another rotation needs to take place because
         // a user left the call while rotation was pending.
         needs_another_rotation: bool,
@@ -889,6 +1119,15 @@ pub struct Client {
     // because WebRTC calls back to the PeerConnectionObserver
     // synchronously.
     frame_crypto_context: Arc<CallMutex<frame_crypto::Context>>,
+    // Also outside the actor so that the application can read the
+    // latest stats synchronously.
+    stats_snapshot: Arc<CallMutex<Option<StatsSnapshot>>>,
+    // Also outside the actor so that incoming video frames can be timed
+    // without a thread hop.
+    setup_latency: Arc<SetupLatencyTracker>,
+    // Also outside the actor because frames are decrypted synchronously, like
+    // frame_crypto_context.
+    decryption_counts: Arc<CallMutex<DecryptionCounts>>,
     actor: Actor<State>,
 }
 
@@ -977,10 +1216,21 @@ struct RaiseHandState {
     pub outstanding: bool,
 }
 
+// Declared in drop order: the gatherer, then the PeerConnection it came from, then
+// that PeerConnection's observer.
+struct RefreshedIceGatherer {
+    _ice_gatherer: IceGatherer,
+    _peer_connection: PeerConnection,
+    _observer_impl: Box<PeerConnectionObserverImpl>,
+}
+
 /// The state inside the Actor
 struct State {
     // Things passed in that never change
     client_id: ClientId,
+    // What timers are scheduled by, instead of Instant::now(), so that tests
+    // can run them without waiting.
+    clock: Clock,
     group_id: GroupId,
     kind: GroupCallKind,
     sfu_client: Box<dyn SfuClient>,
@@ -1018,11 +1268,17 @@ struct State {
     local_ice_ufrag: String,
     local_ice_pwd: String,
     sfu_info: Option<SfuInfo>,
+    peer_connection_factory: PeerConnectionFactory,
     peer_connection: PeerConnection,
     peer_connection_observer_impl: Box<PeerConnectionObserverImpl>,
+    // Set by set_ice_servers() when the relay servers' credentials are known to expire.
+    ice_servers_expire_at: Option<Instant>,
+    ice_servers_refresh_requested: bool,
+    refreshed_ice_gatherer: Option<RefreshedIceGatherer>,
     rtp_data_to_sfu_next_seqnum: u32,
     rtp_data_through_sfu_next_seqnum: u32,
     next_heartbeat_time: Option<Instant>,
+    intervals: GroupCallIntervals,
     /// The remote demux IDs are in the order of the corresponding transceivers
     /// in peer_connection. Each demux ID is associated with two transceivers
     /// (audio and video). None represents an unused transceiver pair.
@@ -1033,6 +1289,13 @@ struct State {
     next_stats_time: Option<Instant>,
     get_stats_interval: Duration,
     stats_observer: Box<StatsObserver>,
+    stats_snapshot: Arc<CallMutex<Option<StatsSnapshot>>>,
+
+    // Things for summarizing the call when it ends
+    joined_at: Option<Instant>,
+    time_joined: Duration,
+    max_participant_count: u32,
+    setup_latency: Arc<SetupLatencyTracker>,
 
     // Things for getting audio levels from the PeerConnection
     audio_levels_interval: Option<Duration>,
@@ -1047,6 +1310,14 @@ struct State {
     silence_started: Option<Instant>,
     // Tracker for the last time speech-related notification sent to the client.
     last_speaking_notification: Option<SpeechEvent>,
+    // If set, the captured audio is also checked for speech while the local user is muted.
+    // This only works if the app keeps capturing audio while muted.
+    detect_speaking_while_muted: bool,
+    // Track the time the current speech while muted began, and the last time the
+    // captured audio was loud enough to count as speech.
+    started_speaking_while_muted: Option<Instant>,
+    last_non_silent_while_muted: Option<Instant>,
+    last_speaking_while_muted_notification_time: Option<Instant>,
 
     next_membership_proof_request_time: Option<Instant>,
 
@@ -1076,6 +1347,11 @@ struct State {
     // be able do decrypt media after leaving if they leave immediately
     // after receiving a newly generated key.
     media_send_key_rotation_state: KeyRotationState,
+    key_rotation_policy: KeyRotationPolicy,
+    // When to rotate the media send key for departures deferred by the key_rotation_policy.
+    deferred_media_send_key_rotation_time: Option<Instant>,
+    // The latest a debounced rotation may be deferred to, set by the first departure it covers.
+    deferred_media_send_key_rotation_deadline: Option<Instant>,
 
     // Things to control video requests.  We want to send them regularly on ticks,
     // but also limit how often they are sent "on demand".  So here's the rule:
@@ -1085,12 +1361,31 @@ struct State {
     active_speaker_height: Option<u16>,
     on_demand_video_request_sent_since_last_heartbeat: bool,
     speaker_rtp_timestamp: Option<rtp::Timestamp>,
+    // Video the app isn't rendering right now (e.g. because it's in the background).
+    // Paused video is requested from the SFU at a height of 0 and its incoming track is
+    // disabled, so it can be resumed without rejoining.
+    rendering_paused_demux_ids: HashSet<DemuxId>,
+    all_rendering_paused: bool,
+    // Kept so the tracks can be disabled and enabled as rendering is paused and resumed.
+    incoming_video_tracks: HashMap<DemuxId, VideoTrack>,
 
     send_rates: SendRates,
     // If set, will always override the send_rates.  Intended for testing.
     send_rates_override: Option<SendRates>,
+    // Whether the OS has given the app audio. Until it has, audio playout and
+    // recording stay off even when there are other devices in the call.
+    audio_session_active: bool,
     max_receive_rate: Option<DataRate>,
     data_mode: DataMode,
+    audio_profile: AudioProfile,
+    audio_loss_protection: AudioLossProtection,
+    // The stats report most recently checked for A/V sync, so each report is only
+    // checked once.
+    av_sync_checked_timestamp_us: i64,
+    decryption_counts: Arc<CallMutex<DecryptionCounts>>,
+    next_e2ee_health_time: Instant,
+    // The last reason passed to handle_video_adaptation_changed.
+    video_send_limitation: Option<QualityLimitationReason>,
     // Demux IDs where video is being forward from, mapped to the server allocated height.
     forwarding_videos: HashMap<DemuxId, u16>,
 
@@ -1099,6 +1394,18 @@ struct State {
     reactions: Vec<Reaction>,
     raised_hands: Vec<DemuxId>,
     raise_hand_state: RaiseHandState,
+    // If set, a raised hand is lowered automatically once the local user
+    // has been speaking for a while.
+    lower_hand_when_speaking: bool,
+    // Whether the remote devices have been updated since the local device showed up in
+    // the call, so that any devices added after that are known to have joined later.
+    remote_devices_updated_since_join: bool,
+    // If set, the local device leaves when another device on the same account joins.
+    leave_when_own_device_joins: bool,
+    // If set, media keys aren't sent to users who are only in the call through a bridge.
+    skip_media_keys_for_bridged_participants: bool,
+    // The breakout group of every device that isn't in the main call, as last sent by the SFU.
+    breakout_groups: HashMap<DemuxId, BreakoutGroupId>,
 
     sfu_reliable_stream: MrpStream<Vec<u8>, (rtp::Header, SfuToDevice)>,
     actor: Actor<State>,
@@ -1163,6 +1470,18 @@ const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
 
 // How often to get and log stats.
 const DEFAULT_STATS_INTERVAL: Duration = Duration::from_secs(10);
+
+// How often to request an updated membership list from the SfuClient.
+const PEEK_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+// How often to report how well frames are decrypting.
+const E2EE_HEALTH_INTERVAL: Duration = Duration::from_secs(60);
+
+// How long before the relay servers expire to ask for fresh ones.
+const ICE_SERVERS_REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+const AUDIO_RTCP_REPORT_INTERVAL_MS: i32 = 5000;
+
 const STATS_INITIAL_OFFSET: Duration = Duration::from_secs(2);
 
 // How often to request an updated membership proof (24 hours).
@@ -1178,6 +1497,43 @@ const DELAYED_BWE_CHECK: Duration = Duration::from_secs(10);
 
 const REACTION_STRING_MAX_SIZE: usize = 256;
 
+// Thresholds for rating the connection quality of remote devices. A device is rated
+// by its worst incoming stream.
+const FAIR_CONNECTION_PACKETS_LOST_PCT: f32 = 3.0;
+const POOR_CONNECTION_PACKETS_LOST_PCT: f32 = 10.0;
+const FAIR_CONNECTION_JITTER_SECS: f64 = 0.03;
+const POOR_CONNECTION_JITTER_SECS: f64 = 0.1;
+
+// How far apart the audio and video of a remote device can be played out before
+// the observer is warned. Most people start to notice somewhere around here.
+const AV_SYNC_SKEW_WARNING_SECS: f64 = 0.15;
+
+/// The cadences of the periodic work a client does while in a call.
+///
+/// The defaults suit most apps. Slowing them down saves battery at the cost of
+/// reacting more slowly to changes in the call; speeding them up is mostly useful
+/// for tests.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GroupCallIntervals {
+    /// How often to send heartbeats and video requests to the SFU.
+    pub heartbeat: Duration,
+    /// How often to get and log stats, unless overridden by
+    /// [`Client::set_rtc_stats_interval`].
+    pub stats: Duration,
+    /// How often to request an updated membership list from the SFU.
+    pub peek_poll: Duration,
+}
+
+impl Default for GroupCallIntervals {
+    fn default() -> Self {
+        Self {
+            heartbeat: HEARTBEAT_INTERVAL,
+            stats: DEFAULT_STATS_INTERVAL,
+            peek_poll: PEEK_POLL_INTERVAL,
+        }
+    }
+}
+
 pub struct ClientStartParams {
     pub group_id: GroupId,
     pub client_id: ClientId,
@@ -1192,7 +1548,11 @@ pub struct ClientStartParams {
     pub incoming_video_sink: Option<Box<dyn VideoSink>>,
     pub ring_id: Option<RingId>,
     pub audio_levels_interval: Option<Duration>,
+    pub intervals: GroupCallIntervals,
     pub obfuscated_resolver: ObfuscatedResolver,
+    /// Relay servers to gather candidates from in addition to connecting to the SFU
+    /// directly. Empty unless the application overrides them for this call.
+    pub ice_servers: Vec<IceServer>,
 }
 
 impl Client {
@@ -1211,12 +1571,15 @@ impl Client {
             incoming_video_sink,
             ring_id,
             audio_levels_interval,
+            intervals,
             obfuscated_resolver,
+            ice_servers,
         } = params;
 
         debug!("group_call::Client(outer)::new(client_id: {})", client_id);
 
         let stopper = Stopper::new();
+        let clock = Clock::default();
 
         // We only send with this key until the first person joins, at which point
         // we ratchet the key forward.
@@ -1225,6 +1588,15 @@ impl Client {
             "Frame encryption context",
         ));
         let frame_crypto_context_for_outside_actor = frame_crypto_context.clone();
+        let stats_snapshot = Arc::new(CallMutex::new(None, "Stats snapshot"));
+        let stats_snapshot_for_outside_actor = stats_snapshot.clone();
+        let setup_latency = Arc::new(SetupLatencyTracker::default());
+        let setup_latency_for_outside_actor = setup_latency.clone();
+        let decryption_counts = Arc::new(CallMutex::new(
+            DecryptionCounts::new(clock.now()),
+            "Decryption counts",
+        ));
+        let decryption_counts_for_outside_actor = decryption_counts.clone();
         let client = Self {
             client_id,
             group_id: group_id.clone(),
@@ -1251,14 +1623,19 @@ impl Client {
                 // but we can't uses dashes due to the sfu.
                 let local_ice_ufrag = random_alphanumeric(4);
                 let local_ice_pwd = random_alphanumeric(22);
-                let audio_rtcp_report_interval_ms = 5000;
-                let ice_servers = vec![];
+                if !ice_servers.is_empty() {
+                    info!(
+                        "group_call::Client(inner)::new(client_id: {}) using {} overridden ICE servers",
+                        client_id,
+                        ice_servers.len()
+                    );
+                }
                 let peer_connection = peer_connection_factory
                     .create_peer_connection(
                         peer_connection_observer,
                         pcf::RffiPeerConnectionKind::GroupCall,
                         &AudioJitterBufferConfig::default(),
-                        audio_rtcp_report_interval_ms,
+                        AUDIO_RTCP_REPORT_INTERVAL_MS,
                         &ice_servers,
                         outgoing_audio_track,
                         outgoing_video_track,
@@ -1275,8 +1652,10 @@ impl Client {
                         ideal_send_rate,\
                         allocated_send_rate"
                 );
+                let next_e2ee_health_time = clock.now() + E2EE_HEALTH_INTERVAL;
                 Ok(State {
                     client_id,
+                    clock,
                     group_id,
                     kind,
                     sfu_client,
@@ -1309,19 +1688,27 @@ impl Client {
                     outgoing_heartbeat_state: Default::default(),
 
                     sfu_info: None,
+                    peer_connection_factory,
                     peer_connection_observer_impl,
                     peer_connection,
+                    ice_servers_expire_at: None,
+                    ice_servers_refresh_requested: false,
+                    refreshed_ice_gatherer: None,
                     rtp_data_to_sfu_next_seqnum: 1,
                     rtp_data_through_sfu_next_seqnum: 1,
 
                     next_heartbeat_time: None,
+                    intervals,
 
                     next_stats_time: None,
-                    get_stats_interval: DEFAULT_STATS_INTERVAL,
-                    stats_observer: create_stats_observer(
-                        call_id_for_stats,
-                        DEFAULT_STATS_INTERVAL,
-                    ),
+                    get_stats_interval: intervals.stats,
+                    stats_observer: create_stats_observer(call_id_for_stats, intervals.stats),
+                    stats_snapshot,
+
+                    joined_at: None,
+                    time_joined: Duration::ZERO,
+                    max_participant_count: 0,
+                    setup_latency,
 
                     audio_levels_interval,
                     next_audio_levels_time: None,
@@ -1331,6 +1718,10 @@ impl Client {
                     started_speaking: None,
                     silence_started: None,
                     last_speaking_notification: None,
+                    detect_speaking_while_muted: false,
+                    started_speaking_while_muted: None,
+                    last_non_silent_while_muted: None,
+                    last_speaking_while_muted_notification_time: None,
 
                     next_membership_proof_request_time: None,
 
@@ -1341,17 +1732,32 @@ impl Client {
                     frame_crypto_context,
                     pending_media_receive_keys: Vec::new(),
                     media_send_key_rotation_state: KeyRotationState::Applied,
+                    key_rotation_policy: KeyRotationPolicy::default(),
+                    deferred_media_send_key_rotation_time: None,
+                    deferred_media_send_key_rotation_deadline: None,
 
                     video_requests: None,
                     active_speaker_height: None,
                     on_demand_video_request_sent_since_last_heartbeat: false,
                     speaker_rtp_timestamp: None,
+                    rendering_paused_demux_ids: HashSet::new(),
+                    all_rendering_paused: false,
+                    incoming_video_tracks: HashMap::new(),
 
                     send_rates: SendRates::default(),
                     send_rates_override: None,
+                    audio_session_active: true,
                     // If the client never calls set_data_mode, use the normal max receive rate.
                     max_receive_rate: Some(NORMAL_MAX_RECEIVE_RATE),
                     data_mode: DataMode::Normal,
+                    audio_profile: AudioProfile::Voice,
+                    audio_loss_protection: AudioLossProtection::new(
+                        AudioLossProtectionConfig::default(),
+                    ),
+                    av_sync_checked_timestamp_us: 0,
+                    decryption_counts,
+                    next_e2ee_health_time,
+                    video_send_limitation: None,
                     forwarding_videos: HashMap::default(),
 
                     outgoing_ring_state: OutgoingRingState::Unknown,
@@ -1359,6 +1765,11 @@ impl Client {
                     reactions: Vec::new(),
                     raised_hands: Vec::new(),
                     raise_hand_state: RaiseHandState::default(),
+                    lower_hand_when_speaking: false,
+                    remote_devices_updated_since_join: false,
+                    leave_when_own_device_joins: false,
+                    skip_media_keys_for_bridged_participants: false,
+                    breakout_groups: HashMap::new(),
 
                     sfu_reliable_stream: MrpStream::with_capacity_limit(RELIABLE_RTP_BUFFER_SIZE),
 
@@ -1366,6 +1777,9 @@ impl Client {
                 })
             })?,
             frame_crypto_context: frame_crypto_context_for_outside_actor,
+            stats_snapshot: stats_snapshot_for_outside_actor,
+            setup_latency: setup_latency_for_outside_actor,
+            decryption_counts: decryption_counts_for_outside_actor,
         };
 
         // After we have the actor, we can initialize the PeerConnectionObserverImpl
@@ -1403,6 +1817,26 @@ impl Client {
         });
     }
 
+    /// Start or stop audio playout and recording as the OS gives audio to the
+    /// app or takes it away, such as a CallKit audio session being activated.
+    /// Audio still stays off while no one else is in the call.
+    pub fn set_audio_session_active(&self, active: bool) {
+        debug!(
+            "group_call::Client(outer)::set_audio_session_active(client_id: {}, active: {})",
+            self.client_id, active
+        );
+        self.actor.send(move |state| {
+            debug!(
+                "group_call::Client(inner)::set_audio_session_active(client_id: {}, active: {})",
+                state.client_id, active
+            );
+            state.audio_session_active = active;
+            let enabled = active && state.send_rates.max != Some(ALL_ALONE_MAX_SEND_RATE);
+            state.peer_connection.set_audio_playout_enabled(enabled);
+            state.peer_connection.set_audio_recording_enabled(enabled);
+        });
+    }
+
     // Should only be used for testing
     pub fn override_send_rates(&self, send_rates_override: SendRates) {
         self.actor.send(move |state| {
@@ -1411,16 +1845,47 @@ impl Client {
         });
     }
 
+    /// Moves this client's clock forward and runs whatever periodic work is due, such as
+    /// applying a rotated media send key, without waiting for real time to pass.
+    #[cfg(feature = "sim")]
+    pub fn advance_clock(&self, by: Duration) {
+        self.actor.send(move |state| {
+            state.clock.advance(by);
+            Self::run_periodic_work(state);
+        });
+    }
+
     // Pulled into a named private method so we can call it recursively.
     fn tick(state: &mut State) {
-        let now = Instant::now();
+        Self::run_periodic_work(state);
+        state.actor.send_delayed(TICK_INTERVAL, Self::tick);
+    }
+
+    fn run_periodic_work(state: &mut State) {
+        let now = state.clock.now();
 
         trace!(
             "group_call::Client(inner)::tick(group_id: {})",
             state.client_id
         );
 
-        Self::request_remote_devices_from_sfu_if_older_than(state, Duration::from_secs(10));
+        Self::request_remote_devices_from_sfu_if_older_than(state, state.intervals.peek_poll);
+
+        if let KeyRotationState::Pending {
+            secret, apply_at, ..
+        } = state.media_send_key_rotation_state
+        {
+            if now >= apply_at {
+                Self::apply_pending_media_send_key(state, secret);
+            }
+        }
+
+        if let Some(rotation_time) = state.deferred_media_send_key_rotation_time {
+            if now >= rotation_time {
+                state.deferred_media_send_key_rotation_time = None;
+                Self::rotate_media_send_key_and_send_to_users_not_removed(state);
+            }
+        }
 
         if let Some(next_heartbeat_time) = state.next_heartbeat_time {
             if now >= next_heartbeat_time {
@@ -1430,7 +1895,7 @@ impl Client {
                 // Also send video requests at the same rate as the heartbeat.
                 Self::send_video_requests_to_sfu(state);
                 state.on_demand_video_request_sent_since_last_heartbeat = false;
-                state.next_heartbeat_time = Some(now + HEARTBEAT_INTERVAL)
+                state.next_heartbeat_time = Some(now + state.intervals.heartbeat)
             }
         }
 
@@ -1441,9 +1906,87 @@ impl Client {
                     .get_stats(state.stats_observer.as_ref());
                 state.next_stats_time = Some(now + state.get_stats_interval);
             }
+            if let Some(mut snapshot) = state.stats_observer.stats_snapshot() {
+                snapshot.av_sync = Self::compute_av_sync(&snapshot, &state.remote_devices);
+                if snapshot.timestamp_us != state.av_sync_checked_timestamp_us {
+                    state.av_sync_checked_timestamp_us = snapshot.timestamp_us;
+                    let skews = Self::av_sync_skews_to_warn_about(&snapshot.av_sync);
+                    if !skews.is_empty() {
+                        state.observer.handle_av_sync_skew(state.client_id, skews);
+                    }
+                }
+                let video_send_limitation = snapshot.video_send_limitation();
+                if video_send_limitation != state.video_send_limitation {
+                    state.video_send_limitation = video_send_limitation;
+                    state
+                        .observer
+                        .handle_video_adaptation_changed(state.client_id, video_send_limitation);
+                }
+                let audio_send_loss_pct = snapshot
+                    .audio_send
+                    .iter()
+                    .map(|stream| stream.remote_packets_lost_pct)
+                    .reduce(f32::max);
+                if let Some(loss_pct) = audio_send_loss_pct {
+                    if state.audio_loss_protection.on_packet_loss(loss_pct) {
+                        Self::configure_audio_encoders(state);
+                    }
+                }
+                if let Ok(mut stats_snapshot) = state.stats_snapshot.lock() {
+                    *stats_snapshot = Some(snapshot);
+                }
+            }
             if let Some(report_json) = state.stats_observer.take_stats_report() {
                 state.observer.handle_rtc_stats_report(report_json)
             }
+            let receive_quality_samples = state.stats_observer.take_receive_quality_samples();
+            if !receive_quality_samples.is_empty() {
+                let qualities = Self::compute_remote_connection_qualities(
+                    &receive_quality_samples,
+                    &state.remote_devices,
+                );
+                if !qualities.is_empty() {
+                    state
+                        .observer
+                        .handle_remote_connection_qualities(state.client_id, qualities);
+                }
+            }
+        }
+
+        if let Some(expire_at) = state.ice_servers_expire_at {
+            if now >= expire_at {
+                warn!(
+                    "group_call::Client(inner)::tick(client_id: {}): ICE servers expired without being refreshed",
+                    state.client_id
+                );
+                state.ice_servers_expire_at = None;
+                state
+                    .observer
+                    .handle_ice_servers_refresh_failed(state.client_id);
+            } else if !state.ice_servers_refresh_requested
+                && now + ICE_SERVERS_REFRESH_MARGIN >= expire_at
+            {
+                state.ice_servers_refresh_requested = true;
+                state.observer.request_ice_servers(state.client_id);
+            }
+        }
+
+        if now >= state.next_e2ee_health_time {
+            state.next_e2ee_health_time = now + E2EE_HEALTH_INTERVAL;
+            let health = state
+                .decryption_counts
+                .lock()
+                .ok()
+                .and_then(|mut counts| counts.take(now));
+            if let Some(health) = health {
+                if health.frames_failed > 0 {
+                    info!(
+                        "group_call::Client(inner)::tick(client_id: {}): {:?}",
+                        state.client_id, health
+                    );
+                }
+                state.observer.handle_e2ee_health(state.client_id, health);
+            }
         }
 
         if let Some(next_speaking_audio_levels_time) = state.next_speaking_audio_levels_time {
@@ -1484,9 +2027,22 @@ impl Client {
                             .observer
                             .handle_speaking_notification(state.client_id, event);
                         state.last_speaking_notification = Some(event);
+
+                        if event == SpeechEvent::LowerHandSuggestion
+                            && state.lower_hand_when_speaking
+                            && state.raise_hand_state.raise
+                        {
+                            info!(
+                                "group_call::Client(inner)::tick(client_id: {}): lowering hand because the local user is speaking",
+                                state.client_id
+                            );
+                            Self::raise_hand_inner(state, false);
+                        }
                     }
                 }
 
+                Self::check_speaking_while_muted(state, captured_level, now);
+
                 state.next_speaking_audio_levels_time = Some(now + state.speaking_interval);
             }
         }
@@ -1607,12 +2163,10 @@ impl Client {
                 peer_connection,
                 payload,
             )?;
-            Ok(Instant::now() + DEVICE_TO_SFU_TIMEOUT)
+            Ok(now + DEVICE_TO_SFU_TIMEOUT)
         }) {
             warn!("Failed to resend reliable data to SFU: {:?}", err);
         }
-
-        state.actor.send_delayed(TICK_INTERVAL, Self::tick);
     }
 
     fn request_remote_devices_as_soon_as_possible(state: &mut State) {
@@ -1638,7 +2192,7 @@ impl Client {
         max_age: Duration,
         rerequest_if_pending: bool,
     ) {
-        let now = Instant::now();
+        let now = state.clock.now();
         let should_request_now = match state.remote_devices_request_state {
             RemoteDevicesRequestState::WaitingForMembershipProof => false,
             RemoteDevicesRequestState::NeverRequested => true,
@@ -1665,7 +2219,7 @@ impl Client {
             }));
             state.remote_devices_request_state = RemoteDevicesRequestState::Requested {
                 should_request_again: false,
-                at: Instant::now(),
+                at: state.clock.now(),
             };
         } else if rerequest_if_pending {
             // We've already requested, so just wait until the next update and then request again.
@@ -1709,7 +2263,7 @@ impl Client {
                         ConnectionState::Connecting,
                     );
 
-                    let now = Instant::now();
+                    let now = state.clock.now();
 
                     // Start heartbeats, audio levels, and raise hand right away.
                     state.next_heartbeat_time = Some(now);
@@ -1742,13 +2296,33 @@ impl Client {
             "group_call::Client(inner)::set_connection_state_and_notify_observer(client_id: {})",
             state.client_id
         );
+        diagnostics::record(
+            DiagnosticEventKind::StateTransition,
+            format!("group call connection: {:?}", connection_state),
+        );
+        if connection_state == ConnectionState::Connected {
+            state.setup_latency.record(SetupMilestone::Connected);
+        }
 
         state.connection_state = connection_state;
+        Self::update_crash_dump(state);
         state
             .observer
             .handle_connection_state_changed(state.client_id, connection_state);
     }
 
+    fn update_crash_dump(state: &State) {
+        if state.connection_state == ConnectionState::NotConnected {
+            crash_dump::remove_call_state("group", state.client_id.into());
+        } else {
+            crash_dump::set_call_state(
+                "group",
+                state.client_id.into(),
+                format_args!("{:?}, {:?}", state.connection_state, state.join_state),
+            );
+        }
+    }
+
     // Pulled into a private method so we can lock/set/unlock the busy state.
     fn take_busy(state: &mut State) -> bool {
         let busy = state.busy.lock();
@@ -1797,7 +2371,19 @@ impl Client {
                     warn!("Already attempted to join.");
                 }
                 JoinState::NotJoined(ring_id) => {
-                    if Self::take_busy(state) {
+                    if state
+                        .last_peek_info
+                        .as_ref()
+                        .map_or(false, PeekInfo::is_full)
+                    {
+                        // Don't bother the SFU; it would only reject the request.
+                        info!(
+                            "group_call::Client(inner)::join(client_id: {}): the call is already full",
+                            state.client_id
+                        );
+                        Self::end_because_call_is_full(state);
+                    } else if Self::take_busy(state) {
+                        state.setup_latency.start();
                         Self::set_join_state_and_notify_observer(state, JoinState::Joining);
                         Self::accept_ring_if_needed(state, ring_id);
 
@@ -1806,7 +2392,7 @@ impl Client {
                             // The Join request will then proceed once SfuClient has the token.
                             state.observer.request_membership_proof(state.client_id);
                             state.next_membership_proof_request_time =
-                                Some(Instant::now() + MEMBERSHIP_PROOF_REQUEST_INTERVAL);
+                                Some(state.clock.now() + MEMBERSHIP_PROOF_REQUEST_INTERVAL);
                         }
 
                         let client_secret = EphemeralSecret::random_from_rng(OsRng);
@@ -1858,7 +2444,18 @@ impl Client {
             state.client_id,
             join_state
         );
+        diagnostics::record(
+            DiagnosticEventKind::StateTransition,
+            format!("group call join: {:?}", join_state),
+        );
         state.join_state = join_state;
+        if let JoinState::Joined(_) = join_state {
+            state.joined_at.get_or_insert_with(Instant::now);
+            state.max_participant_count = state.max_participant_count.max(1);
+        } else if let Some(joined_at) = state.joined_at.take() {
+            state.time_joined += joined_at.elapsed();
+        }
+        Self::update_crash_dump(state);
         state
             .observer
             .handle_join_state_changed(state.client_id, join_state);
@@ -1905,6 +2502,8 @@ impl Client {
         state.next_audio_levels_time = None;
         state.next_speaking_audio_levels_time = None;
         state.next_membership_proof_request_time = None;
+        state.remote_devices_updated_since_join = false;
+        state.breakout_groups.clear();
     }
 
     pub fn disconnect(&self) {
@@ -2152,6 +2751,81 @@ impl Client {
         });
     }
 
+    /// Change how often audio levels are reported, or stop reporting them
+    /// with None.
+    pub fn set_audio_levels_interval(&self, audio_levels_interval: Option<Duration>) {
+        debug!(
+            "group_call::Client(outer)::set_audio_levels_interval(client_id: {}, audio_levels_interval: {:?})",
+            self.client_id, audio_levels_interval
+        );
+        self.actor.send(move |state| {
+            debug!(
+                "group_call::Client(inner)::set_audio_levels_interval(client_id: {}, audio_levels_interval: {:?})",
+                state.client_id, audio_levels_interval
+            );
+            state.audio_levels_interval = audio_levels_interval;
+        });
+    }
+
+    /// Returns the stats from the most recent stats report, or None if the
+    /// call has not been joined long enough to have any.
+    pub fn stats_snapshot(&self) -> Option<StatsSnapshot> {
+        self.stats_snapshot
+            .lock()
+            .map(|stats_snapshot| stats_snapshot.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_audio_profile(&self, audio_profile: AudioProfile) {
+        debug!(
+            "group_call::Client(outer)::set_audio_profile(client_id: {}, audio_profile: {:?})",
+            self.client_id, audio_profile
+        );
+        self.actor.send(move |state| {
+            debug!(
+                "group_call::Client(inner)::set_audio_profile(client_id: {}, audio_profile: {:?})",
+                state.client_id, audio_profile
+            );
+            if state.audio_profile == audio_profile {
+                return;
+            }
+            state.audio_profile = audio_profile;
+            // Otherwise, it will be applied once joined.
+            if let JoinState::Joined(_) = state.join_state {
+                Self::configure_audio_encoders(state);
+            }
+        });
+    }
+
+    /// Changes how outgoing audio is protected against packet loss.
+    pub fn set_audio_loss_protection(&self, config: AudioLossProtectionConfig) {
+        debug!(
+            "group_call::Client(outer)::set_audio_loss_protection(client_id: {}, config: {:?})",
+            self.client_id, config
+        );
+        self.actor.send(move |state| {
+            debug!(
+                "group_call::Client(inner)::set_audio_loss_protection(client_id: {}, config: {:?})",
+                state.client_id, config
+            );
+            let changed = state.audio_loss_protection.set_config(config);
+            if changed {
+                // Otherwise, it will be applied once joined.
+                if let JoinState::Joined(_) = state.join_state {
+                    Self::configure_audio_encoders(state);
+                }
+            }
+        });
+    }
+
+    fn configure_audio_encoders(state: &State) {
+        state.peer_connection.configure_audio_encoders(
+            &state
+                .audio_loss_protection
+                .apply(&AudioEncoderConfig::for_profile(state.audio_profile)),
+        );
+    }
+
     fn set_send_rates_inner(state: &mut State, mut send_rates: SendRates) {
         if let Some(send_rates_override) = &state.send_rates_override {
             send_rates = send_rates_override.clone();
@@ -2167,11 +2841,17 @@ impl Client {
                 }
             } else {
                 info!("Enable audio and outgoing media because there are other devices.");
-                state.peer_connection.set_audio_playout_enabled(true);
+                // Audio also waits for the OS to give it to the app.
+                let audio_enabled = state.audio_session_active;
+                state
+                    .peer_connection
+                    .set_audio_playout_enabled(audio_enabled);
                 state.peer_connection.set_outgoing_media_enabled(true);
-                state.peer_connection.set_audio_recording_enabled(true);
+                state
+                    .peer_connection
+                    .set_audio_recording_enabled(audio_enabled);
                 if state.bwe_check_state == BweCheckState::Disabled {
-                    state.bwe_check_state = BweCheckState::At(Instant::now() + BWE_INTERVAL);
+                    state.bwe_check_state = BweCheckState::At(state.clock.now() + BWE_INTERVAL);
                 }
             }
             if let Err(e) = state.peer_connection.set_send_rates(send_rates.clone()) {
@@ -2205,6 +2885,60 @@ impl Client {
         });
     }
 
+    pub fn set_rendering_paused(&self, demux_ids: Vec<DemuxId>, paused: bool) {
+        debug!(
+            "group_call::Client(outer)::set_rendering_paused(client_id: {}, demux_ids: {:?}, paused: {})",
+            self.client_id, demux_ids, paused
+        );
+        self.actor.send(move |state| {
+            info!(
+                "group_call::Client(inner)::set_rendering_paused(client_id: {}, demux_ids: {:?}, paused: {})",
+                state.client_id, demux_ids, paused
+            );
+            for demux_id in demux_ids {
+                if paused {
+                    state.rendering_paused_demux_ids.insert(demux_id);
+                } else {
+                    state.rendering_paused_demux_ids.remove(&demux_id);
+                }
+            }
+            Self::apply_rendering_paused(state);
+        });
+    }
+
+    pub fn set_all_rendering_paused(&self, paused: bool) {
+        debug!(
+            "group_call::Client(outer)::set_all_rendering_paused(client_id: {}, paused: {})",
+            self.client_id, paused
+        );
+        self.actor.send(move |state| {
+            info!(
+                "group_call::Client(inner)::set_all_rendering_paused(client_id: {}, paused: {})",
+                state.client_id, paused
+            );
+            state.all_rendering_paused = paused;
+            if !paused {
+                // Resuming everything also resumes video that was paused individually.
+                state.rendering_paused_demux_ids.clear();
+            }
+            Self::apply_rendering_paused(state);
+        });
+    }
+
+    fn is_rendering_paused(state: &State, demux_id: DemuxId) -> bool {
+        state.all_rendering_paused || state.rendering_paused_demux_ids.contains(&demux_id)
+    }
+
+    fn apply_rendering_paused(state: &mut State) {
+        for (demux_id, incoming_video_track) in &state.incoming_video_tracks {
+            incoming_video_track.set_enabled(!Self::is_rendering_paused(state, *demux_id));
+        }
+        if !state.on_demand_video_request_sent_since_last_heartbeat {
+            Self::send_video_requests_to_sfu(state);
+            state.on_demand_video_request_sent_since_last_heartbeat = true;
+        }
+    }
+
     fn send_video_requests_to_sfu(state: &mut State) {
         use std::cmp::min;
 
@@ -2227,7 +2961,13 @@ impl Client {
                                 // will request in terms of rotated video.  We assume that all video is sent over the
                                 // wire in landscape format with rotation metadata.
                                 // If it's not, we'll have a problem.
-                                height: Some(min(request.height, request.width) as u32),
+                                height: Some(
+                                    if Self::is_rendering_paused(state, device.demux_id) {
+                                        0
+                                    } else {
+                                        min(request.height, request.width) as u32
+                                    },
+                                ),
                             }
                         })
                 })
@@ -2254,7 +2994,13 @@ impl Client {
                     // ),
                     max_kbps: state.max_receive_rate.map(|rate| rate.as_kbps() as u32),
                     requests,
-                    active_speaker_height: state.active_speaker_height.map(|height| height.into()),
+                    active_speaker_height: state.active_speaker_height.map(|height| {
+                        if state.all_rendering_paused {
+                            0
+                        } else {
+                            height.into()
+                        }
+                    }),
                 }),
                 ..Default::default()
             };
@@ -2402,33 +3148,116 @@ impl Client {
         });
     }
 
-    pub fn set_group_members(&self, group_members: Vec<GroupMember>) {
+    // Lowers the raised hands of everyone in the call. The SFU only honors this for admins.
+    pub fn lower_all_hands(&self) {
+        use protobuf::group_call::device_to_sfu::{AdminAction, LowerAllHands};
         debug!(
-            "group_call::Client(outer)::set_group_members(client_id: {})",
+            "group_call::Client(outer)::lower_all_hands(client_id: {})",
             self.client_id
         );
         self.actor.send(move |state| {
             debug!(
-                "group_call::Client(inner)::set_group_members(client_id: {})",
+                "group_call::Client(inner)::lower_all_hands(client_id: {})",
                 state.client_id
             );
-            let new_members: HashSet<UserId> =
-                group_members.iter().map(|i| i.user_id.clone()).collect();
-            if new_members != state.known_members {
-                info!("known group members changed");
-                state.known_members = new_members;
-                state
-                    .obfuscated_resolver
-                    .set_member_resolver(Arc::new(MemberMap::new(&group_members)));
-                state.sfu_client.set_group_members(group_members);
-                Self::request_remote_devices_as_soon_as_possible(state);
-            }
-        })
-    }
 
-    pub fn set_membership_proof(&self, proof: MembershipProof) {
-        debug!(
-            "group_call::Client(outer)::set_membership_proof(client_id: {})",
+            let msg = DeviceToSfu {
+                admin_action: Some(AdminAction::LowerAllHands(LowerAllHands {})),
+                ..Default::default()
+            };
+
+            if let Err(e) = Self::reliable_send_to_sfu(state, msg) {
+                warn!("{ADMIN_LOG_TAG}: Failed to send lower all hands: {e:?}");
+            } else {
+                info!("{ADMIN_LOG_TAG}: Sent lower all hands.");
+            }
+        });
+    }
+
+    // Moves devices into breakout groups, or back to the main call with group 0.
+    // The SFU only honors this for admins.
+    pub fn assign_breakout_groups(&self, assignments: Vec<(DemuxId, BreakoutGroupId)>) {
+        use protobuf::group_call::device_to_sfu::{AdminAction, AssignBreakoutGroups};
+        debug!(
+            "group_call::Client(outer)::assign_breakout_groups(client_id: {}, assignments: {:?})",
+            self.client_id, assignments
+        );
+        self.actor.send(move |state| {
+            debug!(
+                "group_call::Client(inner)::assign_breakout_groups(client_id: {}, assignments: {:?})",
+                state.client_id, assignments
+            );
+
+            let (demux_ids, breakout_group_ids) = assignments.into_iter().unzip();
+            let msg = DeviceToSfu {
+                admin_action: Some(AdminAction::AssignBreakoutGroups(AssignBreakoutGroups {
+                    demux_ids,
+                    breakout_group_ids,
+                })),
+                ..Default::default()
+            };
+
+            if let Err(e) = Self::reliable_send_to_sfu(state, msg) {
+                warn!("{ADMIN_LOG_TAG}: Failed to send assign breakout groups: {e:?}");
+            } else {
+                info!("{ADMIN_LOG_TAG}: Sent assign breakout groups.");
+            }
+        });
+    }
+
+    // Moves everyone back to the main call. The SFU only honors this for admins.
+    pub fn end_breakout_groups(&self) {
+        use protobuf::group_call::device_to_sfu::{AdminAction, EndBreakoutGroups};
+        debug!(
+            "group_call::Client(outer)::end_breakout_groups(client_id: {})",
+            self.client_id
+        );
+        self.actor.send(move |state| {
+            debug!(
+                "group_call::Client(inner)::end_breakout_groups(client_id: {})",
+                state.client_id
+            );
+
+            let msg = DeviceToSfu {
+                admin_action: Some(AdminAction::EndBreakoutGroups(EndBreakoutGroups {})),
+                ..Default::default()
+            };
+
+            if let Err(e) = Self::reliable_send_to_sfu(state, msg) {
+                warn!("{ADMIN_LOG_TAG}: Failed to send end breakout groups: {e:?}");
+            } else {
+                info!("{ADMIN_LOG_TAG}: Sent end breakout groups.");
+            }
+        });
+    }
+
+    pub fn set_group_members(&self, group_members: Vec<GroupMember>) {
+        debug!(
+            "group_call::Client(outer)::set_group_members(client_id: {})",
+            self.client_id
+        );
+        self.actor.send(move |state| {
+            debug!(
+                "group_call::Client(inner)::set_group_members(client_id: {})",
+                state.client_id
+            );
+            let new_members: HashSet<UserId> =
+                group_members.iter().map(|i| i.user_id.clone()).collect();
+            if new_members != state.known_members {
+                info!("known group members changed");
+                state.known_members = new_members;
+                state
+                    .obfuscated_resolver
+                    .set_member_resolver(Arc::new(MemberMap::new(&group_members)));
+                state.sfu_client.set_group_members(group_members);
+                Self::request_remote_devices_as_soon_as_possible(state);
+            }
+        })
+    }
+
+    pub fn set_membership_proof(&self, proof: MembershipProof) {
+        debug!(
+            "group_call::Client(outer)::set_membership_proof(client_id: {})",
             self.client_id
         );
         self.actor.send(move |state| {
@@ -2474,6 +3303,35 @@ impl Client {
         }
     }
 
+    // Sends the data to all other devices through the SFU. Unlike 1:1 calls,
+    // it isn't resent, so it may be lost.
+    pub fn send_app_data(&self, data: Vec<u8>) {
+        debug!(
+            "group_call::Client(outer)::send_app_data(client_id: {} len: {})",
+            self.client_id,
+            data.len()
+        );
+
+        if data.len() > MAX_APP_DATA_LEN {
+            warn!(
+                "group_call::Client(outer)::send_app_data data size of {} exceeded allowed size of {}",
+                data.len(),
+                MAX_APP_DATA_LEN
+            );
+        } else {
+            self.actor.send(move |state| {
+                debug!(
+                    "group_call::Client(inner)::send_app_data(client_id: {}, len: {})",
+                    state.client_id,
+                    data.len()
+                );
+                if let Err(err) = Self::send_app_data_inner(state, data) {
+                    warn!("Failed to send app data: {:?}", err);
+                }
+            });
+        }
+    }
+
     pub fn raise_hand(&self, raise: bool) {
         debug!(
             "group_call::Client(outer)::raise_hand(client_id: {} raise: {})",
@@ -2481,16 +3339,172 @@ impl Client {
         );
 
         self.actor.send(move |state| {
-            state.raise_hand_state.seqnum += 1;
-            state.raise_hand_state.raise = raise;
-            state.raise_hand_state.outstanding = true;
+            Self::raise_hand_inner(state, raise);
+        });
+    }
 
-            info!(
-                "group_call::Client(inner)::raise_hand(client_id: {}, raise: {} seqnum: {})",
-                state.client_id, state.raise_hand_state.raise, state.raise_hand_state.seqnum
+    fn raise_hand_inner(state: &mut State, raise: bool) {
+        state.raise_hand_state.seqnum += 1;
+        state.raise_hand_state.raise = raise;
+        state.raise_hand_state.outstanding = true;
+
+        info!(
+            "group_call::Client(inner)::raise_hand(client_id: {}, raise: {} seqnum: {})",
+            state.client_id, state.raise_hand_state.raise, state.raise_hand_state.seqnum
+        );
+
+        Self::send_raise_hand(state);
+    }
+
+    pub fn set_lower_hand_when_speaking(&self, enabled: bool) {
+        debug!(
+            "group_call::Client(outer)::set_lower_hand_when_speaking(client_id: {}, enabled: {})",
+            self.client_id, enabled
+        );
+        self.actor.send(move |state| {
+            debug!(
+                "group_call::Client(inner)::set_lower_hand_when_speaking(client_id: {}, enabled: {})",
+                state.client_id, enabled
+            );
+            state.lower_hand_when_speaking = enabled;
+        });
+    }
+
+    pub fn set_skip_media_keys_for_bridged_participants(&self, enabled: bool) {
+        debug!(
+            "group_call::Client(outer)::set_skip_media_keys_for_bridged_participants(client_id: {}, enabled: {})",
+            self.client_id, enabled
+        );
+        self.actor.send(move |state| {
+            debug!(
+                "group_call::Client(inner)::set_skip_media_keys_for_bridged_participants(client_id: {}, enabled: {})",
+                state.client_id, enabled
             );
+            state.skip_media_keys_for_bridged_participants = enabled;
+        });
+    }
+
+    pub fn set_leave_when_own_device_joins(&self, enabled: bool) {
+        debug!(
+            "group_call::Client(outer)::set_leave_when_own_device_joins(client_id: {}, enabled: {})",
+            self.client_id, enabled
+        );
+        self.actor.send(move |state| {
+            debug!(
+                "group_call::Client(inner)::set_leave_when_own_device_joins(client_id: {}, enabled: {})",
+                state.client_id, enabled
+            );
+            state.leave_when_own_device_joins = enabled;
+        });
+    }
 
-            Self::send_raise_hand(state);
+    /// Switches the call to gathering relay candidates from `ice_servers`, without
+    /// reconnecting, e.g. because the credentials of the current ones are about to expire.
+    /// If `expires_in` is given, Observer::request_ice_servers is called shortly before
+    /// the new ones expire too.
+    pub fn set_ice_servers(&self, ice_servers: Vec<IceServer>, expires_in: Option<Duration>) {
+        debug!(
+            "group_call::Client(outer)::set_ice_servers(client_id: {}, count: {}, expires_in: {:?})",
+            self.client_id,
+            ice_servers.len(),
+            expires_in
+        );
+        self.actor.send(move |state| {
+            debug!(
+                "group_call::Client(inner)::set_ice_servers(client_id: {}, count: {}, expires_in: {:?})",
+                state.client_id,
+                ice_servers.len(),
+                expires_in
+            );
+            match Self::gather_from_ice_servers(state, &ice_servers) {
+                Ok(refreshed_ice_gatherer) => {
+                    state.refreshed_ice_gatherer = Some(refreshed_ice_gatherer);
+                    state.ice_servers_expire_at =
+                        expires_in.map(|expires_in| state.clock.now() + expires_in);
+                    state.ice_servers_refresh_requested = false;
+                }
+                Err(err) => {
+                    // The current servers stay in use until they expire.
+                    warn!(
+                        "group_call::Client(inner)::set_ice_servers(client_id: {}) failed: {:?}",
+                        state.client_id, err
+                    );
+                    state
+                        .observer
+                        .handle_ice_servers_refresh_failed(state.client_id);
+                }
+            }
+        });
+    }
+
+    // A shared IceGatherer gathers with the servers of the PeerConnection it's created
+    // from, so a throwaway PeerConnection is configured with the new servers just to
+    // create one, and kept alive for as long as the call uses its gatherer.
+    fn gather_from_ice_servers(
+        state: &State,
+        ice_servers: &[IceServer],
+    ) -> Result<RefreshedIceGatherer> {
+        let (observer_impl, observer) = PeerConnectionObserverImpl::uninitialized(None)?;
+        let peer_connection = state.peer_connection_factory.create_peer_connection(
+            observer,
+            pcf::RffiPeerConnectionKind::GroupCall,
+            &AudioJitterBufferConfig::default(),
+            AUDIO_RTCP_REPORT_INTERVAL_MS,
+            ice_servers,
+            state
+                .peer_connection_factory
+                .create_outgoing_audio_track()?,
+            None,
+        )?;
+        let ice_gatherer = peer_connection.create_shared_ice_gatherer()?;
+        state
+            .peer_connection
+            .use_shared_ice_gatherer(&ice_gatherer)?;
+        Ok(RefreshedIceGatherer {
+            _ice_gatherer: ice_gatherer,
+            _peer_connection: peer_connection,
+            _observer_impl: observer_impl,
+        })
+    }
+
+    pub fn set_detect_speaking_while_muted(&self, enabled: bool) {
+        debug!(
+            "group_call::Client(outer)::set_detect_speaking_while_muted(client_id: {}, enabled: {})",
+            self.client_id, enabled
+        );
+        self.actor.send(move |state| {
+            debug!(
+                "group_call::Client(inner)::set_detect_speaking_while_muted(client_id: {}, enabled: {})",
+                state.client_id, enabled
+            );
+            state.detect_speaking_while_muted = enabled;
+            if !enabled {
+                state.started_speaking_while_muted = None;
+                state.last_non_silent_while_muted = None;
+            }
+        });
+    }
+
+    pub fn set_key_rotation_policy(&self, policy: KeyRotationPolicy) {
+        debug!(
+            "group_call::Client(outer)::set_key_rotation_policy(client_id: {}, policy: {:?})",
+            self.client_id, policy
+        );
+        self.actor.send(move |state| {
+            debug!(
+                "group_call::Client(inner)::set_key_rotation_policy(client_id: {}, policy: {:?})",
+                state.client_id, policy
+            );
+            state.key_rotation_policy = policy;
+            // Don't let a rotation that's already been deferred wait any longer than the
+            // new policy would have it wait.
+            if let Some(rotation_time) = state.deferred_media_send_key_rotation_time.take() {
+                let now = state.clock.now();
+                Self::rotate_media_send_key_according_to_policy(state, now);
+                if let Some(new_rotation_time) = &mut state.deferred_media_send_key_rotation_time {
+                    *new_rotation_time = rotation_time.min(*new_rotation_time);
+                }
+            }
         });
     }
 
@@ -2524,11 +3538,32 @@ impl Client {
                     ConnectionState::NotConnected,
                 );
                 let _join_handles = state.actor.stopper().stop_all_without_joining();
+                let summary = Self::call_summary(state);
+                info!(
+                    "group_call::Client(inner)::end(client_id: {}): {:?}",
+                    state.client_id, summary
+                );
+                state
+                    .observer
+                    .handle_call_summary(state.client_id, summary, reason);
                 state.observer.handle_ended(state.client_id, reason);
             }
         }
     }
 
+    fn call_summary(state: &State) -> CallSummary {
+        let time_joined_so_far = state
+            .joined_at
+            .map_or(Duration::ZERO, |joined_at| joined_at.elapsed());
+        CallSummary {
+            duration: state.time_joined + time_joined_so_far,
+            media_usage: state.stats_observer.media_usage(),
+            max_participant_count: state.max_participant_count,
+            stats_time_series: state.stats_observer.time_series(),
+            setup_latencies: state.setup_latency.latencies(),
+        }
+    }
+
     fn on_sfu_client_join_success(state: &mut State, joined: Joined) {
         match state.connection_state {
             ConnectionState::NotConnected => {
@@ -2542,7 +3577,7 @@ impl Client {
                 let srtp_keys = match &state.dhe_state {
                     DheState::Negotiated { srtp_keys } => srtp_keys,
                     _ => {
-                        Self::end(state, EndReason::FailedToNegotiatedSrtpKeys);
+                        Self::end(state, EndReason::FailedToNegotiateSrtpKeys);
                         return;
                     }
                 };
@@ -2647,7 +3682,7 @@ impl Client {
                     }
                 }
 
-                state.next_stats_time = Some(Instant::now() + STATS_INITIAL_OFFSET);
+                state.next_stats_time = Some(state.clock.now() + STATS_INITIAL_OFFSET);
             }
             JoinState::Pending(_) | JoinState::Joined(_) => {
                 warn!("The SFU completed joining more than once.");
@@ -2667,7 +3702,28 @@ impl Client {
                 _ => EndReason::SfuClientFailedToJoin,
             },
         );
-        Self::end(state, end_reason);
+        if end_reason == EndReason::HasMaxDevices {
+            Self::end_because_call_is_full(state);
+        } else {
+            Self::end(state, end_reason);
+        }
+    }
+
+    fn end_because_call_is_full(state: &mut State) {
+        let (max_devices, device_count) =
+            state
+                .last_peek_info
+                .as_ref()
+                .map_or((None, None), |peek_info| {
+                    (
+                        peek_info.max_devices,
+                        Some(peek_info.device_count_including_pending_devices() as u32),
+                    )
+                });
+        state
+            .observer
+            .handle_call_full(state.client_id, max_devices, device_count);
+        Self::end(state, EndReason::HasMaxDevices);
     }
 
     // Called by the SfuClient after a join attempt completes.
@@ -2697,9 +3753,7 @@ impl Client {
     // Currently, this occurs via on_sfu_client_joined (Joining -> Joined) or
     // or via peek_result_inner (Joining -> Pending -> Joined)
     fn on_client_joined(state: &mut State) {
-        state
-            .peer_connection
-            .configure_audio_encoders(&AudioEncoderConfig::default());
+        Self::configure_audio_encoders(state);
     }
 
     pub fn on_signaling_message_received(
@@ -2870,7 +3924,7 @@ impl Client {
             let old_stats_interval = state.get_stats_interval;
             state.get_stats_interval = if interval.is_zero() {
                 state.stats_observer.set_collect_raw_stats_report(false);
-                DEFAULT_STATS_INTERVAL
+                state.intervals.stats
             } else {
                 state.stats_observer.set_collect_raw_stats_report(true);
                 interval
@@ -2892,8 +3946,9 @@ impl Client {
 
         if let Err(e) = result {
             warn!("Failed to request remote devices from SFU: {:?}", e);
-            state.remote_devices_request_state =
-                RemoteDevicesRequestState::Failed { at: Instant::now() };
+            state.remote_devices_request_state = RemoteDevicesRequestState::Failed {
+                at: state.clock.now(),
+            };
             return;
         }
         let peek_info = result.unwrap();
@@ -2906,8 +3961,9 @@ impl Client {
                 ..
             }
         );
-        state.remote_devices_request_state =
-            RemoteDevicesRequestState::Updated { at: Instant::now() };
+        state.remote_devices_request_state = RemoteDevicesRequestState::Updated {
+            at: state.clock.now(),
+        };
 
         let old_user_ids: HashSet<UserId> = std::mem::take(&mut state.joined_members);
         let new_user_ids: HashSet<UserId> = peek_info
@@ -2993,13 +4049,28 @@ impl Client {
                                 return existing_remote_device;
                             }
                         }
-                        RemoteDeviceState::new(device.demux_id, user_id.clone(), added_time)
+                        RemoteDeviceState::new(
+                            device.demux_id,
+                            user_id.clone(),
+                            device.participant_kind,
+                            added_time,
+                        )
                     })
                 })
                 .collect();
 
             // Recalculate to see the differences
             let new_demux_ids: HashSet<DemuxId> = state.remote_devices.demux_id_set();
+            state
+                .incoming_video_tracks
+                .retain(|demux_id, _| new_demux_ids.contains(demux_id));
+            state
+                .rendering_paused_demux_ids
+                .retain(|demux_id| new_demux_ids.contains(demux_id));
+            // Count the local device too.
+            state.max_participant_count = state
+                .max_participant_count
+                .max(new_demux_ids.len() as u32 + 1);
 
             let added_demux_ids: HashSet<DemuxId> =
                 new_demux_ids.difference(&old_demux_ids).copied().collect();
@@ -3122,7 +4193,8 @@ impl Client {
 
             // If someone was removed, we must reset the send media key and send it to everyone not removed.
             if old_user_ids.difference(&new_user_ids).next().is_some() {
-                Self::rotate_media_send_key_and_send_to_users_not_removed(state);
+                let now = state.clock.now();
+                Self::rotate_media_send_key_according_to_policy(state, now);
             }
 
             // We can't gate this behind the demux IDs changing because a forged demux ID might
@@ -3159,6 +4231,13 @@ impl Client {
             {
                 state.outgoing_ring_state = OutgoingRingState::NotPermittedToRing;
             }
+
+            // Devices that were already there when we joined don't count as joining.
+            if state.remote_devices_updated_since_join {
+                Self::handle_own_devices_joined(state, &added_demux_ids);
+            } else if local_device_is_participant {
+                state.remote_devices_updated_since_join = true;
+            }
         }
         state.last_peek_info = Some(peek_info);
 
@@ -3249,13 +4328,111 @@ impl Client {
         Ok(())
     }
 
+    fn handle_own_devices_joined(state: &mut State, added_demux_ids: &HashSet<DemuxId>) {
+        let Some(self_uuid) = state.self_uuid.lock().expect("can read UUID").clone() else {
+            return;
+        };
+        let mut own_demux_ids: Vec<DemuxId> = state
+            .remote_devices
+            .iter()
+            .filter(|device| added_demux_ids.contains(&device.demux_id))
+            .filter(|device| device.user_id == self_uuid)
+            .map(|device| device.demux_id)
+            .collect();
+        if own_demux_ids.is_empty() {
+            return;
+        }
+        own_demux_ids.sort_unstable();
+
+        for demux_id in own_demux_ids {
+            info!(
+                "group_call::Client(inner)::handle_own_devices_joined(client_id: {}): another device on this account joined with demux_id: {}",
+                state.client_id, demux_id
+            );
+            state
+                .observer
+                .handle_own_device_joined(state.client_id, demux_id);
+        }
+        if state.leave_when_own_device_joins {
+            info!(
+                "group_call::Client(inner)::handle_own_devices_joined(client_id: {}): leaving for the other device",
+                state.client_id
+            );
+            Self::leave_inner(state);
+        }
+    }
+
+    fn check_speaking_while_muted(state: &mut State, captured_level: AudioLevel, now: Instant) {
+        if !state.detect_speaking_while_muted
+            || state.outgoing_heartbeat_state.audio_muted != Some(true)
+        {
+            state.started_speaking_while_muted = None;
+            state.last_non_silent_while_muted = None;
+            return;
+        }
+
+        if captured_level > MIN_NON_SILENT_LEVEL {
+            state.last_non_silent_while_muted = Some(now);
+            state.started_speaking_while_muted.get_or_insert(now);
+        } else if state.last_non_silent_while_muted.map_or(true, |last| {
+            now.saturating_duration_since(last) > SPEAKING_WHILE_MUTED_MAX_GAP
+        }) {
+            state.started_speaking_while_muted = None;
+            state.last_non_silent_while_muted = None;
+        }
+
+        let speaking_long_enough = state.started_speaking_while_muted.map_or(false, |start| {
+            now.saturating_duration_since(start) >= MIN_SPEAKING_WHILE_MUTED
+        });
+        let notified_recently = state
+            .last_speaking_while_muted_notification_time
+            .map_or(false, |last| {
+                now.saturating_duration_since(last) < SPEAKING_WHILE_MUTED_NOTIFICATION_INTERVAL
+            });
+        if speaking_long_enough && !notified_recently {
+            info!(
+                "group_call::Client(inner)::check_speaking_while_muted(client_id: {}): the local user appears to be speaking while muted",
+                state.client_id
+            );
+            state
+                .observer
+                .handle_speaking_notification(state.client_id, SpeechEvent::SpeakingWhileMuted);
+            state.last_speaking_while_muted_notification_time = Some(now);
+            state.started_speaking_while_muted = None;
+        }
+    }
+
+    fn rotate_media_send_key_according_to_policy(state: &mut State, now: Instant) {
+        match state.key_rotation_policy {
+            KeyRotationPolicy::Immediate => {
+                Self::rotate_media_send_key_and_send_to_users_not_removed(state);
+            }
+            KeyRotationPolicy::Debounced(delay) => {
+                let deadline = *state
+                    .deferred_media_send_key_rotation_deadline
+                    .get_or_insert(now + MAX_DEBOUNCED_KEY_ROTATION_DELAY);
+                state.deferred_media_send_key_rotation_time = Some((now + delay).min(deadline));
+            }
+            KeyRotationPolicy::Batched(delay) => {
+                state
+                    .deferred_media_send_key_rotation_time
+                    .get_or_insert(now + delay);
+            }
+        }
+    }
+
     fn rotate_media_send_key_and_send_to_users_not_removed(state: &mut State) {
+        // Whatever rotation was being debounced is covered by this one.
+        state.deferred_media_send_key_rotation_deadline = None;
         match state.media_send_key_rotation_state {
-            KeyRotationState::Pending { secret, .. } => {
+            KeyRotationState::Pending {
+                secret, apply_at, ..
+            } => {
                 info!("Waiting to generate a new media send key until after the pending one has been applied. client_id: {}", state.client_id);
 
                 state.media_send_key_rotation_state = KeyRotationState::Pending {
                     secret,
+                    apply_at,
                     needs_another_rotation: true,
                 }
             }
@@ -3287,39 +4464,46 @@ impl Client {
                     );
                 }
 
+                let delay = Duration::from_secs(MEDIA_SEND_KEY_ROTATION_DELAY_SECS);
                 state.media_send_key_rotation_state = KeyRotationState::Pending {
                     secret,
+                    apply_at: state.clock.now() + delay,
                     needs_another_rotation: false,
                 };
-                state.actor.send_delayed(
-                    Duration::from_secs(MEDIA_SEND_KEY_ROTATION_DELAY_SECS),
-                    move |state| {
-                        info!("Applying the new send key. client_id: {}", state.client_id);
-                        {
-                            let mut frame_crypto_context =
-                                state.frame_crypto_context.lock().expect(
-                                    "Get lock for frame encryption context to reset media send key",
-                                );
-                            frame_crypto_context.reset_send_ratchet(secret);
-                        }
-
-                        let needs_another_rotation = matches!(
-                            state.media_send_key_rotation_state,
-                            KeyRotationState::Pending {
-                                needs_another_rotation: true,
-                                ..
-                            }
-                        );
-                        state.media_send_key_rotation_state = KeyRotationState::Applied;
-                        if needs_another_rotation {
-                            Self::rotate_media_send_key_and_send_to_users_not_removed(state);
-                        }
-                    },
-                )
+                // The tick also applies it if the clock is advanced past apply_at first.
+                state.actor.send_delayed(delay, move |state| {
+                    Self::apply_pending_media_send_key(state, secret);
+                })
             }
         }
     }
 
+    // Does nothing if the key has already been applied.
+    fn apply_pending_media_send_key(state: &mut State, secret: frame_crypto::Secret) {
+        let needs_another_rotation = match state.media_send_key_rotation_state {
+            KeyRotationState::Pending {
+                secret: pending_secret,
+                needs_another_rotation,
+                ..
+            } if pending_secret == secret => needs_another_rotation,
+            _ => return,
+        };
+
+        info!("Applying the new send key. client_id: {}", state.client_id);
+        {
+            let mut frame_crypto_context = state
+                .frame_crypto_context
+                .lock()
+                .expect("Get lock for frame encryption context to reset media send key");
+            frame_crypto_context.reset_send_ratchet(secret);
+        }
+
+        state.media_send_key_rotation_state = KeyRotationState::Applied;
+        if needs_another_rotation {
+            Self::rotate_media_send_key_and_send_to_users_not_removed(state);
+        }
+    }
+
     fn advance_media_send_key_and_send_to_users_with_added_devices(
         state: &mut State,
         users_with_added_devices: HashSet<UserId>,
@@ -3427,6 +4611,22 @@ impl Client {
             ..Default::default()
         };
 
+        // Media is isolated per breakout group, so keys are only shared within ours.
+        // Keys are per user, so a user with devices in several groups gets them anyway.
+        if !state.breakout_groups.is_empty() {
+            let users_in_breakout_group = Self::users_in_local_breakout_group(state);
+            recipients.retain(|user_id| users_in_breakout_group.contains(user_id));
+        }
+        if state.skip_media_keys_for_bridged_participants {
+            let app_users: HashSet<&UserId> = state
+                .remote_devices
+                .iter()
+                .filter(|device| device.participant_kind == ParticipantKind::AppUser)
+                .map(|device| &device.user_id)
+                .collect();
+            recipients.retain(|user_id| app_users.contains(user_id));
+        }
+
         // The multi-recipient API should not be used to send to a user's own UUID. If it
         // is in the set, remove it and send separately with a normal message.
         let self_uuid_to_send_to =
@@ -3464,6 +4664,27 @@ impl Client {
         }
     }
 
+    fn local_breakout_group(state: &State) -> Option<BreakoutGroupId> {
+        match state.join_state {
+            JoinState::Pending(local_demux_id) | JoinState::Joined(local_demux_id) => {
+                state.breakout_groups.get(&local_demux_id).copied()
+            }
+            JoinState::NotJoined(_) | JoinState::Joining => None,
+        }
+    }
+
+    fn users_in_local_breakout_group(state: &State) -> HashSet<UserId> {
+        let local_breakout_group = Self::local_breakout_group(state);
+        state
+            .remote_devices
+            .iter()
+            .filter(|device| {
+                state.breakout_groups.get(&device.demux_id).copied() == local_breakout_group
+            })
+            .map(|device| device.user_id.clone())
+            .collect()
+    }
+
     fn send_pending_media_send_key_to_users_with_added_devices(
         state: &mut State,
         users_with_added_devices: HashSet<UserId>,
@@ -3579,12 +4800,14 @@ impl Client {
             .lock()
             .expect("Get e2ee context to decrypt media");
 
-        Self::decrypt(
+        let result = Self::decrypt(
             &mut frame_crypto_context,
             remote_demux_id,
             ciphertext,
             plaintext_buffer,
-        )
+        );
+        self.record_decryption(remote_demux_id, result.is_ok());
+        result
     }
 
     fn decrypt_data(&self, remote_demux_id: DemuxId, ciphertext: &[u8]) -> Result<Vec<u8>> {
@@ -3594,15 +4817,23 @@ impl Client {
             .expect("Get e2ee context to encrypt data");
 
         let mut plaintext = vec![0; Self::get_plaintext_buffer_size(ciphertext.len())];
-        Self::decrypt(
+        let result = Self::decrypt(
             &mut frame_crypto_context,
             remote_demux_id,
             ciphertext,
             &mut plaintext,
-        )?;
+        );
+        self.record_decryption(remote_demux_id, result.is_ok());
+        result?;
         Ok(plaintext)
     }
 
+    fn record_decryption(&self, remote_demux_id: DemuxId, decrypted: bool) {
+        if let Ok(mut counts) = self.decryption_counts.lock() {
+            counts.record(remote_demux_id, decrypted);
+        }
+    }
+
     fn decrypt(
         frame_crypto_context: &mut frame_crypto::Context,
         remote_demux_id: DemuxId,
@@ -3622,16 +4853,88 @@ impl Client {
         // the write_slice that supports overlapping copies.
         let encrypted_payload = plaintext.write_slice_overlapping(ciphertext.remaining())?;
 
-        frame_crypto_context.decrypt(
+        if let Err(err) = frame_crypto_context.decrypt(
             remote_demux_id,
             ratchet_counter,
             frame_counter as u64,
             encrypted_payload,
             &mac,
-        )?;
+        ) {
+            metrics::increment_counter(metrics::FRAME_DECRYPT_FAILURES, 1);
+            // Failures for every frame in a row are collapsed into one event.
+            diagnostics::record(
+                DiagnosticEventKind::DecryptFailure,
+                format!("demux_id: {}", remote_demux_id),
+            );
+            return Err(err.into());
+        }
         Ok(encrypted_payload.len())
     }
 
+    // Rates each known remote device by the worst of its incoming streams.
+    fn compute_remote_connection_qualities(
+        samples: &[ReceiveQualitySample],
+        remote_devices: &RemoteDevices,
+    ) -> Vec<RemoteConnectionQuality> {
+        let mut quality_by_demux_id: HashMap<DemuxId, ConnectionQuality> = HashMap::new();
+        for sample in samples {
+            if sample.packets_received == 0 && sample.packets_lost <= 0 {
+                // Nothing was received on this stream (muted, or not forwarded).
+                continue;
+            }
+            let demux_id = demux_id_from_ssrc(sample.ssrc);
+            if remote_devices.find_by_demux_id(demux_id).is_none() {
+                continue;
+            }
+            let quality = ConnectionQuality::from_receive_quality_sample(sample);
+            quality_by_demux_id
+                .entry(demux_id)
+                .and_modify(|worst| *worst = (*worst).max(quality))
+                .or_insert(quality);
+        }
+        let mut qualities: Vec<RemoteConnectionQuality> = quality_by_demux_id
+            .into_iter()
+            .map(|(demux_id, quality)| RemoteConnectionQuality { demux_id, quality })
+            .collect();
+        qualities.sort_unstable_by_key(|q| q.demux_id);
+        qualities
+    }
+
+    // Pairs up the incoming audio and video of each known remote device.
+    fn compute_av_sync(
+        snapshot: &StatsSnapshot,
+        remote_devices: &RemoteDevices,
+    ) -> Vec<AvSyncStats> {
+        let mut av_sync: Vec<AvSyncStats> = snapshot
+            .audio_recv
+            .iter()
+            .filter_map(|audio| {
+                let demux_id = demux_id_from_ssrc(audio.ssrc);
+                remote_devices.find_by_demux_id(demux_id)?;
+                // Only one video layer is forwarded at a time, so the first one
+                // flowing is the one being played out.
+                snapshot
+                    .video_recv
+                    .iter()
+                    .filter(|video| demux_id_from_ssrc(video.ssrc) == demux_id)
+                    .find_map(|video| AvSyncStats::from_streams(audio, video))
+            })
+            .collect();
+        av_sync.sort_unstable_by_key(|stats| stats.audio_ssrc);
+        av_sync
+    }
+
+    fn av_sync_skews_to_warn_about(av_sync: &[AvSyncStats]) -> Vec<RemoteAvSyncSkew> {
+        av_sync
+            .iter()
+            .filter(|stats| stats.skew.abs() >= AV_SYNC_SKEW_WARNING_SECS)
+            .map(|stats| RemoteAvSyncSkew {
+                demux_id: demux_id_from_ssrc(stats.audio_ssrc),
+                skew: stats.skew,
+            })
+            .collect()
+    }
+
     fn send_heartbeat(state: &mut State) -> Result<()> {
         let heartbeat_msg = protobuf::group_call::DeviceToDevice {
             heartbeat: {
@@ -3657,6 +4960,14 @@ impl Client {
         Self::broadcast_data_through_sfu(state, &react_msg.encode_to_vec())
     }
 
+    fn send_app_data_inner(state: &mut State, data: Vec<u8>) -> Result<()> {
+        let app_data_msg = protobuf::group_call::DeviceToDevice {
+            app_data: Some(protobuf::group_call::device_to_device::AppData { data: Some(data) }),
+            ..Default::default()
+        };
+        Self::broadcast_data_through_sfu(state, &app_data_msg.encode_to_vec())
+    }
+
     fn send_raise_hand(state: &mut State) {
         use protobuf::group_call::device_to_sfu::RaiseHand;
         let msg = DeviceToSfu {
@@ -3857,7 +5168,7 @@ impl Client {
                 &payload,
             )?;
             state.rtp_data_through_sfu_next_seqnum = new_seqnum;
-            Ok((payload, Instant::now() + DEVICE_TO_SFU_TIMEOUT))
+            Ok((payload, state.clock.now() + DEVICE_TO_SFU_TIMEOUT))
         })
     }
 
@@ -3922,6 +5233,9 @@ impl Client {
                         if let Some(reaction) = msg.reaction {
                             self.handle_reaction(demux_id, reaction);
                         }
+                        if let Some(app_data) = msg.app_data {
+                            self.handle_app_data(demux_id, app_data);
+                        }
                     } else {
                         warn!(
                             "Ignoring received RTP data because decoding failed. demux_id: {}",
@@ -3993,7 +5307,7 @@ impl Client {
 
     fn handle_sfu_to_device_inner(actor: &Actor<State>, header: rtp::Header, msg: SfuToDevice) {
         use protobuf::group_call::sfu_to_device::{
-            CurrentDevices, DeviceJoinedOrLeft, RaisedHands, Removed, Speaker,
+            BreakoutGroups, CurrentDevices, DeviceJoinedOrLeft, RaisedHands, Removed, Speaker,
         };
         // TODO: Use video_request to throttle down how much we send when it's not needed.
         let SfuToDevice {
@@ -4006,6 +5320,7 @@ impl Client {
             raised_hands,
             mrp_header: _,
             content,
+            breakout_groups,
         } = msg;
 
         if let Some(content) = content {
@@ -4075,6 +5390,13 @@ impl Client {
         {
             Self::handle_raised_hands(actor, demux_ids, target_seqnum);
         }
+        if let Some(BreakoutGroups {
+            demux_ids,
+            breakout_group_ids,
+        }) = breakout_groups
+        {
+            Self::handle_breakout_groups(actor, demux_ids, breakout_group_ids);
+        }
     }
 
     fn handle_removed_received(actor: &Actor<State>) {
@@ -4263,6 +5585,76 @@ impl Client {
         }
     }
 
+    fn handle_app_data(
+        &self,
+        demux_id: DemuxId,
+        app_data: protobuf::group_call::device_to_device::AppData,
+    ) {
+        trace!("handle_app_data(): demux_id = {}", demux_id);
+
+        let data = app_data.data.unwrap_or_default();
+        if data.len() > MAX_APP_DATA_LEN {
+            warn!(
+                "group_call::handle_app_data data size of {} exceeded allowed size of {}",
+                data.len(),
+                MAX_APP_DATA_LEN
+            );
+        } else {
+            self.actor.send(move |state| {
+                state
+                    .observer
+                    .handle_app_data(state.client_id, demux_id, data);
+            });
+        }
+    }
+
+    fn handle_breakout_groups(
+        actor: &Actor<State>,
+        demux_ids: Vec<DemuxId>,
+        breakout_group_ids: Vec<BreakoutGroupId>,
+    ) {
+        actor.send(move |state| {
+            let breakout_groups: HashMap<DemuxId, BreakoutGroupId> = demux_ids
+                .into_iter()
+                .zip(breakout_group_ids)
+                .filter(|(_, breakout_group_id)| *breakout_group_id != 0)
+                .collect();
+            if state.breakout_groups == breakout_groups {
+                return;
+            }
+
+            let old_breakout_group = Self::local_breakout_group(state);
+            let old_users = Self::users_in_local_breakout_group(state);
+            state.breakout_groups = breakout_groups;
+            let new_breakout_group = Self::local_breakout_group(state);
+            let new_users = Self::users_in_local_breakout_group(state);
+
+            if old_breakout_group != new_breakout_group {
+                info!(
+                    "group_call::Client(inner)::handle_breakout_groups(client_id: {}): moved to breakout group {:?}",
+                    state.client_id, new_breakout_group
+                );
+                state
+                    .observer
+                    .handle_breakout_group_changed(state.client_id, new_breakout_group);
+            }
+
+            // Same as when devices are added or removed, except that isolating the groups
+            // can't wait for a deferred rotation.
+            let added_users: HashSet<UserId> = new_users.difference(&old_users).cloned().collect();
+            if !added_users.is_empty() {
+                Self::advance_media_send_key_and_send_to_users_with_added_devices(
+                    state,
+                    added_users.clone(),
+                );
+                Self::send_pending_media_send_key_to_users_with_added_devices(state, added_users);
+            }
+            if old_users.difference(&new_users).next().is_some() {
+                Self::rotate_media_send_key_and_send_to_users_not_removed(state);
+            }
+        });
+    }
+
     fn handle_raised_hands(actor: &Actor<State>, raised_hands: Vec<DemuxId>, server_seqnum: u32) {
         actor.send(move |state| {
             // The server has previously received a hand raise request from the client or admin
@@ -4321,10 +5713,26 @@ impl Client {
                         .handle_raised_hands(state.client_id, state.raised_hands.clone());
                 }
             }
+
+            // The server can lower our hand on our behalf, such as when an admin lowers
+            // all hands. Keep the local state in line so that auto-lowering doesn't
+            // send a redundant request.
+            if let JoinState::Joined(local_demux_id) = state.join_state {
+                if state.raise_hand_state.raise
+                    && !state.raise_hand_state.outstanding
+                    && !state.raised_hands.contains(&local_demux_id)
+                {
+                    info!(
+                        "group_call::Client(inner)::handle_raised_hands(client_id: {}): local hand was lowered by the server",
+                        state.client_id
+                    );
+                    state.raise_hand_state.raise = false;
+                }
+            }
         });
     }
 
-    #[cfg(feature = "sim")]
+    #[cfg(any(feature = "sim", feature = "headless"))]
     pub fn synchronize(&self) {
         let barrier = Arc::new(std::sync::Barrier::new(2));
         let barrier_for_task = barrier.clone();
@@ -4404,6 +5812,10 @@ impl PeerConnectionObserverTrait for PeerConnectionObserverImpl {
             self.log_id(),
             ice_connection_state
         );
+        diagnostics::record(
+            DiagnosticEventKind::IceChange,
+            format!("group call: {:?}", ice_connection_state),
+        );
         if let Some(client) = &self.client {
             client.actor.send(move |state| {
                 debug!("group_call::Client(inner)::handle_ice_connection_state_changed(client_id: {}, state: {:?})", state.client_id, ice_connection_state);
@@ -4485,6 +5897,13 @@ impl PeerConnectionObserverTrait for PeerConnectionObserverImpl {
                 );
 
                 if let Some(remote_demux_id) = demux_id {
+                    if Client::is_rendering_paused(state, remote_demux_id) {
+                        incoming_video_track.set_enabled(false);
+                    }
+                    state
+                        .incoming_video_tracks
+                        .insert(remote_demux_id, incoming_video_track.clone());
+
                     // When PeerConnection::SetRemoteDescription triggers PeerConnectionObserver::OnAddTrack,
                     // if it's a VideoTrack, this is where it comes.  Each platform does different things:
                     // - iOS: The VideoTrack is wrapped in an RTCVideoTrack and passed to the app
@@ -4519,6 +5938,7 @@ impl PeerConnectionObserverTrait for PeerConnectionObserverImpl {
             incoming_video_sink.on_video_frame(demux_id, video_frame)
         }
         if let Some(client) = &self.client {
+            client.setup_latency.record_video_frame();
             let prev_height = self
                 .last_height_by_demux_id
                 .lock()
@@ -4613,6 +6033,12 @@ impl PeerConnectionObserverTrait for PeerConnectionObserverImpl {
     }
 }
 
+// Each device is allocated a block of 16 SSRCs starting at its demux ID
+// (see RTP_DATA_THROUGH_SFU_SSRC_OFFSET).
+fn demux_id_from_ssrc(ssrc: rtp::Ssrc) -> DemuxId {
+    ssrc & !0b1111
+}
+
 fn random_alphanumeric(len: usize) -> String {
     std::iter::repeat(())
         .map(|()| rand::rngs::OsRng.sample(rand::distributions::Alphanumeric))
@@ -4900,6 +6326,7 @@ mod tests {
 
         connecting: Event,
         joined: Event,
+        left: Event,
         peek_changed: Event,
         reactions_called: Event,
         remote_devices_changed: Event,
@@ -4908,6 +6335,13 @@ mod tests {
         peek_state: Arc<CallMutex<FakeObserverPeekState>>,
         send_rates: Arc<CallMutex<Option<SendRates>>>,
         ended: Waitable<EndReason>,
+        call_summary: Waitable<(CallSummary, EndReason)>,
+        call_full: Waitable<(Option<u32>, Option<u32>)>,
+        own_device_joined: Waitable<DemuxId>,
+        ice_servers_requested: Event,
+        ice_servers_refresh_failed: Event,
+        breakout_group_changed: Waitable<Option<BreakoutGroupId>>,
+        app_data: Waitable<(DemuxId, Vec<u8>)>,
         reactions: Arc<CallMutex<Vec<Reaction>>>,
 
         request_membership_proof_invocation_count: Arc<AtomicU64>,
@@ -4937,6 +6371,7 @@ mod tests {
                 )),
                 connecting: Event::default(),
                 joined: Event::default(),
+                left: Event::default(),
                 peek_changed: Event::default(),
                 reactions_called: Event::default(),
                 remote_devices_changed: Event::default(),
@@ -4951,6 +6386,13 @@ mod tests {
                 )),
                 send_rates: Arc::new(CallMutex::new(None, "FakeObserver send rates")),
                 ended: Waitable::default(),
+                call_summary: Waitable::default(),
+                call_full: Waitable::default(),
+                own_device_joined: Waitable::default(),
+                ice_servers_requested: Event::default(),
+                ice_servers_refresh_failed: Event::default(),
+                breakout_group_changed: Waitable::default(),
+                app_data: Waitable::default(),
                 reactions: Arc::new(CallMutex::new(Default::default(), "FakeObserver reactions")),
                 request_membership_proof_invocation_count: Default::default(),
                 request_group_members_invocation_count: Default::default(),
@@ -5109,6 +6551,8 @@ mod tests {
                     .expect("Lock joined members at join time to handle update");
                 *owned_remote_devices_at_join_time = self.remote_devices();
                 self.joined.set();
+            } else if let JoinState::NotJoined(_) = join_state {
+                self.left.set();
             }
         }
 
@@ -5164,6 +6608,10 @@ mod tests {
 
         fn handle_raised_hands(&self, _client_id: ClientId, _raised_hands: Vec<DemuxId>) {}
 
+        fn handle_app_data(&self, _client_id: ClientId, demux_id: DemuxId, data: Vec<u8>) {
+            self.app_data.set((demux_id, data));
+        }
+
         fn handle_rtc_stats_report(&self, _report_json: String) {}
 
         fn handle_peek_changed(
@@ -5300,12 +6748,50 @@ mod tests {
             }
         }
 
-        fn handle_incoming_video_track(
-            &mut self,
+        fn handle_incoming_video_track(
+            &mut self,
+            _client_id: ClientId,
+            _remote_demux_id: DemuxId,
+            _incoming_video_track: VideoTrack,
+        ) {
+        }
+
+        fn handle_call_summary(
+            &self,
+            _client_id: ClientId,
+            summary: CallSummary,
+            reason: EndReason,
+        ) {
+            self.call_summary.set((summary, reason));
+        }
+
+        fn handle_call_full(
+            &self,
+            _client_id: ClientId,
+            max_devices: Option<u32>,
+            device_count: Option<u32>,
+        ) {
+            self.call_full.set((max_devices, device_count));
+        }
+
+        fn handle_own_device_joined(&self, _client_id: ClientId, demux_id: DemuxId) {
+            self.own_device_joined.set(demux_id);
+        }
+
+        fn request_ice_servers(&self, _client_id: ClientId) {
+            self.ice_servers_requested.set();
+        }
+
+        fn handle_ice_servers_refresh_failed(&self, _client_id: ClientId) {
+            self.ice_servers_refresh_failed.set();
+        }
+
+        fn handle_breakout_group_changed(
+            &self,
             _client_id: ClientId,
-            _remote_demux_id: DemuxId,
-            _incoming_video_track: VideoTrack,
+            breakout_group_id: Option<BreakoutGroupId>,
         ) {
+            self.breakout_group_changed.set(breakout_group_id);
         }
 
         fn handle_ended(&self, _client_id: ClientId, reason: EndReason) {
@@ -5330,6 +6816,33 @@ mod tests {
         }
 
         fn with_sfu_client(user_id: UserId, demux_id: DemuxId, sfu_client: FakeSfuClient) -> Self {
+            Self::with_sfu_client_and_intervals(
+                user_id,
+                demux_id,
+                sfu_client,
+                GroupCallIntervals::default(),
+            )
+        }
+
+        fn with_intervals(
+            user_id: UserId,
+            demux_id: DemuxId,
+            intervals: GroupCallIntervals,
+        ) -> Self {
+            Self::with_sfu_client_and_intervals(
+                user_id,
+                demux_id,
+                FakeSfuClient::new(demux_id, None),
+                intervals,
+            )
+        }
+
+        fn with_sfu_client_and_intervals(
+            user_id: UserId,
+            demux_id: DemuxId,
+            sfu_client: FakeSfuClient,
+            intervals: GroupCallIntervals,
+        ) -> Self {
             let observer = FakeObserver::new(user_id.clone());
             let fake_busy = Arc::new(CallMutex::new(false, "fake_busy"));
             let fake_self_uuid = Arc::new(CallMutex::new(Some(user_id.clone()), "fake_self_uuid"));
@@ -5355,6 +6868,8 @@ mod tests {
                 incoming_video_sink: None,
                 ring_id: None,
                 audio_levels_interval: Some(Duration::from_millis(200)),
+                intervals,
+                ice_servers: vec![],
             })
             .expect("Start Client");
             Self {
@@ -5368,6 +6883,7 @@ mod tests {
                     devices: vec![PeekDeviceInfo {
                         demux_id,
                         user_id: Some(user_id),
+                        participant_kind: ParticipantKind::AppUser,
                     }],
                     ..Default::default()
                 },
@@ -5416,6 +6932,7 @@ mod tests {
                 .map(|client| PeekDeviceInfo {
                     demux_id: client.demux_id,
                     user_id: Some(client.user_id.clone()),
+                    participant_kind: ParticipantKind::AppUser,
                 })
                 .collect();
             // Need to clone to pass over to the actor and set in observer.
@@ -5436,6 +6953,7 @@ mod tests {
                 .map(|client| PeekDeviceInfo {
                     demux_id: client.demux_id,
                     user_id: Some(client.user_id.clone()),
+                    participant_kind: ParticipantKind::AppUser,
                 })
                 .collect();
             let peek_info = PeekInfo {
@@ -5533,6 +7051,200 @@ mod tests {
         }
     }
 
+    /// A step of a [SimulatedCall] script.
+    #[derive(Clone, Copy, Debug)]
+    enum SimStep {
+        /// The client joins and is admitted right away.
+        Join(DemuxId),
+        /// The client joins but waits in the lobby until [SimStep::Admit].
+        RequestToJoin(DemuxId),
+        Admit(DemuxId),
+        /// The client leaves, and the others see it gone.
+        Leave(DemuxId),
+        /// Every client's clock moves forward, running whatever timers are due.
+        Advance(Duration),
+        /// The client's connection to the SFU changes, as reported by ICE.
+        Ice(DemuxId, IceConnectionState),
+        /// The SFU moves the given devices into breakout groups; everyone else is in the main
+        /// call.
+        Breakout(&'static [(DemuxId, BreakoutGroupId)]),
+    }
+
+    /// Runs clients in one call against fake SFUs that report the same membership to all of
+    /// them, with clocks that only move forward when the script says so, so that timing-dependent
+    /// behavior can be tested without sleeping.
+    struct SimulatedCall {
+        clients: Vec<TestClient>,
+        joined: Vec<DemuxId>,
+        pending: Vec<DemuxId>,
+    }
+
+    impl SimulatedCall {
+        /// Creates clients with demux IDs 1 through `count`, each with its own user ID, none of
+        /// which have joined yet.
+        fn new(count: u32) -> Self {
+            Self {
+                clients: (1..=count)
+                    .map(|demux_id| TestClient::new(vec![demux_id as u8], demux_id))
+                    .collect(),
+                joined: Vec::new(),
+                pending: Vec::new(),
+            }
+        }
+
+        fn client(&mut self, demux_id: DemuxId) -> &mut TestClient {
+            self.clients
+                .iter_mut()
+                .find(|client| client.demux_id == demux_id)
+                .expect("simulated client")
+        }
+
+        fn run(&mut self, script: &[SimStep]) {
+            for step in script {
+                debug!("SimulatedCall step: {:?}", step);
+                match *step {
+                    SimStep::Join(demux_id) => {
+                        self.client(demux_id).connect_join_and_wait_until_joined();
+                        self.joined.push(demux_id);
+                        self.publish_membership();
+                    }
+                    SimStep::RequestToJoin(demux_id) => {
+                        let client = self.client(demux_id);
+                        client
+                            .sfu_client
+                            .set_response_join_state(JoinState::Pending(demux_id));
+                        client.client.connect();
+                        client.client.join();
+                        client.wait_for_client_to_process();
+                        self.pending.push(demux_id);
+                        self.publish_membership();
+                    }
+                    SimStep::Admit(demux_id) => {
+                        self.pending.retain(|pending| *pending != demux_id);
+                        self.joined.push(demux_id);
+                        self.publish_membership();
+                        assert!(self
+                            .client(demux_id)
+                            .observer
+                            .joined
+                            .wait(Duration::from_secs(5)));
+                    }
+                    SimStep::Leave(demux_id) => {
+                        self.client(demux_id).client.leave();
+                        self.joined.retain(|joined| *joined != demux_id);
+                        self.publish_membership();
+                    }
+                    SimStep::Advance(by) => {
+                        for client in &self.clients {
+                            client.client.advance_clock(by);
+                        }
+                        self.wait_for_clients_to_process();
+                    }
+                    SimStep::Ice(demux_id, ice_connection_state) => {
+                        let client = self.client(demux_id);
+                        client.client.actor.send(move |state| {
+                            let _ = state
+                                .peer_connection_observer_impl
+                                .handle_ice_connection_state_changed(ice_connection_state);
+                        });
+                        // Once to pass the change along, and again to handle it.
+                        client.wait_for_client_to_process();
+                        client.wait_for_client_to_process();
+                    }
+                    SimStep::Breakout(breakout_groups) => {
+                        let (demux_ids, breakout_group_ids): (Vec<_>, Vec<_>) =
+                            breakout_groups.iter().copied().unzip();
+                        for client in &self.clients {
+                            Client::handle_breakout_groups(
+                                &client.client.actor,
+                                demux_ids.clone(),
+                                breakout_group_ids.clone(),
+                            );
+                        }
+                        self.wait_for_clients_to_process();
+                    }
+                }
+            }
+        }
+
+        /// Gives everyone in the call (or waiting to get in) the current membership, as the SFU
+        /// would when they next poll it.
+        fn publish_membership(&self) {
+            let joined: Vec<TestClient> = self
+                .joined
+                .iter()
+                .filter_map(|demux_id| self.clients.iter().find(|c| c.demux_id == *demux_id))
+                .cloned()
+                .collect();
+            let to_devices = |demux_ids: &[DemuxId]| -> Vec<PeekDeviceInfo> {
+                demux_ids
+                    .iter()
+                    .map(|demux_id| PeekDeviceInfo {
+                        demux_id: *demux_id,
+                        user_id: Some(vec![*demux_id as u8]),
+                        participant_kind: ParticipantKind::AppUser,
+                    })
+                    .collect()
+            };
+            for demux_id in self.joined.iter().chain(&self.pending) {
+                let client = self
+                    .clients
+                    .iter()
+                    .find(|client| client.demux_id == *demux_id)
+                    .expect("simulated client");
+                client.observer.set_recipients(joined.clone());
+                client.client.set_peek_result(Ok(PeekInfo {
+                    devices: to_devices(&self.joined),
+                    pending_devices: to_devices(&self.pending),
+                    ..client.default_peek_info.clone()
+                }));
+                client.set_up_rtp_with_remotes(joined.clone());
+            }
+            self.wait_for_clients_to_process();
+        }
+
+        /// Waits twice, so that anything one client sent to another, such as a media key, has
+        /// been handled too.
+        fn wait_for_clients_to_process(&self) {
+            for _ in 0..2 {
+                for client in &self.clients {
+                    client.wait_for_client_to_process();
+                }
+            }
+        }
+
+        /// Whether `receiver` can decrypt what `sender` encrypts now.
+        fn can_decrypt(&mut self, receiver: DemuxId, sender: DemuxId) -> bool {
+            let plaintext = &b"Fake Audio"[..];
+            let ciphertext = self.client(sender).encrypt_media(plaintext).unwrap();
+            self.client(receiver)
+                .decrypt_media(sender, &ciphertext)
+                .is_ok_and(|decrypted| decrypted == plaintext)
+        }
+
+        fn connection_state(&self, demux_id: DemuxId) -> ConnectionState {
+            let (sender, receiver) = mpsc::channel();
+            self.clients
+                .iter()
+                .find(|client| client.demux_id == demux_id)
+                .expect("simulated client")
+                .client
+                .actor
+                .send(move |state| {
+                    let _ = sender.send(state.connection_state);
+                });
+            receiver
+                .recv_timeout(Duration::from_secs(5))
+                .expect("connection state")
+        }
+
+        fn end(self) {
+            for client in &self.clients {
+                client.disconnect_and_wait_until_ended();
+            }
+        }
+    }
+
     #[test]
     fn frame_encryption_normal() {
         let mut client1 = TestClient::new(vec![1], 1);
@@ -5771,6 +7483,104 @@ mod tests {
         client5.disconnect_and_wait_until_ended();
     }
 
+    #[test]
+    fn simulated_key_rotation_after_leave() {
+        use SimStep::*;
+
+        let key_rotation_delay = Duration::from_secs(MEDIA_SEND_KEY_ROTATION_DELAY_SECS);
+        let mut call = SimulatedCall::new(3);
+        call.run(&[Join(1), Join(2), Join(3)]);
+        assert!(call.can_decrypt(2, 1));
+        assert!(call.can_decrypt(3, 1));
+
+        // 3 can keep decrypting until the new key is applied.
+        call.run(&[Leave(3), Advance(key_rotation_delay / 2)]);
+        assert!(call.can_decrypt(2, 1));
+        assert!(call.can_decrypt(3, 1));
+
+        call.run(&[Advance(key_rotation_delay / 2)]);
+        assert!(call.can_decrypt(2, 1));
+        assert!(!call.can_decrypt(3, 1));
+
+        call.end();
+    }
+
+    #[test]
+    fn simulated_join_while_key_rotation_pending() {
+        use SimStep::*;
+
+        let key_rotation_delay = Duration::from_secs(MEDIA_SEND_KEY_ROTATION_DELAY_SECS);
+        let mut call = SimulatedCall::new(4);
+        call.run(&[Join(1), Join(2), Join(3)]);
+
+        // 4 joins after 1 generated a new key but before it's applied, so it needs both.
+        // Meanwhile 2 leaves, which needs another rotation once this one is applied.
+        call.run(&[Leave(3), Advance(key_rotation_delay / 2), Join(4), Leave(2)]);
+        assert!(call.can_decrypt(4, 1));
+
+        call.run(&[Advance(key_rotation_delay / 2)]);
+        assert!(call.can_decrypt(2, 1));
+        assert!(!call.can_decrypt(3, 1));
+        assert!(call.can_decrypt(4, 1));
+
+        call.run(&[Advance(key_rotation_delay)]);
+        assert!(!call.can_decrypt(2, 1));
+        assert!(call.can_decrypt(4, 1));
+
+        call.end();
+    }
+
+    #[test]
+    fn simulated_admission() {
+        use SimStep::*;
+
+        let mut call = SimulatedCall::new(2);
+        call.run(&[Join(1), RequestToJoin(2)]);
+        assert!(!call.can_decrypt(2, 1));
+
+        call.run(&[Admit(2)]);
+        assert!(call.can_decrypt(2, 1));
+        assert!(call.can_decrypt(1, 2));
+
+        call.end();
+    }
+
+    #[test]
+    fn simulated_reconnection() {
+        use SimStep::*;
+
+        let mut call = SimulatedCall::new(2);
+        call.run(&[
+            Join(1),
+            Join(2),
+            Ice(1, IceConnectionState::Checking),
+            Ice(1, IceConnectionState::Connected),
+        ]);
+        assert_eq!(ConnectionState::Connected, call.connection_state(1));
+
+        call.run(&[Ice(1, IceConnectionState::Disconnected)]);
+        assert_eq!(ConnectionState::Reconnecting, call.connection_state(1));
+
+        // Keys are kept through reconnecting.
+        call.run(&[
+            Advance(Duration::from_secs(30)),
+            Ice(1, IceConnectionState::Connected),
+        ]);
+        assert_eq!(ConnectionState::Connected, call.connection_state(1));
+        assert!(call.can_decrypt(2, 1));
+        assert!(call.can_decrypt(1, 2));
+
+        call.run(&[
+            Ice(1, IceConnectionState::Disconnected),
+            Ice(1, IceConnectionState::Failed),
+        ]);
+        assert_eq!(
+            Some(EndReason::IceFailedAfterConnected),
+            call.client(1).observer.ended.wait(Duration::from_secs(5))
+        );
+        call.client(2).disconnect_and_wait_until_ended();
+    }
+
     #[test]
     fn frame_encryption_resend_keys() {
         let mut client1 = TestClient::new(vec![1], 1);
@@ -6070,6 +7880,177 @@ mod tests {
         assert_eq!(0, client2.observer.send_signaling_message_to_group_invocation_count());
     }
 
+    #[test]
+    fn batched_key_rotation_policy() {
+        let client1 = TestClient::new(vec![1], 1);
+        client1.connect_join_and_wait_until_joined();
+        client1
+            .client
+            .set_key_rotation_policy(KeyRotationPolicy::Batched(Duration::from_millis(500)));
+        let client2 = TestClient::new(vec![2], 2);
+        client2.connect_join_and_wait_until_joined();
+        let client3 = TestClient::new(vec![3], 3);
+        client3.connect_join_and_wait_until_joined();
+        let client4 = TestClient::new(vec![4], 4);
+        client4.connect_join_and_wait_until_joined();
+        set_group_and_wait_until_applied(&[&client1, &client2, &client3, &client4]);
+
+        // Clear the counts from sending keys to the new clients.
+        client1.observer.send_signaling_message_invocation_count();
+        client1
+            .observer
+            .send_signaling_message_to_group_invocation_count();
+
+        // Neither departure rotates the key right away.
+        client3.disconnect_and_wait_until_ended();
+        client1.set_remotes_and_wait_until_applied(&[&client1, &client2, &client4]);
+        client4.disconnect_and_wait_until_ended();
+        client1.set_remotes_and_wait_until_applied(&[&client1, &client2]);
+        assert_eq!(
+            0,
+            client1.observer.send_signaling_message_invocation_count()
+        );
+        assert_eq!(
+            0,
+            client1
+                .observer
+                .send_signaling_message_to_group_invocation_count()
+        );
+
+        // Both departures are covered by a single rotation once the batch is done.
+        client1.client.advance_clock(Duration::from_millis(600));
+        client1.wait_for_client_to_process();
+        assert_eq!(
+            1,
+            client1.observer.send_signaling_message_invocation_count()
+        );
+        assert_eq!(
+            0,
+            client1
+                .observer
+                .send_signaling_message_to_group_invocation_count()
+        );
+
+        client1.disconnect_and_wait_until_ended();
+        client2.disconnect_and_wait_until_ended();
+    }
+
+    #[test]
+    fn debounced_key_rotation_policy_is_capped() {
+        let client1 = TestClient::new(vec![1], 1);
+        client1.connect_join_and_wait_until_joined();
+        client1
+            .client
+            .set_key_rotation_policy(KeyRotationPolicy::Debounced(Duration::from_secs(20)));
+        let client2 = TestClient::new(vec![2], 2);
+        client2.connect_join_and_wait_until_joined();
+        let client3 = TestClient::new(vec![3], 3);
+        client3.connect_join_and_wait_until_joined();
+        let client4 = TestClient::new(vec![4], 4);
+        client4.connect_join_and_wait_until_joined();
+        set_group_and_wait_until_applied(&[&client1, &client2, &client3, &client4]);
+
+        // Clear the counts from sending keys to the new clients.
+        client1.observer.send_signaling_message_invocation_count();
+        client1
+            .observer
+            .send_signaling_message_to_group_invocation_count();
+
+        // The second departure would push the rotation back to 35s after the first,
+        // but it's capped at MAX_DEBOUNCED_KEY_ROTATION_DELAY (30s).
+        client3.disconnect_and_wait_until_ended();
+        client1.set_remotes_and_wait_until_applied(&[&client1, &client2, &client4]);
+        client1.client.advance_clock(Duration::from_secs(15));
+        client4.disconnect_and_wait_until_ended();
+        client1.set_remotes_and_wait_until_applied(&[&client1, &client2]);
+        client1.client.advance_clock(Duration::from_secs(14));
+        client1.wait_for_client_to_process();
+        assert_eq!(
+            0,
+            client1.observer.send_signaling_message_invocation_count()
+        );
+
+        client1.client.advance_clock(Duration::from_secs(2));
+        client1.wait_for_client_to_process();
+        assert_eq!(
+            1,
+            client1.observer.send_signaling_message_invocation_count()
+        );
+        assert_eq!(
+            0,
+            client1
+                .observer
+                .send_signaling_message_to_group_invocation_count()
+        );
+
+        client1.disconnect_and_wait_until_ended();
+        client2.disconnect_and_wait_until_ended();
+    }
+
+    #[test]
+    fn ice_servers_are_refreshed_before_they_expire() {
+        let client1 = TestClient::new(vec![1], 1);
+        client1.connect_join_and_wait_until_joined();
+
+        client1
+            .client
+            .set_ice_servers(vec![IceServer::none()], Some(Duration::from_secs(600)));
+        client1.client.advance_clock(Duration::from_secs(500));
+        client1.wait_for_client_to_process();
+        assert!(!client1.observer.ice_servers_requested.wait(Duration::ZERO));
+
+        client1.client.advance_clock(Duration::from_secs(50));
+        assert!(client1
+            .observer
+            .ice_servers_requested
+            .wait(Duration::from_secs(5)));
+
+        // Only requested once per expiry.
+        client1.client.advance_clock(Duration::from_secs(5));
+        client1.wait_for_client_to_process();
+        assert!(!client1.observer.ice_servers_requested.wait(Duration::ZERO));
+
+        // Fresh servers push the expiry back without reconnecting.
+        client1
+            .client
+            .set_ice_servers(vec![IceServer::none()], Some(Duration::from_secs(600)));
+        client1.client.advance_clock(Duration::from_secs(100));
+        client1.wait_for_client_to_process();
+        assert!(!client1
+            .observer
+            .ice_servers_refresh_failed
+            .wait(Duration::ZERO));
+        assert!(client1.observer.ended.wait(Duration::ZERO).is_none());
+
+        client1.disconnect_and_wait_until_ended();
+    }
+
+    #[test]
+    fn ice_servers_expiring_without_refresh_is_reported() {
+        let client1 = TestClient::new(vec![1], 1);
+        client1.connect_join_and_wait_until_joined();
+
+        client1
+            .client
+            .set_ice_servers(vec![IceServer::none()], Some(Duration::from_secs(600)));
+        client1.client.advance_clock(Duration::from_secs(600));
+        assert!(client1
+            .observer
+            .ice_servers_refresh_failed
+            .wait(Duration::from_secs(5)));
+
+        // Reported once, and the call carries on.
+        client1.client.advance_clock(Duration::from_secs(600));
+        client1.wait_for_client_to_process();
+        assert!(!client1
+            .observer
+            .ice_servers_refresh_failed
+            .wait(Duration::ZERO));
+        assert!(client1.observer.ended.wait(Duration::ZERO).is_none());
+
+        client1.disconnect_and_wait_until_ended();
+    }
+
     #[test]
     fn remote_heartbeat_state() {
         let client1 = TestClient::new(vec![1], 1);
@@ -6168,6 +8149,30 @@ mod tests {
         assert_eq!(1, client2.observer.reactions()[0].demux_id)
     }
 
+    #[test]
+    fn app_data() {
+        let client1 = TestClient::new(vec![1], 1);
+        client1.connect_join_and_wait_until_joined();
+
+        let client2 = TestClient::new(vec![2], 2);
+        client2.connect_join_and_wait_until_joined();
+
+        set_group_and_wait_until_applied(&[&client1, &client2]);
+
+        client1.client.send_app_data(b"caption".to_vec());
+        assert_eq!(
+            Some((1, b"caption".to_vec())),
+            client2.observer.app_data.wait(Duration::from_secs(5))
+        );
+
+        // Too large to send, so nothing arrives.
+        client1.client.send_app_data(vec![0; MAX_APP_DATA_LEN + 1]);
+        assert_eq!(
+            None,
+            client2.observer.app_data.wait(Duration::from_millis(200))
+        );
+    }
+
     #[test]
     fn ignore_devices_that_arent_members() {
         let client = TestClient::new(vec![1], 1);
@@ -6180,10 +8185,12 @@ mod tests {
                 PeekDeviceInfo {
                     demux_id: 2,
                     user_id: Some(b"2".to_vec()),
+                    participant_kind: ParticipantKind::AppUser,
                 },
                 PeekDeviceInfo {
                     demux_id: 3,
                     user_id: None,
+                    participant_kind: ParticipantKind::AppUser,
                 },
             ],
             pending_devices: vec![],
@@ -6217,6 +8224,7 @@ mod tests {
             devices: vec![PeekDeviceInfo {
                 demux_id: 1,
                 user_id: Some(b"1".to_vec()),
+                participant_kind: ParticipantKind::AppUser,
             }],
             pending_devices: vec![],
             creator: None,
@@ -6431,6 +8439,31 @@ mod tests {
         client1.disconnect_and_wait_until_ended();
     }
 
+    #[test]
+    fn configurable_peek_poll_interval() {
+        let intervals = GroupCallIntervals {
+            peek_poll: Duration::from_millis(400),
+            ..Default::default()
+        };
+        let fast_client = TestClient::with_intervals(vec![1], 1, intervals);
+        let default_client = TestClient::new(vec![2], 2);
+
+        for client in [&fast_client, &default_client] {
+            client.client.set_membership_proof(b"proof".to_vec());
+            client.connect_join_and_wait_until_joined();
+            client.set_remotes_and_wait_until_applied(&[]);
+        }
+        let fast_count = fast_client.sfu_client.request_count();
+        let default_count = default_client.sfu_client.request_count();
+
+        std::thread::sleep(Duration::from_millis(700));
+        assert_eq!(fast_count + 1, fast_client.sfu_client.request_count());
+        assert_eq!(default_count, default_client.sfu_client.request_count());
+
+        fast_client.disconnect_and_wait_until_ended();
+        default_client.disconnect_and_wait_until_ended();
+    }
+
     #[test]
     #[ignore]
     fn polling_error_handling() {
@@ -6631,6 +8664,78 @@ mod tests {
         client1.disconnect_and_wait_until_ended();
     }
 
+    #[test]
+    fn rendering_paused() {
+        use protobuf::group_call::DeviceToSfu;
+
+        let mut client1 = TestClient::new(vec![1], 1);
+        let client2 = TestClient::new(vec![2], 2);
+        let client3 = TestClient::new(vec![3], 3);
+
+        let (sender, receiver) = mpsc::channel();
+        client1.sfu_rtp_packet_sender = Some(sender);
+        client1.connect_join_and_wait_until_joined();
+        client1.set_remotes_and_wait_until_applied(&[&client2, &client3]);
+
+        let requests = vec![
+            VideoRequest {
+                demux_id: 2,
+                width: 640,
+                height: 480,
+                framerate: None,
+            },
+            VideoRequest {
+                demux_id: 3,
+                width: 640,
+                height: 480,
+                framerate: None,
+            },
+        ];
+        client1.client.request_video(requests, 480);
+
+        // Video requests are also sent periodically, so skip any that were sent before the
+        // last change was applied.
+        let wait_for_requested_heights =
+            |expected_heights: &[(DemuxId, u32)], expected_active_speaker_height: u32| {
+                let deadline = Instant::now() + Duration::from_secs(3);
+                loop {
+                    let (_header, payload) = receiver
+                        .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                        .expect("Get video request matching the expected heights");
+                    let Some(video_request) = DeviceToSfu::decode(&payload[..])
+                        .ok()
+                        .and_then(|msg| msg.video_request)
+                    else {
+                        continue;
+                    };
+                    let heights: Vec<_> = video_request
+                        .requests
+                        .iter()
+                        .map(|request| (request.demux_id.unwrap(), request.height.unwrap()))
+                        .collect();
+                    if heights == expected_heights
+                        && video_request.active_speaker_height
+                            == Some(expected_active_speaker_height)
+                    {
+                        return;
+                    }
+                }
+            };
+        wait_for_requested_heights(&[(2, 480), (3, 480)], 480);
+
+        client1.client.set_rendering_paused(vec![2], true);
+        wait_for_requested_heights(&[(2, 0), (3, 480)], 480);
+
+        client1.client.set_all_rendering_paused(true);
+        wait_for_requested_heights(&[(2, 0), (3, 0)], 0);
+
+        // Resuming everything resumes the individually paused video too.
+        client1.client.set_all_rendering_paused(false);
+        wait_for_requested_heights(&[(2, 480), (3, 480)], 480);
+
+        client1.disconnect_and_wait_until_ended();
+    }
+
     #[test]
     fn audio_level_polling() {
         let client1 = TestClient::new(vec![1], 1);
@@ -6740,6 +8845,133 @@ mod tests {
         client1.disconnect_and_wait_until_ended();
     }
 
+    #[test]
+    fn device_to_sfu_lower_all_hands() {
+        use protobuf::group_call::{
+            device_to_sfu::{AdminAction, LowerAllHands},
+            DeviceToSfu,
+        };
+
+        let mut client1 = TestClient::new(vec![1], 1);
+
+        let (sender, receiver) = mpsc::channel();
+        client1.sfu_rtp_packet_sender = Some(sender);
+        client1.connect_join_and_wait_until_joined();
+        client1.set_remotes_and_wait_until_applied(&[]);
+        client1.client.lower_all_hands();
+
+        let (header, payload) = receiver
+            .recv_timeout(Duration::from_secs(1))
+            .expect("Get RTP packet to SFU");
+        assert_eq!(1, header.ssrc);
+        assert_eq!(
+            DeviceToSfu {
+                admin_action: Some(AdminAction::LowerAllHands(LowerAllHands {})),
+                mrp_header: Some(MrpHeader {
+                    seqnum: Some(1),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            DeviceToSfu::decode(&payload[..]).unwrap()
+        );
+
+        client1.disconnect_and_wait_until_ended();
+    }
+
+    #[test]
+    fn device_to_sfu_assign_breakout_groups() {
+        use protobuf::group_call::{
+            device_to_sfu::{AdminAction, AssignBreakoutGroups},
+            DeviceToSfu,
+        };
+
+        let mut client1 = TestClient::new(vec![1], 1);
+
+        let (sender, receiver) = mpsc::channel();
+        client1.sfu_rtp_packet_sender = Some(sender);
+        client1.connect_join_and_wait_until_joined();
+        client1.set_remotes_and_wait_until_applied(&[]);
+        client1.client.assign_breakout_groups(vec![(2, 1), (3, 0)]);
+
+        let (header, payload) = receiver
+            .recv_timeout(Duration::from_secs(1))
+            .expect("Get RTP packet to SFU");
+        assert_eq!(1, header.ssrc);
+        assert_eq!(
+            DeviceToSfu {
+                admin_action: Some(AdminAction::AssignBreakoutGroups(AssignBreakoutGroups {
+                    demux_ids: vec![2, 3],
+                    breakout_group_ids: vec![1, 0],
+                })),
+                mrp_header: Some(MrpHeader {
+                    seqnum: Some(1),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            DeviceToSfu::decode(&payload[..]).unwrap()
+        );
+
+        client1.disconnect_and_wait_until_ended();
+    }
+
+    #[test]
+    fn breakout_group_changed() {
+        let client1 = TestClient::new(vec![1], 1);
+        let client2 = TestClient::new(vec![2], 2);
+        client1.connect_join_and_wait_until_joined();
+        client2.connect_join_and_wait_until_joined();
+        set_group_and_wait_until_applied(&[&client1, &client2]);
+
+        // Moving someone else doesn't change the local breakout group.
+        Client::handle_breakout_groups(&client1.client.actor, vec![2], vec![7]);
+        client1.wait_for_client_to_process();
+        assert_eq!(
+            None,
+            client1.observer.breakout_group_changed.wait(Duration::ZERO)
+        );
+
+        Client::handle_breakout_groups(&client1.client.actor, vec![1, 2], vec![5, 7]);
+        assert_eq!(
+            Some(Some(5)),
+            client1
+                .observer
+                .breakout_group_changed
+                .wait(Duration::from_secs(5))
+        );
+
+        // Group 0 is the main call.
+        Client::handle_breakout_groups(&client1.client.actor, vec![1, 2], vec![0, 7]);
+        assert_eq!(
+            Some(None),
+            client1
+                .observer
+                .breakout_group_changed
+                .wait(Duration::from_secs(5))
+        );
+
+        client1.disconnect_and_wait_until_ended();
+        client2.disconnect_and_wait_until_ended();
+    }
+
+    #[test]
+    fn breakout_groups_isolate_media() {
+        use SimStep::*;
+
+        let key_rotation_delay = Duration::from_secs(MEDIA_SEND_KEY_ROTATION_DELAY_SECS);
+        let mut call = SimulatedCall::new(3);
+        call.run(&[Join(1), Join(2), Join(3)]);
+        assert!(call.can_decrypt(3, 1));
+
+        // 1 and 2 move to a breakout group; 3 stays in the main call.
+        call.run(&[Breakout(&[(1, 5), (2, 5)]), Advance(key_rotation_delay)]);
+        assert!(call.can_decrypt(2, 1));
+        assert!(!call.can_decrypt(3, 1));
+
+        call.end();
+    }
+
     #[test]
     fn device_to_sfu_approve() {
         use protobuf::group_call::{
@@ -6960,6 +9192,159 @@ mod tests {
             Some(EndReason::HasMaxDevices),
             client3.observer.ended.wait(Duration::from_secs(5))
         );
+        // Without peeking, how full the call is isn't known.
+        assert_eq!(
+            Some((None, None)),
+            client3.observer.call_full.wait(Duration::from_secs(5))
+        );
+    }
+
+    #[test]
+    fn full_call_after_peeking() {
+        let client1 = TestClient::new(vec![1], 1);
+        client1.client.connect();
+        client1.client.set_peek_result(Ok(PeekInfo {
+            devices: vec![
+                PeekDeviceInfo {
+                    demux_id: 2,
+                    user_id: Some(vec![2]),
+                    participant_kind: ParticipantKind::AppUser,
+                },
+                PeekDeviceInfo {
+                    demux_id: 3,
+                    user_id: Some(vec![3]),
+                    participant_kind: ParticipantKind::AppUser,
+                },
+            ],
+            max_devices: Some(2),
+            pending_devices: vec![],
+            creator: None,
+            era_id: None,
+            call_link_state: None,
+        }));
+        client1.client.join();
+
+        // The join is refused without asking the SFU.
+        assert_eq!(
+            Some(EndReason::HasMaxDevices),
+            client1.observer.ended.wait(Duration::from_secs(5))
+        );
+        assert_eq!(
+            Some((Some(2), Some(2))),
+            client1.observer.call_full.wait(Duration::from_secs(5))
+        );
+        assert!(!client1.observer.joined.wait(Duration::ZERO));
+    }
+
+    #[test]
+    fn own_device_joined() {
+        let client1a = TestClient::new(vec![1], 11);
+        let client1b = TestClient::new(vec![1], 12);
+        let client2 = TestClient::new(vec![2], 21);
+
+        client1a.connect_join_and_wait_until_joined();
+        client1a.set_remotes_and_wait_until_applied(&[&client1a]);
+        // A device of another user joining doesn't count.
+        client1a.set_remotes_and_wait_until_applied(&[&client1a, &client2]);
+        assert_eq!(
+            None,
+            client1a.observer.own_device_joined.wait(Duration::ZERO)
+        );
+
+        client1a.set_remotes_and_wait_until_applied(&[&client1a, &client2, &client1b]);
+        assert_eq!(
+            Some(12),
+            client1a
+                .observer
+                .own_device_joined
+                .wait(Duration::from_secs(5))
+        );
+        // Without opting in, the local device stays in the call.
+        assert!(!client1a.observer.left.wait(Duration::ZERO));
+
+        client1a.disconnect_and_wait_until_ended();
+    }
+
+    #[test]
+    fn leave_when_own_device_joins() {
+        let client1a = TestClient::new(vec![1], 11);
+        let client1b = TestClient::new(vec![1], 12);
+
+        client1a.connect_join_and_wait_until_joined();
+        client1a.client.set_leave_when_own_device_joins(true);
+        // Devices already in the call when joining don't count.
+        client1a.set_remotes_and_wait_until_applied(&[&client1a, &client1b]);
+        assert_eq!(
+            None,
+            client1a.observer.own_device_joined.wait(Duration::ZERO)
+        );
+
+        let client1c = TestClient::new(vec![1], 13);
+        client1a.set_remotes_and_wait_until_applied(&[&client1a, &client1b, &client1c]);
+        assert_eq!(
+            Some(13),
+            client1a
+                .observer
+                .own_device_joined
+                .wait(Duration::from_secs(5))
+        );
+        assert!(client1a.observer.left.wait(Duration::from_secs(5)));
+
+        client1a.disconnect_and_wait_until_ended();
+    }
+
+    #[test]
+    fn skip_media_keys_for_bridged_participants() {
+        let mut client1 = TestClient::new(vec![1], 1);
+        let mut client2 = TestClient::new(vec![2], 2);
+        let mut client3 = TestClient::new(vec![3], 3);
+        client1.connect_join_and_wait_until_joined();
+        client2.connect_join_and_wait_until_joined();
+        client3.connect_join_and_wait_until_joined();
+        client2.set_remotes_and_wait_until_applied(&[&client1, &client3]);
+        client3.set_remotes_and_wait_until_applied(&[&client1, &client2]);
+
+        // client3 is in the call through a bridge.
+        client1
+            .client
+            .set_skip_media_keys_for_bridged_participants(true);
+        client1
+            .observer
+            .set_recipients(vec![client2.clone(), client3.clone()]);
+        client1.client.set_peek_result(Ok(PeekInfo {
+            devices: vec![
+                PeekDeviceInfo {
+                    demux_id: client2.demux_id,
+                    user_id: Some(client2.user_id.clone()),
+                    participant_kind: ParticipantKind::AppUser,
+                },
+                PeekDeviceInfo {
+                    demux_id: client3.demux_id,
+                    user_id: Some(client3.user_id.clone()),
+                    participant_kind: ParticipantKind::Bridged,
+                },
+            ],
+            ..client1.default_peek_info.clone()
+        }));
+        client1.wait_for_client_to_process();
+        client2.wait_for_client_to_process();
+        client3.wait_for_client_to_process();
+
+        let plaintext = &b"Fake Audio"[..];
+        let ciphertext = client1.encrypt_media(plaintext).unwrap();
+        assert_eq!(
+            plaintext,
+            client2
+                .decrypt_media(client1.demux_id, &ciphertext)
+                .unwrap()
+        );
+        assert!(client3
+            .decrypt_media(client1.demux_id, &ciphertext)
+            .is_err());
+
+        client1.disconnect_and_wait_until_ended();
+        client2.disconnect_and_wait_until_ended();
+        client3.disconnect_and_wait_until_ended();
     }
 
     #[test]
@@ -6970,6 +9355,7 @@ mod tests {
             devices: vec![PeekDeviceInfo {
                 demux_id: 2,
                 user_id: None,
+                participant_kind: ParticipantKind::AppUser,
             }],
             max_devices: Some(2),
             pending_devices: vec![],
@@ -7320,6 +9706,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn call_summary_on_end() {
+        let client1 = TestClient::new(vec![1], 1);
+        let client2 = TestClient::new(vec![2], 2);
+        let client3 = TestClient::new(vec![3], 3);
+        client1.connect_join_and_wait_until_joined();
+        client1.set_remotes_and_wait_until_applied(&[&client1, &client2, &client3]);
+        client1.set_remotes_and_wait_until_applied(&[&client1, &client2]);
+        client1.disconnect_and_wait_until_ended();
+
+        let (summary, reason) = client1
+            .observer
+            .call_summary
+            .wait(Duration::from_secs(5))
+            .expect("summary before ended");
+        assert_eq!(EndReason::DeviceExplicitlyDisconnected, reason);
+        assert_eq!(3, summary.max_participant_count);
+        assert!(summary.duration > Duration::ZERO);
+    }
+
     #[test]
     fn send_rates() {
         init_logging();
@@ -7340,6 +9746,7 @@ mod tests {
                 PeekDeviceInfo {
                     demux_id,
                     user_id: Some(user_id.as_bytes().to_vec()),
+                    participant_kind: ParticipantKind::AppUser,
                 }
             })
             .collect();
@@ -7902,6 +10309,7 @@ mod tests {
 #[cfg(test)]
 mod remote_devices_tests {
     use super::*;
+    use crate::webrtc::stats_observer::InboundStreamStats;
 
     #[test]
     fn latest_speaker_of_empty_devices() {
@@ -7990,13 +10398,171 @@ mod remote_devices_tests {
         );
     }
 
+    fn receive_quality_sample(
+        ssrc: u32,
+        packets_received: u32,
+        packets_lost: i32,
+        jitter: f64,
+    ) -> ReceiveQualitySample {
+        ReceiveQualitySample {
+            ssrc,
+            is_video: false,
+            packets_received,
+            packets_lost,
+            jitter,
+            video_frozen: false,
+        }
+    }
+
+    #[test]
+    fn remote_connection_qualities_rate_by_worst_stream() {
+        let remote_devices = RemoteDevices::from_iter(vec![
+            remote_device_state(16, None),
+            remote_device_state(32, None),
+            remote_device_state(48, None),
+        ]);
+        let samples = vec![
+            // Good audio, lossy video
+            receive_quality_sample(16, 500, 0, 0.01),
+            receive_quality_sample(18, 900, 100, 0.0),
+            // Jittery audio
+            receive_quality_sample(32, 500, 0, 0.05),
+            // Good audio, frozen video
+            receive_quality_sample(48, 500, 1, 0.01),
+            ReceiveQualitySample {
+                is_video: true,
+                video_frozen: true,
+                ..receive_quality_sample(50, 100, 0, 0.0)
+            },
+            // Unknown device
+            receive_quality_sample(64, 500, 500, 0.5),
+        ];
+        assert_eq!(
+            vec![
+                RemoteConnectionQuality {
+                    demux_id: 16,
+                    quality: ConnectionQuality::Poor
+                },
+                RemoteConnectionQuality {
+                    demux_id: 32,
+                    quality: ConnectionQuality::Fair
+                },
+                RemoteConnectionQuality {
+                    demux_id: 48,
+                    quality: ConnectionQuality::Poor
+                },
+            ],
+            Client::compute_remote_connection_qualities(&samples, &remote_devices)
+        );
+    }
+
+    #[test]
+    fn remote_connection_qualities_skip_idle_streams() {
+        let remote_devices = RemoteDevices::from_iter(vec![remote_device_state(16, None)]);
+        let samples = vec![receive_quality_sample(16, 0, 0, 0.5)];
+        assert_eq!(
+            Vec::<RemoteConnectionQuality>::new(),
+            Client::compute_remote_connection_qualities(&samples, &remote_devices)
+        );
+
+        let samples = vec![
+            receive_quality_sample(16, 0, 0, 0.5),
+            receive_quality_sample(18, 1000, 0, 0.0),
+        ];
+        assert_eq!(
+            vec![RemoteConnectionQuality {
+                demux_id: 16,
+                quality: ConnectionQuality::Good
+            }],
+            Client::compute_remote_connection_qualities(&samples, &remote_devices)
+        );
+    }
+
+    fn inbound_stream_stats(ssrc: u32, estimated_playout_timestamp: f64) -> InboundStreamStats {
+        InboundStreamStats {
+            ssrc,
+            packets_per_second: 50.0,
+            estimated_playout_timestamp: Some(estimated_playout_timestamp),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn av_sync_pairs_streams_by_remote_device() {
+        let remote_devices = RemoteDevices::from_iter(vec![
+            remote_device_state(16, None),
+            remote_device_state(32, None),
+            remote_device_state(48, None),
+        ]);
+        let snapshot = StatsSnapshot {
+            audio_recv: vec![
+                inbound_stream_stats(32, 100.0),
+                inbound_stream_stats(16, 100.0),
+                // No video
+                inbound_stream_stats(48, 100.0),
+                // Unknown device
+                inbound_stream_stats(64, 100.0),
+            ],
+            video_recv: vec![
+                // A layer that isn't being forwarded
+                InboundStreamStats {
+                    packets_per_second: 0.0,
+                    ..inbound_stream_stats(17, 50.0)
+                },
+                inbound_stream_stats(19, 99.95),
+                inbound_stream_stats(33, 100.5),
+                inbound_stream_stats(65, 0.0),
+            ],
+            ..Default::default()
+        };
+        let av_sync = Client::compute_av_sync(&snapshot, &remote_devices);
+        assert_eq!(2, av_sync.len());
+        assert_eq!((16, 19), (av_sync[0].audio_ssrc, av_sync[0].video_ssrc));
+        assert!((av_sync[0].skew - 0.05).abs() < 1e-9);
+        assert_eq!((32, 33), (av_sync[1].audio_ssrc, av_sync[1].video_ssrc));
+        assert_eq!(-0.5, av_sync[1].skew);
+
+        assert_eq!(
+            vec![RemoteAvSyncSkew {
+                demux_id: 32,
+                skew: -0.5
+            }],
+            Client::av_sync_skews_to_warn_about(&av_sync)
+        );
+    }
+
+    #[test]
+    fn e2ee_health() {
+        let start = Instant::now();
+        let mut counts = DecryptionCounts::new(start);
+        assert_eq!(None, counts.take(start + Duration::from_secs(60)));
+
+        for (demux_id, decrypted) in [(32, true), (48, false), (16, false), (48, false)] {
+            counts.record(demux_id, decrypted);
+        }
+        assert_eq!(
+            Some(E2eeHealth {
+                window: Duration::from_secs(30),
+                frames_decrypted: 1,
+                frames_failed: 3,
+                failures_by_sender: vec![(16, 1), (48, 2)],
+            }),
+            counts.take(start + Duration::from_secs(90))
+        );
+        assert_eq!(None, counts.take(start + Duration::from_secs(150)));
+    }
+
     fn time(timestamp: u64) -> SystemTime {
         SystemTime::UNIX_EPOCH + Duration::from_secs(timestamp)
     }
 
     fn remote_device_state(id: u32, spoken_at: Option<SystemTime>) -> RemoteDeviceState {
-        let mut remote_device_state =
-            RemoteDeviceState::new(id, id.to_be_bytes().to_vec(), time(1));
+        let mut remote_device_state = RemoteDeviceState::new(
+            id,
+            id.to_be_bytes().to_vec(),
+            ParticipantKind::AppUser,
+            time(1),
+        );
 
         remote_device_state.speaker_time = spoken_at;
 
@@ -0,0 +1,305 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Logging shared by every platform.
+//!
+//! Each platform has its own [`log::Log`] that hands messages to the app, but they should all be
+//! wrapped in a [`RedactingLogger`] when registered, so that no module has to remember to redact
+//! what it logs.
+
+use std::{
+    borrow::Cow,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        RwLock,
+    },
+};
+
+use crate::core::util::redact_log_message;
+
+/// The max levels set at runtime, with overrides for particular modules.
+struct LevelFilters {
+    /// None until either the max level or a module's level is first set at runtime.
+    base: Option<log::LevelFilter>,
+    modules: Vec<(String, log::LevelFilter)>,
+}
+
+impl LevelFilters {
+    /// The level of the most specific module override matching the target, or the base level.
+    ///
+    /// An override of a deeper module is more specific, as is a longer path to the same one.
+    fn level_for(&self, target: &str) -> log::LevelFilter {
+        self.modules
+            .iter()
+            .filter_map(|(module, level)| {
+                let end = module_end_in_target(target, module)?;
+                Some(((end, module.len()), *level))
+            })
+            .max_by_key(|(specificity, _)| *specificity)
+            .map(|(_, level)| level)
+            .or(self.base)
+            .unwrap_or_else(log::max_level)
+    }
+
+    /// The level to give [`log::set_max_level`] so that nothing any override allows is skipped.
+    fn max(&self) -> log::LevelFilter {
+        self.modules
+            .iter()
+            .map(|(_, level)| *level)
+            .chain(self.base)
+            .max()
+            .unwrap_or_else(log::max_level)
+    }
+}
+
+static LEVEL_FILTERS: RwLock<LevelFilters> = RwLock::new(LevelFilters {
+    base: None,
+    modules: Vec::new(),
+});
+/// Whether there are any module overrides, to skip the lock for every message when not.
+static HAS_MODULE_LEVELS: AtomicBool = AtomicBool::new(false);
+
+/// If a target such as "ringrtc::core::group_call" is in a module such as "group_call" or
+/// "core", or a path of them such as "core::group_call", including submodules, returns where
+/// the deepest match ends.
+fn module_end_in_target(target: &str, module: &str) -> Option<usize> {
+    if module.is_empty() {
+        return None;
+    }
+    target
+        .match_indices(module)
+        .map(|(start, _)| (start, start + module.len()))
+        .filter(|(start, end)| {
+            (*start == 0 || target[..*start].ends_with("::"))
+                && (*end == target.len() || target[*end..].starts_with("::"))
+        })
+        .map(|(_, end)| end)
+        .last()
+}
+
+/// Whether the runtime module overrides allow the message; everything else has already been
+/// filtered by [`log::max_level`].
+fn module_levels_allow(metadata: &log::Metadata) -> bool {
+    if !HAS_MODULE_LEVELS.load(Ordering::Relaxed) {
+        return true;
+    }
+    match LEVEL_FILTERS.read() {
+        Ok(filters) => metadata.level() <= filters.level_for(metadata.target()),
+        Err(_) => true,
+    }
+}
+
+/// Applies a change to the filters, then updates [`log::max_level`] to match.
+fn update_level_filters(update: impl FnOnce(&mut LevelFilters)) {
+    // Nothing may be logged while the lock is held, since the logger reads it.
+    if let Ok(mut filters) = LEVEL_FILTERS.write() {
+        filters.base.get_or_insert_with(log::max_level);
+        update(&mut filters);
+        HAS_MODULE_LEVELS.store(!filters.modules.is_empty(), Ordering::Relaxed);
+        log::set_max_level(filters.max());
+    }
+}
+
+/// Wraps a platform logger, redacting every message before passing it on.
+///
+/// See [`redact_log_message`] for what is redacted.
+pub struct RedactingLogger<L> {
+    inner: L,
+    // Only replaced by tests, since redact_log_message does nothing in debug builds.
+    redact: fn(&str) -> Cow<'_, str>,
+}
+
+impl<L: log::Log> RedactingLogger<L> {
+    pub const fn new(inner: L) -> Self {
+        Self {
+            inner,
+            redact: redact_message,
+        }
+    }
+}
+
+fn redact_message(text: &str) -> Cow<'_, str> {
+    redact_log_message(text)
+}
+
+impl<L: log::Log> log::Log for RedactingLogger<L> {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        module_levels_allow(metadata) && self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let message = record.args().to_string();
+        let message = (self.redact)(&message);
+        self.inner.log(
+            &log::Record::builder()
+                .args(format_args!("{}", message))
+                .metadata(record.metadata().clone())
+                .module_path(record.module_path())
+                .file(record.file())
+                .line(record.line())
+                .build(),
+        );
+    }
+
+    fn flush(&self) {
+        self.inner.flush()
+    }
+}
+
+/// Converts from the numbering used across the FFI boundaries, which matches
+/// [`log::LevelFilter`]: 0 for Off through 5 for Trace.
+pub fn level_filter_from_raw(raw: u8) -> Option<log::LevelFilter> {
+    log::LevelFilter::iter().find(|level| *level as u8 == raw)
+}
+
+/// Lowers a level set at runtime to [`log::STATIC_MAX_LEVEL`], since anything more verbose was
+/// compiled out. With the `release_max_level_info` feature of `log`, that's Info in release
+/// builds, so asking for Debug or Trace there gets Info.
+fn clamp_level(level: log::LevelFilter, static_max: log::LevelFilter) -> log::LevelFilter {
+    level.min(static_max)
+}
+
+/// Changes the most verbose level that gets logged, for apps that let users turn on more detailed
+/// logging without restarting. Release builds log Info at most, since more verbose messages are
+/// compiled out of them.
+///
+/// Platform loggers may filter further; on Android, for example, Trace is never logged.
+///
+/// Modules given their own level with [`set_module_log_level`] keep it.
+pub fn set_max_log_level(level: log::LevelFilter) {
+    let level = clamp_level(level, log::STATIC_MAX_LEVEL);
+    update_level_filters(|filters| filters.base = Some(level));
+    info!("Max log level set to {}", level);
+}
+
+/// Overrides the max level for one module, such as "group_call" or "http", including its
+/// submodules, so that support can turn on verbose logging of just the part of RingRTC an
+/// affected user is having trouble with.
+///
+/// The module can also be a path such as "core::group_call". When several overrides match, the
+/// one for the deepest module applies. As with [`set_max_log_level`], release builds log Info at
+/// most.
+pub fn set_module_log_level(module: &str, level: log::LevelFilter) {
+    let level = clamp_level(level, log::STATIC_MAX_LEVEL);
+    update_level_filters(
+        |filters| match filters.modules.iter_mut().find(|(m, _)| m == module) {
+            Some((_, existing)) => *existing = level,
+            None => filters.modules.push((module.to_string(), level)),
+        },
+    );
+    info!("Log level for module {} set to {}", module, level);
+}
+
+/// Removes every override from [`set_module_log_level`].
+pub fn clear_module_log_levels() {
+    update_level_filters(|filters| filters.modules.clear());
+    info!("Module log levels cleared");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::core::util::scrub_log_message;
+
+    #[derive(Default)]
+    struct Collector {
+        messages: Mutex<Vec<String>>,
+    }
+
+    impl log::Log for Collector {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.level() <= log::Level::Info
+        }
+
+        fn log(&self, record: &log::Record) {
+            self.messages
+                .lock()
+                .unwrap()
+                .push(record.args().to_string());
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn redacts_and_filters() {
+        let logger = RedactingLogger {
+            inner: Collector::default(),
+            redact: |text| scrub_log_message(text.into()),
+        };
+        let send = |level, message: &str| {
+            log::Log::log(
+                &logger,
+                &log::Record::builder()
+                    .level(level)
+                    .args(format_args!("{}", message))
+                    .build(),
+            )
+        };
+
+        send(log::Level::Info, "connecting to 192.168.1.1");
+        send(log::Level::Debug, "not logged");
+
+        let messages = logger.inner.messages.lock().unwrap();
+        assert_eq!(vec!["connecting to [REDACTED ipv4]"], *messages);
+    }
+
+    #[test]
+    fn module_levels() {
+        use log::LevelFilter::*;
+
+        let target = "ringrtc::core::group_call";
+        assert_eq!(
+            Some(target.len()),
+            module_end_in_target(target, "group_call")
+        );
+        assert_eq!(Some(13), module_end_in_target(target, "core"));
+        assert_eq!(
+            Some(target.len()),
+            module_end_in_target(target, "core::group_call")
+        );
+        assert_eq!(None, module_end_in_target(target, "call"));
+        assert_eq!(
+            None,
+            module_end_in_target("ringrtc::lite::http_client", "http")
+        );
+
+        let filters = LevelFilters {
+            base: Some(Info),
+            modules: vec![
+                ("group_call".to_string(), Debug),
+                ("core".to_string(), Warn),
+                ("lite".to_string(), Trace),
+                ("sfu".to_string(), Off),
+            ],
+        };
+        assert_eq!(Debug, filters.level_for("ringrtc::core::group_call"));
+        assert_eq!(Warn, filters.level_for("ringrtc::core::connection"));
+        assert_eq!(Trace, filters.level_for("ringrtc::lite::http"));
+        assert_eq!(Off, filters.level_for("ringrtc::lite::sfu"));
+        assert_eq!(Info, filters.level_for("ringrtc::electron"));
+        assert_eq!(Trace, filters.max());
+    }
+
+    #[test]
+    fn levels_clamped_to_static_max() {
+        use log::LevelFilter::*;
+
+        // As in release builds.
+        assert_eq!(Info, clamp_level(Trace, Info));
+        assert_eq!(Info, clamp_level(Debug, Info));
+        assert_eq!(Warn, clamp_level(Warn, Info));
+        assert_eq!(Off, clamp_level(Off, Info));
+
+        // As in debug builds.
+        assert_eq!(Trace, clamp_level(Trace, Trace));
+        assert!(clamp_level(Trace, log::STATIC_MAX_LEVEL) <= log::STATIC_MAX_LEVEL);
+    }
+}
@@ -10,7 +10,8 @@ use std::{fmt, time::Duration};
 
 use crate::{
     common::{
-        ApplicationEvent, CallConfig, CallDirection, CallId, CallMediaType, DeviceId, Result,
+        ApplicationEvent, CallConfig, CallDirection, CallId, CallMediaType, CallSummary, DeviceId,
+        GlareResolution, Result,
     },
     core::{
         call::Call,
@@ -27,6 +28,7 @@ use crate::{
         media::{MediaStream, VideoTrack},
         peer_connection::{AudioLevel, ReceivedAudioLevel},
         peer_connection_observer::NetworkRoute,
+        stats_observer::QualityLimitationReason,
     },
 };
 
@@ -61,12 +63,17 @@ pub trait Platform: sfu::Delegate + fmt::Debug + fmt::Display + Send + Sized + '
     ) -> Result<Connection<Self>>;
 
     /// Inform the client application that a call should be started.
+    ///
+    /// Like the other per-call notifications, this passes along the opaque
+    /// app_data attached with CallManager::set_call_app_data(), which is
+    /// empty if the application didn't attach any.
     fn on_start_call(
         &self,
         remote_peer: &Self::AppRemotePeer,
         call_id: CallId,
         direction: CallDirection,
         call_media_type: CallMediaType,
+        app_data: &[u8],
     ) -> Result<()>;
 
     /// Notify the client application about an event.
@@ -75,8 +82,38 @@ pub trait Platform: sfu::Delegate + fmt::Debug + fmt::Display + Send + Sized + '
         remote_peer: &Self::AppRemotePeer,
         call_id: CallId,
         event: ApplicationEvent,
+        app_data: &[u8],
     ) -> Result<()>;
 
+    /// Notify the client application of the totals for a call that is ending.
+    /// Called right before on_event() with the event that ends the call.
+    fn on_call_summary(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _call_id: CallId,
+        _summary: CallSummary,
+        _event: ApplicationEvent,
+        _app_data: &[u8],
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Notify the client application that a glare with the remote peer was resolved.
+    /// Called before any on_event() for the calls involved.
+    fn on_glare_resolved(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _resolution: GlareResolution,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Notify the client application that another device of the local user
+    /// asked this device to take over its accepted call, using take_over_call().
+    fn on_handoff_requested(&self, _request: signaling::HandoffRequest) -> Result<()> {
+        Ok(())
+    }
+
     /// Notify the client application that the network route has changed (1:1 calls)
     fn on_network_route_changed(
         &self,
@@ -99,6 +136,22 @@ pub trait Platform: sfu::Delegate + fmt::Debug + fmt::Display + Send + Sized + '
         recovered: bool,
     ) -> Result<()>;
 
+    /// Notify the client application that outgoing video started or stopped
+    /// being scaled down, and why. The reason is None once it stops.
+    fn on_video_adaptation_changed(
+        &self,
+        _remote_peer: &Self::AppRemotePeer,
+        _reason: Option<QualityLimitationReason>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Notify the client application of data sent by the remote peer with
+    /// send_app_data().
+    fn on_app_data(&self, _remote_peer: &Self::AppRemotePeer, _data: Vec<u8>) -> Result<()> {
+        Ok(())
+    }
+
     /// Send an offer to a remote peer using the signaling
     /// channel.  Offers are always broadcast to all devices.
     fn on_send_offer(
@@ -194,10 +247,16 @@ pub trait Platform: sfu::Delegate + fmt::Debug + fmt::Display + Send + Sized + '
         remote_peer: &Self::AppRemotePeer,
         call_id: CallId,
         age: Duration,
+        app_data: &[u8],
     ) -> Result<()>;
 
     /// Notify the application that the call is completely concluded
-    fn on_call_concluded(&self, remote_peer: &Self::AppRemotePeer, call_id: CallId) -> Result<()>;
+    fn on_call_concluded(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        app_data: &[u8],
+    ) -> Result<()>;
 
     /// Return true if you want a CallManager to always assume you called
     /// message_sent() for every signaling message.
@@ -279,7 +338,72 @@ pub trait Platform: sfu::Delegate + fmt::Debug + fmt::Display + Send + Sized + '
 
     fn handle_raised_hands(&self, client_id: group_call::ClientId, raised_hands: Vec<DemuxId>);
 
+    fn handle_remote_connection_qualities(
+        &self,
+        _client_id: group_call::ClientId,
+        _qualities: Vec<group_call::RemoteConnectionQuality>,
+    ) {
+    }
+
+    fn handle_av_sync_skew(
+        &self,
+        _client_id: group_call::ClientId,
+        _skews: Vec<group_call::RemoteAvSyncSkew>,
+    ) {
+    }
+
+    fn handle_video_adaptation_changed(
+        &self,
+        _client_id: group_call::ClientId,
+        _reason: Option<QualityLimitationReason>,
+    ) {
+    }
+
+    fn handle_e2ee_health(
+        &self,
+        _client_id: group_call::ClientId,
+        _health: group_call::E2eeHealth,
+    ) {
+    }
+
+    fn handle_app_data(
+        &self,
+        _client_id: group_call::ClientId,
+        _demux_id: DemuxId,
+        _data: Vec<u8>,
+    ) {
+    }
+
     fn handle_rtc_stats_report(&self, _report_json: String) {}
 
+    fn handle_own_device_joined(&self, _client_id: group_call::ClientId, _demux_id: DemuxId) {}
+
+    fn request_ice_servers(&self, _client_id: group_call::ClientId) {}
+
+    fn handle_ice_servers_refresh_failed(&self, _client_id: group_call::ClientId) {}
+
+    fn handle_breakout_group_changed(
+        &self,
+        _client_id: group_call::ClientId,
+        _breakout_group_id: Option<group_call::BreakoutGroupId>,
+    ) {
+    }
+
+    fn handle_call_full(
+        &self,
+        _client_id: group_call::ClientId,
+        _max_devices: Option<u32>,
+        _device_count: Option<u32>,
+    ) {
+    }
+
+    fn handle_call_summary(
+        &self,
+        _client_id: group_call::ClientId,
+        _summary: CallSummary,
+        _reason: group_call::EndReason,
+    ) {
+    }
+
     fn handle_ended(&self, client_id: group_call::ClientId, reason: group_call::EndReason);
 }
@@ -0,0 +1,243 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Measures how long calls take to get through setup, so that the effect of changes such as
+//! gathering ICE candidates early can be quantified.
+//!
+//! Each call has a [SetupLatencyTracker], whose [SetupLatencies] are reported in the call's
+//! summary. Every measurement is also added to in-process histograms covering all calls, which
+//! can be read with [histograms].
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// The upper bounds of the histogram buckets, in milliseconds. A final bucket holds everything
+/// slower.
+pub const BUCKET_BOUNDS_MILLIS: [u64; 8] = [250, 500, 1_000, 2_000, 4_000, 8_000, 16_000, 32_000];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SetupMilestone {
+    /// The call started ringing, on either side.
+    Ringing,
+    /// Media started flowing: accepted and connected for a 1:1 call, or connected to the SFU
+    /// for a group call.
+    Connected,
+    /// The first incoming video frame arrived.
+    FirstVideoFrame,
+}
+
+/// How long after the start of a call each milestone was reached, or None if it wasn't.
+///
+/// 1:1 calls start when they are created, and group calls when joined. Group calls don't ring,
+/// so their time to ring is always None.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SetupLatencies {
+    pub time_to_ring: Option<Duration>,
+    pub time_to_connect: Option<Duration>,
+    pub time_to_first_frame: Option<Duration>,
+}
+
+impl SetupLatencies {
+    fn get_mut(&mut self, milestone: SetupMilestone) -> &mut Option<Duration> {
+        match milestone {
+            SetupMilestone::Ringing => &mut self.time_to_ring,
+            SetupMilestone::Connected => &mut self.time_to_connect,
+            SetupMilestone::FirstVideoFrame => &mut self.time_to_first_frame,
+        }
+    }
+}
+
+/// Records when a call first reaches each milestone.
+#[derive(Debug, Default)]
+pub struct SetupLatencyTracker {
+    /// None until the call starts.
+    started_at: Mutex<Option<Instant>>,
+    latencies: Mutex<SetupLatencies>,
+    /// Set once the first video frame has been recorded, so that every later frame can skip the
+    /// locks.
+    saw_video_frame: AtomicBool,
+}
+
+impl SetupLatencyTracker {
+    /// Starts measuring from now, forgetting anything measured before.
+    pub fn start(&self) {
+        *self
+            .started_at
+            .lock()
+            .expect("SetupLatencyTracker:started_at lock") = Some(Instant::now());
+        *self
+            .latencies
+            .lock()
+            .expect("SetupLatencyTracker:latencies lock") = SetupLatencies::default();
+        self.saw_video_frame.store(false, Ordering::Relaxed);
+    }
+
+    /// Records the milestone unless it was already reached, or the call hasn't started.
+    pub fn record(&self, milestone: SetupMilestone) {
+        let Some(started_at) = *self
+            .started_at
+            .lock()
+            .expect("SetupLatencyTracker:started_at lock")
+        else {
+            return;
+        };
+        let mut latencies = self
+            .latencies
+            .lock()
+            .expect("SetupLatencyTracker:latencies lock");
+        let latency = latencies.get_mut(milestone);
+        if latency.is_none() {
+            let elapsed = started_at.elapsed();
+            *latency = Some(elapsed);
+            info!(
+                "Reached setup milestone {:?} after {:?}",
+                milestone, elapsed
+            );
+            add_to_histograms(milestone, elapsed);
+        }
+    }
+
+    /// Records [SetupMilestone::FirstVideoFrame] for the first frame. Cheap enough to call for
+    /// every frame.
+    pub fn record_video_frame(&self) {
+        if !self.saw_video_frame.swap(true, Ordering::Relaxed) {
+            self.record(SetupMilestone::FirstVideoFrame);
+        }
+    }
+
+    pub fn latencies(&self) -> SetupLatencies {
+        *self
+            .latencies
+            .lock()
+            .expect("SetupLatencyTracker:latencies lock")
+    }
+}
+
+/// How many measurements fell into each of the buckets in [BUCKET_BOUNDS_MILLIS], plus one
+/// more for everything slower.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    pub counts: [u32; BUCKET_BOUNDS_MILLIS.len() + 1],
+}
+
+impl LatencyHistogram {
+    const fn new() -> Self {
+        Self {
+            counts: [0; BUCKET_BOUNDS_MILLIS.len() + 1],
+        }
+    }
+
+    fn add(&mut self, latency: Duration) {
+        let millis = latency.as_millis();
+        let bucket = BUCKET_BOUNDS_MILLIS
+            .iter()
+            .position(|bound| millis <= *bound as u128)
+            .unwrap_or(BUCKET_BOUNDS_MILLIS.len());
+        self.counts[bucket] = self.counts[bucket].saturating_add(1);
+    }
+
+    /// The total number of measurements.
+    pub fn count(&self) -> u32 {
+        self.counts.iter().sum()
+    }
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Setup latencies of every call in this process since the histograms were last reset.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SetupLatencyHistograms {
+    pub time_to_ring: LatencyHistogram,
+    pub time_to_connect: LatencyHistogram,
+    pub time_to_first_frame: LatencyHistogram,
+}
+
+impl SetupLatencyHistograms {
+    const fn new() -> Self {
+        Self {
+            time_to_ring: LatencyHistogram::new(),
+            time_to_connect: LatencyHistogram::new(),
+            time_to_first_frame: LatencyHistogram::new(),
+        }
+    }
+
+    fn add(&mut self, milestone: SetupMilestone, latency: Duration) {
+        match milestone {
+            SetupMilestone::Ringing => self.time_to_ring.add(latency),
+            SetupMilestone::Connected => self.time_to_connect.add(latency),
+            SetupMilestone::FirstVideoFrame => self.time_to_first_frame.add(latency),
+        }
+    }
+}
+
+static HISTOGRAMS: Mutex<SetupLatencyHistograms> = Mutex::new(SetupLatencyHistograms::new());
+
+fn add_to_histograms(milestone: SetupMilestone, latency: Duration) {
+    if let Ok(mut histograms) = HISTOGRAMS.lock() {
+        histograms.add(milestone, latency);
+    }
+}
+
+/// Returns the histograms of all calls so far.
+pub fn histograms() -> SetupLatencyHistograms {
+    HISTOGRAMS
+        .lock()
+        .map(|histograms| histograms.clone())
+        .unwrap_or_default()
+}
+
+/// Clears the histograms, such as after they have been uploaded.
+pub fn reset_histograms() {
+    if let Ok(mut histograms) = HISTOGRAMS.lock() {
+        *histograms = SetupLatencyHistograms::new();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_first_time_only() {
+        let tracker = SetupLatencyTracker::default();
+        tracker.record(SetupMilestone::Ringing);
+        assert_eq!(SetupLatencies::default(), tracker.latencies());
+
+        tracker.start();
+        tracker.record(SetupMilestone::Connected);
+        let time_to_connect = tracker.latencies().time_to_connect;
+        assert!(time_to_connect.is_some());
+        std::thread::sleep(Duration::from_millis(5));
+        tracker.record(SetupMilestone::Connected);
+        tracker.record_video_frame();
+        tracker.record_video_frame();
+        let latencies = tracker.latencies();
+        assert_eq!(None, latencies.time_to_ring);
+        assert_eq!(time_to_connect, latencies.time_to_connect);
+        assert!(latencies.time_to_first_frame.unwrap() > time_to_connect.unwrap());
+
+        tracker.start();
+        assert_eq!(SetupLatencies::default(), tracker.latencies());
+    }
+
+    #[test]
+    fn histogram_buckets() {
+        let mut histogram = LatencyHistogram::default();
+        for millis in [0, 250, 251, 1_500, 60_000] {
+            histogram.add(Duration::from_millis(millis));
+        }
+        assert_eq!([2, 1, 0, 1, 0, 0, 0, 0, 1], histogram.counts);
+        assert_eq!(5, histogram.count());
+    }
+}
@@ -5,17 +5,22 @@
 
 /// The messages we send over the signaling channel to establish a call.
 use std::{
+    borrow::Cow,
     convert::TryInto,
     fmt,
+    io::{Read, Write},
     net::{IpAddr, SocketAddr},
     time::Duration,
 };
 
 use bytes::{Bytes, BytesMut};
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
 use prost::Message as _;
 
 use crate::{
-    common::{CallMediaType, DeviceId, Result},
+    common::{CallId, CallMediaType, DeviceId, Result},
+    error::RingRtcError,
+    lite::sfu::UserId,
     protobuf,
 };
 
@@ -82,6 +87,85 @@ impl fmt::Debug for Message {
     }
 }
 
+// A compressed opaque value starts with a zero byte, which can't start an encoded
+// protobuf (there is no field number 0), followed by the compression version.
+const COMPRESSED_OPAQUE_MARKER: u8 = 0;
+const COMPRESSED_OPAQUE_VERSION_DEFLATE: u8 = 1;
+// Anything that decompresses to more than this is rejected rather than buffered.
+const MAX_DECOMPRESSED_OPAQUE_SIZE: u64 = 64 * 1024;
+
+fn is_compressed_opaque(opaque: &[u8]) -> bool {
+    opaque.first() == Some(&COMPRESSED_OPAQUE_MARKER)
+}
+
+fn compress_opaque(opaque: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = DeflateEncoder::new(
+        vec![COMPRESSED_OPAQUE_MARKER, COMPRESSED_OPAQUE_VERSION_DEFLATE],
+        Compression::best(),
+    );
+    encoder.write_all(opaque)?;
+    Ok(encoder.finish()?)
+}
+
+fn decompress_opaque(opaque: &[u8]) -> Result<Cow<'_, [u8]>> {
+    match opaque {
+        [COMPRESSED_OPAQUE_MARKER, COMPRESSED_OPAQUE_VERSION_DEFLATE, compressed @ ..] => {
+            let mut decompressed = Vec::new();
+            DeflateDecoder::new(compressed)
+                .take(MAX_DECOMPRESSED_OPAQUE_SIZE + 1)
+                .read_to_end(&mut decompressed)
+                .map_err(|_| RingRtcError::InvalidCompressedOpaque)?;
+            if decompressed.len() as u64 > MAX_DECOMPRESSED_OPAQUE_SIZE {
+                return Err(RingRtcError::InvalidCompressedOpaque.into());
+            }
+            Ok(Cow::Owned(decompressed))
+        }
+        [COMPRESSED_OPAQUE_MARKER, ..] => Err(RingRtcError::UnknownSignaledProtocolVersion.into()),
+        _ => Ok(Cow::Borrowed(opaque)),
+    }
+}
+
+/// Encodes the V4 parameters in at most max_size bytes, first by compressing them
+/// and then by dropping optional fields until they fit.
+///
+/// Receivers that predate compression can't decode a compressed value, so it is
+/// only used when the uncompressed value is over budget.
+fn fit_v4_to_size(
+    mut v4: protobuf::signaling::ConnectionParametersV4,
+    max_size: usize,
+    encode: impl Fn(protobuf::signaling::ConnectionParametersV4) -> Result<Vec<u8>>,
+) -> Result<Vec<u8>> {
+    loop {
+        let opaque = encode(v4.clone())?;
+        if opaque.len() <= max_size {
+            return Ok(opaque);
+        }
+        let compressed = compress_opaque(&opaque)?;
+        if compressed.len() <= max_size {
+            return Ok(compressed);
+        }
+        if !trim_optional_v4_field(&mut v4) {
+            return Err(RingRtcError::SignalingMessageTooLarge(
+                opaque.len().min(compressed.len()),
+                max_size,
+            )
+            .into());
+        }
+    }
+}
+
+/// Drops the least important optional field that is still set, returning false
+/// once there is nothing left to drop. Video codecs go first, from the end of the
+/// list so the most preferred one is kept, and then the max bitrate, which the
+/// receiver falls back to its default for.
+fn trim_optional_v4_field(v4: &mut protobuf::signaling::ConnectionParametersV4) -> bool {
+    if v4.receive_video_codecs.len() > 1 {
+        v4.receive_video_codecs.pop();
+        return true;
+    }
+    v4.max_bitrate_bps.take().is_some()
+}
+
 // It's convenient to be able to now the type of a message without having
 // an entire message, so we have the related MessageType enum.
 #[repr(i32)]
@@ -115,10 +199,18 @@ impl Offer {
 
     fn deserialize_opaque(opaque: &[u8]) -> Result<protobuf::signaling::Offer> {
         Ok(protobuf::signaling::Offer::decode(Bytes::from(
-            opaque.to_owned(),
+            decompress_opaque(opaque)?.into_owned(),
         ))?)
     }
 
+    fn serialize_v4(v4: protobuf::signaling::ConnectionParametersV4) -> Result<Vec<u8>> {
+        let proto = protobuf::signaling::Offer { v4: Some(v4) };
+
+        let mut opaque = BytesMut::with_capacity(proto.encoded_len());
+        proto.encode(&mut opaque)?;
+        Ok(opaque.to_vec())
+    }
+
     pub fn latest_version(&self) -> Version {
         Version::V4
     }
@@ -127,12 +219,7 @@ impl Offer {
         call_media_type: CallMediaType,
         v4: protobuf::signaling::ConnectionParametersV4,
     ) -> Result<Self> {
-        let proto = protobuf::signaling::Offer { v4: Some(v4) };
-
-        let mut opaque = BytesMut::with_capacity(proto.encoded_len());
-        proto.encode(&mut opaque)?;
-
-        Self::new(call_media_type, opaque.to_vec())
+        Self::new(call_media_type, Self::serialize_v4(v4)?)
     }
 
     pub fn to_v4(&self) -> Option<protobuf::signaling::ConnectionParametersV4> {
@@ -145,10 +232,73 @@ impl Offer {
         }
     }
 
+    /// Returns a copy of this offer marked as taking over an accepted call
+    /// from another device of the sender.
+    pub fn to_handoff(&self) -> Result<Self> {
+        match self.to_v4() {
+            Some(mut v4) => {
+                v4.handoff = Some(true);
+                Self::from_v4(self.call_media_type, v4)
+            }
+            None => Ok(self.clone()),
+        }
+    }
+
+    /// Returns a copy of this offer carrying the high half of the sender's
+    /// 128-bit call ID.
+    pub fn with_call_id_extension(&self, extension: u64) -> Result<Self> {
+        match self.to_v4() {
+            Some(mut v4) => {
+                v4.call_id_extension = Some(extension);
+                Self::from_v4(self.call_media_type, v4)
+            }
+            None => Ok(self.clone()),
+        }
+    }
+
+    /// The high half of the sender's 128-bit call ID, if it sent one.
+    pub fn call_id_extension(&self) -> Option<u64> {
+        self.proto.v4.as_ref().and_then(|v4| v4.call_id_extension)
+    }
+
+    /// Returns a copy of this offer whose opaque value is at most max_size
+    /// bytes, compressing it and then trimming optional fields as needed.
+    pub fn fit_to_size(&self, max_size: usize) -> Result<Self> {
+        if self.opaque.len() <= max_size {
+            return Ok(self.clone());
+        }
+        let v4 = self.to_v4().ok_or(RingRtcError::SignalingMessageTooLarge(
+            self.opaque.len(),
+            max_size,
+        ))?;
+        Self::new(
+            self.call_media_type,
+            fit_v4_to_size(v4, max_size, Self::serialize_v4)?,
+        )
+    }
+
+    /// Whether the opaque value is compressed.
+    pub fn is_compressed(&self) -> bool {
+        is_compressed_opaque(&self.opaque)
+    }
+
+    /// Whether the sender is taking over an accepted call from another
+    /// of its devices.
+    pub fn is_handoff(&self) -> bool {
+        matches!(
+            self.proto.v4,
+            Some(protobuf::signaling::ConnectionParametersV4 {
+                handoff: Some(true),
+                ..
+            })
+        )
+    }
+
     pub fn to_info_string(&self) -> String {
         format!(
-            "opaque.len={}\tproto.version={}\ttype={}",
+            "opaque.len={}\tcompressed={}\tproto.version={}\ttype={}",
             self.opaque.len(),
+            self.is_compressed(),
             self.latest_version(),
             self.call_media_type
         )
@@ -171,21 +321,24 @@ impl Answer {
 
     fn deserialize_opaque(opaque: &[u8]) -> Result<protobuf::signaling::Answer> {
         Ok(protobuf::signaling::Answer::decode(Bytes::from(
-            opaque.to_owned(),
+            decompress_opaque(opaque)?.into_owned(),
         ))?)
     }
 
-    pub fn latest_version(&self) -> Version {
-        Version::V4
-    }
-
-    pub fn from_v4(v4: protobuf::signaling::ConnectionParametersV4) -> Result<Self> {
+    fn serialize_v4(v4: protobuf::signaling::ConnectionParametersV4) -> Result<Vec<u8>> {
         let proto = protobuf::signaling::Answer { v4: Some(v4) };
 
         let mut opaque = BytesMut::with_capacity(proto.encoded_len());
         proto.encode(&mut opaque)?;
+        Ok(opaque.to_vec())
+    }
+
+    pub fn latest_version(&self) -> Version {
+        Version::V4
+    }
 
-        Self::new(opaque.to_vec())
+    pub fn from_v4(v4: protobuf::signaling::ConnectionParametersV4) -> Result<Self> {
+        Self::new(Self::serialize_v4(v4)?)
     }
 
     pub fn to_v4(&self) -> Option<protobuf::signaling::ConnectionParametersV4> {
@@ -199,10 +352,29 @@ impl Answer {
         }
     }
 
+    /// Returns a copy of this answer whose opaque value is at most max_size
+    /// bytes, compressing it and then trimming optional fields as needed.
+    pub fn fit_to_size(&self, max_size: usize) -> Result<Self> {
+        if self.opaque.len() <= max_size {
+            return Ok(self.clone());
+        }
+        let v4 = self.to_v4().ok_or(RingRtcError::SignalingMessageTooLarge(
+            self.opaque.len(),
+            max_size,
+        ))?;
+        Self::new(fit_v4_to_size(v4, max_size, Self::serialize_v4)?)
+    }
+
+    /// Whether the opaque value is compressed.
+    pub fn is_compressed(&self) -> bool {
+        is_compressed_opaque(&self.opaque)
+    }
+
     pub fn to_info_string(&self) -> String {
         format!(
-            "opaque.len={}\tproto.version={}",
+            "opaque.len={}\tcompressed={}\tproto.version={}",
             self.opaque.len(),
+            self.is_compressed(),
             self.latest_version()
         )
     }
@@ -223,6 +395,13 @@ pub struct IceCandidate {
     pub opaque: Vec<u8>,
 }
 
+/// The new ICE credentials of one side after an ICE restart.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IceRestart {
+    pub ice_ufrag: String,
+    pub ice_pwd: String,
+}
+
 impl From<SocketAddr> for protobuf::signaling::SocketAddr {
     fn from(addr: SocketAddr) -> Self {
         Self {
@@ -269,6 +448,7 @@ impl IceCandidate {
         let ice_candidate_proto = protobuf::signaling::IceCandidate {
             added_v3: Some(ice_candidate_proto_v3),
             removed: None,
+            restart: None,
         };
 
         let mut opaque = Vec::with_capacity(ice_candidate_proto.encoded_len());
@@ -280,6 +460,7 @@ impl IceCandidate {
     pub fn from_removed_address(removed_address: SocketAddr) -> Result<Self> {
         let ice_candidate_proto = protobuf::signaling::IceCandidate {
             removed: Some(removed_address.into()),
+            restart: None,
             // Old clients blow up if they don't find an added candidate,
             // so we need to put something here.
             // It must pass WebRTC's ParseCandidate, VerifyCandidate,
@@ -303,9 +484,24 @@ impl IceCandidate {
             // - A protocol (UDP/TCP) that doesn't pair with anything (or you might create new pairs)
             // - Either an unset generation (for no warnings) or a set generation (for warnings, but no memory of the candidate)
             // So it's not paired, the foundation, IP, port, and type don't matter except to pass parsing
-            added_v3: Some(protobuf::signaling::IceCandidateV3 {
-                sdp: Some("candidate:FAKE 1 tcp 0 127.0.0.1 0 typ host".to_owned()),
+            added_v3: Some(fake_added_v3()),
+        };
+
+        let mut opaque = Vec::with_capacity(ice_candidate_proto.encoded_len());
+        ice_candidate_proto.encode(&mut opaque)?;
+
+        Ok(Self::new(opaque))
+    }
+
+    pub fn from_ice_restart(restart: IceRestart) -> Result<Self> {
+        let ice_candidate_proto = protobuf::signaling::IceCandidate {
+            restart: Some(protobuf::signaling::IceRestart {
+                ice_ufrag: Some(restart.ice_ufrag),
+                ice_pwd: Some(restart.ice_pwd),
             }),
+            removed: None,
+            // Same as for removals; see from_removed_address.
+            added_v3: Some(fake_added_v3()),
         };
 
         let mut opaque = Vec::with_capacity(ice_candidate_proto.encoded_len());
@@ -335,26 +531,85 @@ impl IceCandidate {
         }
     }
 
+    pub fn ice_restart(&self) -> Option<IceRestart> {
+        match protobuf::signaling::IceCandidate::decode(Bytes::from(self.opaque.clone())).ok()? {
+            protobuf::signaling::IceCandidate {
+                restart:
+                    Some(protobuf::signaling::IceRestart {
+                        ice_ufrag: Some(ice_ufrag),
+                        ice_pwd: Some(ice_pwd),
+                    }),
+                ..
+            } => Some(IceRestart { ice_ufrag, ice_pwd }),
+            _ => None,
+        }
+    }
+
     pub fn to_info_string(&self) -> String {
         format!("opaque.len={}", self.opaque.len())
     }
 }
 
+/// A candidate that passes parsing on old clients but never pairs with anything.
+fn fake_added_v3() -> protobuf::signaling::IceCandidateV3 {
+    protobuf::signaling::IceCandidateV3 {
+        sdp: Some("candidate:FAKE 1 tcp 0 127.0.0.1 0 typ host".to_owned()),
+    }
+}
+
+/// Why a callee declined a call, so the caller can show more than a
+/// generic remote hangup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DeclineReason {
+    Busy,
+    DoNotDisturb,
+    TextReplySent,
+    // Rejected by the application rather than the user, such as for a
+    // blocked contact or a managed device that doesn't allow calls.
+    Policy,
+}
+
+impl DeclineReason {
+    pub fn from_i32(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(DeclineReason::Busy),
+            1 => Some(DeclineReason::DoNotDisturb),
+            2 => Some(DeclineReason::TextReplySent),
+            3 => Some(DeclineReason::Policy),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Hangup {
     Normal, // on this device
+    // Declined by the user on this device
+    Declined(DeclineReason),
     AcceptedOnAnotherDevice(DeviceId),
     DeclinedOnAnotherDevice(DeviceId),
     BusyOnAnotherDevice(DeviceId),
     // If you want to express that you NeedPermission on your device,
     // You can either fill it in or with your own device_id.
     NeedPermission(Option<DeviceId>),
+    // The call was taken over by the given device of the same user.
+    HandedOff(DeviceId),
+    // Ended on this device because the connection failed or its media stalled.
+    NetworkFailure,
+    // Ended because nobody answered before the ring timeout.
+    RingTimeout,
 }
 
 impl Hangup {
     pub fn to_type_and_device_id(&self) -> (HangupType, Option<DeviceId>) {
         match self {
             Self::Normal => (HangupType::Normal, None),
+            Self::Declined(DeclineReason::Busy) => (HangupType::DeclinedBusy, None),
+            Self::Declined(DeclineReason::DoNotDisturb) => (HangupType::DeclinedDoNotDisturb, None),
+            Self::Declined(DeclineReason::TextReplySent) => {
+                (HangupType::DeclinedTextReplySent, None)
+            }
+            Self::Declined(DeclineReason::Policy) => (HangupType::DeclinedByPolicy, None),
             Self::AcceptedOnAnotherDevice(other_device_id) => {
                 (HangupType::AcceptedOnAnotherDevice, Some(*other_device_id))
             }
@@ -365,10 +620,13 @@ impl Hangup {
                 (HangupType::BusyOnAnotherDevice, Some(*other_device_id))
             }
             Self::NeedPermission(other_device_id) => (HangupType::NeedPermission, *other_device_id),
+            Self::HandedOff(other_device_id) => (HangupType::HandedOff, Some(*other_device_id)),
+            Self::NetworkFailure => (HangupType::NetworkFailure, None),
+            Self::RingTimeout => (HangupType::RingTimeout, None),
         }
     }
 
-    // For Normal, device_id is ignored
+    // For Normal, NetworkFailure, RingTimeout, and the Declined types, device_id is ignored
     // For NeedPermission, we can't express an unset DeviceId because the Android and iOS apps
     // give us DeviceIds of 0 rather than None when receiving, so we just assume it's set.
     // But since our receive logic doesn't care if it's 0 or None or anything else
@@ -380,6 +638,13 @@ impl Hangup {
             HangupType::DeclinedOnAnotherDevice => Self::DeclinedOnAnotherDevice(device_id),
             HangupType::BusyOnAnotherDevice => Self::BusyOnAnotherDevice(device_id),
             HangupType::NeedPermission => Self::NeedPermission(Some(device_id)),
+            HangupType::DeclinedBusy => Self::Declined(DeclineReason::Busy),
+            HangupType::DeclinedDoNotDisturb => Self::Declined(DeclineReason::DoNotDisturb),
+            HangupType::DeclinedTextReplySent => Self::Declined(DeclineReason::TextReplySent),
+            HangupType::HandedOff => Self::HandedOff(device_id),
+            HangupType::NetworkFailure => Self::NetworkFailure,
+            HangupType::DeclinedByPolicy => Self::Declined(DeclineReason::Policy),
+            HangupType::RingTimeout => Self::RingTimeout,
         }
     }
 }
@@ -407,6 +672,20 @@ pub enum HangupType {
     BusyOnAnotherDevice = 3,
     // On either another device or this device
     NeedPermission = 4,
+    // On this device, declined with a reason. Clients that don't know
+    // these types treat them as Normal.
+    DeclinedBusy = 5,
+    DeclinedDoNotDisturb = 6,
+    DeclinedTextReplySent = 7,
+    // On another device, which took over the call
+    HandedOff = 8,
+    // On this device, ended because of a failed connection or stalled media.
+    // Clients that don't know these types treat them as Normal.
+    NetworkFailure = 9,
+    // On this device, declined by the application rather than the user
+    DeclinedByPolicy = 10,
+    // On either device, ended because nobody answered in time
+    RingTimeout = 11,
 }
 
 impl HangupType {
@@ -417,6 +696,13 @@ impl HangupType {
             2 => Some(HangupType::DeclinedOnAnotherDevice),
             3 => Some(HangupType::BusyOnAnotherDevice),
             4 => Some(HangupType::NeedPermission),
+            5 => Some(HangupType::DeclinedBusy),
+            6 => Some(HangupType::DeclinedDoNotDisturb),
+            7 => Some(HangupType::DeclinedTextReplySent),
+            8 => Some(HangupType::HandedOff),
+            9 => Some(HangupType::NetworkFailure),
+            10 => Some(HangupType::DeclinedByPolicy),
+            11 => Some(HangupType::RingTimeout),
             _ => None,
         }
     }
@@ -444,6 +730,99 @@ pub struct SendHangup {
     pub hangup: Hangup,
 }
 
+/// A message handed to the application for sending, but not yet confirmed
+/// as sent. It can be serialized for the application to persist and sent
+/// again after a restart.
+#[derive(Clone, Debug)]
+pub struct PendingMessage {
+    pub call_id: CallId,
+    pub receiver_device_id: Option<DeviceId>,
+    pub message: Message,
+}
+
+impl PendingMessage {
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut proto = protobuf::signaling::PendingMessage {
+            call_id: Some(self.call_id.as_u64()),
+            receiver_device_id: self.receiver_device_id,
+            ..Default::default()
+        };
+        match &self.message {
+            Message::Offer(offer) => {
+                proto.offer = Some(offer.opaque.clone());
+                proto.offer_video = Some(offer.call_media_type == CallMediaType::Video);
+            }
+            Message::Answer(answer) => {
+                proto.answer = Some(answer.opaque.clone());
+            }
+            Message::Ice(ice) => {
+                proto.ice_candidates = ice
+                    .candidates
+                    .iter()
+                    .map(|candidate| candidate.opaque.clone())
+                    .collect();
+            }
+            Message::Hangup(hangup) => {
+                let (hangup_type, hangup_device_id) = hangup.to_type_and_device_id();
+                proto.hangup_type = Some(hangup_type as i32);
+                proto.hangup_device_id = hangup_device_id;
+            }
+            Message::Busy => {
+                proto.busy = Some(true);
+            }
+        }
+
+        let mut bytes = BytesMut::with_capacity(proto.encoded_len());
+        proto.encode(&mut bytes)?;
+        Ok(bytes.to_vec())
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let proto = protobuf::signaling::PendingMessage::decode(bytes)?;
+
+        let call_id = match proto.call_id {
+            Some(call_id) => CallId::new(call_id),
+            None => return Err(RingRtcError::InvalidPendingMessage("call_id").into()),
+        };
+
+        let message = if let Some(opaque) = proto.offer {
+            let call_media_type = if proto.offer_video == Some(true) {
+                CallMediaType::Video
+            } else {
+                CallMediaType::Audio
+            };
+            Message::Offer(Offer::new(call_media_type, opaque)?)
+        } else if let Some(opaque) = proto.answer {
+            Message::Answer(Answer::new(opaque)?)
+        } else if !proto.ice_candidates.is_empty() {
+            Message::Ice(Ice {
+                candidates: proto
+                    .ice_candidates
+                    .into_iter()
+                    .map(IceCandidate::new)
+                    .collect(),
+            })
+        } else if let Some(hangup_type) = proto.hangup_type {
+            let hangup_type = HangupType::from_i32(hangup_type)
+                .ok_or(RingRtcError::InvalidPendingMessage("hangup_type"))?;
+            Message::Hangup(Hangup::from_type_and_device_id(
+                hangup_type,
+                proto.hangup_device_id.unwrap_or(0),
+            ))
+        } else if proto.busy == Some(true) {
+            Message::Busy
+        } else {
+            return Err(RingRtcError::InvalidPendingMessage("message").into());
+        };
+
+        Ok(Self {
+            call_id,
+            receiver_device_id: proto.receiver_device_id,
+            message,
+        })
+    }
+}
+
 /// An Offer with extra info specific to receiving
 pub struct ReceivedOffer {
     pub offer: Offer,
@@ -481,9 +860,120 @@ pub struct ReceivedBusy {
     pub sender_device_id: DeviceId,
 }
 
+/// Received from another device of the local user, asking this device to
+/// take over an accepted call by calling the remote device itself.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandoffRequest {
+    pub call_id: CallId,
+    pub remote_user_id: UserId,
+    pub remote_device_id: DeviceId,
+    pub call_media_type: CallMediaType,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
 pub struct SenderStatus {
     pub video_enabled: Option<bool>,
     pub sharing_screen: Option<bool>,
     pub audio_enabled: Option<bool>,
+    pub on_hold: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+    use crate::common::WideCallId;
+
+    fn v4_with_codecs(codec_count: usize) -> protobuf::signaling::ConnectionParametersV4 {
+        protobuf::signaling::ConnectionParametersV4 {
+            public_key: Some(rand::thread_rng().gen::<[u8; 32]>().to_vec()),
+            ice_ufrag: Some("ufrag".to_owned()),
+            ice_pwd: Some("password".to_owned()),
+            receive_video_codecs: (0..codec_count)
+                .map(|_| protobuf::signaling::VideoCodec {
+                    r#type: Some(protobuf::signaling::VideoCodecType::Vp8 as i32),
+                })
+                .collect(),
+            max_bitrate_bps: Some(2_000_000),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fit_to_size_leaves_small_offer_alone() {
+        let offer = Offer::from_v4(CallMediaType::Audio, v4_with_codecs(2)).unwrap();
+        let fitted = offer.fit_to_size(offer.opaque.len()).unwrap();
+        assert_eq!(offer.opaque, fitted.opaque);
+        assert!(!fitted.is_compressed());
+    }
+
+    #[test]
+    fn fit_to_size_compresses() {
+        let v4 = v4_with_codecs(200);
+        let offer = Offer::from_v4(CallMediaType::Video, v4.clone()).unwrap();
+        let max_size = offer.opaque.len() / 2;
+
+        let fitted = offer.fit_to_size(max_size).unwrap();
+        assert!(fitted.is_compressed());
+        assert!(fitted.opaque.len() <= max_size);
+        assert_eq!(Some(v4.clone()), fitted.to_v4());
+
+        // The receiving side decompresses transparently.
+        let received = Offer::new(CallMediaType::Video, fitted.opaque).unwrap();
+        assert_eq!(Some(v4), received.to_v4());
+    }
+
+    #[test]
+    fn fit_to_size_trims_optional_fields() {
+        let v4 = v4_with_codecs(200);
+        let answer = Answer::from_v4(v4.clone()).unwrap();
+
+        // The random public key doesn't compress, so only trimming everything
+        // optional fits in the size of the fully trimmed answer.
+        let mut trimmed_v4 = v4;
+        trimmed_v4.receive_video_codecs.truncate(1);
+        trimmed_v4.max_bitrate_bps = None;
+        let max_size = Answer::from_v4(trimmed_v4.clone()).unwrap().opaque.len();
+
+        let fitted = answer.fit_to_size(max_size).unwrap();
+        assert!(fitted.opaque.len() <= max_size);
+        assert_eq!(Some(trimmed_v4), fitted.to_v4());
+
+        // Trimming is deterministic.
+        assert_eq!(fitted.opaque, answer.fit_to_size(max_size).unwrap().opaque);
+    }
+
+    #[test]
+    fn fit_to_size_fails_when_required_fields_are_too_large() {
+        let answer = Answer::from_v4(v4_with_codecs(200)).unwrap();
+        assert!(answer.fit_to_size(20).is_err());
+    }
+
+    #[test]
+    fn call_id_extension_round_trips() {
+        let offer = Offer::from_v4(CallMediaType::Audio, v4_with_codecs(1)).unwrap();
+        assert_eq!(None, offer.call_id_extension());
+
+        let offer = offer.with_call_id_extension(0x0123_4567_89ab_cdef).unwrap();
+        let received = Offer::new(CallMediaType::Audio, offer.opaque).unwrap();
+        assert_eq!(Some(0x0123_4567_89ab_cdef), received.call_id_extension());
+    }
+
+    #[test]
+    fn wide_call_ids_differ_across_senders() {
+        let call_id = CallId::new(0x1234);
+        let a = WideCallId::generate(call_id, b"sender a", 1);
+        let b = WideCallId::generate(call_id, b"sender b", 1);
+        assert_eq!(call_id, a.call_id());
+        assert_eq!(call_id, b.call_id());
+        assert_ne!(a, b);
+        assert_eq!(0x1234, a.as_u128() as u64);
+        assert_eq!(32, a.to_string().len());
+    }
+
+    #[test]
+    fn unknown_compression_version_is_rejected() {
+        assert!(Answer::new(vec![COMPRESSED_OPAQUE_MARKER, 99, 1, 2, 3]).is_err());
+    }
 }
@@ -0,0 +1,28 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Storage the application can provide for outgoing 1:1 signaling.
+//!
+//! Only one signaling message is handed to the application at a time, and
+//! it stays pending until the application calls message_sent() or
+//! message_send_failure(). If the process dies in between, the message is
+//! lost and call setup stalls. With a store set, the Call Manager persists
+//! the pending message so that the application can pass it back with
+//! CallManager::resend_pending_message() on the next startup.
+
+use crate::{common::Result, core::platform::Platform};
+
+/// A trait describing where the pending signaling message is persisted.
+pub trait SignalingStore<T>: Send + 'static
+where
+    T: Platform,
+{
+    /// Persist the serialized pending message for the given remote peer,
+    /// replacing anything saved before.
+    fn save(&self, remote_peer: &T::AppRemotePeer, pending: Vec<u8>) -> Result<()>;
+
+    /// Remove the saved message, since it is no longer pending.
+    fn clear(&self) -> Result<()>;
+}
@@ -0,0 +1,84 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! A transport the Call Manager can send signaling messages over directly,
+//! instead of handing them to the application through the Platform.
+//!
+//! This lets integrations without a full application shim, such as bots or
+//! the call simulator, carry signaling over a channel implemented in Rust
+//! (a websocket, for example). Messages received over the transport are
+//! passed back to the Call Manager with the usual received_*() APIs.
+
+use std::collections::HashSet;
+
+use crate::{
+    common::{CallId, Result},
+    core::{group_call, platform::Platform, signaling},
+    lite::sfu::UserId,
+};
+
+/// A trait describing a channel for sending signaling messages.
+///
+/// Once set with CallManager::set_signaling_transport(), it is used for
+/// all outgoing signaling in place of the corresponding Platform methods.
+pub trait SignalingTransport<T>: Send + 'static
+where
+    T: Platform,
+{
+    /// Send an offer to a remote peer. Offers are always broadcast to all
+    /// devices.
+    fn send_offer(
+        &self,
+        remote_peer: &T::AppRemotePeer,
+        call_id: CallId,
+        offer: signaling::Offer,
+    ) -> Result<()>;
+
+    /// Send an answer to a remote peer.
+    fn send_answer(
+        &self,
+        remote_peer: &T::AppRemotePeer,
+        call_id: CallId,
+        send: signaling::SendAnswer,
+    ) -> Result<()>;
+
+    /// Send ICE candidates to a remote peer.
+    fn send_ice(
+        &self,
+        remote_peer: &T::AppRemotePeer,
+        call_id: CallId,
+        send: signaling::SendIce,
+    ) -> Result<()>;
+
+    /// Send a hangup message to a remote peer.
+    fn send_hangup(
+        &self,
+        remote_peer: &T::AppRemotePeer,
+        call_id: CallId,
+        send: signaling::SendHangup,
+    ) -> Result<()>;
+
+    /// Send a busy message to a remote peer. This always broadcasts to all
+    /// devices.
+    fn send_busy(&self, remote_peer: &T::AppRemotePeer, call_id: CallId) -> Result<()>;
+
+    /// Send a generic call message to a recipient.
+    fn send_call_message(
+        &self,
+        recipient_id: UserId,
+        message: Vec<u8>,
+        urgency: group_call::SignalingMessageUrgency,
+    ) -> Result<()>;
+
+    /// Send a generic call message to a group. Send to all members of the group
+    /// or, if recipients_override is not empty, to the given subset of members.
+    fn send_call_message_to_group(
+        &self,
+        group_id: group_call::GroupId,
+        message: Vec<u8>,
+        urgency: group_call::SignalingMessageUrgency,
+        recipients_override: HashSet<UserId>,
+    ) -> Result<()>;
+}
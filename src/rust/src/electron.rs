@@ -5,13 +5,9 @@
 
 use std::{
     cell::RefCell,
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     convert::TryFrom,
-    sync::{
-        atomic::AtomicBool,
-        mpsc::{channel, Receiver, Sender},
-        Arc, Mutex,
-    },
+    sync::{Arc, Mutex, PoisonError},
     time::Duration,
 };
 
@@ -22,34 +18,49 @@ use neon::{
 };
 
 use crate::{
-    common::{CallConfig, CallId, CallMediaType, DataMode, DeviceId, Result},
+    common::{
+        AcceptOptions, CallBitrateConfig, CallConfig, CallId, CallMediaType, CallSummary, DataMode,
+        DeviceId, GlareResolution, MaxSendBitrate, Result,
+    },
     core::{
         call_manager::CallManager,
-        group_call,
+        crypto, group_call,
         group_call::{GroupId, SignalingMessageUrgency},
-        signaling,
+        logging::{self, RedactingLogger},
+        setup_latency, signaling,
         util::minmax,
     },
+    frame_crypto::CryptoContext,
     lite::{
         call_links::{
-            self, CallLinkDeleteRequest, CallLinkRestrictions, CallLinkRootKey, CallLinkState,
-            CallLinkUpdateRequest, Empty,
+            self, CallLinkDeleteRequest, CallLinkRestrictions, CallLinkRootKey,
+            CallLinkRootKeyVersion, CallLinkState, CallLinkUpdateRequest, Empty,
         },
-        http, sfu,
+        crash_dump, diagnostics, http, sfu,
         sfu::{DemuxId, GroupMember, PeekInfo, UserId},
     },
     native::{
         CallState, CallStateHandler, EndReason, GroupUpdate, GroupUpdateHandler, NativeCallContext,
-        NativePlatform, PeerId, SignalingSender,
+        NativePlatform, PeerId, SignalingSender, VideoNegotiationEvent,
     },
     webrtc::{
+        audio_injector::AudioInjectionMode,
         field_trial,
-        media::{AudioTrack, VideoFrame, VideoPixelFormat, VideoSink, VideoSource, VideoTrack},
-        peer_connection::AudioLevel,
+        media::{
+            AudioLossProtectionConfig, AudioLossProtectionMode, AudioProfile, AudioTrack,
+            VideoContentHint, VideoFormatLimits, VideoFrame, VideoPixelFormat, VideoRotation,
+            VideoSink, VideoSource, VideoTrack,
+        },
+        peer_connection::{AudioLevel, JitterBufferConfig},
         peer_connection_factory::{
             self as pcf, AudioDevice, IceServer, PeerConnectionFactory, RffiAudioDeviceModuleType,
         },
-        peer_connection_observer::NetworkRoute,
+        peer_connection_observer::{NetworkRoute, TransportProtocol},
+        sdp_observer::RffiVideoCodecType,
+        stats_observer::{
+            CandidatePairState, CandidatePairStatistics, InboundStreamStats, OutboundStreamStats,
+            QualityLimitationReason, StatsSnapshot,
+        },
     },
 };
 
@@ -64,6 +75,16 @@ const MAX_VIDEO_FPS: u8 = 30;
 const GROUP_CALL_MAX_VIDEO_WIDTH: u16 = 640;
 const GROUP_CALL_MAX_VIDEO_HEIGHT: u16 = 480;
 
+// Screenshare keeps its full resolution, trading framerate for legibility instead.
+const SCREENSHARE_MAX_VIDEO_FPS: u8 = 15;
+const SCREENSHARE_TEXT_MAX_VIDEO_FPS: u8 = 5;
+
+// How many events can wait for JavaScript before periodic ones (see Event::is_periodic) are
+// dropped. Other events are queued up to EVENT_QUEUE_LIMIT, since losing one would break the
+// call, but past that JavaScript isn't keeping up at all and sending them fails instead.
+const EVENT_QUEUE_CAPACITY: usize = 256;
+const EVENT_QUEUE_LIMIT: usize = 4096;
+
 /// A structure for packing the contents of log messages.
 pub struct LogMessage {
     level: i8,
@@ -72,11 +93,11 @@ pub struct LogMessage {
     message: String,
 }
 
-// We store the log messages in a queue to be given to JavaScript when it processes events so
+// We store the log messages in a queue to be given to JavaScript along with events so
 // it can show the messages in the console.
-// We could report these as Events, but then logging during event processing would cause
-// the event handler to be rescheduled over and over.
-static LOG: Log = Log;
+// We could report these as Events, but then logging during event delivery would cause
+// delivery to be rescheduled over and over.
+static LOG: RedactingLogger<Log> = RedactingLogger::new(Log);
 lazy_static! {
     static ref LOG_MESSAGES: Mutex<Vec<LogMessage>> = Mutex::new(Vec::new());
     static ref CURRENT_EVENT_REPORTER: Mutex<Option<EventReporter>> = Mutex::new(None);
@@ -149,7 +170,11 @@ pub enum Event {
     },
     // The call with the given remote PeerId has changed state.
     // We assume only one call per remote PeerId at a time.
-    CallState(PeerId, CallId, CallState),
+    // The bytes are the app data attached to the call, or empty.
+    CallState(PeerId, CallId, CallState, Vec<u8>),
+    // The call with the given remote PeerId is ending, with these totals.
+    // Sent right before the CallState::Ended for the same call.
+    CallSummary(PeerId, CallId, CallSummary, EndReason, Vec<u8>),
     // The state of the remote audio (whether enabled or not) changed.
     // Like call state, we ID the call by PeerId and assume there is only one.
     RemoteAudioStateChange(PeerId, bool),
@@ -159,6 +184,32 @@ pub enum Event {
     // Whether the remote is sharing its screen or not changed.
     // Like call state, we ID the call by PeerId and assume there is only one.
     RemoteSharingScreenChange(PeerId, bool),
+    // Whether the remote has put the call on hold or not changed.
+    // Like call state, we ID the call by PeerId and assume there is only one.
+    RemoteHoldChange(PeerId, bool),
+    // An incoming call is waiting for the app to accept or decline it.
+    IncomingCallWaiting(PeerId, CallId),
+    // A glare with the given remote PeerId was resolved.
+    // Sent before any state changes for the calls involved.
+    GlareResolved(PeerId, GlareResolution),
+    // The remote moved the call to another of its devices.
+    // Like call state, we ID the call by PeerId and assume there is only one.
+    RemoteHandedOff(PeerId),
+    // The callee started sending early media, which is being played out.
+    RemoteEarlyMedia(PeerId),
+    // A step of mid-call video negotiation with the remote PeerId.
+    VideoNegotiation(PeerId, VideoNegotiationEvent),
+    // Nothing has been received from the remote PeerId for the media stall timeout.
+    MediaStalled(PeerId),
+    // The remote PeerId sent application data.
+    AppData(PeerId, Vec<u8>),
+    // Video sent to the PeerId started or stopped being adapted.
+    VideoAdaptationChanged {
+        peer_id: PeerId,
+        reason: Option<QualityLimitationReason>,
+    },
+    // Another device of the local user asked this one to take over its call.
+    HandoffRequested(signaling::HandoffRequest),
     // The group call has an update.
     GroupUpdate(GroupUpdate),
     // A call link request has completed.
@@ -187,31 +238,122 @@ pub enum Event {
         peer_id: PeerId,
         recovered: bool,
     },
+    // Audio devices were added or removed.
+    AudioDevicesChanged,
+}
+
+impl Event {
+    /// Whether the event is sent regularly and superseded by the next one, so that it can be
+    /// dropped when JavaScript falls behind.
+    fn is_periodic(&self) -> bool {
+        matches!(
+            self,
+            Event::AudioLevels { .. }
+                | Event::GroupUpdate(GroupUpdate::AudioLevels(..))
+                | Event::GroupUpdate(GroupUpdate::RemoteConnectionQualities(..))
+                | Event::GroupUpdate(GroupUpdate::AvSyncSkew(..))
+        )
+    }
+}
+
+#[derive(Default)]
+struct EventQueue {
+    events: VecDeque<Event>,
+    // Whether a call to deliver_events is pending on the JavaScript thread. While it is, new
+    // events are picked up by that call rather than scheduling another.
+    delivery_scheduled: bool,
+    dropped_periodic_events: usize,
 }
 
-/// Wraps a [`std::sync::mpsc::Sender`] with a callback to report new events.
+enum NextDelivery {
+    Event(Event),
+    LogMessages,
+    Finished,
+}
+
+impl EventQueue {
+    /// Lets the next event or log message schedule delivery again, after a scheduled delivery
+    /// didn't run or stopped early.
+    fn unschedule_delivery(queue: &Mutex<Self>) {
+        // This may run while unwinding, so it must not panic itself.
+        queue
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .delivery_scheduled = false;
+    }
+
+    /// Takes the next event to deliver, or marks delivery as finished if there's nothing left,
+    /// including log messages.
+    fn next_to_deliver(queue: &Mutex<Self>) -> NextDelivery {
+        let mut queue = queue.lock().expect("lock event queue");
+        if let Some(event) = queue.events.pop_front() {
+            return NextDelivery::Event(event);
+        }
+        // Checked while holding the queue lock, because Log::log queues its message before
+        // taking the lock to schedule delivery.
+        if !LOG_MESSAGES.lock().expect("lock log messages").is_empty() {
+            return NextDelivery::LogMessages;
+        }
+        queue.delivery_scheduled = false;
+        NextDelivery::Finished
+    }
+}
+
+/// Queues events from any thread for delivery on the JavaScript thread.
+///
+/// Events are delivered in the order they were sent, one observer call at a time, by
+/// [`deliver_events`] running as a task on a Neon [`Channel`]. Because Node never runs such tasks
+/// while JavaScript is already on the stack, events are never delivered re-entrantly: any caused
+/// by an observer calling back into RingRTC are queued and delivered once it returns.
 #[derive(Clone)]
 struct EventReporter {
-    sender: Sender<Event>,
-    report: Arc<dyn Fn() + Send + Sync>,
+    queue: Arc<Mutex<EventQueue>>,
+    schedule_delivery: Arc<dyn Fn() + Send + Sync>,
 }
 
 impl EventReporter {
-    fn new(sender: Sender<Event>, report: impl Fn() + Send + Sync + 'static) -> Self {
+    fn new(
+        queue: Arc<Mutex<EventQueue>>,
+        schedule_delivery: impl Fn() + Send + Sync + 'static,
+    ) -> Self {
         Self {
-            sender,
-            report: Arc::new(report),
+            queue,
+            schedule_delivery: Arc::new(schedule_delivery),
         }
     }
 
     fn send(&self, event: Event) -> Result<()> {
-        self.sender.send(event)?;
-        self.report();
+        let schedule = {
+            let mut queue = self.queue.lock().expect("lock event queue");
+            if queue.events.len() >= EVENT_QUEUE_CAPACITY && event.is_periodic() {
+                // Don't log here; logging reports to this same queue.
+                queue.dropped_periodic_events += 1;
+                return Ok(());
+            }
+            if queue.events.len() >= EVENT_QUEUE_LIMIT {
+                return Err(anyhow::anyhow!(
+                    "{} events are already waiting for JavaScript",
+                    EVENT_QUEUE_LIMIT
+                ));
+            }
+            queue.events.push_back(event);
+            !std::mem::replace(&mut queue.delivery_scheduled, true)
+        };
+        if schedule {
+            (self.schedule_delivery)();
+        }
         Ok(())
     }
 
+    /// Makes sure queued log messages get delivered, even if no events are.
     fn report(&self) {
-        (self.report)();
+        let schedule = {
+            let mut queue = self.queue.lock().expect("lock event queue");
+            !std::mem::replace(&mut queue.delivery_scheduled, true)
+        };
+        if schedule {
+            (self.schedule_delivery)();
+        }
     }
 }
 
@@ -266,11 +408,13 @@ impl CallStateHandler for EventReporter {
         remote_peer_id: &str,
         call_id: CallId,
         call_state: CallState,
+        app_data: &[u8],
     ) -> Result<()> {
         self.send(Event::CallState(
             remote_peer_id.to_string(),
             call_id,
             call_state,
+            app_data.to_vec(),
         ))
     }
 
@@ -306,6 +450,17 @@ impl CallStateHandler for EventReporter {
         ))
     }
 
+    fn handle_remote_hold(&self, remote_peer_id: &str, on_hold: bool) -> Result<()> {
+        self.send(Event::RemoteHoldChange(remote_peer_id.to_string(), on_hold))
+    }
+
+    fn handle_incoming_call_waiting(&self, remote_peer_id: &str, call_id: CallId) -> Result<()> {
+        self.send(Event::IncomingCallWaiting(
+            remote_peer_id.to_string(),
+            call_id,
+        ))
+    }
+
     fn handle_audio_levels(
         &self,
         remote_peer_id: &str,
@@ -325,6 +480,70 @@ impl CallStateHandler for EventReporter {
             recovered,
         })
     }
+
+    fn handle_call_summary(
+        &self,
+        remote_peer_id: &str,
+        call_id: CallId,
+        summary: CallSummary,
+        reason: EndReason,
+        app_data: &[u8],
+    ) -> Result<()> {
+        self.send(Event::CallSummary(
+            remote_peer_id.to_string(),
+            call_id,
+            summary,
+            reason,
+            app_data.to_vec(),
+        ))
+    }
+
+    fn handle_glare_resolved(
+        &self,
+        remote_peer_id: &str,
+        resolution: GlareResolution,
+    ) -> Result<()> {
+        self.send(Event::GlareResolved(remote_peer_id.to_string(), resolution))
+    }
+
+    fn handle_remote_handed_off(&self, remote_peer_id: &str) -> Result<()> {
+        self.send(Event::RemoteHandedOff(remote_peer_id.to_string()))
+    }
+
+    fn handle_remote_early_media(&self, remote_peer_id: &str) -> Result<()> {
+        self.send(Event::RemoteEarlyMedia(remote_peer_id.to_string()))
+    }
+
+    fn handle_video_negotiation(
+        &self,
+        remote_peer_id: &str,
+        event: VideoNegotiationEvent,
+    ) -> Result<()> {
+        self.send(Event::VideoNegotiation(remote_peer_id.to_string(), event))
+    }
+
+    fn handle_media_stalled(&self, remote_peer_id: &str) -> Result<()> {
+        self.send(Event::MediaStalled(remote_peer_id.to_string()))
+    }
+
+    fn handle_app_data(&self, remote_peer_id: &str, data: Vec<u8>) -> Result<()> {
+        self.send(Event::AppData(remote_peer_id.to_string(), data))
+    }
+
+    fn handle_video_adaptation_changed(
+        &self,
+        remote_peer_id: &str,
+        reason: Option<QualityLimitationReason>,
+    ) -> Result<()> {
+        self.send(Event::VideoAdaptationChanged {
+            peer_id: remote_peer_id.to_string(),
+            reason,
+        })
+    }
+
+    fn handle_handoff_requested(&self, request: signaling::HandoffRequest) -> Result<()> {
+        self.send(Event::HandoffRequested(request))
+    }
 }
 
 impl http::Delegate for EventReporter {
@@ -346,7 +565,6 @@ impl GroupUpdateHandler for EventReporter {
 pub struct CallEndpoint {
     call_manager: CallManager<NativePlatform>,
 
-    events_receiver: Receiver<Event>,
     event_reporter: EventReporter,
     // This is what we use to control mute/not.
     // It should probably be per-call, but for now it's easier to have only one.
@@ -369,6 +587,13 @@ pub struct CallEndpoint {
     js_object: Arc<Root<JsObject>>,
 
     most_recent_overlarge_frame_dimensions: (u32, u32),
+
+    // The format the current call would send, before any limits set by the app,
+    // so that it can be re-applied when the limits change.
+    outgoing_video_format: (u16, u16, u8),
+    outgoing_video_is_screen_content: bool,
+    camera_video_limits: VideoFormatLimits,
+    screenshare_video_limits: VideoFormatLimits,
 }
 
 impl CallEndpoint {
@@ -378,7 +603,6 @@ impl CallEndpoint {
         use_ringrtc_adm: bool,
     ) -> Result<Self> {
         // Relevant for both group calls and 1:1 calls
-        let (events_sender, events_receiver) = channel::<Event>();
         let mut audio_config = pcf::AudioConfig::default();
         if use_ringrtc_adm {
             audio_config.audio_device_module_type = RffiAudioDeviceModuleType::RingRtc;
@@ -392,51 +616,23 @@ impl CallEndpoint {
         outgoing_video_track.set_enabled(false);
         let incoming_video_sink = Box::<LastFramesVideoSink>::default();
 
-        let event_reported = Arc::new(AtomicBool::new(false));
         let js_object = Arc::new(Root::new(cx, &*js_object));
         let js_object_weak = Arc::downgrade(&js_object);
         let mut js_channel = cx.channel();
         js_channel.unref(cx); // Don't keep Node alive just for this channel.
-        let event_reporter = EventReporter::new(events_sender, move || {
-            // First check to see if an event has been reported recently.
-            // We aren't using this for synchronizing any other memory state,
-            // so Relaxed is good enough.
-            if event_reported.swap(true, std::sync::atomic::Ordering::Relaxed) {
-                return;
-            }
-
-            // Then signal the event through the JavaScript channel.
+        let event_queue = Arc::new(Mutex::new(EventQueue::default()));
+        let event_queue_for_delivery = event_queue.clone();
+        let event_reporter = EventReporter::new(event_queue, move || {
             // Ignore any failures; maybe we're resetting the CallEndpoint,
             // or in the process of quitting the app.
-            if let Some(js_object) = js_object_weak.upgrade() {
-                let event_reported_for_callback = event_reported.clone();
-                let _ = js_channel.try_send(move |mut cx| {
-                    // We aren't using this for synchronizing any other memory state,
-                    // so Relaxed is good enough.
-                    // But we have to do it before the items are actually processed,
-                    // because otherwise a new event could come in *during* the processing.
-                    event_reported_for_callback.store(false, std::sync::atomic::Ordering::Relaxed);
-
-                    let result = cx.try_catch(|cx| {
-                        let observer = js_object.as_ref().to_inner(cx);
-                        let method_name = "processEvents";
-                        let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
-                        method.call(cx, observer, [])?;
-                        Ok(())
-                    });
-                    match result {
-                        Ok(_) => {}
-                        Err(e) => {
-                            error!(
-                                "{}",
-                                e.to_string(&mut cx)
-                                    .map(|s| s.value(&mut cx))
-                                    .unwrap_or_else(|_| "[failed to stringify]".to_string())
-                            );
-                        }
-                    }
-                    Ok(())
-                });
+            let scheduled = js_object_weak.upgrade().is_some_and(|js_object| {
+                let event_queue = event_queue_for_delivery.clone();
+                js_channel
+                    .try_send(move |cx| deliver_events(cx, &js_object, &event_queue))
+                    .is_ok()
+            });
+            if !scheduled {
+                EventQueue::unschedule_delivery(&event_queue_for_delivery);
             }
         });
 
@@ -453,6 +649,11 @@ impl CallEndpoint {
             info!("audio_device_module using cubeb backend {:?}", backend);
         }
 
+        let event_reporter_for_devices = event_reporter.clone();
+        peer_connection_factory.set_audio_device_change_listener(Some(Box::new(move || {
+            let _ = event_reporter_for_devices.send(Event::AudioDevicesChanged);
+        })));
+
         // Only relevant for 1:1 calls
         let signaling_sender = Box::new(event_reporter.clone());
         let should_assume_messages_sent = false; // Use async notification from app to send next message.
@@ -473,7 +674,6 @@ impl CallEndpoint {
 
         Ok(Self {
             call_manager,
-            events_receiver,
             event_reporter,
             outgoing_audio_track,
             outgoing_video_source,
@@ -482,8 +682,27 @@ impl CallEndpoint {
             peer_connection_factory,
             js_object,
             most_recent_overlarge_frame_dimensions: (0, 0),
+            outgoing_video_format: (MAX_VIDEO_WIDTH, MAX_VIDEO_HEIGHT, MAX_VIDEO_FPS),
+            outgoing_video_is_screen_content: false,
+            camera_video_limits: Default::default(),
+            screenshare_video_limits: Default::default(),
         })
     }
+
+    /// Adapt outgoing video to the given format, within the app's limits for
+    /// the kind of content being sent.
+    fn adapt_outgoing_video(&mut self, format: (u16, u16, u8), is_screen_content: bool) {
+        self.outgoing_video_format = format;
+        self.outgoing_video_is_screen_content = is_screen_content;
+        let limits = if is_screen_content {
+            self.screenshare_video_limits
+        } else {
+            self.camera_video_limits
+        };
+        let (width, height, fps) = limits.apply(format);
+        self.outgoing_video_source
+            .adapt_output_format(width, height, fps);
+    }
 }
 
 #[derive(Clone, Default)]
@@ -544,7 +763,7 @@ fn get_id_arg(cx: &mut FunctionContext, i: usize) -> u64 {
     id
 }
 
-fn create_id_arg<'a>(cx: &mut FunctionContext<'a>, id: u64) -> Handle<'a, JsValue> {
+fn create_id_arg<'a>(cx: &mut impl Context<'a>, id: u64) -> Handle<'a, JsValue> {
     let high = cx.number(u64_to_js_num((id >> 32) & 0xFFFFFFFF));
     let low = cx.number(u64_to_js_num(id & 0xFFFFFFFF));
     let unsigned = cx.boolean(true);
@@ -555,17 +774,14 @@ fn create_id_arg<'a>(cx: &mut FunctionContext<'a>, id: u64) -> Handle<'a, JsValu
     obj.upcast()
 }
 
-fn to_js_buffer<'a>(cx: &mut FunctionContext<'a>, data: &[u8]) -> Handle<'a, JsValue> {
+fn to_js_buffer<'a>(cx: &mut impl Context<'a>, data: &[u8]) -> Handle<'a, JsValue> {
     let mut js_buffer = cx.buffer(data.len()).expect("create Buffer");
     js_buffer.as_mut_slice(cx).copy_from_slice(data.as_ref());
 
     js_buffer.upcast()
 }
 
-fn to_js_peek_info<'a>(
-    cx: &mut FunctionContext<'a>,
-    peek_info: PeekInfo,
-) -> JsResult<'a, JsObject> {
+fn to_js_peek_info<'a>(cx: &mut impl Context<'a>, peek_info: PeekInfo) -> JsResult<'a, JsObject> {
     let PeekInfo {
         devices,
         pending_devices: _pending_devices,
@@ -580,6 +796,8 @@ fn to_js_peek_info<'a>(
         let js_device = cx.empty_object();
         let js_demux_id = cx.number(device.demux_id);
         js_device.set(cx, "demuxId", js_demux_id)?;
+        let js_participant_kind = cx.number(device.participant_kind as i32);
+        js_device.set(cx, "participantKind", js_participant_kind)?;
         if let Some(user_id) = &device.user_id {
             let js_user_id = to_js_buffer(cx, user_id);
             js_device.set(cx, "userId", js_user_id)?;
@@ -636,7 +854,7 @@ fn to_js_peek_info<'a>(
 }
 
 fn to_js_call_link_state<'a>(
-    cx: &mut FunctionContext<'a>,
+    cx: &mut impl Context<'a>,
     state: Option<&CallLinkState>,
 ) -> JsResult<'a, JsValue> {
     match state {
@@ -662,18 +880,337 @@ fn to_js_call_link_state<'a>(
                 )
                 .or_else(|e| cx.throw_range_error(e.to_string()))?;
             state_object.set(cx, "expiration", js_expiration)?;
+            let js_description = cx.string(&state.metadata.description);
+            state_object.set(cx, "description", js_description)?;
+            if let Some(accent_color) = state.metadata.accent_color {
+                let js_accent_color = cx.number(accent_color);
+                state_object.set(cx, "accentColor", js_accent_color)?;
+            }
+            if let Some(avatar) = &state.metadata.avatar {
+                let js_avatar = cx.string(avatar);
+                state_object.set(cx, "avatar", js_avatar)?;
+            }
             Ok(state_object.upcast())
         }
         None => Ok(cx.undefined().upcast()),
     }
 }
 
+fn to_js_call_summary<'a>(
+    cx: &mut impl Context<'a>,
+    summary: &CallSummary,
+) -> JsResult<'a, JsObject> {
+    let media_usage = &summary.media_usage;
+    let js_summary = cx.empty_object();
+    let js_duration = cx.number(summary.duration.as_millis() as f64);
+    js_summary.set(cx, "durationMillis", js_duration)?;
+    let js_audio_bytes_sent = cx.number(u64_to_js_num(media_usage.audio_bytes_sent));
+    js_summary.set(cx, "audioBytesSent", js_audio_bytes_sent)?;
+    let js_audio_bytes_received = cx.number(u64_to_js_num(media_usage.audio_bytes_received));
+    js_summary.set(cx, "audioBytesReceived", js_audio_bytes_received)?;
+    let js_video_bytes_sent = cx.number(u64_to_js_num(media_usage.video_bytes_sent));
+    js_summary.set(cx, "videoBytesSent", js_video_bytes_sent)?;
+    let js_video_bytes_received = cx.number(u64_to_js_num(media_usage.video_bytes_received));
+    js_summary.set(cx, "videoBytesReceived", js_video_bytes_received)?;
+    let js_average_round_trip_time: Handle<JsValue> = match media_usage.average_round_trip_time() {
+        None => cx.undefined().upcast(),
+        Some(rtt) => cx.number(rtt.as_secs_f64() * 1000.0).upcast(),
+    };
+    js_summary.set(cx, "averageRoundTripTimeMillis", js_average_round_trip_time)?;
+    let js_average_mos = to_js_mos(cx, media_usage.average_mos());
+    js_summary.set(cx, "averageMos", js_average_mos)?;
+    let js_worst_minute_mos = to_js_mos(cx, media_usage.worst_minute_mos());
+    js_summary.set(cx, "worstMinuteMos", js_worst_minute_mos)?;
+    let js_max_participant_count = cx.number(summary.max_participant_count);
+    js_summary.set(cx, "maxParticipantCount", js_max_participant_count)?;
+    let js_stats_time_series = cx.string(summary.stats_time_series.export());
+    js_summary.set(cx, "statsTimeSeries", js_stats_time_series)?;
+    let latencies = &summary.setup_latencies;
+    let js_setup_latencies = cx.empty_object();
+    for (key, latency) in [
+        ("timeToRingMillis", latencies.time_to_ring),
+        ("timeToConnectMillis", latencies.time_to_connect),
+        ("timeToFirstFrameMillis", latencies.time_to_first_frame),
+    ] {
+        if let Some(latency) = latency {
+            let js_latency = cx.number(latency.as_millis() as f64);
+            js_setup_latencies.set(cx, key, js_latency)?;
+        }
+    }
+    js_summary.set(cx, "setupLatencies", js_setup_latencies)?;
+    Ok(js_summary)
+}
+
+fn to_js_mos<'a>(cx: &mut impl Context<'a>, mos: Option<f32>) -> Handle<'a, JsValue> {
+    match mos {
+        None => cx.undefined().upcast(),
+        Some(mos) => cx.number(mos).upcast(),
+    }
+}
+
+fn to_js_video_codec<'a>(
+    cx: &mut impl Context<'a>,
+    codec: Option<RffiVideoCodecType>,
+) -> Handle<'a, JsValue> {
+    match codec {
+        None => cx.undefined().upcast(),
+        Some(RffiVideoCodecType::Vp8) => cx.string("VP8").upcast(),
+        Some(RffiVideoCodecType::Vp9) => cx.string("VP9").upcast(),
+    }
+}
+
+fn to_js_quality_limitation_reason<'a>(
+    cx: &mut impl Context<'a>,
+    reason: Option<QualityLimitationReason>,
+) -> Handle<'a, JsValue> {
+    match reason {
+        None => cx.undefined().upcast(),
+        Some(reason) => cx.number(reason as i32).upcast(),
+    }
+}
+
+fn to_js_outbound_stream_stats<'a>(
+    cx: &mut impl Context<'a>,
+    stats: &OutboundStreamStats,
+) -> JsResult<'a, JsObject> {
+    let js_stats = cx.empty_object();
+    let js_ssrc = cx.number(stats.ssrc);
+    js_stats.set(cx, "ssrc", js_ssrc)?;
+    let js_bitrate = cx.number(stats.bitrate_bps);
+    js_stats.set(cx, "bitrateBps", js_bitrate)?;
+    let js_packets_per_second = cx.number(stats.packets_per_second);
+    js_stats.set(cx, "packetsPerSecond", js_packets_per_second)?;
+    let js_packet_loss = cx.number(stats.remote_packets_lost_pct);
+    js_stats.set(cx, "remotePacketLossPercent", js_packet_loss)?;
+    let js_jitter = cx.number(stats.remote_jitter * 1000.0);
+    js_stats.set(cx, "remoteJitterMillis", js_jitter)?;
+    let js_round_trip_time = cx.number(stats.remote_round_trip_time * 1000.0);
+    js_stats.set(cx, "remoteRoundTripTimeMillis", js_round_trip_time)?;
+    let js_framerate = cx.number(stats.framerate);
+    js_stats.set(cx, "framerate", js_framerate)?;
+    let js_frame_width = cx.number(stats.frame_width);
+    js_stats.set(cx, "frameWidth", js_frame_width)?;
+    let js_frame_height = cx.number(stats.frame_height);
+    js_stats.set(cx, "frameHeight", js_frame_height)?;
+    let js_codec = to_js_video_codec(cx, stats.codec);
+    js_stats.set(cx, "codec", js_codec)?;
+    let js_reason = to_js_quality_limitation_reason(cx, stats.quality_limitation_reason);
+    js_stats.set(cx, "qualityLimitationReason", js_reason)?;
+    Ok(js_stats)
+}
+
+fn to_js_inbound_stream_stats<'a>(
+    cx: &mut impl Context<'a>,
+    stats: &InboundStreamStats,
+) -> JsResult<'a, JsObject> {
+    let js_stats = cx.empty_object();
+    let js_ssrc = cx.number(stats.ssrc);
+    js_stats.set(cx, "ssrc", js_ssrc)?;
+    let js_bitrate = cx.number(stats.bitrate_bps);
+    js_stats.set(cx, "bitrateBps", js_bitrate)?;
+    let js_packets_per_second = cx.number(stats.packets_per_second);
+    js_stats.set(cx, "packetsPerSecond", js_packets_per_second)?;
+    let js_packet_loss = cx.number(stats.packets_lost_pct);
+    js_stats.set(cx, "packetLossPercent", js_packet_loss)?;
+    let js_jitter = cx.number(stats.jitter * 1000.0);
+    js_stats.set(cx, "jitterMillis", js_jitter)?;
+    let js_jitter_buffer_delay = cx.number(stats.jitter_buffer_delay * 1000.0);
+    js_stats.set(cx, "jitterBufferDelayMillis", js_jitter_buffer_delay)?;
+    let js_framerate = cx.number(stats.framerate);
+    js_stats.set(cx, "framerate", js_framerate)?;
+    let js_frames_decoded = cx.number(stats.frames_decoded);
+    js_stats.set(cx, "framesDecoded", js_frames_decoded)?;
+    let js_frames_dropped = cx.number(stats.frames_dropped);
+    js_stats.set(cx, "framesDropped", js_frames_dropped)?;
+    let js_freeze_count = cx.number(stats.freeze_count);
+    js_stats.set(cx, "freezeCount", js_freeze_count)?;
+    let js_frame_width = cx.number(stats.frame_width);
+    js_stats.set(cx, "frameWidth", js_frame_width)?;
+    let js_frame_height = cx.number(stats.frame_height);
+    js_stats.set(cx, "frameHeight", js_frame_height)?;
+    let js_codec = to_js_video_codec(cx, stats.codec);
+    js_stats.set(cx, "codec", js_codec)?;
+    Ok(js_stats)
+}
+
+fn to_js_transport_protocol<'a>(
+    cx: &mut impl Context<'a>,
+    protocol: TransportProtocol,
+) -> Handle<'a, JsValue> {
+    match protocol {
+        TransportProtocol::Udp => cx.string("udp").upcast(),
+        TransportProtocol::Tcp => cx.string("tcp").upcast(),
+        TransportProtocol::Tls => cx.string("tls").upcast(),
+        TransportProtocol::Unknown => cx.undefined().upcast(),
+    }
+}
+
+fn to_js_candidate_pairs<'a>(
+    cx: &mut impl Context<'a>,
+    candidate_pairs: &[CandidatePairStatistics],
+) -> JsResult<'a, JsArray> {
+    let js_pairs = JsArray::new(cx, candidate_pairs.len());
+    for (i, pair) in candidate_pairs.iter().enumerate() {
+        let js_pair = cx.empty_object();
+        let js_local_candidate_type = cx.number(pair.local_candidate_type as i32);
+        js_pair.set(cx, "localCandidateType", js_local_candidate_type)?;
+        let js_local_protocol = to_js_transport_protocol(cx, pair.local_protocol);
+        js_pair.set(cx, "localProtocol", js_local_protocol)?;
+        let js_local_relay_protocol = to_js_transport_protocol(cx, pair.local_relay_protocol);
+        js_pair.set(cx, "localRelayProtocol", js_local_relay_protocol)?;
+        let js_remote_candidate_type = cx.number(pair.remote_candidate_type as i32);
+        js_pair.set(cx, "remoteCandidateType", js_remote_candidate_type)?;
+        let js_state = cx.string(match pair.state {
+            CandidatePairState::Frozen => "frozen",
+            CandidatePairState::Waiting => "waiting",
+            CandidatePairState::InProgress => "in-progress",
+            CandidatePairState::Failed => "failed",
+            CandidatePairState::Succeeded => "succeeded",
+        });
+        js_pair.set(cx, "state", js_state)?;
+        let js_nominated = cx.boolean(pair.nominated);
+        js_pair.set(cx, "nominated", js_nominated)?;
+        let js_selected = cx.boolean(pair.selected);
+        js_pair.set(cx, "selected", js_selected)?;
+        let js_round_trip_time = cx.number(pair.current_round_trip_time * 1000.0);
+        js_pair.set(cx, "roundTripTimeMillis", js_round_trip_time)?;
+        let js_bytes_sent = cx.number(pair.bytes_sent as f64);
+        js_pair.set(cx, "bytesSent", js_bytes_sent)?;
+        let js_bytes_received = cx.number(pair.bytes_received as f64);
+        js_pair.set(cx, "bytesReceived", js_bytes_received)?;
+        js_pairs.set(cx, i as u32, js_pair)?;
+    }
+    Ok(js_pairs)
+}
+
+fn to_js_stats_snapshot<'a>(
+    cx: &mut impl Context<'a>,
+    snapshot: Option<&StatsSnapshot>,
+) -> JsResult<'a, JsValue> {
+    let snapshot = match snapshot {
+        Some(snapshot) => snapshot,
+        None => return Ok(cx.undefined().upcast()),
+    };
+
+    let js_snapshot = cx.empty_object();
+    let js_timestamp = cx.number(snapshot.timestamp_us as f64 / 1000.0);
+    js_snapshot.set(cx, "timestampMillis", js_timestamp)?;
+    let js_round_trip_time = cx.number(snapshot.round_trip_time * 1000.0);
+    js_snapshot.set(cx, "roundTripTimeMillis", js_round_trip_time)?;
+    let js_available_outgoing_bitrate = cx.number(snapshot.available_outgoing_bitrate_bps);
+    js_snapshot.set(
+        cx,
+        "availableOutgoingBitrateBps",
+        js_available_outgoing_bitrate,
+    )?;
+    for (key, streams) in [
+        ("audioSend", &snapshot.audio_send),
+        ("videoSend", &snapshot.video_send),
+    ] {
+        let js_streams = JsArray::new(cx, streams.len());
+        for (i, stream) in streams.iter().enumerate() {
+            let js_stream = to_js_outbound_stream_stats(cx, stream)?;
+            js_streams.set(cx, i as u32, js_stream)?;
+        }
+        js_snapshot.set(cx, key, js_streams)?;
+    }
+    for (key, streams) in [
+        ("audioReceive", &snapshot.audio_recv),
+        ("videoReceive", &snapshot.video_recv),
+    ] {
+        let js_streams = JsArray::new(cx, streams.len());
+        for (i, stream) in streams.iter().enumerate() {
+            let js_stream = to_js_inbound_stream_stats(cx, stream)?;
+            js_streams.set(cx, i as u32, js_stream)?;
+        }
+        js_snapshot.set(cx, key, js_streams)?;
+    }
+    let js_jitter_buffer = cx.empty_object();
+    for (key, delay) in [
+        ("targetDelayMillis", snapshot.jitter_buffer.target_delay),
+        ("maxDelayMillis", snapshot.jitter_buffer.max_delay),
+    ] {
+        if let Some(delay) = delay {
+            let js_delay = cx.number(delay.as_millis() as f64);
+            js_jitter_buffer.set(cx, key, js_delay)?;
+        }
+    }
+    js_snapshot.set(cx, "jitterBuffer", js_jitter_buffer)?;
+    let js_av_sync = JsArray::new(cx, snapshot.av_sync.len());
+    for (i, av_sync) in snapshot.av_sync.iter().enumerate() {
+        let js_stats = cx.empty_object();
+        let js_audio_ssrc = cx.number(av_sync.audio_ssrc);
+        js_stats.set(cx, "audioSsrc", js_audio_ssrc)?;
+        let js_video_ssrc = cx.number(av_sync.video_ssrc);
+        js_stats.set(cx, "videoSsrc", js_video_ssrc)?;
+        let js_skew = cx.number(av_sync.skew * 1000.0);
+        js_stats.set(cx, "skewMillis", js_skew)?;
+        js_av_sync.set(cx, i as u32, js_stats)?;
+    }
+    js_snapshot.set(cx, "avSync", js_av_sync)?;
+    let js_mos = to_js_mos(cx, snapshot.mos);
+    js_snapshot.set(cx, "mos", js_mos)?;
+    let js_minute_mos = to_js_mos(cx, snapshot.minute_mos);
+    js_snapshot.set(cx, "minuteMos", js_minute_mos)?;
+    let js_candidate_pairs = to_js_candidate_pairs(cx, &snapshot.candidate_pairs)?;
+    js_snapshot.set(cx, "candidatePairs", js_candidate_pairs)?;
+    Ok(js_snapshot.upcast())
+}
+
+fn video_negotiation_event_string(event: VideoNegotiationEvent) -> &'static str {
+    match event {
+        VideoNegotiationEvent::RemoteAddRequested => "RemoteAddRequested",
+        VideoNegotiationEvent::RemoteAdded => "RemoteAdded",
+        VideoNegotiationEvent::RemoteRemoved => "RemoteRemoved",
+        VideoNegotiationEvent::LocalAddAccepted => "LocalAddAccepted",
+        VideoNegotiationEvent::LocalAddDeclined => "LocalAddDeclined",
+    }
+}
+
+fn end_reason_string(reason: &EndReason) -> &'static str {
+    match reason {
+        EndReason::LocalHangup => "LocalHangup",
+        EndReason::RemoteHangup => "RemoteHangup",
+        EndReason::RemoteHangupNeedPermission => "RemoteHangupNeedPermission",
+        EndReason::Declined => "Declined",
+        EndReason::Busy => "Busy",
+        EndReason::Glare => "Glare",
+        EndReason::ReCall => "ReCall",
+        EndReason::ReceivedOfferExpired { .. } => "ReceivedOfferExpired",
+        EndReason::ReceivedOfferWhileActive => "ReceivedOfferWhileActive",
+        EndReason::ReceivedOfferWithGlare => "ReceivedOfferWithGlare",
+        EndReason::SignalingFailure => "SignalingFailure",
+        EndReason::GlareFailure => "GlareFailure",
+        EndReason::ConnectionFailure => "ConnectionFailure",
+        EndReason::InternalFailure => "InternalFailure",
+        EndReason::Timeout => "Timeout",
+        EndReason::AcceptedOnAnotherDevice => "AcceptedOnAnotherDevice",
+        EndReason::DeclinedOnAnotherDevice => "DeclinedOnAnotherDevice",
+        EndReason::BusyOnAnotherDevice => "BusyOnAnotherDevice",
+        EndReason::DeclinedBusy => "DeclinedBusy",
+        EndReason::DeclinedDoNotDisturb => "DeclinedDoNotDisturb",
+        EndReason::DeclinedTextReplySent => "DeclinedTextReplySent",
+        EndReason::HandedOff => "HandedOff",
+        EndReason::MediaStalled => "MediaStalled",
+        EndReason::RemoteHangupNetworkFailure => "RemoteHangupNetworkFailure",
+        EndReason::DeclinedByPolicy => "DeclinedByPolicy",
+        EndReason::RemoteHangupRingTimeout => "RemoteHangupRingTimeout",
+    }
+}
+
 static CALL_ENDPOINT_PROPERTY_KEY: &str = "__call_endpoint_addr";
 
 fn with_call_endpoint<T>(cx: &mut FunctionContext, body: impl FnOnce(&mut CallEndpoint) -> T) -> T {
-    let endpoint = cx
-        .this::<JsObject>()
-        .expect("this is an object")
+    let this = cx.this::<JsObject>().expect("this is an object");
+    with_call_endpoint_object(cx, this, body)
+}
+
+fn with_call_endpoint_object<'a, T>(
+    cx: &mut impl Context<'a>,
+    js_object: Handle<'a, JsObject>,
+    body: impl FnOnce(&mut CallEndpoint) -> T,
+) -> T {
+    let endpoint = js_object
         .get::<JsBox<RefCell<CallEndpoint>>, _, _>(cx, CALL_ENDPOINT_PROPERTY_KEY)
         .expect("has endpoint");
     let mut endpoint = endpoint.borrow_mut();
@@ -720,6 +1257,77 @@ fn createCallEndpoint(mut cx: FunctionContext) -> JsResult<JsValue> {
     Ok(cx.boxed(RefCell::new(endpoint)).upcast())
 }
 
+#[allow(non_snake_case)]
+fn setMaxLogLevel(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let level = cx.argument::<JsNumber>(0)?.value(&mut cx) as u8;
+    match logging::level_filter_from_raw(level) {
+        Some(level_filter) => logging::set_max_log_level(level_filter),
+        None => return cx.throw_error(format!("invalid log level {}", level)),
+    }
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setModuleLogLevel(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let module = cx.argument::<JsString>(0)?.value(&mut cx);
+    let level = cx.argument::<JsNumber>(1)?.value(&mut cx) as u8;
+    match logging::level_filter_from_raw(level) {
+        Some(level_filter) => logging::set_module_log_level(&module, level_filter),
+        None => return cx.throw_error(format!("invalid log level {}", level)),
+    }
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn clearModuleLogLevels(mut cx: FunctionContext) -> JsResult<JsValue> {
+    logging::clear_module_log_levels();
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn getSetupLatencyHistograms(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let histograms = setup_latency::histograms();
+    let js_histograms = cx.empty_object();
+    let js_bounds = JsArray::new(&mut cx, setup_latency::BUCKET_BOUNDS_MILLIS.len());
+    for (i, bound) in setup_latency::BUCKET_BOUNDS_MILLIS.iter().enumerate() {
+        let js_bound = cx.number(*bound as f64);
+        js_bounds.set(&mut cx, i as u32, js_bound)?;
+    }
+    js_histograms.set(&mut cx, "bucketBoundsMillis", js_bounds)?;
+    for (key, histogram) in [
+        ("timeToRing", &histograms.time_to_ring),
+        ("timeToConnect", &histograms.time_to_connect),
+        ("timeToFirstFrame", &histograms.time_to_first_frame),
+    ] {
+        let js_counts = JsArray::new(&mut cx, histogram.counts.len());
+        for (i, count) in histogram.counts.iter().enumerate() {
+            let js_count = cx.number(*count);
+            js_counts.set(&mut cx, i as u32, js_count)?;
+        }
+        js_histograms.set(&mut cx, key, js_counts)?;
+    }
+    if cx.argument::<JsBoolean>(0)?.value(&mut cx) {
+        setup_latency::reset_histograms();
+    }
+    Ok(js_histograms.upcast())
+}
+
+#[allow(non_snake_case)]
+fn exportDiagnostics(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let exported = diagnostics::export();
+    let mut js_exported = cx.buffer(exported.len())?;
+    js_exported.as_mut_slice(&mut cx).copy_from_slice(&exported);
+    Ok(js_exported.upcast())
+}
+
+#[allow(non_snake_case)]
+fn setCrashDumpPath(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let path = cx.argument::<JsString>(0)?.value(&mut cx);
+    let file = std::fs::File::create(path).or_else(|err| cx.throw_error(format!("{}", err)))?;
+    crash_dump::set_dump_file(file);
+    Ok(cx.undefined().upcast())
+}
+
 #[allow(non_snake_case)]
 fn setSelfUuid(mut cx: FunctionContext) -> JsResult<JsValue> {
     debug!("JsCallManager.setSelfUuid()");
@@ -735,11 +1343,45 @@ fn setSelfUuid(mut cx: FunctionContext) -> JsResult<JsValue> {
     Ok(cx.undefined().upcast())
 }
 
+#[allow(non_snake_case)]
+fn setGroupCallIntervals(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let heartbeat_millis = cx.argument::<JsNumber>(0)?.value(&mut cx) as u64;
+    let stats_millis = cx.argument::<JsNumber>(1)?.value(&mut cx) as u64;
+    let peek_poll_millis = cx.argument::<JsNumber>(2)?.value(&mut cx) as u64;
+    debug!(
+        "JsCallManager.setGroupCallIntervals({}, {}, {})",
+        heartbeat_millis, stats_millis, peek_poll_millis
+    );
+
+    // Zero means "use the default".
+    let defaults = group_call::GroupCallIntervals::default();
+    let interval_or_default = |millis: u64, default: Duration| {
+        if millis == 0 {
+            default
+        } else {
+            Duration::from_millis(millis)
+        }
+    };
+    let intervals = group_call::GroupCallIntervals {
+        heartbeat: interval_or_default(heartbeat_millis, defaults.heartbeat),
+        stats: interval_or_default(stats_millis, defaults.stats),
+        peek_poll: interval_or_default(peek_poll_millis, defaults.peek_poll),
+    };
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.set_group_call_intervals(intervals)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
 #[allow(non_snake_case)]
 fn createOutgoingCall(mut cx: FunctionContext) -> JsResult<JsValue> {
     let peer_id = cx.argument::<JsString>(0)?.value(&mut cx) as PeerId;
     let video_enabled = cx.argument::<JsBoolean>(1)?.value(&mut cx);
     let local_device_id = cx.argument::<JsNumber>(2)?.value(&mut cx) as DeviceId;
+    let app_data = cx.argument::<JsBuffer>(3)?.as_slice(&cx).to_vec();
 
     let media_type = if video_enabled {
         CallMediaType::Video
@@ -754,6 +1396,9 @@ fn createOutgoingCall(mut cx: FunctionContext) -> JsResult<JsValue> {
 
     let call_id = CallId::random();
     with_call_endpoint(&mut cx, |endpoint| {
+        if !app_data.is_empty() {
+            endpoint.call_manager.set_call_app_data(call_id, app_data)?;
+        }
         endpoint.call_manager.create_outgoing_call(
             peer_id,
             call_id,
@@ -766,6 +1411,34 @@ fn createOutgoingCall(mut cx: FunctionContext) -> JsResult<JsValue> {
     Ok(create_id_arg(&mut cx, call_id.as_u64()))
 }
 
+#[allow(non_snake_case)]
+fn takeOverCall(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let peer_id = cx.argument::<JsString>(0)?.value(&mut cx) as PeerId;
+    let call_id = CallId::new(get_id_arg(&mut cx, 1));
+    let video_enabled = cx.argument::<JsBoolean>(2)?.value(&mut cx);
+    let local_device_id = cx.argument::<JsNumber>(3)?.value(&mut cx) as DeviceId;
+
+    let media_type = if video_enabled {
+        CallMediaType::Video
+    } else {
+        CallMediaType::Audio
+    };
+
+    debug!(
+        "JsCallManager.takeOverCall({}, {}, {}, {})",
+        peer_id, call_id, media_type, local_device_id
+    );
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .take_over_call(peer_id, call_id, media_type, local_device_id)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
 #[allow(non_snake_case)]
 fn cancelGroupRing(mut cx: FunctionContext) -> JsResult<JsValue> {
     debug!("JsCallManager.cancelGroupRing()");
@@ -802,36 +1475,25 @@ fn cancelGroupRing(mut cx: FunctionContext) -> JsResult<JsValue> {
     Ok(cx.undefined().upcast())
 }
 
-#[allow(non_snake_case)]
-fn proceed(mut cx: FunctionContext) -> JsResult<JsValue> {
-    let call_id = CallId::new(get_id_arg(&mut cx, 0));
-    let js_ice_servers = cx.argument::<JsArray>(1)?;
-    let hide_ip = cx.argument::<JsBoolean>(2)?.value(&mut cx);
-    let data_mode = cx.argument::<JsNumber>(3)?.value(&mut cx) as i32;
-    let audio_levels_interval_millis = cx.argument::<JsNumber>(4)?.value(&mut cx) as u64;
-
-    info!("proceed(): callId: {}, hideIp: {}", call_id, hide_ip);
+fn get_ice_servers_arg(cx: &mut FunctionContext, index: usize) -> NeonResult<Vec<IceServer>> {
+    let js_ice_servers = cx.argument::<JsArray>(index)?;
     let mut ice_servers = Vec::new();
-    for i in 0..js_ice_servers.len(&mut cx) {
-        let obj = js_ice_servers.get::<JsObject, _, _>(&mut cx, i)?;
+    for i in 0..js_ice_servers.len(cx) {
+        let obj = js_ice_servers.get::<JsObject, _, _>(cx, i)?;
         let username = obj
-            .get_opt::<JsString, _, _>(&mut cx, "username")?
-            .map_or("".to_string(), |handle| handle.value(&mut cx));
+            .get_opt::<JsString, _, _>(cx, "username")?
+            .map_or("".to_string(), |handle| handle.value(cx));
         let password: String = obj
-            .get_opt::<JsString, _, _>(&mut cx, "password")?
-            .map_or("".to_string(), |handle| handle.value(&mut cx));
+            .get_opt::<JsString, _, _>(cx, "password")?
+            .map_or("".to_string(), |handle| handle.value(cx));
         let hostname = obj
-            .get_opt::<JsString, _, _>(&mut cx, "hostname")?
-            .map_or("".to_string(), |handle| handle.value(&mut cx));
-        let js_ice_server_urls = obj
-            .get_opt::<JsArray, _, _>(&mut cx, "urls")?
-            .expect("ice urls");
-
-        let mut ice_server_urls = Vec::with_capacity(js_ice_server_urls.len(&mut cx) as usize);
-        for i in 0..js_ice_server_urls.len(&mut cx) {
-            let url: String = js_ice_server_urls
-                .get::<JsString, _, _>(&mut cx, i)?
-                .value(&mut cx);
+            .get_opt::<JsString, _, _>(cx, "hostname")?
+            .map_or("".to_string(), |handle| handle.value(cx));
+        let js_ice_server_urls = obj.get_opt::<JsArray, _, _>(cx, "urls")?.expect("ice urls");
+
+        let mut ice_server_urls = Vec::with_capacity(js_ice_server_urls.len(cx) as usize);
+        for i in 0..js_ice_server_urls.len(cx) {
+            let url: String = js_ice_server_urls.get::<JsString, _, _>(cx, i)?.value(cx);
             info!("  server: {}", url);
             ice_server_urls.push(url);
         }
@@ -839,13 +1501,51 @@ fn proceed(mut cx: FunctionContext) -> JsResult<JsValue> {
         let ice_server = IceServer::new(username, password, hostname, ice_server_urls);
         ice_servers.push(ice_server);
     }
+    Ok(ice_servers)
+}
+
+#[allow(non_snake_case)]
+fn proceed(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let call_id = CallId::new(get_id_arg(&mut cx, 0));
+    let ice_servers = get_ice_servers_arg(&mut cx, 1)?;
+    let hide_ip = cx.argument::<JsBoolean>(2)?.value(&mut cx);
+    let data_mode = cx.argument::<JsNumber>(3)?.value(&mut cx) as i32;
+    let audio_levels_interval_millis = cx.argument::<JsNumber>(4)?.value(&mut cx) as u64;
+    let ring_timeout_millis = cx.argument::<JsNumber>(5)?.value(&mut cx) as u64;
+    let connect_timeout_millis = cx.argument::<JsNumber>(6)?.value(&mut cx) as u64;
+    let early_media = cx.argument::<JsBoolean>(7)?.value(&mut cx);
+    let media_stall_timeout_millis = cx.argument::<JsNumber>(8)?.value(&mut cx) as u64;
+    let hang_up_on_media_stall = cx.argument::<JsBoolean>(9)?.value(&mut cx);
+    let audio_profile = cx.argument::<JsNumber>(10)?.value(&mut cx) as i32;
+    let jitter_buffer = jitter_buffer_arg(&mut cx, 11)?;
 
+    info!("proceed(): callId: {}, hideIp: {}", call_id, hide_ip);
     let audio_levels_interval = if audio_levels_interval_millis == 0 {
         None
     } else {
         Some(Duration::from_millis(audio_levels_interval_millis))
     };
 
+    let mut call_config = CallConfig::default()
+        .with_data_mode(DataMode::from_i32(data_mode))
+        .with_relay_only(hide_ip)
+        .with_early_media(early_media)
+        .with_audio_profile(AudioProfile::from_i32(audio_profile))
+        .with_jitter_buffer(jitter_buffer);
+    if ring_timeout_millis > 0 {
+        call_config = call_config.with_ring_timeout(Duration::from_millis(ring_timeout_millis));
+    }
+    if connect_timeout_millis > 0 {
+        call_config =
+            call_config.with_connect_timeout(Duration::from_millis(connect_timeout_millis));
+    }
+    if media_stall_timeout_millis > 0 {
+        call_config = call_config.with_media_stall_timeout(
+            Duration::from_millis(media_stall_timeout_millis),
+            hang_up_on_media_stall,
+        );
+    }
+
     with_call_endpoint(&mut cx, |endpoint| {
         let call_context = NativeCallContext::new(
             hide_ip,
@@ -854,21 +1554,16 @@ fn proceed(mut cx: FunctionContext) -> JsResult<JsValue> {
             endpoint.outgoing_video_track.clone(),
             endpoint.incoming_video_sink.clone(),
         );
-        endpoint.outgoing_video_track.set_content_hint(false);
+        endpoint
+            .outgoing_video_track
+            .set_content_hint(VideoContentHint::None);
         // This should be cleared at with "call concluded", but just in case
         // we'll clear here as well.
         endpoint.incoming_video_sink.clear();
-        endpoint.outgoing_video_source.adapt_output_format(
-            MAX_VIDEO_WIDTH,
-            MAX_VIDEO_HEIGHT,
-            MAX_VIDEO_FPS,
-        );
-        endpoint.call_manager.proceed(
-            call_id,
-            call_context,
-            CallConfig::default().with_data_mode(DataMode::from_i32(data_mode)),
-            audio_levels_interval,
-        )?;
+        endpoint.adapt_outgoing_video((MAX_VIDEO_WIDTH, MAX_VIDEO_HEIGHT, MAX_VIDEO_FPS), false);
+        endpoint
+            .call_manager
+            .proceed(call_id, call_context, call_config, audio_levels_interval)?;
         Ok(())
     })
     .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
@@ -876,18 +1571,266 @@ fn proceed(mut cx: FunctionContext) -> JsResult<JsValue> {
 }
 
 #[allow(non_snake_case)]
-fn accept(mut cx: FunctionContext) -> JsResult<JsValue> {
+fn preGatherIce(mut cx: FunctionContext) -> JsResult<JsValue> {
     let call_id = CallId::new(get_id_arg(&mut cx, 0));
-    debug!("JsCallManager.accept({})", call_id);
+    let ice_servers = get_ice_servers_arg(&mut cx, 1)?;
+    info!("preGatherIce(): callId: {}", call_id);
 
     with_call_endpoint(&mut cx, |endpoint| {
-        endpoint.call_manager.accept_call(call_id)?;
-        Ok(())
-    })
-    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
-    Ok(cx.undefined().upcast())
-}
-
+        // Only relay candidates are gathered before proceeding.
+        let call_context = NativeCallContext::new(
+            true,
+            ice_servers,
+            endpoint.outgoing_audio_track.clone(),
+            endpoint.outgoing_video_track.clone(),
+            endpoint.incoming_video_sink.clone(),
+        );
+        endpoint
+            .call_manager
+            .pre_gather_ice(call_id, call_context)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setOnHold(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let call_id = CallId::new(get_id_arg(&mut cx, 0));
+    let on_hold = cx.argument::<JsBoolean>(1)?.value(&mut cx);
+    debug!("JsCallManager.setOnHold({}, {})", call_id, on_hold);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.set_on_hold(call_id, on_hold)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn handOff(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let call_id = CallId::new(get_id_arg(&mut cx, 0));
+    let remote_user_id = cx.argument::<JsBuffer>(1)?;
+    let remote_user_id = remote_user_id.as_slice(&cx).to_vec();
+    let target_device_id = cx.argument::<JsNumber>(2)?.value(&mut cx) as DeviceId;
+    debug!("JsCallManager.handOff({}, {})", call_id, target_device_id);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .hand_off(call_id, remote_user_id, target_device_id)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn restartIce(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let call_id = CallId::new(get_id_arg(&mut cx, 0));
+    debug!("JsCallManager.restartIce({})", call_id);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.restart_ice(call_id)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn enableVideo(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let call_id = CallId::new(get_id_arg(&mut cx, 0));
+    debug!("JsCallManager.enableVideo({})", call_id);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.enable_video(call_id)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn disableVideo(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let call_id = CallId::new(get_id_arg(&mut cx, 0));
+    debug!("JsCallManager.disableVideo({})", call_id);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.disable_video(call_id)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn getVerificationCode(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let call_id = CallId::new(get_id_arg(&mut cx, 0));
+    debug!("JsCallManager.getVerificationCode({})", call_id);
+
+    let verification_code = with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.verification_code(call_id)
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(match verification_code {
+        Some(verification_code) => cx.string(verification_code.to_string()).upcast(),
+        None => cx.null().upcast(),
+    })
+}
+
+#[allow(non_snake_case)]
+fn setCallAppData(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let call_id = CallId::new(get_id_arg(&mut cx, 0));
+    let app_data = cx.argument::<JsBuffer>(1)?.as_slice(&cx).to_vec();
+    debug!("JsCallManager.setCallAppData({})", call_id);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.set_call_app_data(call_id, app_data)
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn getWideCallId(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let call_id = CallId::new(get_id_arg(&mut cx, 0));
+    debug!("JsCallManager.getWideCallId({})", call_id);
+
+    let wide_call_id = with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.wide_call_id(call_id)
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(match wide_call_id {
+        Some(wide_call_id) => cx.string(wide_call_id.to_string()).upcast(),
+        None => cx.null().upcast(),
+    })
+}
+
+#[allow(non_snake_case)]
+fn respondToVideoRequest(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let call_id = CallId::new(get_id_arg(&mut cx, 0));
+    let accepted = cx.argument::<JsBoolean>(1)?.value(&mut cx);
+    debug!(
+        "JsCallManager.respondToVideoRequest({}, {})",
+        call_id, accepted
+    );
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .respond_to_video_request(call_id, accepted)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn sendAppData(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let call_id = CallId::new(get_id_arg(&mut cx, 0));
+    let data = cx.argument::<JsBuffer>(1)?.as_slice(&cx).to_vec();
+    let reliable = cx.argument::<JsBoolean>(2)?.value(&mut cx);
+    debug!(
+        "JsCallManager.sendAppData({}, {}, {})",
+        call_id,
+        data.len(),
+        reliable
+    );
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .send_app_data(call_id, data, reliable)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn startEarlyMedia(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let call_id = CallId::new(get_id_arg(&mut cx, 0));
+    debug!("JsCallManager.startEarlyMedia({})", call_id);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.start_early_media(call_id)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setRestartIceOnDisconnect(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let enabled = cx.argument::<JsBoolean>(0)?.value(&mut cx);
+    debug!("JsCallManager.setRestartIceOnDisconnect({})", enabled);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .set_restart_ice_on_disconnect(enabled)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setRelayOnlyByDefault(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let enabled = cx.argument::<JsBoolean>(0)?.value(&mut cx);
+    debug!("JsCallManager.setRelayOnlyByDefault({})", enabled);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.set_relay_only_by_default(enabled)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setMaxSignalingMessageSize(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let max_size = cx.argument::<JsNumber>(0)?.value(&mut cx) as usize; // saturating cast
+    debug!("JsCallManager.setMaxSignalingMessageSize({})", max_size);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .set_max_signaling_message_size((max_size > 0).then_some(max_size))?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn accept(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let call_id = CallId::new(get_id_arg(&mut cx, 0));
+    let options = AcceptOptions {
+        audio_muted: cx.argument::<JsBoolean>(1)?.value(&mut cx),
+        video_disabled: cx.argument::<JsBoolean>(2)?.value(&mut cx),
+    };
+    debug!("JsCallManager.accept({}, {:?})", call_id, options);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        // Disable the tracks before the media is enabled by the accept.
+        if options.audio_muted {
+            endpoint.outgoing_audio_track.set_enabled(false);
+        }
+        if options.video_disabled {
+            endpoint.outgoing_video_track.set_enabled(false);
+        }
+        endpoint
+            .call_manager
+            .accept_call_with_options(call_id, options)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
 #[allow(non_snake_case)]
 fn ignore(mut cx: FunctionContext) -> JsResult<JsValue> {
     let call_id = CallId::new(get_id_arg(&mut cx, 0));
@@ -913,6 +1856,86 @@ fn hangup(mut cx: FunctionContext) -> JsResult<JsValue> {
     Ok(cx.undefined().upcast())
 }
 
+#[allow(non_snake_case)]
+fn decline(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let call_id = CallId::new(get_id_arg(&mut cx, 0));
+    let reason = cx.argument::<JsNumber>(1)?.value(&mut cx) as i32;
+    debug!("JsCallManager.decline({}, {})", call_id, reason);
+
+    let reason = match signaling::DeclineReason::from_i32(reason) {
+        Some(reason) => reason,
+        None => return cx.throw_error(format!("invalid decline reason: {}", reason)),
+    };
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.decline(call_id, reason)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setCallWaitingEnabled(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let enabled = cx.argument::<JsBoolean>(0)?.value(&mut cx);
+    debug!("JsCallManager.setCallWaitingEnabled({})", enabled);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.set_call_waiting_enabled(enabled)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn holdAndAcceptWaitingCall(mut cx: FunctionContext) -> JsResult<JsValue> {
+    debug!("JsCallManager.holdAndAcceptWaitingCall()");
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.hold_and_accept_waiting_call()?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn declineWaitingCall(mut cx: FunctionContext) -> JsResult<JsValue> {
+    debug!("JsCallManager.declineWaitingCall()");
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.decline_waiting_call()?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn resumeHeldCall(mut cx: FunctionContext) -> JsResult<JsValue> {
+    debug!("JsCallManager.resumeHeldCall()");
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.resume_held_call()?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn hangupHeldCall(mut cx: FunctionContext) -> JsResult<JsValue> {
+    debug!("JsCallManager.hangupHeldCall()");
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.hangup_held_call()?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
 #[allow(non_snake_case)]
 fn signalingMessageSent(mut cx: FunctionContext) -> JsResult<JsValue> {
     let call_id = CallId::new(get_id_arg(&mut cx, 0));
@@ -939,6 +1962,144 @@ fn signalingMessageSendFailed(mut cx: FunctionContext) -> JsResult<JsValue> {
     Ok(cx.undefined().upcast())
 }
 
+#[allow(non_snake_case)]
+fn updateBitrateConfig(mut cx: FunctionContext) -> JsResult<JsValue> {
+    debug!("JsCallManager.updateBitrateConfig()");
+    let max_audio_bitrate_bps = cx.argument::<JsNumber>(0)?.value(&mut cx) as u64;
+    let max_video_bitrate_bps = cx.argument::<JsNumber>(1)?.value(&mut cx) as u64;
+    let max_total_bitrate_bps = cx.argument::<JsNumber>(2)?.value(&mut cx) as u64;
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        let active_connection = endpoint.call_manager.active_connection()?;
+        active_connection.update_bitrate_config(CallBitrateConfig::from_bps(
+            max_audio_bitrate_bps,
+            max_video_bitrate_bps,
+            max_total_bitrate_bps,
+        ))?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setMaxSendBitrate(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let call_id = CallId::new(get_id_arg(&mut cx, 0));
+    let audio_bps = cx.argument::<JsNumber>(1)?.value(&mut cx) as u64;
+    let camera_video_bps = cx.argument::<JsNumber>(2)?.value(&mut cx) as u64;
+    let screen_share_bps = cx.argument::<JsNumber>(3)?.value(&mut cx) as u64;
+    debug!("JsCallManager.setMaxSendBitrate({})", call_id);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.set_max_send_bitrate(
+            call_id,
+            MaxSendBitrate::from_bps(audio_bps, camera_video_bps, screen_share_bps),
+        )?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+/// Converts an interval in milliseconds to an optional Duration, where 0
+/// means none.
+fn audio_levels_interval_from_millis(millis: u64) -> Option<Duration> {
+    (millis > 0).then(|| Duration::from_millis(millis))
+}
+
+#[allow(non_snake_case)]
+fn setAudioLevelsInterval(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let interval_millis = cx.argument::<JsNumber>(0)?.value(&mut cx) as u64;
+    debug!("JsCallManager.setAudioLevelsInterval({})", interval_millis);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        let active_connection = endpoint.call_manager.active_connection()?;
+        active_connection
+            .set_audio_levels_interval(audio_levels_interval_from_millis(interval_millis))?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+fn audio_loss_protection_arg(
+    cx: &mut FunctionContext,
+    start: usize,
+) -> NeonResult<AudioLossProtectionConfig> {
+    let fec = cx.argument::<JsNumber>(start)?.value(cx) as i32;
+    let enable_loss_pct = cx.argument::<JsNumber>(start + 1)?.value(cx) as f32;
+    let disable_loss_pct = cx.argument::<JsNumber>(start + 2)?.value(cx) as f32;
+    Ok(AudioLossProtectionConfig {
+        fec: AudioLossProtectionMode::from_i32(fec),
+        enable_loss_pct,
+        disable_loss_pct,
+    })
+}
+
+#[allow(non_snake_case)]
+fn setAudioLossProtection(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let config = audio_loss_protection_arg(&mut cx, 0)?;
+    debug!("JsCallManager.setAudioLossProtection({:?})", config);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        let active_connection = endpoint.call_manager.active_connection()?;
+        active_connection.set_audio_loss_protection(config)?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+/// Negative delays leave the WebRTC default in place.
+fn jitter_buffer_arg(cx: &mut FunctionContext, start: usize) -> NeonResult<JitterBufferConfig> {
+    let target_delay_millis = cx.argument::<JsNumber>(start)?.value(cx);
+    let max_delay_millis = cx.argument::<JsNumber>(start + 1)?.value(cx);
+    let to_delay = |millis: f64| (millis >= 0.0).then(|| Duration::from_millis(millis as u64));
+    Ok(JitterBufferConfig {
+        target_delay: to_delay(target_delay_millis),
+        max_delay: to_delay(max_delay_millis),
+    })
+}
+
+#[allow(non_snake_case)]
+fn getStats(mut cx: FunctionContext) -> JsResult<JsValue> {
+    debug!("JsCallManager.getStats()");
+
+    let snapshot = with_call_endpoint(&mut cx, |endpoint| {
+        let active_connection = endpoint.call_manager.active_connection()?;
+        active_connection.stats_snapshot()
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    to_js_stats_snapshot(&mut cx, snapshot.as_ref())
+}
+
+#[allow(non_snake_case)]
+fn getCandidatePairs(mut cx: FunctionContext) -> JsResult<JsValue> {
+    debug!("JsCallManager.getCandidatePairs()");
+
+    let snapshot = with_call_endpoint(&mut cx, |endpoint| {
+        let active_connection = endpoint.call_manager.active_connection()?;
+        active_connection.stats_snapshot()
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    let candidate_pairs = snapshot.map(|snapshot| snapshot.candidate_pairs);
+    Ok(to_js_candidate_pairs(&mut cx, candidate_pairs.as_deref().unwrap_or_default())?.upcast())
+}
+
+#[allow(non_snake_case)]
+fn updateAudioProfile(mut cx: FunctionContext) -> JsResult<JsValue> {
+    debug!("JsCallManager.updateAudioProfile()");
+    let audio_profile = cx.argument::<JsNumber>(0)?.value(&mut cx) as i32;
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        let active_connection = endpoint.call_manager.active_connection()?;
+        active_connection.update_audio_profile(AudioProfile::from_i32(audio_profile))?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
 #[allow(non_snake_case)]
 fn updateDataMode(mut cx: FunctionContext) -> JsResult<JsValue> {
     debug!("JsCallManager.updateDataMode()");
@@ -1216,7 +2377,68 @@ fn setOutgoingVideoEnabled(mut cx: FunctionContext) -> JsResult<JsValue> {
         endpoint.outgoing_video_track.set_enabled(enabled);
         let mut active_connection = endpoint.call_manager.active_connection()?;
         active_connection.update_sender_status(signaling::SenderStatus {
-            video_enabled: Some(enabled),
+            video_enabled: Some(enabled),
+            ..Default::default()
+        })?;
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+/// Reads an optional VideoContentHint, falling back to Detailed for
+/// screenshare and None for camera video. A hint that doesn't match whether
+/// the video is a screenshare is ignored.
+fn get_content_hint_arg(
+    cx: &mut FunctionContext,
+    i: usize,
+    is_screenshare: bool,
+) -> VideoContentHint {
+    let default = if is_screenshare {
+        VideoContentHint::Detailed
+    } else {
+        VideoContentHint::None
+    };
+    cx.argument_opt(i)
+        .and_then(|arg| arg.downcast::<JsNumber, _>(cx).ok())
+        .and_then(|hint| VideoContentHint::from_i32(hint.value(cx) as i32))
+        .filter(|hint| hint.is_screen_content() == is_screenshare)
+        .unwrap_or(default)
+}
+
+/// The output format (width, height, fps) for screen content, or None for
+/// camera video, which has its own limits.
+fn screenshare_output_format(content_hint: VideoContentHint) -> Option<(u16, u16, u8)> {
+    match content_hint {
+        // No resolution limit
+        VideoContentHint::Detailed => Some((0, 0, SCREENSHARE_MAX_VIDEO_FPS)),
+        VideoContentHint::Text => Some((0, 0, SCREENSHARE_TEXT_MAX_VIDEO_FPS)),
+        VideoContentHint::None | VideoContentHint::Fluid => None,
+    }
+}
+
+#[allow(non_snake_case)]
+fn setOutgoingVideoIsScreenShare(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let is_screenshare = cx.argument::<JsBoolean>(0)?.value(&mut cx);
+    let content_hint = get_content_hint_arg(&mut cx, 1, is_screenshare);
+    debug!(
+        "JsCallManager.setOutgoingVideoIsScreenShare({}, {:?})",
+        is_screenshare, content_hint
+    );
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.outgoing_video_track.set_content_hint(content_hint);
+
+        let format = screenshare_output_format(content_hint).unwrap_or((
+            MAX_VIDEO_WIDTH,
+            MAX_VIDEO_HEIGHT,
+            MAX_VIDEO_FPS,
+        ));
+        endpoint.adapt_outgoing_video(format, content_hint.is_screen_content());
+
+        let mut active_connection = endpoint.call_manager.active_connection()?;
+        active_connection.update_sender_status(signaling::SenderStatus {
+            sharing_screen: Some(is_screenshare),
             ..Default::default()
         })?;
         Ok(())
@@ -1226,33 +2448,33 @@ fn setOutgoingVideoEnabled(mut cx: FunctionContext) -> JsResult<JsValue> {
 }
 
 #[allow(non_snake_case)]
-fn setOutgoingVideoIsScreenShare(mut cx: FunctionContext) -> JsResult<JsValue> {
+fn setOutgoingVideoLimits(mut cx: FunctionContext) -> JsResult<JsValue> {
     let is_screenshare = cx.argument::<JsBoolean>(0)?.value(&mut cx);
+    // 0 means no limit.
+    let max_width = cx.argument::<JsNumber>(1)?.value(&mut cx) as u16;
+    let max_height = cx.argument::<JsNumber>(2)?.value(&mut cx) as u16;
+    let max_fps = cx.argument::<JsNumber>(3)?.value(&mut cx) as u8;
+    let limits = VideoFormatLimits {
+        max_width: (max_width > 0).then_some(max_width),
+        max_height: (max_height > 0).then_some(max_height),
+        max_fps: (max_fps > 0).then_some(max_fps),
+    };
     debug!(
-        "JsCallManager.setOutgoingVideoIsScreenShare({})",
-        is_screenshare
+        "JsCallManager.setOutgoingVideoLimits({}, {:?})",
+        is_screenshare, limits
     );
 
     with_call_endpoint(&mut cx, |endpoint| {
-        endpoint
-            .outgoing_video_track
-            .set_content_hint(is_screenshare);
-
-        let (width, height, fps) = if is_screenshare {
-            // Remove limit
-            (0, 0, 0)
+        if is_screenshare {
+            endpoint.screenshare_video_limits = limits;
         } else {
-            (MAX_VIDEO_WIDTH, MAX_VIDEO_HEIGHT, MAX_VIDEO_FPS)
-        };
-        endpoint
-            .outgoing_video_source
-            .adapt_output_format(width, height, fps);
-
-        let mut active_connection = endpoint.call_manager.active_connection()?;
-        active_connection.update_sender_status(signaling::SenderStatus {
-            sharing_screen: Some(is_screenshare),
-            ..Default::default()
-        })?;
+            endpoint.camera_video_limits = limits;
+        }
+        // Takes effect right away, without restarting capture.
+        endpoint.adapt_outgoing_video(
+            endpoint.outgoing_video_format,
+            endpoint.outgoing_video_is_screen_content,
+        );
         Ok(())
     })
     .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
@@ -1265,6 +2487,18 @@ fn sendVideoFrame(mut cx: FunctionContext) -> JsResult<JsValue> {
     let height = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
     let pixel_format = cx.argument::<JsNumber>(2)?.value(&mut cx) as i32;
     let buffer = cx.argument::<JsBuffer>(3)?;
+    let rotation = match cx.argument_opt(4) {
+        Some(arg) if !arg.is_a::<JsUndefined, _>(&mut cx) => {
+            let degrees = arg
+                .downcast_or_throw::<JsNumber, _>(&mut cx)?
+                .value(&mut cx);
+            match VideoRotation::from_degrees(degrees as i32) {
+                Some(rotation) => rotation,
+                None => return cx.throw_error("Invalid rotation"),
+            }
+        }
+        _ => VideoRotation::None,
+    };
 
     let pixel_format = VideoPixelFormat::from_i32(pixel_format);
     if pixel_format.is_none() {
@@ -1272,7 +2506,8 @@ fn sendVideoFrame(mut cx: FunctionContext) -> JsResult<JsValue> {
     }
     let pixel_format = pixel_format.unwrap();
 
-    let frame = VideoFrame::copy_from_slice(width, height, pixel_format, buffer.as_slice(&cx));
+    let frame = VideoFrame::copy_from_slice(width, height, pixel_format, buffer.as_slice(&cx))
+        .with_rotation(rotation);
     with_call_endpoint(&mut cx, |endpoint| {
         endpoint.outgoing_video_source.push_frame(frame);
         Ok(())
@@ -1281,12 +2516,74 @@ fn sendVideoFrame(mut cx: FunctionContext) -> JsResult<JsValue> {
     Ok(cx.undefined().upcast())
 }
 
+#[allow(non_snake_case)]
+fn sendAudioSamples(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let samples = cx.argument::<JsTypedArray<i16>>(0)?;
+    let sample_rate_hz = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let channels = cx.argument::<JsNumber>(2)?.value(&mut cx) as usize;
+
+    let samples = samples.as_slice(&cx).to_vec();
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .peer_connection_factory
+            .audio_injector()?
+            .push(&samples, sample_rate_hz, channels)
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setAudioInjectionMode(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let mode = cx.argument::<JsNumber>(0)?.value(&mut cx) as i32;
+    debug!("JsCallManager.setAudioInjectionMode({})", mode);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        let mode = AudioInjectionMode::from_i32(mode)
+            .ok_or_else(|| anyhow::anyhow!("invalid audio injection mode: {}", mode))?;
+        endpoint
+            .peer_connection_factory
+            .audio_injector()?
+            .set_mode(mode)
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn clearInjectedAudio(mut cx: FunctionContext) -> JsResult<JsValue> {
+    debug!("JsCallManager.clearInjectedAudio()");
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.peer_connection_factory.audio_injector()?.clear()
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn shareSystemAudio(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let enabled = cx.argument::<JsBoolean>(0)?.value(&mut cx);
+    debug!("JsCallManager.shareSystemAudio({})", enabled);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.peer_connection_factory.share_system_audio(enabled)
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+/// Copies the next frame from the given remote into `rgba_buffer`, returning
+/// `[width, height, rotation]`. Unless `apply_rotation` is false, the frame is
+/// rotated upright first and the rotation is always 0. Otherwise it's up to
+/// the renderer to rotate the frame clockwise by `rotation` degrees.
 fn receive_video_frame<'a>(
     cx: &mut FunctionContext<'a>,
     mut rgba_buffer: Handle<JsBuffer>,
     demux_id: DemuxId,
     max_width: u32,
     max_height: u32,
+    apply_rotation: bool,
 ) -> JsResult<'a, JsValue> {
     let frame = with_call_endpoint(cx, |endpoint| {
         if let Some(frame) = endpoint.incoming_video_sink.pop(demux_id) {
@@ -1311,13 +2608,19 @@ fn receive_video_frame<'a>(
     });
 
     if let Some(frame) = frame {
-        let frame = frame.apply_rotation();
+        let frame = if apply_rotation {
+            frame.apply_rotation()
+        } else {
+            frame
+        };
         frame.to_rgba(rgba_buffer.as_mut_slice(cx));
         let js_width = cx.number(frame.width());
         let js_height = cx.number(frame.height());
-        let result = JsArray::new(cx, 2);
+        let js_rotation = cx.number(frame.rotation().degrees());
+        let result = JsArray::new(cx, 3);
         result.set(cx, 0, js_width)?;
         result.set(cx, 1, js_height)?;
+        result.set(cx, 2, js_rotation)?;
         Ok(result.upcast())
     } else {
         Ok(cx.undefined().upcast())
@@ -1329,7 +2632,23 @@ fn receiveVideoFrame(mut cx: FunctionContext) -> JsResult<JsValue> {
     let rgba_buffer = cx.argument::<JsBuffer>(0)?;
     let max_width = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32; // saturating cast
     let max_height = cx.argument::<JsNumber>(2)?.value(&mut cx) as u32; // saturating cast
-    receive_video_frame(&mut cx, rgba_buffer, 0, max_width, max_height)
+    let apply_rotation = get_apply_rotation_arg(&mut cx, 3);
+    receive_video_frame(
+        &mut cx,
+        rgba_buffer,
+        0,
+        max_width,
+        max_height,
+        apply_rotation,
+    )
+}
+
+/// Reads the optional argument for whether to rotate received frames
+/// upright, which defaults to true.
+fn get_apply_rotation_arg(cx: &mut FunctionContext, i: usize) -> bool {
+    cx.argument_opt(i)
+        .and_then(|arg| arg.downcast::<JsBoolean, _>(cx).ok())
+        .map_or(true, |apply_rotation| apply_rotation.value(cx))
 }
 
 // Group Calls
@@ -1341,7 +2660,15 @@ fn receiveGroupCallVideoFrame(mut cx: FunctionContext) -> JsResult<JsValue> {
     let rgba_buffer = cx.argument::<JsBuffer>(2)?;
     let max_width = cx.argument::<JsNumber>(3)?.value(&mut cx) as u32; // saturating cast
     let max_height = cx.argument::<JsNumber>(4)?.value(&mut cx) as u32; // saturating cast
-    receive_video_frame(&mut cx, rgba_buffer, remote_demux_id, max_width, max_height)
+    let apply_rotation = get_apply_rotation_arg(&mut cx, 5);
+    receive_video_frame(
+        &mut cx,
+        rgba_buffer,
+        remote_demux_id,
+        max_width,
+        max_height,
+        apply_rotation,
+    )
 }
 
 #[allow(non_snake_case)]
@@ -1350,6 +2677,7 @@ fn createGroupCallClient(mut cx: FunctionContext) -> JsResult<JsValue> {
     let sfu_url = cx.argument::<JsString>(1)?.value(&mut cx);
     let hkdf_extra_info = cx.argument::<JsValue>(2)?.as_value(&mut cx);
     let audio_levels_interval_millis = cx.argument::<JsNumber>(3)?.value(&mut cx) as u64;
+    let ice_servers = get_ice_servers_arg(&mut cx, 4)?;
 
     let mut client_id = group_call::INVALID_CLIENT_ID;
 
@@ -1374,10 +2702,13 @@ fn createGroupCallClient(mut cx: FunctionContext) -> JsResult<JsValue> {
     };
 
     with_call_endpoint(&mut cx, |endpoint| {
-        endpoint.outgoing_video_source.adapt_output_format(
-            GROUP_CALL_MAX_VIDEO_WIDTH,
-            GROUP_CALL_MAX_VIDEO_HEIGHT,
-            MAX_VIDEO_FPS,
+        endpoint.adapt_outgoing_video(
+            (
+                GROUP_CALL_MAX_VIDEO_WIDTH,
+                GROUP_CALL_MAX_VIDEO_HEIGHT,
+                MAX_VIDEO_FPS,
+            ),
+            false,
         );
 
         let peer_connection_factory = endpoint.peer_connection_factory.clone();
@@ -1393,6 +2724,7 @@ fn createGroupCallClient(mut cx: FunctionContext) -> JsResult<JsValue> {
             outgoing_audio_track,
             outgoing_video_track,
             Some(incoming_video_sink),
+            ice_servers,
         );
         if let Ok(v) = result {
             client_id = v;
@@ -1432,6 +2764,7 @@ fn createCallLinkCallClient(mut cx: FunctionContext) -> JsResult<JsValue> {
     } else {
         Some(Duration::from_millis(audio_levels_interval_millis))
     };
+    let ice_servers = get_ice_servers_arg(&mut cx, 6)?;
 
     let mut client_id = group_call::INVALID_CLIENT_ID;
 
@@ -1451,6 +2784,7 @@ fn createCallLinkCallClient(mut cx: FunctionContext) -> JsResult<JsValue> {
             outgoing_audio_track,
             outgoing_video_track,
             Some(incoming_video_sink),
+            ice_servers,
         );
         if let Ok(v) = result {
             client_id = v;
@@ -1491,7 +2825,9 @@ fn join(mut cx: FunctionContext) -> JsResult<JsValue> {
     let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
 
     with_call_endpoint(&mut cx, |endpoint| {
-        endpoint.outgoing_video_track.set_content_hint(false);
+        endpoint
+            .outgoing_video_track
+            .set_content_hint(VideoContentHint::None);
         endpoint.call_manager.join(client_id);
         Ok(())
     })
@@ -1507,7 +2843,9 @@ fn leave(mut cx: FunctionContext) -> JsResult<JsValue> {
         // When leaving, make sure outgoing media is stopped as soon as possible.
         endpoint.outgoing_audio_track.set_enabled(false);
         endpoint.outgoing_video_track.set_enabled(false);
-        endpoint.outgoing_video_track.set_content_hint(false);
+        endpoint
+            .outgoing_video_track
+            .set_content_hint(VideoContentHint::None);
         endpoint.call_manager.leave(client_id);
         Ok(())
     })
@@ -1523,7 +2861,9 @@ fn disconnect(mut cx: FunctionContext) -> JsResult<JsValue> {
         // When disconnecting, make sure outgoing media is stopped as soon as possible.
         endpoint.outgoing_audio_track.set_enabled(false);
         endpoint.outgoing_video_track.set_enabled(false);
-        endpoint.outgoing_video_track.set_content_hint(false);
+        endpoint
+            .outgoing_video_track
+            .set_content_hint(VideoContentHint::None);
         endpoint.call_manager.disconnect(client_id);
         Ok(())
     })
@@ -1580,25 +2920,17 @@ fn setPresenting(mut cx: FunctionContext) -> JsResult<JsValue> {
 fn setOutgoingGroupCallVideoIsScreenShare(mut cx: FunctionContext) -> JsResult<JsValue> {
     let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
     let is_screenshare = cx.argument::<JsBoolean>(1)?.value(&mut cx);
+    let content_hint = get_content_hint_arg(&mut cx, 2, is_screenshare);
 
     with_call_endpoint(&mut cx, |endpoint| {
-        endpoint
-            .outgoing_video_track
-            .set_content_hint(is_screenshare);
+        endpoint.outgoing_video_track.set_content_hint(content_hint);
 
-        let (width, height, fps) = if is_screenshare {
-            // Remove limit
-            (0, 0, 0)
-        } else {
-            (
-                GROUP_CALL_MAX_VIDEO_WIDTH,
-                GROUP_CALL_MAX_VIDEO_HEIGHT,
-                MAX_VIDEO_FPS,
-            )
-        };
-        endpoint
-            .outgoing_video_source
-            .adapt_output_format(width, height, fps);
+        let format = screenshare_output_format(content_hint).unwrap_or((
+            GROUP_CALL_MAX_VIDEO_WIDTH,
+            GROUP_CALL_MAX_VIDEO_HEIGHT,
+            MAX_VIDEO_FPS,
+        ));
+        endpoint.adapt_outgoing_video(format, content_hint.is_screen_content());
 
         endpoint
             .call_manager
@@ -1656,27 +2988,248 @@ fn groupRaiseHand(mut cx: FunctionContext) -> JsResult<JsValue> {
     Ok(cx.undefined().upcast())
 }
 
+#[allow(non_snake_case)]
+fn groupSendAppData(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+    let data = cx.argument::<JsBuffer>(1)?.as_slice(&cx).to_vec();
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.send_app_data(client_id, data);
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn groupLowerAllHands(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.lower_all_hands(client_id);
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn groupAssignBreakoutGroups(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+    let js_demux_ids = cx.argument::<JsArray>(1)?;
+    let js_breakout_group_ids = cx.argument::<JsArray>(2)?;
+
+    let len = js_demux_ids.len(&mut cx);
+    if js_breakout_group_ids.len(&mut cx) != len {
+        return cx.throw_range_error("demuxIds and breakoutGroupIds must be the same length");
+    }
+    let mut assignments = Vec::with_capacity(len as usize);
+    for i in 0..len {
+        let demux_id = js_demux_ids
+            .get::<JsNumber, _, _>(&mut cx, i)?
+            .value(&mut cx) as DemuxId;
+        let breakout_group_id = js_breakout_group_ids
+            .get::<JsNumber, _, _>(&mut cx, i)?
+            .value(&mut cx) as group_call::BreakoutGroupId;
+        assignments.push((demux_id, breakout_group_id));
+    }
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .assign_breakout_groups(client_id, assignments);
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn groupEndBreakoutGroups(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.end_breakout_groups(client_id);
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setLowerHandWhenSpeaking(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+    let enabled = cx.argument::<JsBoolean>(1)?.value(&mut cx);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .set_lower_hand_when_speaking(client_id, enabled);
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setSkipMediaKeysForBridgedParticipants(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+    let enabled = cx.argument::<JsBoolean>(1)?.value(&mut cx);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .set_skip_media_keys_for_bridged_participants(client_id, enabled);
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setLeaveWhenOwnDeviceJoins(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+    let enabled = cx.argument::<JsBoolean>(1)?.value(&mut cx);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .set_leave_when_own_device_joins(client_id, enabled);
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setDetectSpeakingWhileMuted(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+    let enabled = cx.argument::<JsBoolean>(1)?.value(&mut cx);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .set_detect_speaking_while_muted(client_id, enabled);
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setKeyRotationPolicy(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+    let kind = cx.argument::<JsNumber>(1)?.value(&mut cx) as i32;
+    let delay_millis = cx.argument::<JsNumber>(2)?.value(&mut cx) as u64;
+    let delay = Duration::from_millis(delay_millis);
+
+    let policy = match kind {
+        0 => group_call::KeyRotationPolicy::Immediate,
+        1 => group_call::KeyRotationPolicy::Debounced(delay),
+        2 => group_call::KeyRotationPolicy::Batched(delay),
+        _ => {
+            return cx.throw_range_error(format!("invalid key rotation policy: {}", kind));
+        }
+    };
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .set_key_rotation_policy(client_id, policy);
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
 #[allow(non_snake_case)]
 fn resendMediaKeys(mut cx: FunctionContext) -> JsResult<JsValue> {
     let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
 
-    with_call_endpoint(&mut cx, |endpoint| {
-        endpoint.call_manager.resend_media_keys(client_id);
-        Ok(())
-    })
-    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
-    Ok(cx.undefined().upcast())
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.resend_media_keys(client_id);
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setDataMode(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+    let data_mode = cx.argument::<JsNumber>(1)?.value(&mut cx) as i32;
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .set_data_mode(client_id, DataMode::from_i32(data_mode));
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setGroupCallAudioLevelsInterval(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+    let interval_millis = cx.argument::<JsNumber>(1)?.value(&mut cx) as u64;
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.set_audio_levels_interval(
+            client_id,
+            audio_levels_interval_from_millis(interval_millis),
+        );
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setGroupCallAudioLossProtection(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+    let config = audio_loss_protection_arg(&mut cx, 1)?;
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .set_audio_loss_protection(client_id, config);
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn getGroupCallStats(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+
+    let snapshot = with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.group_call_stats_snapshot(client_id)
+    });
+    to_js_stats_snapshot(&mut cx, snapshot.as_ref())
+}
+
+#[allow(non_snake_case)]
+fn getGroupCallCandidatePairs(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+
+    let snapshot = with_call_endpoint(&mut cx, |endpoint| {
+        endpoint.call_manager.group_call_stats_snapshot(client_id)
+    });
+    let candidate_pairs = snapshot.map(|snapshot| snapshot.candidate_pairs);
+    Ok(to_js_candidate_pairs(&mut cx, candidate_pairs.as_deref().unwrap_or_default())?.upcast())
 }
 
 #[allow(non_snake_case)]
-fn setDataMode(mut cx: FunctionContext) -> JsResult<JsValue> {
+fn setGroupCallAudioProfile(mut cx: FunctionContext) -> JsResult<JsValue> {
     let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
-    let data_mode = cx.argument::<JsNumber>(1)?.value(&mut cx) as i32;
+    let audio_profile = cx.argument::<JsNumber>(1)?.value(&mut cx) as i32;
 
     with_call_endpoint(&mut cx, |endpoint| {
         endpoint
             .call_manager
-            .set_data_mode(client_id, DataMode::from_i32(data_mode));
+            .set_audio_profile(client_id, AudioProfile::from_i32(audio_profile));
         Ok(())
     })
     .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
@@ -1728,6 +3281,45 @@ fn requestVideo(mut cx: FunctionContext) -> JsResult<JsValue> {
     Ok(cx.undefined().upcast())
 }
 
+#[allow(non_snake_case)]
+fn setRenderingPaused(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+    let js_demux_ids = cx.argument::<JsArray>(1)?;
+    let paused = cx.argument::<JsBoolean>(2)?.value(&mut cx);
+
+    let mut demux_ids = Vec::with_capacity(js_demux_ids.len(&mut cx) as usize);
+    for i in 0..js_demux_ids.len(&mut cx) {
+        let demux_id = js_demux_ids
+            .get::<JsNumber, _, _>(&mut cx, i)?
+            .value(&mut cx) as DemuxId;
+        demux_ids.push(demux_id);
+    }
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .set_rendering_paused(client_id, demux_ids, paused);
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setAllRenderingPaused(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+    let paused = cx.argument::<JsBoolean>(1)?.value(&mut cx);
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .set_all_rendering_paused(client_id, paused);
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
 #[allow(non_snake_case)]
 fn approveUser(mut cx: FunctionContext) -> JsResult<JsValue> {
     let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
@@ -1928,6 +3520,7 @@ fn readCallLink(mut cx: FunctionContext) -> JsResult<JsValue> {
             endpoint.call_manager.http_client(),
             &sfu_url,
             root_key,
+            None,
             &auth_presentation,
             Box::new(move |result| {
                 // Ignore errors, that can only mean we're shutting down.
@@ -1984,6 +3577,7 @@ fn createCallLink(mut cx: FunctionContext) -> JsResult<JsValue> {
             &admin_passkey,
             &public_zkparams,
             restrictions,
+            None,
             Box::new(move |result| {
                 // Ignore errors, that can only mean we're shutting down.
                 let _ = event_reporter.send(Event::CallLinkResponse { request_id, result });
@@ -2047,6 +3641,9 @@ fn updateCallLink(mut cx: FunctionContext) -> JsResult<JsValue> {
                 encrypted_name: new_name.as_deref(),
                 restrictions: new_restrictions,
                 revoked: new_revoked,
+                reset_epoch: false,
+                encrypted_metadata: None,
+                new_admin_passkey: None,
             },
             Box::new(move |result| {
                 // Ignore errors, that can only mean we're shutting down.
@@ -2059,6 +3656,71 @@ fn updateCallLink(mut cx: FunctionContext) -> JsResult<JsValue> {
     Ok(cx.undefined().upcast())
 }
 
+#[allow(non_snake_case)]
+fn rotateCallLinkAdminPasskey(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let request_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as u32;
+    let sfu_url = cx.argument::<JsString>(1)?.value(&mut cx);
+    let auth_presentation = cx.argument::<JsBuffer>(2)?;
+    let auth_presentation = auth_presentation.as_slice(&cx).to_vec();
+    let root_key_bytes = cx.argument::<JsBuffer>(3)?;
+    let root_key = CallLinkRootKey::try_from(root_key_bytes.as_slice(&cx))
+        .or_else(|e| cx.throw_type_error(e.to_string()))?;
+    let admin_passkey = cx.argument::<JsBuffer>(4)?;
+    let admin_passkey = admin_passkey.as_slice(&cx).to_vec();
+    let new_admin_passkey = cx.argument::<JsBuffer>(5)?;
+    let new_admin_passkey = new_admin_passkey.as_slice(&cx).to_vec();
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        let event_reporter = endpoint.event_reporter.clone();
+        call_links::rotate_admin_passkey(
+            endpoint.call_manager.shared_http_client(),
+            &sfu_url,
+            root_key,
+            &auth_presentation,
+            &admin_passkey,
+            &new_admin_passkey,
+            Box::new(move |result| {
+                // Ignore errors, that can only mean we're shutting down.
+                let _ = event_reporter.send(Event::CallLinkResponse { request_id, result });
+            }),
+        );
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn migrateCallLink(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let request_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as u32;
+    let sfu_url = cx.argument::<JsString>(1)?.value(&mut cx);
+    let auth_presentation = cx.argument::<JsBuffer>(2)?;
+    let auth_presentation = auth_presentation.as_slice(&cx).to_vec();
+    let root_key_bytes = cx.argument::<JsBuffer>(3)?;
+    let root_key = CallLinkRootKey::try_from(root_key_bytes.as_slice(&cx))
+        .or_else(|e| cx.throw_type_error(e.to_string()))?;
+    let admin_passkey = cx.argument::<JsBuffer>(4)?;
+    let admin_passkey = admin_passkey.as_slice(&cx).to_vec();
+
+    with_call_endpoint(&mut cx, |endpoint| {
+        let event_reporter = endpoint.event_reporter.clone();
+        call_links::read_and_migrate_call_link(
+            endpoint.call_manager.shared_http_client(),
+            &sfu_url,
+            root_key,
+            &auth_presentation,
+            &admin_passkey,
+            Box::new(move |result| {
+                // Ignore errors, that can only mean we're shutting down.
+                let _ = event_reporter.send(Event::CallLinkResponse { request_id, result });
+            }),
+        );
+        Ok(())
+    })
+    .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+    Ok(cx.undefined().upcast())
+}
+
 #[allow(non_snake_case)]
 fn deleteCallLink(mut cx: FunctionContext) -> JsResult<JsValue> {
     let request_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as u32;
@@ -2092,31 +3754,37 @@ fn deleteCallLink(mut cx: FunctionContext) -> JsResult<JsValue> {
     Ok(cx.undefined().upcast())
 }
 
-#[allow(non_snake_case)]
-fn getAudioInputs(mut cx: FunctionContext) -> JsResult<JsValue> {
-    let devices = with_call_endpoint(&mut cx, |endpoint| {
-        endpoint
-            .peer_connection_factory
-            .get_audio_recording_devices()
-    })
-    .unwrap_or_else(|_| Vec::<AudioDevice>::new());
-
-    let js_devices = JsArray::new(&mut cx, devices.len());
+fn to_js_audio_devices<'a>(
+    cx: &mut impl Context<'a>,
+    devices: &[AudioDevice],
+) -> JsResult<'a, JsArray> {
+    let js_devices = JsArray::new(cx, devices.len());
     for (i, device) in devices.iter().enumerate() {
-        let js_device = JsObject::new(&mut cx);
+        let js_device = JsObject::new(cx);
         let name = cx.string(device.name.clone());
-        js_device.set(&mut cx, "name", name)?;
+        js_device.set(cx, "name", name)?;
         let unique_id = cx.string(device.unique_id.clone());
-        js_device.set(&mut cx, "uniqueId", unique_id)?;
+        js_device.set(cx, "uniqueId", unique_id)?;
         let index = cx.number(i as f64);
-        js_device.set(&mut cx, "index", index)?;
+        js_device.set(cx, "index", index)?;
         if !device.i18n_key.is_empty() {
             let i18n_key = cx.string(device.i18n_key.clone());
-            js_device.set(&mut cx, "i18nKey", i18n_key)?;
+            js_device.set(cx, "i18nKey", i18n_key)?;
         }
-        js_devices.set(&mut cx, i as u32, js_device)?;
+        js_devices.set(cx, i as u32, js_device)?;
     }
-    Ok(js_devices.upcast())
+    Ok(js_devices)
+}
+
+#[allow(non_snake_case)]
+fn getAudioInputs(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let devices = with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .peer_connection_factory
+            .get_audio_recording_devices()
+    })
+    .unwrap_or_else(|_| Vec::<AudioDevice>::new());
+    Ok(to_js_audio_devices(&mut cx, &devices)?.upcast())
 }
 
 #[allow(non_snake_case)]
@@ -2140,23 +3808,41 @@ fn getAudioOutputs(mut cx: FunctionContext) -> JsResult<JsValue> {
         endpoint.peer_connection_factory.get_audio_playout_devices()
     })
     .unwrap_or_else(|_| Vec::<AudioDevice>::new());
+    Ok(to_js_audio_devices(&mut cx, &devices)?.upcast())
+}
 
-    let js_devices = JsArray::new(&mut cx, devices.len());
-    for (i, device) in devices.iter().enumerate() {
-        let js_device = JsObject::new(&mut cx);
-        let name = cx.string(device.name.clone());
-        js_device.set(&mut cx, "name", name)?;
-        let unique_id = cx.string(device.unique_id.clone());
-        js_device.set(&mut cx, "uniqueId", unique_id)?;
-        let index = cx.number(i as f64);
-        js_device.set(&mut cx, "index", index)?;
-        if !device.i18n_key.is_empty() {
-            let i18n_key = cx.string(device.i18n_key.clone());
-            js_device.set(&mut cx, "i18nKey", i18n_key)?;
+#[allow(non_snake_case)]
+fn setAudioInputById(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let unique_id = cx.argument::<JsString>(0)?.value(&mut cx);
+    let selected = match with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .peer_connection_factory
+            .set_audio_recording_device_by_id(&unique_id)
+    }) {
+        Ok(()) => true,
+        Err(err) => {
+            error!("setAudioInputById failed: {}", err);
+            false
         }
-        js_devices.set(&mut cx, i as u32, js_device)?;
-    }
-    Ok(js_devices.upcast())
+    };
+    Ok(cx.boolean(selected).upcast())
+}
+
+#[allow(non_snake_case)]
+fn setAudioOutputById(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let unique_id = cx.argument::<JsString>(0)?.value(&mut cx);
+    let selected = match with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .peer_connection_factory
+            .set_audio_playout_device_by_id(&unique_id)
+    }) {
+        Ok(()) => true,
+        Err(err) => {
+            error!("setAudioOutputById failed: {}", err);
+            false
+        }
+    };
+    Ok(cx.boolean(selected).upcast())
 }
 
 #[allow(non_snake_case)]
@@ -2171,706 +3857,1059 @@ fn setAudioOutput(mut cx: FunctionContext) -> JsResult<JsValue> {
         Err(err) => error!("setAudioOutput failed: {}", err),
     };
 
-    Ok(cx.undefined().upcast())
-}
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setRtcStatsInterval(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+    let interval = Duration::from_millis(cx.argument::<JsNumber>(1)?.value(&mut cx) as u64);
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .set_rtc_stats_interval(client_id, interval)
+    });
+
+    Ok(cx.undefined().upcast())
+}
+
+#[allow(non_snake_case)]
+fn setGroupCallIceServers(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
+    let ice_servers = get_ice_servers_arg(&mut cx, 1)?;
+    let expires_in = cx
+        .argument_opt(2)
+        .and_then(|arg| arg.downcast::<JsNumber, _>(&mut cx).ok())
+        .map(|millis| Duration::from_millis(millis.value(&mut cx) as u64));
+    with_call_endpoint(&mut cx, |endpoint| {
+        endpoint
+            .call_manager
+            .set_group_call_ice_servers(client_id, ice_servers, expires_in)
+    });
+
+    Ok(cx.undefined().upcast())
+}
+
+/// Delivers queued log messages and events to the observer of the JavaScript CallManager until
+/// there are none left, running on the JavaScript thread. See [`EventReporter`].
+fn deliver_events(
+    mut cx: TaskContext,
+    js_object: &Root<JsObject>,
+    queue: &Mutex<EventQueue>,
+) -> NeonResult<()> {
+    // Unless this returns after finding the queue empty, which unschedules delivery itself, make
+    // sure later events schedule another delivery, even if this fails or panics.
+    struct UnscheduleOnDrop<'a>(Option<&'a Mutex<EventQueue>>);
+    impl Drop for UnscheduleOnDrop<'_> {
+        fn drop(&mut self) {
+            if let Some(queue) = self.0 {
+                EventQueue::unschedule_delivery(queue);
+            }
+        }
+    }
+    let mut unschedule = UnscheduleOnDrop(Some(queue));
+
+    let call_manager = js_object.to_inner(&mut cx);
+    let observer = call_manager.get::<JsObject, _, _>(&mut cx, "observer")?;
+
+    loop {
+        if let Err(e) = cx.try_catch(|cx| deliver_log_messages(cx, observer)) {
+            // Logging this would only queue another message for the failing observer.
+            eprintln!(
+                "error: could not log to JavaScript: {}",
+                js_exception_to_string(&mut cx, e)
+            );
+        }
+
+        let dropped = std::mem::take(
+            &mut queue
+                .lock()
+                .expect("lock event queue")
+                .dropped_periodic_events,
+        );
+        if dropped > 0 {
+            warn!(
+                "Dropped {} periodic events while JavaScript was busy",
+                dropped
+            );
+        }
+
+        match EventQueue::next_to_deliver(queue) {
+            NextDelivery::Event(event) => {
+                // Catch exceptions for each event, so that one failing observer call doesn't
+                // hold up the events after it.
+                if let Err(e) = cx.try_catch(|cx| deliver_event(cx, call_manager, observer, event))
+                {
+                    error!("{}", js_exception_to_string(&mut cx, e));
+                }
+            }
+            NextDelivery::LogMessages => {}
+            NextDelivery::Finished => {
+                unschedule.0 = None;
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn js_exception_to_string<'a>(cx: &mut TaskContext<'a>, e: Handle<'a, JsValue>) -> String {
+    e.to_string(cx)
+        .map(|s| s.value(cx))
+        .unwrap_or_else(|_| "[failed to stringify]".to_string())
+}
+
+fn deliver_log_messages<'a>(
+    cx: &mut TaskContext<'a>,
+    observer: Handle<'a, JsObject>,
+) -> NeonResult<()> {
+    let log_entries = std::mem::take(&mut *LOG_MESSAGES.lock().expect("lock log messages"));
+    if log_entries.is_empty() {
+        return Ok(());
+    }
+    let method = observer.get::<JsFunction, _, _>(cx, "onLogMessage")?;
+    for log_entry in log_entries {
+        let args = [
+            cx.number(log_entry.level).upcast(),
+            cx.string(log_entry.file).upcast(),
+            cx.number(log_entry.line).upcast(),
+            cx.string(log_entry.message).upcast(),
+        ];
+        method.call(cx, observer, args)?;
+    }
+    Ok(())
+}
+
+fn deliver_event<'a>(
+    cx: &mut TaskContext<'a>,
+    call_manager: Handle<'a, JsObject>,
+    observer: Handle<'a, JsObject>,
+    event: Event,
+) -> NeonResult<()> {
+    match event {
+        Event::SendSignaling(peer_id, maybe_device_id, call_id, signal) => {
+            let (method_name, data1, data2, data3): (
+                &str,
+                Handle<JsValue>,
+                Handle<JsValue>,
+                Handle<JsValue>,
+            ) = match signal {
+                signaling::Message::Offer(offer) => {
+                    let mut opaque = cx.buffer(offer.opaque.len())?;
+                    opaque.as_mut_slice(cx).copy_from_slice(&offer.opaque);
+
+                    (
+                        "onSendOffer",
+                        cx.number(offer.call_media_type as i32).upcast(),
+                        opaque.upcast(),
+                        cx.undefined().upcast(),
+                    )
+                }
+                signaling::Message::Answer(answer) => {
+                    let mut opaque = cx.buffer(answer.opaque.len())?;
+                    opaque.as_mut_slice(cx).copy_from_slice(&answer.opaque);
+
+                    (
+                        "onSendAnswer",
+                        opaque.upcast(),
+                        cx.undefined().upcast(),
+                        cx.undefined().upcast(),
+                    )
+                }
+                signaling::Message::Ice(ice) => {
+                    let js_candidates = JsArray::new(cx, ice.candidates.len());
+                    for (i, candidate) in ice.candidates.iter().enumerate() {
+                        let opaque: neon::handle::Handle<JsValue> = {
+                            let mut js_opaque = cx.buffer(candidate.opaque.len())?;
+                            js_opaque
+                                .as_mut_slice(cx)
+                                .copy_from_slice(candidate.opaque.as_ref());
+                            js_opaque.upcast()
+                        };
+
+                        js_candidates.set(cx, i as u32, opaque)?;
+                    }
+                    (
+                        "onSendIceCandidates",
+                        js_candidates.upcast(),
+                        cx.undefined().upcast(),
+                        cx.undefined().upcast(),
+                    )
+                }
+                signaling::Message::Hangup(hangup) => {
+                    let (hangup_type, hangup_device_id) = hangup.to_type_and_device_id();
+                    let hangup_type = cx.number(hangup_type as i32).upcast();
+                    let device_id = match hangup_device_id {
+                        Some(device_id) => cx.number(device_id).upcast(),
+                        None => cx.null().upcast(),
+                    };
+                    (
+                        "onSendHangup",
+                        hangup_type,
+                        device_id,
+                        cx.undefined().upcast(),
+                    )
+                }
+                signaling::Message::Busy => (
+                    "onSendBusy",
+                    cx.undefined().upcast(),
+                    cx.undefined().upcast(),
+                    cx.undefined().upcast(),
+                ),
+            };
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            let args = [
+                cx.string(peer_id).upcast(),
+                cx.number(maybe_device_id.unwrap_or(0) as f64).upcast(),
+                create_id_arg(cx, call_id.as_u64()),
+                cx.boolean(maybe_device_id.is_none()).upcast(),
+                data1,
+                data2,
+                data3,
+            ];
+            method.call(cx, observer, args)?;
+        }
+
+        Event::CallState(peer_id, call_id, CallState::Incoming(call_media_type), app_data) => {
+            let method_name = "onStartIncomingCall";
+            let args = [
+                cx.string(peer_id).upcast(),
+                create_id_arg(cx, call_id.as_u64()),
+                cx.boolean(call_media_type == CallMediaType::Video).upcast(),
+                to_js_buffer(cx, &app_data),
+            ];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+
+        Event::CallState(peer_id, call_id, CallState::Outgoing(_call_media_type), app_data) => {
+            let method_name = "onStartOutgoingCall";
+            let args = [
+                cx.string(peer_id).upcast(),
+                create_id_arg(cx, call_id.as_u64()),
+                to_js_buffer(cx, &app_data),
+            ];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+
+        Event::CallState(peer_id, call_id, CallState::Ended(reason), app_data) => {
+            let method_name = "onCallEnded";
+            let reason_string = end_reason_string(&reason);
+            let age = match reason {
+                EndReason::ReceivedOfferExpired { age } => age,
+                _ => Duration::ZERO,
+            };
+            let args = [
+                cx.string(peer_id).upcast(),
+                create_id_arg(cx, call_id.as_u64()),
+                cx.string(reason_string).upcast(),
+                cx.number(age.as_secs_f64()).upcast(),
+                to_js_buffer(cx, &app_data),
+            ];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+
+        Event::CallSummary(peer_id, call_id, summary, reason, app_data) => {
+            let method_name = "onCallSummary";
+            let js_summary = to_js_call_summary(cx, &summary)?;
+            let args = [
+                cx.string(peer_id).upcast(),
+                create_id_arg(cx, call_id.as_u64()),
+                js_summary.upcast(),
+                cx.string(end_reason_string(&reason)).upcast(),
+                to_js_buffer(cx, &app_data),
+            ];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+
+        Event::CallState(peer_id, _call_id, state, app_data) => {
+            let method_name = "onCallState";
+            let state_string = match state {
+                CallState::Ringing => "ringing",
+                CallState::Connected => "connected",
+                CallState::Connecting => "connecting",
+                CallState::Concluded => {
+                    // "Call Concluded" means that the core won't issue anymore
+                    // notifications or events for the call. The Desktop client
+                    // doesn't currently need this information for its state.
+
+                    // However, it's a great time to clear things.
+                    with_call_endpoint_object(cx, call_manager, |endpoint| {
+                        endpoint.incoming_video_sink.clear();
+                    });
+
+                    // Make sure to keep handling subsequent events in this batch.
+                    return Ok(());
+                }
+                // All covered above.
+                CallState::Incoming(_) => "incoming",
+                CallState::Outgoing(_) => "outgoing",
+                CallState::Ended(_) => "ended",
+            };
+            let args = [
+                cx.string(peer_id).upcast(),
+                cx.string(state_string).upcast(),
+                to_js_buffer(cx, &app_data),
+            ];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+
+        Event::NetworkRouteChange(peer_id, network_route) => {
+            let method_name = "onNetworkRouteChanged";
+            let args = [
+                cx.string(peer_id).upcast::<JsValue>(),
+                cx.number(network_route.local_adapter_type as i32).upcast(),
+                cx.number(network_route.local_adapter_type_under_vpn as i32)
+                    .upcast(),
+                cx.boolean(network_route.relayed()).upcast(),
+            ];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+
+        Event::RemoteAudioStateChange(peer_id, enabled) => {
+            let method_name = "onRemoteAudioEnabled";
+            let args = [cx.string(peer_id).upcast(), cx.boolean(enabled).upcast()];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+
+        Event::RemoteVideoStateChange(peer_id, enabled) => {
+            if enabled {
+                // Clear out data from the last time video was enabled.
+                with_call_endpoint_object(cx, call_manager, |endpoint| {
+                    endpoint.incoming_video_sink.clear();
+                });
+            }
+
+            let method_name = "onRemoteVideoEnabled";
+            let args = [cx.string(peer_id).upcast(), cx.boolean(enabled).upcast()];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+
+        Event::RemoteSharingScreenChange(peer_id, enabled) => {
+            let method_name = "onRemoteSharingScreen";
+            let args = [cx.string(peer_id).upcast(), cx.boolean(enabled).upcast()];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+
+        Event::RemoteHoldChange(peer_id, on_hold) => {
+            let method_name = "onRemoteHoldChanged";
+            let args = [cx.string(peer_id).upcast(), cx.boolean(on_hold).upcast()];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+
+        Event::IncomingCallWaiting(peer_id, call_id) => {
+            let method_name = "onIncomingCallWaiting";
+            let args = [
+                cx.string(peer_id).upcast(),
+                create_id_arg(cx, call_id.as_u64()),
+            ];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+
+        Event::GlareResolved(peer_id, resolution) => {
+            let method_name = "onGlareResolved";
+            let winning_call_id = match resolution.winning_call_id {
+                Some(call_id) => create_id_arg(cx, call_id.as_u64()),
+                None => cx.null().upcast(),
+            };
+            let args = [
+                cx.string(peer_id).upcast(),
+                create_id_arg(cx, resolution.local_call_id.as_u64()),
+                create_id_arg(cx, resolution.remote_call_id.as_u64()),
+                winning_call_id,
+                cx.boolean(resolution.converted_to_incoming).upcast(),
+            ];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+
+        Event::RemoteHandedOff(peer_id) => {
+            let method_name = "onRemoteHandedOff";
+            let args = [cx.string(peer_id).upcast()];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+
+        Event::RemoteEarlyMedia(peer_id) => {
+            let method_name = "onRemoteEarlyMedia";
+            let args = [cx.string(peer_id).upcast()];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+
+        Event::VideoNegotiation(peer_id, event) => {
+            let method_name = "onVideoNegotiation";
+            let args = [
+                cx.string(peer_id).upcast(),
+                cx.string(video_negotiation_event_string(event)).upcast(),
+            ];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-#[allow(non_snake_case)]
-fn setRtcStatsInterval(mut cx: FunctionContext) -> JsResult<JsValue> {
-    let client_id = cx.argument::<JsNumber>(0)?.value(&mut cx) as group_call::ClientId;
-    let interval = Duration::from_millis(cx.argument::<JsNumber>(1)?.value(&mut cx) as u64);
-    with_call_endpoint(&mut cx, |endpoint| {
-        endpoint
-            .call_manager
-            .set_rtc_stats_interval(client_id, interval)
-    });
+        Event::MediaStalled(peer_id) => {
+            let method_name = "onMediaStalled";
+            let args = [cx.string(peer_id).upcast()];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-    Ok(cx.undefined().upcast())
-}
+        Event::AppData(peer_id, data) => {
+            let method_name = "onAppData";
+            let args = [cx.string(peer_id).upcast(), to_js_buffer(cx, &data)];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-#[allow(non_snake_case)]
-fn processEvents(mut cx: FunctionContext) -> JsResult<JsValue> {
-    let this = cx.this::<JsObject>()?;
-    let observer = this.get::<JsObject, _, _>(&mut cx, "observer")?;
+        Event::VideoAdaptationChanged { peer_id, reason } => {
+            let method_name = "onVideoAdaptationChanged";
+            let args = [
+                cx.string(peer_id).upcast(),
+                to_js_quality_limitation_reason(cx, reason),
+            ];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-    {
-        let log_entries = std::mem::take(&mut *LOG_MESSAGES.lock().expect("lock log messages"));
-        let method = observer.get::<JsFunction, _, _>(&mut cx, "onLogMessage")?;
-        for log_entry in log_entries {
+        Event::HandoffRequested(request) => {
+            let method_name = "onHandoffRequested";
             let args = [
-                cx.number(log_entry.level).upcast(),
-                cx.string(log_entry.file).upcast(),
-                cx.number(log_entry.line).upcast(),
-                cx.string(log_entry.message).upcast(),
+                to_js_buffer(cx, &request.remote_user_id).upcast::<JsValue>(),
+                create_id_arg(cx, request.call_id.as_u64()),
+                cx.number(request.remote_device_id).upcast(),
+                cx.boolean(request.call_media_type == CallMediaType::Video)
+                    .upcast(),
             ];
-            method.call(&mut cx, observer, args)?;
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
         }
-    }
 
-    let events: Vec<Event> = with_call_endpoint(&mut cx, |endpoint| {
-        endpoint.events_receiver.try_iter().collect()
-    });
+        Event::AudioLevels {
+            peer_id,
+            captured_level,
+            received_level,
+        } => {
+            let method_name = "onAudioLevels";
+            let args = [
+                cx.string(peer_id).upcast(),
+                cx.number(captured_level).upcast(),
+                cx.number(received_level).upcast(),
+            ];
 
-    for event in events {
-        match event {
-            Event::SendSignaling(peer_id, maybe_device_id, call_id, signal) => {
-                let (method_name, data1, data2, data3): (
-                    &str,
-                    Handle<JsValue>,
-                    Handle<JsValue>,
-                    Handle<JsValue>,
-                ) = match signal {
-                    signaling::Message::Offer(offer) => {
-                        let mut opaque = cx.buffer(offer.opaque.len())?;
-                        opaque.as_mut_slice(&mut cx).copy_from_slice(&offer.opaque);
-
-                        (
-                            "onSendOffer",
-                            cx.number(offer.call_media_type as i32).upcast(),
-                            opaque.upcast(),
-                            cx.undefined().upcast(),
-                        )
-                    }
-                    signaling::Message::Answer(answer) => {
-                        let mut opaque = cx.buffer(answer.opaque.len())?;
-                        opaque.as_mut_slice(&mut cx).copy_from_slice(&answer.opaque);
-
-                        (
-                            "onSendAnswer",
-                            opaque.upcast(),
-                            cx.undefined().upcast(),
-                            cx.undefined().upcast(),
-                        )
-                    }
-                    signaling::Message::Ice(ice) => {
-                        let js_candidates = JsArray::new(&mut cx, ice.candidates.len());
-                        for (i, candidate) in ice.candidates.iter().enumerate() {
-                            let opaque: neon::handle::Handle<JsValue> = {
-                                let mut js_opaque = cx.buffer(candidate.opaque.len())?;
-                                js_opaque
-                                    .as_mut_slice(&mut cx)
-                                    .copy_from_slice(candidate.opaque.as_ref());
-                                js_opaque.upcast()
-                            };
-
-                            js_candidates.set(&mut cx, i as u32, opaque)?;
-                        }
-                        (
-                            "onSendIceCandidates",
-                            js_candidates.upcast(),
-                            cx.undefined().upcast(),
-                            cx.undefined().upcast(),
-                        )
-                    }
-                    signaling::Message::Hangup(hangup) => {
-                        let (hangup_type, hangup_device_id) = hangup.to_type_and_device_id();
-                        let hangup_type = cx.number(hangup_type as i32).upcast();
-                        let device_id = match hangup_device_id {
-                            Some(device_id) => cx.number(device_id).upcast(),
-                            None => cx.null().upcast(),
-                        };
-                        (
-                            "onSendHangup",
-                            hangup_type,
-                            device_id,
-                            cx.undefined().upcast(),
-                        )
-                    }
-                    signaling::Message::Busy => (
-                        "onSendBusy",
-                        cx.undefined().upcast(),
-                        cx.undefined().upcast(),
-                        cx.undefined().upcast(),
-                    ),
-                };
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                let args = [
-                    cx.string(peer_id).upcast(),
-                    cx.number(maybe_device_id.unwrap_or(0) as f64).upcast(),
-                    create_id_arg(&mut cx, call_id.as_u64()),
-                    cx.boolean(maybe_device_id.is_none()).upcast(),
-                    data1,
-                    data2,
-                    data3,
-                ];
-                method.call(&mut cx, observer, args)?;
-            }
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-            Event::CallState(peer_id, call_id, CallState::Incoming(call_media_type)) => {
-                let method_name = "onStartIncomingCall";
-                let args = [
-                    cx.string(peer_id).upcast(),
-                    create_id_arg(&mut cx, call_id.as_u64()),
-                    cx.boolean(call_media_type == CallMediaType::Video).upcast(),
-                ];
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
-            }
+        Event::LowBandwidthForVideo { peer_id, recovered } => {
+            let method_name = "onLowBandwidthForVideo";
+            let args = [cx.string(peer_id).upcast(), cx.boolean(recovered).upcast()];
 
-            Event::CallState(peer_id, call_id, CallState::Outgoing(_call_media_type)) => {
-                let method_name = "onStartOutgoingCall";
-                let args = [
-                    cx.string(peer_id).upcast(),
-                    create_id_arg(&mut cx, call_id.as_u64()),
-                ];
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
-            }
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-            Event::CallState(peer_id, call_id, CallState::Ended(reason)) => {
-                let method_name = "onCallEnded";
-                let reason_string = match reason {
-                    EndReason::LocalHangup => "LocalHangup",
-                    EndReason::RemoteHangup => "RemoteHangup",
-                    EndReason::RemoteHangupNeedPermission => "RemoteHangupNeedPermission",
-                    EndReason::Declined => "Declined",
-                    EndReason::Busy => "Busy",
-                    EndReason::Glare => "Glare",
-                    EndReason::ReCall => "ReCall",
-                    EndReason::ReceivedOfferExpired { .. } => "ReceivedOfferExpired",
-                    EndReason::ReceivedOfferWhileActive => "ReceivedOfferWhileActive",
-                    EndReason::ReceivedOfferWithGlare => "ReceivedOfferWithGlare",
-                    EndReason::SignalingFailure => "SignalingFailure",
-                    EndReason::GlareFailure => "GlareFailure",
-                    EndReason::ConnectionFailure => "ConnectionFailure",
-                    EndReason::InternalFailure => "InternalFailure",
-                    EndReason::Timeout => "Timeout",
-                    EndReason::AcceptedOnAnotherDevice => "AcceptedOnAnotherDevice",
-                    EndReason::DeclinedOnAnotherDevice => "DeclinedOnAnotherDevice",
-                    EndReason::BusyOnAnotherDevice => "BusyOnAnotherDevice",
-                };
-                let age = match reason {
-                    EndReason::ReceivedOfferExpired { age } => age,
-                    _ => Duration::ZERO,
-                };
-                let args = [
-                    cx.string(peer_id).upcast(),
-                    create_id_arg(&mut cx, call_id.as_u64()),
-                    cx.string(reason_string).upcast(),
-                    cx.number(age.as_secs_f64()).upcast(),
-                ];
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
+        Event::SendHttpRequest {
+            request_id,
+            request:
+                http::Request {
+                    method,
+                    url,
+                    headers,
+                    body,
+                },
+        } => {
+            let method_name = "sendHttpRequest";
+            // Pass headers as an object with the Fetch API. Only the last value will be sent
+            // in case of duplicate headers.
+            let js_headers = JsObject::new(cx);
+            for (name, value) in headers.iter() {
+                let value = cx.string(value);
+                js_headers.set(cx, name.as_str(), value)?;
             }
+            let http_method = method as i32;
+            let body = match body {
+                None => cx.undefined().upcast(),
+                Some(body) => {
+                    let mut js_body = cx.buffer(body.len())?;
+                    js_body.as_mut_slice(cx).copy_from_slice(&body);
+                    js_body.upcast()
+                }
+            };
+            let args = [
+                cx.number(request_id).upcast(),
+                cx.string(url).upcast(),
+                cx.number(http_method).upcast(),
+                js_headers.upcast(),
+                body,
+            ];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-            Event::CallState(peer_id, _call_id, state) => {
-                let method_name = "onCallState";
-                let state_string = match state {
-                    CallState::Ringing => "ringing",
-                    CallState::Connected => "connected",
-                    CallState::Connecting => "connecting",
-                    CallState::Concluded => {
-                        // "Call Concluded" means that the core won't issue anymore
-                        // notifications or events for the call. The Desktop client
-                        // doesn't currently need this information for its state.
-
-                        // However, it's a great time to clear things.
-                        with_call_endpoint(&mut cx, |endpoint| {
-                            endpoint.incoming_video_sink.clear();
-                        });
-
-                        // Make sure to keep handling subsequent events in this batch.
-                        continue;
-                    }
-                    // All covered above.
-                    CallState::Incoming(_) => "incoming",
-                    CallState::Outgoing(_) => "outgoing",
-                    CallState::Ended(_) => "ended",
-                };
-                let args = [
-                    cx.string(peer_id).upcast(),
-                    cx.string(state_string).upcast(),
-                ];
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
-            }
+        Event::SendCallMessage {
+            recipient_id,
+            message,
+            urgency,
+        } => {
+            let method_name = "sendCallMessage";
+            let recipient_id = to_js_buffer(cx, &recipient_id);
+            let message = to_js_buffer(cx, &message);
+            let urgency = cx.number(urgency as i32).upcast();
+            let args = [recipient_id, message, urgency];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-            Event::NetworkRouteChange(peer_id, network_route) => {
-                let method_name = "onNetworkRouteChanged";
-                let args = [
-                    cx.string(peer_id).upcast::<JsValue>(),
-                    cx.number(network_route.local_adapter_type as i32).upcast(),
-                ];
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
+        Event::SendCallMessageToGroup {
+            group_id,
+            message,
+            urgency,
+            recipients_override,
+        } => {
+            let method_name = "sendCallMessageToGroup";
+            let group_id = to_js_buffer(cx, &group_id);
+            let message = to_js_buffer(cx, &message);
+            let urgency = cx.number(urgency as i32).upcast();
+            let js_recipients = JsArray::new(cx, recipients_override.len());
+            for (i, recipient_id) in recipients_override.iter().enumerate() {
+                let js_recipient_id = to_js_buffer(cx, recipient_id);
+                js_recipients.set(cx, i as u32, js_recipient_id)?;
             }
+            let args = [group_id, message, urgency, js_recipients.upcast()];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-            Event::RemoteAudioStateChange(peer_id, enabled) => {
-                let method_name = "onRemoteAudioEnabled";
-                let args = [cx.string(peer_id).upcast(), cx.boolean(enabled).upcast()];
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
-            }
+        // Group Calls
+        Event::GroupUpdate(GroupUpdate::RequestMembershipProof(client_id)) => {
+            let method_name = "requestMembershipProof";
 
-            Event::RemoteVideoStateChange(peer_id, enabled) => {
-                if enabled {
-                    // Clear out data from the last time video was enabled.
-                    with_call_endpoint(&mut cx, |endpoint| {
-                        endpoint.incoming_video_sink.clear();
-                    });
-                }
+            let args = [cx.number(client_id).upcast()];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-                let method_name = "onRemoteVideoEnabled";
-                let args = [cx.string(peer_id).upcast(), cx.boolean(enabled).upcast()];
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
-            }
+        Event::GroupUpdate(GroupUpdate::RequestGroupMembers(client_id)) => {
+            let method_name = "requestGroupMembers";
 
-            Event::RemoteSharingScreenChange(peer_id, enabled) => {
-                let method_name = "onRemoteSharingScreen";
-                let args = [cx.string(peer_id).upcast(), cx.boolean(enabled).upcast()];
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
-            }
+            let args = [cx.number(client_id).upcast()];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-            Event::AudioLevels {
-                peer_id,
-                captured_level,
-                received_level,
-            } => {
-                let method_name = "onAudioLevels";
-                let args = [
-                    cx.string(peer_id).upcast(),
-                    cx.number(captured_level).upcast(),
-                    cx.number(received_level).upcast(),
-                ];
-
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
-            }
+        Event::GroupUpdate(GroupUpdate::ConnectionStateChanged(client_id, connection_state)) => {
+            let method_name = "handleConnectionStateChanged";
 
-            Event::LowBandwidthForVideo { peer_id, recovered } => {
-                let method_name = "onLowBandwidthForVideo";
-                let args = [cx.string(peer_id).upcast(), cx.boolean(recovered).upcast()];
+            let args = [
+                cx.number(client_id).upcast(),
+                cx.number(connection_state.ordinal()).upcast(),
+            ];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
-            }
+        Event::GroupUpdate(GroupUpdate::NetworkRouteChanged(client_id, network_route)) => {
+            let method_name = "handleNetworkRouteChanged";
 
-            Event::SendHttpRequest {
-                request_id,
-                request:
-                    http::Request {
-                        method,
-                        url,
-                        headers,
-                        body,
-                    },
-            } => {
-                let method_name = "sendHttpRequest";
-                // Pass headers as an object with the Fetch API. Only the last value will be sent
-                // in case of duplicate headers.
-                let js_headers = JsObject::new(&mut cx);
-                for (name, value) in headers.iter() {
-                    let value = cx.string(value);
-                    js_headers.set(&mut cx, name.as_str(), value)?;
-                }
-                let http_method = method as i32;
-                let body = match body {
-                    None => cx.undefined().upcast(),
-                    Some(body) => {
-                        let mut js_body = cx.buffer(body.len())?;
-                        js_body.as_mut_slice(&mut cx).copy_from_slice(&body);
-                        js_body.upcast()
-                    }
-                };
-                let args = [
-                    cx.number(request_id).upcast(),
-                    cx.string(url).upcast(),
-                    cx.number(http_method).upcast(),
-                    js_headers.upcast(),
-                    body,
-                ];
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
-            }
+            let args = [
+                cx.number(client_id).upcast::<JsValue>(),
+                cx.number(network_route.local_adapter_type as i32).upcast(),
+            ];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-            Event::SendCallMessage {
-                recipient_id,
-                message,
-                urgency,
-            } => {
-                let method_name = "sendCallMessage";
-                let recipient_id = to_js_buffer(&mut cx, &recipient_id);
-                let message = to_js_buffer(&mut cx, &message);
-                let urgency = cx.number(urgency as i32).upcast();
-                let args = [recipient_id, message, urgency];
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
-            }
+        Event::GroupUpdate(GroupUpdate::JoinStateChanged(client_id, join_state)) => {
+            let method_name = "handleJoinStateChanged";
+
+            let args = [
+                cx.number(client_id).upcast(),
+                cx.number(join_state.ordinal()).upcast(),
+                match join_state {
+                    group_call::JoinState::Pending(demux_id)
+                    | group_call::JoinState::Joined(demux_id) => cx.number(demux_id).upcast(),
+                    _ => cx.null().upcast(),
+                },
+            ];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-            Event::SendCallMessageToGroup {
-                group_id,
-                message,
-                urgency,
-                recipients_override,
-            } => {
-                let method_name = "sendCallMessageToGroup";
-                let group_id = to_js_buffer(&mut cx, &group_id);
-                let message = to_js_buffer(&mut cx, &message);
-                let urgency = cx.number(urgency as i32).upcast();
-                let js_recipients = JsArray::new(&mut cx, recipients_override.len());
-                for (i, recipient_id) in recipients_override.iter().enumerate() {
-                    let js_recipient_id = to_js_buffer(&mut cx, recipient_id);
-                    js_recipients.set(&mut cx, i as u32, js_recipient_id)?;
+        Event::CallLinkResponse { request_id, result } => {
+            let method_name = "handleCallLinkResponse";
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+
+            let js_request_id = cx.number(request_id);
+            let (status, state_object) = match result {
+                Ok(state) => {
+                    let state_object = cx.empty_object();
+                    let js_name = cx.string(state.name);
+                    state_object.set(cx, "name", js_name)?;
+                    let js_revoked = cx.boolean(state.revoked);
+                    state_object.set(cx, "revoked", js_revoked)?;
+                    let js_restrictions = cx.number(match state.restrictions {
+                        call_links::CallLinkRestrictions::None => 0,
+                        call_links::CallLinkRestrictions::AdminApproval => 1,
+                        call_links::CallLinkRestrictions::Unknown => -1,
+                    });
+                    state_object.set(cx, "rawRestrictions", js_restrictions)?;
+                    let js_expiration = cx
+                        .date(
+                            state
+                                .expiration
+                                .duration_since(std::time::SystemTime::UNIX_EPOCH)
+                                .unwrap_or_default()
+                                .as_millis() as f64,
+                        )
+                        .or_else(|e| cx.throw_range_error(e.to_string()))?;
+                    state_object.set(cx, "expiration", js_expiration)?;
+                    let js_old_admin_passkey_rejected =
+                        cx.boolean(state.old_admin_passkey_rejected);
+                    state_object.set(
+                        cx,
+                        "oldAdminPasskeyRejected",
+                        js_old_admin_passkey_rejected,
+                    )?;
+                    (cx.number(200), state_object.upcast())
                 }
-                let args = [group_id, message, urgency, js_recipients.upcast()];
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
-            }
+                Err(status_code) => (cx.number(status_code.code), cx.undefined().upcast()),
+            };
+
+            method.call(
+                cx,
+                observer,
+                [js_request_id.upcast(), status.upcast(), state_object],
+            )?;
+        }
 
-            // Group Calls
-            Event::GroupUpdate(GroupUpdate::RequestMembershipProof(client_id)) => {
-                let method_name = "requestMembershipProof";
+        Event::EmptyResponse { request_id, result } => {
+            let method_name = "handleEmptyResponse";
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
 
-                let args = [cx.number(client_id).upcast()];
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
-            }
+            let js_request_id = cx.number(request_id);
+            let (status, state_object) = match result {
+                Ok(_) => (cx.number(200), cx.empty_object().upcast()),
+                Err(status_code) => (cx.number(status_code.code), cx.undefined().upcast()),
+            };
 
-            Event::GroupUpdate(GroupUpdate::RequestGroupMembers(client_id)) => {
-                let method_name = "requestGroupMembers";
+            method.call(
+                cx,
+                observer,
+                [js_request_id.upcast(), status.upcast(), state_object],
+            )?;
+        }
 
-                let args = [cx.number(client_id).upcast()];
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
-            }
+        Event::GroupUpdate(GroupUpdate::RemoteDeviceStatesChanged(
+            client_id,
+            remote_device_states,
+        )) => {
+            let method_name = "handleRemoteDevicesChanged";
+
+            let js_remote_device_states = JsArray::new(cx, remote_device_states.len());
+            for (i, remote_device_state) in remote_device_states.iter().enumerate() {
+                let demux_id = cx.number(remote_device_state.demux_id);
+                let user_id = to_js_buffer(cx, &remote_device_state.user_id);
+                let participant_kind = cx.number(remote_device_state.participant_kind as i32);
+                let media_keys_received = cx.boolean(remote_device_state.media_keys_received);
+                let audio_muted: neon::handle::Handle<JsValue> =
+                    match remote_device_state.heartbeat_state.audio_muted {
+                        None => cx.undefined().upcast(),
+                        Some(muted) => cx.boolean(muted).upcast(),
+                    };
+                let video_muted: neon::handle::Handle<JsValue> =
+                    match remote_device_state.heartbeat_state.video_muted {
+                        None => cx.undefined().upcast(),
+                        Some(muted) => cx.boolean(muted).upcast(),
+                    };
+                let presenting: neon::handle::Handle<JsValue> =
+                    match remote_device_state.heartbeat_state.presenting {
+                        None => cx.undefined().upcast(),
+                        Some(muted) => cx.boolean(muted).upcast(),
+                    };
+                let sharing_screen: neon::handle::Handle<JsValue> =
+                    match remote_device_state.heartbeat_state.sharing_screen {
+                        None => cx.undefined().upcast(),
+                        Some(muted) => cx.boolean(muted).upcast(),
+                    };
+                // These are strings because we can't safely convert a u64 to a JavaScript-compatible number. We'll convert them to numeric types on the other side.
+                let added_time: neon::handle::Handle<JsValue> = cx
+                    .string(remote_device_state.added_time_as_unix_millis().to_string())
+                    .upcast();
+                let speaker_time: neon::handle::Handle<JsValue> = cx
+                    .string(
+                        remote_device_state
+                            .speaker_time_as_unix_millis()
+                            .to_string(),
+                    )
+                    .upcast();
+                let forwarding_video: neon::handle::Handle<JsValue> =
+                    match remote_device_state.forwarding_video {
+                        None => cx.undefined().upcast(),
+                        Some(forwarding_video) => cx.boolean(forwarding_video).upcast(),
+                    };
+                let is_higher_resolution_pending =
+                    cx.boolean(remote_device_state.is_higher_resolution_pending);
+
+                let js_remote_device_state = cx.empty_object();
+                js_remote_device_state.set(cx, "demuxId", demux_id)?;
+                js_remote_device_state.set(cx, "userId", user_id)?;
+                js_remote_device_state.set(cx, "participantKind", participant_kind)?;
+                js_remote_device_state.set(cx, "mediaKeysReceived", media_keys_received)?;
+                js_remote_device_state.set(cx, "audioMuted", audio_muted)?;
+                js_remote_device_state.set(cx, "videoMuted", video_muted)?;
+                js_remote_device_state.set(cx, "presenting", presenting)?;
+                js_remote_device_state.set(cx, "sharingScreen", sharing_screen)?;
+                js_remote_device_state.set(cx, "addedTime", added_time)?;
+                js_remote_device_state.set(cx, "speakerTime", speaker_time)?;
+                js_remote_device_state.set(cx, "forwardingVideo", forwarding_video)?;
+                js_remote_device_state.set(
+                    cx,
+                    "isHigherResolutionPending",
+                    is_higher_resolution_pending,
+                )?;
 
-            Event::GroupUpdate(GroupUpdate::ConnectionStateChanged(
-                client_id,
-                connection_state,
-            )) => {
-                let method_name = "handleConnectionStateChanged";
-
-                let args = [
-                    cx.number(client_id).upcast(),
-                    cx.number(connection_state.ordinal()).upcast(),
-                ];
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
+                js_remote_device_states.set(cx, i as u32, js_remote_device_state)?;
             }
 
-            Event::GroupUpdate(GroupUpdate::NetworkRouteChanged(client_id, network_route)) => {
-                let method_name = "handleNetworkRouteChanged";
+            let args = [
+                cx.number(client_id).upcast(),
+                js_remote_device_states.upcast(),
+            ];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-                let args = [
-                    cx.number(client_id).upcast::<JsValue>(),
-                    cx.number(network_route.local_adapter_type as i32).upcast(),
-                ];
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
-            }
+        Event::GroupUpdate(GroupUpdate::PeekChanged {
+            client_id,
+            peek_info,
+        }) => {
+            let method_name = "handlePeekChanged";
+            let js_info = to_js_peek_info(cx, peek_info)?;
 
-            Event::GroupUpdate(GroupUpdate::JoinStateChanged(client_id, join_state)) => {
-                let method_name = "handleJoinStateChanged";
-
-                let args = [
-                    cx.number(client_id).upcast(),
-                    cx.number(join_state.ordinal()).upcast(),
-                    match join_state {
-                        group_call::JoinState::Pending(demux_id)
-                        | group_call::JoinState::Joined(demux_id) => cx.number(demux_id).upcast(),
-                        _ => cx.null().upcast(),
-                    },
-                ];
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
-            }
+            let args = [cx.number(client_id).upcast(), js_info.upcast()];
 
-            Event::CallLinkResponse { request_id, result } => {
-                let method_name = "handleCallLinkResponse";
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-
-                let js_request_id = cx.number(request_id);
-                let (status, state_object) = match result {
-                    Ok(state) => {
-                        let state_object = cx.empty_object();
-                        let js_name = cx.string(state.name);
-                        state_object.set(&mut cx, "name", js_name)?;
-                        let js_revoked = cx.boolean(state.revoked);
-                        state_object.set(&mut cx, "revoked", js_revoked)?;
-                        let js_restrictions = cx.number(match state.restrictions {
-                            call_links::CallLinkRestrictions::None => 0,
-                            call_links::CallLinkRestrictions::AdminApproval => 1,
-                            call_links::CallLinkRestrictions::Unknown => -1,
-                        });
-                        state_object.set(&mut cx, "rawRestrictions", js_restrictions)?;
-                        let js_expiration = cx
-                            .date(
-                                state
-                                    .expiration
-                                    .duration_since(std::time::SystemTime::UNIX_EPOCH)
-                                    .unwrap_or_default()
-                                    .as_millis() as f64,
-                            )
-                            .or_else(|e| cx.throw_range_error(e.to_string()))?;
-                        state_object.set(&mut cx, "expiration", js_expiration)?;
-                        (cx.number(200), state_object.upcast())
-                    }
-                    Err(status_code) => (cx.number(status_code.code), cx.undefined().upcast()),
-                };
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-                method.call(
-                    &mut cx,
-                    observer,
-                    [js_request_id.upcast(), status.upcast(), state_object],
-                )?;
-            }
+        Event::GroupUpdate(GroupUpdate::PeekResult {
+            request_id,
+            peek_result,
+        }) => {
+            let (js_status, js_info) = match peek_result {
+                Ok(peek_info) => {
+                    let js_info = to_js_peek_info(cx, peek_info)?;
+                    (cx.number(200), js_info.upcast())
+                }
+                Err(status) => (cx.number(status.code), cx.undefined().upcast()),
+            };
 
-            Event::EmptyResponse { request_id, result } => {
-                let method_name = "handleEmptyResponse";
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
+            let method_name = "handlePeekResponse";
 
-                let js_request_id = cx.number(request_id);
-                let (status, state_object) = match result {
-                    Ok(_) => (cx.number(200), cx.empty_object().upcast()),
-                    Err(status_code) => (cx.number(status_code.code), cx.undefined().upcast()),
-                };
+            let args = [cx.number(request_id).upcast(), js_status.upcast(), js_info];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-                method.call(
-                    &mut cx,
-                    observer,
-                    [js_request_id.upcast(), status.upcast(), state_object],
-                )?;
-            }
+        Event::GroupUpdate(GroupUpdate::Ended(client_id, reason)) => {
+            let method_name = "handleEnded";
+            let args = [
+                cx.number(client_id).upcast(),
+                cx.number(reason as i32).upcast(),
+            ];
+            with_call_endpoint_object(cx, call_manager, |endpoint| {
+                endpoint.incoming_video_sink.clear();
+                Ok(())
+            })
+            .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-            Event::GroupUpdate(GroupUpdate::RemoteDeviceStatesChanged(
-                client_id,
-                remote_device_states,
-            )) => {
-                let method_name = "handleRemoteDevicesChanged";
-
-                let js_remote_device_states = JsArray::new(&mut cx, remote_device_states.len());
-                for (i, remote_device_state) in remote_device_states.iter().enumerate() {
-                    let demux_id = cx.number(remote_device_state.demux_id);
-                    let user_id = to_js_buffer(&mut cx, &remote_device_state.user_id);
-                    let media_keys_received = cx.boolean(remote_device_state.media_keys_received);
-                    let audio_muted: neon::handle::Handle<JsValue> =
-                        match remote_device_state.heartbeat_state.audio_muted {
-                            None => cx.undefined().upcast(),
-                            Some(muted) => cx.boolean(muted).upcast(),
-                        };
-                    let video_muted: neon::handle::Handle<JsValue> =
-                        match remote_device_state.heartbeat_state.video_muted {
-                            None => cx.undefined().upcast(),
-                            Some(muted) => cx.boolean(muted).upcast(),
-                        };
-                    let presenting: neon::handle::Handle<JsValue> =
-                        match remote_device_state.heartbeat_state.presenting {
-                            None => cx.undefined().upcast(),
-                            Some(muted) => cx.boolean(muted).upcast(),
-                        };
-                    let sharing_screen: neon::handle::Handle<JsValue> =
-                        match remote_device_state.heartbeat_state.sharing_screen {
-                            None => cx.undefined().upcast(),
-                            Some(muted) => cx.boolean(muted).upcast(),
-                        };
-                    // These are strings because we can't safely convert a u64 to a JavaScript-compatible number. We'll convert them to numeric types on the other side.
-                    let added_time: neon::handle::Handle<JsValue> = cx
-                        .string(remote_device_state.added_time_as_unix_millis().to_string())
-                        .upcast();
-                    let speaker_time: neon::handle::Handle<JsValue> = cx
-                        .string(
-                            remote_device_state
-                                .speaker_time_as_unix_millis()
-                                .to_string(),
-                        )
-                        .upcast();
-                    let forwarding_video: neon::handle::Handle<JsValue> =
-                        match remote_device_state.forwarding_video {
-                            None => cx.undefined().upcast(),
-                            Some(forwarding_video) => cx.boolean(forwarding_video).upcast(),
-                        };
-                    let is_higher_resolution_pending =
-                        cx.boolean(remote_device_state.is_higher_resolution_pending);
-
-                    let js_remote_device_state = cx.empty_object();
-                    js_remote_device_state.set(&mut cx, "demuxId", demux_id)?;
-                    js_remote_device_state.set(&mut cx, "userId", user_id)?;
-                    js_remote_device_state.set(
-                        &mut cx,
-                        "mediaKeysReceived",
-                        media_keys_received,
-                    )?;
-                    js_remote_device_state.set(&mut cx, "audioMuted", audio_muted)?;
-                    js_remote_device_state.set(&mut cx, "videoMuted", video_muted)?;
-                    js_remote_device_state.set(&mut cx, "presenting", presenting)?;
-                    js_remote_device_state.set(&mut cx, "sharingScreen", sharing_screen)?;
-                    js_remote_device_state.set(&mut cx, "addedTime", added_time)?;
-                    js_remote_device_state.set(&mut cx, "speakerTime", speaker_time)?;
-                    js_remote_device_state.set(&mut cx, "forwardingVideo", forwarding_video)?;
-                    js_remote_device_state.set(
-                        &mut cx,
-                        "isHigherResolutionPending",
-                        is_higher_resolution_pending,
-                    )?;
+        Event::GroupUpdate(GroupUpdate::Ring {
+            group_id,
+            ring_id,
+            sender_id,
+            update,
+        }) => {
+            let method_name = "groupCallRingUpdate";
 
-                    js_remote_device_states.set(&mut cx, i as u32, js_remote_device_state)?;
-                }
+            let args = [
+                to_js_buffer(cx, &group_id).upcast::<JsValue>(),
+                JsBigInt::from_i64(cx, ring_id.into()).upcast(),
+                to_js_buffer(cx, &sender_id).upcast(),
+                cx.number(update as i32).upcast(),
+            ];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-                let args = [
-                    cx.number(client_id).upcast(),
-                    js_remote_device_states.upcast(),
-                ];
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
+        Event::GroupUpdate(GroupUpdate::AudioLevels(
+            client_id,
+            captured_level,
+            received_levels,
+        )) => {
+            let js_received_levels = JsArray::new(cx, received_levels.len());
+            for (i, received_level) in received_levels.iter().enumerate() {
+                let js_received_level = JsObject::new(cx);
+                let js_demux_id = cx.number(received_level.demux_id);
+                js_received_level.set(cx, "demuxId", js_demux_id)?;
+                let js_level = cx.number(received_level.level);
+                js_received_level.set(cx, "level", js_level)?;
+                js_received_levels.set(cx, i as u32, js_received_level)?;
             }
 
-            Event::GroupUpdate(GroupUpdate::PeekChanged {
-                client_id,
-                peek_info,
-            }) => {
-                let method_name = "handlePeekChanged";
-                let js_info = to_js_peek_info(&mut cx, peek_info)?;
+            let method_name = "handleAudioLevels";
+            let args = [
+                cx.number(client_id).upcast(),
+                cx.number(captured_level).upcast(),
+                js_received_levels.upcast(),
+            ];
+
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+
+        Event::GroupUpdate(GroupUpdate::LowBandwidthForVideo {
+            group_id: client_id,
+            recovered,
+        }) => {
+            let method_name = "handleLowBandwidthForVideo";
+            let args = [
+                cx.number(client_id).upcast(),
+                cx.boolean(recovered).upcast(),
+            ];
 
-                let args = [cx.number(client_id).upcast(), js_info.upcast()];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
+        Event::GroupUpdate(GroupUpdate::Reactions(client_id, reactions)) => {
+            let method_name = "handleReactions";
+
+            let js_reactions = JsArray::new(cx, reactions.len());
+            for (i, reaction) in reactions.into_iter().enumerate() {
+                let js_reaction = JsObject::new(cx);
+                let js_demux_id = cx.number(reaction.demux_id);
+                js_reaction.set(cx, "demuxId", js_demux_id)?;
+                let js_value = cx.string(reaction.value);
+                js_reaction.set(cx, "value", js_value)?;
+                js_reactions.set(cx, i as u32, js_reaction)?;
             }
 
-            Event::GroupUpdate(GroupUpdate::PeekResult {
-                request_id,
-                peek_result,
-            }) => {
-                let (js_status, js_info) = match peek_result {
-                    Ok(peek_info) => {
-                        let js_info = to_js_peek_info(&mut cx, peek_info)?;
-                        (cx.number(200), js_info.upcast())
-                    }
-                    Err(status) => (cx.number(status.code), cx.undefined().upcast()),
-                };
+            let args = [cx.number(client_id).upcast(), js_reactions.upcast()];
 
-                let method_name = "handlePeekResponse";
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-                let args = [cx.number(request_id).upcast(), js_status.upcast(), js_info];
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
+        Event::GroupUpdate(GroupUpdate::RaisedHands(client_id, raised_hands)) => {
+            let js_raised_hands = JsArray::new(cx, raised_hands.len());
+            for (i, raised_hand) in raised_hands.into_iter().enumerate() {
+                let js_demux_id = cx.number(raised_hand);
+                js_raised_hands.set(cx, i as u32, js_demux_id)?;
             }
 
-            Event::GroupUpdate(GroupUpdate::Ended(client_id, reason)) => {
-                let method_name = "handleEnded";
-                let args = [
-                    cx.number(client_id).upcast(),
-                    cx.number(reason as i32).upcast(),
-                ];
-                with_call_endpoint(&mut cx, |endpoint| {
-                    endpoint.incoming_video_sink.clear();
-                    Ok(())
-                })
-                .or_else(|err: anyhow::Error| cx.throw_error(format!("{}", err)))?;
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
-            }
+            let method_name = "handleRaisedHands";
+            let args = [cx.number(client_id).upcast(), js_raised_hands.upcast()];
 
-            Event::GroupUpdate(GroupUpdate::Ring {
-                group_id,
-                ring_id,
-                sender_id,
-                update,
-            }) => {
-                let method_name = "groupCallRingUpdate";
-
-                let args = [
-                    to_js_buffer(&mut cx, &group_id).upcast::<JsValue>(),
-                    JsBigInt::from_i64(&mut cx, ring_id.into()).upcast(),
-                    to_js_buffer(&mut cx, &sender_id).upcast(),
-                    cx.number(update as i32).upcast(),
-                ];
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
-            }
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-            Event::GroupUpdate(GroupUpdate::AudioLevels(
-                client_id,
-                captured_level,
-                received_levels,
-            )) => {
-                let js_received_levels = JsArray::new(&mut cx, received_levels.len());
-                for (i, received_level) in received_levels.iter().enumerate() {
-                    let js_received_level = JsObject::new(&mut cx);
-                    let js_demux_id = cx.number(received_level.demux_id);
-                    js_received_level.set(&mut cx, "demuxId", js_demux_id)?;
-                    let js_level = cx.number(received_level.level);
-                    js_received_level.set(&mut cx, "level", js_level)?;
-                    js_received_levels.set(&mut cx, i as u32, js_received_level)?;
-                }
+        Event::GroupUpdate(GroupUpdate::AppData(client_id, demux_id, data)) => {
+            let method_name = "handleAppData";
+            let args = [
+                cx.number(client_id).upcast(),
+                cx.number(demux_id).upcast(),
+                to_js_buffer(cx, &data),
+            ];
 
-                let method_name = "handleAudioLevels";
-                let args = [
-                    cx.number(client_id).upcast(),
-                    cx.number(captured_level).upcast(),
-                    js_received_levels.upcast(),
-                ];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
+        Event::GroupUpdate(GroupUpdate::RemoteConnectionQualities(client_id, qualities)) => {
+            let js_qualities = JsArray::new(cx, qualities.len());
+            for (i, quality) in qualities.iter().enumerate() {
+                let js_quality = JsObject::new(cx);
+                let js_demux_id = cx.number(quality.demux_id);
+                js_quality.set(cx, "demuxId", js_demux_id)?;
+                let js_value = cx.number(quality.quality as i32);
+                js_quality.set(cx, "quality", js_value)?;
+                js_qualities.set(cx, i as u32, js_quality)?;
             }
 
-            Event::GroupUpdate(GroupUpdate::LowBandwidthForVideo {
-                group_id: client_id,
-                recovered,
-            }) => {
-                let method_name = "handleLowBandwidthForVideo";
-                let args = [
-                    cx.number(client_id).upcast(),
-                    cx.boolean(recovered).upcast(),
-                ];
-
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
-            }
+            let method_name = "handleRemoteConnectionQualities";
+            let args = [cx.number(client_id).upcast(), js_qualities.upcast()];
 
-            Event::GroupUpdate(GroupUpdate::Reactions(client_id, reactions)) => {
-                let method_name = "handleReactions";
-
-                let js_reactions = JsArray::new(&mut cx, reactions.len());
-                for (i, reaction) in reactions.into_iter().enumerate() {
-                    let js_reaction = JsObject::new(&mut cx);
-                    let js_demux_id = cx.number(reaction.demux_id);
-                    js_reaction.set(&mut cx, "demuxId", js_demux_id)?;
-                    let js_value = cx.string(reaction.value);
-                    js_reaction.set(&mut cx, "value", js_value)?;
-                    js_reactions.set(&mut cx, i as u32, js_reaction)?;
-                }
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-                let args = [cx.number(client_id).upcast(), js_reactions.upcast()];
+        Event::GroupUpdate(GroupUpdate::VideoAdaptationChanged(client_id, reason)) => {
+            let method_name = "handleVideoAdaptationChanged";
+            let args = [
+                cx.number(client_id).upcast(),
+                to_js_quality_limitation_reason(cx, reason),
+            ];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
+        Event::GroupUpdate(GroupUpdate::E2eeHealth(client_id, health)) => {
+            let js_health = JsObject::new(cx);
+            let js_window_millis = cx.number(health.window.as_millis() as f64);
+            js_health.set(cx, "windowMillis", js_window_millis)?;
+            let js_frames_decrypted = cx.number(health.frames_decrypted as f64);
+            js_health.set(cx, "framesDecrypted", js_frames_decrypted)?;
+            let js_frames_failed = cx.number(health.frames_failed as f64);
+            js_health.set(cx, "framesFailed", js_frames_failed)?;
+            let js_failures = JsArray::new(cx, health.failures_by_sender.len());
+            for (i, (demux_id, count)) in health.failures_by_sender.iter().enumerate() {
+                let js_failure = JsObject::new(cx);
+                let js_demux_id = cx.number(*demux_id);
+                js_failure.set(cx, "demuxId", js_demux_id)?;
+                let js_count = cx.number(*count as f64);
+                js_failure.set(cx, "framesFailed", js_count)?;
+                js_failures.set(cx, i as u32, js_failure)?;
             }
+            js_health.set(cx, "failuresBySender", js_failures)?;
 
-            Event::GroupUpdate(GroupUpdate::RaisedHands(client_id, raised_hands)) => {
-                let js_raised_hands = JsArray::new(&mut cx, raised_hands.len());
-                for (i, raised_hand) in raised_hands.into_iter().enumerate() {
-                    let js_demux_id = cx.number(raised_hand);
-                    js_raised_hands.set(&mut cx, i as u32, js_demux_id)?;
-                }
+            let method_name = "handleE2eeHealth";
+            let args = [cx.number(client_id).upcast(), js_health.upcast()];
 
-                let method_name = "handleRaisedHands";
-                let args = [cx.number(client_id).upcast(), js_raised_hands.upcast()];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
 
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
+        Event::GroupUpdate(GroupUpdate::AvSyncSkew(client_id, skews)) => {
+            let js_skews = JsArray::new(cx, skews.len());
+            for (i, skew) in skews.iter().enumerate() {
+                let js_skew = JsObject::new(cx);
+                let js_demux_id = cx.number(skew.demux_id);
+                js_skew.set(cx, "demuxId", js_demux_id)?;
+                let js_skew_millis = cx.number(skew.skew * 1000.0);
+                js_skew.set(cx, "skewMillis", js_skew_millis)?;
+                js_skews.set(cx, i as u32, js_skew)?;
             }
 
-            Event::GroupUpdate(GroupUpdate::RtcStatsReportComplete { report_json }) => {
-                let method_name = "handleRtcStatsReportComplete";
-                let args = [cx.string(report_json).upcast()];
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
-            }
-            Event::GroupUpdate(GroupUpdate::SpeechEvent(client_id, event)) => {
-                let method_name = "handleSpeechEvent";
-                let args = [
-                    cx.number(client_id).upcast(),
-                    cx.number(event as i32).upcast(),
-                ];
-                let method = observer.get::<JsFunction, _, _>(&mut cx, method_name)?;
-                method.call(&mut cx, observer, args)?;
-            }
+            let method_name = "handleAvSyncSkew";
+            let args = [cx.number(client_id).upcast(), js_skews.upcast()];
+
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+
+        Event::GroupUpdate(GroupUpdate::CallSummary(client_id, summary, reason)) => {
+            let js_summary = to_js_call_summary(cx, &summary)?;
+            let method_name = "handleGroupCallSummary";
+            let args = [
+                cx.number(client_id).upcast(),
+                js_summary.upcast(),
+                cx.number(reason as i32).upcast(),
+            ];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+
+        Event::GroupUpdate(GroupUpdate::OwnDeviceJoined(client_id, demux_id)) => {
+            let method_name = "handleOwnDeviceJoined";
+            let args = [cx.number(client_id).upcast(), cx.number(demux_id).upcast()];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+
+        Event::GroupUpdate(GroupUpdate::RequestIceServers(client_id)) => {
+            let method_name = "requestIceServers";
+            let args = [cx.number(client_id).upcast()];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+
+        Event::GroupUpdate(GroupUpdate::IceServersRefreshFailed(client_id)) => {
+            let method_name = "handleIceServersRefreshFailed";
+            let args = [cx.number(client_id).upcast()];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+
+        Event::GroupUpdate(GroupUpdate::BreakoutGroupChanged(client_id, breakout_group_id)) => {
+            let method_name = "handleBreakoutGroupChanged";
+            let js_breakout_group_id = match breakout_group_id {
+                None => cx.undefined().upcast(),
+                Some(breakout_group_id) => cx.number(breakout_group_id).upcast(),
+            };
+            let args = [cx.number(client_id).upcast(), js_breakout_group_id];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+
+        Event::GroupUpdate(GroupUpdate::CallFull {
+            client_id,
+            max_devices,
+            device_count,
+        }) => {
+            let method_name = "handleCallFull";
+            let js_max_devices = match max_devices {
+                None => cx.undefined().upcast(),
+                Some(max_devices) => cx.number(max_devices).upcast(),
+            };
+            let js_device_count = match device_count {
+                None => cx.undefined().upcast(),
+                Some(device_count) => cx.number(device_count).upcast(),
+            };
+            let args: [Handle<JsValue>; 3] = [
+                cx.number(client_id).upcast(),
+                js_max_devices,
+                js_device_count,
+            ];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+
+        Event::GroupUpdate(GroupUpdate::RtcStatsReportComplete { report_json }) => {
+            let method_name = "handleRtcStatsReportComplete";
+            let args = [cx.string(report_json).upcast()];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+        Event::GroupUpdate(GroupUpdate::SpeechEvent(client_id, event)) => {
+            let method_name = "handleSpeechEvent";
+            let args = [
+                cx.number(client_id).upcast(),
+                cx.number(event as i32).upcast(),
+            ];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
+        }
+        Event::AudioDevicesChanged => {
+            let (inputs, outputs) = with_call_endpoint_object(cx, call_manager, |endpoint| {
+                let pcf = &mut endpoint.peer_connection_factory;
+                (
+                    pcf.get_audio_recording_devices().unwrap_or_default(),
+                    pcf.get_audio_playout_devices().unwrap_or_default(),
+                )
+            });
+            let method_name = "onAudioDevicesChanged";
+            let args = [
+                to_js_audio_devices(cx, &inputs)?.upcast(),
+                to_js_audio_devices(cx, &outputs)?.upcast(),
+            ];
+            let method = observer.get::<JsFunction, _, _>(cx, method_name)?;
+            method.call(cx, observer, args)?;
         }
     }
-    Ok(cx.undefined().upcast())
+    Ok(())
 }
 
 #[allow(non_snake_case)]
@@ -2885,8 +4924,9 @@ fn CallLinkRootKey_parse(mut cx: FunctionContext) -> JsResult<JsBuffer> {
     let string = cx.argument::<JsString>(0)?.value(&mut cx);
     match CallLinkRootKey::try_from(string.as_str()) {
         Ok(key) => {
-            let mut buffer = cx.buffer(key.bytes().len())?;
-            buffer.as_mut_slice(&mut cx).copy_from_slice(&key.bytes());
+            let bytes = key.to_versioned_bytes();
+            let mut buffer = cx.buffer(bytes.len())?;
+            buffer.as_mut_slice(&mut cx).copy_from_slice(&bytes);
             Ok(buffer)
         }
         Err(e) => cx.throw_error(e.to_string()),
@@ -2904,9 +4944,9 @@ fn CallLinkRootKey_validate(mut cx: FunctionContext) -> JsResult<JsUndefined> {
 
 #[allow(non_snake_case)]
 fn CallLinkRootKey_generate(mut cx: FunctionContext) -> JsResult<JsBuffer> {
-    let key = CallLinkRootKey::generate(rand::rngs::OsRng);
-    let mut buffer = cx.buffer(key.bytes().len())?;
-    buffer.as_mut_slice(&mut cx).copy_from_slice(&key.bytes());
+    let bytes = CallLinkRootKey::generate(rand::rngs::OsRng).to_versioned_bytes();
+    let mut buffer = cx.buffer(bytes.len())?;
+    buffer.as_mut_slice(&mut cx).copy_from_slice(&bytes);
     Ok(buffer)
 }
 
@@ -2932,6 +4972,22 @@ fn CallLinkRootKey_deriveRoomId(mut cx: FunctionContext) -> JsResult<JsBuffer> {
     }
 }
 
+#[allow(non_snake_case)]
+fn CallLinkRootKey_withLatestVersion(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let bytes = cx.argument::<JsBuffer>(0)?;
+    match CallLinkRootKey::try_from(bytes.as_slice(&cx)) {
+        Ok(key) => {
+            let bytes = key
+                .with_version(CallLinkRootKeyVersion::LATEST)
+                .to_versioned_bytes();
+            let mut buffer = cx.buffer(bytes.len())?;
+            buffer.as_mut_slice(&mut cx).copy_from_slice(&bytes);
+            Ok(buffer)
+        }
+        Err(e) => cx.throw_error(e.to_string()),
+    }
+}
+
 #[allow(non_snake_case)]
 fn CallLinkRootKey_toFormattedString(mut cx: FunctionContext) -> JsResult<JsString> {
     let bytes = cx.argument::<JsBuffer>(0)?;
@@ -2944,6 +5000,117 @@ fn CallLinkRootKey_toFormattedString(mut cx: FunctionContext) -> JsResult<JsStri
     }
 }
 
+// A CryptoContext lives in a JsBox owned by the JS object that wraps it, and is freed when that
+// object is garbage collected. Calls only come from the JS thread, so its lock is never contended.
+impl Finalize for CryptoContext {}
+
+fn crypto_secret_argument(cx: &mut FunctionContext, i: usize) -> NeonResult<crypto::Secret> {
+    let secret = cx.argument::<JsBuffer>(i)?;
+    match crypto::Secret::try_from(secret.as_slice(cx)) {
+        Ok(secret) => Ok(secret),
+        Err(_) => cx.throw_error("secrets must be 32 bytes"),
+    }
+}
+
+fn crypto_send_state_to_js<'a>(
+    cx: &mut FunctionContext<'a>,
+    (ratchet_counter, secret): (crypto::RatchetCounter, crypto::Secret),
+) -> JsResult<'a, JsObject> {
+    let js_ratchet_counter = cx.number(ratchet_counter);
+    let mut js_secret = cx.buffer(secret.len())?;
+    js_secret.as_mut_slice(cx).copy_from_slice(&secret);
+    let obj = cx.empty_object();
+    obj.set(cx, "ratchetCounter", js_ratchet_counter)?;
+    obj.set(cx, "secret", js_secret)?;
+    Ok(obj)
+}
+
+#[allow(non_snake_case)]
+fn CryptoContext_new(mut cx: FunctionContext) -> JsResult<JsBox<CryptoContext>> {
+    let secret = crypto_secret_argument(&mut cx, 0)?;
+    Ok(cx.boxed(CryptoContext::new(secret)))
+}
+
+#[allow(non_snake_case)]
+fn CryptoContext_encrypt(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let context = cx.argument::<JsBox<CryptoContext>>(0)?;
+    let mut data = cx.argument::<JsBuffer>(1)?;
+    let mut mac = crypto::Mac::default();
+    let result = context.lock().encrypt(data.as_mut_slice(&mut cx), &mut mac);
+    match result {
+        Ok((ratchet_counter, frame_counter)) => {
+            let js_ratchet_counter = cx.number(ratchet_counter);
+            let js_frame_counter = cx.number(frame_counter as f64);
+            let mut js_mac = cx.buffer(mac.len())?;
+            js_mac.as_mut_slice(&mut cx).copy_from_slice(&mac);
+            let obj = cx.empty_object();
+            obj.set(&mut cx, "ratchetCounter", js_ratchet_counter)?;
+            obj.set(&mut cx, "frameCounter", js_frame_counter)?;
+            obj.set(&mut cx, "mac", js_mac)?;
+            Ok(obj)
+        }
+        Err(e) => cx.throw_error(e.to_string()),
+    }
+}
+
+#[allow(non_snake_case)]
+fn CryptoContext_decrypt(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let context = cx.argument::<JsBox<CryptoContext>>(0)?;
+    let sender_id = cx.argument::<JsNumber>(1)?.value(&mut cx) as crypto::SenderId;
+    let ratchet_counter = cx.argument::<JsNumber>(2)?.value(&mut cx) as crypto::RatchetCounter;
+    let frame_counter = cx.argument::<JsNumber>(3)?.value(&mut cx) as crypto::FrameCounter;
+    let mut data = cx.argument::<JsBuffer>(4)?;
+    let mac = cx.argument::<JsBuffer>(5)?;
+    let Ok(mac) = crypto::Mac::try_from(mac.as_slice(&cx)) else {
+        return cx.throw_error("MACs must be 16 bytes");
+    };
+    let decrypted = context
+        .lock()
+        .decrypt(
+            sender_id,
+            ratchet_counter,
+            frame_counter,
+            data.as_mut_slice(&mut cx),
+            &mac,
+        )
+        .is_ok();
+    Ok(cx.boolean(decrypted))
+}
+
+#[allow(non_snake_case)]
+fn CryptoContext_addReceiveSecret(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let context = cx.argument::<JsBox<CryptoContext>>(0)?;
+    let sender_id = cx.argument::<JsNumber>(1)?.value(&mut cx) as crypto::SenderId;
+    let ratchet_counter = cx.argument::<JsNumber>(2)?.value(&mut cx) as crypto::RatchetCounter;
+    let secret = crypto_secret_argument(&mut cx, 3)?;
+    context
+        .lock()
+        .add_receive_secret(sender_id, ratchet_counter, secret);
+    Ok(cx.undefined())
+}
+
+#[allow(non_snake_case)]
+fn CryptoContext_getSendState(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let context = cx.argument::<JsBox<CryptoContext>>(0)?;
+    let state = context.lock().send_state();
+    crypto_send_state_to_js(&mut cx, state)
+}
+
+#[allow(non_snake_case)]
+fn CryptoContext_advanceSendRatchet(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let context = cx.argument::<JsBox<CryptoContext>>(0)?;
+    let state = context.lock().advance_send_ratchet();
+    crypto_send_state_to_js(&mut cx, state)
+}
+
+#[allow(non_snake_case)]
+fn CryptoContext_resetSendRatchet(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let context = cx.argument::<JsBox<CryptoContext>>(0)?;
+    let secret = crypto_secret_argument(&mut cx, 1)?;
+    context.lock().reset_send_ratchet(secret);
+    Ok(cx.undefined())
+}
+
 #[neon::main]
 fn register(mut cx: ModuleContext) -> NeonResult<()> {
     cx.export_function("createCallEndpoint", createCallEndpoint)?;
@@ -2957,24 +5124,81 @@ fn register(mut cx: ModuleContext) -> NeonResult<()> {
         CallLinkRootKey_generateAdminPasskey,
     )?;
     cx.export_function("CallLinkRootKey_deriveRoomId", CallLinkRootKey_deriveRoomId)?;
+    cx.export_function(
+        "CallLinkRootKey_withLatestVersion",
+        CallLinkRootKey_withLatestVersion,
+    )?;
     cx.export_function(
         "CallLinkRootKey_toFormattedString",
         CallLinkRootKey_toFormattedString,
     )?;
 
+    cx.export_function("CryptoContext_new", CryptoContext_new)?;
+    cx.export_function("CryptoContext_encrypt", CryptoContext_encrypt)?;
+    cx.export_function("CryptoContext_decrypt", CryptoContext_decrypt)?;
+    cx.export_function(
+        "CryptoContext_addReceiveSecret",
+        CryptoContext_addReceiveSecret,
+    )?;
+    cx.export_function("CryptoContext_getSendState", CryptoContext_getSendState)?;
+    cx.export_function(
+        "CryptoContext_advanceSendRatchet",
+        CryptoContext_advanceSendRatchet,
+    )?;
+    cx.export_function(
+        "CryptoContext_resetSendRatchet",
+        CryptoContext_resetSendRatchet,
+    )?;
+
     let js_property_key = cx.string(CALL_ENDPOINT_PROPERTY_KEY);
     cx.export_value("callEndpointPropertyKey", js_property_key)?;
 
     cx.export_function("cm_setSelfUuid", setSelfUuid)?;
+    cx.export_function("cm_setMaxLogLevel", setMaxLogLevel)?;
+    cx.export_function("cm_setModuleLogLevel", setModuleLogLevel)?;
+    cx.export_function("cm_clearModuleLogLevels", clearModuleLogLevels)?;
+    cx.export_function("cm_exportDiagnostics", exportDiagnostics)?;
+    cx.export_function("cm_setCrashDumpPath", setCrashDumpPath)?;
+    cx.export_function("cm_getSetupLatencyHistograms", getSetupLatencyHistograms)?;
+    cx.export_function("cm_setGroupCallIntervals", setGroupCallIntervals)?;
     cx.export_function("cm_createOutgoingCall", createOutgoingCall)?;
+    cx.export_function("cm_takeOverCall", takeOverCall)?;
     cx.export_function("cm_cancelGroupRing", cancelGroupRing)?;
     cx.export_function("cm_proceed", proceed)?;
+    cx.export_function("cm_preGatherIce", preGatherIce)?;
     cx.export_function("cm_accept", accept)?;
+    cx.export_function("cm_setOnHold", setOnHold)?;
+    cx.export_function("cm_handOff", handOff)?;
+    cx.export_function("cm_restartIce", restartIce)?;
+    cx.export_function("cm_startEarlyMedia", startEarlyMedia)?;
+    cx.export_function("cm_enableVideo", enableVideo)?;
+    cx.export_function("cm_disableVideo", disableVideo)?;
+    cx.export_function("cm_respondToVideoRequest", respondToVideoRequest)?;
+    cx.export_function("cm_sendAppData", sendAppData)?;
+    cx.export_function("cm_getVerificationCode", getVerificationCode)?;
+    cx.export_function("cm_getWideCallId", getWideCallId)?;
+    cx.export_function("cm_setCallAppData", setCallAppData)?;
+    cx.export_function("cm_setRestartIceOnDisconnect", setRestartIceOnDisconnect)?;
+    cx.export_function("cm_setRelayOnlyByDefault", setRelayOnlyByDefault)?;
+    cx.export_function("cm_setMaxSignalingMessageSize", setMaxSignalingMessageSize)?;
     cx.export_function("cm_ignore", ignore)?;
     cx.export_function("cm_hangup", hangup)?;
+    cx.export_function("cm_decline", decline)?;
+    cx.export_function("cm_setCallWaitingEnabled", setCallWaitingEnabled)?;
+    cx.export_function("cm_holdAndAcceptWaitingCall", holdAndAcceptWaitingCall)?;
+    cx.export_function("cm_declineWaitingCall", declineWaitingCall)?;
+    cx.export_function("cm_resumeHeldCall", resumeHeldCall)?;
+    cx.export_function("cm_hangupHeldCall", hangupHeldCall)?;
     cx.export_function("cm_signalingMessageSent", signalingMessageSent)?;
     cx.export_function("cm_signalingMessageSendFailed", signalingMessageSendFailed)?;
     cx.export_function("cm_updateDataMode", updateDataMode)?;
+    cx.export_function("cm_updateAudioProfile", updateAudioProfile)?;
+    cx.export_function("cm_setAudioLevelsInterval", setAudioLevelsInterval)?;
+    cx.export_function("cm_setAudioLossProtection", setAudioLossProtection)?;
+    cx.export_function("cm_getStats", getStats)?;
+    cx.export_function("cm_getCandidatePairs", getCandidatePairs)?;
+    cx.export_function("cm_updateBitrateConfig", updateBitrateConfig)?;
+    cx.export_function("cm_setMaxSendBitrate", setMaxSendBitrate)?;
     cx.export_function("cm_receivedOffer", receivedOffer)?;
     cx.export_function("cm_receivedAnswer", receivedAnswer)?;
     cx.export_function("cm_receivedIceCandidates", receivedIceCandidates)?;
@@ -2989,7 +5213,12 @@ fn register(mut cx: ModuleContext) -> NeonResult<()> {
         "cm_setOutgoingVideoIsScreenShare",
         setOutgoingVideoIsScreenShare,
     )?;
+    cx.export_function("cm_setOutgoingVideoLimits", setOutgoingVideoLimits)?;
     cx.export_function("cm_sendVideoFrame", sendVideoFrame)?;
+    cx.export_function("cm_sendAudioSamples", sendAudioSamples)?;
+    cx.export_function("cm_setAudioInjectionMode", setAudioInjectionMode)?;
+    cx.export_function("cm_clearInjectedAudio", clearInjectedAudio)?;
+    cx.export_function("cm_shareSystemAudio", shareSystemAudio)?;
     cx.export_function("cm_receiveVideoFrame", receiveVideoFrame)?;
     cx.export_function("cm_receiveGroupCallVideoFrame", receiveGroupCallVideoFrame)?;
     cx.export_function("cm_createGroupCallClient", createGroupCallClient)?;
@@ -3009,9 +5238,37 @@ fn register(mut cx: ModuleContext) -> NeonResult<()> {
     cx.export_function("cm_groupRing", groupRing)?;
     cx.export_function("cm_groupReact", groupReact)?;
     cx.export_function("cm_groupRaiseHand", groupRaiseHand)?;
+    cx.export_function("cm_groupSendAppData", groupSendAppData)?;
+    cx.export_function("cm_groupLowerAllHands", groupLowerAllHands)?;
+    cx.export_function("cm_groupAssignBreakoutGroups", groupAssignBreakoutGroups)?;
+    cx.export_function("cm_groupEndBreakoutGroups", groupEndBreakoutGroups)?;
+    cx.export_function("cm_setLowerHandWhenSpeaking", setLowerHandWhenSpeaking)?;
+    cx.export_function(
+        "cm_setDetectSpeakingWhileMuted",
+        setDetectSpeakingWhileMuted,
+    )?;
+    cx.export_function(
+        "cm_setSkipMediaKeysForBridgedParticipants",
+        setSkipMediaKeysForBridgedParticipants,
+    )?;
+    cx.export_function("cm_setLeaveWhenOwnDeviceJoins", setLeaveWhenOwnDeviceJoins)?;
+    cx.export_function("cm_setKeyRotationPolicy", setKeyRotationPolicy)?;
     cx.export_function("cm_resendMediaKeys", resendMediaKeys)?;
     cx.export_function("cm_setDataMode", setDataMode)?;
+    cx.export_function("cm_setGroupCallAudioProfile", setGroupCallAudioProfile)?;
+    cx.export_function(
+        "cm_setGroupCallAudioLevelsInterval",
+        setGroupCallAudioLevelsInterval,
+    )?;
+    cx.export_function(
+        "cm_setGroupCallAudioLossProtection",
+        setGroupCallAudioLossProtection,
+    )?;
+    cx.export_function("cm_getGroupCallStats", getGroupCallStats)?;
+    cx.export_function("cm_getGroupCallCandidatePairs", getGroupCallCandidatePairs)?;
     cx.export_function("cm_requestVideo", requestVideo)?;
+    cx.export_function("cm_setRenderingPaused", setRenderingPaused)?;
+    cx.export_function("cm_setAllRenderingPaused", setAllRenderingPaused)?;
     cx.export_function("cm_approveUser", approveUser)?;
     cx.export_function("cm_denyUser", denyUser)?;
     cx.export_function("cm_removeClient", removeClient)?;
@@ -3024,11 +5281,15 @@ fn register(mut cx: ModuleContext) -> NeonResult<()> {
     cx.export_function("cm_createCallLink", createCallLink)?;
     cx.export_function("cm_updateCallLink", updateCallLink)?;
     cx.export_function("cm_deleteCallLink", deleteCallLink)?;
+    cx.export_function("cm_rotateCallLinkAdminPasskey", rotateCallLinkAdminPasskey)?;
+    cx.export_function("cm_migrateCallLink", migrateCallLink)?;
     cx.export_function("cm_getAudioInputs", getAudioInputs)?;
     cx.export_function("cm_setAudioInput", setAudioInput)?;
+    cx.export_function("cm_setAudioInputById", setAudioInputById)?;
     cx.export_function("cm_getAudioOutputs", getAudioOutputs)?;
     cx.export_function("cm_setAudioOutput", setAudioOutput)?;
+    cx.export_function("cm_setAudioOutputById", setAudioOutputById)?;
     cx.export_function("cm_setRtcStatsInterval", setRtcStatsInterval)?;
-    cx.export_function("cm_processEvents", processEvents)?;
+    cx.export_function("cm_setGroupCallIceServers", setGroupCallIceServers)?;
     Ok(())
 }
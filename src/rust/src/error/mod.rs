@@ -39,6 +39,8 @@ pub enum RingRtcError {
     ActiveMediaStreamAlreadySet(DeviceId),
     #[error("Pending incoming call is already set, remote_device: {0}")]
     PendingCallAlreadySet(DeviceId),
+    #[error("Handoff is already in progress, remote_device: {0}")]
+    HandoffAlreadyInProgress(DeviceId),
     #[error("Application Connection is already set, remote_device: {0}")]
     AppConnectionAlreadySet(DeviceId),
     #[error("Application Call Context is already set, call_id: {0}")]
@@ -67,6 +69,9 @@ pub enum RingRtcError {
     #[allow(dead_code)]
     #[error("Unable to set Audio Device")]
     SetAudioDevice,
+    #[allow(dead_code)]
+    #[error("No Audio Device with the given ID")]
+    AudioDeviceNotFound,
 
     // WebRTC / C++ session description error codes
     #[error("CreateSessionDescriptionObserver failure. error msg: {0}, type: {1}")]
@@ -91,6 +96,14 @@ pub enum RingRtcError {
     MungeSdp,
     #[error("Unknown signaled protocol version")]
     UnknownSignaledProtocolVersion,
+    #[error("Invalid decline reason: {0}")]
+    InvalidDeclineReason(i32),
+    #[error("Invalid pending signaling message, missing or bad: {0}")]
+    InvalidPendingMessage(&'static str),
+    #[error("Invalid compressed signaling message")]
+    InvalidCompressedOpaque,
+    #[error("Signaling message of {0} bytes can't fit in {1} bytes")]
+    SignalingMessageTooLarge(usize, usize),
 
     // RTP Data error codes
     #[error("RTP data protocol error: {0}")]
@@ -99,6 +112,10 @@ pub enum RingRtcError {
     SendRtp,
     #[error("Unable to receive RTP data")]
     ReceiveRtp,
+    #[error("App data of {0} bytes can't fit in {1} bytes")]
+    AppDataTooLarge(usize, usize),
+    #[error("Too much reliable app data is waiting to be acknowledged")]
+    AppDataQueueFull,
 
     // IceGatherer error codes
     #[error("UseSharedIceGatherer failure")]
@@ -127,4 +144,6 @@ pub enum RingRtcError {
     SrtpKeyNegotiationFailure,
     #[error("Buffer too small")]
     BufferTooSmall,
+    #[error("Invalid log level: {0}")]
+    InvalidLogLevel(i32),
 }
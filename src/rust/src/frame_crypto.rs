@@ -0,0 +1,348 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! A plain C API for RingRTC's frame encryption, for integrators that carry
+//! media through their own pipeline but need to interoperate with RingRTC
+//! clients. It wraps [`crypto::Context`], the same state a group call uses.
+//!
+//! # Ownership
+//!
+//! A context is created with rtc_crypto_Context_create() and owned by the
+//! caller until passed to rtc_crypto_Context_destroy(). Buffers passed in are
+//! only borrowed for the duration of the call.
+//!
+//! # Thread safety
+//!
+//! Every function may be called from any thread. Calls on the same context
+//! are serialized by a lock, so a context can be shared between an encoding
+//! and a decoding thread, though each call then waits for the other.
+
+use std::sync::{Mutex, MutexGuard};
+
+use libc::size_t;
+
+use crate::core::crypto::{self, FrameCounter, RatchetCounter, Secret, SenderId};
+
+/// The size of the secrets that seed the send and receive ratchets.
+pub const RTC_CRYPTO_SECRET_SIZE_BYTES: size_t = std::mem::size_of::<Secret>();
+/// The size of the MAC sent along with each encrypted frame.
+pub const RTC_CRYPTO_MAC_SIZE_BYTES: size_t = crypto::MAC_SIZE_BYTES;
+
+/// A [`crypto::Context`] that can be shared across threads.
+pub struct CryptoContext(Mutex<crypto::Context>);
+
+impl CryptoContext {
+    pub fn new(initial_send_secret: Secret) -> Self {
+        Self(Mutex::new(crypto::Context::new(initial_send_secret)))
+    }
+
+    pub fn lock(&self) -> MutexGuard<'_, crypto::Context> {
+        // The context is never left partially updated, so poisoning is harmless.
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// # Safety
+///
+/// `secret` must point to RTC_CRYPTO_SECRET_SIZE_BYTES readable bytes.
+unsafe fn read_secret(secret: *const u8) -> Option<Secret> {
+    if secret.is_null() {
+        return None;
+    }
+    let mut out = Secret::default();
+    out.copy_from_slice(std::slice::from_raw_parts(secret, out.len()));
+    Some(out)
+}
+
+/// Creates a context that encrypts with the given send secret, which must
+/// also be shared with the receivers, at ratchet counter 0. Returns null if
+/// `initial_send_secret` is null.
+///
+/// # Safety
+///
+/// `initial_send_secret` must point to RTC_CRYPTO_SECRET_SIZE_BYTES bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rtc_crypto_Context_create(
+    initial_send_secret: *const u8,
+) -> *mut CryptoContext {
+    match read_secret(initial_send_secret) {
+        Some(secret) => Box::into_raw(Box::new(CryptoContext::new(secret))),
+        None => {
+            error!("null initial_send_secret passed into rtc_crypto_Context_create");
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// # Safety
+///
+/// `context` must come from `rtc_crypto_Context_create` and not already be destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn rtc_crypto_Context_destroy(context: *mut CryptoContext) {
+    if !context.is_null() {
+        drop(Box::from_raw(context));
+    }
+}
+
+/// Encrypts `data` in place, filling in the MAC, ratchet counter, and frame
+/// counter to send along with it. Returns false on failure, leaving `data`
+/// untouched.
+///
+/// # Safety
+///
+/// - `context` must come from `rtc_crypto_Context_create` and not already be destroyed.
+/// - `data` must point to `data_len` writable bytes.
+/// - `mac_out` must point to RTC_CRYPTO_MAC_SIZE_BYTES writable bytes.
+/// - `ratchet_counter_out` and `frame_counter_out` must be valid to write.
+#[no_mangle]
+pub unsafe extern "C" fn rtc_crypto_Context_encrypt(
+    context: *const CryptoContext,
+    data: *mut u8,
+    data_len: size_t,
+    mac_out: *mut u8,
+    ratchet_counter_out: *mut RatchetCounter,
+    frame_counter_out: *mut FrameCounter,
+) -> bool {
+    let Some(context) = context.as_ref() else {
+        error!("null context passed into rtc_crypto_Context_encrypt");
+        return false;
+    };
+    if (data.is_null() && data_len > 0)
+        || mac_out.is_null()
+        || ratchet_counter_out.is_null()
+        || frame_counter_out.is_null()
+    {
+        error!("null buffer passed into rtc_crypto_Context_encrypt");
+        return false;
+    }
+    let data = if data_len > 0 {
+        std::slice::from_raw_parts_mut(data, data_len)
+    } else {
+        &mut []
+    };
+    let mut mac = crypto::Mac::default();
+    match context.lock().encrypt(data, &mut mac) {
+        Ok((ratchet_counter, frame_counter)) => {
+            std::slice::from_raw_parts_mut(mac_out, mac.len()).copy_from_slice(&mac);
+            *ratchet_counter_out = ratchet_counter;
+            *frame_counter_out = frame_counter;
+            true
+        }
+        Err(err) => {
+            warn!("rtc_crypto_Context_encrypt() failed: {}", err);
+            false
+        }
+    }
+}
+
+/// Decrypts `data` in place, using the receive secrets added for
+/// `sender_id`. Returns false if none of them match the MAC, in which case
+/// `data` is left untouched.
+///
+/// # Safety
+///
+/// - `context` must come from `rtc_crypto_Context_create` and not already be destroyed.
+/// - `data` must point to `data_len` writable bytes.
+/// - `mac` must point to RTC_CRYPTO_MAC_SIZE_BYTES readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rtc_crypto_Context_decrypt(
+    context: *const CryptoContext,
+    sender_id: SenderId,
+    ratchet_counter: RatchetCounter,
+    frame_counter: FrameCounter,
+    data: *mut u8,
+    data_len: size_t,
+    mac: *const u8,
+) -> bool {
+    let Some(context) = context.as_ref() else {
+        error!("null context passed into rtc_crypto_Context_decrypt");
+        return false;
+    };
+    if (data.is_null() && data_len > 0) || mac.is_null() {
+        error!("null buffer passed into rtc_crypto_Context_decrypt");
+        return false;
+    }
+    let data = if data_len > 0 {
+        std::slice::from_raw_parts_mut(data, data_len)
+    } else {
+        &mut []
+    };
+    let mut mac_copy = crypto::Mac::default();
+    mac_copy.copy_from_slice(std::slice::from_raw_parts(mac, mac_copy.len()));
+    match context
+        .lock()
+        .decrypt(sender_id, ratchet_counter, frame_counter, data, &mac_copy)
+    {
+        Ok(()) => true,
+        Err(err) => {
+            debug!("rtc_crypto_Context_decrypt() failed: {}", err);
+            false
+        }
+    }
+}
+
+/// Adds a secret that `sender_id` encrypts with, as of `ratchet_counter`.
+/// A few older secrets are kept per sender for frames that arrive late.
+///
+/// # Safety
+///
+/// - `context` must come from `rtc_crypto_Context_create` and not already be destroyed.
+/// - `secret` must point to RTC_CRYPTO_SECRET_SIZE_BYTES readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rtc_crypto_Context_addReceiveSecret(
+    context: *const CryptoContext,
+    sender_id: SenderId,
+    ratchet_counter: RatchetCounter,
+    secret: *const u8,
+) {
+    let Some(context) = context.as_ref() else {
+        error!("null context passed into rtc_crypto_Context_addReceiveSecret");
+        return;
+    };
+    let Some(secret) = read_secret(secret) else {
+        error!("null secret passed into rtc_crypto_Context_addReceiveSecret");
+        return;
+    };
+    context
+        .lock()
+        .add_receive_secret(sender_id, ratchet_counter, secret);
+}
+
+/// Gets the current send ratchet counter and secret, as receivers need them.
+///
+/// # Safety
+///
+/// - `context` must come from `rtc_crypto_Context_create` and not already be destroyed.
+/// - `ratchet_counter_out` must be valid to write.
+/// - `secret_out` must point to RTC_CRYPTO_SECRET_SIZE_BYTES writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rtc_crypto_Context_getSendState(
+    context: *const CryptoContext,
+    ratchet_counter_out: *mut RatchetCounter,
+    secret_out: *mut u8,
+) -> bool {
+    let Some(context) = context.as_ref() else {
+        error!("null context passed into rtc_crypto_Context_getSendState");
+        return false;
+    };
+    let state = context.lock().send_state();
+    write_send_state(state, ratchet_counter_out, secret_out)
+}
+
+/// Ratchets the send secret forward, as when a receiver joins, and gets the
+/// new ratchet counter and secret. Receivers that had the previous secret
+/// can ratchet to the new one themselves.
+///
+/// # Safety
+///
+/// - `context` must come from `rtc_crypto_Context_create` and not already be destroyed.
+/// - `ratchet_counter_out` must be valid to write.
+/// - `secret_out` must point to RTC_CRYPTO_SECRET_SIZE_BYTES writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rtc_crypto_Context_advanceSendRatchet(
+    context: *const CryptoContext,
+    ratchet_counter_out: *mut RatchetCounter,
+    secret_out: *mut u8,
+) -> bool {
+    let Some(context) = context.as_ref() else {
+        error!("null context passed into rtc_crypto_Context_advanceSendRatchet");
+        return false;
+    };
+    let state = context.lock().advance_send_ratchet();
+    write_send_state(state, ratchet_counter_out, secret_out)
+}
+
+/// Replaces the send secret, resetting the ratchet counter to 0, as when a
+/// receiver leaves and must not be able to decrypt what follows.
+///
+/// # Safety
+///
+/// - `context` must come from `rtc_crypto_Context_create` and not already be destroyed.
+/// - `secret` must point to RTC_CRYPTO_SECRET_SIZE_BYTES readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rtc_crypto_Context_resetSendRatchet(
+    context: *const CryptoContext,
+    secret: *const u8,
+) {
+    let Some(context) = context.as_ref() else {
+        error!("null context passed into rtc_crypto_Context_resetSendRatchet");
+        return;
+    };
+    let Some(secret) = read_secret(secret) else {
+        error!("null secret passed into rtc_crypto_Context_resetSendRatchet");
+        return;
+    };
+    context.lock().reset_send_ratchet(secret);
+}
+
+/// # Safety
+///
+/// Same as for `rtc_crypto_Context_getSendState`.
+unsafe fn write_send_state(
+    (ratchet_counter, secret): (RatchetCounter, Secret),
+    ratchet_counter_out: *mut RatchetCounter,
+    secret_out: *mut u8,
+) -> bool {
+    if ratchet_counter_out.is_null() || secret_out.is_null() {
+        error!("null output passed for the send state");
+        return false;
+    }
+    *ratchet_counter_out = ratchet_counter;
+    std::slice::from_raw_parts_mut(secret_out, secret.len()).copy_from_slice(&secret);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_through_c_api() {
+        let secret = [1u8; 32];
+        let plaintext = b"frame".to_vec();
+        unsafe {
+            let sender = rtc_crypto_Context_create(secret.as_ptr());
+            let receiver = rtc_crypto_Context_create([2u8; 32].as_ptr());
+            rtc_crypto_Context_addReceiveSecret(receiver, 1, 0, secret.as_ptr());
+
+            let mut data = plaintext.clone();
+            let mut mac = [0u8; RTC_CRYPTO_MAC_SIZE_BYTES];
+            let mut ratchet_counter = 0;
+            let mut frame_counter = 0;
+            assert!(rtc_crypto_Context_encrypt(
+                sender,
+                data.as_mut_ptr(),
+                data.len(),
+                mac.as_mut_ptr(),
+                &mut ratchet_counter,
+                &mut frame_counter,
+            ));
+            assert_ne!(plaintext, data);
+
+            assert!(!rtc_crypto_Context_decrypt(
+                receiver,
+                2,
+                ratchet_counter,
+                frame_counter,
+                data.as_mut_ptr(),
+                data.len(),
+                mac.as_ptr(),
+            ));
+            assert!(rtc_crypto_Context_decrypt(
+                receiver,
+                1,
+                ratchet_counter,
+                frame_counter,
+                data.as_mut_ptr(),
+                data.len(),
+                mac.as_ptr(),
+            ));
+            assert_eq!(plaintext, data);
+
+            rtc_crypto_Context_destroy(sender);
+            rtc_crypto_Context_destroy(receiver);
+        }
+    }
+}
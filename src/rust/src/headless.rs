@@ -0,0 +1,552 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! A plain C API for participating in group calls without an application
+//! shim, for server-side participants such as recording, transcription, or
+//! load-testing bots written in other languages.
+//!
+//! Only audio is sent, pushed with rtc_groupcall_Client_sendAudio(), which
+//! replaces the microphone. Received media isn't delivered to the delegate,
+//! since WebRTC doesn't expose the decoded audio of remote devices.
+//!
+//! HTTP requests to the SFU go through an rtc_http_Client, as with the lite
+//! API. Media keys are exchanged over the application's signaling channel:
+//! outgoing messages are handed to the delegate, and received messages must
+//! be passed back with rtc_groupcall_Client_receivedCallMessage().
+
+use std::{collections::HashSet, sync::Arc};
+
+use anyhow::anyhow;
+use libc::{c_void, size_t};
+use prost::Message;
+
+use crate::{
+    common::Result,
+    core::{
+        call_mutex::CallMutex,
+        group_call::{
+            self, ClientId, ConnectionState, EndReason, GroupId, HttpSfuClient, JoinState,
+            Reaction, RemoteDeviceState, RemoteDevicesChangedReason, SignalingMessageUrgency,
+            SpeechEvent,
+        },
+    },
+    lite::{
+        ffi::ios::{rtc_Bytes, rtc_OptionalU32, rtc_String},
+        http,
+        sfu::{
+            ios::{rtc_UserIds, rtc_sfu_GroupMembers},
+            DemuxId, MemberMap, ObfuscatedResolver, PeekInfo, UserId,
+        },
+    },
+    protobuf,
+    webrtc::{
+        audio_injector::AudioInjectionMode,
+        media::VideoTrack,
+        peer_connection::{AudioLevel, ReceivedAudioLevel},
+        peer_connection_factory::{AudioConfig, PeerConnectionFactory, RffiAudioDeviceModuleType},
+        peer_connection_observer::NetworkRoute,
+    },
+};
+
+/// There is only ever one group call per Client.
+const CLIENT_ID: ClientId = 1;
+
+/// A group call joined without an application shim.
+pub struct Client {
+    group_call: group_call::Client,
+    peer_connection_factory: PeerConnectionFactory,
+}
+
+impl Client {
+    fn start(
+        http_client: &http::ios::Client,
+        config: &rtc_groupcall_ClientConfig,
+        delegate: rtc_groupcall_Delegate,
+    ) -> Result<Self> {
+        let sfu_url = config
+            .sfu_url
+            .to_string()
+            .ok_or_else(|| anyhow!("invalid sfu_url"))?;
+        let delegate = Arc::new(delegate);
+
+        let peer_connection_factory = PeerConnectionFactory::new(
+            &AudioConfig {
+                audio_device_module_type: RffiAudioDeviceModuleType::RingRtc,
+                ..Default::default()
+            },
+            false,
+        )?;
+        // There is no microphone to mix with.
+        peer_connection_factory
+            .audio_injector()?
+            .set_mode(AudioInjectionMode::Replace)?;
+        let outgoing_audio_track = peer_connection_factory.create_outgoing_audio_track()?;
+
+        let sfu_client = HttpSfuClient::new(
+            Box::new(http_client.clone()),
+            sfu_url,
+            None,
+            None,
+            config.hkdf_extra_info.to_vec(),
+        );
+        let group_call = group_call::Client::start(group_call::ClientStartParams {
+            group_id: config.group_id.to_vec(),
+            client_id: CLIENT_ID,
+            kind: group_call::GroupCallKind::SignalGroup,
+            sfu_client: Box::new(sfu_client),
+            observer: Box::new(Observer { delegate }),
+            busy: Arc::new(CallMutex::new(false, "busy")),
+            self_uuid: Arc::new(CallMutex::new(Some(config.self_uuid.to_vec()), "self_uuid")),
+            peer_connection_factory: Some(peer_connection_factory.clone()),
+            outgoing_audio_track,
+            outgoing_video_track: None,
+            incoming_video_sink: None,
+            ring_id: None,
+            audio_levels_interval: None,
+            intervals: Default::default(),
+            obfuscated_resolver: ObfuscatedResolver::new(Arc::new(MemberMap::new(&[])), None),
+            ice_servers: vec![],
+        })?;
+
+        Ok(Self {
+            group_call,
+            peer_connection_factory,
+        })
+    }
+
+    fn received_call_message(&self, sender_user_id: UserId, message: &[u8]) -> Result<()> {
+        let message = protobuf::signaling::CallMessage::decode(message)?;
+        let group_call_message = message
+            .group_call_message
+            .ok_or_else(|| anyhow!("not a group call message"))?;
+        self.group_call
+            .on_signaling_message_received(sender_user_id, group_call_message);
+        Ok(())
+    }
+
+    fn send_audio(&self, samples: &[i16], sample_rate_hz: u32, channels: usize) -> Result<()> {
+        self.peer_connection_factory
+            .audio_injector()?
+            .push(samples, sample_rate_hz, channels)
+    }
+}
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct rtc_groupcall_ClientConfig<'a> {
+    pub sfu_url: rtc_String<'a>,
+    pub group_id: rtc_Bytes<'a>,
+    /// The ACI of the bot's account, needed to exchange media keys.
+    pub self_uuid: rtc_Bytes<'a>,
+    pub hkdf_extra_info: rtc_Bytes<'a>,
+}
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct rtc_groupcall_RemoteDevice<'a> {
+    pub demux_id: DemuxId,
+    pub user_id: rtc_Bytes<'a>,
+    pub media_keys_received: bool,
+}
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct rtc_groupcall_RemoteDevices<'a> {
+    pub ptr: *const rtc_groupcall_RemoteDevice<'a>,
+    pub count: size_t,
+}
+
+/// The callbacks are invoked on RingRTC's threads, and the audio callback on
+/// WebRTC's media threads, so they should return quickly.
+#[repr(C)]
+pub struct rtc_groupcall_Delegate {
+    pub retained: *mut c_void,
+    pub release: extern "C" fn(retained: *mut c_void),
+    /// Respond with rtc_groupcall_Client_setMembershipProof().
+    pub request_membership_proof: extern "C" fn(unretained: *const c_void),
+    /// Respond with rtc_groupcall_Client_setGroupMembers().
+    pub request_group_members: extern "C" fn(unretained: *const c_void),
+    /// Send a serialized CallMessage to the given user. The urgency is a
+    /// group_call::SignalingMessageUrgency.
+    pub send_call_message: extern "C" fn(
+        unretained: *const c_void,
+        recipient_id: rtc_Bytes<'_>,
+        message: rtc_Bytes<'_>,
+        urgency: i32,
+    ),
+    /// Send a serialized CallMessage to the group, or only to the given
+    /// recipients if there are any.
+    pub send_call_message_to_group: extern "C" fn(
+        unretained: *const c_void,
+        group_id: rtc_Bytes<'_>,
+        message: rtc_Bytes<'_>,
+        urgency: i32,
+        recipients_override: rtc_UserIds<'_>,
+    ),
+    /// The connection state is a group_call::ConnectionState ordinal.
+    pub handle_connection_state_changed:
+        extern "C" fn(unretained: *const c_void, connection_state: i32),
+    /// The join state is a group_call::JoinState ordinal, with the local
+    /// demux ID once one has been assigned.
+    pub handle_join_state_changed:
+        extern "C" fn(unretained: *const c_void, join_state: i32, demux_id: rtc_OptionalU32),
+    pub handle_remote_devices_changed:
+        extern "C" fn(unretained: *const c_void, remote_devices: rtc_groupcall_RemoteDevices<'_>),
+    /// The reason is a group_call::EndReason.
+    pub handle_ended: extern "C" fn(unretained: *const c_void, reason: i32),
+}
+
+unsafe impl Send for rtc_groupcall_Delegate {}
+unsafe impl Sync for rtc_groupcall_Delegate {}
+
+impl Drop for rtc_groupcall_Delegate {
+    fn drop(&mut self) {
+        (self.release)(self.retained)
+    }
+}
+
+fn urgency_ordinal(urgency: SignalingMessageUrgency) -> i32 {
+    match urgency {
+        SignalingMessageUrgency::Droppable => 0,
+        SignalingMessageUrgency::HandleImmediately => 1,
+    }
+}
+
+struct Observer {
+    delegate: Arc<rtc_groupcall_Delegate>,
+}
+
+impl group_call::Observer for Observer {
+    fn request_membership_proof(&self, _client_id: ClientId) {
+        (self.delegate.request_membership_proof)(self.delegate.retained);
+    }
+
+    fn request_group_members(&self, _client_id: ClientId) {
+        (self.delegate.request_group_members)(self.delegate.retained);
+    }
+
+    fn send_signaling_message(
+        &mut self,
+        recipient_id: UserId,
+        call_message: protobuf::signaling::CallMessage,
+        urgency: SignalingMessageUrgency,
+    ) {
+        let message = call_message.encode_to_vec();
+        (self.delegate.send_call_message)(
+            self.delegate.retained,
+            rtc_Bytes::from(&recipient_id),
+            rtc_Bytes::from(&message),
+            urgency_ordinal(urgency),
+        );
+    }
+
+    fn send_signaling_message_to_group(
+        &mut self,
+        group_id: GroupId,
+        call_message: protobuf::signaling::CallMessage,
+        urgency: SignalingMessageUrgency,
+        recipients_override: HashSet<UserId>,
+    ) {
+        let message = call_message.encode_to_vec();
+        let recipients_override: Vec<rtc_Bytes<'_>> =
+            recipients_override.iter().map(rtc_Bytes::from).collect();
+        (self.delegate.send_call_message_to_group)(
+            self.delegate.retained,
+            rtc_Bytes::from(&group_id),
+            rtc_Bytes::from(&message),
+            urgency_ordinal(urgency),
+            rtc_UserIds::from(&recipients_override),
+        );
+    }
+
+    fn handle_connection_state_changed(
+        &self,
+        _client_id: ClientId,
+        connection_state: ConnectionState,
+    ) {
+        (self.delegate.handle_connection_state_changed)(
+            self.delegate.retained,
+            connection_state.ordinal(),
+        );
+    }
+
+    fn handle_network_route_changed(&self, _client_id: ClientId, _network_route: NetworkRoute) {}
+
+    fn handle_join_state_changed(&self, _client_id: ClientId, join_state: JoinState) {
+        let demux_id = match join_state {
+            JoinState::Pending(demux_id) | JoinState::Joined(demux_id) => {
+                rtc_OptionalU32::from(demux_id)
+            }
+            JoinState::NotJoined(_) | JoinState::Joining => rtc_OptionalU32::default(),
+        };
+        (self.delegate.handle_join_state_changed)(
+            self.delegate.retained,
+            join_state.ordinal(),
+            demux_id,
+        );
+    }
+
+    fn handle_remote_devices_changed(
+        &self,
+        _client_id: ClientId,
+        remote_devices: &[RemoteDeviceState],
+        _reason: RemoteDevicesChangedReason,
+    ) {
+        let remote_devices: Vec<rtc_groupcall_RemoteDevice<'_>> = remote_devices
+            .iter()
+            .map(|device| rtc_groupcall_RemoteDevice {
+                demux_id: device.demux_id,
+                user_id: rtc_Bytes::from(&device.user_id),
+                media_keys_received: device.media_keys_received,
+            })
+            .collect();
+        (self.delegate.handle_remote_devices_changed)(
+            self.delegate.retained,
+            rtc_groupcall_RemoteDevices {
+                ptr: remote_devices.as_ptr(),
+                count: remote_devices.len(),
+            },
+        );
+    }
+
+    fn handle_peek_changed(
+        &self,
+        _client_id: ClientId,
+        _peek_info: &PeekInfo,
+        _joined_members: &HashSet<UserId>,
+    ) {
+    }
+
+    fn handle_incoming_video_track(
+        &mut self,
+        _client_id: ClientId,
+        _remote_demux_id: DemuxId,
+        _incoming_video_track: VideoTrack,
+    ) {
+    }
+
+    fn handle_speaking_notification(&mut self, _client_id: ClientId, _speech_event: SpeechEvent) {}
+
+    fn handle_audio_levels(
+        &self,
+        _client_id: ClientId,
+        _captured_level: AudioLevel,
+        _received_levels: Vec<ReceivedAudioLevel>,
+    ) {
+    }
+
+    fn handle_low_bandwidth_for_video(&self, _client_id: ClientId, _recovered: bool) {}
+
+    fn handle_reactions(&self, _client_id: ClientId, _reactions: Vec<Reaction>) {}
+
+    fn handle_raised_hands(&self, _client_id: ClientId, _raised_hands: Vec<DemuxId>) {}
+
+    fn handle_rtc_stats_report(&self, _report_json: String) {}
+
+    fn handle_ended(&self, _client_id: ClientId, reason: EndReason) {
+        (self.delegate.handle_ended)(self.delegate.retained, reason as i32);
+    }
+}
+
+/// Creates a client and starts connecting to the group call's SFU. Call
+/// rtc_groupcall_Client_join() to start exchanging media.
+///
+/// Returns null on failure, in which case the delegate has been released.
+///
+/// # Safety
+///
+/// `http_client` must come from `rtc_http_Client_create` and not already be destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn rtc_groupcall_Client_create(
+    http_client: *const http::ios::Client,
+    config: rtc_groupcall_ClientConfig,
+    delegate: rtc_groupcall_Delegate,
+) -> *mut Client {
+    info!("rtc_groupcall_Client_create():");
+
+    let Some(http_client) = http_client.as_ref() else {
+        error!("null http_client passed into rtc_groupcall_Client_create");
+        return std::ptr::null_mut();
+    };
+    match Client::start(http_client, &config, delegate) {
+        Ok(client) => {
+            client.group_call.connect();
+            Box::into_raw(Box::new(client))
+        }
+        Err(err) => {
+            error!("rtc_groupcall_Client_create() failed: {:?}", err);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Leaves the call and disconnects from the SFU. No more delegate callbacks
+/// are made once this returns, though the delegate may be released later.
+///
+/// # Safety
+///
+/// `client` must come from `rtc_groupcall_Client_create` and not already be destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn rtc_groupcall_Client_destroy(client: *mut Client) {
+    info!("rtc_groupcall_Client_destroy():");
+
+    if client.is_null() {
+        return;
+    }
+    let client = Box::from_raw(client);
+    client.group_call.disconnect();
+    client.group_call.synchronize();
+}
+
+/// # Safety
+///
+/// `client` must come from `rtc_groupcall_Client_create` and not already be destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn rtc_groupcall_Client_setMembershipProof(
+    client: *const Client,
+    membership_proof: rtc_Bytes,
+) {
+    if let Some(client) = client.as_ref() {
+        client
+            .group_call
+            .set_membership_proof(membership_proof.to_vec());
+    } else {
+        error!("null client passed into rtc_groupcall_Client_setMembershipProof");
+    }
+}
+
+/// # Safety
+///
+/// `client` must come from `rtc_groupcall_Client_create` and not already be destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn rtc_groupcall_Client_setGroupMembers(
+    client: *const Client,
+    group_members: rtc_sfu_GroupMembers,
+) {
+    if let Some(client) = client.as_ref() {
+        client.group_call.set_group_members(group_members.to_vec());
+    } else {
+        error!("null client passed into rtc_groupcall_Client_setGroupMembers");
+    }
+}
+
+/// # Safety
+///
+/// `client` must come from `rtc_groupcall_Client_create` and not already be destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn rtc_groupcall_Client_join(client: *const Client) {
+    if let Some(client) = client.as_ref() {
+        client.group_call.join();
+    } else {
+        error!("null client passed into rtc_groupcall_Client_join");
+    }
+}
+
+/// # Safety
+///
+/// `client` must come from `rtc_groupcall_Client_create` and not already be destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn rtc_groupcall_Client_leave(client: *const Client) {
+    if let Some(client) = client.as_ref() {
+        client.group_call.leave();
+    } else {
+        error!("null client passed into rtc_groupcall_Client_leave");
+    }
+}
+
+/// Passes a CallMessage received over the signaling channel, which carries
+/// the media keys of the other participants.
+///
+/// Returns false if the message could not be parsed.
+///
+/// # Safety
+///
+/// `client` must come from `rtc_groupcall_Client_create` and not already be destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn rtc_groupcall_Client_receivedCallMessage(
+    client: *const Client,
+    sender_user_id: rtc_Bytes,
+    message: rtc_Bytes,
+) -> bool {
+    let Some(client) = client.as_ref() else {
+        error!("null client passed into rtc_groupcall_Client_receivedCallMessage");
+        return false;
+    };
+    match client.received_call_message(sender_user_id.to_vec(), message.as_slice()) {
+        Ok(()) => true,
+        Err(err) => {
+            error!(
+                "rtc_groupcall_Client_receivedCallMessage() failed: {:?}",
+                err
+            );
+            false
+        }
+    }
+}
+
+/// Sends the local media key again to everyone in the call, for example
+/// after the application's signaling channel was interrupted.
+///
+/// # Safety
+///
+/// `client` must come from `rtc_groupcall_Client_create` and not already be destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn rtc_groupcall_Client_resendMediaKeys(client: *const Client) {
+    if let Some(client) = client.as_ref() {
+        client.group_call.resend_media_keys();
+    } else {
+        error!("null client passed into rtc_groupcall_Client_resendMediaKeys");
+    }
+}
+
+/// # Safety
+///
+/// `client` must come from `rtc_groupcall_Client_create` and not already be destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn rtc_groupcall_Client_setOutgoingAudioMuted(
+    client: *const Client,
+    muted: bool,
+) {
+    if let Some(client) = client.as_ref() {
+        client.group_call.set_outgoing_audio_muted(muted);
+    } else {
+        error!("null client passed into rtc_groupcall_Client_setOutgoingAudioMuted");
+    }
+}
+
+/// Queues interleaved 16-bit PCM audio to be sent, at any sample rate.
+/// Silence is sent when nothing is queued.
+///
+/// Returns false if the audio could not be queued.
+///
+/// # Safety
+///
+/// - `client` must come from `rtc_groupcall_Client_create` and not already be destroyed.
+/// - `samples` must point to `samples_per_channel * channels` samples.
+#[no_mangle]
+pub unsafe extern "C" fn rtc_groupcall_Client_sendAudio(
+    client: *const Client,
+    samples: *const i16,
+    samples_per_channel: size_t,
+    channels: size_t,
+    sample_rate_hz: u32,
+) -> bool {
+    let Some(client) = client.as_ref() else {
+        error!("null client passed into rtc_groupcall_Client_sendAudio");
+        return false;
+    };
+    if samples.is_null() {
+        error!("null samples passed into rtc_groupcall_Client_sendAudio");
+        return false;
+    }
+    let samples = std::slice::from_raw_parts(samples, samples_per_channel * channels);
+    match client.send_audio(samples, sample_rate_hz, channels) {
+        Ok(()) => true,
+        Err(err) => {
+            error!("rtc_groupcall_Client_sendAudio() failed: {:?}", err);
+            false
+        }
+    }
+}
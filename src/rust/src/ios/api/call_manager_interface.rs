@@ -10,7 +10,7 @@ use std::{convert::TryFrom, ffi::c_void, fmt, ptr, slice, time::Duration};
 use libc::size_t;
 
 use crate::{
-    common::{CallConfig, CallMediaType, DataMode, DeviceId},
+    common::{AcceptOptions, CallBitrateConfig, CallConfig, CallMediaType, DataMode, DeviceId},
     core::{group_call, signaling},
     ios::{call_manager, call_manager::IosCallManager},
     lite::{call_links::CallLinkRootKey, http, sfu, sfu::DemuxId},
@@ -225,6 +225,7 @@ impl Drop for AppMediaStreamInterface {
 pub struct AppRemoteDeviceState {
     pub demuxId: DemuxId,
     pub user_id: AppByteSlice,
+    pub participantKind: i32,
     pub mediaKeysReceived: bool,
     pub audioMuted: AppOptionalBool,
     pub videoMuted: AppOptionalBool,
@@ -344,8 +345,13 @@ pub struct AppInterface {
     ),
     /// Swift event callback method.
     pub onEvent: extern "C" fn(object: *mut c_void, remote: *const c_void, event: i32),
-    pub onNetworkRouteChanged:
-        extern "C" fn(object: *mut c_void, remote: *const c_void, localNetworkAdapterType: i32),
+    pub onNetworkRouteChanged: extern "C" fn(
+        object: *mut c_void,
+        remote: *const c_void,
+        localNetworkAdapterType: i32,
+        localNetworkAdapterTypeUnderVpn: i32,
+        relayed: bool,
+    ),
     pub onAudioLevels: extern "C" fn(
         object: *mut c_void,
         remote: *const c_void,
@@ -415,6 +421,7 @@ pub struct AppInterface {
         context: *mut c_void,
         audioJitterBufferMaxPackets: i32,
         audioJitterBufferMaxTargetDelayMs: i32,
+        relayOnly: bool,
     ) -> AppConnectionInterface,
     /// Request that the application create an application Media Stream object
     /// associated with the given application Connection object.
@@ -429,6 +436,13 @@ pub struct AppInterface {
     pub onCompareRemotes:
         extern "C" fn(object: *mut c_void, remote1: *const c_void, remote2: *const c_void) -> bool,
     pub onCallConcluded: extern "C" fn(object: *mut c_void, remote: *const c_void),
+    pub onHandoffRequested: extern "C" fn(
+        object: *mut c_void,
+        remoteUserId: AppByteSlice,
+        callId: u64,
+        remoteDeviceId: u32,
+        callMediaType: i32,
+    ),
 
     // Group Calls
     pub groupCallRingUpdate: extern "C" fn(
@@ -497,6 +511,12 @@ pub struct AppInterface {
         extern "C" fn(object: *mut c_void, clientId: group_call::ClientId, reason: i32),
     pub handleSpeakingNotification:
         extern "C" fn(object: *mut c_void, clientId: group_call::ClientId, event: i32),
+    pub handleCallFull: extern "C" fn(
+        object: *mut c_void,
+        clientId: group_call::ClientId,
+        maxDevices: AppOptionalUInt32,
+        deviceCount: AppOptionalUInt32,
+    ),
 }
 
 // Add an empty Send trait to allow transfer of ownership between threads.
@@ -575,6 +595,30 @@ pub extern "C" fn ringrtcCall(
     }
 }
 
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcTakeOverCall(
+    callManager: *mut c_void,
+    appRemote: *const c_void,
+    callId: u64,
+    callMediaType: i32,
+    appLocalDevice: u32,
+) -> *mut c_void {
+    match call_manager::take_over_call(
+        callManager as *mut IosCallManager,
+        appRemote,
+        callId,
+        CallMediaType::from_i32(callMediaType),
+        appLocalDevice as DeviceId,
+    ) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
 #[no_mangle]
 #[allow(non_snake_case)]
 pub extern "C" fn ringrtcProceed(
@@ -583,17 +627,39 @@ pub extern "C" fn ringrtcProceed(
     appCallContext: AppCallContext,
     dataMode: i32,
     audioLevelsIntervalMillis: u64,
+    hideIp: bool,
+    ringTimeoutMillis: u64,
+    connectTimeoutMillis: u64,
+    earlyMedia: bool,
+    mediaStallTimeoutMillis: u64,
+    hangUpOnMediaStall: bool,
 ) -> *mut c_void {
     let audio_levels_interval = if audioLevelsIntervalMillis == 0 {
         None
     } else {
         Some(Duration::from_millis(audioLevelsIntervalMillis))
     };
+    let mut call_config = CallConfig::default()
+        .with_data_mode(DataMode::from_i32(dataMode))
+        .with_relay_only(hideIp)
+        .with_early_media(earlyMedia);
+    if ringTimeoutMillis > 0 {
+        call_config = call_config.with_ring_timeout(Duration::from_millis(ringTimeoutMillis));
+    }
+    if connectTimeoutMillis > 0 {
+        call_config = call_config.with_connect_timeout(Duration::from_millis(connectTimeoutMillis));
+    }
+    if mediaStallTimeoutMillis > 0 {
+        call_config = call_config.with_media_stall_timeout(
+            Duration::from_millis(mediaStallTimeoutMillis),
+            hangUpOnMediaStall,
+        );
+    }
     match call_manager::proceed(
         callManager as *mut IosCallManager,
         callId,
         appCallContext,
-        CallConfig::default().with_data_mode(DataMode::from_i32(dataMode)),
+        call_config,
         audio_levels_interval,
     ) {
         Ok(_v) => {
@@ -640,6 +706,30 @@ pub extern "C" fn ringrtcHangup(callManager: *mut c_void) -> *mut c_void {
     }
 }
 
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcDecline(
+    callManager: *mut c_void,
+    callId: u64,
+    reason: i32,
+) -> *mut c_void {
+    let reason = match signaling::DeclineReason::from_i32(reason) {
+        Some(reason) => reason,
+        None => {
+            error!("Invalid decline reason: {}", reason);
+            return ptr::null_mut();
+        }
+    };
+
+    match call_manager::decline(callManager as *mut IosCallManager, callId, reason) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
 #[no_mangle]
 #[allow(non_snake_case)]
 pub extern "C" fn ringrtcCancelGroupRing(
@@ -873,8 +963,273 @@ pub extern "C" fn ringrtcReceivedCallMessage(
 
 #[no_mangle]
 #[allow(non_snake_case)]
-pub extern "C" fn ringrtcAccept(callManager: *mut c_void, callId: u64) -> *mut c_void {
-    match call_manager::accept_call(callManager as *mut IosCallManager, callId) {
+pub extern "C" fn ringrtcAccept(
+    callManager: *mut c_void,
+    callId: u64,
+    audioMuted: bool,
+    videoDisabled: bool,
+) -> *mut c_void {
+    match call_manager::accept_call(
+        callManager as *mut IosCallManager,
+        callId,
+        AcceptOptions {
+            audio_muted: audioMuted,
+            video_disabled: videoDisabled,
+        },
+    ) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcSetOnHold(
+    callManager: *mut c_void,
+    callId: u64,
+    onHold: bool,
+) -> *mut c_void {
+    match call_manager::set_on_hold(callManager as *mut IosCallManager, callId, onHold) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcHandOff(
+    callManager: *mut c_void,
+    callId: u64,
+    remoteUserId: AppByteSlice,
+    targetDeviceId: u32,
+) -> *mut c_void {
+    let remote_user_id = match byte_vec_from_app_slice(&remoteUserId) {
+        Some(remote_user_id) => remote_user_id,
+        None => {
+            error!("Missing remote user ID");
+            return ptr::null_mut();
+        }
+    };
+    match call_manager::hand_off(
+        callManager as *mut IosCallManager,
+        callId,
+        remote_user_id,
+        targetDeviceId as DeviceId,
+    ) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcRestartIce(callManager: *mut c_void, callId: u64) -> *mut c_void {
+    match call_manager::restart_ice(callManager as *mut IosCallManager, callId) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcEnableVideo(callManager: *mut c_void, callId: u64) -> *mut c_void {
+    match call_manager::enable_video(callManager as *mut IosCallManager, callId) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcDisableVideo(callManager: *mut c_void, callId: u64) -> *mut c_void {
+    match call_manager::disable_video(callManager as *mut IosCallManager, callId) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+/// Returns the verification code of the call, or -1 if there isn't one yet.
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcGetVerificationCode(callManager: *mut c_void, callId: u64) -> i32 {
+    match call_manager::get_verification_code(callManager as *mut IosCallManager, callId) {
+        Ok(Some(code)) => code.value() as i32,
+        Ok(None) => -1,
+        Err(e) => {
+            error!("{:?}", e);
+            -1
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcRespondToVideoRequest(
+    callManager: *mut c_void,
+    callId: u64,
+    accepted: bool,
+) -> *mut c_void {
+    match call_manager::respond_to_video_request(
+        callManager as *mut IosCallManager,
+        callId,
+        accepted,
+    ) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcStartEarlyMedia(callManager: *mut c_void, callId: u64) -> *mut c_void {
+    match call_manager::start_early_media(callManager as *mut IosCallManager, callId) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcSetRestartIceOnDisconnect(
+    callManager: *mut c_void,
+    enabled: bool,
+) -> *mut c_void {
+    match call_manager::set_restart_ice_on_disconnect(callManager as *mut IosCallManager, enabled) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcSetAudioSessionActive(
+    callManager: *mut c_void,
+    active: bool,
+) -> *mut c_void {
+    match call_manager::set_audio_session_active(callManager as *mut IosCallManager, active) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcSetRelayOnlyByDefault(
+    callManager: *mut c_void,
+    enabled: bool,
+) -> *mut c_void {
+    match call_manager::set_relay_only_by_default(callManager as *mut IosCallManager, enabled) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcSetMaxSignalingMessageSize(
+    callManager: *mut c_void,
+    maxSize: u32,
+) -> *mut c_void {
+    match call_manager::set_max_signaling_message_size(
+        callManager as *mut IosCallManager,
+        (maxSize > 0).then_some(maxSize as usize),
+    ) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcSetCallWaitingEnabled(
+    callManager: *mut c_void,
+    enabled: bool,
+) -> *mut c_void {
+    match call_manager::set_call_waiting_enabled(callManager as *mut IosCallManager, enabled) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcHoldAndAcceptWaitingCall(callManager: *mut c_void) -> *mut c_void {
+    match call_manager::hold_and_accept_waiting_call(callManager as *mut IosCallManager) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcDeclineWaitingCall(callManager: *mut c_void) -> *mut c_void {
+    match call_manager::decline_waiting_call(callManager as *mut IosCallManager) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcResumeHeldCall(callManager: *mut c_void) -> *mut c_void {
+    match call_manager::resume_held_call(callManager as *mut IosCallManager) {
+        Ok(_v) => {
+            // Return the object reference back as indication of success.
+            callManager
+        }
+        Err(_e) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcHangupHeldCall(callManager: *mut c_void) -> *mut c_void {
+    match call_manager::hangup_held_call(callManager as *mut IosCallManager) {
         Ok(_v) => {
             // Return the object reference back as indication of success.
             callManager
@@ -937,6 +1292,23 @@ pub extern "C" fn ringrtcUpdateDataMode(callManager: *mut c_void, dataMode: i32)
     }
 }
 
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "C" fn ringrtcUpdateBitrateConfig(
+    callManager: *mut c_void,
+    maxAudioBitrateBps: u64,
+    maxVideoBitrateBps: u64,
+    maxTotalBitrateBps: u64,
+) {
+    let result = call_manager::update_bitrate_config(
+        callManager as *mut IosCallManager,
+        CallBitrateConfig::from_bps(maxAudioBitrateBps, maxVideoBitrateBps, maxTotalBitrateBps),
+    );
+    if result.is_err() {
+        error!("ringrtcUpdateBitrateConfig(): {:?}", result.err());
+    }
+}
+
 #[no_mangle]
 #[allow(non_snake_case)]
 pub extern "C" fn ringrtcDrop(callManager: *mut c_void, callId: u64) -> *mut c_void {
@@ -10,7 +10,10 @@ use std::{ffi::c_void, sync::Arc, time::Duration};
 use anyhow::anyhow;
 
 use crate::{
-    common::{CallConfig, CallId, CallMediaType, DataMode, DeviceId, Result},
+    common::{
+        AcceptOptions, CallBitrateConfig, CallConfig, CallId, CallMediaType, DataMode, DeviceId,
+        Result, VerificationCode,
+    },
     core::{
         call_manager,
         call_manager::CallManager,
@@ -66,6 +69,23 @@ pub fn call(
     )
 }
 
+/// Application notification to take over a call from another device of the local user.
+pub fn take_over_call(
+    call_manager: *mut IosCallManager,
+    app_remote: *const c_void,
+    call_id: u64,
+    call_media_type: CallMediaType,
+    app_local_device: DeviceId,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.take_over_call(
+        AppObject::from(app_remote),
+        CallId::from(call_id),
+        call_media_type,
+        app_local_device,
+    )
+}
+
 /// Application notification to proceed with a new call
 pub fn proceed(
     call_manager: *mut IosCallManager,
@@ -104,6 +124,16 @@ pub fn hangup(call_manager: *mut IosCallManager) -> Result<()> {
     call_manager.hangup()
 }
 
+/// Application notification to decline the active call with a reason.
+pub fn decline(
+    call_manager: *mut IosCallManager,
+    call_id: u64,
+    reason: signaling::DeclineReason,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.decline(CallId::from(call_id), reason)
+}
+
 /// Application notification cancelling a group ring.
 pub fn cancel_group_ring(
     call_manager: *mut IosCallManager,
@@ -297,10 +327,142 @@ pub fn received_call_message(
 }
 
 /// Application notification to accept the incoming call
-pub fn accept_call(call_manager: *mut IosCallManager, call_id: u64) -> Result<()> {
+pub fn accept_call(
+    call_manager: *mut IosCallManager,
+    call_id: u64,
+    options: AcceptOptions,
+) -> Result<()> {
+    let call_id = CallId::from(call_id);
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.accept_call_with_options(call_id, options)
+}
+
+/// Application request to put the call on hold or take it off hold
+pub fn set_on_hold(call_manager: *mut IosCallManager, call_id: u64, on_hold: bool) -> Result<()> {
+    let call_id = CallId::from(call_id);
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_on_hold(call_id, on_hold)
+}
+
+/// Application request to have another device of the local user take over the call
+pub fn hand_off(
+    call_manager: *mut IosCallManager,
+    call_id: u64,
+    remote_user_id: UserId,
+    target_device_id: DeviceId,
+) -> Result<()> {
+    let call_id = CallId::from(call_id);
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.hand_off(call_id, remote_user_id, target_device_id)
+}
+
+/// Application request to restart ICE on the active call
+pub fn restart_ice(call_manager: *mut IosCallManager, call_id: u64) -> Result<()> {
+    let call_id = CallId::from(call_id);
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.restart_ice(call_id)
+}
+
+/// Application request to start sending video on the active call
+pub fn enable_video(call_manager: *mut IosCallManager, call_id: u64) -> Result<()> {
+    let call_id = CallId::from(call_id);
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.enable_video(call_id)
+}
+
+/// Application request to stop sending video on the active call
+pub fn disable_video(call_manager: *mut IosCallManager, call_id: u64) -> Result<()> {
+    let call_id = CallId::from(call_id);
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.disable_video(call_id)
+}
+
+/// Application response to the remote peer's request to send video
+pub fn respond_to_video_request(
+    call_manager: *mut IosCallManager,
+    call_id: u64,
+    accepted: bool,
+) -> Result<()> {
+    let call_id = CallId::from(call_id);
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.respond_to_video_request(call_id, accepted)
+}
+
+/// Application request for the verification code of an active call
+pub fn get_verification_code(
+    call_manager: *mut IosCallManager,
+    call_id: u64,
+) -> Result<Option<VerificationCode>> {
+    let call_id = CallId::from(call_id);
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.verification_code(call_id)
+}
+
+/// Application request to send early media on a ringing incoming call
+pub fn start_early_media(call_manager: *mut IosCallManager, call_id: u64) -> Result<()> {
     let call_id = CallId::from(call_id);
     let call_manager = unsafe { ptr_as_mut(call_manager)? };
-    call_manager.accept_call(call_id)
+    call_manager.start_early_media(call_id)
+}
+
+/// Application request to enable or disable restarting ICE when the connection drops
+pub fn set_restart_ice_on_disconnect(
+    call_manager: *mut IosCallManager,
+    enabled: bool,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_restart_ice_on_disconnect(enabled)
+}
+
+/// Application notification that the audio session was activated or deactivated
+pub fn set_audio_session_active(call_manager: *mut IosCallManager, active: bool) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_audio_session_active(active)
+}
+
+/// Application request to enable or disable relay-only ICE for all calls
+pub fn set_relay_only_by_default(call_manager: *mut IosCallManager, enabled: bool) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_relay_only_by_default(enabled)
+}
+
+/// Application request to limit the size of outgoing offers and answers
+pub fn set_max_signaling_message_size(
+    call_manager: *mut IosCallManager,
+    max_size: Option<usize>,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_max_signaling_message_size(max_size)
+}
+
+/// Application request to enable or disable call waiting
+pub fn set_call_waiting_enabled(call_manager: *mut IosCallManager, enabled: bool) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.set_call_waiting_enabled(enabled)
+}
+
+/// Application request to put the active call on hold and accept the waiting call
+pub fn hold_and_accept_waiting_call(call_manager: *mut IosCallManager) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.hold_and_accept_waiting_call()
+}
+
+/// Application request to decline the waiting call as busy
+pub fn decline_waiting_call(call_manager: *mut IosCallManager) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.decline_waiting_call()
+}
+
+/// Application request to resume the held call once no other call is active
+pub fn resume_held_call(call_manager: *mut IosCallManager) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.resume_held_call()
+}
+
+/// Application request to hang up the held call
+pub fn hangup_held_call(call_manager: *mut IosCallManager) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    call_manager.hangup_held_call()
 }
 
 /// CMI request for the active Connection object
@@ -348,6 +510,16 @@ pub fn update_data_mode(call_manager: *mut IosCallManager, data_mode: DataMode)
     active_connection.inject_update_data_mode(data_mode)
 }
 
+/// Request to update the bitrate caps on the direct connection
+pub fn update_bitrate_config(
+    call_manager: *mut IosCallManager,
+    bitrate_config: CallBitrateConfig,
+) -> Result<()> {
+    let call_manager = unsafe { ptr_as_mut(call_manager)? };
+    let mut active_connection = call_manager.active_connection()?;
+    active_connection.inject_update_bitrate_config(bitrate_config)
+}
+
 /// CMI request to drop the active call
 pub fn drop_call(call_manager: *mut IosCallManager, call_id: u64) -> Result<()> {
     let call_id = CallId::from(call_id);
@@ -410,6 +582,7 @@ pub fn create_group_call_client(
         outgoing_audio_track,
         outgoing_video_track,
         None,
+        vec![],
     )
 }
 
@@ -454,6 +627,7 @@ pub fn create_call_link_call_client(
         outgoing_audio_track,
         outgoing_video_track,
         None,
+        vec![],
     )
 }
 
@@ -115,6 +115,7 @@ impl Platform for IosPlatform {
         let audio_jitter_buffer_max_packets = call_config.audio_jitter_buffer_config.max_packets;
         let audio_jitter_buffer_max_target_delay_ms =
             call_config.audio_jitter_buffer_config.max_target_delay_ms;
+        let relay_only = call_config.relay_only;
 
         let connection = Connection::new(
             call.clone(),
@@ -144,6 +145,7 @@ impl Platform for IosPlatform {
             call.call_context()?.object,
             audio_jitter_buffer_max_packets,
             audio_jitter_buffer_max_target_delay_ms,
+            relay_only,
         );
 
         if app_connection_interface.object.is_null() || app_connection_interface.pc.is_null() {
@@ -186,6 +188,7 @@ impl Platform for IosPlatform {
         call_id: CallId,
         direction: CallDirection,
         call_media_type: CallMediaType,
+        _app_data: &[u8],
     ) -> Result<()> {
         info!("on_start_call(): id: {}, direction: {}", call_id, direction);
 
@@ -205,6 +208,7 @@ impl Platform for IosPlatform {
         remote_peer: &Self::AppRemotePeer,
         _call_id: CallId,
         event: ApplicationEvent,
+        _app_data: &[u8],
     ) -> Result<()> {
         info!("on_event(): {}", event);
 
@@ -224,6 +228,8 @@ impl Platform for IosPlatform {
             self.app_interface.object,
             remote_peer.ptr,
             network_route.local_adapter_type as i32,
+            network_route.local_adapter_type_under_vpn as i32,
+            network_route.relayed(),
         );
 
         Ok(())
@@ -517,12 +523,23 @@ impl Platform for IosPlatform {
         remote_peer: &Self::AppRemotePeer,
         call_id: CallId,
         _age: Duration,
+        app_data: &[u8],
     ) -> Result<()> {
         // iOS already keeps track of the offer timestamp, so no need to pass the age through.
-        self.on_event(remote_peer, call_id, ApplicationEvent::ReceivedOfferExpired)
+        self.on_event(
+            remote_peer,
+            call_id,
+            ApplicationEvent::ReceivedOfferExpired,
+            app_data,
+        )
     }
 
-    fn on_call_concluded(&self, remote_peer: &Self::AppRemotePeer, _call_id: CallId) -> Result<()> {
+    fn on_call_concluded(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        _call_id: CallId,
+        _app_data: &[u8],
+    ) -> Result<()> {
         info!("on_call_concluded():");
 
         (self.app_interface.onCallConcluded)(self.app_interface.object, remote_peer.ptr);
@@ -530,6 +547,21 @@ impl Platform for IosPlatform {
         Ok(())
     }
 
+    fn on_handoff_requested(&self, request: signaling::HandoffRequest) -> Result<()> {
+        info!("on_handoff_requested(): call_id: {}", request.call_id);
+
+        let remote_user_id = app_slice_from_bytes(Some(&request.remote_user_id));
+        (self.app_interface.onHandoffRequested)(
+            self.app_interface.object,
+            remote_user_id,
+            u64::from(request.call_id),
+            request.remote_device_id,
+            request.call_media_type as i32,
+        );
+
+        Ok(())
+    }
+
     // Group Calls
 
     fn group_call_ring_update(
@@ -675,6 +707,7 @@ impl Platform for IosPlatform {
             .map(|remote_device_state| AppRemoteDeviceState {
                 demuxId: remote_device_state.demux_id,
                 user_id: app_slice_from_bytes(Some(&remote_device_state.user_id)),
+                participantKind: remote_device_state.participant_kind as i32,
                 mediaKeysReceived: remote_device_state.media_keys_received,
                 audioMuted: app_option_from_bool(remote_device_state.heartbeat_state.audio_muted),
                 videoMuted: app_option_from_bool(remote_device_state.heartbeat_state.video_muted),
@@ -779,6 +812,20 @@ impl Platform for IosPlatform {
         );
     }
 
+    fn handle_call_full(
+        &self,
+        client_id: group_call::ClientId,
+        max_devices: Option<u32>,
+        device_count: Option<u32>,
+    ) {
+        (self.app_interface.handleCallFull)(
+            self.app_interface.object,
+            client_id,
+            app_option_from_u32(max_devices),
+            app_option_from_u32(device_count),
+        );
+    }
+
     fn handle_ended(&self, client_id: group_call::ClientId, reason: group_call::EndReason) {
         (self.app_interface.handleEnded)(self.app_interface.object, client_id, reason as i32);
     }
@@ -37,7 +37,7 @@ use crate::native::{
 };
 use crate::webrtc::logging;
 use crate::webrtc::media::{
-    AudioTrack, VideoFrame, VideoPixelFormat, VideoSink, VideoSource, VideoTrack,
+    AudioTrack, VideoContentHint, VideoFrame, VideoPixelFormat, VideoSink, VideoSource, VideoTrack,
 };
 
 use crate::webrtc::peer_connection::AudioLevel;
@@ -842,6 +842,7 @@ impl CallStateHandler for EventReporter {
         remote_peer_id: &str,
         call_id: CallId,
         call_state: CallState,
+        _app_data: &[u8],
     ) -> Result<()> {
         info!("[JV] CallStatehandler, invoke self.send");
 
@@ -1542,6 +1543,7 @@ pub unsafe extern "C" fn createGroupCallClient(
         outgoing_audio_track,
         outgoing_video_track,
         Some(incoming_video_sink),
+        vec![],
     );
 
     info!("And return the client_id");
@@ -1672,7 +1674,9 @@ pub unsafe extern "C" fn disconnect(endpoint: i64, client_id: ClientId) -> i64 {
     let call_endpoint = ptr_as_mut(endpoint as *mut CallEndpoint).unwrap();
     call_endpoint.outgoing_audio_track.set_enabled(false);
     call_endpoint.outgoing_video_track.set_enabled(false);
-    call_endpoint.outgoing_video_track.set_content_hint(false);
+    call_endpoint
+        .outgoing_video_track
+        .set_content_hint(VideoContentHint::None);
     call_endpoint.call_manager.disconnect(client_id);
     1
 }
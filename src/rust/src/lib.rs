@@ -17,32 +17,55 @@ extern crate log;
 #[macro_use]
 extern crate static_assertions;
 
+// Only the lite module is built for the web.
+#[cfg(not(target_arch = "wasm32"))]
 #[macro_use]
 pub mod common;
 
+#[cfg(not(target_arch = "wasm32"))]
 mod error;
 
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!("ringrtc");
+
 // Doesn't depend on WebRTC
 pub mod lite {
+    #[cfg(feature = "uniffi")]
+    pub mod bindings;
     pub mod call_links;
+    #[cfg(not(target_arch = "wasm32"))]
+    pub mod crash_dump;
+    #[cfg(not(target_arch = "wasm32"))]
+    pub mod diagnostics;
     pub mod ffi;
     pub mod http;
     pub mod logging;
+    pub mod metrics;
+    #[cfg(feature = "python")]
+    pub mod python;
     pub mod sfu;
+    #[cfg(target_arch = "wasm32")]
+    pub mod wasm;
 }
 
 /// Core, platform independent functionality.
+#[cfg(not(target_arch = "wasm32"))]
 pub mod core {
     pub mod call;
     pub mod call_fsm;
     pub mod call_manager;
     pub mod call_mutex;
+    pub mod clock;
     pub mod connection;
     pub mod connection_fsm;
     pub mod crypto;
     pub mod group_call;
+    pub mod logging;
     pub mod platform;
+    pub mod setup_latency;
     pub mod signaling;
+    pub mod signaling_store;
+    pub mod signaling_transport;
     pub mod util;
 }
 
@@ -58,10 +81,12 @@ mod android {
 
     mod api {
         mod call_link_root_key;
+        mod crypto_context;
         mod jni_call_manager;
     }
     mod android_platform;
     mod call_manager;
+    mod direct_video;
     mod error;
     mod logging;
     mod webrtc_java_media_stream;
@@ -84,6 +109,14 @@ mod ios {
 #[cfg(feature = "electron")]
 pub mod electron;
 
+#[cfg(feature = "headless")]
+/// Plain C API for joining group calls without an application.
+pub mod headless;
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Plain C API for frame encryption outside of a call.
+pub mod frame_crypto;
+
 #[cfg(feature = "native")]
 pub mod native;
 
@@ -95,6 +128,7 @@ pub mod java {
 }
 
 /// Foreign Function Interface (FFI) to WebRTC C++ library.
+#[cfg(not(target_arch = "wasm32"))]
 pub mod webrtc {
     pub mod arc;
     pub use arc::Arc;
@@ -102,6 +136,7 @@ pub mod webrtc {
     pub mod audio_device_module;
     #[cfg(all(not(feature = "sim"), feature = "native"))]
     pub mod audio_device_module_utils;
+    pub mod audio_injector;
     pub mod field_trial;
     pub mod ice_gatherer;
     #[cfg(feature = "injectable_network")]
@@ -118,6 +153,8 @@ pub mod webrtc {
     pub mod rtp;
     pub mod sdp_observer;
     pub mod stats_observer;
+    #[cfg(all(not(feature = "sim"), feature = "native"))]
+    pub mod system_audio;
     #[cfg(not(feature = "sim"))]
     mod ffi {
         #[cfg(feature = "native")]
@@ -0,0 +1,570 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Bindings for the `lite` surface (HTTP, SFU peeking, and call links),
+//! generated with UniFFI so that the Kotlin and Swift scaffolding doesn't
+//! have to be written by hand for every API addition.
+//!
+//! The hand-written `ios` and JNI bindings stay in place until the apps have
+//! moved over. The foreign code is generated from the built library:
+//!
+//! ```text
+//! cargo run --features uniffi_bindgen --bin uniffi-bindgen -- \
+//!     generate --library <path to libringrtc> --language kotlin --out-dir <dir>
+//! ```
+
+use std::{collections::HashMap, sync::Arc, time::SystemTime};
+
+use crate::lite::{
+    call_links::{self, CallLinkMemberResolver, CallLinkRootKey, CallLinkRootKeyVersion},
+    http, sfu,
+};
+
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+pub enum LiteError {
+    #[error("invalid call link root key")]
+    InvalidRootKey,
+    #[error("invalid membership proof")]
+    InvalidMembershipProof,
+}
+
+// Call link root keys
+
+#[uniffi::export]
+pub fn call_link_root_key_parse(string: String) -> Result<Vec<u8>, LiteError> {
+    CallLinkRootKey::try_from(string.as_str())
+        .map(|root_key| root_key.to_versioned_bytes())
+        .map_err(|_| LiteError::InvalidRootKey)
+}
+
+#[uniffi::export]
+pub fn call_link_root_key_validate(root_key: Vec<u8>) -> bool {
+    CallLinkRootKey::try_from(root_key.as_slice()).is_ok()
+}
+
+#[uniffi::export]
+pub fn call_link_root_key_generate() -> Vec<u8> {
+    CallLinkRootKey::generate(rand::rngs::OsRng).to_versioned_bytes()
+}
+
+#[uniffi::export]
+pub fn call_link_root_key_generate_admin_passkey() -> Vec<u8> {
+    CallLinkRootKey::generate_admin_passkey(rand::rngs::OsRng)
+}
+
+#[uniffi::export]
+pub fn call_link_root_key_derive_room_id(root_key: Vec<u8>) -> Result<Vec<u8>, LiteError> {
+    Ok(parse_root_key(&root_key)?.derive_room_id())
+}
+
+/// The same key at the newest version, to pass to [`HttpClient::migrate_call_link`].
+#[uniffi::export]
+pub fn call_link_root_key_with_latest_version(root_key: Vec<u8>) -> Result<Vec<u8>, LiteError> {
+    Ok(parse_root_key(&root_key)?
+        .with_version(CallLinkRootKeyVersion::LATEST)
+        .to_versioned_bytes())
+}
+
+#[uniffi::export]
+pub fn call_link_root_key_to_formatted_string(root_key: Vec<u8>) -> Result<String, LiteError> {
+    Ok(parse_root_key(&root_key)?.to_formatted_string())
+}
+
+fn parse_root_key(root_key: &[u8]) -> Result<CallLinkRootKey, LiteError> {
+    CallLinkRootKey::try_from(root_key).map_err(|_| LiteError::InvalidRootKey)
+}
+
+// HTTP
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum HttpMethod {
+    Get,
+    Put,
+    Post,
+    Delete,
+}
+
+impl From<http::Method> for HttpMethod {
+    fn from(method: http::Method) -> Self {
+        match method {
+            http::Method::Get => Self::Get,
+            http::Method::Put => Self::Put,
+            http::Method::Post => Self::Post,
+            http::Method::Delete => Self::Delete,
+        }
+    }
+}
+
+#[derive(Clone, Debug, uniffi::Record)]
+pub struct HttpRequest {
+    pub method: HttpMethod,
+    pub url: String,
+    pub headers: HashMap<String, String>,
+    pub body: Option<Vec<u8>>,
+}
+
+impl From<http::Request> for HttpRequest {
+    fn from(request: http::Request) -> Self {
+        Self {
+            method: request.method.into(),
+            url: request.url,
+            headers: request.headers,
+            body: request.body,
+        }
+    }
+}
+
+#[derive(Clone, Debug, uniffi::Record)]
+pub struct HttpResponse {
+    pub status_code: u16,
+    pub body: Vec<u8>,
+}
+
+/// Makes HTTP requests on behalf of an [`HttpClient`]. Responses are passed
+/// back with [`HttpClient::received_response`].
+#[uniffi::export(with_foreign)]
+pub trait HttpDelegate: Send + Sync {
+    fn send_request(&self, request_id: u32, request: HttpRequest);
+}
+
+struct ForeignHttpDelegate(Arc<dyn HttpDelegate>);
+
+impl http::Delegate for ForeignHttpDelegate {
+    fn send_request(&self, request_id: u32, request: http::Request) {
+        self.0.send_request(request_id, request.into())
+    }
+}
+
+// SFU and call link results
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum CallLinkRestrictions {
+    None,
+    AdminApproval,
+    Unknown,
+}
+
+impl From<call_links::CallLinkRestrictions> for CallLinkRestrictions {
+    fn from(restrictions: call_links::CallLinkRestrictions) -> Self {
+        match restrictions {
+            call_links::CallLinkRestrictions::None => Self::None,
+            call_links::CallLinkRestrictions::AdminApproval => Self::AdminApproval,
+            call_links::CallLinkRestrictions::Unknown => Self::Unknown,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, uniffi::Record)]
+pub struct CallLinkState {
+    pub name: String,
+    pub restrictions: CallLinkRestrictions,
+    pub revoked: bool,
+    pub expiration_epoch_seconds: u64,
+    /// Never null, but may be empty.
+    pub description: String,
+    /// An RGB color, as 0xRRGGBB.
+    pub accent_color: Option<u32>,
+    pub avatar: Option<String>,
+}
+
+impl From<call_links::CallLinkState> for CallLinkState {
+    fn from(state: call_links::CallLinkState) -> Self {
+        Self {
+            name: state.name,
+            restrictions: state.restrictions.into(),
+            revoked: state.revoked,
+            expiration_epoch_seconds: state
+                .expiration
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |expiration| expiration.as_secs()),
+            description: state.metadata.description,
+            accent_color: state.metadata.accent_color,
+            avatar: state.metadata.avatar,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, uniffi::Record)]
+pub struct PeekDeviceInfo {
+    pub demux_id: u32,
+    pub user_id: Option<Vec<u8>>,
+    /// Bridged in by a gateway, such as a phone dialing in over PSTN or SIP.
+    pub bridged: bool,
+}
+
+impl From<sfu::PeekDeviceInfo> for PeekDeviceInfo {
+    fn from(device: sfu::PeekDeviceInfo) -> Self {
+        Self {
+            demux_id: device.demux_id,
+            user_id: device.user_id,
+            bridged: device.participant_kind == sfu::ParticipantKind::Bridged,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, uniffi::Record)]
+pub struct PeekInfo {
+    pub devices: Vec<PeekDeviceInfo>,
+    pub pending_devices: Vec<PeekDeviceInfo>,
+    pub creator: Option<Vec<u8>>,
+    pub era_id: Option<String>,
+    pub max_devices: Option<u32>,
+    pub call_link_state: Option<CallLinkState>,
+}
+
+impl From<sfu::PeekInfo> for PeekInfo {
+    fn from(peek_info: sfu::PeekInfo) -> Self {
+        Self {
+            devices: peek_info.devices.into_iter().map(Into::into).collect(),
+            pending_devices: peek_info
+                .pending_devices
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            creator: peek_info.creator,
+            era_id: peek_info.era_id,
+            max_devices: peek_info.max_devices,
+            call_link_state: peek_info.call_link_state.map(Into::into),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, uniffi::Enum)]
+pub enum PeekResult {
+    Success { peek_info: PeekInfo },
+    Failure { status_code: u16 },
+}
+
+impl From<sfu::PeekResult> for PeekResult {
+    fn from(result: sfu::PeekResult) -> Self {
+        match result {
+            Ok(peek_info) => Self::Success {
+                peek_info: peek_info.into(),
+            },
+            Err(status) => Self::Failure {
+                status_code: status.code,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, uniffi::Enum)]
+pub enum ReadCallLinkResult {
+    Success { state: CallLinkState },
+    Failure { status_code: u16 },
+}
+
+/// A failure with status code 409 means there's an ongoing call using the link.
+#[derive(Clone, Debug, PartialEq, uniffi::Enum)]
+pub enum DeleteCallLinkResult {
+    Success,
+    Failure { status_code: u16 },
+}
+
+impl<T> From<Result<T, http::ResponseStatus>> for DeleteCallLinkResult {
+    fn from(result: Result<T, http::ResponseStatus>) -> Self {
+        match result {
+            Ok(_) => Self::Success,
+            Err(status) => Self::Failure {
+                status_code: status.code,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, uniffi::Record)]
+pub struct GroupMember {
+    pub user_id: Vec<u8>,
+    pub member_id: Vec<u8>,
+}
+
+/// Receives the results of requests made with an [`HttpClient`], matched up
+/// by the request ID passed when making them.
+#[uniffi::export(with_foreign)]
+pub trait LiteObserver: Send + Sync {
+    fn handle_peek_result(&self, request_id: u32, result: PeekResult);
+    fn handle_read_call_link_result(&self, request_id: u32, result: ReadCallLinkResult);
+    fn handle_delete_call_link_result(&self, request_id: u32, result: DeleteCallLinkResult);
+}
+
+/// An HTTP client that makes requests through the application, and the
+/// SFU and call link requests made with it.
+#[derive(uniffi::Object)]
+pub struct HttpClient {
+    client: http::DelegatingClient,
+    observer: Arc<dyn LiteObserver>,
+}
+
+#[uniffi::export]
+impl HttpClient {
+    #[uniffi::constructor]
+    pub fn new(delegate: Arc<dyn HttpDelegate>, observer: Arc<dyn LiteObserver>) -> Arc<Self> {
+        Arc::new(Self {
+            client: http::DelegatingClient::new(ForeignHttpDelegate(delegate)),
+            observer,
+        })
+    }
+
+    /// A None response indicates a failure.
+    pub fn received_response(&self, request_id: u32, response: Option<HttpResponse>) {
+        self.client.received_response(
+            request_id,
+            response.map(|response| http::Response {
+                status: response.status_code.into(),
+                body: response.body,
+            }),
+        )
+    }
+
+    pub fn peek(
+        &self,
+        request_id: u32,
+        sfu_url: String,
+        membership_proof: Vec<u8>,
+        group_members: Vec<GroupMember>,
+    ) -> Result<(), LiteError> {
+        let auth_header = sfu::auth_header_from_membership_proof(&membership_proof)
+            .ok_or(LiteError::InvalidMembershipProof)?;
+        let group_members: Vec<sfu::GroupMember> = group_members
+            .into_iter()
+            .map(|member| sfu::GroupMember {
+                user_id: member.user_id,
+                member_id: member.member_id,
+            })
+            .collect();
+        let observer = self.observer.clone();
+        sfu::peek(
+            &self.client,
+            &sfu_url,
+            None,
+            auth_header,
+            Arc::new(sfu::MemberMap::new(&group_members)),
+            None,
+            Box::new(move |peek_result| {
+                observer.handle_peek_result(request_id, peek_result.into())
+            }),
+        );
+        Ok(())
+    }
+
+    pub fn peek_call_link(
+        &self,
+        request_id: u32,
+        sfu_url: String,
+        auth_credential_presentation: Vec<u8>,
+        link_root_key: Vec<u8>,
+    ) -> Result<(), LiteError> {
+        let link_root_key = parse_root_key(&link_root_key)?;
+        let observer = self.observer.clone();
+        sfu::peek(
+            &self.client,
+            &sfu_url,
+            Some(hex::encode(link_root_key.derive_room_id())),
+            call_links::auth_header_from_auth_credential(&auth_credential_presentation),
+            Arc::new(CallLinkMemberResolver::from(&link_root_key)),
+            Some(link_root_key),
+            Box::new(move |peek_result| {
+                observer.handle_peek_result(request_id, peek_result.into())
+            }),
+        );
+        Ok(())
+    }
+
+    pub fn read_call_link(
+        &self,
+        request_id: u32,
+        sfu_url: String,
+        auth_credential_presentation: Vec<u8>,
+        link_root_key: Vec<u8>,
+    ) -> Result<(), LiteError> {
+        let link_root_key = parse_root_key(&link_root_key)?;
+        let observer = self.observer.clone();
+        call_links::read_call_link(
+            &self.client,
+            &sfu_url,
+            link_root_key,
+            None,
+            &auth_credential_presentation,
+            Box::new(move |result| {
+                let result = match result {
+                    Ok(state) => ReadCallLinkResult::Success {
+                        state: state.into(),
+                    },
+                    Err(status) => ReadCallLinkResult::Failure {
+                        status_code: status.code,
+                    },
+                };
+                observer.handle_read_call_link_result(request_id, result)
+            }),
+        );
+        Ok(())
+    }
+
+    /// Moves a call link to the version of `link_root_key`, reporting the result like
+    /// [`Self::read_call_link`]. Once this succeeds, store and share `link_root_key` in place of
+    /// the old key.
+    pub fn migrate_call_link(
+        &self,
+        request_id: u32,
+        sfu_url: String,
+        auth_credential_presentation: Vec<u8>,
+        link_root_key: Vec<u8>,
+        admin_passkey: Vec<u8>,
+    ) -> Result<(), LiteError> {
+        let link_root_key = parse_root_key(&link_root_key)?;
+        let observer = self.observer.clone();
+        call_links::read_and_migrate_call_link(
+            Arc::new(self.client.clone()),
+            &sfu_url,
+            link_root_key,
+            &auth_credential_presentation,
+            &admin_passkey,
+            Box::new(move |result| {
+                let result = match result {
+                    Ok(state) => ReadCallLinkResult::Success {
+                        state: state.into(),
+                    },
+                    Err(status) => ReadCallLinkResult::Failure {
+                        status_code: status.code,
+                    },
+                };
+                observer.handle_read_call_link_result(request_id, result)
+            }),
+        );
+        Ok(())
+    }
+
+    pub fn delete_call_link(
+        &self,
+        request_id: u32,
+        sfu_url: String,
+        auth_credential_presentation: Vec<u8>,
+        link_root_key: Vec<u8>,
+        admin_passkey: Vec<u8>,
+    ) -> Result<(), LiteError> {
+        let link_root_key = parse_root_key(&link_root_key)?;
+        let observer = self.observer.clone();
+        call_links::delete_call_link(
+            &self.client,
+            &sfu_url,
+            link_root_key,
+            &auth_credential_presentation,
+            &call_links::CallLinkDeleteRequest {
+                admin_passkey: &admin_passkey,
+            },
+            Box::new(move |result| {
+                observer.handle_delete_call_link_result(request_id, result.into())
+            }),
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn peek_result_conversion() {
+        let peek_info = sfu::PeekInfo {
+            devices: vec![sfu::PeekDeviceInfo {
+                demux_id: 16,
+                user_id: Some(b"alice".to_vec()),
+                participant_kind: sfu::ParticipantKind::AppUser,
+            }],
+            pending_devices: vec![sfu::PeekDeviceInfo {
+                demux_id: 32,
+                user_id: None,
+                participant_kind: sfu::ParticipantKind::Bridged,
+            }],
+            creator: Some(b"alice".to_vec()),
+            era_id: Some("era".to_string()),
+            max_devices: Some(8),
+            call_link_state: Some(call_links::CallLinkState {
+                name: "Book club".to_string(),
+                restrictions: call_links::CallLinkRestrictions::AdminApproval,
+                revoked: false,
+                expiration: SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+                epoch: None,
+                metadata: call_links::CallLinkMetadata {
+                    description: "Chapter 3".to_string(),
+                    accent_color: Some(0x3a76f0),
+                    avatar: None,
+                },
+                members_can_add: false,
+                revision: Some(3),
+                old_admin_passkey_rejected: false,
+            }),
+        };
+
+        assert_eq!(
+            PeekResult::Success {
+                peek_info: PeekInfo {
+                    devices: vec![PeekDeviceInfo {
+                        demux_id: 16,
+                        user_id: Some(b"alice".to_vec()),
+                        bridged: false,
+                    }],
+                    pending_devices: vec![PeekDeviceInfo {
+                        demux_id: 32,
+                        user_id: None,
+                        bridged: true,
+                    }],
+                    creator: Some(b"alice".to_vec()),
+                    era_id: Some("era".to_string()),
+                    max_devices: Some(8),
+                    call_link_state: Some(CallLinkState {
+                        name: "Book club".to_string(),
+                        restrictions: CallLinkRestrictions::AdminApproval,
+                        revoked: false,
+                        expiration_epoch_seconds: 1_700_000_000,
+                        description: "Chapter 3".to_string(),
+                        accent_color: Some(0x3a76f0),
+                        avatar: None,
+                    }),
+                },
+            },
+            PeekResult::from(Ok(peek_info))
+        );
+        assert_eq!(
+            PeekResult::Failure { status_code: 413 },
+            PeekResult::from(Err(http::ResponseStatus::GROUP_CALL_FULL))
+        );
+    }
+
+    #[test]
+    fn delete_call_link_result_conversion() {
+        assert_eq!(
+            DeleteCallLinkResult::Success,
+            DeleteCallLinkResult::from(Ok::<_, http::ResponseStatus>(call_links::Empty {}))
+        );
+        assert_eq!(
+            DeleteCallLinkResult::Failure { status_code: 409 },
+            DeleteCallLinkResult::from(Err::<(), _>(http::ResponseStatus::CALL_LINK_IN_USE))
+        );
+    }
+
+    #[test]
+    fn root_key_round_trip() {
+        let root_key = call_link_root_key_generate();
+        assert!(call_link_root_key_validate(root_key.clone()));
+        let formatted = call_link_root_key_to_formatted_string(root_key.clone()).unwrap();
+        assert_eq!(root_key, call_link_root_key_parse(formatted).unwrap());
+
+        let latest_root_key = call_link_root_key_with_latest_version(root_key.clone()).unwrap();
+        assert_ne!(root_key, latest_root_key);
+        let formatted = call_link_root_key_to_formatted_string(latest_root_key.clone()).unwrap();
+        assert_eq!(
+            latest_root_key,
+            call_link_root_key_parse(formatted).unwrap()
+        );
+        assert!(matches!(
+            call_link_root_key_derive_room_id(vec![1, 2, 3]),
+            Err(LiteError::InvalidRootKey)
+        ));
+    }
+}
@@ -4,17 +4,29 @@
 //
 
 mod base16;
+mod epoch;
+mod link_uri;
 mod member_resolver;
+mod metadata;
+mod monitor;
 mod root_key;
 
 use std::{
     collections::HashMap,
+    sync::Arc,
     time::{Duration, SystemTime},
 };
 
 use base64::{engine::general_purpose::STANDARD as base64, Engine};
+pub use epoch::CallLinkEpoch;
+pub use link_uri::{CallLinkUri, CallLinkUriError};
 pub use member_resolver::CallLinkMemberResolver;
-pub use root_key::CallLinkRootKey;
+pub use metadata::CallLinkMetadata;
+pub use monitor::{
+    AuthCredentialPresentationCallback, CallLinkAuthProvider, CallLinkExpirationObserver,
+    CallLinkMonitor,
+};
+pub use root_key::{CallLinkRootKey, CallLinkRootKeyVersion};
 use serde::{self, Deserialize, Serialize};
 use serde_with::serde_as;
 
@@ -48,6 +60,16 @@ pub struct CallLinkResponse<'a> {
     pub revoked: bool,
     #[serde(rename = "expiration")]
     pub expiration_unix_timestamp: u64,
+    /// Missing for links created before epochs, which never expire this way.
+    #[serde(default)]
+    pub epoch: Option<CallLinkEpoch>,
+    #[serde(rename = "metadata", default)]
+    pub encrypted_metadata: &'a [u8],
+    #[serde(default)]
+    pub members_can_add: bool,
+    /// Missing from older servers, which don't support conditional updates.
+    #[serde(default)]
+    pub revision: Option<u64>,
 }
 
 impl<'a> TryFrom<&'a sfu_to_device::peek_info::CallLinkState> for CallLinkResponse<'a> {
@@ -67,6 +89,11 @@ impl<'a> TryFrom<&'a sfu_to_device::peek_info::CallLinkState> for CallLinkRespon
             restrictions: value.restrictions().into(),
             revoked: value.revoked(),
             expiration_unix_timestamp: value.expiration_unix_timestamp(),
+            // Peeks don't include the epoch; the SFU checks it on join.
+            epoch: None,
+            encrypted_metadata: value.encrypted_metadata().as_bytes(),
+            members_can_add: value.members_can_add(),
+            revision: value.revision,
         })
     }
 }
@@ -77,6 +104,15 @@ pub struct CallLinkState {
     pub restrictions: CallLinkRestrictions,
     pub revoked: bool,
     pub expiration: SystemTime,
+    pub epoch: Option<CallLinkEpoch>,
+    pub metadata: CallLinkMetadata,
+    /// Whether members other than the admin can add people to the call.
+    pub members_can_add: bool,
+    /// Changes whenever the link is updated; see [`update_call_link_restrictions`].
+    pub revision: Option<u64>,
+    /// Set by [`rotate_admin_passkey`] when the old passkey was no longer accepted, because an
+    /// earlier attempt had already gone through; always false otherwise.
+    pub old_admin_passkey_rejected: bool,
 }
 
 impl CallLinkState {
@@ -100,8 +136,31 @@ impl CallLinkState {
             revoked: deserialized.revoked,
             expiration: SystemTime::UNIX_EPOCH
                 + Duration::from_secs(deserialized.expiration_unix_timestamp),
+            epoch: deserialized.epoch,
+            metadata: CallLinkMetadata::decrypt(deserialized.encrypted_metadata, root_key),
+            members_can_add: deserialized.members_can_add,
+            revision: deserialized.revision,
+            old_admin_passkey_rejected: false,
         }
     }
+
+    /// Rejects a link shared before the epoch was last reset.
+    ///
+    /// `link_epoch` is the epoch the link was shared with, if any. Links without one predate
+    /// epochs, and are only valid for call links that don't have one either.
+    pub fn check_epoch(
+        self,
+        link_epoch: Option<CallLinkEpoch>,
+    ) -> Result<Self, http::ResponseStatus> {
+        if self.epoch.is_some() && self.epoch != link_epoch {
+            info!(
+                "call link epoch is {:?}, but the link has {:?}",
+                self.epoch, link_epoch
+            );
+            return Err(http::ResponseStatus::CALL_LINK_EPOCH_EXPIRED);
+        }
+        Ok(self)
+    }
 }
 
 // Use type that serializes to `{}` in JSON
@@ -113,6 +172,43 @@ pub type ReadCallLinkResultCallback =
 
 pub type EmptyResultCallback = Box<dyn FnOnce(Result<Empty, http::ResponseStatus>) + Send>;
 
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct CallLinkUsageResponse {
+    #[serde(rename = "lastCallTime", default)]
+    last_call_unix_timestamp: Option<u64>,
+    #[serde(default)]
+    peak_participants: u32,
+    #[serde(default)]
+    total_calls: u32,
+}
+
+/// How much a call link has been used, for admins deciding whether to keep it around.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CallLinkUsage {
+    /// When the most recent call started, or None if there hasn't been one.
+    pub last_call: Option<SystemTime>,
+    /// The most participants seen in a single call. The server only samples this, so short spikes
+    /// may be missed.
+    pub approximate_peak_participants: u32,
+    pub total_calls: u32,
+}
+
+impl From<CallLinkUsageResponse> for CallLinkUsage {
+    fn from(value: CallLinkUsageResponse) -> Self {
+        Self {
+            last_call: value
+                .last_call_unix_timestamp
+                .map(|timestamp| SystemTime::UNIX_EPOCH + Duration::from_secs(timestamp)),
+            approximate_peak_participants: value.peak_participants,
+            total_calls: value.total_calls,
+        }
+    }
+}
+
+pub type CallLinkUsageResultCallback =
+    Box<dyn FnOnce(Result<CallLinkUsage, http::ResponseStatus>) + Send>;
+
 fn call_link_url_from_sfu_url(sfu_url: &str) -> String {
     format!("{}/v1/call-link", sfu_url.trim_end_matches('/'))
 }
@@ -121,10 +217,14 @@ pub fn auth_header_from_auth_credential(auth_presentation: &[u8]) -> String {
     format!("Bearer auth.{}", base64.encode(auth_presentation))
 }
 
+/// Reads the state of a call link, failing with
+/// [`CALL_LINK_EPOCH_EXPIRED`](http::ResponseStatus::CALL_LINK_EPOCH_EXPIRED) if `epoch`, the
+/// epoch the link was shared with, is no longer current.
 pub fn read_call_link(
     http_client: &dyn http::Client,
     sfu_url: &str,
     root_key: CallLinkRootKey,
+    epoch: Option<CallLinkEpoch>,
     auth_presentation: &[u8],
     result_callback: ReadCallLinkResultCallback,
 ) {
@@ -146,7 +246,8 @@ pub fn read_call_link(
         },
         Box::new(move |http_response| {
             let result = http::parse_json_response::<CallLinkResponse>(http_response.as_ref())
-                .map(|response| CallLinkState::from_serialized(response, &root_key));
+                .map(|response| CallLinkState::from_serialized(response, &root_key))
+                .and_then(|state| state.check_epoch(epoch));
             result_callback(result);
         }),
     )
@@ -164,6 +265,10 @@ struct CallLinkCreateRequest<'a> {
 
     #[serde_as(as = "serde_with::base64::Base64")]
     zkparams: &'a [u8],
+
+    #[serde(rename = "metadata", skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<serde_with::base64::Base64>")]
+    encrypted_metadata: Option<&'a [u8]>,
 }
 
 #[serde_as]
@@ -182,6 +287,34 @@ pub struct CallLinkUpdateRequest<'a> {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub revoked: Option<bool>,
+
+    /// Bumps the epoch, invalidating every link shared so far.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub reset_epoch: bool,
+
+    /// From [`CallLinkMetadata::encrypt`], replacing all the metadata at once.
+    #[serde(rename = "metadata", skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<serde_with::base64::Base64>")]
+    pub encrypted_metadata: Option<&'a [u8]>,
+
+    /// Replaces `admin_passkey`; see [`rotate_admin_passkey`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<serde_with::base64::Base64>")]
+    pub new_admin_passkey: Option<&'a [u8]>,
+}
+
+#[serde_as]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CallLinkRestrictionsUpdateRequest<'a> {
+    #[serde_as(as = "serde_with::base64::Base64")]
+    admin_passkey: &'a [u8],
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    restrictions: Option<CallLinkRestrictions>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    members_can_add: Option<bool>,
 }
 
 #[serde_as]
@@ -192,6 +325,14 @@ pub struct CallLinkDeleteRequest<'a> {
     pub admin_passkey: &'a [u8],
 }
 
+#[serde_as]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CallLinkUsageRequest<'a> {
+    #[serde_as(as = "serde_with::base64::Base64")]
+    admin_passkey: &'a [u8],
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn create_call_link(
     http_client: &dyn http::Client,
@@ -201,6 +342,7 @@ pub fn create_call_link(
     admin_passkey: &[u8],
     public_zkparams: &[u8],
     restrictions: Option<CallLinkRestrictions>,
+    encrypted_metadata: Option<&[u8]>,
     result_callback: ReadCallLinkResultCallback,
 ) {
     http_client.send_request(
@@ -223,6 +365,7 @@ pub fn create_call_link(
                     admin_passkey,
                     restrictions,
                     zkparams: public_zkparams,
+                    encrypted_metadata,
                 })
                 .expect("cannot fail to serialize"),
             ),
@@ -268,6 +411,208 @@ pub fn update_call_link(
     )
 }
 
+/// Switches a call link from `admin_passkey` to `new_admin_passkey`, which should come from
+/// [`CallLinkRootKey::generate_admin_passkey`]. The SFU makes the switch atomically, so a leaked
+/// passkey can be retired without recreating the link.
+///
+/// Keep both passkeys until this succeeds, since a lost response leaves it unclear which one is
+/// in effect. If the old passkey is rejected, an earlier attempt may have gone through, so this
+/// checks the new one instead and reports that in
+/// [`CallLinkState::old_admin_passkey_rejected`]. Either way, the new passkey is in effect on
+/// success and the old one can be dropped. If the check with the new passkey fails too, for any
+/// reason, the old passkey's 403 is returned, since the new one could not be confirmed; on any
+/// other failure, retry with the same pair of passkeys.
+pub fn rotate_admin_passkey(
+    http_client: Arc<dyn http::Client + Send + Sync>,
+    sfu_url: &str,
+    root_key: CallLinkRootKey,
+    auth_presentation: &[u8],
+    admin_passkey: &[u8],
+    new_admin_passkey: &[u8],
+    result_callback: ReadCallLinkResultCallback,
+) {
+    let retry_http_client = http_client.clone();
+    let sfu_url = sfu_url.to_string();
+    let retry_root_key = root_key.clone();
+    let auth_presentation = auth_presentation.to_vec();
+    let new_admin_passkey = new_admin_passkey.to_vec();
+    update_call_link(
+        http_client.as_ref(),
+        &sfu_url.clone(),
+        root_key,
+        &auth_presentation.clone(),
+        &CallLinkUpdateRequest {
+            admin_passkey,
+            new_admin_passkey: Some(&new_admin_passkey.clone()),
+            ..CallLinkUpdateRequest::default()
+        },
+        Box::new(move |result| match result {
+            Err(status) if status.code == 403 => update_call_link(
+                retry_http_client.as_ref(),
+                &sfu_url,
+                retry_root_key,
+                &auth_presentation,
+                &CallLinkUpdateRequest {
+                    admin_passkey: &new_admin_passkey,
+                    new_admin_passkey: Some(&new_admin_passkey),
+                    ..CallLinkUpdateRequest::default()
+                },
+                Box::new(move |result| {
+                    result_callback(
+                        result
+                            .map(|state| CallLinkState {
+                                old_admin_passkey_rejected: true,
+                                ..state
+                            })
+                            .map_err(|_| status),
+                    )
+                }),
+            ),
+            result => result_callback(result),
+        }),
+    )
+}
+
+/// Changes whether joining a call link needs admin approval and whether members can add people,
+/// leaving either alone if it's None.
+///
+/// The change only goes through if the link is still at `revision`, as read from
+/// [`CallLinkState::revision`]. Otherwise it fails with
+/// [`CALL_LINK_REVISION_CONFLICT`](http::ResponseStatus::CALL_LINK_REVISION_CONFLICT), so that two
+/// admins editing at once don't silently undo each other's changes; read the link again, check
+/// whether the change still makes sense, and retry with the new revision.
+#[allow(clippy::too_many_arguments)]
+pub fn update_call_link_restrictions(
+    http_client: &dyn http::Client,
+    sfu_url: &str,
+    root_key: CallLinkRootKey,
+    auth_presentation: &[u8],
+    admin_passkey: &[u8],
+    restrictions: Option<CallLinkRestrictions>,
+    members_can_add: Option<bool>,
+    revision: u64,
+    result_callback: ReadCallLinkResultCallback,
+) {
+    http_client.send_request(
+        http::Request {
+            method: http::Method::Put,
+            url: call_link_url_from_sfu_url(sfu_url),
+            headers: HashMap::from_iter([
+                (
+                    "Authorization".to_string(),
+                    auth_header_from_auth_credential(auth_presentation),
+                ),
+                (
+                    "X-Room-Id".to_string(),
+                    hex::encode(root_key.derive_room_id()),
+                ),
+                ("Content-Type".to_string(), "application/json".to_string()),
+                ("If-Match".to_string(), format!("\"{revision}\"")),
+            ]),
+            body: Some(
+                serde_json::to_vec(&CallLinkRestrictionsUpdateRequest {
+                    admin_passkey,
+                    restrictions,
+                    members_can_add,
+                })
+                .expect("cannot fail to serialize"),
+            ),
+        },
+        Box::new(move |http_response| {
+            let result = http::parse_json_response::<CallLinkResponse>(http_response.as_ref())
+                .map(|response| CallLinkState::from_serialized(response, &root_key));
+            if result.as_ref().err() == Some(&http::ResponseStatus::CALL_LINK_REVISION_CONFLICT) {
+                info!("call link changed since revision {revision}, not updating restrictions");
+            }
+            result_callback(result);
+        }),
+    )
+}
+
+/// Re-encrypts a call link's name and metadata under `root_key`'s version, after moving the key
+/// to a new version with [`CallLinkRootKey::with_version`].
+///
+/// `state` should come from reading the link just before, with any version of the key; the read
+/// decrypts under every version. Links shared with the old version keep working, since neither
+/// the room nor the key bytes change.
+pub fn migrate_call_link(
+    http_client: &dyn http::Client,
+    sfu_url: &str,
+    root_key: CallLinkRootKey,
+    auth_presentation: &[u8],
+    admin_passkey: &[u8],
+    state: &CallLinkState,
+    result_callback: ReadCallLinkResultCallback,
+) {
+    info!(
+        "migrating call link state to root key version {:?}",
+        root_key.version()
+    );
+    let mut rng = rand::rngs::OsRng;
+    let encrypted_name = if state.name.is_empty() {
+        vec![]
+    } else {
+        root_key.encrypt(state.name.as_bytes(), &mut rng)
+    };
+    let encrypted_metadata = state.metadata.encrypt(&root_key, &mut rng);
+    update_call_link(
+        http_client,
+        sfu_url,
+        root_key,
+        auth_presentation,
+        &CallLinkUpdateRequest {
+            admin_passkey,
+            encrypted_name: Some(&encrypted_name),
+            encrypted_metadata: Some(&encrypted_metadata),
+            ..CallLinkUpdateRequest::default()
+        },
+        result_callback,
+    )
+}
+
+/// Reads a call link and then migrates it with [`migrate_call_link`], for apps that don't have
+/// its state at hand.
+///
+/// `root_key` should already have the new version, such as from
+/// [`CallLinkRootKey::with_version`] with [`CallLinkRootKeyVersion::LATEST`]. Once this succeeds,
+/// the app should store and share that key in place of the old one.
+pub fn read_and_migrate_call_link(
+    http_client: Arc<dyn http::Client + Send + Sync>,
+    sfu_url: &str,
+    root_key: CallLinkRootKey,
+    auth_presentation: &[u8],
+    admin_passkey: &[u8],
+    result_callback: ReadCallLinkResultCallback,
+) {
+    let migrate_http_client = http_client.clone();
+    let sfu_url = sfu_url.to_string();
+    let migrate_root_key = root_key.clone();
+    let auth_presentation = auth_presentation.to_vec();
+    let admin_passkey = admin_passkey.to_vec();
+    read_call_link(
+        http_client.as_ref(),
+        &sfu_url.clone(),
+        root_key,
+        None,
+        &auth_presentation.clone(),
+        Box::new(move |result| match result {
+            Ok(state) => migrate_call_link(
+                migrate_http_client.as_ref(),
+                &sfu_url,
+                migrate_root_key,
+                &auth_presentation,
+                &admin_passkey,
+                &state,
+                result_callback,
+            ),
+            Err(status) => result_callback(Err(status)),
+        }),
+    )
+}
+
+/// Deletes a call link, failing with
+/// [`CALL_LINK_IN_USE`](http::ResponseStatus::CALL_LINK_IN_USE) if there's an ongoing call using
+/// it. Deleting is idempotent, so it's safe to retry once the call has ended.
 pub fn delete_call_link(
     http_client: &dyn http::Client,
     sfu_url: &str,
@@ -295,6 +640,46 @@ pub fn delete_call_link(
         },
         Box::new(move |http_response| {
             let result = http::parse_json_response::<Empty>(http_response.as_ref());
+            if matches!(result, Err(status) if status == http::ResponseStatus::CALL_LINK_IN_USE) {
+                info!("call link is in use by an ongoing call, not deleting");
+            }
+            result_callback(result);
+        }),
+    )
+}
+
+/// Fetches usage statistics for a call link, which only its admin can see.
+pub fn read_call_link_usage(
+    http_client: &dyn http::Client,
+    sfu_url: &str,
+    root_key: &CallLinkRootKey,
+    auth_presentation: &[u8],
+    admin_passkey: &[u8],
+    result_callback: CallLinkUsageResultCallback,
+) {
+    http_client.send_request(
+        http::Request {
+            method: http::Method::Post,
+            url: format!("{}/usage", call_link_url_from_sfu_url(sfu_url)),
+            headers: HashMap::from_iter([
+                (
+                    "Authorization".to_string(),
+                    auth_header_from_auth_credential(auth_presentation),
+                ),
+                (
+                    "X-Room-Id".to_string(),
+                    hex::encode(root_key.derive_room_id()),
+                ),
+                ("Content-Type".to_string(), "application/json".to_string()),
+            ]),
+            body: Some(
+                serde_json::to_vec(&CallLinkUsageRequest { admin_passkey })
+                    .expect("cannot fail to serialize"),
+            ),
+        },
+        Box::new(move |http_response| {
+            let result = http::parse_json_response::<CallLinkUsageResponse>(http_response.as_ref())
+                .map(CallLinkUsage::from);
             result_callback(result);
         }),
     )
@@ -306,9 +691,12 @@ pub mod ios {
 
     use super::*;
     use crate::lite::{
-        ffi::ios::{cstr, rtc_Bytes, rtc_OptionalU16, rtc_String},
+        ffi::ios::{cstr, rtc_Bytes, rtc_OptionalU16, rtc_OptionalU32, rtc_String, FromOrDefault},
         http,
-        sfu::ios::rtc_sfu_Response,
+        sfu::ios::{
+            register_cancellable_request, rtc_sfu_RequestToken, rtc_sfu_Response,
+            unregister_cancellable_request,
+        },
     };
 
     pub type Client = http::DelegatingClient;
@@ -339,7 +727,10 @@ pub mod ios {
             .and_then(|s| CallLinkRootKey::try_from(s).ok());
         match root_key {
             Some(key) => {
-                callback(context, rtc_Bytes::from(key.bytes().as_slice()));
+                callback(
+                    context,
+                    rtc_Bytes::from(key.to_versioned_bytes().as_slice()),
+                );
                 true
             }
             None => false,
@@ -357,7 +748,10 @@ pub mod ios {
         callback: extern "C" fn(context: *mut c_void, result: rtc_Bytes),
     ) {
         let root_key = CallLinkRootKey::generate(rand::rngs::OsRng);
-        callback(context, rtc_Bytes::from(root_key.bytes().as_slice()));
+        callback(
+            context,
+            rtc_Bytes::from(root_key.to_versioned_bytes().as_slice()),
+        );
     }
 
     #[no_mangle]
@@ -387,6 +781,27 @@ pub mod ios {
         }
     }
 
+    /// The same key with [`CallLinkRootKeyVersion::LATEST`], for passing to
+    /// `rtc_sfu_migrateCallLink_async`.
+    #[no_mangle]
+    pub extern "C" fn rtc_calllinks_CallLinkRootKey_withLatestVersion(
+        root_key_bytes: rtc_Bytes,
+        context: *mut c_void,
+        callback: extern "C" fn(context: *mut c_void, result: rtc_Bytes),
+    ) -> *const c_char {
+        match CallLinkRootKey::try_from(root_key_bytes.as_slice()) {
+            Ok(root_key) => {
+                let root_key = root_key.with_version(CallLinkRootKeyVersion::LATEST);
+                callback(
+                    context,
+                    rtc_Bytes::from(root_key.to_versioned_bytes().as_slice()),
+                );
+                std::ptr::null()
+            }
+            Err(_) => cstr!("invalid root key").as_ptr(),
+        }
+    }
+
     #[no_mangle]
     pub extern "C" fn rtc_calllinks_CallLinkRootKey_toFormattedString(
         root_key_bytes: rtc_Bytes,
@@ -412,6 +827,11 @@ pub mod ios {
         pub expiration_epoch_seconds: u64,
         pub raw_restrictions: i8,
         pub revoked: bool,
+        pub description: rtc_String<'a>,
+        /// Null if there is none.
+        pub avatar: rtc_String<'a>,
+        pub accent_color: rtc_OptionalU32,
+        pub old_admin_passkey_rejected: bool,
     }
 
     impl<'a> From<&'a CallLinkState> for rtc_calllinks_CallLinkState<'a> {
@@ -429,6 +849,10 @@ pub mod ios {
                     CallLinkRestrictions::Unknown => -1,
                 },
                 revoked: value.revoked,
+                description: value.metadata.description.as_str().into(),
+                avatar: rtc_String::from_or_default(value.metadata.avatar.as_deref()),
+                accent_color: rtc_OptionalU32::from_or_default(value.metadata.accent_color),
+                old_admin_passkey_rejected: value.old_admin_passkey_rejected,
             }
         }
     }
@@ -509,6 +933,37 @@ pub mod ios {
         }
     }
 
+    /// Starts reading a call link, returning false if the arguments were invalid.
+    ///
+    /// # Safety
+    ///
+    /// `sfu_url` must be a valid, non-null C string.
+    unsafe fn start_read_call_link(
+        http_client: &http::ios::Client,
+        sfu_url: *const c_char,
+        auth_credential_presentation: rtc_Bytes,
+        link_root_key: rtc_Bytes,
+        result_callback: ReadCallLinkResultCallback,
+    ) -> bool {
+        let Ok(sfu_url) = CStr::from_ptr(sfu_url).to_str() else {
+            error!("invalid sfu_url");
+            return false;
+        };
+        let Ok(link_root_key) = CallLinkRootKey::try_from(link_root_key.as_slice()) else {
+            error!("invalid link_root_key");
+            return false;
+        };
+        read_call_link(
+            http_client,
+            sfu_url,
+            link_root_key,
+            None,
+            auth_credential_presentation.as_slice(),
+            result_callback,
+        );
+        true
+    }
+
     /// # Safety
     ///
     /// - `http_client` must come from `rtc_http_Client_create` and not already be destroyed
@@ -525,26 +980,94 @@ pub mod ios {
         info!("rtc_sfu_readCallLink():");
 
         if let Some(http_client) = http_client.as_ref() {
-            if let Ok(sfu_url) = CStr::from_ptr(sfu_url).to_str() {
-                if let Ok(link_root_key) = CallLinkRootKey::try_from(link_root_key.as_slice()) {
-                    read_call_link(
-                        http_client,
-                        sfu_url,
-                        link_root_key,
-                        auth_credential_presentation.as_slice(),
-                        Box::new(move |result| delegate.handle_response(request_id, result)),
-                    )
-                } else {
-                    error!("invalid link_root_key");
-                }
-            } else {
-                error!("invalid sfu_url");
-            }
+            start_read_call_link(
+                http_client,
+                sfu_url,
+                auth_credential_presentation,
+                link_root_key,
+                Box::new(move |result| delegate.handle_response(request_id, result)),
+            );
         } else {
             error!("null http_client passed into rtc_sfu_readCallLink");
         }
     }
 
+    /// Like `rtc_sfu_readCallLink`, but returns a token to identify the request instead of
+    /// taking a request ID. See `rtc_sfu_RequestToken`.
+    ///
+    /// # Safety
+    ///
+    /// - `http_client` must come from `rtc_http_Client_create` and not already be destroyed
+    /// - `sfu_url` must be a valid, non-null C string.
+    #[no_mangle]
+    pub unsafe extern "C" fn rtc_sfu_readCallLink_async(
+        http_client: *const http::ios::Client,
+        sfu_url: *const c_char,
+        auth_credential_presentation: rtc_Bytes,
+        link_root_key: rtc_Bytes,
+        delegate: rtc_sfu_CallLinkDelegate,
+    ) -> rtc_sfu_RequestToken {
+        info!("rtc_sfu_readCallLink_async():");
+
+        let Some(http_client) = http_client.as_ref() else {
+            error!("null http_client passed into rtc_sfu_readCallLink_async");
+            return 0;
+        };
+        let (token, result_callback) = register_cancellable_request(move |token, result| {
+            delegate.handle_response(token, result)
+        });
+        if start_read_call_link(
+            http_client,
+            sfu_url,
+            auth_credential_presentation,
+            link_root_key,
+            result_callback,
+        ) {
+            token
+        } else {
+            unregister_cancellable_request(token);
+            0
+        }
+    }
+
+    /// Starts creating a call link, returning false if the arguments were invalid.
+    ///
+    /// # Safety
+    ///
+    /// `sfu_url` must be a valid, non-null C string.
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn start_create_call_link(
+        http_client: &http::ios::Client,
+        sfu_url: *const c_char,
+        create_credential_presentation: rtc_Bytes,
+        link_root_key: rtc_Bytes,
+        admin_passkey: rtc_Bytes,
+        call_link_public_params: rtc_Bytes,
+        restrictions: i8,
+        result_callback: ReadCallLinkResultCallback,
+    ) -> bool {
+        let Ok(sfu_url) = CStr::from_ptr(sfu_url).to_str() else {
+            error!("invalid sfu_url");
+            return false;
+        };
+        let Ok(link_root_key) = CallLinkRootKey::try_from(link_root_key.as_slice()) else {
+            error!("invalid link_root_key");
+            return false;
+        };
+        create_call_link(
+            http_client,
+            sfu_url,
+            link_root_key,
+            create_credential_presentation.as_slice(),
+            admin_passkey.as_slice(),
+            call_link_public_params.as_slice(),
+            from_i8_to_restrictions(restrictions),
+            None,
+            result_callback,
+        );
+        true
+    }
+
     /// # Safety
     ///
     /// - `http_client` must come from `rtc_http_Client_create` and not already be destroyed
@@ -563,31 +1086,128 @@ pub mod ios {
     ) {
         info!("rtc_sfu_createCallLink():");
 
-        let restrictions = from_i8_to_restrictions(restrictions);
         if let Some(http_client) = http_client.as_ref() {
-            if let Ok(sfu_url) = CStr::from_ptr(sfu_url).to_str() {
-                if let Ok(link_root_key) = CallLinkRootKey::try_from(link_root_key.as_slice()) {
-                    create_call_link(
-                        http_client,
-                        sfu_url,
-                        link_root_key,
-                        create_credential_presentation.as_slice(),
-                        admin_passkey.as_slice(),
-                        call_link_public_params.as_slice(),
-                        restrictions,
-                        Box::new(move |result| delegate.handle_response(request_id, result)),
-                    )
-                } else {
-                    error!("invalid link_root_key");
-                }
-            } else {
-                error!("invalid sfu_url");
-            }
+            start_create_call_link(
+                http_client,
+                sfu_url,
+                create_credential_presentation,
+                link_root_key,
+                admin_passkey,
+                call_link_public_params,
+                restrictions,
+                Box::new(move |result| delegate.handle_response(request_id, result)),
+            );
         } else {
             error!("null http_client passed into rtc_sfu_createCallLink");
         }
     }
 
+    /// Like `rtc_sfu_createCallLink`, but returns a token to identify the request instead of
+    /// taking a request ID. See `rtc_sfu_RequestToken`.
+    ///
+    /// # Safety
+    ///
+    /// - `http_client` must come from `rtc_http_Client_create` and not already be destroyed
+    /// - `sfu_url` must be a valid, non-null C string.
+    #[no_mangle]
+    pub unsafe extern "C" fn rtc_sfu_createCallLink_async(
+        http_client: *const http::ios::Client,
+        sfu_url: *const c_char,
+        create_credential_presentation: rtc_Bytes,
+        link_root_key: rtc_Bytes,
+        admin_passkey: rtc_Bytes,
+        call_link_public_params: rtc_Bytes,
+        restrictions: i8,
+        delegate: rtc_sfu_CallLinkDelegate,
+    ) -> rtc_sfu_RequestToken {
+        info!("rtc_sfu_createCallLink_async():");
+
+        let Some(http_client) = http_client.as_ref() else {
+            error!("null http_client passed into rtc_sfu_createCallLink_async");
+            return 0;
+        };
+        let (token, result_callback) = register_cancellable_request(move |token, result| {
+            delegate.handle_response(token, result)
+        });
+        if start_create_call_link(
+            http_client,
+            sfu_url,
+            create_credential_presentation,
+            link_root_key,
+            admin_passkey,
+            call_link_public_params,
+            restrictions,
+            result_callback,
+        ) {
+            token
+        } else {
+            unregister_cancellable_request(token);
+            0
+        }
+    }
+
+    /// Starts updating a call link, returning false if the arguments were invalid.
+    ///
+    /// # Safety
+    ///
+    /// - `sfu_url` must be a valid, non-null C string.
+    /// - `new_name` must be null or a valid C string.
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn start_update_call_link(
+        http_client: &http::ios::Client,
+        sfu_url: *const c_char,
+        auth_credential_presentation: rtc_Bytes,
+        link_root_key: rtc_Bytes,
+        admin_passkey: rtc_Bytes,
+        new_name: *const c_char,
+        new_restrictions: i8,
+        new_revoked: i8,
+        result_callback: ReadCallLinkResultCallback,
+    ) -> bool {
+        let Ok(sfu_url) = CStr::from_ptr(sfu_url).to_str() else {
+            error!("invalid sfu_url");
+            return false;
+        };
+        let Ok(link_root_key) = CallLinkRootKey::try_from(link_root_key.as_slice()) else {
+            error!("invalid link_root_key");
+            return false;
+        };
+        let new_name = if new_name.is_null() {
+            None
+        } else {
+            Some(CStr::from_ptr(new_name))
+        };
+        let encrypted_name = new_name.map(|name| {
+            let name_bytes = name.to_bytes();
+            if name_bytes.is_empty() {
+                vec![]
+            } else {
+                link_root_key.encrypt(name_bytes, rand::rngs::OsRng)
+            }
+        });
+        update_call_link(
+            http_client,
+            sfu_url,
+            link_root_key,
+            auth_credential_presentation.as_slice(),
+            &CallLinkUpdateRequest {
+                admin_passkey: admin_passkey.as_slice(),
+                encrypted_name: encrypted_name.as_deref(),
+                restrictions: from_i8_to_restrictions(new_restrictions),
+                revoked: match new_revoked {
+                    0 => Some(false),
+                    1 => Some(true),
+                    _ => None,
+                },
+                reset_epoch: false,
+                encrypted_metadata: None,
+                new_admin_passkey: None,
+            },
+            result_callback,
+        );
+        true
+    }
+
     /// # Safety
     ///
     /// - `http_client` must come from `rtc_http_Client_create` and not already be destroyed
@@ -608,49 +1228,193 @@ pub mod ios {
         info!("rtc_sfu_updateCallLink():");
 
         if let Some(http_client) = http_client.as_ref() {
-            if let Ok(sfu_url) = CStr::from_ptr(sfu_url).to_str() {
-                if let Ok(link_root_key) = CallLinkRootKey::try_from(link_root_key.as_slice()) {
-                    let new_name = if new_name.is_null() {
-                        None
-                    } else {
-                        Some(CStr::from_ptr(new_name))
-                    };
-                    let encrypted_name = new_name.map(|name| {
-                        let name_bytes = name.to_bytes();
-                        if name_bytes.is_empty() {
-                            vec![]
-                        } else {
-                            link_root_key.encrypt(name_bytes, rand::rngs::OsRng)
-                        }
-                    });
-                    update_call_link(
-                        http_client,
-                        sfu_url,
-                        link_root_key,
-                        auth_credential_presentation.as_slice(),
-                        &CallLinkUpdateRequest {
-                            admin_passkey: admin_passkey.as_slice(),
-                            encrypted_name: encrypted_name.as_deref(),
-                            restrictions: from_i8_to_restrictions(new_restrictions),
-                            revoked: match new_revoked {
-                                0 => Some(false),
-                                1 => Some(true),
-                                _ => None,
-                            },
-                        },
-                        Box::new(move |result| delegate.handle_response(request_id, result)),
-                    )
-                } else {
-                    error!("invalid link_root_key");
-                }
-            } else {
-                error!("invalid sfu_url");
-            }
+            start_update_call_link(
+                http_client,
+                sfu_url,
+                auth_credential_presentation,
+                link_root_key,
+                admin_passkey,
+                new_name,
+                new_restrictions,
+                new_revoked,
+                Box::new(move |result| delegate.handle_response(request_id, result)),
+            );
         } else {
             error!("null http_client passed into rtc_sfu_createCallLink");
         }
     }
 
+    /// Like `rtc_sfu_updateCallLink`, but returns a token to identify the request instead of
+    /// taking a request ID. See `rtc_sfu_RequestToken`.
+    ///
+    /// # Safety
+    ///
+    /// - `http_client` must come from `rtc_http_Client_create` and not already be destroyed
+    /// - `sfu_url` must be a valid, non-null C string.
+    /// - `new_name` must be null or a valid C string.
+    #[no_mangle]
+    pub unsafe extern "C" fn rtc_sfu_updateCallLink_async(
+        http_client: *const http::ios::Client,
+        sfu_url: *const c_char,
+        auth_credential_presentation: rtc_Bytes,
+        link_root_key: rtc_Bytes,
+        admin_passkey: rtc_Bytes,
+        new_name: *const c_char,
+        new_restrictions: i8,
+        new_revoked: i8,
+        delegate: rtc_sfu_CallLinkDelegate,
+    ) -> rtc_sfu_RequestToken {
+        info!("rtc_sfu_updateCallLink_async():");
+
+        let Some(http_client) = http_client.as_ref() else {
+            error!("null http_client passed into rtc_sfu_updateCallLink_async");
+            return 0;
+        };
+        let (token, result_callback) = register_cancellable_request(move |token, result| {
+            delegate.handle_response(token, result)
+        });
+        if start_update_call_link(
+            http_client,
+            sfu_url,
+            auth_credential_presentation,
+            link_root_key,
+            admin_passkey,
+            new_name,
+            new_restrictions,
+            new_revoked,
+            result_callback,
+        ) {
+            token
+        } else {
+            unregister_cancellable_request(token);
+            0
+        }
+    }
+
+    /// Switches a call link to a new admin passkey, returning a token to identify the request
+    /// (0 if the arguments were invalid). See `rotate_admin_passkey`.
+    ///
+    /// # Safety
+    ///
+    /// - `http_client` must come from `rtc_http_Client_create` and not already be destroyed
+    /// - `sfu_url` must be a valid, non-null C string.
+    #[no_mangle]
+    pub unsafe extern "C" fn rtc_sfu_rotateCallLinkAdminPasskey_async(
+        http_client: *const http::ios::Client,
+        sfu_url: *const c_char,
+        auth_credential_presentation: rtc_Bytes,
+        link_root_key: rtc_Bytes,
+        admin_passkey: rtc_Bytes,
+        new_admin_passkey: rtc_Bytes,
+        delegate: rtc_sfu_CallLinkDelegate,
+    ) -> rtc_sfu_RequestToken {
+        info!("rtc_sfu_rotateCallLinkAdminPasskey_async():");
+
+        let Some(http_client) = http_client.as_ref() else {
+            error!("null http_client passed into rtc_sfu_rotateCallLinkAdminPasskey_async");
+            return 0;
+        };
+        let Ok(sfu_url) = CStr::from_ptr(sfu_url).to_str() else {
+            error!("invalid sfu_url");
+            return 0;
+        };
+        let Ok(link_root_key) = CallLinkRootKey::try_from(link_root_key.as_slice()) else {
+            error!("invalid link_root_key");
+            return 0;
+        };
+        let (token, result_callback) = register_cancellable_request(move |token, result| {
+            delegate.handle_response(token, result)
+        });
+        rotate_admin_passkey(
+            Arc::new(http_client.clone()),
+            sfu_url,
+            link_root_key,
+            auth_credential_presentation.as_slice(),
+            admin_passkey.as_slice(),
+            new_admin_passkey.as_slice(),
+            result_callback,
+        );
+        token
+    }
+
+    /// Migrates a call link to the version of `link_root_key`, returning a token to identify the
+    /// request (0 if the arguments were invalid). See `read_and_migrate_call_link`.
+    ///
+    /// # Safety
+    ///
+    /// - `http_client` must come from `rtc_http_Client_create` and not already be destroyed
+    /// - `sfu_url` must be a valid, non-null C string.
+    #[no_mangle]
+    pub unsafe extern "C" fn rtc_sfu_migrateCallLink_async(
+        http_client: *const http::ios::Client,
+        sfu_url: *const c_char,
+        auth_credential_presentation: rtc_Bytes,
+        link_root_key: rtc_Bytes,
+        admin_passkey: rtc_Bytes,
+        delegate: rtc_sfu_CallLinkDelegate,
+    ) -> rtc_sfu_RequestToken {
+        info!("rtc_sfu_migrateCallLink_async():");
+
+        let Some(http_client) = http_client.as_ref() else {
+            error!("null http_client passed into rtc_sfu_migrateCallLink_async");
+            return 0;
+        };
+        let Ok(sfu_url) = CStr::from_ptr(sfu_url).to_str() else {
+            error!("invalid sfu_url");
+            return 0;
+        };
+        let Ok(link_root_key) = CallLinkRootKey::try_from(link_root_key.as_slice()) else {
+            error!("invalid link_root_key");
+            return 0;
+        };
+        let (token, result_callback) = register_cancellable_request(move |token, result| {
+            delegate.handle_response(token, result)
+        });
+        read_and_migrate_call_link(
+            Arc::new(http_client.clone()),
+            sfu_url,
+            link_root_key,
+            auth_credential_presentation.as_slice(),
+            admin_passkey.as_slice(),
+            result_callback,
+        );
+        token
+    }
+
+    /// Starts deleting a call link, returning false if the arguments were invalid.
+    ///
+    /// # Safety
+    ///
+    /// `sfu_url` must be a valid, non-null C string.
+    unsafe fn start_delete_call_link(
+        http_client: &http::ios::Client,
+        sfu_url: *const c_char,
+        auth_credential_presentation: rtc_Bytes,
+        link_root_key: rtc_Bytes,
+        admin_passkey: rtc_Bytes,
+        result_callback: EmptyResultCallback,
+    ) -> bool {
+        let Ok(sfu_url) = CStr::from_ptr(sfu_url).to_str() else {
+            error!("invalid sfu_url");
+            return false;
+        };
+        let Ok(link_root_key) = CallLinkRootKey::try_from(link_root_key.as_slice()) else {
+            error!("invalid link_root_key");
+            return false;
+        };
+        delete_call_link(
+            http_client,
+            sfu_url,
+            link_root_key,
+            auth_credential_presentation.as_slice(),
+            &CallLinkDeleteRequest {
+                admin_passkey: admin_passkey.as_slice(),
+            },
+            result_callback,
+        );
+        true
+    }
+
     /// # Safety
     ///
     /// - `http_client` must come from `rtc_http_Client_create` and not already be destroyed
@@ -668,26 +1432,268 @@ pub mod ios {
         info!("rtc_sfu_deleteCallLink():");
 
         if let Some(http_client) = http_client.as_ref() {
-            if let Ok(sfu_url) = CStr::from_ptr(sfu_url).to_str() {
-                if let Ok(link_root_key) = CallLinkRootKey::try_from(link_root_key.as_slice()) {
-                    delete_call_link(
-                        http_client,
-                        sfu_url,
-                        link_root_key,
-                        auth_credential_presentation.as_slice(),
-                        &CallLinkDeleteRequest {
-                            admin_passkey: admin_passkey.as_slice(),
-                        },
-                        Box::new(move |result| delegate.handle_response(request_id, result)),
-                    )
-                } else {
-                    error!("invalid link_root_key");
-                }
-            } else {
-                error!("invalid sfu_url");
-            }
+            start_delete_call_link(
+                http_client,
+                sfu_url,
+                auth_credential_presentation,
+                link_root_key,
+                admin_passkey,
+                Box::new(move |result| delegate.handle_response(request_id, result)),
+            );
         } else {
             error!("null http_client passed into rtc_sfu_deleteCallLink");
         }
     }
+
+    /// Like `rtc_sfu_deleteCallLink`, but returns a token to identify the request instead of
+    /// taking a request ID. See `rtc_sfu_RequestToken`.
+    ///
+    /// # Safety
+    ///
+    /// - `http_client` must come from `rtc_http_Client_create` and not already be destroyed
+    /// - `sfu_url` must be a valid, non-null C string.
+    #[no_mangle]
+    pub unsafe extern "C" fn rtc_sfu_deleteCallLink_async(
+        http_client: *const http::ios::Client,
+        sfu_url: *const c_char,
+        auth_credential_presentation: rtc_Bytes,
+        link_root_key: rtc_Bytes,
+        admin_passkey: rtc_Bytes,
+        delegate: rtc_sfu_EmptyDelegate,
+    ) -> rtc_sfu_RequestToken {
+        info!("rtc_sfu_deleteCallLink_async():");
+
+        let Some(http_client) = http_client.as_ref() else {
+            error!("null http_client passed into rtc_sfu_deleteCallLink_async");
+            return 0;
+        };
+        let (token, result_callback) = register_cancellable_request(move |token, result| {
+            delegate.handle_response(token, result)
+        });
+        if start_delete_call_link(
+            http_client,
+            sfu_url,
+            auth_credential_presentation,
+            link_root_key,
+            admin_passkey,
+            result_callback,
+        ) {
+            token
+        } else {
+            unregister_cancellable_request(token);
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, sync::Mutex};
+
+    use super::*;
+
+    /// Stores one call link's encrypted name and metadata, and responds to reads and updates
+    /// right away.
+    #[derive(Default)]
+    struct FakeCallLinkServer {
+        encrypted_name: Mutex<String>,
+        encrypted_metadata: Mutex<String>,
+        /// If set, updates are rejected unless they have this (base64) admin passkey.
+        admin_passkey: Mutex<Option<String>>,
+    }
+
+    impl http::Client for FakeCallLinkServer {
+        fn send_request(&self, request: http::Request, callback: http::ResponseCallback) {
+            if request.method == http::Method::Put {
+                let update: serde_json::Value =
+                    serde_json::from_slice(request.body.as_deref().unwrap()).unwrap();
+                let accepted = {
+                    let mut admin_passkey = self.admin_passkey.lock().unwrap();
+                    let accepted = admin_passkey
+                        .as_ref()
+                        .is_none_or(|passkey| update["adminPasskey"] == *passkey);
+                    if accepted {
+                        if let Some(new_passkey) = update["newAdminPasskey"].as_str() {
+                            *admin_passkey = Some(new_passkey.to_string());
+                        }
+                    }
+                    accepted
+                };
+                if !accepted {
+                    return callback(Some(http::Response {
+                        status: 403.into(),
+                        body: vec![],
+                    }));
+                }
+                if let Some(name) = update["name"].as_str() {
+                    *self.encrypted_name.lock().unwrap() = name.to_string();
+                }
+                if let Some(metadata) = update["metadata"].as_str() {
+                    *self.encrypted_metadata.lock().unwrap() = metadata.to_string();
+                }
+            }
+            let body = serde_json::json!({
+                "name": *self.encrypted_name.lock().unwrap(),
+                "restrictions": "none",
+                "revoked": false,
+                "expiration": 1_700_000_000,
+                "metadata": *self.encrypted_metadata.lock().unwrap(),
+            });
+            callback(Some(http::Response {
+                status: 200.into(),
+                body: serde_json::to_vec(&body).unwrap(),
+            }))
+        }
+    }
+
+    #[test]
+    fn migrate_v1_to_v2() {
+        let v1_key = CallLinkRootKey::generate(rand::thread_rng());
+        assert_eq!(CallLinkRootKeyVersion::V1, v1_key.version());
+        let metadata = CallLinkMetadata {
+            description: "Weekly sync".to_string(),
+            accent_color: Some(0x3a76f0),
+            avatar: None,
+        };
+        let server = Arc::new(FakeCallLinkServer::default());
+        *server.encrypted_name.lock().unwrap() =
+            base64.encode(v1_key.encrypt(b"Secret Hideout", rand::thread_rng()));
+        *server.encrypted_metadata.lock().unwrap() =
+            base64.encode(metadata.encrypt(&v1_key, rand::thread_rng()));
+        let v1_encrypted_name = server.encrypted_name.lock().unwrap().clone();
+
+        let v2_key = v1_key.with_version(CallLinkRootKeyVersion::LATEST);
+        let result = Arc::new(Mutex::new(None));
+        let result_for_callback = result.clone();
+        read_and_migrate_call_link(
+            server.clone(),
+            "https://sfu.example",
+            v2_key.clone(),
+            b"auth",
+            b"passkey",
+            Box::new(move |state| *result_for_callback.lock().unwrap() = Some(state)),
+        );
+        let state = result.lock().unwrap().take().unwrap().unwrap();
+        assert_eq!("Secret Hideout", state.name);
+        assert_eq!(metadata, state.metadata);
+        assert_ne!(v1_encrypted_name, *server.encrypted_name.lock().unwrap());
+
+        // Both the migrated key and the one shared before the migration can still read the link.
+        for key in [v2_key, v1_key] {
+            let result = Arc::new(Mutex::new(None));
+            let result_for_callback = result.clone();
+            read_call_link(
+                server.as_ref(),
+                "https://sfu.example",
+                key,
+                None,
+                b"auth",
+                Box::new(move |state| *result_for_callback.lock().unwrap() = Some(state)),
+            );
+            let state = result.lock().unwrap().take().unwrap().unwrap();
+            assert_eq!("Secret Hideout", state.name);
+            assert_eq!(metadata, state.metadata);
+        }
+    }
+
+    #[test]
+    fn rotate_admin_passkey_recovers_from_earlier_attempt() {
+        let root_key = CallLinkRootKey::generate(rand::thread_rng());
+        let old_passkey = CallLinkRootKey::generate_admin_passkey(rand::thread_rng());
+        let new_passkey = CallLinkRootKey::generate_admin_passkey(rand::thread_rng());
+        let server = Arc::new(FakeCallLinkServer::default());
+        *server.admin_passkey.lock().unwrap() = Some(base64.encode(&old_passkey));
+
+        let rotate = || {
+            let result = Arc::new(Mutex::new(None));
+            let result_for_callback = result.clone();
+            rotate_admin_passkey(
+                server.clone(),
+                "https://sfu.example",
+                root_key.clone(),
+                b"auth",
+                &old_passkey,
+                &new_passkey,
+                Box::new(move |state| *result_for_callback.lock().unwrap() = Some(state)),
+            );
+            let state = result.lock().unwrap().take();
+            state.unwrap()
+        };
+
+        assert!(!rotate().unwrap().old_admin_passkey_rejected);
+        assert_eq!(
+            Some(base64.encode(&new_passkey)),
+            *server.admin_passkey.lock().unwrap()
+        );
+
+        // As if the first response had been lost.
+        assert!(rotate().unwrap().old_admin_passkey_rejected);
+
+        // Neither passkey works any more.
+        *server.admin_passkey.lock().unwrap() = Some(base64.encode(b"someone else's"));
+        assert_eq!(Err(http::ResponseStatus::from(403)), rotate());
+    }
+
+    /// Answers each request with the next status, and no body.
+    struct ScriptedStatuses(Mutex<VecDeque<u16>>);
+
+    impl http::Client for ScriptedStatuses {
+        fn send_request(&self, _request: http::Request, callback: http::ResponseCallback) {
+            let status = self.0.lock().unwrap().pop_front().unwrap();
+            callback(Some(http::Response {
+                status: status.into(),
+                body: vec![],
+            }))
+        }
+    }
+
+    #[test]
+    fn rotate_admin_passkey_reports_rejection_if_new_passkey_unconfirmed() {
+        // The retry with the new passkey fails for an unrelated reason.
+        let server = Arc::new(ScriptedStatuses(Mutex::new(VecDeque::from([403, 500]))));
+        let result = Arc::new(Mutex::new(None));
+        let result_for_callback = result.clone();
+        rotate_admin_passkey(
+            server.clone(),
+            "https://sfu.example",
+            CallLinkRootKey::generate(rand::thread_rng()),
+            b"auth",
+            b"old passkey",
+            b"new passkey",
+            Box::new(move |state| *result_for_callback.lock().unwrap() = Some(state)),
+        );
+        assert_eq!(
+            Some(Err(http::ResponseStatus::from(403))),
+            result.lock().unwrap().take()
+        );
+        assert!(server.0.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn usage_from_response() {
+        let parse = |body: &str| {
+            http::parse_json_response::<CallLinkUsageResponse>(Some(&http::Response {
+                status: 200.into(),
+                body: body.as_bytes().to_vec(),
+            }))
+            .map(CallLinkUsage::from)
+        };
+        assert_eq!(
+            Ok(CallLinkUsage {
+                last_call: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+                approximate_peak_participants: 12,
+                total_calls: 40,
+            }),
+            parse(r#"{"lastCallTime":1700000000,"peakParticipants":12,"totalCalls":40}"#)
+        );
+        // A link that was never used.
+        assert_eq!(
+            Ok(CallLinkUsage {
+                last_call: None,
+                approximate_peak_participants: 0,
+                total_calls: 0,
+            }),
+            parse(r#"{"lastCallTime":null}"#)
+        );
+    }
 }
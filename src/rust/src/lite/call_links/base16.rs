@@ -85,7 +85,6 @@ pub enum DecodeError {
 const SKIP_ALPHABET: &[u8] = b"-"; // only skip "-"
 
 impl ConsonantBase16<'_> {
-    #[allow(dead_code)]
     pub fn parse(string: &str) -> Result<Vec<u8>, DecodeError> {
         Self::parse_with_separators(string, usize::MAX)
     }
@@ -0,0 +1,76 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+use super::base16;
+
+/// The generation of a call link, bumped by the server whenever the link is
+/// created or its epoch is reset.
+///
+/// Links shared before a reset carry an older epoch, and are rejected even
+/// though the root key hasn't changed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CallLinkEpoch(u32);
+
+impl CallLinkEpoch {
+    pub fn value(self) -> u32 {
+        self.0
+    }
+
+    /// Formats the epoch for links, in the same alphabet as the root key.
+    pub fn to_formatted_string(self) -> String {
+        format!(
+            "{:-^.2}",
+            base16::ConsonantBase16::from(self.0.to_be_bytes().as_slice())
+        )
+    }
+}
+
+impl From<u32> for CallLinkEpoch {
+    fn from(value: u32) -> Self {
+        Self(value)
+    }
+}
+
+impl TryFrom<&str> for CallLinkEpoch {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
+        let bytes = base16::ConsonantBase16::parse_with_separators(value, 2).map_err(|e| {
+            error!("Parsing error: {:?}", e);
+            anyhow!("invalid epoch string")
+        })?;
+        let bytes: [u8; 4] = bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| anyhow!("invalid epoch length"))?;
+        Ok(Self(u32::from_be_bytes(bytes)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for value in [0, 1, 0x1234_5678, u32::MAX] {
+            let epoch = CallLinkEpoch::from(value);
+            let formatted = epoch.to_formatted_string();
+            assert_eq!(formatted.len(), 9, "{formatted}");
+            assert_eq!(epoch, CallLinkEpoch::try_from(formatted.as_str()).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(CallLinkEpoch::try_from("bcdf").is_err());
+        assert!(CallLinkEpoch::try_from("bcdf-bcdf-bcdf").is_err());
+        assert!(CallLinkEpoch::try_from("bcdf-bcda").is_err());
+    }
+}
@@ -0,0 +1,249 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+use sha2::{Digest, Sha256};
+
+use super::{base16, CallLinkEpoch, CallLinkRootKey};
+
+const URI_PREFIX: &str = "https://signal.link/call/#";
+const CURRENT_VERSION: u8 = 1;
+const CHECKSUM_LEN: usize = 2;
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum CallLinkUriError {
+    #[error("not a call link")]
+    Malformed,
+    /// The link was made by a newer client; the app should suggest updating.
+    #[error("unsupported call link version {0}")]
+    UnsupportedVersion(u8),
+    #[error("call link checksum mismatch")]
+    ChecksumMismatch,
+}
+
+/// Everything needed to join a call link, as shared with other people.
+///
+/// Every platform should go through this type rather than building links by hand, so that the same
+/// link comes out byte for byte everywhere. The same string is used for QR codes.
+///
+/// The canonical form is
+///
+/// ```text
+/// https://signal.link/call/#v=1&key=<root key>[&epoch=<epoch>]&check=<checksum>
+/// ```
+///
+/// where the checksum covers everything else, catching links that were mistyped or cut short.
+/// Links from before versioning, with only `key` and an optional `epoch`, are still accepted.
+#[derive(Clone)]
+pub struct CallLinkUri {
+    pub root_key: CallLinkRootKey,
+    pub epoch: Option<CallLinkEpoch>,
+}
+
+impl CallLinkUri {
+    fn checksum(version: u8, root_key: &CallLinkRootKey, epoch: Option<CallLinkEpoch>) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update([version]);
+        // Includes the key's version (for all but V1 keys, whose links predate it), so that
+        // changing only the version is caught too.
+        hasher.update(root_key.to_versioned_bytes());
+        if let Some(epoch) = epoch {
+            hasher.update(epoch.value().to_be_bytes());
+        }
+        let digest = hasher.finalize();
+        base16::ConsonantBase16::from(&digest[..CHECKSUM_LEN]).to_string()
+    }
+
+    // Not a Display implementation so we don't accidentally log it.
+    pub fn to_uri_string(&self) -> String {
+        let mut uri = format!(
+            "{URI_PREFIX}v={CURRENT_VERSION}&key={}",
+            self.root_key.to_formatted_string()
+        );
+        if let Some(epoch) = self.epoch {
+            uri.push_str("&epoch=");
+            uri.push_str(&epoch.to_formatted_string());
+        }
+        uri.push_str("&check=");
+        uri.push_str(&Self::checksum(CURRENT_VERSION, &self.root_key, self.epoch));
+        uri
+    }
+
+    /// Parses a link in any version this client understands.
+    ///
+    /// Unknown parameters are ignored, so that later versions can add optional ones without
+    /// bumping the version.
+    pub fn parse(uri: &str) -> Result<Self, CallLinkUriError> {
+        let fragment = uri
+            .trim()
+            .strip_prefix(URI_PREFIX)
+            .ok_or(CallLinkUriError::Malformed)?;
+
+        let mut version = None;
+        let mut root_key = None;
+        let mut epoch = None;
+        let mut check = None;
+        for parameter in fragment.split('&') {
+            let (name, value) = parameter
+                .split_once('=')
+                .ok_or(CallLinkUriError::Malformed)?;
+            let slot = match name {
+                "v" => &mut version,
+                "key" => &mut root_key,
+                "epoch" => &mut epoch,
+                "check" => &mut check,
+                _ => continue,
+            };
+            if slot.replace(value).is_some() {
+                return Err(CallLinkUriError::Malformed);
+            }
+        }
+
+        // Links without a version predate checksums.
+        let version = match version {
+            None => 0,
+            Some(version) => version.parse().map_err(|_| CallLinkUriError::Malformed)?,
+        };
+        if version > CURRENT_VERSION {
+            return Err(CallLinkUriError::UnsupportedVersion(version));
+        }
+
+        let root_key = root_key
+            .and_then(|root_key| CallLinkRootKey::try_from(root_key).ok())
+            .ok_or(CallLinkUriError::Malformed)?;
+        let epoch = epoch
+            .map(|epoch| CallLinkEpoch::try_from(epoch).map_err(|_| CallLinkUriError::Malformed))
+            .transpose()?;
+
+        match (version, check) {
+            (0, None) => {}
+            (0, Some(_)) | (_, None) => return Err(CallLinkUriError::Malformed),
+            (_, Some(check)) => {
+                if base16::ConsonantBase16::parse(check).is_err() {
+                    return Err(CallLinkUriError::Malformed);
+                }
+                if check != Self::checksum(version, &root_key, epoch) {
+                    return Err(CallLinkUriError::ChecksumMismatch);
+                }
+            }
+        }
+
+        Ok(Self { root_key, epoch })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lite::call_links::CallLinkRootKeyVersion;
+
+    #[test]
+    fn test_round_trip() {
+        let root_key = CallLinkRootKey::generate(rand::thread_rng());
+        for epoch in [None, Some(CallLinkEpoch::from(0x1234_5678))] {
+            let uri = CallLinkUri {
+                root_key: root_key.clone(),
+                epoch,
+            }
+            .to_uri_string();
+            let parsed = CallLinkUri::parse(&uri).unwrap();
+            assert_eq!(root_key.bytes(), parsed.root_key.bytes());
+            assert_eq!(epoch, parsed.epoch);
+            assert_eq!(uri, parsed.to_uri_string());
+        }
+    }
+
+    #[test]
+    fn test_canonical_form() {
+        let root_key =
+            CallLinkRootKey::try_from("bcdf-ghkm-npqr-stxz-bcdf-ghkm-npqr-stxz").unwrap();
+        let uri = CallLinkUri {
+            root_key,
+            epoch: Some(CallLinkEpoch::from(1)),
+        }
+        .to_uri_string();
+        let (without_check, check) = uri.rsplit_once("&check=").unwrap();
+        assert_eq!(
+            "https://signal.link/call/#v=1&key=bcdf-ghkm-npqr-stxz-bcdf-ghkm-npqr-stxz&epoch=bbbb-bbbc",
+            without_check
+        );
+        assert_eq!(4, check.len());
+    }
+
+    #[test]
+    fn test_key_version_is_checked() {
+        let v1_key = CallLinkRootKey::generate(rand::thread_rng());
+        let v2_key = v1_key.with_version(CallLinkRootKeyVersion::V2);
+        let uri = CallLinkUri {
+            root_key: v2_key.clone(),
+            epoch: None,
+        }
+        .to_uri_string();
+        let parsed = CallLinkUri::parse(&uri).unwrap();
+        assert_eq!(CallLinkRootKeyVersion::V2, parsed.root_key.version());
+
+        // The key string ends with the version byte, 0x02.
+        let formatted_v2_key = v2_key.to_formatted_string();
+        let explicit_v1_key = format!(
+            "{}bc",
+            formatted_v2_key.strip_suffix("bd").expect("version 2")
+        );
+        for other_key in [explicit_v1_key, v1_key.to_formatted_string()] {
+            assert_eq!(
+                Err(CallLinkUriError::ChecksumMismatch),
+                CallLinkUri::parse(&uri.replace(&formatted_v2_key, &other_key)).map(|_| ())
+            );
+        }
+    }
+
+    #[test]
+    fn test_legacy() {
+        let parsed = CallLinkUri::parse(
+            "https://signal.link/call/#key=bcdf-ghkm-npqr-stxz-bcdf-ghkm-npqr-stxz",
+        )
+        .unwrap();
+        assert!(parsed.epoch.is_none());
+        assert!(parsed.to_uri_string().contains("&check="));
+    }
+
+    #[test]
+    fn test_invalid() {
+        let uri = CallLinkUri {
+            root_key: CallLinkRootKey::generate(rand::thread_rng()),
+            epoch: Some(CallLinkEpoch::from(7)),
+        }
+        .to_uri_string();
+
+        let (without_check, check) = uri.rsplit_once("&check=").unwrap();
+        assert_eq!(
+            Err(CallLinkUriError::Malformed),
+            CallLinkUri::parse(without_check).map(|_| ())
+        );
+        let other_check = if check == "bbbb" { "bbbc" } else { "bbbb" };
+        assert_eq!(
+            Err(CallLinkUriError::ChecksumMismatch),
+            CallLinkUri::parse(&format!("{without_check}&check={other_check}")).map(|_| ())
+        );
+        assert_eq!(
+            Err(CallLinkUriError::ChecksumMismatch),
+            CallLinkUri::parse(&uri.replace("&epoch=bbbb-bbbm", "&epoch=bbbb-bbbn")).map(|_| ())
+        );
+        assert_eq!(
+            Err(CallLinkUriError::UnsupportedVersion(2)),
+            CallLinkUri::parse(&uri.replace("#v=1&", "#v=2&")).map(|_| ())
+        );
+        assert_eq!(
+            Err(CallLinkUriError::Malformed),
+            CallLinkUri::parse(&format!("{uri}&key=bcdf")).map(|_| ())
+        );
+        assert_eq!(
+            Err(CallLinkUriError::Malformed),
+            CallLinkUri::parse("https://signal.link/call/#epoch=bbbb-bbbc").map(|_| ())
+        );
+        assert_eq!(
+            Err(CallLinkUriError::Malformed),
+            CallLinkUri::parse("https://example.com/").map(|_| ())
+        );
+    }
+}
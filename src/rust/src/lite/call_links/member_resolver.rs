@@ -3,19 +3,16 @@
 // SPDX-License-Identifier: AGPL-3.0-only
 //
 
-use std::collections::VecDeque;
+use std::{collections::VecDeque, sync::Mutex};
 
 use hex::FromHex;
 
 use super::CallLinkRootKey;
-use crate::{
-    core::call_mutex::CallMutex,
-    lite::sfu::{MemberResolver, OpaqueUserIdMapping, UserId},
-};
+use crate::lite::sfu::{MemberResolver, OpaqueUserIdMapping, UserId};
 
 pub struct CallLinkMemberResolver {
     zkparams: zkgroup::call_links::CallLinkSecretParams,
-    cache: CallMutex<VecDeque<OpaqueUserIdMapping>>,
+    cache: Mutex<VecDeque<OpaqueUserIdMapping>>,
     #[cfg(test)]
     pub cache_hits: std::sync::atomic::AtomicU64,
 }
@@ -35,7 +32,7 @@ impl<'a> From<&'a CallLinkRootKey> for CallLinkMemberResolver {
             zkparams: zkgroup::call_links::CallLinkSecretParams::derive_from_root_key(
                 &value.bytes(),
             ),
-            cache: CallMutex::new(VecDeque::new(), "CallLinkMemberResolver.cache"),
+            cache: Mutex::new(VecDeque::new()),
             #[cfg(test)]
             cache_hits: Default::default(),
         }
@@ -44,9 +41,12 @@ impl<'a> From<&'a CallLinkRootKey> for CallLinkMemberResolver {
 
 impl MemberResolver for CallLinkMemberResolver {
     fn resolve(&self, opaque_user_id: &str) -> Option<UserId> {
-        let mut locked_cache = self.cache.lock_or_reset(|_| {
+        let mut locked_cache = self.cache.lock().unwrap_or_else(|poison_error| {
             error!("resetting CallLinkMemberResolver cache after panic");
-            VecDeque::default()
+            self.cache.clear_poison();
+            let mut locked_cache = poison_error.into_inner();
+            locked_cache.clear();
+            locked_cache
         });
         if let Some(mapping) = locked_cache
             .iter()
@@ -0,0 +1,108 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+use base64::{engine::general_purpose::STANDARD as base64, Engine};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use super::CallLinkRootKey;
+
+/// Details shown alongside a call link's name.
+///
+/// Like the name, these are encrypted with the root key, so the server only ever sees one opaque
+/// blob. Updating any of them replaces all of them.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallLinkMetadata {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    /// An RGB color, as 0xRRGGBB.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accent_color: Option<u32>,
+    /// An app-defined reference to an avatar stored elsewhere, such as an attachment pointer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<String>,
+}
+
+impl CallLinkMetadata {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Empty metadata encrypts to nothing, which clears it on the server.
+    pub fn encrypt(&self, root_key: &CallLinkRootKey, rng: impl RngCore + CryptoRng) -> Vec<u8> {
+        if self.is_empty() {
+            return vec![];
+        }
+        let serialized = serde_json::to_vec(self).expect("cannot fail to serialize");
+        root_key.encrypt(&serialized, rng)
+    }
+
+    /// Decrypts metadata as sent by the server, base64-encoded.
+    ///
+    /// Metadata that fails to decrypt is treated as missing rather than failing the whole request,
+    /// as with the name. Unknown fields are ignored, so that newer clients can add more.
+    pub(super) fn decrypt(encrypted_base64: &[u8], root_key: &CallLinkRootKey) -> Self {
+        if encrypted_base64.is_empty() {
+            return Self::default();
+        }
+        base64
+            .decode(encrypted_base64)
+            .ok()
+            .and_then(|encrypted_bytes| root_key.decrypt(&encrypted_bytes).ok())
+            .and_then(|metadata_bytes| serde_json::from_slice(&metadata_bytes).ok())
+            .unwrap_or_else(|| {
+                warn!("encrypted metadata of call link failed to decrypt");
+                Default::default()
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let root_key = CallLinkRootKey::generate(rand::thread_rng());
+        let metadata = CallLinkMetadata {
+            description: "Every other Tuesday".to_string(),
+            accent_color: Some(0x3a76f0),
+            avatar: Some("attachment-1234".to_string()),
+        };
+        let encrypted = base64.encode(metadata.encrypt(&root_key, rand::thread_rng()));
+        assert_eq!(
+            metadata,
+            CallLinkMetadata::decrypt(encrypted.as_bytes(), &root_key)
+        );
+    }
+
+    #[test]
+    fn test_empty_and_invalid() {
+        let root_key = CallLinkRootKey::generate(rand::thread_rng());
+        assert!(CallLinkMetadata::default()
+            .encrypt(&root_key, rand::thread_rng())
+            .is_empty());
+        assert_eq!(
+            CallLinkMetadata::default(),
+            CallLinkMetadata::decrypt(b"", &root_key)
+        );
+        assert_eq!(
+            CallLinkMetadata::default(),
+            CallLinkMetadata::decrypt(b"not base64!", &root_key)
+        );
+
+        let other_key = CallLinkRootKey::generate(rand::thread_rng());
+        let encrypted = CallLinkMetadata {
+            description: "secret".to_string(),
+            ..Default::default()
+        }
+        .encrypt(&other_key, rand::thread_rng());
+        assert_eq!(
+            CallLinkMetadata::default(),
+            CallLinkMetadata::decrypt(base64.encode(encrypted).as_bytes(), &root_key)
+        );
+    }
+}
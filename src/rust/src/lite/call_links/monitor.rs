@@ -0,0 +1,356 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+use super::{read_call_link, CallLinkEpoch, CallLinkRootKey, ReadCallLinkResultCallback};
+use crate::lite::http;
+
+/// What the SFU returns when an auth credential presentation is rejected, such as when the
+/// credential has expired.
+const CREDENTIAL_REJECTED: http::ResponseStatus = http::ResponseStatus { code: 401 };
+
+pub type AuthCredentialPresentationCallback = Box<dyn FnOnce(Option<Vec<u8>>) + Send>;
+
+/// Supplies the auth credential presentations used for call link requests, so that they can be
+/// refreshed without the caller noticing.
+pub trait CallLinkAuthProvider: Send + Sync {
+    /// Presents an auth credential for requests about `root_key`, or None if there isn't one.
+    ///
+    /// `refresh` is set when the SFU rejected the previous presentation, in which case the
+    /// credential should be fetched again rather than taken from a cache.
+    fn present_auth_credential(
+        &self,
+        root_key: &CallLinkRootKey,
+        refresh: bool,
+        callback: AuthCredentialPresentationCallback,
+    );
+}
+
+/// Told when a call link read through a [`CallLinkMonitor`] is about to expire.
+pub trait CallLinkExpirationObserver: Send + Sync {
+    fn handle_call_link_expiring(&self, root_key: &CallLinkRootKey, expiration: SystemTime);
+}
+
+struct WatchedLink {
+    root_key: CallLinkRootKey,
+    expiration: SystemTime,
+    notified: bool,
+}
+
+/// Reads call links for long-lived UI, such as a call list.
+///
+/// Requests get their credentials from a [`CallLinkAuthProvider`], and are retried once with a
+/// refreshed credential if the SFU rejects one. Every link read successfully is remembered, and
+/// the [`CallLinkExpirationObserver`] is told once when it gets within `warning_period` of
+/// expiring. Reading the link again picks up an extended expiration.
+pub struct CallLinkMonitor {
+    http_client: Arc<dyn http::Client + Send + Sync>,
+    sfu_url: String,
+    auth_provider: Arc<dyn CallLinkAuthProvider>,
+    observer: Arc<dyn CallLinkExpirationObserver>,
+    warning_period: Duration,
+    links: Mutex<HashMap<Vec<u8>, WatchedLink>>,
+}
+
+impl CallLinkMonitor {
+    pub fn new(
+        http_client: Arc<dyn http::Client + Send + Sync>,
+        sfu_url: String,
+        auth_provider: Arc<dyn CallLinkAuthProvider>,
+        observer: Arc<dyn CallLinkExpirationObserver>,
+        warning_period: Duration,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            http_client,
+            sfu_url,
+            auth_provider,
+            observer,
+            warning_period,
+            links: Mutex::default(),
+        })
+    }
+
+    /// Like [`read_call_link`], but with a credential from the auth provider, and watching the
+    /// link's expiration afterwards.
+    pub fn read_call_link(
+        self: &Arc<Self>,
+        root_key: CallLinkRootKey,
+        epoch: Option<CallLinkEpoch>,
+        result_callback: ReadCallLinkResultCallback,
+    ) {
+        self.read_call_link_with_credential(root_key, epoch, false, result_callback)
+    }
+
+    fn read_call_link_with_credential(
+        self: &Arc<Self>,
+        root_key: CallLinkRootKey,
+        epoch: Option<CallLinkEpoch>,
+        refresh: bool,
+        result_callback: ReadCallLinkResultCallback,
+    ) {
+        let monitor = self.clone();
+        let provider_root_key = root_key.clone();
+        self.auth_provider.present_auth_credential(
+            &provider_root_key,
+            refresh,
+            Box::new(move |presentation| {
+                let Some(presentation) = presentation else {
+                    warn!("no call link auth credential available");
+                    result_callback(Err(http::ResponseStatus::INVALID_CLIENT_AUTH));
+                    return;
+                };
+                let response_monitor = monitor.clone();
+                let response_root_key = root_key.clone();
+                read_call_link(
+                    &*monitor.http_client,
+                    &monitor.sfu_url,
+                    root_key,
+                    epoch,
+                    &presentation,
+                    Box::new(move |result| match result {
+                        Err(status) if status == CREDENTIAL_REJECTED && !refresh => {
+                            info!("call link auth credential was rejected, refreshing it");
+                            response_monitor.read_call_link_with_credential(
+                                response_root_key,
+                                epoch,
+                                true,
+                                result_callback,
+                            );
+                        }
+                        Ok(state) => {
+                            response_monitor.watch(response_root_key, state.expiration);
+                            response_monitor.check_expirations(SystemTime::now());
+                            result_callback(Ok(state));
+                        }
+                        Err(status) => result_callback(Err(status)),
+                    }),
+                );
+            }),
+        );
+    }
+
+    fn watch(&self, root_key: CallLinkRootKey, expiration: SystemTime) {
+        let mut links = self.links.lock().expect("CallLinkMonitor:links lock");
+        match links.entry(root_key.derive_room_id()) {
+            Entry::Occupied(mut entry) => {
+                let link = entry.get_mut();
+                if link.expiration != expiration {
+                    link.expiration = expiration;
+                    link.notified = false;
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(WatchedLink {
+                    root_key,
+                    expiration,
+                    notified: false,
+                });
+            }
+        }
+    }
+
+    /// Tells the observer about every watched link that expires within the warning period of
+    /// `now` and hasn't been reported yet. Reads check this already, but apps should also call it
+    /// periodically (such as whenever the call list is shown), since links expire without any
+    /// request being made.
+    pub fn check_expirations(&self, now: SystemTime) {
+        let expiring: Vec<(CallLinkRootKey, SystemTime)> = {
+            let mut links = self.links.lock().expect("CallLinkMonitor:links lock");
+            links
+                .values_mut()
+                .filter(|link| !link.notified && link.expiration <= now + self.warning_period)
+                .map(|link| {
+                    link.notified = true;
+                    (link.root_key.clone(), link.expiration)
+                })
+                .collect()
+        };
+        // Notify without the lock held, in case the observer reads the link again.
+        for (root_key, expiration) in expiring {
+            self.observer
+                .handle_call_link_expiring(&root_key, expiration);
+        }
+    }
+
+    /// Stops watching a link, such as after it's deleted.
+    pub fn forget(&self, root_key: &CallLinkRootKey) {
+        self.links
+            .lock()
+            .expect("CallLinkMonitor:links lock")
+            .remove(&root_key.derive_room_id());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+
+    struct FakeClient {
+        responses: Mutex<VecDeque<http::Response>>,
+        auth_headers: Mutex<Vec<String>>,
+    }
+
+    impl http::Client for FakeClient {
+        fn send_request(&self, request: http::Request, callback: http::ResponseCallback) {
+            self.auth_headers
+                .lock()
+                .unwrap()
+                .push(request.headers["Authorization"].clone());
+            let response = self.responses.lock().unwrap().pop_front();
+            callback(response);
+        }
+    }
+
+    #[derive(Default)]
+    struct FakeAuthProvider {
+        refreshes: Mutex<u8>,
+    }
+
+    impl CallLinkAuthProvider for FakeAuthProvider {
+        fn present_auth_credential(
+            &self,
+            _root_key: &CallLinkRootKey,
+            refresh: bool,
+            callback: AuthCredentialPresentationCallback,
+        ) {
+            let mut refreshes = self.refreshes.lock().unwrap();
+            if refresh {
+                *refreshes += 1;
+            }
+            callback(Some(vec![*refreshes]));
+        }
+    }
+
+    #[derive(Default)]
+    struct FakeObserver {
+        expiring: Mutex<Vec<SystemTime>>,
+    }
+
+    impl CallLinkExpirationObserver for FakeObserver {
+        fn handle_call_link_expiring(&self, _root_key: &CallLinkRootKey, expiration: SystemTime) {
+            self.expiring.lock().unwrap().push(expiration);
+        }
+    }
+
+    fn state_response(expiration: SystemTime) -> http::Response {
+        let expiration = expiration
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        http::Response {
+            status: 200.into(),
+            body: format!(
+                r#"{{"name":"","restrictions":"none","revoked":false,"expiration":{expiration}}}"#
+            )
+            .into_bytes(),
+        }
+    }
+
+    fn setup(
+        responses: Vec<http::Response>,
+    ) -> (Arc<FakeClient>, Arc<FakeObserver>, Arc<CallLinkMonitor>) {
+        let client = Arc::new(FakeClient {
+            responses: Mutex::new(responses.into()),
+            auth_headers: Mutex::default(),
+        });
+        let observer = Arc::new(FakeObserver::default());
+        let monitor = CallLinkMonitor::new(
+            client.clone(),
+            "sfu.example".to_string(),
+            Arc::new(FakeAuthProvider::default()),
+            observer.clone(),
+            Duration::from_secs(24 * 60 * 60),
+        );
+        (client, observer, monitor)
+    }
+
+    /// Returns the status of the read, which completes synchronously with a [`FakeClient`].
+    fn read(monitor: &Arc<CallLinkMonitor>, root_key: &CallLinkRootKey) -> u16 {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        monitor.read_call_link(
+            root_key.clone(),
+            None,
+            Box::new(move |result| {
+                let _ = sender.send(result.map_or_else(|status| status.code, |_| 200));
+            }),
+        );
+        receiver.recv().unwrap()
+    }
+
+    #[test]
+    fn refreshes_rejected_credential_once() {
+        let far_future = SystemTime::now() + Duration::from_secs(30 * 24 * 60 * 60);
+        let rejected = http::Response {
+            status: CREDENTIAL_REJECTED,
+            body: vec![],
+        };
+        let (client, _observer, monitor) = setup(vec![
+            rejected.clone(),
+            state_response(far_future),
+            rejected.clone(),
+            rejected,
+        ]);
+        let root_key = CallLinkRootKey::generate(rand::thread_rng());
+
+        assert_eq!(200, read(&monitor, &root_key));
+        // A credential that's still rejected after refreshing is reported.
+        assert_eq!(401, read(&monitor, &root_key));
+
+        let auth_headers = client.auth_headers.lock().unwrap();
+        assert_eq!(4, auth_headers.len());
+        assert_ne!(auth_headers[0], auth_headers[1]);
+    }
+
+    #[test]
+    fn notifies_once_per_expiration() {
+        // Expirations are in whole seconds.
+        let now_seconds = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let soon = SystemTime::UNIX_EPOCH + Duration::from_secs(now_seconds + 60 * 60);
+        let later = soon + Duration::from_secs(30 * 24 * 60 * 60);
+        let (_client, observer, monitor) = setup(vec![
+            state_response(soon),
+            state_response(soon),
+            state_response(later),
+        ]);
+        let root_key = CallLinkRootKey::generate(rand::thread_rng());
+
+        assert_eq!(200, read(&monitor, &root_key));
+        assert_eq!(vec![soon], *observer.expiring.lock().unwrap());
+
+        // Nothing new to report until the expiration changes.
+        assert_eq!(200, read(&monitor, &root_key));
+        monitor.check_expirations(SystemTime::now());
+        assert_eq!(vec![soon], *observer.expiring.lock().unwrap());
+
+        // An extended expiration is reported again once it gets close.
+        assert_eq!(200, read(&monitor, &root_key));
+        monitor.check_expirations(SystemTime::now());
+        assert_eq!(vec![soon], *observer.expiring.lock().unwrap());
+        monitor.check_expirations(later);
+        assert_eq!(vec![soon, later], *observer.expiring.lock().unwrap());
+    }
+
+    #[test]
+    fn forgotten_links_are_not_reported() {
+        let expiration = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let (_client, observer, monitor) = setup(vec![]);
+        let root_key = CallLinkRootKey::generate(rand::thread_rng());
+
+        monitor.watch(root_key.clone(), expiration);
+        monitor.forget(&root_key);
+        monitor.check_expirations(expiration);
+        assert!(observer.expiring.lock().unwrap().is_empty());
+    }
+}
@@ -14,9 +14,48 @@ use sha2::Sha256;
 
 use super::base16;
 
+/// Selects how a root key derives its encryption key.
+///
+/// New versions only change what's derived from the key bytes, never the bytes themselves, so the
+/// room ID and zkgroup params stay the same and a link can move to a new version without being
+/// recreated. See [`super::migrate_call_link`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CallLinkRootKeyVersion {
+    V1 = 1,
+    V2 = 2,
+}
+
+impl CallLinkRootKeyVersion {
+    /// Newest first, the order to try when decrypting.
+    const ALL: [Self; 2] = [Self::V2, Self::V1];
+
+    /// What existing links should be migrated to.
+    pub const LATEST: Self = Self::V2;
+
+    fn encryption_label(self) -> &'static [u8] {
+        match self {
+            Self::V1 => b"20230501-Signal-CallLinkRootKey-AES",
+            Self::V2 => b"20241015-Signal-CallLinkRootKey-V2-AES",
+        }
+    }
+}
+
+impl TryFrom<u8> for CallLinkRootKeyVersion {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u8) -> std::result::Result<Self, Self::Error> {
+        Self::ALL
+            .into_iter()
+            .find(|version| *version as u8 == value)
+            .ok_or_else(|| anyhow!("unsupported root key version {value}"))
+    }
+}
+
 #[derive(Clone)]
 pub struct CallLinkRootKey {
     bytes: [u8; 16],
+    version: CallLinkRootKeyVersion,
 }
 
 #[derive(Debug)]
@@ -50,7 +89,23 @@ impl CallLinkRootKey {
             rng.fill_bytes(&mut bytes);
         }
 
-        Self { bytes }
+        // Stay on V1 until every client can parse versioned keys.
+        Self {
+            bytes,
+            version: CallLinkRootKeyVersion::V1,
+        }
+    }
+
+    pub fn version(&self) -> CallLinkRootKeyVersion {
+        self.version
+    }
+
+    /// The same key, encrypting with `version` from now on.
+    pub fn with_version(&self, version: CallLinkRootKeyVersion) -> Self {
+        Self {
+            bytes: self.bytes,
+            version,
+        }
     }
 
     pub fn generate_admin_passkey(mut rng: impl RngCore + CryptoRng) -> Vec<u8> {
@@ -64,6 +119,7 @@ impl CallLinkRootKey {
     pub fn derive_room_id(&self) -> Vec<u8> {
         // There are no constraints on the room ID, other than not being unreasonably long.
         // It's never shown to users, but it does appear in HTTP requests to the calling server.
+        // It doesn't depend on the version, so that migrating keeps the same room.
         let mut room_id_bytes = [0u8; 32];
         Hkdf::<Sha256>::new(None, &self.bytes)
             .expand(
@@ -74,10 +130,10 @@ impl CallLinkRootKey {
         room_id_bytes.to_vec()
     }
 
-    fn make_cipher(&self) -> Aes256GcmSiv {
+    fn make_cipher(&self, version: CallLinkRootKeyVersion) -> Aes256GcmSiv {
         let mut key = Key::<Aes256GcmSiv>::default();
         Hkdf::<Sha256>::new(None, &self.bytes)
-            .expand(b"20230501-Signal-CallLinkRootKey-AES", &mut key)
+            .expand(version.encryption_label(), &mut key)
             .expect("valid output length");
         Aes256GcmSiv::new(&key)
     }
@@ -102,7 +158,7 @@ impl CallLinkRootKey {
         }
 
         let tag = self
-            .make_cipher()
+            .make_cipher(self.version)
             .encrypt_in_place_detached(&nonce.into(), &[], &mut buffer[nonce.len()..])
             .expect("can encrypt arbitrary data");
         buffer.extend_from_slice(&tag);
@@ -110,6 +166,8 @@ impl CallLinkRootKey {
         buffer
     }
 
+    /// Decrypts data encrypted under any version of this key, not just the current one, so that
+    /// links shared before a migration can still read state written after it, and vice versa.
     pub fn decrypt(&self, encrypted: &[u8]) -> Result<Vec<u8>, FailedToDecrypt> {
         let nonce_len = <Aes256GcmSiv as AeadCore>::NonceSize::USIZE;
         if encrypted.len() < nonce_len {
@@ -117,10 +175,18 @@ impl CallLinkRootKey {
         }
         let (nonce, ciphertext) = encrypted.split_at(nonce_len);
 
-        let mut plaintext = self
-            .make_cipher()
-            .decrypt(nonce.into(), ciphertext)
-            .map_err(|_| FailedToDecrypt)?;
+        let versions = std::iter::once(self.version).chain(
+            CallLinkRootKeyVersion::ALL
+                .into_iter()
+                .filter(|version| *version != self.version),
+        );
+        let mut plaintext = versions
+            .find_map(|version| {
+                self.make_cipher(version)
+                    .decrypt(nonce.into(), ciphertext)
+                    .ok()
+            })
+            .ok_or(FailedToDecrypt)?;
 
         let padding_marker_position = plaintext
             .iter()
@@ -131,15 +197,27 @@ impl CallLinkRootKey {
         Ok(plaintext)
     }
 
+    /// The key bytes, without the version. This is what zkgroup derives call link params from.
     pub fn bytes(&self) -> [u8; 16] {
         self.bytes
     }
 
+    /// The key bytes followed by the version, except for V1 keys, which are just the bytes.
+    ///
+    /// This is what should be stored and shared; [`TryFrom<&[u8]>`] accepts both forms.
+    pub fn to_versioned_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.bytes.to_vec();
+        if self.version != CallLinkRootKeyVersion::V1 {
+            bytes.push(self.version as u8);
+        }
+        bytes
+    }
+
     // Not a Display implementation so we don't accidentally log it.
     pub fn to_formatted_string(&self) -> String {
         format!(
             "{:-^.2}",
-            base16::ConsonantBase16::from(self.bytes.as_slice())
+            base16::ConsonantBase16::from(self.to_versioned_bytes().as_slice())
         )
     }
 }
@@ -161,13 +239,19 @@ impl TryFrom<&[u8]> for CallLinkRootKey {
     type Error = anyhow::Error;
 
     fn try_from(value: &[u8]) -> std::result::Result<Self, Self::Error> {
-        let bytes: [u8; 16] = value.try_into()?;
+        let (bytes, version) = match value {
+            [bytes @ .., version] if bytes.len() == 16 => {
+                (bytes, CallLinkRootKeyVersion::try_from(*version)?)
+            }
+            bytes => (bytes, CallLinkRootKeyVersion::V1),
+        };
+        let bytes: [u8; 16] = bytes.try_into()?;
 
         if Self::has_repeated_chunk(&bytes) {
             bail!("invalid root key adjacent bytes");
         }
 
-        Ok(Self { bytes })
+        Ok(Self { bytes, version })
     }
 }
 
@@ -208,4 +292,38 @@ mod tests {
         let different_ciphertext = key.encrypt(b"Secret Base", rand::thread_rng());
         assert_eq!(ciphertext.len(), different_ciphertext.len(),);
     }
+
+    #[test]
+    fn test_versions() {
+        let v1_key = CallLinkRootKey::generate(rand::thread_rng());
+        let v2_key = v1_key.with_version(CallLinkRootKeyVersion::V2);
+        assert_eq!(v1_key.derive_room_id(), v2_key.derive_room_id());
+
+        // V1 keys keep their original format.
+        assert_eq!(16, v1_key.to_versioned_bytes().len());
+        let formatted = v2_key.to_formatted_string();
+        let round_trip_key = CallLinkRootKey::try_from(formatted.as_str()).unwrap();
+        assert_eq!(CallLinkRootKeyVersion::V2, round_trip_key.version());
+        assert_eq!(v2_key.bytes(), round_trip_key.bytes());
+
+        // Either version can read what the other wrote.
+        let v1_ciphertext = v1_key.encrypt(b"Secret Hideout", rand::thread_rng());
+        let v2_ciphertext = v2_key.encrypt(b"Secret Hideout", rand::thread_rng());
+        assert!(v1_key
+            .make_cipher(CallLinkRootKeyVersion::V1)
+            .decrypt(v2_ciphertext[..12].into(), &v2_ciphertext[12..])
+            .is_err());
+        assert_eq!(
+            b"Secret Hideout".as_slice(),
+            v1_key.decrypt(&v2_ciphertext).unwrap().as_slice()
+        );
+        assert_eq!(
+            b"Secret Hideout".as_slice(),
+            v2_key.decrypt(&v1_ciphertext).unwrap().as_slice()
+        );
+
+        let mut unknown_version = v1_key.to_versioned_bytes();
+        unknown_version.push(99);
+        assert!(CallLinkRootKey::try_from(unknown_version.as_slice()).is_err());
+    }
 }
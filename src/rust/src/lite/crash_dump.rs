@@ -0,0 +1,295 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! A minimal dump of what was going on when the process panicked, for postmortem analysis of
+//! crashes during calls.
+//!
+//! Once the app registers where the dump should go, with [set_dump_file] or
+//! [set_dump_callback], a panic writes the current state of every call, the in-flight HTTP
+//! requests, and the most recent [diagnostics](crate::lite::diagnostics) events there.
+//!
+//! By the time the panic hook runs, the panicking thread may hold any lock and the allocator
+//! may be what failed. So the dump is formatted into a fixed buffer on the stack, state is
+//! kept in fixed-size tables that never allocate, and everything is read with `try_lock`:
+//! anything that's locked is left out rather than risking a deadlock.
+
+use std::{
+    fmt::{self, Write as _},
+    fs::File,
+    io::Write as _,
+    panic,
+    sync::{Mutex, Once},
+};
+
+/// How many calls have their state tracked; beyond this, new calls are left out.
+const MAX_CALLS: usize = 8;
+/// How many in-flight HTTP requests are tracked; beyond this, new requests are left out.
+const MAX_IN_FLIGHT_REQUESTS: usize = 16;
+/// How many of the most recent diagnostic events are included.
+const RECENT_EVENTS: usize = 32;
+/// The most that a call's state description can take up; longer ones are truncated.
+const MAX_STATE_LEN: usize = 64;
+/// The size of the whole dump; anything more is truncated.
+const DUMP_CAPACITY: usize = 8 * 1024;
+
+/// Where the dump is written.
+pub enum DumpTarget {
+    /// Opened ahead of time, so that nothing has to be looked up at the time of the panic.
+    File(File),
+    /// Called with the dump, on the panicking thread. It must not panic itself.
+    Callback(Box<dyn Fn(&[u8]) + Send + Sync>),
+}
+
+static TARGET: Mutex<Option<DumpTarget>> = Mutex::new(None);
+static INSTALL_HOOK: Once = Once::new();
+
+/// Text of bounded length that is written in place, without allocating.
+struct FixedBuffer<const N: usize> {
+    bytes: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> FixedBuffer<N> {
+    const fn new() -> Self {
+        Self {
+            bytes: [0; N],
+            len: 0,
+        }
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+
+    fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl<const N: usize> fmt::Write for FixedBuffer<N> {
+    /// Fails once the buffer is full, keeping as much as fits.
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let available = N - self.len;
+        let written = s.len().min(available);
+        self.bytes[self.len..self.len + written].copy_from_slice(&s.as_bytes()[..written]);
+        self.len += written;
+        if written < s.len() {
+            Err(fmt::Error)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+struct CallStateEntry {
+    kind: &'static str,
+    id: u64,
+    state: FixedBuffer<MAX_STATE_LEN>,
+}
+
+static CALL_STATES: Mutex<[Option<CallStateEntry>; MAX_CALLS]> =
+    Mutex::new([const { None }; MAX_CALLS]);
+static IN_FLIGHT_REQUESTS: Mutex<[Option<u32>; MAX_IN_FLIGHT_REQUESTS]> =
+    Mutex::new([None; MAX_IN_FLIGHT_REQUESTS]);
+
+/// Writes the dump to the file when the process panics, replacing any previous target.
+pub fn set_dump_file(file: File) {
+    set_dump_target(Some(DumpTarget::File(file)));
+}
+
+/// Calls back with the dump when the process panics, replacing any previous target.
+pub fn set_dump_callback(callback: impl Fn(&[u8]) + Send + Sync + 'static) {
+    set_dump_target(Some(DumpTarget::Callback(Box::new(callback))));
+}
+
+/// Sets where the dump is written, or with None, that it isn't.
+///
+/// The first call installs a panic hook that runs the one that was already installed (such as
+/// one that logs the panic) before writing the dump, so it should come after logging has been
+/// initialized.
+pub fn set_dump_target(target: Option<DumpTarget>) {
+    if let Ok(mut current) = TARGET.lock() {
+        *current = target;
+    }
+    INSTALL_HOOK.call_once(|| {
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |panic_info| {
+            previous_hook(panic_info);
+            write_dump(panic_info);
+        }));
+    });
+}
+
+/// Records the state of a call, such as `format_args!("{:?}", state)`, keyed by what kind of
+/// call it is and its ID.
+pub fn set_call_state(kind: &'static str, id: u64, state: fmt::Arguments) {
+    let Ok(mut call_states) = CALL_STATES.lock() else {
+        return;
+    };
+    let slot = match call_states
+        .iter()
+        .position(|entry| matches!(entry, Some(entry) if entry.kind == kind && entry.id == id))
+        .or_else(|| call_states.iter().position(Option::is_none))
+    {
+        Some(index) => &mut call_states[index],
+        None => return,
+    };
+    let entry = slot.get_or_insert_with(|| CallStateEntry {
+        kind,
+        id,
+        state: FixedBuffer::new(),
+    });
+    entry.state.clear();
+    // Truncation is fine.
+    let _ = entry.state.write_fmt(state);
+}
+
+/// Forgets a call, once it has ended.
+pub fn remove_call_state(kind: &'static str, id: u64) {
+    if let Ok(mut call_states) = CALL_STATES.lock() {
+        for slot in call_states.iter_mut() {
+            if matches!(slot, Some(entry) if entry.kind == kind && entry.id == id) {
+                *slot = None;
+            }
+        }
+    }
+}
+
+pub fn http_request_started(request_id: u32) {
+    if let Ok(mut requests) = IN_FLIGHT_REQUESTS.lock() {
+        if let Some(slot) = requests.iter_mut().find(|slot| slot.is_none()) {
+            *slot = Some(request_id);
+        }
+    }
+}
+
+pub fn http_request_finished(request_id: u32) {
+    if let Ok(mut requests) = IN_FLIGHT_REQUESTS.lock() {
+        if let Some(slot) = requests.iter_mut().find(|slot| **slot == Some(request_id)) {
+            *slot = None;
+        }
+    }
+}
+
+/// Writes everything but the panic itself, leaving out whatever is locked.
+fn write_state(out: &mut impl fmt::Write) -> fmt::Result {
+    match CALL_STATES.try_lock() {
+        Ok(call_states) => {
+            for entry in call_states.iter().flatten() {
+                out.write_str("call: ")?;
+                out.write_str(entry.kind)?;
+                write!(out, " {} ", entry.id)?;
+                // Truncation may have split a character, which is left out.
+                for chunk in entry.state.as_bytes().utf8_chunks() {
+                    out.write_str(chunk.valid())?;
+                }
+                out.write_char('\n')?;
+            }
+        }
+        Err(_) => out.write_str("call: (locked)\n")?,
+    }
+
+    out.write_str("http in flight:")?;
+    match IN_FLIGHT_REQUESTS.try_lock() {
+        Ok(requests) => {
+            for request_id in requests.iter().flatten() {
+                write!(out, " {}", request_id)?;
+            }
+        }
+        Err(_) => out.write_str(" (locked)")?,
+    }
+    out.write_char('\n')?;
+
+    crate::lite::diagnostics::write_recent(out, RECENT_EVENTS)
+}
+
+fn write_dump(panic_info: &panic::PanicHookInfo) {
+    let Ok(target) = TARGET.try_lock() else {
+        return;
+    };
+    let Some(target) = target.as_ref() else {
+        return;
+    };
+
+    let mut dump = FixedBuffer::<DUMP_CAPACITY>::new();
+    // Whatever fit before the buffer filled up is still worth writing.
+    let _ = writeln!(dump, "RingRTC crash dump v1")
+        .and_then(|_| writeln!(dump, "panic: {}", panic_info))
+        .and_then(|_| write_state(&mut dump));
+
+    match target {
+        DumpTarget::File(file) => {
+            let mut file: &File = file;
+            let _ = file.write_all(dump.as_bytes());
+            let _ = file.sync_data();
+        }
+        DumpTarget::Callback(callback) => callback(dump.as_bytes()),
+    }
+}
+
+#[cfg(any(target_os = "ios", feature = "check-all"))]
+pub mod ios {
+    use std::fs::File;
+
+    use crate::lite::ffi::ios::rtc_String;
+
+    /// Creates (or truncates) the file at `path` to hold the dump, returning false if it can't.
+    #[no_mangle]
+    pub extern "C" fn rtc_crashDump_setPath(path: rtc_String) -> bool {
+        let Some(path) = path.as_str() else {
+            error!("rtc_crashDump_setPath(): invalid path");
+            return false;
+        };
+        match File::create(path) {
+            Ok(file) => {
+                super::set_dump_file(file);
+                true
+            }
+            Err(err) => {
+                error!("rtc_crashDump_setPath(): {}", err);
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_buffer_truncates() {
+        let mut buffer = FixedBuffer::<8>::new();
+        assert!(write!(buffer, "call {}", 1).is_ok());
+        assert!(write!(buffer, "23").is_ok());
+        assert!(write!(buffer, "456").is_err());
+        assert_eq!(b"call 123", buffer.as_bytes());
+    }
+
+    #[test]
+    fn tracks_calls_and_requests() {
+        // Other tests may track calls and requests of their own in the meantime.
+        const KIND: &str = "test";
+
+        set_call_state(KIND, 1, format_args!("{:?}", "Connecting"));
+        set_call_state(KIND, 2, format_args!("{:?}", "Connecting"));
+        set_call_state(KIND, 1, format_args!("Connected"));
+        remove_call_state(KIND, 2);
+        http_request_started(1_000_001);
+        http_request_started(1_000_002);
+        http_request_finished(1_000_001);
+
+        let mut dump = FixedBuffer::<DUMP_CAPACITY>::new();
+        write_state(&mut dump).unwrap();
+        let dump = std::str::from_utf8(dump.as_bytes()).unwrap();
+        assert!(dump.contains("call: test 1 Connected\n"));
+        assert!(!dump.contains("call: test 2 "));
+        assert!(dump.contains(" 1000002"));
+        assert!(!dump.contains(" 1000001"));
+
+        remove_call_state(KIND, 1);
+        http_request_finished(1_000_002);
+    }
+}
@@ -0,0 +1,204 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! A record of recent significant events, for attaching to bug reports.
+//!
+//! Logs are often gone or incomplete by the time a user reports that a call failed, so the most
+//! recent events are also kept in memory, where the app can export them along with the report.
+//! Details must not include anything identifying, since the export is sent as is.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+
+/// How many events are kept; older ones are dropped first.
+const CAPACITY: usize = 256;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiagnosticEventKind {
+    StateTransition,
+    IceChange,
+    HttpFailure,
+    DecryptFailure,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticEvent {
+    /// Milliseconds since the Unix epoch, of the first occurrence.
+    pub timestamp_millis: u64,
+    pub kind: DiagnosticEventKind,
+    pub detail: String,
+    /// How many times the event happened in a row, such as for every frame that fails to decrypt.
+    pub count: u32,
+}
+
+#[derive(Serialize)]
+struct Export<'a> {
+    version: u32,
+    events: &'a VecDeque<DiagnosticEvent>,
+}
+
+/// A bounded history of events, collapsing repeats.
+pub struct DiagnosticLog {
+    events: VecDeque<DiagnosticEvent>,
+    capacity: usize,
+}
+
+impl DiagnosticLog {
+    pub const fn new(capacity: usize) -> Self {
+        Self {
+            events: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    pub fn record(&mut self, timestamp: SystemTime, kind: DiagnosticEventKind, detail: String) {
+        if let Some(last) = self.events.back_mut() {
+            if last.kind == kind && last.detail == detail {
+                last.count = last.count.saturating_add(1);
+                return;
+            }
+        }
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(DiagnosticEvent {
+            timestamp_millis: timestamp
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |since_epoch| since_epoch.as_millis() as u64),
+            kind,
+            detail,
+            count: 1,
+        });
+    }
+
+    pub fn events(&self) -> impl Iterator<Item = &DiagnosticEvent> {
+        self.events.iter()
+    }
+
+    /// Serializes the events, oldest first, as compact JSON.
+    pub fn export(&self) -> Vec<u8> {
+        serde_json::to_vec(&Export {
+            version: 1,
+            events: &self.events,
+        })
+        .expect("cannot fail to serialize")
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+}
+
+static GLOBAL_LOG: Mutex<DiagnosticLog> = Mutex::new(DiagnosticLog::new(CAPACITY));
+
+/// Adds an event to the global log.
+pub fn record(kind: DiagnosticEventKind, detail: impl Into<String>) {
+    match GLOBAL_LOG.lock() {
+        Ok(mut log) => log.record(SystemTime::now(), kind, detail.into()),
+        Err(_) => warn!("diagnostics: log lock poisoned, dropping event"),
+    }
+}
+
+/// Exports the global log; see [`DiagnosticLog::export`].
+pub fn export() -> Vec<u8> {
+    GLOBAL_LOG
+        .lock()
+        .map(|log| log.export())
+        .unwrap_or_default()
+}
+
+/// Writes up to `count` of the most recent events in the global log, one per line, without
+/// allocating and without waiting for the log's lock, for [crash_dump](crate::lite::crash_dump).
+pub(crate) fn write_recent(out: &mut impl fmt::Write, count: usize) -> fmt::Result {
+    let Ok(log) = GLOBAL_LOG.try_lock() else {
+        return out.write_str("event: (locked)\n");
+    };
+    let skipped = log.events.len().saturating_sub(count);
+    for event in log.events().skip(skipped) {
+        writeln!(
+            out,
+            "event: {} {:?} {} (x{})",
+            event.timestamp_millis, event.kind, event.detail, event.count
+        )?;
+    }
+    Ok(())
+}
+
+/// Empties the global log, such as after a report has been sent.
+pub fn clear() {
+    if let Ok(mut log) = GLOBAL_LOG.lock() {
+        log.clear();
+    }
+}
+
+#[cfg(any(target_os = "ios", feature = "check-all"))]
+pub mod ios {
+    use std::ffi::c_void;
+
+    use crate::lite::ffi::ios::rtc_Bytes;
+
+    #[no_mangle]
+    pub extern "C" fn rtc_diagnostics_export(
+        context: *mut c_void,
+        callback: extern "C" fn(context: *mut c_void, result: rtc_Bytes),
+    ) {
+        let exported = super::export();
+        callback(context, rtc_Bytes::from(&exported));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn drops_oldest_and_collapses_repeats() {
+        use DiagnosticEventKind::*;
+
+        let mut log = DiagnosticLog::new(2);
+        let mut record = |millis, kind, detail: &str| {
+            log.record(
+                UNIX_EPOCH + Duration::from_millis(millis),
+                kind,
+                detail.to_string(),
+            )
+        };
+
+        record(1, IceChange, "Checking");
+        record(2, IceChange, "Connected");
+        record(3, DecryptFailure, "demux_id: 16");
+        record(4, DecryptFailure, "demux_id: 16");
+
+        let events: Vec<_> = log.events().collect();
+        assert_eq!(2, events.len());
+        assert_eq!("Connected", events[0].detail);
+        assert_eq!(3, events[1].timestamp_millis);
+        assert_eq!(2, events[1].count);
+    }
+
+    #[test]
+    fn export_format() {
+        let mut log = DiagnosticLog::new(CAPACITY);
+        log.record(
+            UNIX_EPOCH + Duration::from_millis(1_700_000_000_000),
+            DiagnosticEventKind::HttpFailure,
+            "404".to_string(),
+        );
+        assert_eq!(
+            r#"{"version":1,"events":[{"timestampMillis":1700000000000,"kind":"httpFailure","detail":"404","count":1}]}"#,
+            String::from_utf8(log.export()).unwrap()
+        );
+    }
+}
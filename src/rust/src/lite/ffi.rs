@@ -5,7 +5,12 @@
 
 //! For passing values to/from another language.
 
-#[cfg(any(target_os = "ios", feature = "java", feature = "check-all"))]
+#[cfg(any(
+    target_os = "ios",
+    feature = "java",
+    feature = "headless",
+    feature = "check-all"
+))]
 pub mod ios {
     use libc::size_t;
 
@@ -148,4 +153,23 @@ pub mod ios {
     }
     // Allows the macro to be used from other modules without #[macro_use].
     pub(crate) use cstr;
+
+    // ringrtc.h is generated from these definitions, but the Swift code using
+    // them is not, so pin down the layouts it relies on.
+    mod layout {
+        use std::mem::{offset_of, size_of};
+
+        use super::*;
+
+        const PTR: usize = size_of::<usize>();
+
+        const_assert_eq!(size_of::<rtc_OptionalU16>(), 4);
+        const_assert_eq!(offset_of!(rtc_OptionalU16, valid), 2);
+        const_assert_eq!(size_of::<rtc_OptionalU32>(), 8);
+        const_assert_eq!(offset_of!(rtc_OptionalU32, valid), 4);
+        const_assert_eq!(size_of::<rtc_Bytes>(), 2 * PTR);
+        const_assert_eq!(offset_of!(rtc_Bytes, count), PTR);
+        const_assert_eq!(size_of::<rtc_String>(), 2 * PTR);
+        const_assert_eq!(offset_of!(rtc_String, count), PTR);
+    }
 }
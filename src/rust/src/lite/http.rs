@@ -9,10 +9,13 @@
 use std::{
     collections::HashMap,
     sync::{Arc, Mutex},
+    time::Instant,
 };
 
 use serde::Deserialize;
 
+use crate::lite::metrics;
+
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Method {
@@ -62,14 +65,21 @@ impl ResponseStatus {
 
     pub const GROUP_CALL_NOT_STARTED: Self = Self { code: 404 };
     pub const GROUP_CALL_FULL: Self = Self { code: 413 };
+    /// There's an ongoing call using the call link, so it can't be deleted (or its restrictions
+    /// changed) until the call ends.
+    pub const CALL_LINK_IN_USE: Self = Self { code: 409 };
+    /// The call link was changed by someone else since the revision an update was based on.
+    pub const CALL_LINK_REVISION_CONFLICT: Self = Self { code: 412 };
 
     // Artificial codes not actually returned by the server
     pub const INVALID_CLIENT_AUTH: Self = Self { code: 601 };
     pub const REQUEST_FAILED: Self = Self { code: 602 };
+    pub const REQUEST_CANCELLED: Self = Self { code: 603 };
     pub const INVALID_RESPONSE_BODY_UTF8: Self = Self { code: 701 };
     pub const INVALID_RESPONSE_BODY_JSON: Self = Self { code: 702 };
     pub const CALL_LINK_EXPIRED: Self = Self { code: 703 };
     pub const CALL_LINK_INVALID: Self = Self { code: 704 };
+    pub const CALL_LINK_EPOCH_EXPIRED: Self = Self { code: 705 };
 }
 
 impl std::fmt::Display for ResponseStatus {
@@ -122,6 +132,11 @@ pub fn parse_json_response<'a, D: Deserialize<'a>>(
 ) -> Result<D, ResponseStatus> {
     let response = response.ok_or(ResponseStatus::REQUEST_FAILED)?;
     if !response.status.is_success() {
+        #[cfg(not(target_arch = "wasm32"))]
+        crate::lite::diagnostics::record(
+            crate::lite::diagnostics::DiagnosticEventKind::HttpFailure,
+            response.status.to_string(),
+        );
         return Err(response.status);
     }
     let deserialized = serde_json::from_slice(&response.body)
@@ -203,6 +218,20 @@ impl Client for DelegatingClient {
             "  url: {} method: {:?} headers: {:?}",
             request.url, request.method, request.headers
         );
+        let sent_at = Instant::now();
+        let response_callback: ResponseCallback = Box::new(move |response| {
+            metrics::record_histogram(
+                metrics::HTTP_REQUEST_DURATION,
+                sent_at.elapsed().as_secs_f64(),
+            );
+            if !response
+                .as_ref()
+                .map_or(false, |response| response.status.is_success())
+            {
+                metrics::increment_counter(metrics::HTTP_REQUEST_FAILURES, 1);
+            }
+            response_callback(response)
+        });
         let request_id = {
             let mut response_callbacks = self
                 .response_callbacks
@@ -230,15 +259,24 @@ impl ResponseCallbacks {
         self.next_request_id = self.next_request_id.wrapping_add(1);
         self.response_callback_by_request_id
             .insert(request_id, response_callback);
+        #[cfg(not(target_arch = "wasm32"))]
+        crate::lite::crash_dump::http_request_started(request_id);
         request_id
     }
 
     fn pop(&mut self, request_id: u32) -> Option<ResponseCallback> {
+        #[cfg(not(target_arch = "wasm32"))]
+        crate::lite::crash_dump::http_request_finished(request_id);
         self.response_callback_by_request_id.remove(&request_id)
     }
 }
 
-#[cfg(any(target_os = "ios", feature = "java", feature = "check-all"))]
+#[cfg(any(
+    target_os = "ios",
+    feature = "java",
+    feature = "headless",
+    feature = "check-all"
+))]
 pub mod ios {
     use libc::{c_void, size_t};
 
@@ -307,6 +345,24 @@ pub mod ios {
         pub body: rtc_Bytes<'a>,
     }
 
+    // The layouts the Swift code relies on; see lite::ffi::ios.
+    mod layout {
+        use std::mem::{offset_of, size_of};
+
+        use super::*;
+
+        const PTR: usize = size_of::<usize>();
+
+        const_assert_eq!(size_of::<rtc_http_Request>(), 7 * PTR);
+        const_assert_eq!(offset_of!(rtc_http_Request, method), 2 * PTR);
+        const_assert_eq!(offset_of!(rtc_http_Request, headers), 3 * PTR);
+        const_assert_eq!(offset_of!(rtc_http_Request, body), 5 * PTR);
+        const_assert_eq!(size_of::<rtc_http_Headers>(), 2 * PTR);
+        const_assert_eq!(size_of::<rtc_http_Header>(), 4 * PTR);
+        const_assert_eq!(size_of::<rtc_http_Response>(), 3 * PTR);
+        const_assert_eq!(offset_of!(rtc_http_Response, body), PTR);
+    }
+
     // Returns an owned pointer which should be destroyed
     // with rtc_http_Client_destroy.
     #[no_mangle]
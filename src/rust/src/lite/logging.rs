@@ -5,11 +5,17 @@
 
 //! Make calls to the platform to do logging
 
-#[cfg(any(target_os = "ios", feature = "check-all"))]
+#[cfg(any(target_os = "ios", feature = "headless", feature = "check-all"))]
 pub mod ios {
     use std::ffi::c_void;
 
-    use crate::lite::ffi::ios::{rtc_OptionalU32, rtc_String, FromOrDefault};
+    use crate::{
+        core::logging::{
+            clear_module_log_levels, level_filter_from_raw, set_max_log_level,
+            set_module_log_level, RedactingLogger,
+        },
+        lite::ffi::ios::{rtc_OptionalU32, rtc_String, FromOrDefault},
+    };
 
     #[repr(C)]
     pub struct rtc_log_Record<'a> {
@@ -32,22 +38,12 @@ pub mod ios {
 
     #[no_mangle]
     pub extern "C" fn rtc_log_init(delegate: rtc_log_Delegate, max_level: u8) -> bool {
-        if log::set_boxed_logger(Box::new(delegate)).is_err() {
+        if log::set_boxed_logger(Box::new(RedactingLogger::new(delegate))).is_err() {
             warn!("Logging already initialized");
             return false;
         }
 
-        let max_level_filter = match max_level {
-            level if level == (log::LevelFilter::Off as u8) => Some(log::LevelFilter::Off),
-            level if level == (log::LevelFilter::Error as u8) => Some(log::LevelFilter::Error),
-            level if level == (log::LevelFilter::Warn as u8) => Some(log::LevelFilter::Warn),
-            level if level == (log::LevelFilter::Info as u8) => Some(log::LevelFilter::Info),
-            level if level == (log::LevelFilter::Debug as u8) => Some(log::LevelFilter::Debug),
-            level if level == (log::LevelFilter::Trace as u8) => Some(log::LevelFilter::Trace),
-            _ => None,
-        };
-
-        if let Some(max_level_filter) = max_level_filter {
+        if let Some(max_level_filter) = level_filter_from_raw(max_level) {
             log::set_max_level(max_level_filter);
         } else {
             log::set_max_level(log::LevelFilter::Debug);
@@ -63,6 +59,47 @@ pub mod ios {
         true
     }
 
+    /// Changes the max log level set by [`rtc_log_init`], returning false if it's not valid.
+    #[no_mangle]
+    pub extern "C" fn rtc_log_setMaxLevel(max_level: u8) -> bool {
+        match level_filter_from_raw(max_level) {
+            Some(max_level_filter) => {
+                set_max_log_level(max_level_filter);
+                true
+            }
+            None => {
+                warn!("Invalid max log level = {:?}", max_level);
+                false
+            }
+        }
+    }
+
+    /// Overrides the max log level for one module, returning false if the module or level isn't
+    /// valid.
+    #[no_mangle]
+    pub extern "C" fn rtc_log_setModuleLevel(module: rtc_String, level: u8) -> bool {
+        let Some(module) = module.as_str() else {
+            warn!("Invalid log module");
+            return false;
+        };
+        match level_filter_from_raw(level) {
+            Some(level_filter) => {
+                set_module_log_level(module, level_filter);
+                true
+            }
+            None => {
+                warn!("Invalid log level = {:?}", level);
+                false
+            }
+        }
+    }
+
+    /// Removes every override from [`rtc_log_setModuleLevel`].
+    #[no_mangle]
+    pub extern "C" fn rtc_log_clearModuleLevels() {
+        clear_module_log_levels();
+    }
+
     impl log::Log for rtc_log_Delegate {
         fn enabled(&self, _metadata: &log::Metadata) -> bool {
             true
@@ -0,0 +1,108 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Reports internal metrics to an app-provided [MetricsSink], such as one that exports them to
+//! Prometheus or OpenTelemetry, so that deployments can monitor RingRTC without parsing logs.
+//!
+//! Nothing is reported until a sink is installed with [set_metrics_sink].
+
+use std::sync::{Arc, RwLock};
+
+/// How long HTTP requests take until a response (or failure) comes back, in seconds. A
+/// histogram.
+pub const HTTP_REQUEST_DURATION: &str = "ringrtc.http.request.duration";
+/// HTTP requests that failed or got an unsuccessful status. A counter.
+pub const HTTP_REQUEST_FAILURES: &str = "ringrtc.http.request.failures";
+/// Incoming group call frames that couldn't be decrypted. A counter.
+pub const FRAME_DECRYPT_FAILURES: &str = "ringrtc.frame.decrypt_failures";
+/// ICE restarts started by either side of a 1:1 call. A counter.
+pub const ICE_RESTARTS: &str = "ringrtc.ice.restarts";
+/// Incoming video frames dropped before being rendered. A counter.
+pub const VIDEO_FRAMES_DROPPED: &str = "ringrtc.video.frames_dropped";
+/// The round trip time of the most recent stats report, in seconds. A gauge.
+pub const ROUND_TRIP_TIME: &str = "ringrtc.connection.round_trip_time";
+
+/// Receives metrics as they happen.
+///
+/// Methods are called from many threads, including ones handling media, so they should return
+/// quickly, such as by updating an atomic.
+pub trait MetricsSink: Send + Sync {
+    fn increment_counter(&self, name: &'static str, value: u64);
+    fn set_gauge(&self, name: &'static str, value: f64);
+    fn record_histogram(&self, name: &'static str, value: f64);
+}
+
+static SINK: RwLock<Option<Arc<dyn MetricsSink>>> = RwLock::new(None);
+
+/// Installs the sink that all metrics are reported to, replacing any previous one. None stops
+/// reporting.
+pub fn set_metrics_sink(sink: Option<Arc<dyn MetricsSink>>) {
+    if let Ok(mut current) = SINK.write() {
+        *current = sink;
+    }
+}
+
+fn with_sink(report: impl FnOnce(&dyn MetricsSink)) {
+    if let Ok(sink) = SINK.read() {
+        if let Some(sink) = sink.as_deref() {
+            report(sink);
+        }
+    }
+}
+
+pub fn increment_counter(name: &'static str, value: u64) {
+    with_sink(|sink| sink.increment_counter(name, value));
+}
+
+pub fn set_gauge(name: &'static str, value: f64) {
+    with_sink(|sink| sink.set_gauge(name, value));
+}
+
+pub fn record_histogram(name: &'static str, value: f64) {
+    with_sink(|sink| sink.record_histogram(name, value));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Collector {
+        counters: Mutex<Vec<(&'static str, u64)>>,
+    }
+
+    impl MetricsSink for Collector {
+        fn increment_counter(&self, name: &'static str, value: u64) {
+            self.counters.lock().unwrap().push((name, value));
+        }
+
+        fn set_gauge(&self, _name: &'static str, _value: f64) {}
+
+        fn record_histogram(&self, _name: &'static str, _value: f64) {}
+    }
+
+    #[test]
+    fn reports_to_installed_sink() {
+        const NAME: &str = "test.counter";
+
+        increment_counter(NAME, 1);
+        let collector = Arc::new(Collector::default());
+        set_metrics_sink(Some(collector.clone()));
+        increment_counter(NAME, 2);
+        set_metrics_sink(None);
+        increment_counter(NAME, 3);
+
+        // Other tests may report metrics of their own in the meantime.
+        let counters = collector.counters.lock().unwrap();
+        let reported: Vec<u64> = counters
+            .iter()
+            .filter(|(name, _)| *name == NAME)
+            .map(|(_, value)| *value)
+            .collect();
+        assert_eq!(vec![2], reported);
+    }
+}
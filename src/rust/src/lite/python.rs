@@ -0,0 +1,583 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Python bindings for call link administration and SFU peeking, built with
+//! PyO3 so that operations can be scripted without going through a client.
+//!
+//! The module is built as an extension module named `ringrtc`:
+//!
+//! ```text
+//! cargo rustc --release --features python --crate-type cdylib
+//! cp ../../target/release/libringrtc.so ringrtc.so
+//! ```
+//!
+//! Requests are made synchronously through a Python callable, so that the
+//! script decides how HTTP is done:
+//!
+//! ```python
+//! def send_request(method, url, headers, body):
+//!     response = requests.request(method, url, headers=headers, data=body)
+//!     return (response.status_code, response.content)
+//!
+//! client = ringrtc.CallLinkClient("https://sfu.example.org", send_request)
+//! state = client.read_call_link(auth_credential_presentation, root_key)
+//! ```
+
+use std::{borrow::Cow, sync::mpsc, time::SystemTime};
+
+use pyo3::{
+    create_exception,
+    exceptions::{PyException, PyValueError},
+    prelude::*,
+    types::PyBytes,
+};
+
+use crate::lite::{
+    call_links::{self, CallLinkMemberResolver, CallLinkRootKey, CallLinkRootKeyVersion},
+    http, sfu,
+};
+
+create_exception!(
+    ringrtc,
+    RequestFailed,
+    PyException,
+    "A request to the SFU failed. The argument is the HTTP status code."
+);
+
+fn request_failed(status: http::ResponseStatus) -> PyErr {
+    RequestFailed::new_err(status.code)
+}
+
+fn parse_root_key(root_key: &[u8]) -> PyResult<CallLinkRootKey> {
+    CallLinkRootKey::try_from(root_key)
+        .map_err(|_| PyValueError::new_err("invalid call link root key"))
+}
+
+/// An impl of [`http::Client`] that calls a Python callable, which takes
+/// `(method, url, headers, body)` and returns `(status_code, body)`.
+struct PythonHttpClient {
+    send_request: Py<PyAny>,
+}
+
+impl PythonHttpClient {
+    fn send(&self, py: Python<'_>, request: http::Request) -> PyResult<http::Response> {
+        let method = match request.method {
+            http::Method::Get => "GET",
+            http::Method::Put => "PUT",
+            http::Method::Post => "POST",
+            http::Method::Delete => "DELETE",
+        };
+        let body = request.body.map(|body| PyBytes::new(py, &body));
+        let (status_code, body): (u16, Vec<u8>) = self
+            .send_request
+            .call1(py, (method, request.url, request.headers, body))?
+            .extract(py)?;
+        Ok(http::Response {
+            status: status_code.into(),
+            body,
+        })
+    }
+}
+
+impl http::Client for PythonHttpClient {
+    fn send_request(&self, request: http::Request, callback: http::ResponseCallback) {
+        let response = Python::with_gil(|py| {
+            self.send(py, request).map_err(|err| {
+                error!("python: send_request failed: {}", err);
+                err.restore(py);
+            })
+        });
+        callback(response.ok())
+    }
+}
+
+/// Runs a request whose callback is invoked before it returns, which is
+/// always the case with [`PythonHttpClient`].
+fn run_sync<T: Send + 'static>(start: impl FnOnce(Box<dyn FnOnce(T) + Send>)) -> T {
+    let (sender, receiver) = mpsc::channel();
+    start(Box::new(move |result| {
+        let _ = sender.send(result);
+    }));
+    receiver
+        .try_recv()
+        .expect("PythonHttpClient responds synchronously")
+}
+
+/// Fails with the Python exception raised by `send_request`, if there was
+/// one, rather than the status code it turned into.
+fn check_result<T>(py: Python<'_>, result: Result<T, http::ResponseStatus>) -> PyResult<T> {
+    if let Some(err) = PyErr::take(py) {
+        return Err(err);
+    }
+    result.map_err(request_failed)
+}
+
+#[pyclass(eq, eq_int, frozen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CallLinkRestrictions {
+    #[pyo3(name = "NONE")]
+    None,
+    #[pyo3(name = "ADMIN_APPROVAL")]
+    AdminApproval,
+    #[pyo3(name = "UNKNOWN")]
+    Unknown,
+}
+
+impl From<call_links::CallLinkRestrictions> for CallLinkRestrictions {
+    fn from(restrictions: call_links::CallLinkRestrictions) -> Self {
+        match restrictions {
+            call_links::CallLinkRestrictions::None => Self::None,
+            call_links::CallLinkRestrictions::AdminApproval => Self::AdminApproval,
+            call_links::CallLinkRestrictions::Unknown => Self::Unknown,
+        }
+    }
+}
+
+impl TryFrom<CallLinkRestrictions> for call_links::CallLinkRestrictions {
+    type Error = PyErr;
+
+    fn try_from(restrictions: CallLinkRestrictions) -> PyResult<Self> {
+        match restrictions {
+            CallLinkRestrictions::None => Ok(Self::None),
+            CallLinkRestrictions::AdminApproval => Ok(Self::AdminApproval),
+            CallLinkRestrictions::Unknown => Err(PyValueError::new_err(
+                "UNKNOWN restrictions cannot be sent to the server",
+            )),
+        }
+    }
+}
+
+#[pyclass(frozen, get_all)]
+#[derive(Clone, Debug)]
+pub struct CallLinkState {
+    pub name: String,
+    pub restrictions: CallLinkRestrictions,
+    pub revoked: bool,
+    pub expiration_epoch_seconds: u64,
+    pub description: String,
+    pub accent_color: Option<u32>,
+    pub avatar: Option<String>,
+    /// See `rotate_admin_passkey`.
+    pub old_admin_passkey_rejected: bool,
+}
+
+impl From<call_links::CallLinkState> for CallLinkState {
+    fn from(state: call_links::CallLinkState) -> Self {
+        Self {
+            name: state.name,
+            restrictions: state.restrictions.into(),
+            revoked: state.revoked,
+            expiration_epoch_seconds: state
+                .expiration
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |expiration| expiration.as_secs()),
+            description: state.metadata.description,
+            accent_color: state.metadata.accent_color,
+            avatar: state.metadata.avatar,
+            old_admin_passkey_rejected: state.old_admin_passkey_rejected,
+        }
+    }
+}
+
+#[pymethods]
+impl CallLinkState {
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+#[pyclass(frozen)]
+#[derive(Clone, Debug)]
+pub struct PeekDeviceInfo {
+    #[pyo3(get)]
+    pub demux_id: u32,
+    pub user_id: Option<Vec<u8>>,
+    /// Bridged in by a gateway, such as a phone dialing in over PSTN or SIP.
+    #[pyo3(get)]
+    pub bridged: bool,
+}
+
+impl From<sfu::PeekDeviceInfo> for PeekDeviceInfo {
+    fn from(device: sfu::PeekDeviceInfo) -> Self {
+        Self {
+            demux_id: device.demux_id,
+            user_id: device.user_id,
+            bridged: device.participant_kind == sfu::ParticipantKind::Bridged,
+        }
+    }
+}
+
+#[pymethods]
+impl PeekDeviceInfo {
+    #[getter]
+    fn user_id(&self) -> Option<Cow<'static, [u8]>> {
+        self.user_id.clone().map(Cow::Owned)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+#[pyclass(frozen)]
+#[derive(Clone, Debug)]
+pub struct PeekInfo {
+    #[pyo3(get)]
+    pub devices: Vec<PeekDeviceInfo>,
+    #[pyo3(get)]
+    pub pending_devices: Vec<PeekDeviceInfo>,
+    pub creator: Option<Vec<u8>>,
+    #[pyo3(get)]
+    pub era_id: Option<String>,
+    #[pyo3(get)]
+    pub max_devices: Option<u32>,
+    #[pyo3(get)]
+    pub call_link_state: Option<CallLinkState>,
+}
+
+impl From<sfu::PeekInfo> for PeekInfo {
+    fn from(peek_info: sfu::PeekInfo) -> Self {
+        Self {
+            devices: peek_info.devices.into_iter().map(Into::into).collect(),
+            pending_devices: peek_info
+                .pending_devices
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            creator: peek_info.creator,
+            era_id: peek_info.era_id,
+            max_devices: peek_info.max_devices,
+            call_link_state: peek_info.call_link_state.map(Into::into),
+        }
+    }
+}
+
+#[pymethods]
+impl PeekInfo {
+    #[getter]
+    fn creator(&self) -> Option<Cow<'static, [u8]>> {
+        self.creator.clone().map(Cow::Owned)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+/// Administers and peeks call links and group calls on one SFU.
+#[pyclass(frozen)]
+pub struct CallLinkClient {
+    sfu_url: String,
+    http_client: PythonHttpClient,
+}
+
+#[pymethods]
+impl CallLinkClient {
+    #[new]
+    fn new(sfu_url: String, send_request: Py<PyAny>) -> Self {
+        Self {
+            sfu_url,
+            http_client: PythonHttpClient { send_request },
+        }
+    }
+
+    fn read_call_link(
+        &self,
+        py: Python<'_>,
+        auth_credential_presentation: &[u8],
+        root_key: &[u8],
+    ) -> PyResult<CallLinkState> {
+        let root_key = parse_root_key(root_key)?;
+        let result = run_sync(|callback| {
+            call_links::read_call_link(
+                &self.http_client,
+                &self.sfu_url,
+                root_key,
+                None,
+                auth_credential_presentation,
+                callback,
+            )
+        });
+        check_result(py, result).map(Into::into)
+    }
+
+    #[pyo3(signature = (create_credential_presentation, root_key, admin_passkey, call_link_public_params, restrictions=None))]
+    fn create_call_link(
+        &self,
+        py: Python<'_>,
+        create_credential_presentation: &[u8],
+        root_key: &[u8],
+        admin_passkey: &[u8],
+        call_link_public_params: &[u8],
+        restrictions: Option<CallLinkRestrictions>,
+    ) -> PyResult<CallLinkState> {
+        let root_key = parse_root_key(root_key)?;
+        let restrictions = restrictions.map(TryInto::try_into).transpose()?;
+        let result = run_sync(|callback| {
+            call_links::create_call_link(
+                &self.http_client,
+                &self.sfu_url,
+                root_key,
+                create_credential_presentation,
+                admin_passkey,
+                call_link_public_params,
+                restrictions,
+                None,
+                callback,
+            )
+        });
+        check_result(py, result).map(Into::into)
+    }
+
+    /// Changes any of the name, restrictions (such as requiring admin
+    /// approval to join), and revocation of a call link. An empty name
+    /// clears it.
+    ///
+    /// The description, accent color, and avatar are stored together, so
+    /// passing any of them replaces all three.
+    #[pyo3(signature = (auth_credential_presentation, root_key, admin_passkey, name=None, restrictions=None, revoked=None, description=None, accent_color=None, avatar=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn update_call_link(
+        &self,
+        py: Python<'_>,
+        auth_credential_presentation: &[u8],
+        root_key: &[u8],
+        admin_passkey: &[u8],
+        name: Option<&str>,
+        restrictions: Option<CallLinkRestrictions>,
+        revoked: Option<bool>,
+        description: Option<String>,
+        accent_color: Option<u32>,
+        avatar: Option<String>,
+    ) -> PyResult<CallLinkState> {
+        let root_key = parse_root_key(root_key)?;
+        let encrypted_name = name.map(|name| {
+            if name.is_empty() {
+                vec![]
+            } else {
+                root_key.encrypt(name.as_bytes(), rand::rngs::OsRng)
+            }
+        });
+        let encrypted_metadata =
+            if description.is_some() || accent_color.is_some() || avatar.is_some() {
+                let metadata = call_links::CallLinkMetadata {
+                    description: description.unwrap_or_default(),
+                    accent_color,
+                    avatar,
+                };
+                Some(metadata.encrypt(&root_key, rand::rngs::OsRng))
+            } else {
+                None
+            };
+        let restrictions = restrictions.map(TryInto::try_into).transpose()?;
+        let result = run_sync(|callback| {
+            call_links::update_call_link(
+                &self.http_client,
+                &self.sfu_url,
+                root_key,
+                auth_credential_presentation,
+                &call_links::CallLinkUpdateRequest {
+                    admin_passkey,
+                    encrypted_name: encrypted_name.as_deref(),
+                    restrictions,
+                    revoked,
+                    reset_epoch: false,
+                    encrypted_metadata: encrypted_metadata.as_deref(),
+                    new_admin_passkey: None,
+                },
+                callback,
+            )
+        });
+        check_result(py, result).map(Into::into)
+    }
+
+    /// Switches to `new_admin_passkey`. See the Rust `rotate_admin_passkey`
+    /// for what to do if this fails.
+    fn rotate_admin_passkey(
+        &self,
+        py: Python<'_>,
+        auth_credential_presentation: &[u8],
+        root_key: &[u8],
+        admin_passkey: &[u8],
+        new_admin_passkey: &[u8],
+    ) -> PyResult<CallLinkState> {
+        let root_key = parse_root_key(root_key)?;
+        let result = run_sync(|callback| {
+            call_links::rotate_admin_passkey(
+                std::sync::Arc::new(PythonHttpClient {
+                    send_request: self.http_client.send_request.clone_ref(py),
+                }),
+                &self.sfu_url,
+                root_key,
+                auth_credential_presentation,
+                admin_passkey,
+                new_admin_passkey,
+                callback,
+            )
+        });
+        check_result(py, result).map(Into::into)
+    }
+
+    /// Moves a call link to the version of `root_key`, from
+    /// `root_key_with_latest_version`. Once this succeeds, store and share
+    /// `root_key` in place of the old key.
+    fn migrate_call_link(
+        &self,
+        py: Python<'_>,
+        auth_credential_presentation: &[u8],
+        root_key: &[u8],
+        admin_passkey: &[u8],
+    ) -> PyResult<CallLinkState> {
+        let root_key = parse_root_key(root_key)?;
+        let result = run_sync(|callback| {
+            call_links::read_call_link(
+                &self.http_client,
+                &self.sfu_url,
+                root_key.clone(),
+                None,
+                auth_credential_presentation,
+                callback,
+            )
+        });
+        let state = check_result(py, result)?;
+        let result = run_sync(|callback| {
+            call_links::migrate_call_link(
+                &self.http_client,
+                &self.sfu_url,
+                root_key,
+                auth_credential_presentation,
+                admin_passkey,
+                &state,
+                callback,
+            )
+        });
+        check_result(py, result).map(Into::into)
+    }
+
+    fn delete_call_link(
+        &self,
+        py: Python<'_>,
+        auth_credential_presentation: &[u8],
+        root_key: &[u8],
+        admin_passkey: &[u8],
+    ) -> PyResult<()> {
+        let root_key = parse_root_key(root_key)?;
+        let result = run_sync(|callback| {
+            call_links::delete_call_link(
+                &self.http_client,
+                &self.sfu_url,
+                root_key,
+                auth_credential_presentation,
+                &call_links::CallLinkDeleteRequest { admin_passkey },
+                callback,
+            )
+        });
+        check_result(py, result).map(|_| ())
+    }
+
+    fn peek_call_link(
+        &self,
+        py: Python<'_>,
+        auth_credential_presentation: &[u8],
+        root_key: &[u8],
+    ) -> PyResult<PeekInfo> {
+        let root_key = parse_root_key(root_key)?;
+        let result = run_sync(|callback| {
+            sfu::peek(
+                &self.http_client,
+                &self.sfu_url,
+                Some(hex::encode(root_key.derive_room_id())),
+                call_links::auth_header_from_auth_credential(auth_credential_presentation),
+                std::sync::Arc::new(CallLinkMemberResolver::from(&root_key)),
+                Some(root_key),
+                callback,
+            )
+        });
+        check_result(py, result).map(Into::into)
+    }
+
+    /// Peeks a group call, where `group_members` is a list of
+    /// `(user_id, member_id)` pairs used to resolve who is in the call.
+    fn peek_group_call(
+        &self,
+        py: Python<'_>,
+        membership_proof: &[u8],
+        group_members: Vec<(Vec<u8>, Vec<u8>)>,
+    ) -> PyResult<PeekInfo> {
+        let auth_header = sfu::auth_header_from_membership_proof(membership_proof)
+            .ok_or_else(|| PyValueError::new_err("invalid membership proof"))?;
+        let group_members: Vec<sfu::GroupMember> = group_members
+            .into_iter()
+            .map(|(user_id, member_id)| sfu::GroupMember { user_id, member_id })
+            .collect();
+        let result = run_sync(|callback| {
+            sfu::peek(
+                &self.http_client,
+                &self.sfu_url,
+                None,
+                auth_header,
+                std::sync::Arc::new(sfu::MemberMap::new(&group_members)),
+                None,
+                callback,
+            )
+        });
+        check_result(py, result).map(Into::into)
+    }
+}
+
+#[pyfunction]
+fn generate_root_key() -> Cow<'static, [u8]> {
+    Cow::Owned(CallLinkRootKey::generate(rand::rngs::OsRng).to_versioned_bytes())
+}
+
+#[pyfunction]
+fn generate_admin_passkey() -> Cow<'static, [u8]> {
+    Cow::Owned(CallLinkRootKey::generate_admin_passkey(rand::rngs::OsRng))
+}
+
+#[pyfunction]
+fn parse_root_key_string(string: &str) -> PyResult<Cow<'static, [u8]>> {
+    CallLinkRootKey::try_from(string)
+        .map(|root_key| Cow::Owned(root_key.to_versioned_bytes()))
+        .map_err(|_| PyValueError::new_err("invalid call link root key"))
+}
+
+/// The same key at the newest version, to pass to
+/// `CallLinkClient.migrate_call_link`.
+#[pyfunction]
+fn root_key_with_latest_version(root_key: &[u8]) -> PyResult<Cow<'static, [u8]>> {
+    Ok(Cow::Owned(
+        parse_root_key(root_key)?
+            .with_version(CallLinkRootKeyVersion::LATEST)
+            .to_versioned_bytes(),
+    ))
+}
+
+#[pyfunction]
+fn format_root_key(root_key: &[u8]) -> PyResult<String> {
+    Ok(parse_root_key(root_key)?.to_formatted_string())
+}
+
+#[pyfunction]
+fn derive_room_id(root_key: &[u8]) -> PyResult<Cow<'static, [u8]>> {
+    Ok(Cow::Owned(parse_root_key(root_key)?.derive_room_id()))
+}
+
+#[pymodule]
+fn ringrtc(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("RequestFailed", m.py().get_type::<RequestFailed>())?;
+    m.add_class::<CallLinkRestrictions>()?;
+    m.add_class::<CallLinkState>()?;
+    m.add_class::<PeekDeviceInfo>()?;
+    m.add_class::<PeekInfo>()?;
+    m.add_class::<CallLinkClient>()?;
+    m.add_function(wrap_pyfunction!(generate_root_key, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_admin_passkey, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_root_key_string, m)?)?;
+    m.add_function(wrap_pyfunction!(root_key_with_latest_version, m)?)?;
+    m.add_function(wrap_pyfunction!(format_root_key, m)?)?;
+    m.add_function(wrap_pyfunction!(derive_room_id, m)?)?;
+    Ok(())
+}
@@ -11,7 +11,7 @@ use std::{
     iter::FromIterator,
     net::{IpAddr, SocketAddr},
     str::FromStr,
-    sync::Arc,
+    sync::{Arc, Mutex, MutexGuard},
 };
 
 use base64::{engine::general_purpose::STANDARD as base64, Engine};
@@ -26,7 +26,10 @@ use crate::{
         http,
     },
     protobuf::group_call::sfu_to_device::{
-        peek_info::PeekDeviceInfo as ProtoPeekDeviceInfo, PeekInfo as ProtoPeekInfo,
+        peek_info::{
+            ParticipantKind as ProtoParticipantKind, PeekDeviceInfo as ProtoPeekDeviceInfo,
+        },
+        PeekInfo as ProtoPeekInfo,
     },
 };
 
@@ -80,6 +83,14 @@ impl PeekInfo {
         self.devices.len() + self.pending_devices.len()
     }
 
+    /// Whether no more devices can join, counting pending devices too.
+    /// False if the maximum is unknown.
+    pub fn is_full(&self) -> bool {
+        self.max_devices.map_or(false, |max_devices| {
+            self.device_count_including_pending_devices() >= max_devices as usize
+        })
+    }
+
     pub fn deobfuscate_proto(
         proto: ProtoPeekInfo,
         obfuscated_resolver: &ObfuscatedResolver,
@@ -124,11 +135,33 @@ impl PeekInfo {
     }
 }
 
+/// How a participant is connected to the call.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ParticipantKind {
+    /// A device running the app. Unknown kinds are treated the same way.
+    #[default]
+    #[serde(other)]
+    AppUser,
+    /// Bridged in by a gateway, such as a phone dialing in over PSTN or SIP.
+    Bridged,
+}
+
+impl From<ProtoParticipantKind> for ParticipantKind {
+    fn from(value: ProtoParticipantKind) -> Self {
+        match value {
+            ProtoParticipantKind::AppUser => Self::AppUser,
+            ProtoParticipantKind::Bridged => Self::Bridged,
+        }
+    }
+}
+
 /// The per-device state observed by "peeking".
 #[derive(Clone, Debug, PartialEq)]
 pub struct PeekDeviceInfo {
     pub demux_id: DemuxId,
     pub user_id: Option<UserId>,
+    pub participant_kind: ParticipantKind,
 }
 
 /// Form of PeekInfo sent over HTTP.
@@ -156,6 +189,8 @@ struct SerializedPeekDeviceInfo {
     opaque_user_id: Option<OpaqueUserId>,
     #[serde(rename = "demuxId")]
     demux_id: u32,
+    #[serde(rename = "participantKind", default)]
+    participant_kind: ParticipantKind,
 }
 
 impl SerializedPeekInfo<'_> {
@@ -172,21 +207,39 @@ impl SerializedPeekInfo<'_> {
             _ => None,
         };
 
+        // Resolve every ID in one batch, in the order they're used below.
+        let opaque_user_ids: Vec<&str> = self
+            .devices
+            .iter()
+            .chain(&self.pending_clients)
+            .filter_map(|device| device.opaque_user_id.as_deref())
+            .chain(self.creator.as_deref())
+            .collect();
+        let mut user_ids = member_resolver.resolve_bulk(&opaque_user_ids).into_iter();
+        let mut deobfuscate_device = |device: SerializedPeekDeviceInfo| PeekDeviceInfo {
+            demux_id: device.demux_id,
+            user_id: device
+                .opaque_user_id
+                .and_then(|_| user_ids.next().flatten()),
+            participant_kind: device.participant_kind,
+        };
+
+        let devices = self
+            .devices
+            .into_iter()
+            .map(&mut deobfuscate_device)
+            .collect();
+        let pending_devices = self
+            .pending_clients
+            .into_iter()
+            .map(&mut deobfuscate_device)
+            .collect();
+        let creator = self.creator.and_then(|_| user_ids.next().flatten());
+
         PeekInfo {
-            devices: self
-                .devices
-                .into_iter()
-                .map(|device| device.deobfuscate(member_resolver))
-                .collect(),
-            pending_devices: self
-                .pending_clients
-                .into_iter()
-                .map(|device| device.deobfuscate(member_resolver))
-                .collect(),
-            creator: self
-                .creator
-                .as_ref()
-                .and_then(|opaque_user_id| member_resolver.resolve(opaque_user_id)),
+            devices,
+            pending_devices,
+            creator,
             era_id: self.era_id,
             max_devices: self.max_devices,
             call_link_state: state,
@@ -194,25 +247,15 @@ impl SerializedPeekInfo<'_> {
     }
 }
 
-impl SerializedPeekDeviceInfo {
-    fn deobfuscate(self, member_resolver: &dyn MemberResolver) -> PeekDeviceInfo {
-        PeekDeviceInfo {
-            demux_id: self.demux_id,
-            user_id: self
-                .opaque_user_id
-                .and_then(|user_id| member_resolver.resolve(&user_id)),
-        }
-    }
-}
-
 impl TryFrom<ProtoPeekDeviceInfo> for SerializedPeekDeviceInfo {
     type Error = String;
-    fn try_from(
-        ProtoPeekDeviceInfo {
+    fn try_from(proto: ProtoPeekDeviceInfo) -> Result<Self, Self::Error> {
+        let participant_kind = proto.participant_kind().into();
+        let ProtoPeekDeviceInfo {
             demux_id,
             opaque_user_id,
-        }: ProtoPeekDeviceInfo,
-    ) -> Result<Self, Self::Error> {
+            participant_kind: _,
+        } = proto;
         if demux_id.is_none() {
             return Err("Missing required fields in PeekDeviceInfo".to_string());
         }
@@ -220,6 +263,7 @@ impl TryFrom<ProtoPeekDeviceInfo> for SerializedPeekDeviceInfo {
         Ok(Self {
             opaque_user_id: opaque_user_id.map(OpaqueUserId::from),
             demux_id: demux_id.unwrap(),
+            participant_kind,
         })
     }
 }
@@ -364,11 +408,32 @@ pub type DemuxId = u32;
 
 pub trait MemberResolver {
     fn resolve(&self, opaque_user_id: &str) -> Option<UserId>;
+
+    /// Resolves many opaque user IDs at once, returning the results in the same order.
+    ///
+    /// Implementations can override this when resolving a batch is cheaper than resolving
+    /// each ID on its own.
+    fn resolve_bulk(&self, opaque_user_ids: &[&str]) -> Vec<Option<UserId>> {
+        opaque_user_ids
+            .iter()
+            .map(|opaque_user_id| self.resolve(opaque_user_id))
+            .collect()
+    }
 }
 
+// Bounds the resolution cache for calls with a lot of churn. Going over simply starts over.
+const MAX_RESOLVED_USER_IDS: usize = 1024;
+
+/// Resolves the obfuscated IDs in peek info, caching the results so that frequent peeks
+/// of a large call don't repeat the same work on every poll.
+///
+/// The cache is invalidated whenever the member resolver changes (e.g. because the group
+/// membership changed).
 pub struct ObfuscatedResolver {
     member_resolver: Arc<dyn MemberResolver + Send + Sync>,
     call_link_root_key: Option<CallLinkRootKey>,
+    // Unresolvable IDs are cached too, since they're just as costly to look up.
+    resolved_user_ids: Mutex<HashMap<OpaqueUserId, Option<UserId>>>,
 }
 
 impl ObfuscatedResolver {
@@ -379,11 +444,59 @@ impl ObfuscatedResolver {
         Self {
             member_resolver,
             call_link_root_key,
+            resolved_user_ids: Mutex::new(HashMap::new()),
         }
     }
 
     pub fn resolve_user_id(&self, opaque_user_id: &str) -> Option<UserId> {
-        self.member_resolver.resolve(opaque_user_id)
+        self.resolve_user_ids(&[opaque_user_id])
+            .pop()
+            .expect("one result per ID")
+    }
+
+    /// Resolves many opaque user IDs at once, only asking the member resolver about
+    /// the ones that haven't been resolved before.
+    pub fn resolve_user_ids(&self, opaque_user_ids: &[&str]) -> Vec<Option<UserId>> {
+        let mut resolved_user_ids = self.lock_resolved_user_ids();
+
+        let mut unresolved: Vec<&str> = opaque_user_ids
+            .iter()
+            .copied()
+            .filter(|opaque_user_id| !resolved_user_ids.contains_key(*opaque_user_id))
+            .collect();
+        unresolved.sort_unstable();
+        unresolved.dedup();
+
+        if !unresolved.is_empty() {
+            if resolved_user_ids.len() + unresolved.len() > MAX_RESOLVED_USER_IDS {
+                resolved_user_ids.clear();
+            }
+            let user_ids = self.member_resolver.resolve_bulk(&unresolved);
+            resolved_user_ids.extend(unresolved.into_iter().map(OpaqueUserId::from).zip(user_ids));
+        }
+
+        opaque_user_ids
+            .iter()
+            .map(|opaque_user_id| resolved_user_ids.get(*opaque_user_id).cloned().flatten())
+            .collect()
+    }
+
+    /// Forgets everything resolved so far.
+    pub fn invalidate_cache(&self) {
+        self.lock_resolved_user_ids().clear();
+    }
+
+    /// Locks the cache, emptying it if a panic happened while it was locked.
+    fn lock_resolved_user_ids(&self) -> MutexGuard<'_, HashMap<OpaqueUserId, Option<UserId>>> {
+        self.resolved_user_ids
+            .lock()
+            .unwrap_or_else(|poison_error| {
+                error!("resetting ObfuscatedResolver cache after panic");
+                self.resolved_user_ids.clear_poison();
+                let mut resolved_user_ids = poison_error.into_inner();
+                resolved_user_ids.clear();
+                resolved_user_ids
+            })
     }
 
     pub fn resolve_call_link_name(&self, opaque_call_link_name: &str) -> Option<String> {
@@ -402,6 +515,7 @@ impl ObfuscatedResolver {
 
     pub fn set_member_resolver(&mut self, member_resolver: Arc<dyn MemberResolver + Send + Sync>) {
         self.member_resolver = member_resolver;
+        self.invalidate_cache();
     }
 
     pub fn get_call_link_root_key(&self) -> Option<&CallLinkRootKey> {
@@ -413,6 +527,10 @@ impl MemberResolver for ObfuscatedResolver {
     fn resolve(&self, user_id: &str) -> Option<UserId> {
         self.resolve_user_id(user_id)
     }
+
+    fn resolve_bulk(&self, opaque_user_ids: &[&str]) -> Vec<Option<UserId>> {
+        self.resolve_user_ids(opaque_user_ids)
+    }
 }
 
 /// Associates a group member's UserId with their GroupMemberId.
@@ -450,6 +568,23 @@ impl MemberResolver for MemberMap {
             }
         })
     }
+
+    fn resolve_bulk(&self, opaque_user_ids: &[&str]) -> Vec<Option<UserId>> {
+        // Index the members once rather than scanning them for every ID.
+        let user_id_by_opaque_user_id: HashMap<&str, &UserId> = self
+            .members
+            .iter()
+            .map(|entry| (entry.opaque_user_id.as_str(), &entry.user_id))
+            .collect();
+        opaque_user_ids
+            .iter()
+            .map(|opaque_user_id| {
+                user_id_by_opaque_user_id
+                    .get(opaque_user_id)
+                    .map(|user_id| (*user_id).clone())
+            })
+            .collect()
+    }
 }
 
 /// Associates a group member's OpaqueUserId with their UUID.
@@ -664,22 +799,188 @@ pub fn join(
     );
 }
 
-#[cfg(any(target_os = "ios", feature = "java", feature = "check-all"))]
+#[cfg(any(
+    target_os = "ios",
+    feature = "java",
+    feature = "headless",
+    feature = "check-all"
+))]
 pub mod ios {
     use std::{
+        collections::HashMap,
         ffi::{c_char, CStr},
-        sync::Arc,
+        sync::{
+            atomic::{AtomicU32, Ordering},
+            Arc, Mutex,
+        },
     };
 
+    use lazy_static::lazy_static;
     use libc::{c_void, size_t};
 
     use crate::lite::{
         call_links::{self, CallLinkMemberResolver, CallLinkRootKey},
         ffi::ios::{rtc_Bytes, rtc_OptionalU16, rtc_OptionalU32, rtc_String, FromOrDefault},
         http,
-        sfu::{self, Delegate, GroupMember, PeekInfo, PeekResult},
+        sfu::{self, Delegate, GroupMember, PeekInfo, PeekResult, PeekResultCallback},
     };
 
+    /// Identifies a request started by one of the `_async` functions, such as
+    /// `rtc_sfu_peek_async`, so that it can be cancelled with `rtc_sfu_cancel`.
+    /// Its delegate gets it as the request ID. Never 0, which means the request
+    /// couldn't be started.
+    pub type rtc_sfu_RequestToken = u32;
+
+    static NEXT_REQUEST_TOKEN: AtomicU32 = AtomicU32::new(1);
+
+    type CancelRequest = Box<dyn FnOnce() + Send>;
+
+    lazy_static! {
+        // How to cancel each request started with a token, until it completes.
+        static ref CANCELLABLE_REQUESTS: Mutex<HashMap<rtc_sfu_RequestToken, CancelRequest>> =
+            Mutex::new(HashMap::new());
+    }
+
+    /// Registers a request before it is started, returning its token and the callback to pass
+    /// its result to.
+    ///
+    /// `complete` is called exactly once: with the result, or with
+    /// [`http::ResponseStatus::REQUEST_CANCELLED`] if the request is cancelled first, in which
+    /// case the result is ignored when it arrives.
+    pub(crate) fn register_cancellable_request<T: 'static>(
+        complete: impl FnOnce(rtc_sfu_RequestToken, Result<T, http::ResponseStatus>) + Send + 'static,
+    ) -> (
+        rtc_sfu_RequestToken,
+        Box<dyn FnOnce(Result<T, http::ResponseStatus>) + Send>,
+    ) {
+        let token = loop {
+            // Skip 0 when wrapping around.
+            let token = NEXT_REQUEST_TOKEN.fetch_add(1, Ordering::Relaxed);
+            if token != 0 {
+                break token;
+            }
+        };
+        let complete = Arc::new(Mutex::new(Some(complete)));
+        let complete_on_cancel = complete.clone();
+        CANCELLABLE_REQUESTS
+            .lock()
+            .expect("lock cancellable requests")
+            .insert(
+                token,
+                Box::new(move || {
+                    let complete = complete_on_cancel.lock().expect("lock completion").take();
+                    if let Some(complete) = complete {
+                        complete(token, Err(http::ResponseStatus::REQUEST_CANCELLED));
+                    }
+                }),
+            );
+        let on_result = Box::new(move |result: Result<T, http::ResponseStatus>| {
+            CANCELLABLE_REQUESTS
+                .lock()
+                .expect("lock cancellable requests")
+                .remove(&token);
+            let complete = complete.lock().expect("lock completion").take();
+            if let Some(complete) = complete {
+                complete(token, result);
+            }
+        });
+        (token, on_result)
+    }
+
+    /// Forgets a request that failed to start, dropping its delegate without calling it.
+    pub(crate) fn unregister_cancellable_request(token: rtc_sfu_RequestToken) {
+        CANCELLABLE_REQUESTS
+            .lock()
+            .expect("lock cancellable requests")
+            .remove(&token);
+    }
+
+    /// Cancels a request started by one of the `_async` functions. If it hasn't completed yet,
+    /// its delegate is called right away with the status code
+    /// `http::ResponseStatus::REQUEST_CANCELLED` (603), and the response is ignored when it
+    /// arrives. Returns whether the request was still pending.
+    ///
+    /// May be called from any thread, including from within the delegate.
+    #[no_mangle]
+    pub extern "C" fn rtc_sfu_cancel(token: rtc_sfu_RequestToken) -> bool {
+        info!("rtc_sfu_cancel(): token: {}", token);
+
+        // Don't hold the lock while calling the delegate.
+        let cancel = CANCELLABLE_REQUESTS
+            .lock()
+            .expect("lock cancellable requests")
+            .remove(&token);
+        match cancel {
+            Some(cancel) => {
+                cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Starts a peek, returning false if the arguments were invalid.
+    fn start_peek(
+        http_client: &http::ios::Client,
+        request: &rtc_sfu_PeekRequest,
+        result_callback: PeekResultCallback,
+    ) -> bool {
+        let Some(sfu_url) = request.sfu_url.to_string() else {
+            error!("Invalid SFU URL");
+            return false;
+        };
+        let Some(auth_header) =
+            sfu::auth_header_from_membership_proof(request.membership_proof.as_slice())
+        else {
+            error!("Invalid membership proof");
+            return false;
+        };
+        let group_members = request.group_members.to_vec();
+        let opaque_user_id_mappings = sfu::MemberMap::new(&group_members);
+        super::peek(
+            http_client,
+            &sfu_url,
+            None,
+            auth_header,
+            Arc::new(opaque_user_id_mappings),
+            None,
+            result_callback,
+        );
+        true
+    }
+
+    /// Starts a call link peek, returning false if the arguments were invalid.
+    ///
+    /// # Safety
+    ///
+    /// `sfu_url` must be a valid, non-null C string.
+    unsafe fn start_peek_call_link(
+        http_client: &http::ios::Client,
+        sfu_url: *const c_char,
+        auth_credential_presentation: rtc_Bytes,
+        link_root_key: rtc_Bytes,
+        result_callback: PeekResultCallback,
+    ) -> bool {
+        let Ok(sfu_url) = CStr::from_ptr(sfu_url).to_str() else {
+            error!("invalid sfu_url");
+            return false;
+        };
+        let Ok(link_root_key) = CallLinkRootKey::try_from(link_root_key.as_slice()) else {
+            error!("invalid link_root_key");
+            return false;
+        };
+        super::peek(
+            http_client,
+            sfu_url,
+            Some(hex::encode(link_root_key.derive_room_id())),
+            call_links::auth_header_from_auth_credential(auth_credential_presentation.as_slice()),
+            Arc::new(CallLinkMemberResolver::from(&link_root_key)),
+            Some(link_root_key),
+            result_callback,
+        );
+        true
+    }
+
     /// # Safety
     ///
     /// http_client_ptr must come from rtc_http_Client_create and not already be destroyed
@@ -693,34 +994,45 @@ pub mod ios {
         info!("rtc_sfu_peek():");
 
         if let Some(http_client) = http_client.as_ref() {
-            if let Some(sfu_url) = request.sfu_url.to_string() {
-                if let Some(auth_header) =
-                    sfu::auth_header_from_membership_proof(request.membership_proof.as_slice())
-                {
-                    let group_members = request.group_members.to_vec();
-                    let opaque_user_id_mappings = sfu::MemberMap::new(&group_members);
-                    super::peek(
-                        http_client,
-                        &sfu_url,
-                        None,
-                        auth_header,
-                        Arc::new(opaque_user_id_mappings),
-                        None,
-                        Box::new(move |peek_result| {
-                            delegate.handle_peek_result(request_id, peek_result)
-                        }),
-                    );
-                } else {
-                    error!("Invalid membership proof");
-                }
-            } else {
-                error!("Invalid SFU URL");
-            }
+            start_peek(
+                http_client,
+                &request,
+                Box::new(move |peek_result| delegate.handle_peek_result(request_id, peek_result)),
+            );
         } else {
             error!("null http_client passed into rtc_sfu_peek");
         }
     }
 
+    /// Like `rtc_sfu_peek`, but returns a token to identify the request instead of taking a
+    /// request ID. See `rtc_sfu_RequestToken`.
+    ///
+    /// # Safety
+    ///
+    /// http_client_ptr must come from rtc_http_Client_create and not already be destroyed
+    #[no_mangle]
+    pub unsafe extern "C" fn rtc_sfu_peek_async(
+        http_client: *const http::ios::Client,
+        request: rtc_sfu_PeekRequest,
+        delegate: rtc_sfu_Delegate,
+    ) -> rtc_sfu_RequestToken {
+        info!("rtc_sfu_peek_async():");
+
+        let Some(http_client) = http_client.as_ref() else {
+            error!("null http_client passed into rtc_sfu_peek_async");
+            return 0;
+        };
+        let (token, result_callback) = register_cancellable_request(move |token, peek_result| {
+            delegate.handle_peek_result(token, peek_result)
+        });
+        if start_peek(http_client, &request, result_callback) {
+            token
+        } else {
+            unregister_cancellable_request(token);
+            0
+        }
+    }
+
     /// # Safety
     ///
     /// - `http_client` must come from `rtc_http_Client_create` and not already be destroyed
@@ -737,32 +1049,56 @@ pub mod ios {
         info!("rtc_sfu_peekCallLink():");
 
         if let Some(http_client) = http_client.as_ref() {
-            if let Ok(sfu_url) = CStr::from_ptr(sfu_url).to_str() {
-                if let Ok(link_root_key) = CallLinkRootKey::try_from(link_root_key.as_slice()) {
-                    super::peek(
-                        http_client,
-                        sfu_url,
-                        Some(hex::encode(link_root_key.derive_room_id())),
-                        call_links::auth_header_from_auth_credential(
-                            auth_credential_presentation.as_slice(),
-                        ),
-                        Arc::new(CallLinkMemberResolver::from(&link_root_key)),
-                        Some(link_root_key),
-                        Box::new(move |peek_result| {
-                            delegate.handle_peek_result(request_id, peek_result)
-                        }),
-                    );
-                } else {
-                    error!("invalid link_root_key");
-                }
-            } else {
-                error!("invalid sfu_url");
-            }
+            start_peek_call_link(
+                http_client,
+                sfu_url,
+                auth_credential_presentation,
+                link_root_key,
+                Box::new(move |peek_result| delegate.handle_peek_result(request_id, peek_result)),
+            );
         } else {
             error!("null http_client passed into rtc_sfu_peekCallLink");
         }
     }
 
+    /// Like `rtc_sfu_peekCallLink`, but returns a token to identify the request instead of
+    /// taking a request ID. See `rtc_sfu_RequestToken`.
+    ///
+    /// # Safety
+    ///
+    /// - `http_client` must come from `rtc_http_Client_create` and not already be destroyed
+    /// - `sfu_url` must be a valid, non-null C string.
+    #[no_mangle]
+    pub unsafe extern "C" fn rtc_sfu_peekCallLink_async(
+        http_client: *const http::ios::Client,
+        sfu_url: *const c_char,
+        auth_credential_presentation: rtc_Bytes,
+        link_root_key: rtc_Bytes,
+        delegate: rtc_sfu_Delegate,
+    ) -> rtc_sfu_RequestToken {
+        info!("rtc_sfu_peekCallLink_async():");
+
+        let Some(http_client) = http_client.as_ref() else {
+            error!("null http_client passed into rtc_sfu_peekCallLink_async");
+            return 0;
+        };
+        let (token, result_callback) = register_cancellable_request(move |token, peek_result| {
+            delegate.handle_peek_result(token, peek_result)
+        });
+        if start_peek_call_link(
+            http_client,
+            sfu_url,
+            auth_credential_presentation,
+            link_root_key,
+            result_callback,
+        ) {
+            token
+        } else {
+            unregister_cancellable_request(token);
+            0
+        }
+    }
+
     #[repr(C)]
     #[derive(Debug)]
     pub struct rtc_sfu_PeekRequest<'a> {
@@ -787,7 +1123,7 @@ pub mod ios {
             unsafe { std::slice::from_raw_parts(self.ptr, self.count) }
         }
 
-        fn to_vec(&self) -> Vec<GroupMember> {
+        pub(crate) fn to_vec(&self) -> Vec<GroupMember> {
             self.as_slice()
                 .iter()
                 .map(rtc_sfu_GroupMember::to_group_member)
@@ -802,6 +1138,22 @@ pub mod ios {
         pub member_id: rtc_Bytes<'a>,
     }
 
+    // The layouts the Swift code relies on; see lite::ffi::ios.
+    mod layout {
+        use std::mem::{offset_of, size_of};
+
+        use super::*;
+
+        const PTR: usize = size_of::<usize>();
+
+        const_assert_eq!(size_of::<rtc_sfu_PeekRequest>(), 6 * PTR);
+        const_assert_eq!(offset_of!(rtc_sfu_PeekRequest, membership_proof), 2 * PTR);
+        const_assert_eq!(offset_of!(rtc_sfu_PeekRequest, group_members), 4 * PTR);
+        const_assert_eq!(size_of::<rtc_sfu_GroupMembers>(), 2 * PTR);
+        const_assert_eq!(size_of::<rtc_sfu_GroupMember>(), 4 * PTR);
+        const_assert_eq!(offset_of!(rtc_sfu_GroupMember, member_id), 2 * PTR);
+    }
+
     impl rtc_sfu_GroupMember<'_> {
         fn to_group_member(&self) -> GroupMember {
             GroupMember {
@@ -897,6 +1249,42 @@ pub mod ios {
             }
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use std::sync::mpsc;
+
+        use super::*;
+
+        #[test]
+        fn cancellable_request_completes_once() {
+            let (sender, receiver) = mpsc::channel();
+            let (token, on_result) =
+                register_cancellable_request(move |token, result: Result<u8, _>| {
+                    sender.send((token, result)).unwrap();
+                });
+            assert_ne!(0, token);
+            assert!(rtc_sfu_cancel(token));
+            assert!(!rtc_sfu_cancel(token));
+            on_result(Ok(1));
+            assert_eq!(
+                vec![(token, Err(http::ResponseStatus::REQUEST_CANCELLED))],
+                receiver.try_iter().collect::<Vec<_>>()
+            );
+
+            let (sender, receiver) = mpsc::channel();
+            let (token, on_result) =
+                register_cancellable_request(move |token, result: Result<u8, _>| {
+                    sender.send((token, result)).unwrap();
+                });
+            on_result(Ok(1));
+            assert!(!rtc_sfu_cancel(token));
+            assert_eq!(
+                vec![(token, Ok(1))],
+                receiver.try_iter().collect::<Vec<_>>()
+            );
+        }
+    }
 }
 
 #[cfg(test)]
@@ -928,10 +1316,12 @@ mod tests {
                 SerializedPeekDeviceInfo {
                     opaque_user_id: Some("u1".to_string()),
                     demux_id: 0x11111110,
+                    participant_kind: ParticipantKind::AppUser,
                 },
                 SerializedPeekDeviceInfo {
                     opaque_user_id: Some("u2".to_string()),
                     demux_id: 0x22222220,
+                    participant_kind: ParticipantKind::AppUser,
                 },
             ],
             pending_clients: vec![],
@@ -973,10 +1363,12 @@ mod tests {
                 SerializedPeekDeviceInfo {
                     opaque_user_id: Some("u1".to_string()),
                     demux_id: 0x11111110,
+                    participant_kind: ParticipantKind::AppUser,
                 },
                 SerializedPeekDeviceInfo {
                     opaque_user_id: Some("u2".to_string()),
                     demux_id: 0x22222220,
+                    participant_kind: ParticipantKind::AppUser,
                 },
             ],
             creator: None,
@@ -1029,20 +1421,24 @@ mod tests {
                 ProtoPeekDeviceInfo {
                     opaque_user_id: Some("u1".to_string()),
                     demux_id: Some(0x11111110),
+                    participant_kind: None,
                 },
                 ProtoPeekDeviceInfo {
                     opaque_user_id: Some("u2".to_string()),
                     demux_id: Some(0x22222220),
+                    participant_kind: Some(ProtoParticipantKind::Bridged as i32),
                 },
             ],
             pending_devices: vec![
                 ProtoPeekDeviceInfo {
                     opaque_user_id: Some("u3".to_string()),
                     demux_id: Some(0x33333330),
+                    participant_kind: None,
                 },
                 ProtoPeekDeviceInfo {
                     opaque_user_id: Some("u4".to_string()),
                     demux_id: Some(0x44444440),
+                    participant_kind: None,
                 },
             ],
             creator: Some("u1".to_string()),
@@ -1057,20 +1453,24 @@ mod tests {
                     PeekDeviceInfo {
                         demux_id: 0x11111110,
                         user_id: Some(vec![1u8; 4]),
+                        participant_kind: ParticipantKind::AppUser,
                     },
                     PeekDeviceInfo {
                         demux_id: 0x22222220,
                         user_id: Some(vec![2u8; 4]),
+                        participant_kind: ParticipantKind::Bridged,
                     },
                 ],
                 pending_devices: vec![
                     PeekDeviceInfo {
                         demux_id: 0x33333330,
                         user_id: Some(vec![3u8; 4]),
+                        participant_kind: ParticipantKind::AppUser,
                     },
                     PeekDeviceInfo {
                         demux_id: 0x44444440,
                         user_id: Some(vec![4u8; 4]),
+                        participant_kind: ParticipantKind::AppUser,
                     },
                 ],
                 creator: Some(vec![1u8; 4]),
@@ -1093,24 +1493,29 @@ mod tests {
                 ProtoPeekDeviceInfo {
                     opaque_user_id: Some("u1".to_string()),
                     demux_id: Some(0x11111110),
+                    participant_kind: None,
                 },
                 ProtoPeekDeviceInfo {
                     opaque_user_id: Some("u2".to_string()),
                     demux_id: Some(0x22222220),
+                    participant_kind: None,
                 },
                 ProtoPeekDeviceInfo {
                     opaque_user_id: Some("u5".to_string()),
                     demux_id: Some(0x55555550),
+                    participant_kind: None,
                 },
             ],
             pending_devices: vec![
                 ProtoPeekDeviceInfo {
                     opaque_user_id: Some("u3".to_string()),
                     demux_id: Some(0x33333330),
+                    participant_kind: None,
                 },
                 ProtoPeekDeviceInfo {
                     opaque_user_id: Some("u4".to_string()),
                     demux_id: Some(0x44444440),
+                    participant_kind: None,
                 },
             ],
             creator: Some("u1".to_string()),
@@ -1125,24 +1530,29 @@ mod tests {
                     PeekDeviceInfo {
                         demux_id: 0x11111110,
                         user_id: Some(vec![1u8; 4]),
+                        participant_kind: ParticipantKind::AppUser,
                     },
                     PeekDeviceInfo {
                         demux_id: 0x22222220,
                         user_id: Some(vec![2u8; 4]),
+                        participant_kind: ParticipantKind::AppUser,
                     },
                     PeekDeviceInfo {
                         demux_id: 0x55555550,
                         user_id: Some(vec![5u8; 4]),
+                        participant_kind: ParticipantKind::AppUser,
                     },
                 ],
                 pending_devices: vec![
                     PeekDeviceInfo {
                         demux_id: 0x33333330,
                         user_id: Some(vec![3u8; 4]),
+                        participant_kind: ParticipantKind::AppUser,
                     },
                     PeekDeviceInfo {
                         demux_id: 0x44444440,
                         user_id: Some(vec![4u8; 4]),
+                        participant_kind: ParticipantKind::AppUser,
                     },
                 ],
                 creator: Some(vec![1u8; 4]),
@@ -1153,6 +1563,132 @@ mod tests {
         );
     }
 
+    #[test]
+    fn obfuscated_resolver_caches_resolved_ids() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // Counts how many IDs were actually looked up and in how many batches.
+        #[derive(Default)]
+        struct CountingResolver {
+            members: MemberMap,
+            resolved_count: AtomicUsize,
+            bulk_count: AtomicUsize,
+        }
+
+        impl MemberResolver for CountingResolver {
+            fn resolve(&self, opaque_user_id: &str) -> Option<UserId> {
+                self.resolved_count.fetch_add(1, Ordering::Relaxed);
+                self.members.resolve(opaque_user_id)
+            }
+
+            fn resolve_bulk(&self, opaque_user_ids: &[&str]) -> Vec<Option<UserId>> {
+                self.bulk_count.fetch_add(1, Ordering::Relaxed);
+                self.resolved_count
+                    .fetch_add(opaque_user_ids.len(), Ordering::Relaxed);
+                self.members.resolve_bulk(opaque_user_ids)
+            }
+        }
+
+        let member_resolver = Arc::new(CountingResolver {
+            members: MemberMap {
+                members: vec![
+                    OpaqueUserIdMapping {
+                        user_id: vec![1u8; 4],
+                        opaque_user_id: "u1".to_string(),
+                    },
+                    OpaqueUserIdMapping {
+                        user_id: vec![2u8; 4],
+                        opaque_user_id: "u2".to_string(),
+                    },
+                ],
+            },
+            ..Default::default()
+        });
+        let mut obfuscated_resolver = ObfuscatedResolver::new(member_resolver.clone(), None);
+
+        let proto_peek = || ProtoPeekInfo {
+            era_id: Some("paleozoic".to_string()),
+            max_devices: Some(16),
+            devices: vec![
+                ProtoPeekDeviceInfo {
+                    opaque_user_id: Some("u1".to_string()),
+                    demux_id: Some(0x11111110),
+                    participant_kind: None,
+                },
+                ProtoPeekDeviceInfo {
+                    opaque_user_id: Some("u1".to_string()),
+                    demux_id: Some(0x22222220),
+                    participant_kind: None,
+                },
+                ProtoPeekDeviceInfo {
+                    opaque_user_id: Some("unknown".to_string()),
+                    demux_id: Some(0x33333330),
+                    participant_kind: None,
+                },
+            ],
+            pending_devices: vec![ProtoPeekDeviceInfo {
+                opaque_user_id: Some("u2".to_string()),
+                demux_id: Some(0x44444440),
+                participant_kind: None,
+            }],
+            creator: Some("u2".to_string()),
+            call_link_state: None,
+        };
+        let expected_peek_info = PeekInfo {
+            devices: vec![
+                PeekDeviceInfo {
+                    demux_id: 0x11111110,
+                    user_id: Some(vec![1u8; 4]),
+                    participant_kind: ParticipantKind::AppUser,
+                },
+                PeekDeviceInfo {
+                    demux_id: 0x22222220,
+                    user_id: Some(vec![1u8; 4]),
+                    participant_kind: ParticipantKind::AppUser,
+                },
+                PeekDeviceInfo {
+                    demux_id: 0x33333330,
+                    user_id: None,
+                    participant_kind: ParticipantKind::AppUser,
+                },
+            ],
+            pending_devices: vec![PeekDeviceInfo {
+                demux_id: 0x44444440,
+                user_id: Some(vec![2u8; 4]),
+                participant_kind: ParticipantKind::AppUser,
+            }],
+            creator: Some(vec![2u8; 4]),
+            era_id: Some("paleozoic".to_string()),
+            max_devices: Some(16),
+            call_link_state: None,
+        };
+
+        // Each distinct ID is looked up once, in a single batch.
+        assert_eq!(
+            PeekInfo::deobfuscate_proto(proto_peek(), &obfuscated_resolver),
+            Ok(expected_peek_info.clone())
+        );
+        assert_eq!(1, member_resolver.bulk_count.load(Ordering::Relaxed));
+        assert_eq!(3, member_resolver.resolved_count.load(Ordering::Relaxed));
+
+        // Later peeks are resolved from the cache, including the unknown ID.
+        assert_eq!(
+            PeekInfo::deobfuscate_proto(proto_peek(), &obfuscated_resolver),
+            Ok(expected_peek_info.clone())
+        );
+        assert_eq!(1, member_resolver.bulk_count.load(Ordering::Relaxed));
+        assert_eq!(3, member_resolver.resolved_count.load(Ordering::Relaxed));
+
+        // Changing the member resolver invalidates the cache.
+        obfuscated_resolver.set_member_resolver(member_resolver.clone());
+        assert_eq!(
+            PeekInfo::deobfuscate_proto(proto_peek(), &obfuscated_resolver),
+            Ok(expected_peek_info)
+        );
+        assert_eq!(2, member_resolver.bulk_count.load(Ordering::Relaxed));
+        assert_eq!(6, member_resolver.resolved_count.load(Ordering::Relaxed));
+    }
+
     #[allow(clippy::unusual_byte_groupings)]
     #[test]
     fn endpoint_ids_to_user_ids_by_zk_encryption() {
@@ -1184,10 +1720,12 @@ mod tests {
                     SerializedPeekDeviceInfo {
                         opaque_user_id: Some(encrypt(uuid_1, &secret_params)),
                         demux_id: 0x11111110,
+                        participant_kind: ParticipantKind::AppUser,
                     },
                     SerializedPeekDeviceInfo {
                         opaque_user_id: Some(encrypt(uuid_2, &secret_params)),
                         demux_id: 0x22222220,
+                        participant_kind: ParticipantKind::AppUser,
                     },
                 ],
                 pending_clients: vec![],
@@ -1213,4 +1751,30 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn peek_info_with_participant_kinds() {
+        let json = r#"{
+            "conferenceId": "paleozoic",
+            "maxDevices": 16,
+            "participants": [
+                { "opaqueUserId": "u1", "demuxId": 16 },
+                { "opaqueUserId": "u2", "demuxId": 32, "participantKind": "bridged" },
+                { "opaqueUserId": "u3", "demuxId": 48, "participantKind": "somethingNew" }
+            ]
+        }"#;
+        let deserialized: SerializedPeekInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            deserialized
+                .devices
+                .iter()
+                .map(|device| device.participant_kind)
+                .collect::<Vec<_>>(),
+            vec![
+                ParticipantKind::AppUser,
+                ParticipantKind::Bridged,
+                ParticipantKind::AppUser,
+            ]
+        );
+    }
 }
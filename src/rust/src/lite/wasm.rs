@@ -0,0 +1,331 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Bindings for using the `lite` module from a web client, built for
+//! `wasm32-unknown-unknown` with wasm-bindgen.
+//!
+//! Requests are made with the browser's `fetch`, and each API returns a
+//! Promise. Failed requests reject with the status code, using the same
+//! artificial codes as [`http::ResponseStatus`].
+
+use std::{sync::Arc, time::SystemTime};
+
+use futures_channel::oneshot;
+use js_sys::{Array, Object, Reflect, Uint8Array};
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::JsFuture;
+
+use crate::lite::{
+    call_links::{self, CallLinkMemberResolver, CallLinkRootKey, CallLinkRootKeyVersion},
+    http, sfu,
+};
+
+/// An impl of [`http::Client`] that uses the browser's `fetch`.
+pub struct FetchClient;
+
+impl http::Client for FetchClient {
+    fn send_request(&self, request: http::Request, callback: http::ResponseCallback) {
+        wasm_bindgen_futures::spawn_local(async move {
+            let response = match fetch(request).await {
+                Ok(response) => Some(response),
+                Err(err) => {
+                    error!("http:FetchClient: request failed: {:?}", err);
+                    None
+                }
+            };
+            callback(response);
+        });
+    }
+}
+
+async fn fetch(request: http::Request) -> Result<http::Response, JsValue> {
+    let init = web_sys::RequestInit::new();
+    init.set_method(match request.method {
+        http::Method::Get => "GET",
+        http::Method::Put => "PUT",
+        http::Method::Post => "POST",
+        http::Method::Delete => "DELETE",
+    });
+    let headers = web_sys::Headers::new()?;
+    for (name, value) in &request.headers {
+        headers.set(name, value)?;
+    }
+    init.set_headers(&headers);
+    if let Some(body) = &request.body {
+        init.set_body(&Uint8Array::from(body.as_slice()));
+    }
+    let js_request = web_sys::Request::new_with_str_and_init(&request.url, &init)?;
+
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("no window"))?;
+    let response: web_sys::Response = JsFuture::from(window.fetch_with_request(&js_request))
+        .await?
+        .dyn_into()?;
+    let body = JsFuture::from(response.array_buffer()?).await?;
+    Ok(http::Response {
+        status: response.status().into(),
+        body: Uint8Array::new(&body).to_vec(),
+    })
+}
+
+/// Waits for a result passed to a callback, such as the ones taken by
+/// [`sfu::peek`].
+async fn await_callback<T: Send + 'static>(
+    start: impl FnOnce(Box<dyn FnOnce(T) + Send>),
+) -> Result<T, JsValue> {
+    let (sender, receiver) = oneshot::channel();
+    start(Box::new(move |result| {
+        let _ = sender.send(result);
+    }));
+    receiver
+        .await
+        .map_err(|_| JsValue::from(http::ResponseStatus::REQUEST_FAILED.code))
+}
+
+fn set(object: &Object, key: &str, value: impl Into<JsValue>) {
+    Reflect::set(object, &JsValue::from_str(key), &value.into()).expect("object is extensible");
+}
+
+fn bytes_or_null(bytes: Option<Vec<u8>>) -> JsValue {
+    bytes.map_or(JsValue::NULL, |bytes| {
+        Uint8Array::from(bytes.as_slice()).into()
+    })
+}
+
+fn bytes_property(object: &JsValue, key: &str) -> Result<Vec<u8>, JsValue> {
+    let value = Reflect::get(object, &JsValue::from_str(key))?;
+    Ok(value.dyn_into::<Uint8Array>()?.to_vec())
+}
+
+fn call_link_state_to_js(state: call_links::CallLinkState) -> Object {
+    let object = Object::new();
+    set(&object, "name", state.name);
+    set(
+        &object,
+        "restrictions",
+        match state.restrictions {
+            call_links::CallLinkRestrictions::None => "none",
+            call_links::CallLinkRestrictions::AdminApproval => "adminApproval",
+            call_links::CallLinkRestrictions::Unknown => "unknown",
+        },
+    );
+    set(&object, "revoked", state.revoked);
+    set(
+        &object,
+        "expirationEpochSeconds",
+        state
+            .expiration
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0.0, |expiration| expiration.as_secs() as f64),
+    );
+    set(&object, "description", state.metadata.description);
+    set(&object, "accentColor", state.metadata.accent_color);
+    set(&object, "avatar", state.metadata.avatar);
+    object
+}
+
+fn devices_to_js(devices: Vec<sfu::PeekDeviceInfo>) -> Array {
+    devices
+        .into_iter()
+        .map(|device| {
+            let object = Object::new();
+            set(&object, "demuxId", device.demux_id);
+            set(&object, "userId", bytes_or_null(device.user_id));
+            set(
+                &object,
+                "bridged",
+                device.participant_kind == sfu::ParticipantKind::Bridged,
+            );
+            JsValue::from(object)
+        })
+        .collect()
+}
+
+fn peek_info_to_js(peek_info: sfu::PeekInfo) -> JsValue {
+    let object = Object::new();
+    set(&object, "devices", devices_to_js(peek_info.devices));
+    set(
+        &object,
+        "pendingDevices",
+        devices_to_js(peek_info.pending_devices),
+    );
+    set(&object, "creator", bytes_or_null(peek_info.creator));
+    set(&object, "eraId", peek_info.era_id);
+    set(&object, "maxDevices", peek_info.max_devices);
+    set(
+        &object,
+        "callLinkState",
+        peek_info
+            .call_link_state
+            .map_or(JsValue::NULL, |state| call_link_state_to_js(state).into()),
+    );
+    object.into()
+}
+
+fn parse_root_key(root_key: &[u8]) -> Result<CallLinkRootKey, JsValue> {
+    CallLinkRootKey::try_from(root_key).map_err(|_| JsValue::from_str("invalid root key"))
+}
+
+/// Peeks a group call, where `groupMembers` is an array of
+/// `{ userId: Uint8Array, memberId: Uint8Array }`.
+#[wasm_bindgen(js_name = peekGroupCall)]
+pub async fn peek_group_call(
+    sfu_url: String,
+    membership_proof: Vec<u8>,
+    group_members: Array,
+) -> Result<JsValue, JsValue> {
+    let auth_header = sfu::auth_header_from_membership_proof(&membership_proof)
+        .ok_or_else(|| JsValue::from(http::ResponseStatus::INVALID_CLIENT_AUTH.code))?;
+    let group_members = group_members
+        .iter()
+        .map(|member| {
+            Ok(sfu::GroupMember {
+                user_id: bytes_property(&member, "userId")?,
+                member_id: bytes_property(&member, "memberId")?,
+            })
+        })
+        .collect::<Result<Vec<_>, JsValue>>()?;
+    let result = await_callback(|callback| {
+        sfu::peek(
+            &FetchClient,
+            &sfu_url,
+            None,
+            auth_header,
+            Arc::new(sfu::MemberMap::new(&group_members)),
+            None,
+            callback,
+        )
+    })
+    .await?;
+    result
+        .map(peek_info_to_js)
+        .map_err(|status| JsValue::from(status.code))
+}
+
+#[wasm_bindgen(js_name = peekCallLinkCall)]
+pub async fn peek_call_link_call(
+    sfu_url: String,
+    auth_credential_presentation: Vec<u8>,
+    link_root_key: Vec<u8>,
+) -> Result<JsValue, JsValue> {
+    let link_root_key = parse_root_key(&link_root_key)?;
+    let result = await_callback(|callback| {
+        sfu::peek(
+            &FetchClient,
+            &sfu_url,
+            Some(hex::encode(link_root_key.derive_room_id())),
+            call_links::auth_header_from_auth_credential(&auth_credential_presentation),
+            Arc::new(CallLinkMemberResolver::from(&link_root_key)),
+            Some(link_root_key),
+            callback,
+        )
+    })
+    .await?;
+    result
+        .map(peek_info_to_js)
+        .map_err(|status| JsValue::from(status.code))
+}
+
+#[wasm_bindgen(js_name = readCallLink)]
+pub async fn read_call_link(
+    sfu_url: String,
+    auth_credential_presentation: Vec<u8>,
+    link_root_key: Vec<u8>,
+) -> Result<JsValue, JsValue> {
+    let link_root_key = parse_root_key(&link_root_key)?;
+    let result = await_callback(|callback| {
+        call_links::read_call_link(
+            &FetchClient,
+            &sfu_url,
+            link_root_key,
+            None,
+            &auth_credential_presentation,
+            callback,
+        )
+    })
+    .await?;
+    result
+        .map(|state| call_link_state_to_js(state).into())
+        .map_err(|status| JsValue::from(status.code))
+}
+
+/// Moves a call link to the version of `linkRootKey`, from
+/// `callLinkRootKeyWithLatestVersion`. Once this resolves, store and share
+/// `linkRootKey` in place of the old key.
+#[wasm_bindgen(js_name = migrateCallLink)]
+pub async fn migrate_call_link(
+    sfu_url: String,
+    auth_credential_presentation: Vec<u8>,
+    link_root_key: Vec<u8>,
+    admin_passkey: Vec<u8>,
+) -> Result<JsValue, JsValue> {
+    let link_root_key = parse_root_key(&link_root_key)?;
+    let result = await_callback(|callback| {
+        call_links::read_and_migrate_call_link(
+            Arc::new(FetchClient),
+            &sfu_url,
+            link_root_key,
+            &auth_credential_presentation,
+            &admin_passkey,
+            callback,
+        )
+    })
+    .await?;
+    result
+        .map(|state| call_link_state_to_js(state).into())
+        .map_err(|status| JsValue::from(status.code))
+}
+
+/// Deletes a call link, rejecting with 409 if there's an ongoing call using
+/// it.
+#[wasm_bindgen(js_name = deleteCallLink)]
+pub async fn delete_call_link(
+    sfu_url: String,
+    auth_credential_presentation: Vec<u8>,
+    link_root_key: Vec<u8>,
+    admin_passkey: Vec<u8>,
+) -> Result<(), JsValue> {
+    let link_root_key = parse_root_key(&link_root_key)?;
+    let result = await_callback(|callback| {
+        call_links::delete_call_link(
+            &FetchClient,
+            &sfu_url,
+            link_root_key,
+            &auth_credential_presentation,
+            &call_links::CallLinkDeleteRequest {
+                admin_passkey: &admin_passkey,
+            },
+            callback,
+        )
+    })
+    .await?;
+    result
+        .map(|_| ())
+        .map_err(|status| JsValue::from(status.code))
+}
+
+#[wasm_bindgen(js_name = parseCallLinkRootKey)]
+pub fn parse_call_link_root_key(string: &str) -> Result<Vec<u8>, JsValue> {
+    CallLinkRootKey::try_from(string)
+        .map(|root_key| root_key.to_versioned_bytes())
+        .map_err(|_| JsValue::from_str("invalid root key"))
+}
+
+/// The same key at the newest version, to pass to `migrateCallLink`.
+#[wasm_bindgen(js_name = callLinkRootKeyWithLatestVersion)]
+pub fn call_link_root_key_with_latest_version(root_key: &[u8]) -> Result<Vec<u8>, JsValue> {
+    Ok(parse_root_key(root_key)?
+        .with_version(CallLinkRootKeyVersion::LATEST)
+        .to_versioned_bytes())
+}
+
+#[wasm_bindgen(js_name = deriveCallLinkRoomId)]
+pub fn derive_call_link_room_id(root_key: &[u8]) -> Result<Vec<u8>, JsValue> {
+    Ok(parse_root_key(root_key)?.derive_room_id())
+}
+
+#[wasm_bindgen(js_name = formatCallLinkRootKey)]
+pub fn format_call_link_root_key(root_key: &[u8]) -> Result<String, JsValue> {
+    Ok(parse_root_key(root_key)?.to_formatted_string())
+}
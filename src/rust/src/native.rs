@@ -7,7 +7,8 @@ use std::{collections::HashSet, fmt, time::Duration};
 
 use crate::{
     common::{
-        ApplicationEvent, CallConfig, CallDirection, CallId, CallMediaType, DeviceId, Result,
+        ApplicationEvent, CallConfig, CallDirection, CallId, CallMediaType, CallSummary, DeviceId,
+        GlareResolution, Result,
     },
     core::{
         call::Call,
@@ -25,6 +26,7 @@ use crate::{
         peer_connection::{AudioLevel, ReceivedAudioLevel},
         peer_connection_factory::{IceServer, PeerConnectionFactory, RffiPeerConnectionKind},
         peer_connection_observer::{NetworkRoute, PeerConnectionObserver},
+        stats_observer::QualityLimitationReason,
     },
 };
 
@@ -113,15 +115,24 @@ pub trait SignalingSender {
 }
 
 pub trait CallStateHandler {
+    /// app_data is what the application attached to the call with
+    /// CallManager::set_call_app_data(), or empty.
     fn handle_call_state(
         &self,
         remote_peer_id: &str,
         call_id: CallId,
         state: CallState,
+        app_data: &[u8],
     ) -> Result<()>;
     fn handle_remote_audio_state(&self, remote_peer_id: &str, enabled: bool) -> Result<()>;
     fn handle_remote_video_state(&self, remote_peer_id: &str, enabled: bool) -> Result<()>;
     fn handle_remote_sharing_screen(&self, remote_peer_id: &str, enabled: bool) -> Result<()>;
+    fn handle_remote_hold(&self, _remote_peer_id: &str, _on_hold: bool) -> Result<()> {
+        Ok(())
+    }
+    fn handle_incoming_call_waiting(&self, _remote_peer_id: &str, _call_id: CallId) -> Result<()> {
+        Ok(())
+    }
     fn handle_network_route(&self, remote_peer_id: &str, network_route: NetworkRoute)
         -> Result<()>;
     fn handle_audio_levels(
@@ -131,6 +142,52 @@ pub trait CallStateHandler {
         received_level: AudioLevel,
     ) -> Result<()>;
     fn handle_low_bandwidth_for_video(&self, remote_peer_id: &str, recovered: bool) -> Result<()>;
+    fn handle_call_summary(
+        &self,
+        _remote_peer_id: &str,
+        _call_id: CallId,
+        _summary: CallSummary,
+        _reason: EndReason,
+        _app_data: &[u8],
+    ) -> Result<()> {
+        Ok(())
+    }
+    fn handle_glare_resolved(
+        &self,
+        _remote_peer_id: &str,
+        _resolution: GlareResolution,
+    ) -> Result<()> {
+        Ok(())
+    }
+    fn handle_remote_handed_off(&self, _remote_peer_id: &str) -> Result<()> {
+        Ok(())
+    }
+    fn handle_remote_early_media(&self, _remote_peer_id: &str) -> Result<()> {
+        Ok(())
+    }
+    fn handle_video_negotiation(
+        &self,
+        _remote_peer_id: &str,
+        _event: VideoNegotiationEvent,
+    ) -> Result<()> {
+        Ok(())
+    }
+    fn handle_media_stalled(&self, _remote_peer_id: &str) -> Result<()> {
+        Ok(())
+    }
+    fn handle_app_data(&self, _remote_peer_id: &str, _data: Vec<u8>) -> Result<()> {
+        Ok(())
+    }
+    fn handle_video_adaptation_changed(
+        &self,
+        _remote_peer_id: &str,
+        _reason: Option<QualityLimitationReason>,
+    ) -> Result<()> {
+        Ok(())
+    }
+    fn handle_handoff_requested(&self, _request: signaling::HandoffRequest) -> Result<()> {
+        Ok(())
+    }
 }
 
 // These are the different states a call can be in.
@@ -170,6 +227,17 @@ impl fmt::Debug for CallState {
     }
 }
 
+// These are the steps of mid-call video negotiation with the remote peer.
+// Closely tied to call_manager::ApplicationEvent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VideoNegotiationEvent {
+    RemoteAddRequested,
+    RemoteAdded,
+    RemoteRemoved,
+    LocalAddAccepted,
+    LocalAddDeclined,
+}
+
 // These are the different reasons a call can end.
 // Closely tied to call_manager::ApplicationEvent.
 #[derive(Debug)]
@@ -192,6 +260,14 @@ pub enum EndReason {
     AcceptedOnAnotherDevice,
     DeclinedOnAnotherDevice,
     BusyOnAnotherDevice,
+    DeclinedBusy,
+    DeclinedDoNotDisturb,
+    DeclinedTextReplySent,
+    HandedOff,
+    MediaStalled,
+    RemoteHangupNetworkFailure,
+    DeclinedByPolicy,
+    RemoteHangupRingTimeout,
 }
 
 impl fmt::Display for EndReason {
@@ -215,11 +291,89 @@ impl fmt::Display for EndReason {
             EndReason::AcceptedOnAnotherDevice => "AcceptedOnAnotherDevice",
             EndReason::DeclinedOnAnotherDevice => "DeclinedOnAnotherDevice",
             EndReason::BusyOnAnotherDevice => "BusyOnAnotherDevice",
+            EndReason::DeclinedBusy => "DeclinedBusy",
+            EndReason::DeclinedDoNotDisturb => "DeclinedDoNotDisturb",
+            EndReason::DeclinedTextReplySent => "DeclinedTextReplySent",
+            EndReason::HandedOff => "HandedOff",
+            EndReason::MediaStalled => "MediaStalled",
+            EndReason::RemoteHangupNetworkFailure => "RemoteHangupNetworkFailure",
+            EndReason::DeclinedByPolicy => "DeclinedByPolicy",
+            EndReason::RemoteHangupRingTimeout => "RemoteHangupRingTimeout",
         };
         write!(f, "({})", display)
     }
 }
 
+impl EndReason {
+    /// Returns the reason for an event that ends a call, or None if the event
+    /// doesn't end the call.
+    fn from_application_event(event: ApplicationEvent) -> Option<Self> {
+        match event {
+            ApplicationEvent::EndedLocalHangup => Some(EndReason::LocalHangup),
+            ApplicationEvent::EndedRemoteHangup => Some(EndReason::RemoteHangup),
+            ApplicationEvent::EndedRemoteHangupNeedPermission => {
+                Some(EndReason::RemoteHangupNeedPermission)
+            }
+            ApplicationEvent::EndedRemoteBusy => Some(EndReason::Busy),
+            ApplicationEvent::EndedRemoteGlare => Some(EndReason::Glare),
+            ApplicationEvent::EndedRemoteReCall => Some(EndReason::ReCall),
+            ApplicationEvent::EndedTimeout => Some(EndReason::Timeout),
+            ApplicationEvent::EndedInternalFailure => Some(EndReason::InternalFailure),
+            ApplicationEvent::EndedSignalingFailure => Some(EndReason::SignalingFailure),
+            ApplicationEvent::EndedGlareHandlingFailure => Some(EndReason::GlareFailure),
+            ApplicationEvent::EndedConnectionFailure => Some(EndReason::ConnectionFailure),
+            ApplicationEvent::EndedAppDroppedCall => Some(EndReason::Declined),
+            ApplicationEvent::ReceivedOfferExpired => Some(EndReason::ReceivedOfferExpired {
+                age: Duration::ZERO,
+            }),
+            ApplicationEvent::ReceivedOfferWhileActive => Some(EndReason::ReceivedOfferWhileActive),
+            ApplicationEvent::ReceivedOfferWithGlare => Some(EndReason::ReceivedOfferWithGlare),
+            ApplicationEvent::EndedRemoteHangupAccepted => Some(EndReason::AcceptedOnAnotherDevice),
+            ApplicationEvent::EndedRemoteHangupDeclined => Some(EndReason::DeclinedOnAnotherDevice),
+            ApplicationEvent::EndedRemoteHangupBusy => Some(EndReason::BusyOnAnotherDevice),
+            ApplicationEvent::EndedRemoteDeclinedBusy => Some(EndReason::DeclinedBusy),
+            ApplicationEvent::EndedRemoteDeclinedDoNotDisturb => {
+                Some(EndReason::DeclinedDoNotDisturb)
+            }
+            ApplicationEvent::EndedRemoteDeclinedTextReplySent => {
+                Some(EndReason::DeclinedTextReplySent)
+            }
+            ApplicationEvent::EndedHandedOff => Some(EndReason::HandedOff),
+            ApplicationEvent::EndedMediaStalled => Some(EndReason::MediaStalled),
+            ApplicationEvent::EndedRemoteHangupNetworkFailure => {
+                Some(EndReason::RemoteHangupNetworkFailure)
+            }
+            ApplicationEvent::EndedRemoteDeclinedByPolicy => Some(EndReason::DeclinedByPolicy),
+            ApplicationEvent::EndedRemoteHangupRingTimeout => {
+                Some(EndReason::RemoteHangupRingTimeout)
+            }
+            ApplicationEvent::LocalRinging
+            | ApplicationEvent::RemoteRinging
+            | ApplicationEvent::LocalAccepted
+            | ApplicationEvent::RemoteAccepted
+            | ApplicationEvent::Reconnecting
+            | ApplicationEvent::Reconnected
+            | ApplicationEvent::RemoteAudioEnable
+            | ApplicationEvent::RemoteAudioDisable
+            | ApplicationEvent::RemoteVideoEnable
+            | ApplicationEvent::RemoteVideoDisable
+            | ApplicationEvent::RemoteSharingScreenEnable
+            | ApplicationEvent::RemoteSharingScreenDisable
+            | ApplicationEvent::RemoteHeld
+            | ApplicationEvent::RemoteResumed
+            | ApplicationEvent::IncomingCallWaiting
+            | ApplicationEvent::RemoteHandedOff
+            | ApplicationEvent::RemoteEarlyMedia
+            | ApplicationEvent::RemoteVideoAddRequested
+            | ApplicationEvent::RemoteVideoAdded
+            | ApplicationEvent::RemoteVideoRemoved
+            | ApplicationEvent::LocalVideoAddAccepted
+            | ApplicationEvent::LocalVideoAddDeclined
+            | ApplicationEvent::MediaStalled => None,
+        }
+    }
+}
+
 // Group Calls
 
 pub trait GroupUpdateHandler {
@@ -255,6 +409,24 @@ pub enum GroupUpdate {
     },
     Reactions(group_call::ClientId, Vec<group_call::Reaction>),
     RaisedHands(group_call::ClientId, Vec<DemuxId>),
+    AppData(group_call::ClientId, DemuxId, Vec<u8>),
+    RemoteConnectionQualities(
+        group_call::ClientId,
+        Vec<group_call::RemoteConnectionQuality>,
+    ),
+    AvSyncSkew(group_call::ClientId, Vec<group_call::RemoteAvSyncSkew>),
+    E2eeHealth(group_call::ClientId, group_call::E2eeHealth),
+    VideoAdaptationChanged(group_call::ClientId, Option<QualityLimitationReason>),
+    CallSummary(group_call::ClientId, CallSummary, group_call::EndReason),
+    OwnDeviceJoined(group_call::ClientId, DemuxId),
+    RequestIceServers(group_call::ClientId),
+    IceServersRefreshFailed(group_call::ClientId),
+    BreakoutGroupChanged(group_call::ClientId, Option<group_call::BreakoutGroupId>),
+    CallFull {
+        client_id: group_call::ClientId,
+        max_devices: Option<u32>,
+        device_count: Option<u32>,
+    },
     RtcStatsReportComplete {
         report_json: String,
     },
@@ -288,6 +460,33 @@ impl fmt::Display for GroupUpdate {
             GroupUpdate::RaisedHands(_, raised_hands) => {
                 format!("RaisedHands({:?})", raised_hands)
             }
+            GroupUpdate::AppData(_, demux_id, data) => {
+                format!("AppData({}, {} bytes)", demux_id, data.len())
+            }
+            GroupUpdate::RemoteConnectionQualities(_, qualities) => {
+                format!("RemoteConnectionQualities({:?})", qualities)
+            }
+            GroupUpdate::AvSyncSkew(_, skews) => format!("AvSyncSkew({:?})", skews),
+            GroupUpdate::E2eeHealth(_, health) => format!("E2eeHealth({:?})", health),
+            GroupUpdate::VideoAdaptationChanged(_, reason) => {
+                format!("VideoAdaptationChanged({:?})", reason)
+            }
+            GroupUpdate::CallSummary(_, summary, reason) => {
+                format!("CallSummary({:?}, {:?})", summary, reason)
+            }
+            GroupUpdate::OwnDeviceJoined(_, demux_id) => {
+                format!("OwnDeviceJoined({})", demux_id)
+            }
+            GroupUpdate::RequestIceServers(_) => "RequestIceServers".to_string(),
+            GroupUpdate::IceServersRefreshFailed(_) => "IceServersRefreshFailed".to_string(),
+            GroupUpdate::BreakoutGroupChanged(_, breakout_group_id) => {
+                format!("BreakoutGroupChanged({:?})", breakout_group_id)
+            }
+            GroupUpdate::CallFull {
+                max_devices,
+                device_count,
+                ..
+            } => format!("CallFull({:?}, {:?})", max_devices, device_count),
             GroupUpdate::RtcStatsReportComplete { .. } => "RtcStatsReportComplete".to_string(),
             GroupUpdate::SpeechEvent(_, event) => {
                 format!("SpeechEvent({:?}", event)
@@ -337,9 +536,15 @@ impl NativePlatform {
         }
     }
 
-    fn send_state(&self, peer_id: &str, call_id: CallId, state: CallState) -> Result<()> {
+    fn send_state(
+        &self,
+        peer_id: &str,
+        call_id: CallId,
+        state: CallState,
+        app_data: &[u8],
+    ) -> Result<()> {
         self.state_handler
-            .handle_call_state(peer_id, call_id, state)
+            .handle_call_state(peer_id, call_id, state, app_data)
     }
 
     fn send_network_route(&self, peer_id: &str, network_route: NetworkRoute) -> Result<()> {
@@ -381,6 +586,14 @@ impl NativePlatform {
             .handle_remote_sharing_screen(peer_id, enabled)
     }
 
+    fn send_remote_hold(&self, peer_id: &str, on_hold: bool) -> Result<()> {
+        self.state_handler.handle_remote_hold(peer_id, on_hold)
+    }
+
+    fn send_video_negotiation(&self, peer_id: &str, event: VideoNegotiationEvent) -> Result<()> {
+        self.state_handler.handle_video_negotiation(peer_id, event)
+    }
+
     fn send_signaling(
         &self,
         recipient_id: &str,
@@ -456,7 +669,7 @@ impl Platform for NativePlatform {
             true,  /* enable_video_frame_event */
             true,  /* enable_video_frame_content */
         )?;
-        let kind = if context.hide_ip {
+        let kind = if context.hide_ip || connection.call_config().relay_only {
             RffiPeerConnectionKind::Relayed
         } else {
             RffiPeerConnectionKind::Direct
@@ -464,7 +677,10 @@ impl Platform for NativePlatform {
         let pc = self.peer_connection_factory.create_peer_connection(
             pc_observer,
             kind,
-            &connection.call_config().audio_jitter_buffer_config,
+            &connection
+                .call_config()
+                .jitter_buffer
+                .apply(&connection.call_config().audio_jitter_buffer_config),
             connection.call_config().audio_rtcp_report_interval_ms,
             &context.ice_servers,
             context.outgoing_audio_track.clone(),
@@ -505,6 +721,7 @@ impl Platform for NativePlatform {
         call_id: CallId,
         direction: CallDirection,
         call_media_type: CallMediaType,
+        app_data: &[u8],
     ) -> Result<()> {
         info!(
             "NativePlatform::on_start_call(): remote_peer: {}, call_id: {}, direction: {}, call_media_type: {}",
@@ -517,6 +734,7 @@ impl Platform for NativePlatform {
                 CallDirection::Outgoing => CallState::Outgoing(call_media_type),
                 CallDirection::Incoming => CallState::Incoming(call_media_type),
             },
+            app_data,
         )?;
         Ok(())
     }
@@ -526,6 +744,7 @@ impl Platform for NativePlatform {
         remote_peer: &Self::AppRemotePeer,
         call_id: CallId,
         event: ApplicationEvent,
+        app_data: &[u8],
     ) -> Result<()> {
         info!(
             "NativePlatform::on_event(): remote_peer: {}, event: {}",
@@ -534,94 +753,139 @@ impl Platform for NativePlatform {
 
         match event {
             ApplicationEvent::LocalRinging | ApplicationEvent::RemoteRinging => {
-                self.send_state(remote_peer, call_id, CallState::Ringing)
+                self.send_state(remote_peer, call_id, CallState::Ringing, app_data)
             }
             ApplicationEvent::LocalAccepted
             | ApplicationEvent::RemoteAccepted
             | ApplicationEvent::Reconnected => {
-                self.send_state(remote_peer, call_id, CallState::Connected)
+                self.send_state(remote_peer, call_id, CallState::Connected, app_data)
             }
             ApplicationEvent::Reconnecting => {
-                self.send_state(remote_peer, call_id, CallState::Connecting)
+                self.send_state(remote_peer, call_id, CallState::Connecting, app_data)
             }
             ApplicationEvent::EndedLocalHangup => self.send_state(
                 remote_peer,
                 call_id,
                 CallState::Ended(EndReason::LocalHangup),
+                app_data,
             ),
             ApplicationEvent::EndedRemoteHangup => self.send_state(
                 remote_peer,
                 call_id,
                 CallState::Ended(EndReason::RemoteHangup),
+                app_data,
             ),
             ApplicationEvent::EndedRemoteHangupNeedPermission => self.send_state(
                 remote_peer,
                 call_id,
                 CallState::Ended(EndReason::RemoteHangupNeedPermission),
+                app_data,
+            ),
+            ApplicationEvent::EndedRemoteBusy => self.send_state(
+                remote_peer,
+                call_id,
+                CallState::Ended(EndReason::Busy),
+                app_data,
+            ),
+            ApplicationEvent::EndedRemoteGlare => self.send_state(
+                remote_peer,
+                call_id,
+                CallState::Ended(EndReason::Glare),
+                app_data,
+            ),
+            ApplicationEvent::EndedRemoteReCall => self.send_state(
+                remote_peer,
+                call_id,
+                CallState::Ended(EndReason::ReCall),
+                app_data,
+            ),
+            ApplicationEvent::EndedTimeout => self.send_state(
+                remote_peer,
+                call_id,
+                CallState::Ended(EndReason::Timeout),
+                app_data,
             ),
-            ApplicationEvent::EndedRemoteBusy => {
-                self.send_state(remote_peer, call_id, CallState::Ended(EndReason::Busy))
-            }
-            ApplicationEvent::EndedRemoteGlare => {
-                self.send_state(remote_peer, call_id, CallState::Ended(EndReason::Glare))
-            }
-            ApplicationEvent::EndedRemoteReCall => {
-                self.send_state(remote_peer, call_id, CallState::Ended(EndReason::ReCall))
-            }
-            ApplicationEvent::EndedTimeout => {
-                self.send_state(remote_peer, call_id, CallState::Ended(EndReason::Timeout))
-            }
             ApplicationEvent::EndedInternalFailure => self.send_state(
                 remote_peer,
                 call_id,
                 CallState::Ended(EndReason::InternalFailure),
+                app_data,
             ),
             ApplicationEvent::EndedSignalingFailure => self.send_state(
                 remote_peer,
                 call_id,
                 CallState::Ended(EndReason::SignalingFailure),
+                app_data,
             ),
             ApplicationEvent::EndedGlareHandlingFailure => self.send_state(
                 remote_peer,
                 call_id,
                 CallState::Ended(EndReason::GlareFailure),
+                app_data,
             ),
             ApplicationEvent::EndedConnectionFailure => self.send_state(
                 remote_peer,
                 call_id,
                 CallState::Ended(EndReason::ConnectionFailure),
+                app_data,
+            ),
+            ApplicationEvent::EndedAppDroppedCall => self.send_state(
+                remote_peer,
+                call_id,
+                CallState::Ended(EndReason::Declined),
+                app_data,
             ),
-            ApplicationEvent::EndedAppDroppedCall => {
-                self.send_state(remote_peer, call_id, CallState::Ended(EndReason::Declined))
-            }
             ApplicationEvent::ReceivedOfferExpired => {
                 debug_assert!(false, "should use on_offer_expired instead");
-                self.on_offer_expired(remote_peer, call_id, Duration::ZERO)
+                self.on_offer_expired(remote_peer, call_id, Duration::ZERO, app_data)
             }
             ApplicationEvent::ReceivedOfferWhileActive => self.send_state(
                 remote_peer,
                 call_id,
                 CallState::Ended(EndReason::ReceivedOfferWhileActive),
+                app_data,
             ),
             ApplicationEvent::ReceivedOfferWithGlare => self.send_state(
                 remote_peer,
                 call_id,
                 CallState::Ended(EndReason::ReceivedOfferWithGlare),
+                app_data,
             ),
             ApplicationEvent::EndedRemoteHangupAccepted => self.send_state(
                 remote_peer,
                 call_id,
                 CallState::Ended(EndReason::AcceptedOnAnotherDevice),
+                app_data,
             ),
             ApplicationEvent::EndedRemoteHangupDeclined => self.send_state(
                 remote_peer,
                 call_id,
                 CallState::Ended(EndReason::DeclinedOnAnotherDevice),
+                app_data,
             ),
             ApplicationEvent::EndedRemoteHangupBusy => self.send_state(
                 remote_peer,
                 call_id,
                 CallState::Ended(EndReason::BusyOnAnotherDevice),
+                app_data,
+            ),
+            ApplicationEvent::EndedRemoteDeclinedBusy => self.send_state(
+                remote_peer,
+                call_id,
+                CallState::Ended(EndReason::DeclinedBusy),
+                app_data,
+            ),
+            ApplicationEvent::EndedRemoteDeclinedDoNotDisturb => self.send_state(
+                remote_peer,
+                call_id,
+                CallState::Ended(EndReason::DeclinedDoNotDisturb),
+                app_data,
+            ),
+            ApplicationEvent::EndedRemoteDeclinedTextReplySent => self.send_state(
+                remote_peer,
+                call_id,
+                CallState::Ended(EndReason::DeclinedTextReplySent),
+                app_data,
             ),
             ApplicationEvent::RemoteAudioEnable => self.send_remote_audio_state(remote_peer, true),
             ApplicationEvent::RemoteAudioDisable => {
@@ -637,10 +901,118 @@ impl Platform for NativePlatform {
             ApplicationEvent::RemoteSharingScreenDisable => {
                 self.send_remote_sharing_screen(remote_peer, false)
             }
+            ApplicationEvent::RemoteHeld => self.send_remote_hold(remote_peer, true),
+            ApplicationEvent::RemoteResumed => self.send_remote_hold(remote_peer, false),
+            ApplicationEvent::IncomingCallWaiting => self
+                .state_handler
+                .handle_incoming_call_waiting(remote_peer, call_id),
+            ApplicationEvent::RemoteHandedOff => {
+                self.state_handler.handle_remote_handed_off(remote_peer)
+            }
+            ApplicationEvent::EndedHandedOff => self.send_state(
+                remote_peer,
+                call_id,
+                CallState::Ended(EndReason::HandedOff),
+                app_data,
+            ),
+            ApplicationEvent::RemoteEarlyMedia => {
+                self.state_handler.handle_remote_early_media(remote_peer)
+            }
+            ApplicationEvent::RemoteVideoAddRequested => {
+                self.send_video_negotiation(remote_peer, VideoNegotiationEvent::RemoteAddRequested)
+            }
+            ApplicationEvent::RemoteVideoAdded => {
+                self.send_video_negotiation(remote_peer, VideoNegotiationEvent::RemoteAdded)
+            }
+            ApplicationEvent::RemoteVideoRemoved => {
+                self.send_video_negotiation(remote_peer, VideoNegotiationEvent::RemoteRemoved)
+            }
+            ApplicationEvent::LocalVideoAddAccepted => {
+                self.send_video_negotiation(remote_peer, VideoNegotiationEvent::LocalAddAccepted)
+            }
+            ApplicationEvent::LocalVideoAddDeclined => {
+                self.send_video_negotiation(remote_peer, VideoNegotiationEvent::LocalAddDeclined)
+            }
+            ApplicationEvent::MediaStalled => self.state_handler.handle_media_stalled(remote_peer),
+            ApplicationEvent::EndedMediaStalled => self.send_state(
+                remote_peer,
+                call_id,
+                CallState::Ended(EndReason::MediaStalled),
+                app_data,
+            ),
+            ApplicationEvent::EndedRemoteHangupNetworkFailure => self.send_state(
+                remote_peer,
+                call_id,
+                CallState::Ended(EndReason::RemoteHangupNetworkFailure),
+                app_data,
+            ),
+            ApplicationEvent::EndedRemoteDeclinedByPolicy => self.send_state(
+                remote_peer,
+                call_id,
+                CallState::Ended(EndReason::DeclinedByPolicy),
+                app_data,
+            ),
+            ApplicationEvent::EndedRemoteHangupRingTimeout => self.send_state(
+                remote_peer,
+                call_id,
+                CallState::Ended(EndReason::RemoteHangupRingTimeout),
+                app_data,
+            ),
         }?;
         Ok(())
     }
 
+    fn on_call_summary(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        summary: CallSummary,
+        event: ApplicationEvent,
+        app_data: &[u8],
+    ) -> Result<()> {
+        info!(
+            "NativePlatform::on_call_summary(): remote_peer: {}, summary: {:?}",
+            remote_peer, summary
+        );
+
+        match EndReason::from_application_event(event) {
+            Some(reason) => self.state_handler.handle_call_summary(
+                remote_peer,
+                call_id,
+                summary,
+                reason,
+                app_data,
+            ),
+            None => {
+                warn!(
+                    "NativePlatform::on_call_summary(): {} doesn't end a call",
+                    event
+                );
+                Ok(())
+            }
+        }
+    }
+
+    fn on_glare_resolved(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        resolution: GlareResolution,
+    ) -> Result<()> {
+        info!(
+            "NativePlatform::on_glare_resolved(): remote_peer: {}, resolution: {:?}",
+            remote_peer, resolution
+        );
+
+        self.state_handler
+            .handle_glare_resolved(remote_peer, resolution)
+    }
+
+    fn on_handoff_requested(&self, request: signaling::HandoffRequest) -> Result<()> {
+        info!("NativePlatform::on_handoff_requested(): {:?}", request);
+
+        self.state_handler.handle_handoff_requested(request)
+    }
+
     fn on_network_route_changed(
         &self,
         remote_peer: &Self::AppRemotePeer,
@@ -682,11 +1054,36 @@ impl Platform for NativePlatform {
         self.send_low_bandwidth_for_video(remote_peer, recovered)
     }
 
+    fn on_app_data(&self, remote_peer: &Self::AppRemotePeer, data: Vec<u8>) -> Result<()> {
+        debug!(
+            "NativePlatform::on_app_data(): remote_peer: {}, len: {}",
+            remote_peer,
+            data.len()
+        );
+
+        self.state_handler.handle_app_data(remote_peer, data)
+    }
+
+    fn on_video_adaptation_changed(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        reason: Option<QualityLimitationReason>,
+    ) -> Result<()> {
+        info!(
+            "NativePlatform::on_video_adaptation_changed(): remote_peer: {}, reason: {:?}",
+            remote_peer, reason
+        );
+
+        self.state_handler
+            .handle_video_adaptation_changed(remote_peer, reason)
+    }
+
     fn on_offer_expired(
         &self,
         remote_peer: &Self::AppRemotePeer,
         call_id: CallId,
         age: Duration,
+        app_data: &[u8],
     ) -> Result<()> {
         info!(
             "NativePlatform::on_offer_expired(): remote_peer: {}, age: {:?}",
@@ -697,17 +1094,23 @@ impl Platform for NativePlatform {
             remote_peer,
             call_id,
             CallState::Ended(EndReason::ReceivedOfferExpired { age }),
+            app_data,
         )?;
         Ok(())
     }
 
-    fn on_call_concluded(&self, remote_peer: &Self::AppRemotePeer, call_id: CallId) -> Result<()> {
+    fn on_call_concluded(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        call_id: CallId,
+        app_data: &[u8],
+    ) -> Result<()> {
         info!(
             "NativePlatform::on_call_concluded(): remote_peer: {}",
             remote_peer
         );
 
-        self.send_state(remote_peer, call_id, CallState::Concluded)?;
+        self.send_state(remote_peer, call_id, CallState::Concluded, app_data)?;
         Ok(())
     }
 
@@ -958,6 +1361,70 @@ impl Platform for NativePlatform {
         }
     }
 
+    fn handle_app_data(&self, client_id: group_call::ClientId, demux_id: DemuxId, data: Vec<u8>) {
+        trace!("NativePlatform::handle_app_data(): id: {}", client_id);
+
+        let result = self.send_group_update(GroupUpdate::AppData(client_id, demux_id, data));
+        if result.is_err() {
+            error!("{:?}", result.err());
+        }
+    }
+
+    fn handle_remote_connection_qualities(
+        &self,
+        client_id: group_call::ClientId,
+        qualities: Vec<group_call::RemoteConnectionQuality>,
+    ) {
+        debug!(
+            "NativePlatform::handle_remote_connection_qualities(): id: {}",
+            client_id
+        );
+
+        let result =
+            self.send_group_update(GroupUpdate::RemoteConnectionQualities(client_id, qualities));
+        if result.is_err() {
+            error!("{:?}", result.err());
+        }
+    }
+
+    fn handle_av_sync_skew(
+        &self,
+        client_id: group_call::ClientId,
+        skews: Vec<group_call::RemoteAvSyncSkew>,
+    ) {
+        debug!("NativePlatform::handle_av_sync_skew(): id: {}", client_id);
+
+        let result = self.send_group_update(GroupUpdate::AvSyncSkew(client_id, skews));
+        if result.is_err() {
+            error!("{:?}", result.err());
+        }
+    }
+
+    fn handle_e2ee_health(&self, client_id: group_call::ClientId, health: group_call::E2eeHealth) {
+        debug!("NativePlatform::handle_e2ee_health(): id: {}", client_id);
+
+        let result = self.send_group_update(GroupUpdate::E2eeHealth(client_id, health));
+        if result.is_err() {
+            error!("{:?}", result.err());
+        }
+    }
+
+    fn handle_video_adaptation_changed(
+        &self,
+        client_id: group_call::ClientId,
+        reason: Option<QualityLimitationReason>,
+    ) {
+        debug!(
+            "NativePlatform::handle_video_adaptation_changed(): id: {}",
+            client_id
+        );
+
+        let result = self.send_group_update(GroupUpdate::VideoAdaptationChanged(client_id, reason));
+        if result.is_err() {
+            error!("{:?}", result.err());
+        }
+    }
+
     fn handle_join_state_changed(
         &self,
         client_id: group_call::ClientId,
@@ -1033,6 +1500,90 @@ impl Platform for NativePlatform {
         }
     }
 
+    fn handle_own_device_joined(&self, client_id: group_call::ClientId, demux_id: DemuxId) {
+        info!(
+            "NativePlatform::handle_own_device_joined(): id: {}",
+            client_id
+        );
+
+        let result = self.send_group_update(GroupUpdate::OwnDeviceJoined(client_id, demux_id));
+        if result.is_err() {
+            error!("{:?}", result.err());
+        }
+    }
+
+    fn request_ice_servers(&self, client_id: group_call::ClientId) {
+        info!("NativePlatform::request_ice_servers(): id: {}", client_id);
+
+        let result = self.send_group_update(GroupUpdate::RequestIceServers(client_id));
+        if result.is_err() {
+            error!("{:?}", result.err());
+        }
+    }
+
+    fn handle_ice_servers_refresh_failed(&self, client_id: group_call::ClientId) {
+        info!(
+            "NativePlatform::handle_ice_servers_refresh_failed(): id: {}",
+            client_id
+        );
+
+        let result = self.send_group_update(GroupUpdate::IceServersRefreshFailed(client_id));
+        if result.is_err() {
+            error!("{:?}", result.err());
+        }
+    }
+
+    fn handle_breakout_group_changed(
+        &self,
+        client_id: group_call::ClientId,
+        breakout_group_id: Option<group_call::BreakoutGroupId>,
+    ) {
+        info!(
+            "NativePlatform::handle_breakout_group_changed(): id: {}",
+            client_id
+        );
+
+        let result = self.send_group_update(GroupUpdate::BreakoutGroupChanged(
+            client_id,
+            breakout_group_id,
+        ));
+        if result.is_err() {
+            error!("{:?}", result.err());
+        }
+    }
+
+    fn handle_call_full(
+        &self,
+        client_id: group_call::ClientId,
+        max_devices: Option<u32>,
+        device_count: Option<u32>,
+    ) {
+        info!("NativePlatform::handle_call_full(): id: {}", client_id);
+
+        let result = self.send_group_update(GroupUpdate::CallFull {
+            client_id,
+            max_devices,
+            device_count,
+        });
+        if result.is_err() {
+            error!("{:?}", result.err());
+        }
+    }
+
+    fn handle_call_summary(
+        &self,
+        client_id: group_call::ClientId,
+        summary: CallSummary,
+        reason: group_call::EndReason,
+    ) {
+        info!("NativePlatform::handle_call_summary(): id: {}", client_id);
+
+        let result = self.send_group_update(GroupUpdate::CallSummary(client_id, summary, reason));
+        if result.is_err() {
+            error!("{:?}", result.err());
+        }
+    }
+
     fn handle_ended(&self, client_id: group_call::ClientId, reason: group_call::EndReason) {
         info!("NativePlatform::handle_ended(): id: {}", client_id);
 
@@ -17,7 +17,8 @@ use std::{
 
 use crate::{
     common::{
-        ApplicationEvent, CallConfig, CallDirection, CallId, CallMediaType, DeviceId, Result,
+        ApplicationEvent, CallConfig, CallDirection, CallId, CallMediaType, CallSummary, DeviceId,
+        GlareResolution, Result,
     },
     core::{
         call::Call,
@@ -63,6 +64,14 @@ struct SimStats {
     busy_hangups_sent: AtomicUsize,
     /// Number of need permission hangups sent
     need_permission_hangups_sent: AtomicUsize,
+    /// Number of hangups sent declining with a reason
+    declined_with_reason_hangups_sent: AtomicUsize,
+    /// Number of handed off hangups sent
+    handed_off_hangups_sent: AtomicUsize,
+    /// Number of network failure hangups sent
+    network_failure_hangups_sent: AtomicUsize,
+    /// Number of ring timeout hangups sent
+    ring_timeout_hangups_sent: AtomicUsize,
     /// Number of busy messages sent
     busys_sent: AtomicUsize,
     /// Number of start outgoing call events
@@ -102,12 +111,22 @@ pub struct SimPlatform {
     /// True if the signaling functions should indicate a signaling
     /// failure to the call manager.
     force_signaling_fault: Arc<AtomicBool>,
+    /// True if on_call_summary() should fail.
+    force_call_summary_fault: Arc<AtomicBool>,
     /// Track event frequencies
     event_map: Arc<Mutex<HashMap<ApplicationEvent, usize>>>,
+    /// Track the application data passed with each 1:1 call notification
+    app_data_notified: Arc<Mutex<Vec<Vec<u8>>>>,
     /// Track whether disconnecting of incoming media happened
     incoming_media_disconnected: Arc<AtomicBool>,
     /// Track group call ring updates
     group_call_ring_updates: Arc<Mutex<Vec<GroupCallRingUpdate>>>,
+    /// Track resolved glares
+    glare_resolutions: Arc<Mutex<Vec<GlareResolution>>>,
+    /// Track handoff requests from other devices
+    handoff_requests: Arc<Mutex<Vec<signaling::HandoffRequest>>>,
+    /// Track network route changes for 1:1 calls
+    network_routes: Arc<Mutex<Vec<NetworkRoute>>>,
     /// Track outgoing opaque messages
     outgoing_call_messages: Arc<Mutex<Vec<OutgoingCallMessage>>>,
     /// Call Manager
@@ -184,11 +203,16 @@ impl Platform for SimPlatform {
         call_id: CallId,
         direction: CallDirection,
         call_media_type: CallMediaType,
+        app_data: &[u8],
     ) -> Result<()> {
         info!(
             "on_start_call(): remote_peer: {}, call_id: {}, direction: {}, call_media_type {}",
             remote_peer, call_id, direction, call_media_type
         );
+        self.app_data_notified
+            .lock()
+            .unwrap()
+            .push(app_data.to_vec());
 
         if self.force_internal_fault.load(Ordering::Acquire) {
             Err(SimError::StartCallError.into())
@@ -201,13 +225,34 @@ impl Platform for SimPlatform {
         }
     }
 
+    fn on_call_summary(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        _call_id: CallId,
+        _summary: CallSummary,
+        event: ApplicationEvent,
+        _app_data: &[u8],
+    ) -> Result<()> {
+        info!("on_call_summary(): {}, remote_peer: {}", event, remote_peer);
+        if self.force_call_summary_fault.load(Ordering::Acquire) {
+            Err(SimError::CallSummaryError.into())
+        } else {
+            Ok(())
+        }
+    }
+
     fn on_event(
         &self,
         remote_peer: &Self::AppRemotePeer,
         _call_id: CallId,
         event: ApplicationEvent,
+        app_data: &[u8],
     ) -> Result<()> {
         info!("on_event(): {}, remote_peer: {}", event, remote_peer);
+        self.app_data_notified
+            .lock()
+            .unwrap()
+            .push(app_data.to_vec());
 
         let mut map = self.event_map.lock().unwrap();
         map.entry(event).and_modify(|e| *e += 1).or_insert(1);
@@ -215,12 +260,34 @@ impl Platform for SimPlatform {
         Ok(())
     }
 
+    fn on_glare_resolved(
+        &self,
+        remote_peer: &Self::AppRemotePeer,
+        resolution: GlareResolution,
+    ) -> Result<()> {
+        info!(
+            "on_glare_resolved(): {:?}, remote_peer: {}",
+            resolution, remote_peer
+        );
+
+        self.glare_resolutions.lock().unwrap().push(resolution);
+        Ok(())
+    }
+
+    fn on_handoff_requested(&self, request: signaling::HandoffRequest) -> Result<()> {
+        info!("on_handoff_requested(): {:?}", request);
+
+        self.handoff_requests.lock().unwrap().push(request);
+        Ok(())
+    }
+
     fn on_network_route_changed(
         &self,
         _remote_peer: &Self::AppRemotePeer,
         network_route: NetworkRoute,
     ) -> Result<()> {
         info!("on_network_route_changed(): {:?}", network_route);
+        self.network_routes.lock().unwrap().push(network_route);
         Ok(())
     }
 
@@ -375,6 +442,30 @@ impl Platform for SimPlatform {
                         .need_permission_hangups_sent
                         .fetch_add(1, Ordering::AcqRel);
                 }
+                signaling::Hangup::Declined(_) => {
+                    let _ = self
+                        .stats
+                        .declined_with_reason_hangups_sent
+                        .fetch_add(1, Ordering::AcqRel);
+                }
+                signaling::Hangup::HandedOff(_) => {
+                    let _ = self
+                        .stats
+                        .handed_off_hangups_sent
+                        .fetch_add(1, Ordering::AcqRel);
+                }
+                signaling::Hangup::NetworkFailure => {
+                    let _ = self
+                        .stats
+                        .network_failure_hangups_sent
+                        .fetch_add(1, Ordering::AcqRel);
+                }
+                signaling::Hangup::RingTimeout => {
+                    let _ = self
+                        .stats
+                        .ring_timeout_hangups_sent
+                        .fetch_add(1, Ordering::AcqRel);
+                }
             }
             if self.force_internal_fault.load(Ordering::Acquire) {
                 self.message_send_failure(call_id).unwrap();
@@ -494,6 +585,7 @@ impl Platform for SimPlatform {
         _remote_peer: &Self::AppRemotePeer,
         _call_id: CallId,
         _age: Duration,
+        _app_data: &[u8],
     ) -> Result<()> {
         info!("on_offer_expired():");
         let _ = self.stats.offer_expired.fetch_add(1, Ordering::AcqRel);
@@ -504,8 +596,13 @@ impl Platform for SimPlatform {
         &self,
         _remote_peer: &Self::AppRemotePeer,
         _call_id: CallId,
+        app_data: &[u8],
     ) -> Result<()> {
         info!("on_call_concluded():");
+        self.app_data_notified
+            .lock()
+            .unwrap()
+            .push(app_data.to_vec());
         if self.force_internal_fault.load(Ordering::Acquire) {
             Err(SimError::CallConcludedError.into())
         } else {
@@ -676,6 +773,11 @@ impl SimPlatform {
         self.force_signaling_fault.store(enable, Ordering::Release);
     }
 
+    pub fn force_call_summary_fault(&mut self, enable: bool) {
+        self.force_call_summary_fault
+            .store(enable, Ordering::Release);
+    }
+
     pub fn no_auto_message_sent_for_ice(&mut self, enable: bool) {
         self.no_auto_message_sent_for_ice
             .store(enable, Ordering::Release);
@@ -737,6 +839,11 @@ impl SimPlatform {
         self.last_ice_sent.lock().unwrap().clone()
     }
 
+    /// The application data passed with each 1:1 call notification, in order.
+    pub fn app_data_notified(&self) -> Vec<Vec<u8>> {
+        self.app_data_notified.lock().unwrap().clone()
+    }
+
     pub fn normal_hangups_sent(&self) -> usize {
         self.stats.normal_hangups_sent.load(Ordering::Acquire)
     }
@@ -759,6 +866,26 @@ impl SimPlatform {
             .load(Ordering::Acquire)
     }
 
+    pub fn declined_with_reason_hangups_sent(&self) -> usize {
+        self.stats
+            .declined_with_reason_hangups_sent
+            .load(Ordering::Acquire)
+    }
+
+    pub fn handed_off_hangups_sent(&self) -> usize {
+        self.stats.handed_off_hangups_sent.load(Ordering::Acquire)
+    }
+
+    pub fn network_failure_hangups_sent(&self) -> usize {
+        self.stats
+            .network_failure_hangups_sent
+            .load(Ordering::Acquire)
+    }
+
+    pub fn ring_timeout_hangups_sent(&self) -> usize {
+        self.stats.ring_timeout_hangups_sent.load(Ordering::Acquire)
+    }
+
     pub fn busys_sent(&self) -> usize {
         self.stats.busys_sent.load(Ordering::Acquire)
     }
@@ -791,6 +918,18 @@ impl SimPlatform {
         std::mem::take(&mut *self.group_call_ring_updates.lock().unwrap())
     }
 
+    pub fn take_glare_resolutions(&self) -> Vec<GlareResolution> {
+        std::mem::take(&mut *self.glare_resolutions.lock().unwrap())
+    }
+
+    pub fn take_handoff_requests(&self) -> Vec<signaling::HandoffRequest> {
+        std::mem::take(&mut *self.handoff_requests.lock().unwrap())
+    }
+
+    pub fn take_network_routes(&self) -> Vec<NetworkRoute> {
+        std::mem::take(&mut *self.network_routes.lock().unwrap())
+    }
+
     pub fn take_outgoing_call_messages(&self) -> Vec<OutgoingCallMessage> {
         std::mem::take(&mut *self.outgoing_call_messages.lock().unwrap())
     }
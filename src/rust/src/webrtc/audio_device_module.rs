@@ -8,7 +8,7 @@ use std::{
     ffi::{c_uchar, c_void, CStr},
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc, Mutex, RwLock,
+        Arc, Mutex, PoisonError, RwLock,
     },
     time::{Duration, Instant},
 };
@@ -25,7 +25,9 @@ use crate::{
     webrtc,
     webrtc::{
         audio_device_module_utils::{copy_and_truncate_string, DeviceCollectionWrapper},
+        audio_injector::{AudioInjector, CAPTURE_SAMPLE_RATE_HZ},
         ffi::audio_device_module::RffiAudioTransport,
+        system_audio,
     },
 };
 
@@ -100,6 +102,10 @@ pub struct AudioDeviceModule {
     attempted_recording_init: bool,
     attempted_playout_start: bool,
     attempted_recording_start: bool,
+    // Application-provided audio to combine with the recorded data.
+    audio_injector: Arc<AudioInjector>,
+    // System audio to mix into the recorded data (after the application's audio).
+    system_audio_injector: Arc<AudioInjector>,
 }
 
 impl Default for AudioDeviceModule {
@@ -124,6 +130,10 @@ impl Default for AudioDeviceModule {
             attempted_recording_init: false,
             attempted_playout_start: false,
             attempted_recording_start: false,
+            audio_injector: Arc::new(AudioInjector::new()),
+            system_audio_injector: Arc::new(AudioInjector::with_max_queued_samples(
+                system_audio::MAX_QUEUED_SAMPLES,
+            )),
         }
     }
 }
@@ -147,7 +157,7 @@ const ADM_MAX_GUID_SIZE: usize = 128;
 /// Arbitrary string to uniquely identify ringrtc for creating the cubeb object.
 const ADM_CONTEXT: &CStr = c"ringrtc";
 
-const SAMPLE_FREQUENCY: u32 = 48_000;
+const SAMPLE_FREQUENCY: u32 = CAPTURE_SAMPLE_RATE_HZ;
 // Target sample latency. The actual sample latency will
 // not always match this. (it's limited by cubeb's Context::min_latency)
 const SAMPLE_LATENCY: u32 = SAMPLE_FREQUENCY / 100;
@@ -173,6 +183,19 @@ fn write_to_null_or_valid_pointer<T>(ptr: webrtc::ptr::Borrowed<T>, v: T) -> any
 
 static PAUSE_LOGGING: AtomicBool = AtomicBool::new(false);
 
+/// Called on a cubeb thread when audio devices are added or removed.
+pub type DeviceChangeListener = Box<dyn Fn() + Send>;
+
+static DEVICE_CHANGE_LISTENER: Mutex<Option<DeviceChangeListener>> = Mutex::new(None);
+
+/// Set the listener for audio device changes, replacing any previous one.
+/// It is shared by all instances of the ADM.
+pub fn set_device_change_listener(listener: Option<DeviceChangeListener>) {
+    *DEVICE_CHANGE_LISTENER
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) = listener;
+}
+
 struct LogDisableGuard;
 impl LogDisableGuard {
     fn new() -> Self {
@@ -283,6 +306,13 @@ impl AudioDeviceModule {
         if let Some(b) = (flag as *mut AtomicBool).as_ref() {
             b.store(true, Ordering::SeqCst)
         }
+        if let Some(listener) = DEVICE_CHANGE_LISTENER
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+        {
+            listener();
+        }
     }
 
     fn register_device_collection_changed(
@@ -367,6 +397,14 @@ impl AudioDeviceModule {
         0
     }
 
+    pub fn audio_injector(&self) -> Arc<AudioInjector> {
+        Arc::clone(&self.audio_injector)
+    }
+
+    pub fn system_audio_injector(&self) -> Arc<AudioInjector> {
+        Arc::clone(&self.system_audio_injector)
+    }
+
     pub fn backend_name(&self) -> Option<String> {
         self.cubeb_ctx
             .as_ref()
@@ -453,6 +491,15 @@ impl AudioDeviceModule {
                     AudioDeviceModule::device_str(device)
                 );
             }
+            if let Some(last) = last {
+                if collection.default_device_id() != last.default_device_id() {
+                    info!(
+                        "Default {:?} device changed to {:?}",
+                        device_type,
+                        Self::redact_for_logging(collection.default_device_id())
+                    );
+                }
+            }
         }
 
         match device_type {
@@ -686,7 +733,7 @@ impl AudioDeviceModule {
                 return -1;
             }
         };
-        self.playout_device = Some(device);
+        self.playout_device = Some(Self::stream_device(index, device));
         0
     }
 
@@ -720,10 +767,25 @@ impl AudioDeviceModule {
                 return -1;
             }
         };
-        self.recording_device = Some(device);
+        self.recording_device = Some(Self::stream_device(index, device));
         0
     }
 
+    /// The device to open a stream on for the given selection.
+    ///
+    /// On Linux, the default device is opened as "no device", leaving
+    /// routing to the sound server. PulseAudio and PipeWire then keep the
+    /// stream on the default and move it when the default changes or the
+    /// device is unplugged, rather than it staying pinned to whichever device
+    /// was the default when the stream was opened.
+    fn stream_device(index: u16, device: DeviceId) -> DeviceId {
+        if cfg!(target_os = "linux") && index == 0 {
+            std::ptr::null()
+        } else {
+            device
+        }
+    }
+
     pub fn set_recording_device_win(&mut self, device: WindowsDeviceType) -> i32 {
         // DefaultDevice is at index 0 and DefaultCommunicationDevice at index 1
         self.set_recording_device(if device == WindowsDeviceType::DefaultDevice {
@@ -912,6 +974,8 @@ impl AudioDeviceModule {
         .take();
         let mut builder = cubeb::StreamBuilder::<Frame>::new();
         let transport = Arc::clone(&self.audio_transport);
+        let audio_injector = Arc::clone(&self.audio_injector);
+        let system_audio_injector = Arc::clone(&self.system_audio_injector);
         let min_latency = ctx.min_latency(&params).unwrap_or_else(|e| {
             error!(
                 "Could not get min latency for recording; using default: {:?}",
@@ -945,9 +1009,12 @@ impl AudioDeviceModule {
                         buffer.extend(chunk);
                         break;
                     }
+                    let mut chunk = chunk.to_vec();
+                    audio_injector.apply(&mut chunk);
+                    system_audio_injector.apply(&mut chunk);
                     let (ret, _new_mic_level) = AudioDeviceModule::recorded_data_is_available(
                         Arc::clone(&transport),
-                        chunk.to_vec(),
+                        chunk,
                         NUM_CHANNELS,
                         SAMPLE_FREQUENCY,
                         // TODO(mutexlox): do we need different values here?
@@ -134,6 +134,11 @@ impl DeviceCollectionWrapper {
         }
     }
 
+    /// The unique ID of the device at index 0, which is the default.
+    pub fn default_device_id(&self) -> Option<&str> {
+        self.get(0).and_then(|device| device.device_id.as_deref())
+    }
+
     #[cfg(target_os = "windows")]
     /// Returns the number of devices.
     /// Note: On Windows, this is 2 smaller than the number of addressable
@@ -271,4 +276,41 @@ mod audio_device_module_tests {
         let out = webrtc::ptr::Borrowed::null();
         assert!(copy_and_truncate_string(src, out, 5).is_err());
     }
+
+    #[cfg(not(target_os = "windows"))]
+    fn output_device(device_id: &str, preferred: DevicePref) -> MinimalDeviceInfo {
+        MinimalDeviceInfo {
+            devid: std::ptr::null(),
+            device_id: Some(device_id.to_string()),
+            friendly_name: Some(device_id.to_string()),
+            #[cfg(target_os = "linux")]
+            device_type: DeviceType::OUTPUT,
+            preferred,
+            state: DeviceState::Enabled,
+        }
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    // The default follows whichever device is preferred for voice.
+    fn default_device_id_follows_preference() {
+        let before = DeviceCollectionWrapper {
+            device_collection: vec![
+                output_device("speakers", DevicePref::all()),
+                output_device("headset", DevicePref::NONE),
+            ],
+        };
+        assert_eq!(Some("speakers"), before.default_device_id());
+        let after = DeviceCollectionWrapper {
+            device_collection: vec![
+                output_device("speakers", DevicePref::NONE),
+                output_device("headset", DevicePref::all()),
+            ],
+        };
+        assert_eq!(Some("headset"), after.default_device_id());
+        let empty = DeviceCollectionWrapper {
+            device_collection: vec![],
+        };
+        assert_eq!(None, empty.default_device_id());
+    }
 }
@@ -0,0 +1,311 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Injection of application-provided audio into the outgoing audio track.
+//!
+//! Samples pushed by the application are downmixed, resampled to the rate of
+//! the capture path and queued. The audio device module then drains the queue
+//! as it delivers each 10ms chunk of microphone audio, so injected audio picks
+//! up the same capture timestamps and processing as the microphone.
+
+use std::{collections::VecDeque, fmt, sync::Mutex};
+
+use anyhow::anyhow;
+
+use crate::common::Result;
+
+/// The rate the capture path runs at.
+pub const CAPTURE_SAMPLE_RATE_HZ: u32 = 48_000;
+
+/// How much injected audio can be queued before the oldest is dropped, by
+/// default.
+const MAX_QUEUED_SAMPLES: usize = CAPTURE_SAMPLE_RATE_HZ as usize * 2;
+
+/// How injected audio is combined with the microphone.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AudioInjectionMode {
+    /// Injected audio is added to the microphone.
+    #[default]
+    Mix = 0,
+    /// The microphone is replaced, with silence when nothing is queued.
+    Replace = 1,
+}
+
+impl AudioInjectionMode {
+    pub fn from_i32(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Mix),
+            1 => Some(Self::Replace),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Resampler {
+    input_rate_hz: u32,
+    /// Position of the next output sample relative to `last`, in units of
+    /// 1/CAPTURE_SAMPLE_RATE_HZ of an input sample.
+    position: u64,
+    /// The final input sample of the previous push, if any.
+    last: Option<i16>,
+}
+
+impl Resampler {
+    fn resample(&mut self, input_rate_hz: u32, samples: &[i16], out: &mut VecDeque<i16>) {
+        if input_rate_hz != self.input_rate_hz {
+            *self = Self {
+                input_rate_hz,
+                ..Default::default()
+            };
+        }
+        if input_rate_hz == CAPTURE_SAMPLE_RATE_HZ {
+            out.extend(samples);
+            return;
+        }
+
+        // Carry the last sample over so that interpolation is continuous
+        // across pushes.
+        let input: Vec<i16> = self
+            .last
+            .into_iter()
+            .chain(samples.iter().copied())
+            .collect();
+        let Some(&last) = input.last() else {
+            return;
+        };
+        let scale = CAPTURE_SAMPLE_RATE_HZ as u64;
+        let end = (input.len() as u64 - 1) * scale;
+        while self.position < end {
+            let index = (self.position / scale) as usize;
+            let fraction = (self.position % scale) as i64;
+            let sample = (input[index] as i64 * (scale as i64 - fraction)
+                + input[index + 1] as i64 * fraction)
+                / scale as i64;
+            out.push_back(sample as i16);
+            self.position += input_rate_hz as u64;
+        }
+        self.position -= end;
+        self.last = Some(last);
+    }
+}
+
+#[derive(Default)]
+struct State {
+    mode: AudioInjectionMode,
+    queued: VecDeque<i16>,
+    resampler: Resampler,
+}
+
+/// A queue of audio to send in place of, or along with, the microphone.
+///
+/// Shared between the producer, such as the application, which pushes
+/// samples, and the audio device module, which consumes them on the capture
+/// thread.
+pub struct AudioInjector {
+    state: Mutex<State>,
+    max_queued_samples: usize,
+}
+
+impl Default for AudioInjector {
+    fn default() -> Self {
+        Self::with_max_queued_samples(MAX_QUEUED_SAMPLES)
+    }
+}
+
+impl fmt::Debug for AudioInjector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AudioInjector").finish_non_exhaustive()
+    }
+}
+
+impl AudioInjector {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// An injector that drops the oldest queued audio beyond
+    /// `max_queued_samples`, for producers that should stay close to real
+    /// time rather than build up a backlog.
+    pub fn with_max_queued_samples(max_queued_samples: usize) -> Self {
+        Self {
+            state: Default::default(),
+            max_queued_samples,
+        }
+    }
+
+    /// Queue interleaved 16-bit PCM samples for sending.
+    pub fn push(&self, samples: &[i16], sample_rate_hz: u32, channels: usize) -> Result<()> {
+        if sample_rate_hz == 0 || channels == 0 {
+            return Err(anyhow!(
+                "invalid injected audio format: {} Hz, {} channels",
+                sample_rate_hz,
+                channels
+            ));
+        }
+        if samples.len() % channels != 0 {
+            return Err(anyhow!(
+                "injected audio has a partial frame: {} samples, {} channels",
+                samples.len(),
+                channels
+            ));
+        }
+        let mono: Vec<i16> = samples
+            .chunks_exact(channels)
+            .map(|frame| (frame.iter().map(|&s| s as i32).sum::<i32>() / channels as i32) as i16)
+            .collect();
+
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| anyhow!("audio injector mutex poisoned"))?;
+        let State {
+            queued, resampler, ..
+        } = &mut *state;
+        resampler.resample(sample_rate_hz, &mono, queued);
+        if queued.len() > self.max_queued_samples {
+            let excess = queued.len() - self.max_queued_samples;
+            warn!("AudioInjector::push(): dropping {} queued samples", excess);
+            queued.drain(..excess);
+        }
+        Ok(())
+    }
+
+    pub fn set_mode(&self, mode: AudioInjectionMode) -> Result<()> {
+        info!("AudioInjector::set_mode(): {:?}", mode);
+        self.state
+            .lock()
+            .map_err(|_| anyhow!("audio injector mutex poisoned"))?
+            .mode = mode;
+        Ok(())
+    }
+
+    /// Drop any audio that has been queued but not yet sent.
+    pub fn clear(&self) -> Result<()> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| anyhow!("audio injector mutex poisoned"))?;
+        state.queued.clear();
+        state.resampler = Default::default();
+        Ok(())
+    }
+
+    /// Combine queued audio into a chunk of mono microphone samples at
+    /// CAPTURE_SAMPLE_RATE_HZ, according to the current mode.
+    pub fn apply(&self, chunk: &mut [i16]) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let mode = state.mode;
+        if mode == AudioInjectionMode::Mix && state.queued.is_empty() {
+            return;
+        }
+        for sample in chunk.iter_mut() {
+            let injected = state.queued.pop_front();
+            *sample = match mode {
+                AudioInjectionMode::Mix => sample.saturating_add(injected.unwrap_or(0)),
+                AudioInjectionMode::Replace => injected.unwrap_or(0),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mix_adds_to_microphone() {
+        let injector = AudioInjector::new();
+        injector.push(&[100, -100, i16::MAX], 48_000, 1).unwrap();
+
+        let mut chunk = [1000, 1000, 1000, 1000];
+        injector.apply(&mut chunk);
+        assert_eq!([1100, 900, i16::MAX, 1000], chunk);
+
+        // Nothing left, so the microphone passes through.
+        let mut chunk = [5, 5];
+        injector.apply(&mut chunk);
+        assert_eq!([5, 5], chunk);
+    }
+
+    #[test]
+    fn replace_silences_microphone() {
+        let injector = AudioInjector::new();
+        injector.set_mode(AudioInjectionMode::Replace).unwrap();
+        injector.push(&[7, 8], 48_000, 1).unwrap();
+
+        let mut chunk = [1000, 1000, 1000];
+        injector.apply(&mut chunk);
+        assert_eq!([7, 8, 0], chunk);
+    }
+
+    #[test]
+    fn stereo_is_downmixed() {
+        let injector = AudioInjector::new();
+        injector.set_mode(AudioInjectionMode::Replace).unwrap();
+        injector.push(&[100, 300, -10, 10], 48_000, 2).unwrap();
+
+        let mut chunk = [0, 0];
+        injector.apply(&mut chunk);
+        assert_eq!([200, 0], chunk);
+
+        assert!(injector.push(&[1, 2, 3], 48_000, 2).is_err());
+    }
+
+    #[test]
+    fn lower_rates_are_resampled() {
+        let injector = AudioInjector::new();
+        injector.set_mode(AudioInjectionMode::Replace).unwrap();
+        // 10ms at 16kHz becomes (nearly) 10ms at 48kHz, with the remainder
+        // produced once the next push arrives.
+        injector.push(&[0; 160], 16_000, 1).unwrap();
+        injector.push(&[300; 160], 16_000, 1).unwrap();
+
+        let mut chunk = [1; 960];
+        injector.apply(&mut chunk);
+        assert_eq!([0, 0, 0, 100, 200, 300], chunk[475..481]);
+        assert!(chunk[481..957].iter().all(|&s| s == 300));
+        assert_eq!([0, 0, 0], chunk[957..]);
+    }
+
+    #[test]
+    fn queue_is_bounded() {
+        let injector = AudioInjector::new();
+        injector.set_mode(AudioInjectionMode::Replace).unwrap();
+        injector
+            .push(&vec![1; MAX_QUEUED_SAMPLES + 10], 48_000, 1)
+            .unwrap();
+
+        let mut chunk = vec![5; MAX_QUEUED_SAMPLES + 1];
+        injector.apply(&mut chunk);
+        assert!(chunk[..MAX_QUEUED_SAMPLES].iter().all(|&s| s == 1));
+        assert_eq!(0, chunk[MAX_QUEUED_SAMPLES]);
+
+        injector.push(&[1, 1], 48_000, 1).unwrap();
+        injector.clear().unwrap();
+        let mut chunk = [5];
+        injector.apply(&mut chunk);
+        assert_eq!([0], chunk);
+    }
+
+    #[test]
+    fn separate_injectors_mix_over_replace() {
+        let app = AudioInjector::new();
+        app.set_mode(AudioInjectionMode::Replace).unwrap();
+        app.push(&[100, 100], 48_000, 1).unwrap();
+        let system = AudioInjector::with_max_queued_samples(3);
+        system.push(&[1, 2, 3, 4, 5], 48_000, 1).unwrap();
+
+        // The application's audio replaces the microphone, and the most recent
+        // system audio is added on top of that.
+        let mut chunk = [1000, 1000, 1000, 1000];
+        app.apply(&mut chunk);
+        system.apply(&mut chunk);
+        assert_eq!([103, 104, 5, 0], chunk);
+    }
+}
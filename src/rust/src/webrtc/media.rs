@@ -3,7 +3,10 @@
 // SPDX-License-Identifier: AGPL-3.0-only
 //
 
-use std::slice;
+use std::{
+    fmt, slice,
+    sync::{Arc, Mutex, PoisonError},
+};
 
 pub use media::{RffiAudioTrack, RffiMediaStream, RffiVideoFrameBuffer, RffiVideoTrack};
 
@@ -85,6 +88,22 @@ pub enum VideoRotation {
     Clockwise270 = 270,
 }
 
+impl VideoRotation {
+    pub fn from_degrees(degrees: i32) -> Option<Self> {
+        match degrees {
+            0 => Some(VideoRotation::None),
+            90 => Some(VideoRotation::Clockwise90),
+            180 => Some(VideoRotation::Clockwise180),
+            270 => Some(VideoRotation::Clockwise270),
+            _ => None,
+        }
+    }
+
+    pub fn degrees(self) -> i32 {
+        self as i32
+    }
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 pub struct VideoFrameMetadata {
@@ -94,6 +113,15 @@ pub struct VideoFrameMetadata {
 }
 
 impl VideoFrameMetadata {
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// How far the frame must be rotated clockwise to be displayed upright.
+    pub fn rotation(&self) -> VideoRotation {
+        self.rotation
+    }
+
     #[must_use]
     pub fn apply_rotation(&self) -> Self {
         match self.rotation {
@@ -130,6 +158,42 @@ impl VideoPixelFormat {
     }
 }
 
+/// What the outgoing video contains, which decides how it is captured and
+/// encoded. Modeled on webrtc::VideoTrackInterface::ContentHint.
+///
+/// WebRTC is only told whether the track is screen content (Detailed or
+/// Text), which switches VP8/VP9 into their screen content settings and
+/// maintains resolution over framerate. The finer distinction only changes
+/// the framerate RingRTC asks the capturer for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum VideoContentHint {
+    #[default]
+    None = 0,
+    /// Camera-like content where smooth motion matters most.
+    Fluid = 1,
+    /// Screen content such as slides, where resolution matters most.
+    Detailed = 2,
+    /// Screen content that is mostly text, which needs to stay legible.
+    Text = 3,
+}
+
+impl VideoContentHint {
+    pub fn from_i32(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(VideoContentHint::None),
+            1 => Some(VideoContentHint::Fluid),
+            2 => Some(VideoContentHint::Detailed),
+            3 => Some(VideoContentHint::Text),
+            _ => None,
+        }
+    }
+
+    /// Whether this is screen share content rather than camera video.
+    pub fn is_screen_content(self) -> bool {
+        matches!(self, VideoContentHint::Detailed | VideoContentHint::Text)
+    }
+}
+
 pub struct VideoFrame {
     metadata: VideoFrameMetadata,
     rffi_buffer: webrtc::Arc<media::RffiVideoFrameBuffer>,
@@ -148,6 +212,13 @@ impl VideoFrame {
         self.metadata.height
     }
 
+    /// How far the frame must be rotated clockwise to be displayed upright,
+    /// as set by the sender. Sinks that render the frame themselves can
+    /// apply this while drawing instead of calling apply_rotation().
+    pub fn rotation(&self) -> VideoRotation {
+        self.metadata.rotation
+    }
+
     #[must_use]
     pub fn apply_rotation(self) -> Self {
         if self.metadata.rotation == VideoRotation::None {
@@ -200,6 +271,19 @@ impl VideoFrame {
         Self::from_buffer(metadata, rffi_buffer)
     }
 
+    /// Marks the frame as needing the given rotation to be displayed
+    /// upright. The pixels are left as they are.
+    #[must_use]
+    pub fn with_rotation(self, rotation: VideoRotation) -> Self {
+        Self {
+            metadata: VideoFrameMetadata {
+                rotation,
+                ..self.metadata
+            },
+            ..self
+        }
+    }
+
     pub fn to_rgba(&self, rgba_buffer: &mut [u8]) {
         unsafe {
             media::Rust_convertVideoFrameBufferToRgba(
@@ -247,27 +331,113 @@ impl VideoFrame {
     }
 }
 
+/// Caps on the outgoing video format, for low-end devices or to save data.
+///
+/// These only ever tighten the format a call would otherwise use.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VideoFormatLimits {
+    pub max_width: Option<u16>,
+    pub max_height: Option<u16>,
+    pub max_fps: Option<u8>,
+}
+
+impl VideoFormatLimits {
+    /// Apply the limits to a (width, height, fps) output format, where a
+    /// width or height of 0 means unlimited.
+    pub fn apply(&self, (width, height, fps): (u16, u16, u8)) -> (u16, u16, u8) {
+        fn cap<T: Ord + Default + Copy>(value: T, limit: Option<T>) -> T {
+            match limit {
+                Some(limit) if value == T::default() => limit,
+                Some(limit) => value.min(limit),
+                None => value,
+            }
+        }
+        (
+            cap(width, self.max_width),
+            cap(height, self.max_height),
+            cap(fps, self.max_fps),
+        )
+    }
+}
+
+/// Transforms outgoing video frames before they are encoded, for effects
+/// such as background blur, a virtual background, or a watermark.
+///
+/// Frames are processed on the thread pushing them to the [`VideoSource`],
+/// so this should keep up with the capture rate. A frame that doesn't need
+/// changes can be returned as is, which doesn't copy it, and pixels can be
+/// read in place with [`VideoFrame::as_i420`].
+///
+/// Only frames pushed through [`VideoSource::push_frame`] are processed:
+/// those Desktop sends, and those from Rust clients such as the call simulator. The camera capturers
+/// on Android and iOS deliver frames to WebRTC's source directly, bypassing
+/// this hook, and since a processor is Rust code, it can only be set by
+/// whatever creates the source in Rust, not through the app-facing APIs.
+pub trait VideoFrameProcessor: Send {
+    /// Returns the frame to send, or None to drop it.
+    fn process(&mut self, frame: VideoFrame) -> Option<VideoFrame>;
+}
+
 /// Rust wrapper around WebRTC C++ VideoTrackSourceInterface object.
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct VideoSource {
     rffi: webrtc::Arc<media::RffiVideoSource>,
+    // Shared by all clones, so it applies wherever frames are pushed from.
+    processor: Arc<Mutex<Option<Box<dyn VideoFrameProcessor>>>>,
+}
+
+impl fmt::Debug for VideoSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("VideoSource")
+            .field("rffi", &self.rffi)
+            .finish_non_exhaustive()
+    }
 }
 
 impl VideoSource {
     pub fn new(rffi: webrtc::Arc<media::RffiVideoSource>) -> Self {
-        Self { rffi }
+        Self {
+            rffi,
+            processor: Arc::default(),
+        }
+    }
+
+    /// Runs every frame pushed from now on through the processor, replacing
+    /// any set before. None stops processing.
+    pub fn set_frame_processor(&self, processor: Option<Box<dyn VideoFrameProcessor>>) {
+        *self
+            .processor
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = processor;
     }
 
     pub fn rffi(&self) -> &webrtc::Arc<media::RffiVideoSource> {
         &self.rffi
     }
 
+    /// Send a frame as if it was captured now. Only the buffer is passed to
+    /// WebRTC, so a frame with a rotation is rotated upright first.
     pub fn push_frame(&self, frame: VideoFrame) {
+        let Some(frame) = self.process_frame(frame) else {
+            return;
+        };
+        let frame = frame.apply_rotation();
         unsafe {
             media::Rust_pushVideoFrame(self.rffi.as_borrowed(), frame.rffi_buffer.as_borrowed());
         }
     }
 
+    fn process_frame(&self, frame: VideoFrame) -> Option<VideoFrame> {
+        let mut processor = self
+            .processor
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        match processor.as_mut() {
+            Some(processor) => processor.process(frame),
+            None => Some(frame),
+        }
+    }
+
     pub fn adapt_output_format(&self, width: u16, height: u16, fps: u8) {
         unsafe {
             media::Rust_adaptOutputVideoFormat(self.rffi.as_borrowed(), width, height, fps);
@@ -312,8 +482,10 @@ impl VideoTrack {
         unsafe { media::Rust_setVideoTrackEnabled(self.rffi.as_borrowed(), enabled) }
     }
 
-    pub fn set_content_hint(&self, is_screenshare: bool) {
-        unsafe { media::Rust_setVideoTrackContentHint(self.rffi.as_borrowed(), is_screenshare) }
+    pub fn set_content_hint(&self, hint: VideoContentHint) {
+        unsafe {
+            media::Rust_setVideoTrackContentHint(self.rffi.as_borrowed(), hint.is_screen_content())
+        }
     }
 }
 
@@ -415,6 +587,12 @@ impl Default for AudioEncoderConfig {
     }
 }
 
+impl RffiAudioEncoderConfig {
+    pub fn enable_fec(&self) -> bool {
+        self.enable_fec
+    }
+}
+
 impl AudioEncoderConfig {
     pub fn rffi(&self) -> RffiAudioEncoderConfig {
         RffiAudioEncoderConfig {
@@ -432,4 +610,247 @@ impl AudioEncoderConfig {
             enable_fec: self.enable_fec,
         }
     }
+
+    /// The default config for the given profile.
+    pub fn for_profile(profile: AudioProfile) -> Self {
+        match profile {
+            AudioProfile::Voice => Self::default(),
+            AudioProfile::Music => Self {
+                // Music needs more headroom than speech, and shorter packets
+                // keep the latency of the higher bitrate down.
+                initial_packet_size_ms: 20,
+                min_packet_size_ms: 20,
+                max_packet_size_ms: 20,
+
+                initial_bitrate_bps: 96000,
+                min_bitrate_bps: 48000,
+                max_bitrate_bps: 128000,
+
+                bandwidth: AudioBandwidth::Full,
+                complexity: 10,
+                adaptation: 0,
+
+                // CBR wastes bits on quiet passages and DTX cuts off quiet
+                // passages entirely, both of which hurt music.
+                enable_cbr: false,
+                enable_dtx: false,
+                enable_fec: true,
+            },
+        }
+    }
+}
+
+/// What the outgoing audio is tuned for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "call_sim", derive(clap::ValueEnum))]
+pub enum AudioProfile {
+    /// Mono speech at a low bitrate, which suits most calls.
+    #[default]
+    Voice,
+    /// Full-band audio at a higher bitrate, for sharing music or other
+    /// high-fidelity audio.
+    Music,
+}
+
+impl AudioProfile {
+    pub fn from_i32(value: i32) -> Self {
+        match value {
+            1 => AudioProfile::Music,
+            _ => AudioProfile::Voice,
+        }
+    }
+}
+
+/// Whether a kind of audio loss protection is used.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AudioLossProtectionMode {
+    Off,
+    On,
+    /// Used only while the remote side reports enough packet loss.
+    #[default]
+    Auto,
+}
+
+impl AudioLossProtectionMode {
+    pub fn from_i32(value: i32) -> Self {
+        match value {
+            0 => AudioLossProtectionMode::Off,
+            1 => AudioLossProtectionMode::On,
+            _ => AudioLossProtectionMode::Auto,
+        }
+    }
+}
+
+/// How outgoing audio is protected against packet loss.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AudioLossProtectionConfig {
+    /// Opus in-band FEC, which is cheap but only recovers isolated losses.
+    /// It is never used if the audio encoder config disables FEC.
+    pub fec: AudioLossProtectionMode,
+    /// In Auto mode, protection is turned on once the reported loss reaches
+    /// this percentage...
+    pub enable_loss_pct: f32,
+    /// ...and off again once it drops below this one.
+    pub disable_loss_pct: f32,
+}
+
+impl Default for AudioLossProtectionConfig {
+    fn default() -> Self {
+        Self {
+            fec: AudioLossProtectionMode::On,
+            enable_loss_pct: 10.0,
+            disable_loss_pct: 5.0,
+        }
+    }
+}
+
+/// Tracks which audio loss protection is in use, following the configured
+/// modes and, for Auto, the packet loss reported by the remote side.
+#[derive(Clone, Debug)]
+pub struct AudioLossProtection {
+    config: AudioLossProtectionConfig,
+    /// Whether the loss is high enough for Auto protection.
+    high_loss: bool,
+}
+
+impl AudioLossProtection {
+    pub fn new(config: AudioLossProtectionConfig) -> Self {
+        Self {
+            config,
+            high_loss: false,
+        }
+    }
+
+    pub fn config(&self) -> &AudioLossProtectionConfig {
+        &self.config
+    }
+
+    /// Returns true if this changed whether FEC is used.
+    pub fn set_config(&mut self, config: AudioLossProtectionConfig) -> bool {
+        let before = self.fec_enabled();
+        self.config = config;
+        before != self.fec_enabled()
+    }
+
+    /// Updates the Auto state with the latest reported loss, in the range
+    /// 0-100. Returns true if this changed whether FEC is used.
+    pub fn on_packet_loss(&mut self, loss_pct: f32) -> bool {
+        let before = self.fec_enabled();
+        if loss_pct >= self.config.enable_loss_pct {
+            self.high_loss = true;
+        } else if loss_pct < self.config.disable_loss_pct {
+            self.high_loss = false;
+        }
+        before != self.fec_enabled()
+    }
+
+    fn enabled(&self, mode: AudioLossProtectionMode) -> bool {
+        match mode {
+            AudioLossProtectionMode::Off => false,
+            AudioLossProtectionMode::On => true,
+            AudioLossProtectionMode::Auto => self.high_loss,
+        }
+    }
+
+    pub fn fec_enabled(&self) -> bool {
+        self.enabled(self.config.fec)
+    }
+
+    /// The given encoder config with FEC turned off if it isn't in use.
+    pub fn apply(&self, config: &AudioEncoderConfig) -> AudioEncoderConfig {
+        AudioEncoderConfig {
+            enable_fec: config.enable_fec && self.fec_enabled(),
+            ..config.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn audio_loss_protection_auto() {
+        let mut protection = AudioLossProtection::new(AudioLossProtectionConfig {
+            fec: AudioLossProtectionMode::Auto,
+            enable_loss_pct: 10.0,
+            disable_loss_pct: 5.0,
+        });
+        assert!(!protection.fec_enabled());
+
+        assert!(!protection.on_packet_loss(9.0));
+        assert!(protection.on_packet_loss(25.0));
+        assert!(protection.fec_enabled());
+
+        // Stays on until the loss drops below the lower threshold.
+        assert!(!protection.on_packet_loss(7.0));
+        assert!(protection.fec_enabled());
+        assert!(protection.on_packet_loss(2.0));
+        assert!(!protection.fec_enabled());
+    }
+
+    #[test]
+    fn audio_loss_protection_never_enables_disabled_fec() {
+        let protection = AudioLossProtection::new(Default::default());
+        let config = AudioEncoderConfig {
+            enable_fec: false,
+            ..Default::default()
+        };
+        assert!(!protection.apply(&config).enable_fec);
+        assert!(protection.apply(&AudioEncoderConfig::default()).enable_fec);
+    }
+
+    #[test]
+    fn video_frame_metadata_rotation() {
+        let metadata = VideoFrameMetadata {
+            width: 640,
+            height: 480,
+            rotation: VideoRotation::Clockwise90,
+        };
+        assert_eq!(90, metadata.rotation().degrees());
+
+        let rotated = metadata.apply_rotation();
+        assert_eq!((480, 640), (rotated.width(), rotated.height));
+        assert_eq!(VideoRotation::None, rotated.rotation());
+
+        let metadata = VideoFrameMetadata {
+            rotation: VideoRotation::Clockwise180,
+            ..metadata
+        };
+        let rotated = metadata.apply_rotation();
+        assert_eq!((640, 480), (rotated.width(), rotated.height));
+        assert_eq!(VideoRotation::None, rotated.rotation());
+    }
+
+    #[cfg(feature = "sim")]
+    #[test]
+    fn video_frame_processor() {
+        struct Recorder {
+            widths: Arc<Mutex<Vec<u32>>>,
+        }
+
+        impl VideoFrameProcessor for Recorder {
+            fn process(&mut self, frame: VideoFrame) -> Option<VideoFrame> {
+                self.widths.lock().unwrap().push(frame.width());
+                Some(frame)
+            }
+        }
+
+        let frame = || VideoFrame::copy_from_slice(4, 2, VideoPixelFormat::I420, &[0; 12]);
+        let source = VideoSource::new(webrtc::Arc::null());
+        source.push_frame(frame());
+
+        // Set through a clone, as the capturer usually has its own.
+        let widths = Arc::default();
+        source.clone().set_frame_processor(Some(Box::new(Recorder {
+            widths: Arc::clone(&widths),
+        })));
+        source.push_frame(frame());
+        source.push_frame(frame());
+        assert_eq!(vec![4, 4], *widths.lock().unwrap());
+
+        source.set_frame_processor(None);
+        source.push_frame(frame());
+        assert_eq!(2, widths.lock().unwrap().len());
+    }
 }
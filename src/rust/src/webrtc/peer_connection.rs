@@ -4,7 +4,7 @@
 //
 
 //! WebRTC Peer Connection Interface
-use std::{ffi::CString, net::SocketAddr};
+use std::{ffi::CString, net::SocketAddr, time::Duration};
 
 pub use pc::RffiPeerConnection;
 
@@ -23,7 +23,7 @@ use crate::{
         ice_gatherer::IceGatherer,
         media::AudioEncoderConfig,
         network::RffiIpPort,
-        peer_connection_factory::RffiPeerConnectionFactoryOwner,
+        peer_connection_factory::{AudioJitterBufferConfig, RffiPeerConnectionFactoryOwner},
         peer_connection_observer::RffiPeerConnectionObserver,
         rtp,
         sdp_observer::{
@@ -78,6 +78,49 @@ pub struct RffiReceivedAudioLevel {
 pub type AudioLevel = RffiAudioLevel;
 pub type ReceivedAudioLevel = RffiReceivedAudioLevel;
 
+/// How long received audio is held to smooth out jitter.
+///
+/// A shorter delay suits interactive use, while a longer one rides out
+/// poor networks at the cost of latency. Leave a field as None for
+/// WebRTC's adaptive default. This is applied to the audio jitter buffer
+/// when the call's PeerConnection is created, and can't be changed after.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct JitterBufferConfig {
+    /// The delay the jitter buffer aims for.
+    pub target_delay: Option<Duration>,
+    /// The most delay the jitter buffer may add, however bad the jitter.
+    pub max_delay: Option<Duration>,
+}
+
+impl JitterBufferConfig {
+    /// Keeps latency down for interactive use, accepting more glitches.
+    pub fn low_latency() -> Self {
+        Self {
+            target_delay: None,
+            max_delay: Some(Duration::from_millis(150)),
+        }
+    }
+
+    /// Holds media longer so playback stays smooth on poor networks.
+    pub fn smooth() -> Self {
+        Self {
+            target_delay: Some(Duration::from_millis(250)),
+            max_delay: None,
+        }
+    }
+
+    /// The given audio jitter buffer config with these delays in place of
+    /// its own, where set.
+    pub fn apply(&self, config: &AudioJitterBufferConfig) -> AudioJitterBufferConfig {
+        let millis = |delay: Duration| delay.as_millis().try_into().unwrap_or(i32::MAX);
+        AudioJitterBufferConfig {
+            min_delay_ms: self.target_delay.map_or(config.min_delay_ms, millis),
+            max_target_delay_ms: self.max_delay.map_or(config.max_target_delay_ms, millis),
+            ..config.clone()
+        }
+    }
+}
+
 pub enum Protocol<'a> {
     Udp,
     Tcp,
@@ -9,7 +9,9 @@
 use std::ffi::c_void;
 #[cfg(feature = "native")]
 use std::ffi::CStr;
-use std::{ffi::CString, os::raw::c_char};
+#[cfg(all(not(feature = "sim"), feature = "native"))]
+use std::sync::Mutex;
+use std::{ffi::CString, os::raw::c_char, sync::Arc};
 
 use anyhow::anyhow;
 pub use pcf::{RffiPeerConnectionFactoryInterface, RffiPeerConnectionFactoryOwner};
@@ -24,11 +26,14 @@ use crate::webrtc::ffi::peer_connection_factory as pcf;
 use crate::webrtc::injectable_network::InjectableNetwork;
 #[cfg(feature = "sim")]
 use crate::webrtc::sim::peer_connection_factory as pcf;
+#[cfg(all(not(feature = "sim"), feature = "native"))]
+use crate::webrtc::system_audio::SystemAudioCapture;
 use crate::{
     common::Result,
     error::RingRtcError,
     webrtc,
     webrtc::{
+        audio_injector::AudioInjector,
         media::{AudioTrack, VideoSource, VideoTrack},
         peer_connection::PeerConnection,
         peer_connection_observer::{PeerConnectionObserver, PeerConnectionObserverTrait},
@@ -186,9 +191,17 @@ impl Default for AudioConfig {
 }
 
 impl AudioConfig {
-    // Return both the RffiAudioConfig as well as the name of the cubeb backend
-    // in use, if any.
-    fn rffi(&self) -> Result<(RffiAudioConfig, Option<String>)> {
+    // Return the RffiAudioConfig as well as the name of the cubeb backend
+    // in use, if any, and the injectors feeding its capture path.
+    #[allow(clippy::type_complexity)]
+    fn rffi(
+        &self,
+    ) -> Result<(
+        RffiAudioConfig,
+        Option<String>,
+        Option<Arc<AudioInjector>>,
+        Option<Arc<AudioInjector>>,
+    )> {
         let (input_file, output_file) =
             if self.audio_device_module_type == RffiAudioDeviceModuleType::File {
                 if let Some(file_based_adm_config) = &self.file_based_adm_config {
@@ -204,22 +217,26 @@ impl AudioConfig {
             };
 
         #[cfg(all(not(feature = "sim"), feature = "native"))]
-        let (adm_borrowed, backend_name) =
+        let (adm_borrowed, backend_name, audio_injector, system_audio_injector) =
             if self.audio_device_module_type == RffiAudioDeviceModuleType::RingRtc {
                 let mut adm = AudioDeviceModule::new();
                 // Initialize the ADM here. This isn't strictly necessary, but allows
                 // us to log the backend name (e.g. audiounit vs audiounit-rust).
                 adm.init();
                 let backend_name = adm.backend_name();
+                let audio_injector = adm.audio_injector();
+                let system_audio_injector = adm.system_audio_injector();
                 (
                     webrtc::ptr::Borrowed::from_ptr(Box::into_raw(Box::new(adm))).to_void(),
                     backend_name,
+                    Some(audio_injector),
+                    Some(system_audio_injector),
                 )
             } else {
-                (webrtc::ptr::Borrowed::null(), None)
+                (webrtc::ptr::Borrowed::null(), None, None, None)
             };
         #[cfg(any(feature = "sim", not(feature = "native")))]
-        let backend_name = None;
+        let (backend_name, audio_injector, system_audio_injector) = (None, None, None);
 
         Ok((
             RffiAudioConfig {
@@ -237,6 +254,8 @@ impl AudioConfig {
                     .to_void(),
             },
             backend_name,
+            audio_injector,
+            system_audio_injector,
         ))
     }
 }
@@ -294,6 +313,11 @@ pub struct PeerConnectionFactory {
     #[cfg(feature = "native")]
     device_counts: DeviceCounts,
     backend_name: Option<String>,
+    audio_injector: Option<Arc<AudioInjector>>,
+    system_audio_injector: Option<Arc<AudioInjector>>,
+
+    #[cfg(all(not(feature = "sim"), feature = "native"))]
+    system_audio: Arc<Mutex<Option<SystemAudioCapture>>>,
 }
 
 impl PeerConnectionFactory {
@@ -302,7 +326,8 @@ impl PeerConnectionFactory {
     pub fn new(audio_config: &AudioConfig, use_injectable_network: bool) -> Result<Self> {
         debug!("PeerConnectionFactory::new()");
 
-        let (audio_config_rffi, backend_name) = audio_config.rffi()?;
+        let (audio_config_rffi, backend_name, audio_injector, system_audio_injector) =
+            audio_config.rffi()?;
 
         let rffi = unsafe {
             webrtc::Arc::from_owned(pcf::Rust_createPeerConnectionFactory(
@@ -318,6 +343,10 @@ impl PeerConnectionFactory {
             #[cfg(feature = "native")]
             device_counts: Default::default(),
             backend_name,
+            audio_injector,
+            system_audio_injector,
+            #[cfg(all(not(feature = "sim"), feature = "native"))]
+            system_audio: Default::default(),
         })
     }
 
@@ -341,6 +370,10 @@ impl PeerConnectionFactory {
             #[cfg(feature = "native")]
             device_counts: Default::default(),
             backend_name: None,
+            audio_injector: None,
+            system_audio_injector: None,
+            #[cfg(all(not(feature = "sim"), feature = "native"))]
+            system_audio: Default::default(),
         }
     }
 
@@ -544,6 +577,14 @@ impl PeerConnectionFactory {
         Ok(devices)
     }
 
+    /// Select the playout device with the given unique ID, which unlike its
+    /// index doesn't change as other devices come and go.
+    #[cfg(feature = "native")]
+    pub fn set_audio_playout_device_by_id(&mut self, unique_id: &str) -> Result<()> {
+        let index = find_audio_device(&self.get_audio_playout_devices()?, unique_id)?;
+        self.set_audio_playout_device(index)
+    }
+
     #[cfg(feature = "native")]
     pub fn set_audio_playout_device(&mut self, index: u16) -> Result<()> {
         #[cfg(target_os = "windows")]
@@ -660,6 +701,14 @@ impl PeerConnectionFactory {
         Ok(devices)
     }
 
+    /// Select the recording device with the given unique ID, which unlike its
+    /// index doesn't change as other devices come and go.
+    #[cfg(feature = "native")]
+    pub fn set_audio_recording_device_by_id(&mut self, unique_id: &str) -> Result<()> {
+        let index = find_audio_device(&self.get_audio_recording_devices()?, unique_id)?;
+        self.set_audio_recording_device(index)
+    }
+
     #[cfg(feature = "native")]
     pub fn set_audio_recording_device(&mut self, index: u16) -> Result<()> {
         #[cfg(target_os = "windows")]
@@ -684,7 +733,82 @@ impl PeerConnectionFactory {
         }
     }
 
+    /// Call the listener whenever audio devices are added or removed, replacing
+    /// any previous listener. Only the RingRTC audio device module reports
+    /// changes; with others, the listener is never called.
+    #[cfg(feature = "native")]
+    pub fn set_audio_device_change_listener(&self, listener: Option<Box<dyn Fn() + Send>>) {
+        #[cfg(not(feature = "sim"))]
+        crate::webrtc::audio_device_module::set_device_change_listener(listener);
+        #[cfg(feature = "sim")]
+        let _ = listener;
+    }
+
     pub fn audio_backend(&self) -> Option<String> {
         self.backend_name.clone()
     }
+
+    /// The queue for sending application-provided audio, which is only
+    /// available with the RingRTC audio device module.
+    pub fn audio_injector(&self) -> Result<&Arc<AudioInjector>> {
+        self.audio_injector
+            .as_ref()
+            .ok_or_else(|| anyhow!("audio injection requires the RingRTC audio device module"))
+    }
+
+    /// The queue that shared system audio is mixed in from, separately from
+    /// [`Self::audio_injector`]. Only available with the RingRTC audio device
+    /// module.
+    pub fn system_audio_injector(&self) -> Result<&Arc<AudioInjector>> {
+        self.system_audio_injector
+            .as_ref()
+            .ok_or_else(|| anyhow!("system audio capture requires the RingRTC audio device module"))
+    }
+
+    /// Start or stop sending the audio the system is playing along with the
+    /// microphone, such as while sharing the screen. Only supported on
+    /// Windows with the RingRTC audio device module.
+    ///
+    /// Remote participants' audio is part of what the system plays. It goes
+    /// through echo cancellation like the microphone does, so they aren't
+    /// sent back their own audio as long as AEC is enabled.
+    #[cfg(feature = "native")]
+    pub fn share_system_audio(&self, enabled: bool) -> Result<()> {
+        info!("PeerConnectionFactory::share_system_audio({})", enabled);
+
+        #[cfg(not(feature = "sim"))]
+        {
+            let mut system_audio = self
+                .system_audio
+                .lock()
+                .map_err(|_| anyhow!("system audio mutex poisoned"))?;
+            let system_audio_injector = self.system_audio_injector()?;
+            if !enabled {
+                *system_audio = None;
+                // Don't let what was queued trail into the call after it's stopped.
+                system_audio_injector.clear()?;
+            } else if system_audio.is_none() {
+                *system_audio = Some(SystemAudioCapture::start(Arc::clone(
+                    system_audio_injector,
+                ))?);
+            }
+            Ok(())
+        }
+        #[cfg(feature = "sim")]
+        if enabled {
+            Err(anyhow!("system audio capture is not simulated"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The index of the device with the given unique ID, as used to select it.
+#[cfg(feature = "native")]
+fn find_audio_device(devices: &[AudioDevice], unique_id: &str) -> Result<u16> {
+    devices
+        .iter()
+        .position(|device| device.unique_id == unique_id)
+        .map(|index| index as u16)
+        .ok_or_else(|| RingRtcError::AudioDeviceNotFound.into())
 }
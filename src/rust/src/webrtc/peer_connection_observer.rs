@@ -93,6 +93,18 @@ pub enum NetworkAdapterType {
     Cellular5G = 1 << 9,
 }
 
+/// The type of an ICE candidate.
+/// Stays in sync with the C++ value in rffi_defs.h.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum CandidateType {
+    Unknown,
+    Host,
+    ServerReflexive,
+    PeerReflexive,
+    Relay,
+}
+
 /// Ice Network Route structure passed between Rust and C++.
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
@@ -104,6 +116,13 @@ pub struct NetworkRoute {
     pub remote_relayed: bool,
 }
 
+impl NetworkRoute {
+    /// Whether media goes through a TURN server on either side.
+    pub fn relayed(&self) -> bool {
+        self.local_relayed || self.remote_relayed
+    }
+}
+
 /// The callbacks from C++ will ultimately go to an impl of this.
 /// I can't think of a better name :).
 pub trait PeerConnectionObserverTrait {
@@ -94,7 +94,7 @@ pub struct SessionDescription {
 }
 
 #[repr(C)]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum RffiVideoCodecType {
     Vp8 = 8,
     Vp9 = 9,
@@ -234,6 +234,11 @@ impl SessionDescription {
             ice_pwd: Some(ice_pwd),
             receive_video_codecs,
             max_bitrate_bps: Some(data_mode.max_bitrate().as_bps()),
+            ice_restart_supported: Some(true),
+            relay_only: Some(call_config.relay_only),
+            handoff: None,
+            early_media: Some(call_config.early_media),
+            call_id_extension: None,
         })
     }
 
@@ -43,9 +43,9 @@ pub unsafe fn Rust_setVideoTrackEnabled(
 #[allow(non_snake_case, clippy::missing_safety_doc)]
 pub unsafe fn Rust_setVideoTrackContentHint(
     _track: webrtc::ptr::BorrowedRc<RffiVideoTrack>,
-    _is_screenshare: bool,
+    is_screenshare: bool,
 ) {
-    info!("Rust_setVideoTrackContentHint()");
+    info!("Rust_setVideoTrackContentHint({})", is_screenshare);
 }
 
 #[allow(non_snake_case, clippy::missing_safety_doc)]
@@ -61,10 +61,14 @@ impl RffiPeerConnection {
                 local_description_set: false,
                 remote_description_set: false,
                 outgoing_audio_enabled: false,
+                // Like WebRTC, audio playout and recording start out enabled.
+                audio_playout_enabled: true,
+                audio_recording_enabled: true,
                 rtp_packet_sink: None,
                 removed_ice_candidates: vec![],
                 max_bitrate_bps: None,
                 last_sent_rtp_data: None,
+                audio_fec_enabled: None,
             })),
         }
     }
@@ -92,6 +96,22 @@ impl RffiPeerConnection {
         state.outgoing_audio_enabled
     }
 
+    fn set_audio_playout_enabled(&self, enabled: bool) {
+        let mut state = self.state.lock().unwrap();
+        state.audio_playout_enabled = enabled;
+    }
+
+    fn set_audio_recording_enabled(&self, enabled: bool) {
+        let mut state = self.state.lock().unwrap();
+        state.audio_recording_enabled = enabled;
+    }
+
+    /// Whether audio playout and recording are both enabled.
+    pub fn audio_enabled(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state.audio_playout_enabled && state.audio_recording_enabled
+    }
+
     fn set_incoming_media_enabled(&self, _enabled: bool) {
         let _state = self.state.lock().unwrap();
         // Do nothing; the sim implementation doesn't use this.
@@ -128,6 +148,11 @@ impl RffiPeerConnection {
         )
     }
 
+    pub fn audio_fec_enabled(&self) -> Option<bool> {
+        let state = self.state.lock().unwrap();
+        state.audio_fec_enabled
+    }
+
     pub fn last_sent_max_bitrate_bps(&self) -> Option<u64> {
         self.last_sent_rtp_message()?
             .receiver_status?
@@ -141,10 +166,13 @@ struct RffiPeerConnectionState {
     local_description_set: bool,
     remote_description_set: bool,
     outgoing_audio_enabled: bool,
+    audio_playout_enabled: bool,
+    audio_recording_enabled: bool,
     rtp_packet_sink: Option<BoxedRtpPacketSink>,
     removed_ice_candidates: Vec<SocketAddr>,
     max_bitrate_bps: Option<i32>,
     last_sent_rtp_data: Option<Vec<u8>>,
+    audio_fec_enabled: Option<bool>,
 }
 
 #[allow(non_snake_case, clippy::missing_safety_doc)]
@@ -214,18 +242,20 @@ pub unsafe fn Rust_setIncomingMediaEnabled(
 
 #[allow(non_snake_case, clippy::missing_safety_doc)]
 pub unsafe fn Rust_setAudioPlayoutEnabled(
-    _peer_connection: webrtc::ptr::BorrowedRc<RffiPeerConnection>,
-    _enabled: bool,
+    peer_connection: webrtc::ptr::BorrowedRc<RffiPeerConnection>,
+    enabled: bool,
 ) {
-    info!("Rust_setAudioPlayoutEnabled:");
+    info!("Rust_setAudioPlayoutEnabled({})", enabled);
+    (*peer_connection.as_ptr()).set_audio_playout_enabled(enabled);
 }
 
 #[allow(non_snake_case, clippy::missing_safety_doc)]
 pub unsafe fn Rust_setAudioRecordingEnabled(
-    _peer_connection: webrtc::ptr::BorrowedRc<RffiPeerConnection>,
-    _enabled: bool,
+    peer_connection: webrtc::ptr::BorrowedRc<RffiPeerConnection>,
+    enabled: bool,
 ) {
-    info!("Rust_setAudioRecordingEnabled:");
+    info!("Rust_setAudioRecordingEnabled({})", enabled);
+    (*peer_connection.as_ptr()).set_audio_recording_enabled(enabled);
 }
 
 #[allow(non_snake_case, clippy::missing_safety_doc)]
@@ -338,10 +368,14 @@ pub unsafe fn Rust_receiveRtp(
 
 #[allow(non_snake_case, clippy::missing_safety_doc)]
 pub unsafe fn Rust_configureAudioEncoders(
-    _peer_connection: webrtc::ptr::BorrowedRc<RffiPeerConnection>,
-    _config: webrtc::ptr::Borrowed<RffiAudioEncoderConfig>,
+    peer_connection: webrtc::ptr::BorrowedRc<RffiPeerConnection>,
+    config: webrtc::ptr::Borrowed<RffiAudioEncoderConfig>,
 ) {
     info!("Rust_configureAudioEncoders:");
+    if let Some(config) = config.as_ref() {
+        let mut state = (*peer_connection.as_ptr()).state.lock().unwrap();
+        state.audio_fec_enabled = Some(config.enable_fec());
+    }
 }
 
 #[allow(non_snake_case, clippy::missing_safety_doc)]
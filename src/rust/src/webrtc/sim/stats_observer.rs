@@ -40,6 +40,8 @@ pub unsafe fn Rust_createStatsObserver(
             current_round_trip_time: 0.0,
             available_outgoing_bitrate: 0.0,
         },
+        candidate_pair_statistics_size: 0,
+        candidate_pair_statistics: ptr::null(),
     };
 
     // Hit on the onComplete() callback
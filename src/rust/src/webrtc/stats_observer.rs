@@ -7,7 +7,8 @@
 
 use std::{
     borrow::Cow,
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
+    fmt::{self, Write},
     slice,
     sync::Mutex,
     time::{Duration, Instant},
@@ -21,13 +22,36 @@ pub use crate::webrtc::ffi::stats_observer::RffiStatsObserver;
 use crate::webrtc::sim::stats_observer as stats;
 #[cfg(feature = "sim")]
 pub use crate::webrtc::sim::stats_observer::RffiStatsObserver;
-use crate::{common::CallId, webrtc};
+use crate::{
+    common::CallId,
+    lite::metrics,
+    webrtc,
+    webrtc::{
+        peer_connection::JitterBufferConfig,
+        peer_connection_observer::{CandidateType, TransportProtocol},
+        sdp_observer::RffiVideoCodecType,
+    },
+};
 
 /// How often to clean up old stats.
 const CLEAN_UP_STATS_TICKS: u32 = 60;
 
 const MAX_STATS_AGE: Duration = Duration::from_secs(60 * 10);
 
+/// Caps the memory used by very long calls at about four hours of points one
+/// second apart. The oldest points are dropped first.
+const MAX_TIME_SERIES_POINTS: usize = 4 * 60 * 60;
+
+/// How long each per-minute quality estimate covers.
+const MOS_MINUTE_US: i64 = 60 * 1_000_000;
+
+/// E-model parameters for Opus, the only audio codec used, with in-band FEC:
+/// its impairment with no loss, how well it copes with random packet loss,
+/// and the delay added by framing and lookahead, in milliseconds.
+const OPUS_EQUIPMENT_IMPAIRMENT: f64 = 0.0;
+const OPUS_PACKET_LOSS_ROBUSTNESS: f64 = 20.0;
+const OPUS_CODEC_DELAY_MS: f64 = 26.5;
+
 #[derive(Debug, Default)]
 struct Stats {
     timestamp_us: i64,
@@ -42,6 +66,11 @@ struct Stats {
     video_recv: HashMap<u32, (Instant, VideoReceiverStatistics)>,
 
     report_json: Mutex<String>,
+    receive_quality_samples: Mutex<Vec<ReceiveQualitySample>>,
+    media_usage: Mutex<MediaUsage>,
+    snapshot: Mutex<Option<StatsSnapshot>>,
+    minute_mos: MinuteMos,
+    time_series: Mutex<StatsTimeSeries>,
 }
 /// Collector object for obtaining statistics.
 #[derive(Debug)]
@@ -333,6 +362,27 @@ impl StatsObserver {
         *stats_report_json = report_json;
         drop(stats_report_json);
 
+        let mut receive_quality_samples = Vec::new();
+        let mut media_usage = MediaUsage::default();
+        let mut freezes = 0;
+        let mut snapshot = StatsSnapshot {
+            timestamp_us: media_statistics.timestamp_us,
+            round_trip_time: media_statistics
+                .connection_statistics
+                .current_round_trip_time,
+            available_outgoing_bitrate_bps: media_statistics
+                .connection_statistics
+                .available_outgoing_bitrate,
+            ..Default::default()
+        };
+        let round_trip_time = media_statistics
+            .connection_statistics
+            .current_round_trip_time;
+        media_usage.add_round_trip_time(round_trip_time);
+        if round_trip_time > 0.0 {
+            metrics::set_gauge(metrics::ROUND_TRIP_TIME, round_trip_time);
+        }
+
         if media_statistics.audio_sender_statistics_size > 0 {
             let audio_senders = unsafe {
                 if media_statistics.audio_sender_statistics.is_null() {
@@ -348,6 +398,16 @@ impl StatsObserver {
                 let prev_audio_send_stats = stats.audio_send.entry(audio_sender.ssrc).or_default();
 
                 Self::print_audio_sender(audio_sender, prev_audio_send_stats, seconds_elapsed);
+                snapshot
+                    .audio_send
+                    .push(OutboundStreamStats::from_audio_sender(
+                        audio_sender,
+                        prev_audio_send_stats,
+                        seconds_elapsed,
+                    ));
+                media_usage.audio_bytes_sent += audio_sender
+                    .bytes_sent
+                    .saturating_sub(prev_audio_send_stats.bytes_sent);
 
                 *prev_audio_send_stats = audio_sender.clone();
             }
@@ -372,6 +432,16 @@ impl StatsObserver {
                 }
 
                 Self::print_video_sender(video_sender, prev_video_send_stats, seconds_elapsed);
+                snapshot
+                    .video_send
+                    .push(OutboundStreamStats::from_video_sender(
+                        video_sender,
+                        prev_video_send_stats,
+                        seconds_elapsed,
+                    ));
+                media_usage.video_bytes_sent += video_sender
+                    .bytes_sent
+                    .saturating_sub(prev_video_send_stats.bytes_sent);
 
                 *prev_video_send_stats = video_sender.clone();
             }
@@ -395,6 +465,20 @@ impl StatsObserver {
                     .or_insert_with(|| (Instant::now(), Default::default()));
 
                 Self::print_audio_receiver(audio_receiver, prev_audio_recv_stats, seconds_elapsed);
+                snapshot
+                    .audio_recv
+                    .push(InboundStreamStats::from_audio_receiver(
+                        audio_receiver,
+                        prev_audio_recv_stats,
+                        seconds_elapsed,
+                    ));
+                media_usage.audio_bytes_received += audio_receiver
+                    .bytes_received
+                    .saturating_sub(prev_audio_recv_stats.bytes_received);
+                receive_quality_samples.push(ReceiveQualitySample::from_audio_receiver(
+                    audio_receiver,
+                    prev_audio_recv_stats,
+                ));
 
                 *updated_at = Instant::now();
                 *prev_audio_recv_stats = audio_receiver.clone();
@@ -419,12 +503,67 @@ impl StatsObserver {
                     .or_insert_with(|| (Instant::now(), Default::default()));
 
                 Self::print_video_receiver(video_receiver, prev_video_recv_stats, seconds_elapsed);
+                snapshot
+                    .video_recv
+                    .push(InboundStreamStats::from_video_receiver(
+                        video_receiver,
+                        prev_video_recv_stats,
+                        seconds_elapsed,
+                    ));
+                media_usage.video_bytes_received += video_receiver
+                    .bytes_received
+                    .saturating_sub(prev_video_recv_stats.bytes_received);
+                freezes += video_receiver
+                    .freeze_count
+                    .saturating_sub(prev_video_recv_stats.freeze_count);
+                metrics::increment_counter(
+                    metrics::VIDEO_FRAMES_DROPPED,
+                    video_receiver
+                        .frames_dropped
+                        .saturating_sub(prev_video_recv_stats.frames_dropped)
+                        as u64,
+                );
+                receive_quality_samples.push(ReceiveQualitySample::from_video_receiver(
+                    video_receiver,
+                    prev_video_recv_stats,
+                ));
 
                 *updated_at = Instant::now();
                 *prev_video_recv_stats = video_receiver.clone();
             }
         }
 
+        snapshot.mos = snapshot.estimate_audio_mos();
+        if let Some(mos) = snapshot.mos {
+            media_usage.add_mos(mos);
+            if let Some(minute_mos) = stats.minute_mos.add(snapshot.timestamp_us, mos) {
+                media_usage.add_minute_mos(minute_mos);
+            }
+        }
+        snapshot.minute_mos = stats.minute_mos.last_minute;
+        stats
+            .time_series
+            .lock()
+            .unwrap()
+            .push(StatsTimeSeriesPoint::from_snapshot(&snapshot, freezes));
+
+        if media_statistics.candidate_pair_statistics_size > 0 {
+            let candidate_pairs = unsafe {
+                if media_statistics.candidate_pair_statistics.is_null() {
+                    &[]
+                } else {
+                    slice::from_raw_parts(
+                        media_statistics.candidate_pair_statistics,
+                        media_statistics.candidate_pair_statistics_size as usize,
+                    )
+                }
+            };
+            snapshot.candidate_pairs = candidate_pairs.to_vec();
+        }
+
+        *stats.receive_quality_samples.lock().unwrap() = receive_quality_samples;
+        stats.media_usage.lock().unwrap().accumulate(&media_usage);
+        *stats.snapshot.lock().unwrap() = Some(snapshot);
         stats.timestamp_us = media_statistics.timestamp_us;
 
         self.stats_received_count += 1;
@@ -464,6 +603,28 @@ impl StatsObserver {
         }
     }
 
+    /// Returns the receive quality of each incoming stream over the most recent
+    /// stats interval, or an empty list if no new stats have arrived since the last call.
+    pub fn take_receive_quality_samples(&self) -> Vec<ReceiveQualitySample> {
+        std::mem::take(&mut *self.stats.receive_quality_samples.lock().unwrap())
+    }
+
+    /// Returns the media usage accumulated over all stats reports received so far.
+    pub fn media_usage(&self) -> MediaUsage {
+        self.stats.media_usage.lock().unwrap().clone()
+    }
+
+    /// Returns a point for every stats report received so far.
+    pub fn time_series(&self) -> StatsTimeSeries {
+        self.stats.time_series.lock().unwrap().clone()
+    }
+
+    /// Returns the stats for each stream over the most recent stats interval,
+    /// or None if no stats have arrived yet.
+    pub fn stats_snapshot(&self) -> Option<StatsSnapshot> {
+        self.stats.snapshot.lock().unwrap().clone()
+    }
+
     pub fn set_collect_raw_stats_report(&self, collect_raw_stats_report: bool) {
         unsafe {
             stats::Rust_setCollectRawStatsReport(self.rffi.as_borrowed(), collect_raw_stats_report)
@@ -504,6 +665,8 @@ pub struct VideoSenderStatistics {
     pub remote_packets_lost: i32,
     pub remote_jitter: f64,
     pub remote_round_trip_time: f64,
+    /// An RffiVideoCodecType, or 0 if not known yet.
+    pub codec_type: i32,
 }
 
 impl VideoSenderStatistics {
@@ -544,6 +707,8 @@ pub struct AudioReceiverStatistics {
     pub total_audio_energy: f64,
     pub jitter_buffer_delay: f64,
     pub jitter_buffer_emitted_count: u64,
+    /// In milliseconds of the sender's NTP clock, or 0 if not known yet.
+    pub estimated_playout_timestamp: f64,
 }
 
 #[repr(C)]
@@ -558,6 +723,729 @@ pub struct VideoReceiverStatistics {
     pub total_decode_time: f64,
     pub frame_width: u32,
     pub frame_height: u32,
+    pub frames_dropped: u32,
+    pub freeze_count: u32,
+    /// An RffiVideoCodecType, or 0 if not known yet.
+    pub codec_type: i32,
+    /// In milliseconds of the sender's NTP clock, or 0 if not known yet.
+    pub estimated_playout_timestamp: f64,
+}
+
+fn video_codec_from_stats(codec_type: i32) -> Option<RffiVideoCodecType> {
+    match codec_type {
+        8 => Some(RffiVideoCodecType::Vp8),
+        9 => Some(RffiVideoCodecType::Vp9),
+        _ => None,
+    }
+}
+
+/// The change in a counter over a stats interval, as a rate per second.
+fn per_second<T: Into<f64>>(delta: T, seconds_elapsed: f32) -> f64 {
+    if seconds_elapsed > 0.0 {
+        delta.into() / seconds_elapsed as f64
+    } else {
+        0.0
+    }
+}
+
+/// The average time each audio sample spent in the jitter buffer over a
+/// stats interval, in seconds.
+fn average_jitter_buffer_delay(
+    jitter_buffer_delay: f64,
+    prev_jitter_buffer_delay: f64,
+    jitter_buffer_emitted_count: u64,
+    prev_jitter_buffer_emitted_count: u64,
+) -> f64 {
+    let emitted_count =
+        jitter_buffer_emitted_count.saturating_sub(prev_jitter_buffer_emitted_count);
+    if emitted_count > 0 {
+        (jitter_buffer_delay - prev_jitter_buffer_delay) / emitted_count as f64
+    } else {
+        0.0
+    }
+}
+
+/// Converts a stats playout timestamp from milliseconds to seconds, treating 0
+/// as unknown.
+fn estimated_playout_timestamp(estimated_playout_timestamp_ms: f64) -> Option<f64> {
+    if estimated_playout_timestamp_ms > 0.0 {
+        Some(estimated_playout_timestamp_ms / 1000.0)
+    } else {
+        None
+    }
+}
+
+/// Why an outgoing video stream is being sent at lower quality than it
+/// otherwise would be.
+// Must be kept in sync with the TypeScript enum.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum QualityLimitationReason {
+    /// The CPU overuse detector is adapting, such as when the device is
+    /// thermally throttled.
+    Cpu = 1,
+    Bandwidth,
+    Other,
+}
+
+impl QualityLimitationReason {
+    /// Converts the RTCQualityLimitationReason from the stats, where 0 means
+    /// the stream is not limited.
+    fn from_stats(quality_limitation_reason: u32) -> Option<Self> {
+        match quality_limitation_reason {
+            1 => Some(Self::Cpu),
+            2 => Some(Self::Bandwidth),
+            3 => Some(Self::Other),
+            _ => None,
+        }
+    }
+}
+
+/// A structured view of the most recent stats report, for the application.
+///
+/// Rates cover the interval since the previous report. Times are in
+/// seconds, like the underlying WebRTC stats.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatsSnapshot {
+    pub timestamp_us: i64,
+    pub round_trip_time: f64,
+    pub available_outgoing_bitrate_bps: f64,
+    pub audio_send: Vec<OutboundStreamStats>,
+    pub video_send: Vec<OutboundStreamStats>,
+    pub audio_recv: Vec<InboundStreamStats>,
+    pub video_recv: Vec<InboundStreamStats>,
+    /// The jitter buffer delays in effect, as set on the call.
+    pub jitter_buffer: JitterBufferConfig,
+    /// Audio/video sync for each remote sender. Filled in by the call, which
+    /// knows which incoming streams come from the same sender.
+    pub av_sync: Vec<AvSyncStats>,
+    /// The estimated audio quality over the interval; see
+    /// [StatsSnapshot::estimate_audio_mos].
+    pub mos: Option<f32>,
+    /// The average estimated audio quality over the most recent full minute
+    /// of the call, or None if the call hasn't had audio for a minute yet.
+    pub minute_mos: Option<f32>,
+    /// Every ICE candidate pair being checked or used.
+    pub candidate_pairs: Vec<CandidatePairStatistics>,
+}
+
+impl StatsSnapshot {
+    /// Why outgoing video is being adapted, if any stream being sent is. CPU
+    /// takes precedence over bandwidth, then over anything else.
+    pub fn video_send_limitation(&self) -> Option<QualityLimitationReason> {
+        self.video_send
+            .iter()
+            .filter(|stream| stream.packets_per_second > 0.0)
+            .filter_map(|stream| stream.quality_limitation_reason)
+            .min()
+    }
+
+    /// The candidate pair media is being sent over, if one has been selected.
+    pub fn selected_candidate_pair(&self) -> Option<&CandidatePairStatistics> {
+        self.candidate_pairs.iter().find(|pair| pair.selected)
+    }
+
+    /// Estimates the mean opinion score (MOS) of the audio over the interval,
+    /// from 1 (bad) to 4.5 (excellent). Both what is received and, as reported
+    /// back, what is sent are scored, and the worst stream counts. None if no
+    /// audio is flowing in either direction.
+    ///
+    /// Below about 3.5, most people would rate the call as poor.
+    pub fn estimate_audio_mos(&self) -> Option<f32> {
+        let received = self
+            .audio_recv
+            .iter()
+            .filter(|stream| stream.packets_per_second > 0.0)
+            .map(|stream| {
+                let buffering = if stream.jitter_buffer_delay > 0.0 {
+                    stream.jitter_buffer_delay
+                } else {
+                    stream.jitter * 2.0
+                };
+                estimate_mos(
+                    self.round_trip_time / 2.0 + buffering,
+                    stream.packets_lost_pct,
+                )
+            });
+        let sent = self
+            .audio_send
+            .iter()
+            .filter(|stream| stream.packets_per_second > 0.0)
+            .map(|stream| {
+                let round_trip_time = if stream.remote_round_trip_time > 0.0 {
+                    stream.remote_round_trip_time
+                } else {
+                    self.round_trip_time
+                };
+                estimate_mos(
+                    round_trip_time / 2.0 + stream.remote_jitter * 2.0,
+                    stream.remote_packets_lost_pct,
+                )
+            });
+        received.chain(sent).min_by(f32::total_cmp)
+    }
+}
+
+/// Estimates a mean opinion score with a simplified ITU-T G.107 E-model, from
+/// the one-way delay in seconds (excluding the codec) and the percentage of
+/// packets lost.
+fn estimate_mos(one_way_delay: f64, packets_lost_pct: f32) -> f32 {
+    let delay_ms = one_way_delay * 1000.0 + OPUS_CODEC_DELAY_MS;
+    let mut delay_impairment = 0.024 * delay_ms;
+    if delay_ms > 177.3 {
+        delay_impairment += 0.11 * (delay_ms - 177.3);
+    }
+    let loss = packets_lost_pct.clamp(0.0, 100.0) as f64;
+    let loss_impairment = OPUS_EQUIPMENT_IMPAIRMENT
+        + (95.0 - OPUS_EQUIPMENT_IMPAIRMENT) * loss / (loss + OPUS_PACKET_LOSS_ROBUSTNESS);
+    let r = (93.2 - delay_impairment - loss_impairment).clamp(0.0, 100.0);
+    (1.0 + 0.035 * r + 7.0e-6 * r * (r - 60.0) * (100.0 - r)) as f32
+}
+
+/// The overall quality of a call at one stats report.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatsTimeSeriesPoint {
+    pub timestamp_us: i64,
+    pub send_bitrate_bps: f64,
+    pub receive_bitrate_bps: f64,
+    /// The worst of any incoming stream, in the range 0-100.
+    pub packets_lost_pct: f32,
+    /// The worst reported back for any outgoing stream, in the range 0-100.
+    pub remote_packets_lost_pct: f32,
+    pub round_trip_time: f64,
+    /// How many times incoming video froze during the interval.
+    pub freezes: u32,
+}
+
+impl StatsTimeSeriesPoint {
+    fn from_snapshot(snapshot: &StatsSnapshot, freezes: u32) -> Self {
+        let flowing_inbound = snapshot
+            .audio_recv
+            .iter()
+            .chain(&snapshot.video_recv)
+            .filter(|stream| stream.packets_per_second > 0.0);
+        let flowing_outbound = snapshot
+            .audio_send
+            .iter()
+            .chain(&snapshot.video_send)
+            .filter(|stream| stream.packets_per_second > 0.0);
+        Self {
+            timestamp_us: snapshot.timestamp_us,
+            send_bitrate_bps: flowing_outbound
+                .clone()
+                .map(|stream| stream.bitrate_bps)
+                .sum(),
+            receive_bitrate_bps: flowing_inbound
+                .clone()
+                .map(|stream| stream.bitrate_bps)
+                .sum(),
+            packets_lost_pct: flowing_inbound
+                .map(|stream| stream.packets_lost_pct)
+                .fold(0.0, f32::max),
+            remote_packets_lost_pct: flowing_outbound
+                .map(|stream| stream.remote_packets_lost_pct)
+                .fold(0.0, f32::max),
+            round_trip_time: snapshot.round_trip_time,
+            freezes,
+        }
+    }
+}
+
+/// A point for every stats report over the life of a call, so that quality
+/// regressions can be analyzed offline without scraping logs.
+///
+/// Points are as far apart as the stats interval, so per-second points need a
+/// stats interval of one second.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatsTimeSeries {
+    points: VecDeque<StatsTimeSeriesPoint>,
+}
+
+impl StatsTimeSeries {
+    fn push(&mut self, point: StatsTimeSeriesPoint) {
+        if self.points.len() == MAX_TIME_SERIES_POINTS {
+            self.points.pop_front();
+        }
+        self.points.push_back(point);
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = &StatsTimeSeriesPoint> {
+        self.points.iter()
+    }
+
+    /// Formats the points as CSV, with a header row, rounding to whole units.
+    /// Times are relative to the first point.
+    pub fn export(&self) -> String {
+        let mut csv = String::from(
+            "elapsed_ms,send_kbps,receive_kbps,loss_pct,remote_loss_pct,rtt_ms,freezes\n",
+        );
+        let start_us = self.points.front().map_or(0, |point| point.timestamp_us);
+        for point in &self.points {
+            // Writing to a String can't fail.
+            let _ = writeln!(
+                csv,
+                "{},{:.0},{:.0},{:.1},{:.1},{:.0},{}",
+                (point.timestamp_us - start_us) / 1000,
+                point.send_bitrate_bps / 1000.0,
+                point.receive_bitrate_bps / 1000.0,
+                point.packets_lost_pct,
+                point.remote_packets_lost_pct,
+                point.round_trip_time * 1000.0,
+                point.freezes,
+            );
+        }
+        csv
+    }
+}
+
+/// Averages the estimated MOS of each stats interval over each minute of a
+/// call, skipping intervals without audio.
+#[derive(Debug, Default)]
+struct MinuteMos {
+    start_us: i64,
+    total: f64,
+    samples: u32,
+    last_minute: Option<f32>,
+}
+
+impl MinuteMos {
+    /// Returns the minute's average if this sample completes it.
+    fn add(&mut self, timestamp_us: i64, mos: f32) -> Option<f32> {
+        if self.samples == 0 {
+            self.start_us = timestamp_us;
+        }
+        self.total += mos as f64;
+        self.samples += 1;
+        if timestamp_us - self.start_us < MOS_MINUTE_US {
+            return None;
+        }
+        let average = (self.total / self.samples as f64) as f32;
+        self.total = 0.0;
+        self.samples = 0;
+        self.last_minute = Some(average);
+        Some(average)
+    }
+}
+
+/// Stats for a single outgoing stream. Loss, jitter and round trip time are
+/// as reported back by the remote side. The frame fields are only set for
+/// video.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OutboundStreamStats {
+    pub ssrc: u32,
+    pub bitrate_bps: f64,
+    pub packets_per_second: f64,
+    /// In the range 0-100.
+    pub remote_packets_lost_pct: f32,
+    pub remote_jitter: f64,
+    pub remote_round_trip_time: f64,
+    pub framerate: f64,
+    pub frame_width: u32,
+    pub frame_height: u32,
+    /// None for audio, which is always Opus.
+    pub codec: Option<RffiVideoCodecType>,
+    /// None if the stream isn't being adapted, and always for audio.
+    pub quality_limitation_reason: Option<QualityLimitationReason>,
+}
+
+impl OutboundStreamStats {
+    fn from_audio_sender(
+        audio_sender: &AudioSenderStatistics,
+        prev_audio_sender: &AudioSenderStatistics,
+        seconds_elapsed: f32,
+    ) -> Self {
+        let packets_sent = audio_sender
+            .packets_sent
+            .saturating_sub(prev_audio_sender.packets_sent);
+        let packets_lost = audio_sender
+            .remote_packets_lost
+            .saturating_sub(prev_audio_sender.remote_packets_lost);
+        Self {
+            ssrc: audio_sender.ssrc,
+            bitrate_bps: per_second(
+                audio_sender
+                    .bytes_sent
+                    .saturating_sub(prev_audio_sender.bytes_sent) as f64
+                    * 8.0,
+                seconds_elapsed,
+            ),
+            packets_per_second: per_second(packets_sent, seconds_elapsed),
+            remote_packets_lost_pct: StatsObserver::compute_packets_lost_pct(
+                packets_lost,
+                packets_sent as i32,
+            )
+            .clamp(0.0, 100.0),
+            remote_jitter: audio_sender.remote_jitter,
+            remote_round_trip_time: audio_sender.remote_round_trip_time,
+            ..Default::default()
+        }
+    }
+
+    fn from_video_sender(
+        video_sender: &VideoSenderStatistics,
+        prev_video_sender: &VideoSenderStatistics,
+        seconds_elapsed: f32,
+    ) -> Self {
+        let packets_sent = video_sender
+            .packets_sent
+            .saturating_sub(prev_video_sender.packets_sent);
+        let packets_lost = video_sender
+            .remote_packets_lost
+            .saturating_sub(prev_video_sender.remote_packets_lost);
+        Self {
+            ssrc: video_sender.ssrc,
+            bitrate_bps: per_second(
+                video_sender
+                    .bytes_sent
+                    .saturating_sub(prev_video_sender.bytes_sent) as f64
+                    * 8.0,
+                seconds_elapsed,
+            ),
+            packets_per_second: per_second(packets_sent, seconds_elapsed),
+            remote_packets_lost_pct: StatsObserver::compute_packets_lost_pct(
+                packets_lost,
+                packets_sent as i32,
+            )
+            .clamp(0.0, 100.0),
+            remote_jitter: video_sender.remote_jitter,
+            remote_round_trip_time: video_sender.remote_round_trip_time,
+            framerate: per_second(
+                video_sender
+                    .frames_encoded
+                    .saturating_sub(prev_video_sender.frames_encoded),
+                seconds_elapsed,
+            ),
+            frame_width: video_sender.frame_width,
+            frame_height: video_sender.frame_height,
+            codec: video_codec_from_stats(video_sender.codec_type),
+            quality_limitation_reason: QualityLimitationReason::from_stats(
+                video_sender.quality_limitation_reason,
+            ),
+        }
+    }
+}
+
+/// Stats for a single incoming stream. Jitter is only set for audio, and
+/// the frame fields only for video. The frame counts are totals for the life
+/// of the stream.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InboundStreamStats {
+    pub ssrc: u32,
+    pub bitrate_bps: f64,
+    pub packets_per_second: f64,
+    /// In the range 0-100.
+    pub packets_lost_pct: f32,
+    pub jitter: f64,
+    /// The average time audio spent in the jitter buffer over the interval.
+    /// Not reported for video.
+    pub jitter_buffer_delay: f64,
+    /// When the media being played out was captured, in the sender's NTP
+    /// time. None until the sender's first RTCP report arrives.
+    pub estimated_playout_timestamp: Option<f64>,
+    pub framerate: f64,
+    pub frames_decoded: u32,
+    pub frames_dropped: u32,
+    pub freeze_count: u32,
+    pub frame_width: u32,
+    pub frame_height: u32,
+    /// None for audio, which is always Opus.
+    pub codec: Option<RffiVideoCodecType>,
+}
+
+impl InboundStreamStats {
+    fn from_audio_receiver(
+        audio_receiver: &AudioReceiverStatistics,
+        prev_audio_receiver: &AudioReceiverStatistics,
+        seconds_elapsed: f32,
+    ) -> Self {
+        let sample = ReceiveQualitySample::from_audio_receiver(audio_receiver, prev_audio_receiver);
+        Self {
+            ssrc: audio_receiver.ssrc,
+            bitrate_bps: per_second(
+                audio_receiver
+                    .bytes_received
+                    .saturating_sub(prev_audio_receiver.bytes_received) as f64
+                    * 8.0,
+                seconds_elapsed,
+            ),
+            packets_per_second: per_second(sample.packets_received, seconds_elapsed),
+            packets_lost_pct: sample.packets_lost_pct(),
+            jitter: audio_receiver.jitter,
+            jitter_buffer_delay: average_jitter_buffer_delay(
+                audio_receiver.jitter_buffer_delay,
+                prev_audio_receiver.jitter_buffer_delay,
+                audio_receiver.jitter_buffer_emitted_count,
+                prev_audio_receiver.jitter_buffer_emitted_count,
+            ),
+            estimated_playout_timestamp: estimated_playout_timestamp(
+                audio_receiver.estimated_playout_timestamp,
+            ),
+            ..Default::default()
+        }
+    }
+
+    fn from_video_receiver(
+        video_receiver: &VideoReceiverStatistics,
+        prev_video_receiver: &VideoReceiverStatistics,
+        seconds_elapsed: f32,
+    ) -> Self {
+        let sample = ReceiveQualitySample::from_video_receiver(video_receiver, prev_video_receiver);
+        Self {
+            ssrc: video_receiver.ssrc,
+            bitrate_bps: per_second(
+                video_receiver
+                    .bytes_received
+                    .saturating_sub(prev_video_receiver.bytes_received) as f64
+                    * 8.0,
+                seconds_elapsed,
+            ),
+            packets_per_second: per_second(sample.packets_received, seconds_elapsed),
+            packets_lost_pct: sample.packets_lost_pct(),
+            jitter: 0.0,
+            jitter_buffer_delay: 0.0,
+            estimated_playout_timestamp: estimated_playout_timestamp(
+                video_receiver.estimated_playout_timestamp,
+            ),
+            framerate: per_second(
+                video_receiver
+                    .frames_decoded
+                    .saturating_sub(prev_video_receiver.frames_decoded),
+                seconds_elapsed,
+            ),
+            frames_decoded: video_receiver.frames_decoded,
+            frames_dropped: video_receiver.frames_dropped,
+            freeze_count: video_receiver.freeze_count,
+            frame_width: video_receiver.frame_width,
+            frame_height: video_receiver.frame_height,
+            codec: video_codec_from_stats(video_receiver.codec_type),
+        }
+    }
+}
+
+/// How far apart the audio and video from a single remote sender are being
+/// played out.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AvSyncStats {
+    pub audio_ssrc: u32,
+    pub video_ssrc: u32,
+    /// How far video playout trails audio playout, in seconds. Negative if
+    /// video is ahead of audio.
+    pub skew: f64,
+}
+
+impl AvSyncStats {
+    /// Compares what is being played out on each stream at the time of the
+    /// stats report. Returns None unless both streams are flowing and both
+    /// playout timestamps are known.
+    pub fn from_streams(audio: &InboundStreamStats, video: &InboundStreamStats) -> Option<Self> {
+        if audio.packets_per_second <= 0.0 || video.packets_per_second <= 0.0 {
+            return None;
+        }
+        let audio_playout_timestamp = audio.estimated_playout_timestamp?;
+        let video_playout_timestamp = video.estimated_playout_timestamp?;
+        Some(Self {
+            audio_ssrc: audio.ssrc,
+            video_ssrc: video.ssrc,
+            skew: audio_playout_timestamp - video_playout_timestamp,
+        })
+    }
+}
+
+/// How well a single incoming stream was received between two stats reports.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReceiveQualitySample {
+    pub ssrc: u32,
+    pub is_video: bool,
+    pub packets_received: u32,
+    pub packets_lost: i32,
+    /// The jitter at the end of the interval, in seconds.
+    pub jitter: f64,
+    /// For video, whether packets arrived but no frames could be decoded.
+    pub video_frozen: bool,
+}
+
+impl ReceiveQualitySample {
+    fn from_audio_receiver(
+        audio_receiver: &AudioReceiverStatistics,
+        prev_audio_receiver: &AudioReceiverStatistics,
+    ) -> Self {
+        Self {
+            ssrc: audio_receiver.ssrc,
+            is_video: false,
+            packets_received: audio_receiver
+                .packets_received
+                .saturating_sub(prev_audio_receiver.packets_received),
+            packets_lost: audio_receiver
+                .packets_lost
+                .saturating_sub(prev_audio_receiver.packets_lost),
+            jitter: audio_receiver.jitter,
+            video_frozen: false,
+        }
+    }
+
+    fn from_video_receiver(
+        video_receiver: &VideoReceiverStatistics,
+        prev_video_receiver: &VideoReceiverStatistics,
+    ) -> Self {
+        let packets_received = video_receiver
+            .packets_received
+            .saturating_sub(prev_video_receiver.packets_received);
+        let frames_decoded = video_receiver
+            .frames_decoded
+            .saturating_sub(prev_video_receiver.frames_decoded);
+        Self {
+            ssrc: video_receiver.ssrc,
+            is_video: true,
+            packets_received,
+            packets_lost: video_receiver
+                .packets_lost
+                .saturating_sub(prev_video_receiver.packets_lost),
+            jitter: 0.0,
+            video_frozen: packets_received > 0 && frames_decoded == 0,
+        }
+    }
+
+    /// The percentage of expected packets that were lost, in the range 0-100.
+    pub fn packets_lost_pct(&self) -> f32 {
+        let packets_lost = self.packets_lost.max(0);
+        let packets_expected = self.packets_received as i64 + packets_lost as i64;
+        if packets_expected > 0 {
+            packets_lost as f32 / packets_expected as f32 * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Media sent and received over the lifetime of a [StatsObserver].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MediaUsage {
+    pub audio_bytes_sent: u64,
+    pub audio_bytes_received: u64,
+    pub video_bytes_sent: u64,
+    pub video_bytes_received: u64,
+    /// The sum of all round trip time samples, in seconds.
+    total_round_trip_time: f64,
+    round_trip_time_samples: u32,
+    /// The sum of the estimated MOS of every interval with audio.
+    total_mos: f64,
+    mos_samples: u32,
+    worst_minute_mos: Option<f32>,
+}
+
+impl MediaUsage {
+    /// Records a round trip time sample, in seconds. Zero means the round trip
+    /// time isn't known yet and is ignored.
+    pub fn add_round_trip_time(&mut self, round_trip_time: f64) {
+        if round_trip_time > 0.0 {
+            self.total_round_trip_time += round_trip_time;
+            self.round_trip_time_samples += 1;
+        }
+    }
+
+    fn accumulate(&mut self, other: &MediaUsage) {
+        self.audio_bytes_sent += other.audio_bytes_sent;
+        self.audio_bytes_received += other.audio_bytes_received;
+        self.video_bytes_sent += other.video_bytes_sent;
+        self.video_bytes_received += other.video_bytes_received;
+        self.total_round_trip_time += other.total_round_trip_time;
+        self.round_trip_time_samples += other.round_trip_time_samples;
+        self.total_mos += other.total_mos;
+        self.mos_samples += other.mos_samples;
+        if let Some(minute_mos) = other.worst_minute_mos {
+            self.add_minute_mos(minute_mos);
+        }
+    }
+
+    fn add_mos(&mut self, mos: f32) {
+        self.total_mos += mos as f64;
+        self.mos_samples += 1;
+    }
+
+    fn add_minute_mos(&mut self, minute_mos: f32) {
+        self.worst_minute_mos = Some(
+            self.worst_minute_mos
+                .map_or(minute_mos, |worst| worst.min(minute_mos)),
+        );
+    }
+
+    /// The average estimated MOS over every stats interval that had audio; see
+    /// [StatsSnapshot::estimate_audio_mos].
+    pub fn average_mos(&self) -> Option<f32> {
+        if self.mos_samples > 0 {
+            Some((self.total_mos / self.mos_samples as f64) as f32)
+        } else {
+            None
+        }
+    }
+
+    /// The lowest estimated MOS of any full minute, which catches a bad stretch
+    /// that the average over a long call would hide.
+    pub fn worst_minute_mos(&self) -> Option<f32> {
+        self.worst_minute_mos
+    }
+
+    /// The average of all round trip time samples, if there were any.
+    pub fn average_round_trip_time(&self) -> Option<Duration> {
+        if self.round_trip_time_samples > 0 {
+            Some(Duration::from_secs_f64(
+                self.total_round_trip_time / self.round_trip_time_samples as f64,
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+/// Rust version of WebRTC RTCStatsIceCandidatePairState.
+/// Stays in sync with the C++ value in rffi_defs.h.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CandidatePairState {
+    Frozen,
+    Waiting,
+    InProgress,
+    Failed,
+    Succeeded,
+}
+
+/// An ICE candidate pair. Byte counts are totals for the life of the pair.
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CandidatePairStatistics {
+    pub local_candidate_type: CandidateType,
+    pub local_protocol: TransportProtocol,
+    /// Unknown unless the local candidate is a relay.
+    pub local_relay_protocol: TransportProtocol,
+    pub remote_candidate_type: CandidateType,
+    pub state: CandidatePairState,
+    pub nominated: bool,
+    /// Whether media is being sent over this pair.
+    pub selected: bool,
+    /// In seconds, or zero if not measured yet.
+    pub current_round_trip_time: f64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+}
+
+/// Summarizes the pair for logs and bug reports, such as
+/// "Relay/Udp (relay over Tcp) -> Host, Succeeded, selected, rtt 400ms".
+impl fmt::Display for CandidatePairStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:?}/{:?}",
+            self.local_candidate_type, self.local_protocol
+        )?;
+        if self.local_candidate_type == CandidateType::Relay {
+            write!(f, " (relay over {:?})", self.local_relay_protocol)?;
+        }
+        write!(f, " -> {:?}, {:?}", self.remote_candidate_type, self.state)?;
+        if self.selected {
+            write!(f, ", selected")?;
+        }
+        write!(f, ", rtt {:.0}ms", self.current_round_trip_time * 1000.0)
+    }
 }
 
 #[repr(C)]
@@ -581,6 +1469,8 @@ pub struct MediaStatistics {
     pub video_receiver_statistics_size: u32,
     pub video_receiver_statistics: *const VideoReceiverStatistics,
     pub connection_statistics: ConnectionStatistics,
+    pub candidate_pair_statistics_size: u32,
+    pub candidate_pair_statistics: *const CandidatePairStatistics,
 }
 
 /// StatsObserver OnStatsComplete() callback.
@@ -647,3 +1537,280 @@ pub fn create_stats_observer(
     stats_observer.set_rffi(rffi_stats_observer);
     stats_observer
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inbound_video_stats_over_interval() {
+        let prev = VideoReceiverStatistics {
+            ssrc: 1,
+            packets_received: 100,
+            packets_lost: 10,
+            bytes_received: 10_000,
+            frames_decoded: 30,
+            ..Default::default()
+        };
+        let cur = VideoReceiverStatistics {
+            ssrc: 1,
+            packets_received: 190,
+            packets_lost: 20,
+            bytes_received: 20_000,
+            frames_decoded: 90,
+            frames_dropped: 2,
+            freeze_count: 1,
+            frame_width: 640,
+            frame_height: 480,
+            codec_type: 8,
+            estimated_playout_timestamp: 3_900_000_123.0,
+            ..Default::default()
+        };
+        let stats = InboundStreamStats::from_video_receiver(&cur, &prev, 2.0);
+        assert_eq!(
+            InboundStreamStats {
+                ssrc: 1,
+                bitrate_bps: 40_000.0,
+                packets_per_second: 45.0,
+                packets_lost_pct: 10.0,
+                jitter: 0.0,
+                jitter_buffer_delay: 0.0,
+                estimated_playout_timestamp: Some(3_900_000.123),
+                framerate: 30.0,
+                frames_decoded: 90,
+                frames_dropped: 2,
+                freeze_count: 1,
+                frame_width: 640,
+                frame_height: 480,
+                codec: Some(RffiVideoCodecType::Vp8),
+            },
+            stats
+        );
+    }
+
+    #[test]
+    fn av_sync_from_streams() {
+        let audio = InboundStreamStats {
+            ssrc: 16,
+            packets_per_second: 50.0,
+            estimated_playout_timestamp: Some(1000.5),
+            ..Default::default()
+        };
+        let video = InboundStreamStats {
+            ssrc: 17,
+            packets_per_second: 100.0,
+            estimated_playout_timestamp: Some(1000.25),
+            ..Default::default()
+        };
+        assert_eq!(
+            Some(AvSyncStats {
+                audio_ssrc: 16,
+                video_ssrc: 17,
+                skew: 0.25,
+            }),
+            AvSyncStats::from_streams(&audio, &video)
+        );
+
+        let video_without_report = InboundStreamStats {
+            estimated_playout_timestamp: None,
+            ..video.clone()
+        };
+        assert_eq!(
+            None,
+            AvSyncStats::from_streams(&audio, &video_without_report)
+        );
+
+        let muted_audio = InboundStreamStats {
+            packets_per_second: 0.0,
+            ..audio
+        };
+        assert_eq!(None, AvSyncStats::from_streams(&muted_audio, &video));
+    }
+
+    #[test]
+    fn video_send_limitation_prefers_cpu() {
+        let stream = |packets_per_second, quality_limitation_reason| OutboundStreamStats {
+            packets_per_second,
+            quality_limitation_reason,
+            ..Default::default()
+        };
+        let mut snapshot = StatsSnapshot {
+            video_send: vec![
+                stream(100.0, None),
+                stream(100.0, Some(QualityLimitationReason::Bandwidth)),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            Some(QualityLimitationReason::Bandwidth),
+            snapshot.video_send_limitation()
+        );
+
+        snapshot
+            .video_send
+            .push(stream(50.0, Some(QualityLimitationReason::Cpu)));
+        assert_eq!(
+            Some(QualityLimitationReason::Cpu),
+            snapshot.video_send_limitation()
+        );
+
+        // Layers that aren't being sent don't count.
+        snapshot.video_send[2].packets_per_second = 0.0;
+        snapshot.video_send[1].packets_per_second = 0.0;
+        assert_eq!(None, snapshot.video_send_limitation());
+    }
+
+    #[test]
+    fn mos_estimates() {
+        let excellent = estimate_mos(0.02, 0.0);
+        assert!(excellent > 4.3 && excellent <= 4.5, "{}", excellent);
+        assert!(estimate_mos(0.02, 5.0) < excellent);
+        assert!(estimate_mos(0.4, 0.0) < excellent);
+        assert!(estimate_mos(0.3, 20.0) < 3.0);
+        assert_eq!(1.0, estimate_mos(2.0, 100.0));
+    }
+
+    #[test]
+    fn audio_mos_uses_worst_flowing_stream() {
+        let mut snapshot = StatsSnapshot {
+            round_trip_time: 0.05,
+            audio_recv: vec![
+                InboundStreamStats {
+                    packets_per_second: 50.0,
+                    jitter: 0.01,
+                    ..Default::default()
+                },
+                // Muted
+                InboundStreamStats {
+                    packets_lost_pct: 100.0,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let mos = snapshot.estimate_audio_mos().unwrap();
+        assert!(mos > 4.0, "{}", mos);
+
+        snapshot.audio_send.push(OutboundStreamStats {
+            packets_per_second: 50.0,
+            remote_packets_lost_pct: 10.0,
+            ..Default::default()
+        });
+        assert!(snapshot.estimate_audio_mos().unwrap() < mos);
+
+        snapshot.audio_send.clear();
+        snapshot.audio_recv.truncate(1);
+        snapshot.audio_recv[0].packets_per_second = 0.0;
+        assert_eq!(None, snapshot.estimate_audio_mos());
+    }
+
+    #[test]
+    fn minute_mos() {
+        let mut minute_mos = MinuteMos::default();
+        let mut usage = MediaUsage::default();
+        for (seconds, mos) in [(0, 4.0), (30, 3.0), (60, 2.0), (70, 4.0), (130, 4.0)] {
+            usage.add_mos(mos);
+            if let Some(average) = minute_mos.add(seconds * 1_000_000, mos) {
+                usage.add_minute_mos(average);
+            }
+        }
+        assert_eq!(Some(4.0), minute_mos.last_minute);
+        assert_eq!(Some(3.0), usage.worst_minute_mos());
+        assert_eq!(Some(3.4), usage.average_mos());
+    }
+
+    #[test]
+    fn time_series_export() {
+        let mut time_series = StatsTimeSeries::default();
+        let snapshot = StatsSnapshot {
+            timestamp_us: 5_000_000,
+            round_trip_time: 0.042,
+            audio_send: vec![OutboundStreamStats {
+                bitrate_bps: 32_000.0,
+                packets_per_second: 50.0,
+                remote_packets_lost_pct: 1.5,
+                ..Default::default()
+            }],
+            video_recv: vec![
+                InboundStreamStats {
+                    bitrate_bps: 500_000.0,
+                    packets_per_second: 100.0,
+                    packets_lost_pct: 2.0,
+                    ..Default::default()
+                },
+                // Stopped; not counted
+                InboundStreamStats {
+                    bitrate_bps: 0.0,
+                    packets_lost_pct: 100.0,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        time_series.push(StatsTimeSeriesPoint::from_snapshot(&snapshot, 0));
+        time_series.push(StatsTimeSeriesPoint::from_snapshot(
+            &StatsSnapshot {
+                timestamp_us: 6_000_000,
+                ..snapshot
+            },
+            1,
+        ));
+        assert_eq!(
+            "elapsed_ms,send_kbps,receive_kbps,loss_pct,remote_loss_pct,rtt_ms,freezes\n\
+             0,32,500,2.0,1.5,42,0\n\
+             1000,32,500,2.0,1.5,42,1\n",
+            time_series.export()
+        );
+    }
+
+    #[test]
+    fn selected_candidate_pair() {
+        let pair = |local_candidate_type, selected| CandidatePairStatistics {
+            local_candidate_type,
+            local_protocol: TransportProtocol::Udp,
+            local_relay_protocol: if local_candidate_type == CandidateType::Relay {
+                TransportProtocol::Tcp
+            } else {
+                TransportProtocol::Unknown
+            },
+            remote_candidate_type: CandidateType::Host,
+            state: CandidatePairState::Succeeded,
+            nominated: selected,
+            selected,
+            current_round_trip_time: 0.4,
+            bytes_sent: 0,
+            bytes_received: 0,
+        };
+        let mut snapshot = StatsSnapshot {
+            candidate_pairs: vec![
+                pair(CandidateType::Host, false),
+                pair(CandidateType::Relay, true),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            "Relay/Udp (relay over Tcp) -> Host, Succeeded, selected, rtt 400ms",
+            snapshot.selected_candidate_pair().unwrap().to_string()
+        );
+
+        snapshot.candidate_pairs[1].selected = false;
+        assert_eq!(None, snapshot.selected_candidate_pair());
+    }
+
+    #[test]
+    fn outbound_stats_without_elapsed_time() {
+        let cur = AudioSenderStatistics {
+            ssrc: 2,
+            packets_sent: 50,
+            bytes_sent: 5_000,
+            remote_packets_lost: -1,
+            ..Default::default()
+        };
+        let stats =
+            OutboundStreamStats::from_audio_sender(&cur, &AudioSenderStatistics::default(), 0.0);
+        assert_eq!(0.0, stats.bitrate_bps);
+        assert_eq!(0.0, stats.packets_per_second);
+        assert_eq!(0.0, stats.remote_packets_lost_pct);
+        assert_eq!(None, stats.codec);
+    }
+}
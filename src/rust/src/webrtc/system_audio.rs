@@ -0,0 +1,163 @@
+//
+// Copyright 2024 Signal Messenger, LLC
+// SPDX-License-Identifier: AGPL-3.0-only
+//
+
+//! Capture of the audio the system is playing, to share along with the
+//! screen.
+//!
+//! On Windows, WASAPI loopback captures the mix sent to the default output
+//! device. The captured audio is pushed into its own [`AudioInjector`],
+//! separate from the application's, which the audio device module mixes
+//! into the microphone before audio processing. That matters
+//! because the mix includes the call itself: the remote participants being
+//! played out. WebRTC's echo canceller uses that playout as its reference,
+//! so it removes them from the shared audio just as it does from the
+//! microphone, rather than sending them back to themselves.
+
+use std::{
+    ffi::CStr,
+    sync::{mpsc, Arc},
+    thread,
+};
+
+use anyhow::anyhow;
+use cubeb::{Context, MonoFrame, StreamPrefs};
+
+use crate::{
+    common::Result,
+    webrtc::audio_injector::{AudioInjector, CAPTURE_SAMPLE_RATE_HZ},
+};
+
+/// Arbitrary string to identify the loopback cubeb object.
+const LOOPBACK_CONTEXT: &CStr = c"ringrtc system audio";
+
+/// Target latency, 10ms like the capture path.
+const LOOPBACK_LATENCY: u32 = CAPTURE_SAMPLE_RATE_HZ / 100;
+
+/// How much system audio can be queued for the capture path: a few 10ms
+/// frames, so it stays in step with the microphone instead of falling behind
+/// when the two devices' clocks drift apart.
+pub const MAX_QUEUED_SAMPLES: usize = LOOPBACK_LATENCY as usize * 4;
+
+type Frame = MonoFrame<i16>;
+
+/// A running capture of system audio, stopped when dropped.
+///
+/// The cubeb stream lives on its own thread, so the handle can be kept
+/// wherever the application's API is called from.
+#[derive(Debug)]
+pub struct SystemAudioCapture {
+    stop: mpsc::Sender<()>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl SystemAudioCapture {
+    /// Start capturing into `audio_injector`, which should be the audio device
+    /// module's system audio injector, returning once the loopback stream is
+    /// running.
+    pub fn start(audio_injector: Arc<AudioInjector>) -> Result<Self> {
+        if cfg!(not(target_os = "windows")) {
+            return Err(anyhow!("system audio capture is only supported on Windows"));
+        }
+
+        let (started_sender, started_receiver) = mpsc::channel();
+        let (stop_sender, stop_receiver) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name("system-audio".to_string())
+            .spawn(move || {
+                let ctx = match Context::init(Some(LOOPBACK_CONTEXT), None) {
+                    Ok(ctx) => ctx,
+                    Err(e) => {
+                        let _ = started_sender
+                            .send(Err(anyhow!("failed to initialize loopback context: {}", e)));
+                        return;
+                    }
+                };
+                let stream = match Self::start_stream(&ctx, audio_injector) {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        let _ = started_sender.send(Err(e));
+                        return;
+                    }
+                };
+                let _ = started_sender.send(Ok(()));
+
+                // Wait until told to stop, or the handle is dropped.
+                let _ = stop_receiver.recv();
+                if let Err(e) = stream.stop() {
+                    warn!("Failed to stop system audio capture: {}", e);
+                }
+                // The stream must not outlive the ctx.
+                drop(stream);
+                drop(ctx);
+            })?;
+
+        match started_receiver.recv() {
+            Ok(Ok(())) => {
+                info!("Started system audio capture");
+                Ok(Self {
+                    stop: stop_sender,
+                    thread: Some(thread),
+                })
+            }
+            Ok(Err(e)) => {
+                let _ = thread.join();
+                Err(e)
+            }
+            Err(_) => {
+                let _ = thread.join();
+                Err(anyhow!("system audio capture thread exited"))
+            }
+        }
+    }
+
+    fn start_stream(
+        ctx: &Context,
+        audio_injector: Arc<AudioInjector>,
+    ) -> Result<cubeb::Stream<Frame>> {
+        let params = cubeb::StreamParamsBuilder::new()
+            .format(cubeb::SampleFormat::S16NE)
+            .rate(CAPTURE_SAMPLE_RATE_HZ)
+            .channels(1)
+            .layout(cubeb::ChannelLayout::MONO)
+            .prefs(StreamPrefs::LOOPBACK)
+            .take();
+        let mut builder = cubeb::StreamBuilder::<Frame>::new();
+        builder
+            .name("ringrtc system audio")
+            // With LOOPBACK, a null input device is the default output device.
+            .input(std::ptr::null(), &params)
+            .latency(LOOPBACK_LATENCY)
+            .data_callback(move |input, _| {
+                let samples: Vec<i16> = input.iter().map(|f| f.m).collect();
+                if let Err(e) = audio_injector.push(&samples, CAPTURE_SAMPLE_RATE_HZ, 1) {
+                    error!("Failed to queue system audio: {}", e);
+                    return -1;
+                }
+                input.len() as isize
+            })
+            .state_callback(|state| {
+                warn!("System audio state: {:?}", state);
+            });
+        let stream = builder
+            .init(ctx)
+            .map_err(|e| anyhow!("couldn't initialize loopback stream: {}", e))?;
+        stream
+            .start()
+            .map_err(|e| anyhow!("couldn't start loopback stream: {}", e))?;
+        Ok(stream)
+    }
+}
+
+impl Drop for SystemAudioCapture {
+    fn drop(&mut self) {
+        let _ = self.stop.send(());
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                error!("System audio capture thread panicked");
+            }
+        }
+        info!("Stopped system audio capture");
+    }
+}
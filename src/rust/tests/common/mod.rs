@@ -153,6 +153,11 @@ impl TestContext {
         platform.force_signaling_fault(enable);
     }
 
+    pub fn force_call_summary_fault(&self, enable: bool) {
+        let mut platform = self.call_manager.platform().unwrap();
+        platform.force_call_summary_fault(enable);
+    }
+
     pub fn no_auto_message_sent_for_ice(&self, enable: bool) {
         let mut platform = self.call_manager.platform().unwrap();
         platform.no_auto_message_sent_for_ice(enable);
@@ -178,6 +183,11 @@ impl TestContext {
         platform.last_ice_sent()
     }
 
+    pub fn app_data_notified(&self) -> Vec<Vec<u8>> {
+        let platform = self.call_manager.platform().unwrap();
+        platform.app_data_notified()
+    }
+
     pub fn normal_hangups_sent(&self) -> usize {
         let platform = self.call_manager.platform().unwrap();
         platform.normal_hangups_sent()
@@ -203,6 +213,26 @@ impl TestContext {
         platform.busy_hangups_sent()
     }
 
+    pub fn declined_with_reason_hangups_sent(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.declined_with_reason_hangups_sent()
+    }
+
+    pub fn handed_off_hangups_sent(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.handed_off_hangups_sent()
+    }
+
+    pub fn network_failure_hangups_sent(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.network_failure_hangups_sent()
+    }
+
+    pub fn ring_timeout_hangups_sent(&self) -> usize {
+        let platform = self.call_manager.platform().unwrap();
+        platform.ring_timeout_hangups_sent()
+    }
+
     pub fn error_count(&self) -> usize {
         let platform = self.call_manager.platform().unwrap();
         platform.error_count()
@@ -266,6 +296,7 @@ impl TestContext {
             ringrtc::webrtc::media::AudioTrack::new(webrtc::Arc::null(), None),
             ringrtc::webrtc::media::VideoTrack::new(webrtc::Arc::null(), None),
             None,
+            vec![],
         )
     }
 }
@@ -280,6 +311,11 @@ pub fn random_received_offer(_prng: &Prng, age: Duration) -> signaling::Received
             ice_pwd: None,
             receive_video_codecs: vec![],
             max_bitrate_bps: None,
+            ice_restart_supported: Some(true),
+            relay_only: None,
+            handoff: None,
+            early_media: None,
+            call_id_extension: None,
         },
     )
     .unwrap();
@@ -307,6 +343,11 @@ pub fn random_received_answer(
         ice_pwd: None,
         receive_video_codecs: vec![],
         max_bitrate_bps: None,
+        ice_restart_supported: Some(true),
+        relay_only: None,
+        handoff: None,
+        early_media: None,
+        call_id_extension: None,
     })
     .unwrap();
     signaling::ReceivedAnswer {
@@ -15,19 +15,23 @@ use std::time::Duration;
 use prost::Message;
 use ringrtc::{
     common::{
-        units::DataRate, ApplicationEvent, CallConfig, CallId, CallState, ConnectionState, DataMode,
+        units::DataRate, AcceptOptions, ApplicationEvent, CallBitrateConfig, CallConfig, CallId,
+        CallMediaType, CallState, ConnectionState, DataMode, MaxSendBitrate,
     },
     core::{call_manager::MAX_MESSAGE_AGE, group_call, signaling},
     protobuf, webrtc,
     webrtc::{
-        media::MediaStream,
+        media::{AudioLossProtectionConfig, AudioLossProtectionMode, AudioProfile, MediaStream},
         peer_connection_observer::{NetworkAdapterType, NetworkRoute, TransportProtocol},
     },
 };
 
 #[macro_use]
 mod common;
-use common::{random_received_ice_candidate, random_received_offer, test_init, TestContext};
+use common::{
+    random_ice_candidate, random_received_ice_candidate, random_received_offer, test_init,
+    TestContext,
+};
 
 // Create an inbound call session up to the ConnectingBeforeAccepted state.
 //
@@ -110,6 +114,139 @@ fn inbound_ice_connecting() {
     let _ = start_inbound_call();
 }
 
+#[test]
+fn received_relay_only_offer() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let mut received = random_received_offer(&context.prng, Duration::from_secs(0));
+    let mut v4 = received.offer.to_v4().expect(error_line!());
+    v4.relay_only = Some(true);
+    received.offer =
+        signaling::Offer::from_v4(received.offer.call_media_type, v4).expect(error_line!());
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    cm.received_offer(remote_peer, call_id, received)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // The local side didn't ask for relay only, but follows the caller.
+    cm.proceed(
+        call_id,
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default().with_data_mode(DataMode::Normal),
+        None,
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let connection = context
+        .active_call()
+        .get_connection(1)
+        .expect(error_line!());
+    assert!(connection.call_config().relay_only);
+    assert_eq!(context.answers_sent(), 1);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn received_offer_with_call_id_extension() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let mut received = random_received_offer(&context.prng, Duration::from_secs(0));
+    let extension = context.prng.gen::<u64>();
+    received.offer = received
+        .offer
+        .with_call_id_extension(extension)
+        .expect(error_line!());
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    cm.received_offer(remote_peer, call_id, received)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let wide_call_id = cm
+        .wide_call_id(call_id)
+        .expect(error_line!())
+        .expect(error_line!());
+    assert_eq!(wide_call_id.call_id(), call_id);
+    assert_eq!(wide_call_id.extension(), extension);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn pre_gather_ice_before_proceeding() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    cm.received_offer(
+        remote_peer,
+        call_id,
+        random_received_offer(&context.prng, Duration::from_secs(0)),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    cm.pre_gather_ice(call_id, format!("CONTEXT-{}", context.prng.gen::<u16>()))
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    let mut pre_gathering_connection = active_call
+        .get_pre_gathering_connection()
+        .expect(error_line!())
+        .expect(error_line!());
+    assert_eq!(
+        pre_gathering_connection.state().expect(error_line!()),
+        ConnectionState::IceGathering
+    );
+
+    // Only the relayed candidate is kept, and nothing is sent yet.
+    let force_send = false;
+    pre_gathering_connection
+        .inject_local_ice_candidate(random_ice_candidate(&context.prng), force_send, "", None)
+        .expect(error_line!());
+    pre_gathering_connection
+        .inject_local_ice_candidate(
+            random_ice_candidate(&context.prng),
+            force_send,
+            "",
+            Some(TransportProtocol::Udp),
+        )
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.answers_sent(), 0);
+    assert_eq!(context.ice_candidates_sent(), 0);
+
+    // Proceeding replaces the call context and sends the candidate after the answer.
+    cm.proceed(
+        call_id,
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default().with_data_mode(DataMode::Normal),
+        None,
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let connection = active_call.get_connection(1).expect(error_line!());
+    assert!(connection.call_config().relay_only);
+    assert_eq!(context.answers_sent(), 1);
+    assert_eq!(context.ice_candidates_sent(), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
 // Create an inbound call session up to the ConnectedAndAccepted state.
 //
 // 1. receive an offer
@@ -184,6 +321,50 @@ fn inbound_call_connected() {
     let _ = connect_inbound_call();
 }
 
+#[test]
+fn inbound_call_accepted_with_media_off() {
+    test_init();
+
+    let context = start_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::ConnectedBeforeAccepted
+    );
+
+    cm.accept_call_with_options(
+        active_call.call_id(),
+        AcceptOptions {
+            audio_muted: true,
+            video_disabled: true,
+        },
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::ConnectedAndAccepted
+    );
+    assert_eq!(context.event_count(ApplicationEvent::LocalAccepted), 1);
+    assert_eq!(context.error_count(), 0);
+
+    // The initial status goes out along with the accept.
+    let sender_status = active_connection
+        .last_sent_sender_status()
+        .expect(error_line!());
+    assert_eq!(Some(false), sender_status.audio_enabled);
+    assert_eq!(Some(false), sender_status.video_enabled);
+    assert_eq!(None, sender_status.on_hold);
+}
+
 #[test]
 fn inbound_call_hangup_accepted() {
     test_init();
@@ -265,6 +446,82 @@ fn inbound_call_hangup_busy() {
     assert!(!cm.busy());
 }
 
+#[test]
+fn inbound_call_hangup_ring_timeout() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    cm.received_hangup(
+        active_call.call_id(),
+        signaling::ReceivedHangup {
+            sender_device_id: 1,
+            hangup: signaling::Hangup::RingTimeout,
+        },
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedRemoteHangupRingTimeout),
+        1
+    );
+    assert_eq!(context.event_count(ApplicationEvent::EndedRemoteHangup), 0);
+    assert!(!cm.busy());
+}
+
+#[test]
+fn inbound_call_decline_with_reason() {
+    test_init();
+
+    let context = start_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.event_count(ApplicationEvent::LocalRinging), 1);
+
+    cm.decline(
+        active_call.call_id(),
+        signaling::DeclineReason::DoNotDisturb,
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.event_count(ApplicationEvent::EndedLocalHangup), 1);
+    assert_eq!(context.declined_with_reason_hangups_sent(), 1);
+    assert_eq!(context.normal_hangups_sent(), 0);
+    assert!(!cm.busy());
+}
+
+#[test]
+fn inbound_call_decline_with_reason_after_accepted() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    // An accepted call can only be hung up.
+    cm.decline(active_call.call_id(), signaling::DeclineReason::Busy)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+    assert_eq!(context.declined_with_reason_hangups_sent(), 0);
+    assert!(cm.busy());
+}
+
 #[test]
 fn inbound_call_drop_connecting() {
     test_init();
@@ -532,6 +789,55 @@ fn update_data_mode_low() {
     )
 }
 
+#[test]
+fn set_max_send_bitrate() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let active_connection = context.active_connection();
+
+    // The override isn't limited by the data mode, and leaves room for the audio.
+    cm.set_max_send_bitrate(
+        active_call.call_id(),
+        MaxSendBitrate::from_bps(16_000, 3_000_000, 0),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.error_count(), 0);
+
+    assert_eq!(
+        Some(3_016_000),
+        active_connection
+            .app_connection()
+            .unwrap()
+            .max_bitrate_bps()
+    );
+
+    // It's local only, so nothing is sent to the remote peer.
+    assert_eq!(
+        None,
+        active_connection
+            .app_connection()
+            .unwrap()
+            .last_sent_max_bitrate_bps()
+    );
+
+    // Without an override, the data mode applies again.
+    cm.set_max_send_bitrate(active_call.call_id(), MaxSendBitrate::default())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        Some(2_000_000),
+        active_connection
+            .app_connection()
+            .unwrap()
+            .max_bitrate_bps()
+    );
+}
+
 #[test]
 fn update_data_mode_when_relayed() {
     test_init();
@@ -644,186 +950,199 @@ fn update_data_mode_when_relayed() {
 }
 
 #[test]
-fn start_inbound_call_with_error() {
+fn update_bitrate_config() {
     test_init();
 
-    let context = TestContext::new();
+    let context = connect_inbound_call();
     let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
 
-    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
-    let call_id = CallId::new(context.prng.gen::<u64>());
-    cm.received_offer(
-        remote_peer,
-        call_id,
-        random_received_offer(&context.prng, Duration::from_secs(0)),
-    )
-    .expect(error_line!());
-
+    active_connection
+        .inject_update_bitrate_config(CallBitrateConfig::from_bps(16_000, 500_000, 0))
+        .expect(error_line!());
     cm.synchronize().expect(error_line!());
+    assert_eq!(context.error_count(), 0);
 
-    assert!(cm.active_call().is_ok());
-    assert_eq!(context.start_outgoing_count(), 0);
-    assert_eq!(context.start_incoming_count(), 1);
-
-    let active_call = context.active_call();
+    // The video cap leaves room for the audio.
     assert_eq!(
-        active_call.state().expect(error_line!()),
-        CallState::WaitingToProceed
+        Some(516_000),
+        active_connection
+            .app_connection()
+            .unwrap()
+            .max_bitrate_bps()
     );
 
-    // cause the sending of the answer to fail.
-    context.force_internal_fault(true);
-
-    cm.proceed(
-        active_call.call_id(),
-        format!("CONTEXT-{}", context.prng.gen::<u16>()),
-        CallConfig::default().with_data_mode(DataMode::Normal),
-        None,
-    )
-    .expect(error_line!());
+    // The caps go out with the sender status, which keeps the rest.
+    let sender_status = active_connection
+        .last_sent_sender_status()
+        .expect(error_line!());
+    assert_eq!(Some(16_000), sender_status.max_audio_bitrate_bps);
+    assert_eq!(Some(500_000), sender_status.max_video_bitrate_bps);
+    assert_eq!(Some(0), sender_status.max_total_bitrate_bps);
 
+    active_connection
+        .update_sender_status(signaling::SenderStatus {
+            video_enabled: Some(true),
+            ..Default::default()
+        })
+        .expect(error_line!());
     cm.synchronize().expect(error_line!());
 
-    active_call.get_connection(1).expect_err(error_line!());
-
-    assert_eq!(context.error_count(), 1);
-    assert_eq!(context.ended_count(), 1);
-    assert_eq!(context.answers_sent(), 0);
-    assert_eq!(
-        active_call.state().expect(error_line!()),
-        CallState::Terminated
-    );
-    assert!(!cm.busy());
+    let sender_status = active_connection
+        .last_sent_sender_status()
+        .expect(error_line!());
+    assert_eq!(Some(true), sender_status.video_enabled);
+    assert_eq!(Some(500_000), sender_status.max_video_bitrate_bps);
 }
 
 #[test]
-fn receive_offer_while_active() {
+fn update_audio_profile() {
     test_init();
 
     let context = connect_inbound_call();
     let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
 
-    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
-    let call_id = CallId::new(context.prng.gen::<u64>());
-    cm.received_offer(
-        remote_peer,
-        call_id,
-        random_received_offer(&context.prng, Duration::from_secs(0)),
-    )
-    .expect(error_line!());
-
+    active_connection
+        .inject_update_bitrate_config(CallBitrateConfig::from_bps(0, 500_000, 0))
+        .expect(error_line!());
     cm.synchronize().expect(error_line!());
+    assert_eq!(
+        Some(532_000),
+        active_connection
+            .app_connection()
+            .unwrap()
+            .max_bitrate_bps()
+    );
 
-    assert_eq!(context.error_count(), 0);
+    // Music leaves more room for the audio next to the capped video.
+    active_connection
+        .update_audio_profile(AudioProfile::Music)
+        .expect(error_line!());
     assert_eq!(
-        context.event_count(ApplicationEvent::ReceivedOfferWhileActive),
-        1
+        Some(628_000),
+        active_connection
+            .app_connection()
+            .unwrap()
+            .max_bitrate_bps()
     );
-    assert_eq!(context.busys_sent(), 1);
-    assert_eq!(context.call_concluded_count(), 1);
-    assert!(cm.busy());
+
+    active_connection
+        .update_audio_profile(AudioProfile::Voice)
+        .expect(error_line!());
+    assert_eq!(
+        Some(532_000),
+        active_connection
+            .app_connection()
+            .unwrap()
+            .max_bitrate_bps()
+    );
+    assert_eq!(context.error_count(), 0);
 }
 
 #[test]
-fn receive_expired_offer() {
+fn set_audio_levels_interval() {
     test_init();
 
-    let context = TestContext::new();
-    let mut cm = context.cm();
-
-    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
-    let call_id = CallId::new(context.prng.gen::<u64>());
-    let age = Duration::from_secs(86400); // one whole day
-    cm.received_offer(
-        remote_peer,
-        call_id,
-        random_received_offer(&context.prng, age),
-    )
-    .expect(error_line!());
+    let context = connect_inbound_call();
+    let active_connection = context.active_connection();
+    assert_eq!(None, active_connection.audio_levels_interval().unwrap());
 
-    cm.synchronize().expect(error_line!());
+    active_connection
+        .set_audio_levels_interval(Some(Duration::from_millis(200)))
+        .expect(error_line!());
+    assert_eq!(
+        Some(Duration::from_millis(200)),
+        active_connection.audio_levels_interval().unwrap()
+    );
 
+    // The interval is shared with the clones the ticker uses.
+    active_connection
+        .clone()
+        .set_audio_levels_interval(None)
+        .expect(error_line!());
+    assert_eq!(None, active_connection.audio_levels_interval().unwrap());
     assert_eq!(context.error_count(), 0);
-    assert_eq!(context.offer_expired_count(), 1);
-    assert!(!cm.busy());
 }
 
 #[test]
-fn receive_offer_before_age_limit() {
+fn set_audio_loss_protection() {
     test_init();
 
-    let context = TestContext::new();
-    let mut cm = context.cm();
-
-    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
-    let call_id = CallId::new(context.prng.gen::<u64>());
-    let age = MAX_MESSAGE_AGE - Duration::from_secs(1);
-    cm.received_offer(
-        remote_peer,
-        call_id,
-        random_received_offer(&context.prng, age),
-    )
-    .expect(error_line!());
-
-    cm.synchronize().expect(error_line!());
+    let context = connect_inbound_call();
+    let active_connection = context.active_connection();
+    let app_connection = active_connection.app_connection().unwrap();
+    assert_eq!(Some(true), app_connection.audio_fec_enabled());
 
+    active_connection
+        .set_audio_loss_protection(AudioLossProtectionConfig {
+            fec: AudioLossProtectionMode::Off,
+            ..Default::default()
+        })
+        .expect(error_line!());
+    assert_eq!(Some(false), app_connection.audio_fec_enabled());
     assert_eq!(context.error_count(), 0);
-    assert_eq!(context.offer_expired_count(), 0);
-    assert!(cm.busy());
 }
 
 #[test]
-fn receive_offer_at_age_limit() {
+fn received_bitrate_config() {
     test_init();
 
-    let context = TestContext::new();
+    let context = connect_inbound_call();
     let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+    let call_id = active_connection.call_id();
 
-    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
-    let call_id = CallId::new(context.prng.gen::<u64>());
-    let age = MAX_MESSAGE_AGE;
-    cm.received_offer(
-        remote_peer,
-        call_id,
-        random_received_offer(&context.prng, age),
-    )
-    .expect(error_line!());
-
+    active_connection
+        .inject_received_bitrate_config_via_rtp_data(
+            call_id,
+            CallBitrateConfig::from_bps(0, 0, 400_000),
+            1,
+        )
+        .expect(error_line!());
     cm.synchronize().expect(error_line!());
-
     assert_eq!(context.error_count(), 0);
-    assert_eq!(context.offer_expired_count(), 0);
-    assert!(cm.busy());
-}
-
-#[test]
-fn receive_expired_offer_after_age_limit() {
-    test_init();
 
-    let context = TestContext::new();
-    let mut cm = context.cm();
+    assert_eq!(
+        Some(400_000),
+        active_connection
+            .app_connection()
+            .unwrap()
+            .max_bitrate_bps()
+    );
 
-    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
-    let call_id = CallId::new(context.prng.gen::<u64>());
-    let age = MAX_MESSAGE_AGE + Duration::from_secs(1);
-    cm.received_offer(
-        remote_peer,
-        call_id,
-        random_received_offer(&context.prng, age),
-    )
-    .expect(error_line!());
+    // Out of order caps are ignored.
+    active_connection
+        .inject_received_bitrate_config_via_rtp_data(call_id, CallBitrateConfig::default(), 1)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        Some(400_000),
+        active_connection
+            .app_connection()
+            .unwrap()
+            .max_bitrate_bps()
+    );
 
+    // Removing the caps restores the data mode's bitrate.
+    active_connection
+        .inject_received_bitrate_config_via_rtp_data(call_id, CallBitrateConfig::default(), 2)
+        .expect(error_line!());
     cm.synchronize().expect(error_line!());
+    assert_eq!(
+        Some(2_000_000),
+        active_connection
+            .app_connection()
+            .unwrap()
+            .max_bitrate_bps()
+    );
 
-    assert_eq!(context.error_count(), 0);
-    assert_eq!(context.offer_expired_count(), 1);
-    assert!(!cm.busy());
+    // The remote caps are never sent back.
+    assert_eq!(None, active_connection.last_sent_sender_status());
 }
 
 #[test]
-fn offer_after_ice() {
-    // We don't actually expose a way to automatically test that the ICE candidates are handled.
-    // You can check manually by running with RUST_LOG=ringrtc::core::connection/ice_candidates
+fn start_inbound_call_with_error() {
     test_init();
 
     let context = TestContext::new();
@@ -831,12 +1150,6 @@ fn offer_after_ice() {
 
     let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
     let call_id = CallId::new(context.prng.gen::<u64>());
-
-    cm.received_ice(call_id, random_received_ice_candidate(&context.prng))
-        .expect(error_line!());
-    cm.received_ice(call_id, random_received_ice_candidate(&context.prng))
-        .expect(error_line!());
-
     cm.received_offer(
         remote_peer,
         call_id,
@@ -856,6 +1169,9 @@ fn offer_after_ice() {
         CallState::WaitingToProceed
     );
 
+    // cause the sending of the answer to fail.
+    context.force_internal_fault(true);
+
     cm.proceed(
         active_call.call_id(),
         format!("CONTEXT-{}", context.prng.gen::<u16>()),
@@ -866,42 +1182,27 @@ fn offer_after_ice() {
 
     cm.synchronize().expect(error_line!());
 
-    let connection = active_call.get_connection(1).expect(error_line!());
-
-    cm.synchronize().expect(error_line!());
-    assert_eq!(
-        connection.state().expect(error_line!()),
-        ConnectionState::ConnectingBeforeAccepted
-    );
+    active_call.get_connection(1).expect_err(error_line!());
 
-    assert_eq!(context.answers_sent(), 1);
+    assert_eq!(context.error_count(), 1);
+    assert_eq!(context.ended_count(), 1);
+    assert_eq!(context.answers_sent(), 0);
     assert_eq!(
         active_call.state().expect(error_line!()),
-        CallState::ConnectingBeforeAccepted
+        CallState::Terminated
     );
-    assert_eq!(context.error_count(), 0);
-    assert_eq!(context.ended_count(), 0);
-    assert!(cm.busy());
+    assert!(!cm.busy());
 }
 
 #[test]
-fn offer_after_unrelated_ice() {
-    // We don't actually expose a way to automatically test that the ICE candidates are handled.
-    // You can check manually by running with RUST_LOG=ringrtc::core::connection/ice_candidates
+fn receive_offer_while_active() {
     test_init();
 
-    let context = TestContext::new();
+    let context = connect_inbound_call();
     let mut cm = context.cm();
 
     let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
     let call_id = CallId::new(context.prng.gen::<u64>());
-    let other_call_id = CallId::new(context.prng.gen::<u64>());
-
-    cm.received_ice(other_call_id, random_received_ice_candidate(&context.prng))
-        .expect(error_line!());
-    cm.received_ice(other_call_id, random_received_ice_candidate(&context.prng))
-        .expect(error_line!());
-
     cm.received_offer(
         remote_peer,
         call_id,
@@ -911,116 +1212,130 @@ fn offer_after_unrelated_ice() {
 
     cm.synchronize().expect(error_line!());
 
-    assert!(cm.active_call().is_ok());
-    assert_eq!(context.start_outgoing_count(), 0);
-    assert_eq!(context.start_incoming_count(), 1);
-
-    let active_call = context.active_call();
+    assert_eq!(context.error_count(), 0);
     assert_eq!(
-        active_call.state().expect(error_line!()),
-        CallState::WaitingToProceed
+        context.event_count(ApplicationEvent::ReceivedOfferWhileActive),
+        1
     );
+    assert_eq!(context.busys_sent(), 1);
+    assert_eq!(context.call_concluded_count(), 1);
+    assert!(cm.busy());
+}
 
-    cm.proceed(
-        active_call.call_id(),
-        format!("CONTEXT-{}", context.prng.gen::<u16>()),
-        CallConfig::default().with_data_mode(DataMode::Normal),
-        None,
+// Receive an offer from another peer while in an accepted call with call
+// waiting enabled. The new call waits instead of being declined as busy.
+fn receive_waiting_call() -> (TestContext, CallId) {
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+
+    cm.set_call_waiting_enabled(true).expect(error_line!());
+
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    cm.received_offer(
+        "WAITING_PEER".to_owned(),
+        call_id,
+        random_received_offer(&context.prng, Duration::from_secs(0)),
     )
     .expect(error_line!());
 
     cm.synchronize().expect(error_line!());
 
-    let connection = active_call.get_connection(1).expect(error_line!());
-
-    cm.synchronize().expect(error_line!());
+    assert_eq!(context.error_count(), 0);
     assert_eq!(
-        connection.state().expect(error_line!()),
-        ConnectionState::ConnectingBeforeAccepted
+        context.event_count(ApplicationEvent::IncomingCallWaiting),
+        1
     );
-
-    assert_eq!(context.answers_sent(), 1);
     assert_eq!(
-        active_call.state().expect(error_line!()),
-        CallState::ConnectingBeforeAccepted
+        context.event_count(ApplicationEvent::ReceivedOfferWhileActive),
+        0
     );
-    assert_eq!(context.error_count(), 0);
-    assert_eq!(context.ended_count(), 0);
+    assert_eq!(context.busys_sent(), 0);
+    assert_eq!(context.start_incoming_count(), 1);
     assert!(cm.busy());
+
+    (context, call_id)
 }
 
 #[test]
-fn offer_after_hangup() {
+fn decline_waiting_call() {
     test_init();
 
-    let context = TestContext::new();
+    let (context, _) = receive_waiting_call();
     let mut cm = context.cm();
+    let active_call = context.active_call();
 
-    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
-    let call_id = CallId::new(context.prng.gen::<u64>());
-
-    cm.received_hangup(
-        call_id,
-        signaling::ReceivedHangup {
-            sender_device_id: 1,
-            hangup: signaling::Hangup::Normal,
-        },
-    )
-    .expect(error_line!());
+    cm.decline_waiting_call().expect(error_line!());
+    cm.synchronize().expect(error_line!());
 
-    cm.received_offer(
-        remote_peer,
-        call_id,
-        random_received_offer(&context.prng, Duration::from_secs(0)),
-    )
-    .expect(error_line!());
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.busys_sent(), 1);
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::ConnectedAndAccepted
+    );
+    assert!(cm.busy());
+}
 
-    cm.synchronize().expect(error_line!());
+#[test]
+fn waiting_call_timeout() {
+    test_init();
 
-    cm.proceed(
-        call_id,
-        format!("CONTEXT-{}", context.prng.gen::<u16>()),
-        CallConfig::default().with_data_mode(DataMode::Normal),
-        None,
-    )
-    .expect(error_line!());
+    let (context, call_id) = receive_waiting_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
 
+    let mut waiting_call = cm
+        .waiting_call()
+        .expect(error_line!())
+        .expect(error_line!());
+    assert_eq!(waiting_call.call_id(), call_id);
+    waiting_call.inject_call_timeout().expect(error_line!());
     cm.synchronize().expect(error_line!());
 
     assert_eq!(context.error_count(), 0);
-    assert!(!cm.busy());
-    assert_eq!(context.ended_count(), 1);
-    assert_eq!(context.answers_sent(), 0);
+    assert_eq!(context.event_count(ApplicationEvent::EndedTimeout), 1);
+    assert_eq!(context.busys_sent(), 1);
+    assert!(cm.waiting_call().expect(error_line!()).is_none());
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::ConnectedAndAccepted
+    );
+    assert!(cm.busy());
+
+    // There's nothing left to accept.
+    cm.hold_and_accept_waiting_call().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.start_incoming_count(), 1);
+    assert_eq!(context.active_call().call_id(), active_call.call_id());
 }
 
 #[test]
-fn offer_after_unrelated_hangup() {
+fn hold_and_accept_waiting_call() {
     test_init();
 
-    let context = TestContext::new();
+    let (context, call_id) = receive_waiting_call();
     let mut cm = context.cm();
+    let held_call = context.active_call();
+    let held_connection = context.active_connection();
 
-    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
-    let call_id = CallId::new(context.prng.gen::<u64>());
-    let unrelated_call_id = CallId::new(context.prng.gen::<u64>());
-
-    cm.received_hangup(
-        unrelated_call_id,
-        signaling::ReceivedHangup {
-            sender_device_id: 1,
-            hangup: signaling::Hangup::Normal,
-        },
-    )
-    .expect(error_line!());
+    cm.hold_and_accept_waiting_call().expect(error_line!());
+    cm.synchronize().expect(error_line!());
 
-    cm.received_offer(
-        remote_peer,
-        call_id,
-        random_received_offer(&context.prng, Duration::from_secs(0)),
-    )
-    .expect(error_line!());
+    assert_eq!(context.error_count(), 0);
+    assert!(!held_connection
+        .app_connection()
+        .unwrap()
+        .outgoing_audio_enabled());
+    assert_eq!(
+        Some(true),
+        held_connection
+            .last_sent_sender_status()
+            .and_then(|status| status.on_hold)
+    );
+    assert_eq!(context.start_incoming_count(), 2);
 
-    cm.synchronize().expect(error_line!());
+    let active_call = context.active_call();
+    assert_eq!(active_call.call_id(), call_id);
 
     cm.proceed(
         call_id,
@@ -1029,17 +1344,43 @@ fn offer_after_unrelated_hangup() {
         None,
     )
     .expect(error_line!());
+    cm.synchronize().expect(error_line!());
 
+    let mut connection = active_call.get_connection(1).expect(error_line!());
+    connection.inject_ice_connected().expect(error_line!());
     cm.synchronize().expect(error_line!());
 
-    assert_eq!(context.error_count(), 0);
+    // The waiting call is accepted as soon as it connects.
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::ConnectedAndAccepted
+    );
+    assert_eq!(context.event_count(ApplicationEvent::LocalAccepted), 2);
+
+    // Ending the new call leaves the held call in place until it is resumed.
+    cm.hangup().expect(error_line!());
+    cm.synchronize().expect(error_line!());
     assert!(cm.busy());
-    assert_eq!(context.ended_count(), 0);
-    assert_eq!(context.answers_sent(), 1);
+
+    cm.resume_held_call().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.active_call().call_id(), held_call.call_id());
+    assert!(held_connection
+        .app_connection()
+        .unwrap()
+        .outgoing_audio_enabled());
+    assert_eq!(
+        Some(false),
+        held_connection
+            .last_sent_sender_status()
+            .and_then(|status| status.on_hold)
+    );
 }
 
 #[test]
-fn offer_after_ice_and_hangup() {
+fn receive_expired_offer() {
     test_init();
 
     let context = TestContext::new();
@@ -1047,45 +1388,23 @@ fn offer_after_ice_and_hangup() {
 
     let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
     let call_id = CallId::new(context.prng.gen::<u64>());
-
-    cm.received_ice(call_id, random_received_ice_candidate(&context.prng))
-        .expect(error_line!());
-    cm.received_hangup(
-        call_id,
-        signaling::ReceivedHangup {
-            sender_device_id: 1,
-            hangup: signaling::Hangup::Normal,
-        },
-    )
-    .expect(error_line!());
-
+    let age = Duration::from_secs(86400); // one whole day
     cm.received_offer(
         remote_peer,
         call_id,
-        random_received_offer(&context.prng, Duration::from_secs(0)),
-    )
-    .expect(error_line!());
-
-    cm.synchronize().expect(error_line!());
-
-    cm.proceed(
-        call_id,
-        format!("CONTEXT-{}", context.prng.gen::<u16>()),
-        CallConfig::default().with_data_mode(DataMode::Normal),
-        None,
+        random_received_offer(&context.prng, age),
     )
     .expect(error_line!());
 
     cm.synchronize().expect(error_line!());
 
     assert_eq!(context.error_count(), 0);
+    assert_eq!(context.offer_expired_count(), 1);
     assert!(!cm.busy());
-    assert_eq!(context.ended_count(), 1);
-    assert_eq!(context.answers_sent(), 0);
 }
 
 #[test]
-fn offer_after_hangup_and_ice() {
+fn receive_offer_before_age_limit() {
     test_init();
 
     let context = TestContext::new();
@@ -1093,45 +1412,23 @@ fn offer_after_hangup_and_ice() {
 
     let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
     let call_id = CallId::new(context.prng.gen::<u64>());
-
-    cm.received_hangup(
-        call_id,
-        signaling::ReceivedHangup {
-            sender_device_id: 1,
-            hangup: signaling::Hangup::Normal,
-        },
-    )
-    .expect(error_line!());
-    cm.received_ice(call_id, random_received_ice_candidate(&context.prng))
-        .expect(error_line!());
-
+    let age = MAX_MESSAGE_AGE - Duration::from_secs(1);
     cm.received_offer(
         remote_peer,
         call_id,
-        random_received_offer(&context.prng, Duration::from_secs(0)),
-    )
-    .expect(error_line!());
-
-    cm.synchronize().expect(error_line!());
-
-    cm.proceed(
-        call_id,
-        format!("CONTEXT-{}", context.prng.gen::<u16>()),
-        CallConfig::default().with_data_mode(DataMode::Normal),
-        None,
+        random_received_offer(&context.prng, age),
     )
     .expect(error_line!());
 
     cm.synchronize().expect(error_line!());
 
     assert_eq!(context.error_count(), 0);
-    assert!(!cm.busy());
-    assert_eq!(context.ended_count(), 1);
-    assert_eq!(context.answers_sent(), 0);
+    assert_eq!(context.offer_expired_count(), 0);
+    assert!(cm.busy());
 }
 
 #[test]
-fn offer_after_hangup_with_intervening_ice_for_other_call() {
+fn receive_offer_at_age_limit() {
     test_init();
 
     let context = TestContext::new();
@@ -1139,49 +1436,23 @@ fn offer_after_hangup_with_intervening_ice_for_other_call() {
 
     let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
     let call_id = CallId::new(context.prng.gen::<u64>());
-    let unrelated_call_id = CallId::new(context.prng.gen::<u64>());
-
-    cm.received_hangup(
-        call_id,
-        signaling::ReceivedHangup {
-            sender_device_id: 1,
-            hangup: signaling::Hangup::Normal,
-        },
-    )
-    .expect(error_line!());
-    cm.received_ice(
-        unrelated_call_id,
-        random_received_ice_candidate(&context.prng),
-    )
-    .expect(error_line!());
-
+    let age = MAX_MESSAGE_AGE;
     cm.received_offer(
         remote_peer,
         call_id,
-        random_received_offer(&context.prng, Duration::from_secs(0)),
-    )
-    .expect(error_line!());
-
-    cm.synchronize().expect(error_line!());
-
-    cm.proceed(
-        call_id,
-        format!("CONTEXT-{}", context.prng.gen::<u16>()),
-        CallConfig::default().with_data_mode(DataMode::Normal),
-        None,
+        random_received_offer(&context.prng, age),
     )
     .expect(error_line!());
 
     cm.synchronize().expect(error_line!());
 
     assert_eq!(context.error_count(), 0);
+    assert_eq!(context.offer_expired_count(), 0);
     assert!(cm.busy());
-    assert_eq!(context.ended_count(), 0);
-    assert_eq!(context.answers_sent(), 1);
 }
 
 #[test]
-fn offer_after_hangup_with_intervening_hangup_for_other_call() {
+fn receive_expired_offer_after_age_limit() {
     test_init();
 
     let context = TestContext::new();
@@ -1189,52 +1460,23 @@ fn offer_after_hangup_with_intervening_hangup_for_other_call() {
 
     let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
     let call_id = CallId::new(context.prng.gen::<u64>());
-    let unrelated_call_id = CallId::new(context.prng.gen::<u64>());
-
-    cm.received_hangup(
-        call_id,
-        signaling::ReceivedHangup {
-            sender_device_id: 1,
-            hangup: signaling::Hangup::Normal,
-        },
-    )
-    .expect(error_line!());
-    cm.received_hangup(
-        unrelated_call_id,
-        signaling::ReceivedHangup {
-            sender_device_id: 1,
-            hangup: signaling::Hangup::Normal,
-        },
-    )
-    .expect(error_line!());
-
+    let age = MAX_MESSAGE_AGE + Duration::from_secs(1);
     cm.received_offer(
         remote_peer,
         call_id,
-        random_received_offer(&context.prng, Duration::from_secs(0)),
-    )
-    .expect(error_line!());
-
-    cm.synchronize().expect(error_line!());
-
-    cm.proceed(
-        call_id,
-        format!("CONTEXT-{}", context.prng.gen::<u16>()),
-        CallConfig::default().with_data_mode(DataMode::Normal),
-        None,
+        random_received_offer(&context.prng, age),
     )
     .expect(error_line!());
 
     cm.synchronize().expect(error_line!());
 
     assert_eq!(context.error_count(), 0);
-    assert!(cm.busy());
-    assert_eq!(context.ended_count(), 0);
-    assert_eq!(context.answers_sent(), 1);
+    assert_eq!(context.offer_expired_count(), 1);
+    assert!(!cm.busy());
 }
 
 #[test]
-fn offer_after_ice_with_previous_ice_for_other_call() {
+fn offer_after_ice() {
     // We don't actually expose a way to automatically test that the ICE candidates are handled.
     // You can check manually by running with RUST_LOG=ringrtc::core::connection/ice_candidates
     test_init();
@@ -1244,13 +1486,9 @@ fn offer_after_ice_with_previous_ice_for_other_call() {
 
     let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
     let call_id = CallId::new(context.prng.gen::<u64>());
-    let unrelated_call_id = CallId::new(context.prng.gen::<u64>());
 
-    cm.received_ice(
-        unrelated_call_id,
-        random_received_ice_candidate(&context.prng),
-    )
-    .expect(error_line!());
+    cm.received_ice(call_id, random_received_ice_candidate(&context.prng))
+        .expect(error_line!());
     cm.received_ice(call_id, random_received_ice_candidate(&context.prng))
         .expect(error_line!());
 
@@ -1301,28 +1539,24 @@ fn offer_after_ice_with_previous_ice_for_other_call() {
     assert!(cm.busy());
 }
 
-// Two users are in an accepted call. The remote user's leg is ended and they
-// call the local user who is still in the original call. The local user should
-// quietly end the active call and start handling the new incoming one.
 #[test]
-fn recall_when_connected() {
+fn offer_after_unrelated_ice() {
+    // We don't actually expose a way to automatically test that the ICE candidates are handled.
+    // You can check manually by running with RUST_LOG=ringrtc::core::connection/ice_candidates
     test_init();
 
-    let context = connect_inbound_call();
+    let context = TestContext::new();
     let mut cm = context.cm();
 
-    // Verify that one incoming call was started so far.
-    assert_eq!(context.start_incoming_count(), 1);
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    let other_call_id = CallId::new(context.prng.gen::<u64>());
 
-    // Create a new incoming call with same remote
-    let remote_peer = {
-        let active_call = context.active_call();
-        let remote_peer = active_call.remote_peer().expect(error_line!());
-        remote_peer.to_owned()
-    };
-    info!("active remote_peer: {}", remote_peer);
+    cm.received_ice(other_call_id, random_received_ice_candidate(&context.prng))
+        .expect(error_line!());
+    cm.received_ice(other_call_id, random_received_ice_candidate(&context.prng))
+        .expect(error_line!());
 
-    let call_id = CallId::new(context.prng.gen::<u64>());
     cm.received_offer(
         remote_peer,
         call_id,
@@ -1332,165 +1566,586 @@ fn recall_when_connected() {
 
     cm.synchronize().expect(error_line!());
 
-    assert_eq!(context.error_count(), 0);
-    assert_eq!(context.event_count(ApplicationEvent::EndedRemoteReCall), 1);
-    assert_eq!(context.normal_hangups_sent(), 0);
-    assert_eq!(context.busys_sent(), 0);
+    assert!(cm.active_call().is_ok());
+    assert_eq!(context.start_outgoing_count(), 0);
+    assert_eq!(context.start_incoming_count(), 1);
 
-    // Previous call should be concluded.
-    assert_eq!(context.call_concluded_count(), 1);
+    let active_call = context.active_call();
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::WaitingToProceed
+    );
 
-    // The newly incoming call should have been started (not yet proceeded).
-    assert_eq!(context.start_incoming_count(), 2);
+    cm.proceed(
+        active_call.call_id(),
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default().with_data_mode(DataMode::Normal),
+        None,
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    let connection = active_call.get_connection(1).expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        connection.state().expect(error_line!()),
+        ConnectionState::ConnectingBeforeAccepted
+    );
+
+    assert_eq!(context.answers_sent(), 1);
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::ConnectingBeforeAccepted
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+    assert!(cm.busy());
 }
 
 #[test]
-fn group_call_ring() {
+fn offer_after_hangup() {
     test_init();
 
     let context = TestContext::new();
     let mut cm = context.cm();
 
-    let group_id = vec![1, 1, 1];
-    let sender_id = vec![1, 2, 3];
-    let ring_id = group_call::RingId::from(42);
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
 
-    let message = protobuf::signaling::CallMessage {
-        ring_intention: Some(protobuf::signaling::call_message::RingIntention {
-            group_id: Some(group_id.clone()),
-            ring_id: Some(ring_id.into()),
-            r#type: Some(protobuf::signaling::call_message::ring_intention::Type::Ring.into()),
-        }),
-        ..Default::default()
-    };
-    let mut buf = Vec::new();
-    message
-        .encode(&mut buf)
-        .expect("cannot fail encoding to Vec");
+    cm.received_hangup(
+        call_id,
+        signaling::ReceivedHangup {
+            sender_device_id: 1,
+            hangup: signaling::Hangup::Normal,
+        },
+    )
+    .expect(error_line!());
+
+    cm.received_offer(
+        remote_peer,
+        call_id,
+        random_received_offer(&context.prng, Duration::from_secs(0)),
+    )
+    .expect(error_line!());
 
-    cm.received_call_message(sender_id.clone(), 1, 2, buf, Duration::ZERO)
-        .expect(error_line!());
     cm.synchronize().expect(error_line!());
 
-    let ring_updates = cm
-        .platform()
-        .expect(error_line!())
-        .take_group_call_ring_updates();
-    match &ring_updates[..] {
-        [update] => {
-            assert_eq!(
-                &ringrtc::sim::sim_platform::GroupCallRingUpdate {
-                    group_id,
-                    ring_id,
-                    sender_id,
-                    update: group_call::RingUpdate::Requested
-                },
-                update
-            );
-        }
-        _ => panic!("unexpected ring updates: {:?}", ring_updates),
-    }
+    cm.proceed(
+        call_id,
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default().with_data_mode(DataMode::Normal),
+        None,
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
 
+    assert_eq!(context.error_count(), 0);
     assert!(!cm.busy());
+    assert_eq!(context.ended_count(), 1);
+    assert_eq!(context.answers_sent(), 0);
 }
 
 #[test]
-fn group_call_ring_expired() {
+fn offer_after_unrelated_hangup() {
     test_init();
 
     let context = TestContext::new();
     let mut cm = context.cm();
 
-    let group_id = vec![1, 1, 1];
-    let sender_id = vec![1, 2, 3];
-    let ring_id = group_call::RingId::from(42);
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    let unrelated_call_id = CallId::new(context.prng.gen::<u64>());
 
-    let message = protobuf::signaling::CallMessage {
-        ring_intention: Some(protobuf::signaling::call_message::RingIntention {
-            group_id: Some(group_id.clone()),
-            ring_id: Some(ring_id.into()),
-            r#type: Some(protobuf::signaling::call_message::ring_intention::Type::Ring.into()),
-        }),
-        ..Default::default()
-    };
-    let mut buf = Vec::new();
-    message
-        .encode(&mut buf)
-        .expect("cannot fail encoding to Vec");
+    cm.received_hangup(
+        unrelated_call_id,
+        signaling::ReceivedHangup {
+            sender_device_id: 1,
+            hangup: signaling::Hangup::Normal,
+        },
+    )
+    .expect(error_line!());
 
-    cm.received_call_message(
-        sender_id.clone(),
-        1,
-        2,
-        buf,
-        ringrtc::core::call_manager::MAX_MESSAGE_AGE + Duration::from_millis(1),
+    cm.received_offer(
+        remote_peer,
+        call_id,
+        random_received_offer(&context.prng, Duration::from_secs(0)),
     )
     .expect(error_line!());
+
     cm.synchronize().expect(error_line!());
 
-    let ring_updates = cm
-        .platform()
-        .expect(error_line!())
-        .take_group_call_ring_updates();
-    match &ring_updates[..] {
-        [update] => {
-            assert_eq!(
-                &ringrtc::sim::sim_platform::GroupCallRingUpdate {
-                    group_id,
-                    ring_id,
-                    sender_id,
-                    update: group_call::RingUpdate::ExpiredRequest
-                },
-                update
-            );
-        }
-        _ => panic!("unexpected ring updates: {:?}", ring_updates),
-    }
+    cm.proceed(
+        call_id,
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default().with_data_mode(DataMode::Normal),
+        None,
+    )
+    .expect(error_line!());
 
-    assert!(!cm.busy());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert!(cm.busy());
+    assert_eq!(context.ended_count(), 0);
+    assert_eq!(context.answers_sent(), 1);
 }
 
 #[test]
-fn group_call_ring_busy_in_direct_call() {
+fn offer_after_ice_and_hangup() {
     test_init();
 
-    let context = connect_inbound_call();
+    let context = TestContext::new();
     let mut cm = context.cm();
 
-    let self_uuid = vec![1, 0, 1];
-    cm.set_self_uuid(self_uuid.clone()).expect(error_line!());
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
 
-    let group_id = vec![1, 1, 1];
-    let sender_id = vec![1, 2, 3];
-    let ring_id = group_call::RingId::from(42);
+    cm.received_ice(call_id, random_received_ice_candidate(&context.prng))
+        .expect(error_line!());
+    cm.received_hangup(
+        call_id,
+        signaling::ReceivedHangup {
+            sender_device_id: 1,
+            hangup: signaling::Hangup::Normal,
+        },
+    )
+    .expect(error_line!());
 
-    let message = protobuf::signaling::CallMessage {
-        ring_intention: Some(protobuf::signaling::call_message::RingIntention {
-            group_id: Some(group_id.clone()),
-            ring_id: Some(ring_id.into()),
-            r#type: Some(protobuf::signaling::call_message::ring_intention::Type::Ring.into()),
-        }),
-        ..Default::default()
-    };
-    let mut buf = Vec::new();
-    message
-        .encode(&mut buf)
-        .expect("cannot fail encoding to Vec");
+    cm.received_offer(
+        remote_peer,
+        call_id,
+        random_received_offer(&context.prng, Duration::from_secs(0)),
+    )
+    .expect(error_line!());
 
-    cm.received_call_message(sender_id.clone(), 1, 2, buf, Duration::ZERO)
-        .expect(error_line!());
     cm.synchronize().expect(error_line!());
 
-    let ring_updates = cm
-        .platform()
-        .expect(error_line!())
-        .take_group_call_ring_updates();
-    match &ring_updates[..] {
-        [update] => {
-            assert_eq!(
-                &ringrtc::sim::sim_platform::GroupCallRingUpdate {
-                    group_id: group_id.clone(),
-                    ring_id,
+    cm.proceed(
+        call_id,
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default().with_data_mode(DataMode::Normal),
+        None,
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert!(!cm.busy());
+    assert_eq!(context.ended_count(), 1);
+    assert_eq!(context.answers_sent(), 0);
+}
+
+#[test]
+fn offer_after_hangup_and_ice() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
+
+    cm.received_hangup(
+        call_id,
+        signaling::ReceivedHangup {
+            sender_device_id: 1,
+            hangup: signaling::Hangup::Normal,
+        },
+    )
+    .expect(error_line!());
+    cm.received_ice(call_id, random_received_ice_candidate(&context.prng))
+        .expect(error_line!());
+
+    cm.received_offer(
+        remote_peer,
+        call_id,
+        random_received_offer(&context.prng, Duration::from_secs(0)),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    cm.proceed(
+        call_id,
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default().with_data_mode(DataMode::Normal),
+        None,
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert!(!cm.busy());
+    assert_eq!(context.ended_count(), 1);
+    assert_eq!(context.answers_sent(), 0);
+}
+
+#[test]
+fn offer_after_hangup_with_intervening_ice_for_other_call() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    let unrelated_call_id = CallId::new(context.prng.gen::<u64>());
+
+    cm.received_hangup(
+        call_id,
+        signaling::ReceivedHangup {
+            sender_device_id: 1,
+            hangup: signaling::Hangup::Normal,
+        },
+    )
+    .expect(error_line!());
+    cm.received_ice(
+        unrelated_call_id,
+        random_received_ice_candidate(&context.prng),
+    )
+    .expect(error_line!());
+
+    cm.received_offer(
+        remote_peer,
+        call_id,
+        random_received_offer(&context.prng, Duration::from_secs(0)),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    cm.proceed(
+        call_id,
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default().with_data_mode(DataMode::Normal),
+        None,
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert!(cm.busy());
+    assert_eq!(context.ended_count(), 0);
+    assert_eq!(context.answers_sent(), 1);
+}
+
+#[test]
+fn offer_after_hangup_with_intervening_hangup_for_other_call() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    let unrelated_call_id = CallId::new(context.prng.gen::<u64>());
+
+    cm.received_hangup(
+        call_id,
+        signaling::ReceivedHangup {
+            sender_device_id: 1,
+            hangup: signaling::Hangup::Normal,
+        },
+    )
+    .expect(error_line!());
+    cm.received_hangup(
+        unrelated_call_id,
+        signaling::ReceivedHangup {
+            sender_device_id: 1,
+            hangup: signaling::Hangup::Normal,
+        },
+    )
+    .expect(error_line!());
+
+    cm.received_offer(
+        remote_peer,
+        call_id,
+        random_received_offer(&context.prng, Duration::from_secs(0)),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    cm.proceed(
+        call_id,
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default().with_data_mode(DataMode::Normal),
+        None,
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert!(cm.busy());
+    assert_eq!(context.ended_count(), 0);
+    assert_eq!(context.answers_sent(), 1);
+}
+
+#[test]
+fn offer_after_ice_with_previous_ice_for_other_call() {
+    // We don't actually expose a way to automatically test that the ICE candidates are handled.
+    // You can check manually by running with RUST_LOG=ringrtc::core::connection/ice_candidates
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    let unrelated_call_id = CallId::new(context.prng.gen::<u64>());
+
+    cm.received_ice(
+        unrelated_call_id,
+        random_received_ice_candidate(&context.prng),
+    )
+    .expect(error_line!());
+    cm.received_ice(call_id, random_received_ice_candidate(&context.prng))
+        .expect(error_line!());
+
+    cm.received_offer(
+        remote_peer,
+        call_id,
+        random_received_offer(&context.prng, Duration::from_secs(0)),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert!(cm.active_call().is_ok());
+    assert_eq!(context.start_outgoing_count(), 0);
+    assert_eq!(context.start_incoming_count(), 1);
+
+    let active_call = context.active_call();
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::WaitingToProceed
+    );
+
+    cm.proceed(
+        active_call.call_id(),
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default().with_data_mode(DataMode::Normal),
+        None,
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    let connection = active_call.get_connection(1).expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        connection.state().expect(error_line!()),
+        ConnectionState::ConnectingBeforeAccepted
+    );
+
+    assert_eq!(context.answers_sent(), 1);
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::ConnectingBeforeAccepted
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+    assert!(cm.busy());
+}
+
+// Two users are in an accepted call. The remote user's leg is ended and they
+// call the local user who is still in the original call. The local user should
+// quietly end the active call and start handling the new incoming one.
+#[test]
+fn recall_when_connected() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+
+    // Verify that one incoming call was started so far.
+    assert_eq!(context.start_incoming_count(), 1);
+
+    // Create a new incoming call with same remote
+    let remote_peer = {
+        let active_call = context.active_call();
+        let remote_peer = active_call.remote_peer().expect(error_line!());
+        remote_peer.to_owned()
+    };
+    info!("active remote_peer: {}", remote_peer);
+
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    cm.received_offer(
+        remote_peer,
+        call_id,
+        random_received_offer(&context.prng, Duration::from_secs(0)),
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.event_count(ApplicationEvent::EndedRemoteReCall), 1);
+    assert_eq!(context.normal_hangups_sent(), 0);
+    assert_eq!(context.busys_sent(), 0);
+
+    // Previous call should be concluded.
+    assert_eq!(context.call_concluded_count(), 1);
+
+    // The newly incoming call should have been started (not yet proceeded).
+    assert_eq!(context.start_incoming_count(), 2);
+}
+
+#[test]
+fn group_call_ring() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let group_id = vec![1, 1, 1];
+    let sender_id = vec![1, 2, 3];
+    let ring_id = group_call::RingId::from(42);
+
+    let message = protobuf::signaling::CallMessage {
+        ring_intention: Some(protobuf::signaling::call_message::RingIntention {
+            group_id: Some(group_id.clone()),
+            ring_id: Some(ring_id.into()),
+            r#type: Some(protobuf::signaling::call_message::ring_intention::Type::Ring.into()),
+        }),
+        ..Default::default()
+    };
+    let mut buf = Vec::new();
+    message
+        .encode(&mut buf)
+        .expect("cannot fail encoding to Vec");
+
+    cm.received_call_message(sender_id.clone(), 1, 2, buf, Duration::ZERO)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let ring_updates = cm
+        .platform()
+        .expect(error_line!())
+        .take_group_call_ring_updates();
+    match &ring_updates[..] {
+        [update] => {
+            assert_eq!(
+                &ringrtc::sim::sim_platform::GroupCallRingUpdate {
+                    group_id,
+                    ring_id,
+                    sender_id,
+                    update: group_call::RingUpdate::Requested
+                },
+                update
+            );
+        }
+        _ => panic!("unexpected ring updates: {:?}", ring_updates),
+    }
+
+    assert!(!cm.busy());
+}
+
+#[test]
+fn group_call_ring_expired() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let group_id = vec![1, 1, 1];
+    let sender_id = vec![1, 2, 3];
+    let ring_id = group_call::RingId::from(42);
+
+    let message = protobuf::signaling::CallMessage {
+        ring_intention: Some(protobuf::signaling::call_message::RingIntention {
+            group_id: Some(group_id.clone()),
+            ring_id: Some(ring_id.into()),
+            r#type: Some(protobuf::signaling::call_message::ring_intention::Type::Ring.into()),
+        }),
+        ..Default::default()
+    };
+    let mut buf = Vec::new();
+    message
+        .encode(&mut buf)
+        .expect("cannot fail encoding to Vec");
+
+    cm.received_call_message(
+        sender_id.clone(),
+        1,
+        2,
+        buf,
+        ringrtc::core::call_manager::MAX_MESSAGE_AGE + Duration::from_millis(1),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let ring_updates = cm
+        .platform()
+        .expect(error_line!())
+        .take_group_call_ring_updates();
+    match &ring_updates[..] {
+        [update] => {
+            assert_eq!(
+                &ringrtc::sim::sim_platform::GroupCallRingUpdate {
+                    group_id,
+                    ring_id,
+                    sender_id,
+                    update: group_call::RingUpdate::ExpiredRequest
+                },
+                update
+            );
+        }
+        _ => panic!("unexpected ring updates: {:?}", ring_updates),
+    }
+
+    assert!(!cm.busy());
+}
+
+#[test]
+fn group_call_ring_busy_in_direct_call() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+
+    let self_uuid = vec![1, 0, 1];
+    cm.set_self_uuid(self_uuid.clone()).expect(error_line!());
+
+    let group_id = vec![1, 1, 1];
+    let sender_id = vec![1, 2, 3];
+    let ring_id = group_call::RingId::from(42);
+
+    let message = protobuf::signaling::CallMessage {
+        ring_intention: Some(protobuf::signaling::call_message::RingIntention {
+            group_id: Some(group_id.clone()),
+            ring_id: Some(ring_id.into()),
+            r#type: Some(protobuf::signaling::call_message::ring_intention::Type::Ring.into()),
+        }),
+        ..Default::default()
+    };
+    let mut buf = Vec::new();
+    message
+        .encode(&mut buf)
+        .expect("cannot fail encoding to Vec");
+
+    cm.received_call_message(sender_id.clone(), 1, 2, buf, Duration::ZERO)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let ring_updates = cm
+        .platform()
+        .expect(error_line!())
+        .take_group_call_ring_updates();
+    match &ring_updates[..] {
+        [update] => {
+            assert_eq!(
+                &ringrtc::sim::sim_platform::GroupCallRingUpdate {
+                    group_id: group_id.clone(),
+                    ring_id,
                     sender_id,
                     update: group_call::RingUpdate::BusyLocally
                 },
@@ -1584,7 +2239,204 @@ fn group_call_ring_busy_in_group_call() {
                     group_id: group_id.clone(),
                     ring_id,
                     sender_id,
-                    update: group_call::RingUpdate::BusyLocally
+                    update: group_call::RingUpdate::BusyLocally
+                },
+                update
+            );
+        }
+        _ => panic!("unexpected ring updates: {:?}", ring_updates),
+    }
+
+    let messages = cm
+        .platform()
+        .expect(error_line!())
+        .take_outgoing_call_messages();
+    match &messages[..] {
+        [message] => {
+            assert_eq!(&self_uuid[..], &message.recipient_id[..]);
+            assert_eq!(
+                group_call::SignalingMessageUrgency::HandleImmediately,
+                message.urgency
+            );
+            let call_message = protobuf::signaling::CallMessage::decode(&message.message[..])
+                .expect(error_line!());
+            assert_eq!(
+                protobuf::signaling::CallMessage {
+                    ring_response: Some(protobuf::signaling::call_message::RingResponse {
+                        group_id: Some(group_id),
+                        ring_id: Some(ring_id.into()),
+                        r#type: Some(
+                            protobuf::signaling::call_message::ring_response::Type::Busy.into()
+                        ),
+                    }),
+                    ..Default::default()
+                },
+                call_message
+            );
+        }
+        _ => panic!("unexpected messages: {:?}", messages),
+    }
+
+    assert!(cm.busy());
+}
+
+#[test]
+fn group_call_ring_responses() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let group_id = vec![1, 1, 1];
+    let sender_id = vec![1, 2, 3];
+    let ring_id = group_call::RingId::from(42);
+
+    let message = protobuf::signaling::CallMessage {
+        ring_response: Some(protobuf::signaling::call_message::RingResponse {
+            group_id: Some(group_id.clone()),
+            ring_id: Some(ring_id.into()),
+            r#type: Some(protobuf::signaling::call_message::ring_response::Type::Declined.into()),
+        }),
+        ..Default::default()
+    };
+    let mut buf = Vec::new();
+    message
+        .encode(&mut buf)
+        .expect("cannot fail encoding to Vec");
+
+    cm.received_call_message(sender_id.clone(), 1, 2, buf.clone(), Duration::ZERO)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let ring_updates = cm
+        .platform()
+        .expect(error_line!())
+        .take_group_call_ring_updates();
+    // Oops, we didn't set the current user's UUID.
+    assert_eq!(
+        &[] as &[ringrtc::sim::sim_platform::GroupCallRingUpdate],
+        ring_updates
+    );
+
+    cm.set_self_uuid(sender_id.clone()).expect(error_line!());
+
+    // Okay, try again.
+    cm.received_call_message(sender_id.clone(), 1, 2, buf, Duration::ZERO)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let ring_updates = cm
+        .platform()
+        .expect(error_line!())
+        .take_group_call_ring_updates();
+
+    match &ring_updates[..] {
+        [update] => {
+            assert_eq!(
+                &ringrtc::sim::sim_platform::GroupCallRingUpdate {
+                    group_id: group_id.clone(),
+                    ring_id,
+                    sender_id: sender_id.clone(),
+                    update: group_call::RingUpdate::DeclinedOnAnotherDevice
+                },
+                update
+            );
+        }
+        _ => panic!("unexpected ring updates: {:?}", ring_updates),
+    }
+
+    // We should ignore "ringing" messages regardless.
+    let message = protobuf::signaling::CallMessage {
+        ring_response: Some(protobuf::signaling::call_message::RingResponse {
+            group_id: Some(group_id),
+            ring_id: Some(ring_id.into()),
+            r#type: Some(protobuf::signaling::call_message::ring_response::Type::Ringing.into()),
+        }),
+        ..Default::default()
+    };
+    let mut buf = Vec::new();
+    message
+        .encode(&mut buf)
+        .expect("cannot fail encoding to Vec");
+
+    cm.received_call_message(sender_id, 1, 2, buf, Duration::ZERO)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let ring_updates = cm
+        .platform()
+        .expect(error_line!())
+        .take_group_call_ring_updates();
+    assert_eq!(
+        &[] as &[ringrtc::sim::sim_platform::GroupCallRingUpdate],
+        ring_updates
+    );
+}
+
+#[test]
+fn group_call_ring_timeout() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let group_id = vec![1, 1, 1];
+    let sender_id = vec![1, 2, 3];
+    let ring_id = group_call::RingId::from(42);
+
+    let message = protobuf::signaling::CallMessage {
+        ring_intention: Some(protobuf::signaling::call_message::RingIntention {
+            group_id: Some(group_id.clone()),
+            ring_id: Some(ring_id.into()),
+            r#type: Some(protobuf::signaling::call_message::ring_intention::Type::Ring.into()),
+        }),
+        ..Default::default()
+    };
+    let mut buf = Vec::new();
+    message
+        .encode(&mut buf)
+        .expect("cannot fail encoding to Vec");
+
+    cm.received_call_message(sender_id.clone(), 1, 2, buf, Duration::ZERO)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let ring_updates = cm
+        .platform()
+        .expect(error_line!())
+        .take_group_call_ring_updates();
+    match &ring_updates[..] {
+        [update] => {
+            assert_eq!(
+                &ringrtc::sim::sim_platform::GroupCallRingUpdate {
+                    group_id: group_id.clone(),
+                    ring_id,
+                    sender_id: sender_id.clone(),
+                    update: group_call::RingUpdate::Requested
+                },
+                update
+            );
+        }
+        _ => panic!("unexpected ring updates: {:?}", ring_updates),
+    }
+    assert!(!cm.busy());
+
+    // We set the timeout to 1 second for testing, so we only need to sleep a bit longer than that.
+    std::thread::sleep(Duration::from_millis(1_200));
+    cm.synchronize().expect(error_line!());
+
+    let ring_updates = cm
+        .platform()
+        .expect(error_line!())
+        .take_group_call_ring_updates();
+    match &ring_updates[..] {
+        [update] => {
+            assert_eq!(
+                &ringrtc::sim::sim_platform::GroupCallRingUpdate {
+                    group_id: group_id.clone(),
+                    ring_id,
+                    sender_id,
+                    update: group_call::RingUpdate::ExpiredRequest
                 },
                 update
             );
@@ -1592,279 +2444,445 @@ fn group_call_ring_busy_in_group_call() {
         _ => panic!("unexpected ring updates: {:?}", ring_updates),
     }
 
+    // If we join at this point, we should not send an "Accepted" message.
+    // (Even though real time hasn't elapsed, the cancellation removes the ring from the table.)
+    let group_call_id = context.create_group_call(group_id).expect(error_line!());
+    cm.join(group_call_id);
+    cm.synchronize().expect(error_line!());
+
     let messages = cm
         .platform()
         .expect(error_line!())
         .take_outgoing_call_messages();
-    match &messages[..] {
-        [message] => {
-            assert_eq!(&self_uuid[..], &message.recipient_id[..]);
-            assert_eq!(
-                group_call::SignalingMessageUrgency::HandleImmediately,
-                message.urgency
-            );
-            let call_message = protobuf::signaling::CallMessage::decode(&message.message[..])
-                .expect(error_line!());
-            assert_eq!(
-                protobuf::signaling::CallMessage {
-                    ring_response: Some(protobuf::signaling::call_message::RingResponse {
-                        group_id: Some(group_id),
-                        ring_id: Some(ring_id.into()),
-                        r#type: Some(
-                            protobuf::signaling::call_message::ring_response::Type::Busy.into()
-                        ),
-                    }),
-                    ..Default::default()
-                },
-                call_message
-            );
-        }
-        _ => panic!("unexpected messages: {:?}", messages),
-    }
+    assert_eq!(
+        &[] as &[ringrtc::sim::sim_platform::OutgoingCallMessage],
+        &messages[..]
+    );
+}
+
+#[test]
+fn received_status_before_accepted() {
+    let context = start_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+
+    active_connection
+        .handle_received_incoming_media(MediaStream::new(webrtc::Arc::null()))
+        .expect(error_line!());
+
+    active_connection
+        .inject_received_sender_status_via_rtp_data(
+            active_call.call_id(),
+            signaling::SenderStatus {
+                video_enabled: Some(true),
+                sharing_screen: None,
+                audio_enabled: Some(true),
+                on_hold: None,
+            },
+            1,
+        )
+        .expect(error_line!());
+
+    active_connection
+        .inject_received_receiver_status_via_rtp_data(
+            active_call.call_id(),
+            DataRate::from_bps(50_000),
+            1,
+        )
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::RemoteAudioEnable), 0);
+    assert_eq!(context.event_count(ApplicationEvent::RemoteVideoEnable), 0);
+
+    assert_eq!(
+        Some(2_000_000),
+        active_connection
+            .app_connection()
+            .unwrap()
+            .max_bitrate_bps()
+    );
+
+    cm.accept_call(active_call.call_id()).expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::RemoteAudioEnable), 1);
+    assert_eq!(context.event_count(ApplicationEvent::RemoteVideoEnable), 1);
+
+    assert_eq!(
+        Some(50_000),
+        active_connection
+            .app_connection()
+            .unwrap()
+            .max_bitrate_bps()
+    );
+}
+
+#[test]
+fn inbound_call_early_media() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let mut received = random_received_offer(&context.prng, Duration::from_secs(0));
+    let mut v4 = received.offer.to_v4().expect(error_line!());
+    v4.early_media = Some(true);
+    received.offer =
+        signaling::Offer::from_v4(received.offer.call_media_type, v4).expect(error_line!());
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    cm.received_offer(remote_peer, call_id, received)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    cm.proceed(
+        call_id,
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default().with_data_mode(DataMode::Normal),
+        None,
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    // Not connected yet, so there is no way to send it.
+    cm.start_early_media(call_id).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert!(!active_connection
+        .app_connection()
+        .unwrap()
+        .outgoing_audio_enabled());
+
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.event_count(ApplicationEvent::LocalRinging), 1);
+
+    cm.start_early_media(call_id).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert!(active_connection
+        .app_connection()
+        .unwrap()
+        .outgoing_audio_enabled());
+    let message = active_connection
+        .app_connection()
+        .unwrap()
+        .last_sent_rtp_message()
+        .expect(error_line!());
+    assert_eq!(
+        Some(u64::from(call_id)),
+        message.early_media.expect(error_line!()).id
+    );
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::ConnectedBeforeAccepted
+    );
+
+    active_connection
+        .handle_received_incoming_media(MediaStream::new(webrtc::Arc::null()))
+        .expect(error_line!());
+    cm.accept_call(call_id).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::ConnectedAndAccepted
+    );
+    assert_eq!(context.event_count(ApplicationEvent::LocalAccepted), 1);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn inbound_call_early_media_not_offered() {
+    test_init();
+
+    let context = start_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    cm.start_early_media(active_call.call_id())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert!(!active_connection
+        .app_connection()
+        .unwrap()
+        .outgoing_audio_enabled());
+    assert!(active_connection
+        .app_connection()
+        .unwrap()
+        .last_sent_rtp_message()
+        .map_or(true, |message| message.early_media.is_none()));
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn inbound_call_handed_off_by_caller() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let remote_peer = active_call.remote_peer().expect(error_line!()).clone();
+
+    let mut received = random_received_offer(&context.prng, Duration::from_secs(0));
+    received.offer = received.offer.to_handoff().expect(error_line!());
+    received.sender_device_id = 2;
+    cm.received_offer(remote_peer, active_call.call_id(), received)
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    // The new leg is answered alongside the active one, without ringing.
+    assert_eq!(context.start_incoming_count(), 1);
+    assert_eq!(context.answers_sent(), 2);
+    assert_eq!(context.busys_sent(), 0);
+    assert_eq!(active_call.active_device_id().expect(error_line!()), 1);
+
+    let mut received_ice = random_received_ice_candidate(&context.prng);
+    received_ice.sender_device_id = 2;
+    cm.received_ice(active_call.call_id(), received_ice)
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    let mut handoff_connection = active_call.get_connection(2).expect(error_line!());
+    assert_eq!(
+        handoff_connection.state().expect(error_line!()),
+        ConnectionState::ConnectingBeforeAccepted
+    );
+
+    info!("test: injecting ice connected for the handoff connection");
+    handoff_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(active_call.active_device_id().expect(error_line!()), 2);
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::ConnectedAndAccepted
+    );
+    assert_eq!(
+        handoff_connection.state().expect(error_line!()),
+        ConnectionState::ConnectedAndAccepted
+    );
+    assert!(active_call.get_connection(1).is_err());
+    assert_eq!(context.handed_off_hangups_sent(), 1);
+    assert_eq!(context.event_count(ApplicationEvent::RemoteHandedOff), 1);
+    assert_eq!(context.event_count(ApplicationEvent::LocalRinging), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+    assert!(cm.busy());
+}
+
+#[test]
+fn inbound_call_handoff_hangup_before_connected() {
+    test_init();
+
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let remote_peer = active_call.remote_peer().expect(error_line!()).clone();
+
+    let mut received = random_received_offer(&context.prng, Duration::from_secs(0));
+    received.offer = received.offer.to_handoff().expect(error_line!());
+    received.sender_device_id = 2;
+    cm.received_offer(remote_peer, active_call.call_id(), received)
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+    assert!(active_call.get_connection(2).is_ok());
+
+    cm.received_hangup(
+        active_call.call_id(),
+        signaling::ReceivedHangup {
+            sender_device_id: 2,
+            hangup: signaling::Hangup::Normal,
+        },
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
 
+    // The call keeps going with the original leg.
+    assert!(active_call.get_connection(2).is_err());
+    assert_eq!(active_call.active_device_id().expect(error_line!()), 1);
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::ConnectedAndAccepted
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
     assert!(cm.busy());
 }
 
 #[test]
-fn group_call_ring_responses() {
+fn receive_handoff_offer_without_active_call() {
     test_init();
 
     let context = TestContext::new();
     let mut cm = context.cm();
 
-    let group_id = vec![1, 1, 1];
-    let sender_id = vec![1, 2, 3];
-    let ring_id = group_call::RingId::from(42);
-
-    let message = protobuf::signaling::CallMessage {
-        ring_response: Some(protobuf::signaling::call_message::RingResponse {
-            group_id: Some(group_id.clone()),
-            ring_id: Some(ring_id.into()),
-            r#type: Some(protobuf::signaling::call_message::ring_response::Type::Declined.into()),
-        }),
-        ..Default::default()
-    };
-    let mut buf = Vec::new();
-    message
-        .encode(&mut buf)
-        .expect("cannot fail encoding to Vec");
-
-    cm.received_call_message(sender_id.clone(), 1, 2, buf.clone(), Duration::ZERO)
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    let mut received = random_received_offer(&context.prng, Duration::from_secs(0));
+    received.offer = received.offer.to_handoff().expect(error_line!());
+    cm.received_offer(remote_peer, call_id, received)
         .expect(error_line!());
-    cm.synchronize().expect(error_line!());
-
-    let ring_updates = cm
-        .platform()
-        .expect(error_line!())
-        .take_group_call_ring_updates();
-    // Oops, we didn't set the current user's UUID.
-    assert_eq!(
-        &[] as &[ringrtc::sim::sim_platform::GroupCallRingUpdate],
-        ring_updates
-    );
-
-    cm.set_self_uuid(sender_id.clone()).expect(error_line!());
 
-    // Okay, try again.
-    cm.received_call_message(sender_id.clone(), 1, 2, buf, Duration::ZERO)
-        .expect(error_line!());
     cm.synchronize().expect(error_line!());
 
-    let ring_updates = cm
-        .platform()
-        .expect(error_line!())
-        .take_group_call_ring_updates();
+    assert!(cm.active_call().is_err());
+    assert_eq!(context.start_incoming_count(), 0);
+    assert_eq!(context.busys_sent(), 0);
+    assert_eq!(context.call_concluded_count(), 1);
+    assert_eq!(context.error_count(), 0);
+    assert!(!cm.busy());
+}
 
-    match &ring_updates[..] {
-        [update] => {
-            assert_eq!(
-                &ringrtc::sim::sim_platform::GroupCallRingUpdate {
-                    group_id: group_id.clone(),
-                    ring_id,
-                    sender_id: sender_id.clone(),
-                    update: group_call::RingUpdate::DeclinedOnAnotherDevice
-                },
-                update
-            );
-        }
-        _ => panic!("unexpected ring updates: {:?}", ring_updates),
-    }
+#[test]
+fn inbound_call_hangup_handed_off() {
+    test_init();
 
-    // We should ignore "ringing" messages regardless.
-    let message = protobuf::signaling::CallMessage {
-        ring_response: Some(protobuf::signaling::call_message::RingResponse {
-            group_id: Some(group_id),
-            ring_id: Some(ring_id.into()),
-            r#type: Some(protobuf::signaling::call_message::ring_response::Type::Ringing.into()),
-        }),
-        ..Default::default()
-    };
-    let mut buf = Vec::new();
-    message
-        .encode(&mut buf)
-        .expect("cannot fail encoding to Vec");
+    let context = connect_inbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    cm.received_hangup(
+        active_call.call_id(),
+        signaling::ReceivedHangup {
+            sender_device_id: 1,
+            hangup: signaling::Hangup::HandedOff(2),
+        },
+    )
+    .expect(error_line!());
 
-    cm.received_call_message(sender_id, 1, 2, buf, Duration::ZERO)
-        .expect(error_line!());
     cm.synchronize().expect(error_line!());
 
-    let ring_updates = cm
-        .platform()
-        .expect(error_line!())
-        .take_group_call_ring_updates();
-    assert_eq!(
-        &[] as &[ringrtc::sim::sim_platform::GroupCallRingUpdate],
-        ring_updates
-    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.event_count(ApplicationEvent::EndedHandedOff), 1);
+    assert!(!cm.busy());
 }
 
 #[test]
-fn group_call_ring_timeout() {
+fn inbound_call_hand_off() {
     test_init();
 
-    let context = TestContext::new();
+    let context = connect_inbound_call();
     let mut cm = context.cm();
+    let active_call = context.active_call();
 
-    let group_id = vec![1, 1, 1];
-    let sender_id = vec![1, 2, 3];
-    let ring_id = group_call::RingId::from(42);
-
-    let message = protobuf::signaling::CallMessage {
-        ring_intention: Some(protobuf::signaling::call_message::RingIntention {
-            group_id: Some(group_id.clone()),
-            ring_id: Some(ring_id.into()),
-            r#type: Some(protobuf::signaling::call_message::ring_intention::Type::Ring.into()),
-        }),
-        ..Default::default()
-    };
-    let mut buf = Vec::new();
-    message
-        .encode(&mut buf)
-        .expect("cannot fail encoding to Vec");
+    let self_uuid = vec![1, 0, 1];
+    cm.set_self_uuid(self_uuid.clone()).expect(error_line!());
 
-    cm.received_call_message(sender_id.clone(), 1, 2, buf, Duration::ZERO)
+    let remote_user_id = vec![1, 2, 3];
+    cm.hand_off(active_call.call_id(), remote_user_id.clone(), 2)
         .expect(error_line!());
-    cm.synchronize().expect(error_line!());
-
-    let ring_updates = cm
-        .platform()
-        .expect(error_line!())
-        .take_group_call_ring_updates();
-    match &ring_updates[..] {
-        [update] => {
-            assert_eq!(
-                &ringrtc::sim::sim_platform::GroupCallRingUpdate {
-                    group_id: group_id.clone(),
-                    ring_id,
-                    sender_id: sender_id.clone(),
-                    update: group_call::RingUpdate::Requested
-                },
-                update
-            );
-        }
-        _ => panic!("unexpected ring updates: {:?}", ring_updates),
-    }
-    assert!(!cm.busy());
 
-    // We set the timeout to 1 second for testing, so we only need to sleep a bit longer than that.
-    std::thread::sleep(Duration::from_millis(1_200));
     cm.synchronize().expect(error_line!());
 
-    let ring_updates = cm
+    let messages = cm
         .platform()
         .expect(error_line!())
-        .take_group_call_ring_updates();
-    match &ring_updates[..] {
-        [update] => {
+        .take_outgoing_call_messages();
+    match &messages[..] {
+        [message] => {
+            assert_eq!(&self_uuid[..], &message.recipient_id[..]);
+            let call_message = protobuf::signaling::CallMessage::decode(&message.message[..])
+                .expect(error_line!());
             assert_eq!(
-                &ringrtc::sim::sim_platform::GroupCallRingUpdate {
-                    group_id: group_id.clone(),
-                    ring_id,
-                    sender_id,
-                    update: group_call::RingUpdate::ExpiredRequest
+                protobuf::signaling::CallMessage {
+                    handoff_request: Some(protobuf::signaling::call_message::HandoffRequest {
+                        call_id: Some(active_call.call_id().as_u64()),
+                        target_device_id: Some(2),
+                        remote_user_id: Some(remote_user_id),
+                        remote_device_id: Some(1),
+                        video: Some(false),
+                    }),
+                    ..Default::default()
                 },
-                update
+                call_message
             );
         }
-        _ => panic!("unexpected ring updates: {:?}", ring_updates),
+        _ => {
+            panic!("unexpected messages: {:?}", messages)
+        }
     }
 
-    // If we join at this point, we should not send an "Accepted" message.
-    // (Even though real time hasn't elapsed, the cancellation removes the ring from the table.)
-    let group_call_id = context.create_group_call(group_id).expect(error_line!());
-    cm.join(group_call_id);
-    cm.synchronize().expect(error_line!());
-
-    let messages = cm
-        .platform()
-        .expect(error_line!())
-        .take_outgoing_call_messages();
+    // The call continues until the remote peer moves it to the target device.
     assert_eq!(
-        &[] as &[ringrtc::sim::sim_platform::OutgoingCallMessage],
-        &messages[..]
+        active_call.state().expect(error_line!()),
+        CallState::ConnectedAndAccepted
     );
+    assert_eq!(context.ended_count(), 0);
+    assert!(cm.busy());
 }
 
 #[test]
-fn received_status_before_accepted() {
-    let context = start_inbound_call();
+fn receive_handoff_request() {
+    test_init();
+
+    let context = TestContext::new();
     let mut cm = context.cm();
-    let active_call = context.active_call();
-    let mut active_connection = context.active_connection();
 
-    active_connection
-        .inject_ice_connected()
-        .expect(error_line!());
+    let self_uuid = vec![1, 0, 1];
+    cm.set_self_uuid(self_uuid.clone()).expect(error_line!());
 
-    active_connection
-        .handle_received_incoming_media(MediaStream::new(webrtc::Arc::null()))
-        .expect(error_line!());
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    let message = protobuf::signaling::CallMessage {
+        handoff_request: Some(protobuf::signaling::call_message::HandoffRequest {
+            call_id: Some(call_id.as_u64()),
+            target_device_id: Some(2),
+            remote_user_id: Some(vec![1, 2, 3]),
+            remote_device_id: Some(3),
+            video: Some(true),
+        }),
+        ..Default::default()
+    };
+    let mut buf = Vec::new();
+    message
+        .encode(&mut buf)
+        .expect("cannot fail encoding to Vec");
 
-    active_connection
-        .inject_received_sender_status_via_rtp_data(
-            active_call.call_id(),
-            signaling::SenderStatus {
-                video_enabled: Some(true),
-                sharing_screen: None,
-                audio_enabled: Some(true),
-            },
-            1,
-        )
+    // Requests for other devices, and from other users, are ignored.
+    cm.received_call_message(self_uuid.clone(), 1, 3, buf.clone(), Duration::ZERO)
         .expect(error_line!());
-
-    active_connection
-        .inject_received_receiver_status_via_rtp_data(
-            active_call.call_id(),
-            DataRate::from_bps(50_000),
-            1,
-        )
+    cm.received_call_message(vec![1, 2, 3], 1, 2, buf.clone(), Duration::ZERO)
+        .expect(error_line!());
+    cm.received_call_message(self_uuid, 1, 2, buf, Duration::ZERO)
         .expect(error_line!());
 
     cm.synchronize().expect(error_line!());
 
-    assert_eq!(context.event_count(ApplicationEvent::RemoteAudioEnable), 0);
-    assert_eq!(context.event_count(ApplicationEvent::RemoteVideoEnable), 0);
-
-    assert_eq!(
-        Some(2_000_000),
-        active_connection
-            .app_connection()
-            .unwrap()
-            .max_bitrate_bps()
-    );
-
-    cm.accept_call(active_call.call_id()).expect(error_line!());
-
-    cm.synchronize().expect(error_line!());
-
-    assert_eq!(context.event_count(ApplicationEvent::RemoteAudioEnable), 1);
-    assert_eq!(context.event_count(ApplicationEvent::RemoteVideoEnable), 1);
-
     assert_eq!(
-        Some(50_000),
-        active_connection
-            .app_connection()
-            .unwrap()
-            .max_bitrate_bps()
+        cm.platform().expect(error_line!()).take_handoff_requests(),
+        vec![signaling::HandoffRequest {
+            call_id,
+            remote_user_id: vec![1, 2, 3],
+            remote_device_id: 3,
+            call_media_type: CallMediaType::Video,
+        }]
     );
+    assert!(!cm.busy());
 }
@@ -10,17 +10,26 @@ extern crate ringrtc;
 #[macro_use]
 extern crate log;
 
-use std::{net::SocketAddr, thread, time::Duration};
+use std::{
+    collections::HashSet,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
 
 use prost::Message;
 use ringrtc::{
     common::{
         units::DataRate, ApplicationEvent, CallConfig, CallId, CallMediaType, CallState,
-        ConnectionState, DataMode, DeviceId,
+        ConnectionState, DataMode, DeviceId, GlareResolution,
+    },
+    core::{
+        group_call, signaling, signaling_store::SignalingStore,
+        signaling_transport::SignalingTransport,
     },
-    core::{group_call, signaling},
     protobuf,
-    sim::error::SimError,
+    sim::{error::SimError, sim_platform::SimPlatform},
     webrtc,
     webrtc::{
         media::MediaStream,
@@ -109,6 +118,16 @@ fn start_outbound_and_proceed() -> TestContext {
 //
 // Now in the Connecting state.
 fn start_outbound_n_remote_call(n_remotes: u16) -> TestContext {
+    start_outbound_n_remote_call_with_config(
+        n_remotes,
+        CallConfig::default().with_data_mode(DataMode::Normal),
+    )
+}
+
+fn start_outbound_n_remote_call_with_config(
+    n_remotes: u16,
+    call_config: CallConfig,
+) -> TestContext {
     let context = TestContext::new();
     let mut cm = context.cm();
 
@@ -134,7 +153,7 @@ fn start_outbound_n_remote_call(n_remotes: u16) -> TestContext {
     cm.proceed(
         active_call.call_id(),
         format!("CONTEXT-{}", context.prng.gen::<u16>()),
-        CallConfig::default().with_data_mode(DataMode::Normal),
+        call_config,
         None,
     )
     .expect(error_line!());
@@ -241,7 +260,13 @@ fn connected_outbound_call() -> TestContext {
 //
 // Now in the ConnectedAndAccepted state.
 fn connected_and_accepted_outbound_call() -> TestContext {
-    let context = start_outbound_call();
+    connected_and_accepted_outbound_call_with_config(
+        CallConfig::default().with_data_mode(DataMode::Normal),
+    )
+}
+
+fn connected_and_accepted_outbound_call_with_config(call_config: CallConfig) -> TestContext {
+    let context = start_outbound_n_remote_call_with_config(1, call_config);
     let mut cm = context.cm();
     let active_call = context.active_call();
     let mut active_connection = context.active_connection();
@@ -498,6 +523,63 @@ fn outbound_call_connected() {
     let _ = connected_and_accepted_outbound_call();
 }
 
+#[test]
+fn outbound_call_has_wide_call_id() {
+    test_init();
+
+    let context = start_outbound_and_proceed();
+    let cm = context.cm();
+    let call_id = context.active_call().call_id();
+
+    let wide_call_id = cm
+        .wide_call_id(call_id)
+        .expect(error_line!())
+        .expect(error_line!());
+    assert_eq!(wide_call_id.call_id(), call_id);
+    assert_eq!(
+        cm.wide_call_id(CallId::new(call_id.as_u64() + 1))
+            .expect(error_line!()),
+        None
+    );
+}
+
+#[test]
+fn outbound_call_app_data_in_notifications() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    cm.set_call_app_data(call_id, b"account-1".to_vec())
+        .expect(error_line!());
+    cm.create_outgoing_call(remote_peer, call_id, CallMediaType::Audio, 1)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    cm.proceed(
+        call_id,
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default().with_data_mode(DataMode::Normal),
+        None,
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    cm.hangup().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::EndedLocalHangup), 1);
+    let app_data_notified = context.app_data_notified();
+    // At least the start of the call and its end.
+    assert!(app_data_notified.len() >= 2);
+    for app_data in app_data_notified {
+        assert_eq!(app_data, b"account-1");
+    }
+    assert_eq!(context.error_count(), 0);
+}
+
 #[test]
 fn outbound_local_hang_up() {
     test_init();
@@ -709,6 +791,28 @@ fn outbound_call_connected_local_hangup() {
     // TODO - verify that a hangup message was sent via RTP data
 }
 
+#[test]
+fn outbound_call_summary_failure_still_ends_call() {
+    test_init();
+
+    let context = connected_and_accepted_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    context.force_call_summary_fault(true);
+    cm.hangup().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::Terminated
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 1);
+    assert_eq!(context.event_count(ApplicationEvent::EndedLocalHangup), 1);
+    assert!(!cm.busy());
+}
+
 #[test]
 fn outbound_ice_disconnected_after_call_connected_and_reconnect() {
     test_init();
@@ -855,6 +959,7 @@ fn update_sender_status() {
             video_enabled: Some(false),
             sharing_screen: None,
             audio_enabled: Some(false),
+            on_hold: None,
         })
         .expect(error_line!());
 
@@ -867,6 +972,10 @@ fn update_sender_status() {
             video_enabled: Some(false),
             sharing_screen: None,
             audio_enabled: Some(false),
+            on_hold: None,
+            max_audio_bitrate_bps: None,
+            max_video_bitrate_bps: None,
+            max_total_bitrate_bps: None,
         }),
         active_connection.last_sent_sender_status()
     );
@@ -876,6 +985,7 @@ fn update_sender_status() {
             video_enabled: Some(true),
             sharing_screen: None,
             audio_enabled: Some(true),
+            on_hold: None,
         })
         .expect(error_line!());
 
@@ -884,6 +994,7 @@ fn update_sender_status() {
             video_enabled: None,
             sharing_screen: Some(true),
             audio_enabled: None,
+            on_hold: None,
         })
         .expect(error_line!());
 
@@ -896,6 +1007,10 @@ fn update_sender_status() {
             video_enabled: Some(true),
             sharing_screen: Some(true),
             audio_enabled: Some(true),
+            on_hold: None,
+            max_audio_bitrate_bps: None,
+            max_video_bitrate_bps: None,
+            max_total_bitrate_bps: None,
         }),
         active_connection.last_sent_sender_status()
     );
@@ -905,6 +1020,7 @@ fn update_sender_status() {
             video_enabled: None,
             sharing_screen: Some(false),
             audio_enabled: None,
+            on_hold: None,
         })
         .expect(error_line!());
 
@@ -917,11 +1033,201 @@ fn update_sender_status() {
             video_enabled: Some(true),
             sharing_screen: Some(false),
             audio_enabled: Some(true),
+            on_hold: None,
+            max_audio_bitrate_bps: None,
+            max_video_bitrate_bps: None,
+            max_total_bitrate_bps: None,
         }),
         active_connection.last_sent_sender_status()
     );
 }
 
+#[test]
+fn set_on_hold() {
+    test_init();
+
+    let context = connected_and_accepted_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let active_connection = context.active_connection();
+
+    assert!(active_connection
+        .app_connection()
+        .unwrap()
+        .outgoing_audio_enabled());
+
+    cm.set_on_hold(active_call.call_id(), true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.error_count(), 0);
+
+    assert!(!active_connection
+        .app_connection()
+        .unwrap()
+        .outgoing_audio_enabled());
+    assert_eq!(
+        Some(true),
+        active_connection
+            .last_sent_sender_status()
+            .and_then(|status| status.on_hold)
+    );
+
+    cm.set_on_hold(active_call.call_id(), false)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.error_count(), 0);
+
+    assert!(active_connection
+        .app_connection()
+        .unwrap()
+        .outgoing_audio_enabled());
+    assert_eq!(
+        Some(false),
+        active_connection
+            .last_sent_sender_status()
+            .and_then(|status| status.on_hold)
+    );
+}
+
+#[test]
+fn set_on_hold_before_accepted() {
+    test_init();
+
+    let context = start_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let active_connection = context.active_connection();
+
+    cm.set_on_hold(active_call.call_id(), true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(None, active_connection.last_sent_sender_status());
+}
+
+fn last_ice_restart_sent(context: &TestContext) -> Option<signaling::IceRestart> {
+    context
+        .last_ice_sent()?
+        .ice
+        .candidates
+        .iter()
+        .find_map(|candidate| candidate.ice_restart())
+}
+
+fn received_ice_restart() -> signaling::ReceivedIce {
+    signaling::ReceivedIce {
+        ice: signaling::Ice {
+            candidates: vec![
+                signaling::IceCandidate::from_ice_restart(signaling::IceRestart {
+                    ice_ufrag: "UFRAG".to_owned(),
+                    ice_pwd: "PWD".to_owned(),
+                })
+                .expect(error_line!()),
+            ],
+        },
+        sender_device_id: 1,
+    }
+}
+
+#[test]
+fn restart_ice() {
+    test_init();
+
+    let context = connected_and_accepted_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    cm.restart_ice(active_call.call_id()).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ice_candidates_sent(), 1);
+    assert!(last_ice_restart_sent(&context).is_some());
+
+    // The remote side answers with its own credentials, which completes the restart.
+    cm.received_ice(active_call.call_id(), received_ice_restart())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ice_candidates_sent(), 1);
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::ConnectedAndAccepted
+    );
+}
+
+#[test]
+fn restart_ice_before_accepted() {
+    test_init();
+
+    let context = start_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    cm.restart_ice(active_call.call_id()).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert!(last_ice_restart_sent(&context).is_none());
+}
+
+#[test]
+fn received_ice_restart_is_answered() {
+    test_init();
+
+    let context = connected_and_accepted_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    cm.received_ice(active_call.call_id(), received_ice_restart())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ice_candidates_sent(), 1);
+    assert!(last_ice_restart_sent(&context).is_some());
+}
+
+#[test]
+fn restart_ice_on_disconnect() {
+    test_init();
+
+    let context = connected_and_accepted_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+
+    cm.set_restart_ice_on_disconnect(true).expect(error_line!());
+
+    active_connection
+        .inject_ice_disconnected()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.event_count(ApplicationEvent::Reconnecting), 1);
+    assert!(last_ice_restart_sent(&context).is_some());
+}
+
+#[test]
+fn no_restart_ice_on_disconnect_by_default() {
+    test_init();
+
+    let context = connected_and_accepted_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .inject_ice_disconnected()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.event_count(ApplicationEvent::Reconnecting), 1);
+    assert!(last_ice_restart_sent(&context).is_none());
+}
+
 #[test]
 fn update_data_mode_default() {
     test_init();
@@ -1115,68 +1421,431 @@ fn update_data_mode_when_relayed_remote() {
     update_data_mode_when_relayed(false);
 }
 
-#[test]
-fn inject_local_ice_candidate() {
+fn media_stalled(hang_up: bool) {
     test_init();
 
-    let context = connected_and_accepted_outbound_call();
+    // Anything shorter than the minimum is raised to it.
+    let context = connected_and_accepted_outbound_call_with_config(
+        CallConfig::default().with_media_stall_timeout(Duration::from_millis(10), hang_up),
+    );
     let mut cm = context.cm();
-    let mut active_connection = context.active_connection();
 
-    let ice_candidate = random_ice_candidate(&context.prng);
-    let force_send = true;
-    active_connection
-        .inject_local_ice_candidate(ice_candidate, force_send, "", None)
-        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.event_count(ApplicationEvent::MediaStalled), 0);
 
+    // Nothing is ever received in the simulation. The minimum is 3 seconds,
+    // and watching starts with the first check a second after connecting.
+    thread::sleep(Duration::from_millis(4_500));
     cm.synchronize().expect(error_line!());
+
     assert_eq!(context.error_count(), 0);
-    assert_eq!(context.ice_candidates_sent(), 1);
+    assert_eq!(context.event_count(ApplicationEvent::MediaStalled), 1);
+    if hang_up {
+        assert_eq!(context.event_count(ApplicationEvent::EndedMediaStalled), 1);
+        assert!(!cm.busy());
+    } else {
+        assert_eq!(context.event_count(ApplicationEvent::EndedMediaStalled), 0);
+        assert!(cm.busy());
+    }
 }
 
 #[test]
-fn receive_remote_ice_candidate() {
+fn media_stalled_without_hang_up() {
+    media_stalled(false);
+}
+
+#[test]
+fn media_stalled_with_hang_up() {
+    media_stalled(true);
+}
+
+#[test]
+fn network_route_changed_reaches_platform() {
     test_init();
 
     let context = connected_and_accepted_outbound_call();
     let mut cm = context.cm();
-    let active_call = context.active_call();
+    let active_connection = context.active_connection();
 
-    // add a received ICE candidate
-    let call_id = active_call.call_id();
-    cm.received_ice(call_id, random_received_ice_candidate(&context.prng))
-        .expect("receive_ice");
+    let network_route = NetworkRoute {
+        local_adapter_type: NetworkAdapterType::Vpn,
+        local_adapter_type_under_vpn: NetworkAdapterType::Wifi,
+        local_relayed: false,
+        local_relay_protocol: TransportProtocol::Unknown,
+        remote_relayed: true,
+    };
+    active_connection
+        .inject_ice_network_route_changed(network_route)
+        .unwrap();
     cm.synchronize().expect(error_line!());
-
     assert_eq!(context.error_count(), 0);
 
-    // TODO -- verify the ice candidate was buffered
-    // TODO -- verify the ice candidate was applied to the peer_connection
+    let routes = cm.platform().expect(error_line!()).take_network_routes();
+    assert_eq!(routes.last(), Some(&network_route));
+    assert!(routes.last().unwrap().relayed());
 }
 
 #[test]
-fn ice_candidate_removal() {
+fn inject_local_ice_candidate() {
     test_init();
 
     let context = connected_and_accepted_outbound_call();
     let mut cm = context.cm();
     let mut active_connection = context.active_connection();
 
-    let removed_addresses: Vec<SocketAddr> =
-        vec!["1.2.3.4:5".parse().unwrap(), "6.7.8.9:0".parse().unwrap()];
+    let ice_candidate = random_ice_candidate(&context.prng);
     let force_send = true;
     active_connection
-        .inject_local_ice_candidates_removed(removed_addresses.clone(), force_send)
+        .inject_local_ice_candidate(ice_candidate, force_send, "", None)
         .expect(error_line!());
 
     cm.synchronize().expect(error_line!());
     assert_eq!(context.error_count(), 0);
-    assert_eq!(context.ice_candidates_sent(), 2);
-    let last_sent = context
-        .last_ice_sent()
-        .expect("ICE candidate removal was sent");
+    assert_eq!(context.ice_candidates_sent(), 1);
+}
 
-    let active_call = context.active_call();
+#[test]
+fn enable_video_accepted() {
+    test_init();
+
+    let context = connected_and_accepted_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    cm.enable_video(active_call.call_id()).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // The response is retransmitted, but only reported once.
+    for _ in 0..2 {
+        active_connection
+            .inject_received_video_response_via_rtp_data(active_call.call_id(), 1, true)
+            .expect(error_line!());
+    }
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        context.event_count(ApplicationEvent::LocalVideoAddAccepted),
+        1
+    );
+
+    cm.disable_video(active_call.call_id())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // Enabling again needs a new answer, so the old one doesn't count.
+    cm.enable_video(active_call.call_id()).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    active_connection
+        .inject_received_video_response_via_rtp_data(active_call.call_id(), 1, true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        context.event_count(ApplicationEvent::LocalVideoAddAccepted),
+        1
+    );
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn enable_video_declined() {
+    test_init();
+
+    let context = connected_and_accepted_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    cm.enable_video(active_call.call_id()).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // A response to some other request is ignored.
+    active_connection
+        .inject_received_video_response_via_rtp_data(active_call.call_id(), 2, true)
+        .expect(error_line!());
+    active_connection
+        .inject_received_video_response_via_rtp_data(active_call.call_id(), 1, false)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        context.event_count(ApplicationEvent::LocalVideoAddAccepted),
+        0
+    );
+    assert_eq!(
+        context.event_count(ApplicationEvent::LocalVideoAddDeclined),
+        1
+    );
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn remote_video_add_and_remove() {
+    test_init();
+
+    let context = connected_and_accepted_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .inject_received_video_request_via_rtp_data(active_call.call_id(), 1, true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(
+        context.event_count(ApplicationEvent::RemoteVideoAddRequested),
+        1
+    );
+
+    cm.respond_to_video_request(active_call.call_id(), true)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::RemoteVideoAdded), 1);
+
+    active_connection
+        .inject_received_video_request_via_rtp_data(active_call.call_id(), 2, false)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::RemoteVideoRemoved), 1);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn outbound_call_remote_early_media() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    cm.call(remote_peer, CallMediaType::Audio, 1)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default()
+            .with_data_mode(DataMode::Normal)
+            .with_early_media(true),
+        None,
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    cm.received_answer(
+        active_call.call_id(),
+        random_received_answer(&context.prng, 1),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let mut active_connection = context.active_connection();
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.event_count(ApplicationEvent::RemoteRinging), 1);
+
+    // The message is retransmitted, but only reported once.
+    for _ in 0..2 {
+        active_connection
+            .inject_received_early_media_via_rtp_data(active_call.call_id())
+            .expect(error_line!());
+    }
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::RemoteEarlyMedia), 1);
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::ConnectedBeforeAccepted
+    );
+    // Only the callee's audio is played; nothing is sent until accepted.
+    assert!(!active_connection
+        .app_connection()
+        .unwrap()
+        .outgoing_audio_enabled());
+
+    active_connection
+        .inject_received_incoming_media(MediaStream::new(webrtc::Arc::null()))
+        .expect(error_line!());
+    active_connection
+        .inject_received_accepted_via_rtp_data(active_call.call_id())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::ConnectedAndAccepted
+    );
+    assert_eq!(context.event_count(ApplicationEvent::RemoteAccepted), 1);
+    assert_eq!(context.event_count(ApplicationEvent::RemoteEarlyMedia), 1);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn outbound_call_remote_early_media_not_offered() {
+    test_init();
+
+    let context = connected_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    active_connection
+        .inject_received_early_media_via_rtp_data(active_call.call_id())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::RemoteEarlyMedia), 0);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn audio_waits_for_audio_session() {
+    test_init();
+
+    let context = connected_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    cm.set_audio_session_active(false).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    active_connection
+        .inject_received_incoming_media(MediaStream::new(webrtc::Arc::null()))
+        .expect(error_line!());
+    active_connection
+        .inject_received_accepted_via_rtp_data(active_call.call_id())
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::ConnectedAndAccepted
+    );
+    assert!(active_connection
+        .app_connection()
+        .unwrap()
+        .outgoing_audio_enabled());
+    assert!(!active_connection.app_connection().unwrap().audio_enabled());
+
+    cm.set_audio_session_active(true).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert!(active_connection.app_connection().unwrap().audio_enabled());
+
+    cm.set_audio_session_active(false).expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert!(!active_connection.app_connection().unwrap().audio_enabled());
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+}
+
+#[test]
+fn relay_only_by_default() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+    cm.set_relay_only_by_default(true).expect(error_line!());
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    cm.call(remote_peer, CallMediaType::Audio, 1)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default().with_data_mode(DataMode::Normal),
+        None,
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    cm.received_answer(
+        active_call.call_id(),
+        random_received_answer(&context.prng, 1),
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    let mut connection = active_call.get_connection(1).expect(error_line!());
+    assert!(connection.call_config().relay_only);
+
+    let force_send = true;
+    connection
+        .inject_local_ice_candidate(random_ice_candidate(&context.prng), force_send, "", None)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.ice_candidates_sent(), 0);
+
+    connection
+        .inject_local_ice_candidate(
+            random_ice_candidate(&context.prng),
+            force_send,
+            "",
+            Some(TransportProtocol::Udp),
+        )
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.ice_candidates_sent(), 1);
+    assert_eq!(context.error_count(), 0);
+}
+
+#[test]
+fn receive_remote_ice_candidate() {
+    test_init();
+
+    let context = connected_and_accepted_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    // add a received ICE candidate
+    let call_id = active_call.call_id();
+    cm.received_ice(call_id, random_received_ice_candidate(&context.prng))
+        .expect("receive_ice");
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+
+    // TODO -- verify the ice candidate was buffered
+    // TODO -- verify the ice candidate was applied to the peer_connection
+}
+
+#[test]
+fn ice_candidate_removal() {
+    test_init();
+
+    let context = connected_and_accepted_outbound_call();
+    let mut cm = context.cm();
+    let mut active_connection = context.active_connection();
+
+    let removed_addresses: Vec<SocketAddr> =
+        vec!["1.2.3.4:5".parse().unwrap(), "6.7.8.9:0".parse().unwrap()];
+    let force_send = true;
+    active_connection
+        .inject_local_ice_candidates_removed(removed_addresses.clone(), force_send)
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ice_candidates_sent(), 2);
+    let last_sent = context
+        .last_ice_sent()
+        .expect("ICE candidate removal was sent");
+
+    let active_call = context.active_call();
     let call_id = active_call.call_id();
     cm.received_ice(
         call_id,
@@ -1324,6 +1993,94 @@ fn received_remote_hangup_before_connection() {
     assert!(!cm.busy());
 }
 
+#[test]
+fn received_remote_decline_with_reason() {
+    test_init();
+
+    let context = start_outbound_and_proceed();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    cm.received_hangup(
+        active_call.call_id(),
+        signaling::ReceivedHangup {
+            sender_device_id: 1,
+            hangup: signaling::Hangup::Declined(signaling::DeclineReason::TextReplySent),
+        },
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedRemoteDeclinedTextReplySent),
+        1
+    );
+    assert_eq!(context.event_count(ApplicationEvent::EndedRemoteHangup), 0);
+    // Other callees should get Hangup/Declined.
+    assert_eq!(context.declined_hangups_sent(), 1);
+    assert!(!cm.busy());
+}
+
+#[test]
+fn received_remote_decline_by_policy() {
+    test_init();
+
+    let context = start_outbound_and_proceed();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    cm.received_hangup(
+        active_call.call_id(),
+        signaling::ReceivedHangup {
+            sender_device_id: 1,
+            hangup: signaling::Hangup::Declined(signaling::DeclineReason::Policy),
+        },
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedRemoteDeclinedByPolicy),
+        1
+    );
+    assert_eq!(context.event_count(ApplicationEvent::EndedRemoteHangup), 0);
+    // Other callees should get Hangup/Declined.
+    assert_eq!(context.declined_hangups_sent(), 1);
+    assert!(!cm.busy());
+}
+
+#[test]
+fn received_remote_hangup_network_failure() {
+    test_init();
+
+    let context = connected_and_accepted_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+
+    cm.received_hangup(
+        active_call.call_id(),
+        signaling::ReceivedHangup {
+            sender_device_id: 1,
+            hangup: signaling::Hangup::NetworkFailure,
+        },
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedRemoteHangupNetworkFailure),
+        1
+    );
+    assert_eq!(context.event_count(ApplicationEvent::EndedRemoteHangup), 0);
+    assert!(!cm.busy());
+}
+
 #[test]
 fn received_remote_hangup_before_connection_with_message_in_flight() {
     test_init();
@@ -1552,6 +2309,7 @@ fn received_remote_video_status() {
                     video_enabled: Some(enable),
                     sharing_screen: None,
                     audio_enabled: None,
+                    on_hold: None,
                 },
                 i,
             )
@@ -1587,6 +2345,7 @@ fn received_remote_video_status() {
                 video_enabled: Some(true),
                 sharing_screen: None,
                 audio_enabled: None,
+                on_hold: None,
             },
             1,
         )
@@ -1599,6 +2358,7 @@ fn received_remote_video_status() {
                 video_enabled: Some(false),
                 sharing_screen: None,
                 audio_enabled: None,
+                on_hold: None,
             },
             2,
         )
@@ -1641,6 +2401,7 @@ fn ignore_duplicate_remote_video_status() {
                 video_enabled: Some(true),
                 sharing_screen: None,
                 audio_enabled: None,
+                on_hold: None,
             },
             0,
         )
@@ -1654,6 +2415,7 @@ fn ignore_duplicate_remote_video_status() {
                 video_enabled: Some(true),
                 sharing_screen: None,
                 audio_enabled: None,
+                on_hold: None,
             },
             1,
         )
@@ -1667,6 +2429,7 @@ fn ignore_duplicate_remote_video_status() {
                 video_enabled: Some(false),
                 sharing_screen: None,
                 audio_enabled: None,
+                on_hold: None,
             },
             2,
         )
@@ -1680,6 +2443,7 @@ fn ignore_duplicate_remote_video_status() {
                 video_enabled: Some(false),
                 sharing_screen: None,
                 audio_enabled: None,
+                on_hold: None,
             },
             3,
         )
@@ -1725,6 +2489,7 @@ fn received_remote_sharing_screen_status() {
                     video_enabled: None,
                     sharing_screen: Some(enable),
                     audio_enabled: None,
+                    on_hold: None,
                 },
                 i,
             )
@@ -1754,6 +2519,7 @@ fn received_remote_sharing_screen_status() {
                 video_enabled: None,
                 sharing_screen: Some(true),
                 audio_enabled: None,
+                on_hold: None,
             },
             1,
         )
@@ -1766,6 +2532,7 @@ fn received_remote_sharing_screen_status() {
                 video_enabled: None,
                 sharing_screen: Some(false),
                 audio_enabled: None,
+                on_hold: None,
             },
             2,
         )
@@ -1802,6 +2569,7 @@ fn ignore_duplicate_remote_screen_sharing_status() {
                 video_enabled: None,
                 sharing_screen: Some(true),
                 audio_enabled: None,
+                on_hold: None,
             },
             0,
         )
@@ -1815,6 +2583,7 @@ fn ignore_duplicate_remote_screen_sharing_status() {
                 video_enabled: None,
                 sharing_screen: Some(true),
                 audio_enabled: None,
+                on_hold: None,
             },
             1,
         )
@@ -1828,6 +2597,7 @@ fn ignore_duplicate_remote_screen_sharing_status() {
                 video_enabled: None,
                 sharing_screen: Some(false),
                 audio_enabled: None,
+                on_hold: None,
             },
             2,
         )
@@ -1841,6 +2611,7 @@ fn ignore_duplicate_remote_screen_sharing_status() {
                 video_enabled: None,
                 sharing_screen: Some(false),
                 audio_enabled: None,
+                on_hold: None,
             },
             3,
         )
@@ -1892,6 +2663,7 @@ fn received_remote_audio_status() {
                     video_enabled: None,
                     sharing_screen: None,
                     audio_enabled: Some(enable),
+                    on_hold: None,
                 },
                 i,
             )
@@ -1927,6 +2699,7 @@ fn received_remote_audio_status() {
                 video_enabled: None,
                 sharing_screen: None,
                 audio_enabled: Some(true),
+                on_hold: None,
             },
             1,
         )
@@ -1939,6 +2712,7 @@ fn received_remote_audio_status() {
                 video_enabled: None,
                 sharing_screen: None,
                 audio_enabled: Some(false),
+                on_hold: None,
             },
             2,
         )
@@ -1981,6 +2755,7 @@ fn ignore_duplicate_remote_audio_status() {
                 video_enabled: None,
                 sharing_screen: None,
                 audio_enabled: Some(true),
+                on_hold: None,
             },
             0,
         )
@@ -1994,6 +2769,7 @@ fn ignore_duplicate_remote_audio_status() {
                 video_enabled: None,
                 sharing_screen: None,
                 audio_enabled: Some(true),
+                on_hold: None,
             },
             1,
         )
@@ -2007,6 +2783,7 @@ fn ignore_duplicate_remote_audio_status() {
                 video_enabled: None,
                 sharing_screen: None,
                 audio_enabled: Some(false),
+                on_hold: None,
             },
             2,
         )
@@ -2020,6 +2797,7 @@ fn ignore_duplicate_remote_audio_status() {
                 video_enabled: None,
                 sharing_screen: None,
                 audio_enabled: Some(false),
+                on_hold: None,
             },
             3,
         )
@@ -2048,6 +2826,7 @@ fn received_remote_multiple_status() {
                 video_enabled: Some(false),
                 sharing_screen: Some(true),
                 audio_enabled: Some(false),
+                on_hold: None,
             },
             1,
         )
@@ -2060,6 +2839,7 @@ fn received_remote_multiple_status() {
                 video_enabled: Some(true),
                 sharing_screen: Some(false),
                 audio_enabled: Some(true),
+                on_hold: None,
             },
             2,
         )
@@ -2080,6 +2860,34 @@ fn received_remote_multiple_status() {
     assert_eq!(context.event_count(ApplicationEvent::RemoteAudioDisable), 1);
 }
 
+#[test]
+fn received_remote_hold() {
+    test_init();
+
+    let context = connected_and_accepted_outbound_call();
+    let mut cm = context.cm();
+    let active_call = context.active_call();
+    let mut active_connection = context.active_connection();
+
+    for (on_hold, seqnum) in [(true, 1), (true, 2), (false, 3)] {
+        active_connection
+            .inject_received_sender_status_via_rtp_data(
+                active_call.call_id(),
+                signaling::SenderStatus {
+                    on_hold: Some(on_hold),
+                    ..Default::default()
+                },
+                seqnum,
+            )
+            .expect(error_line!());
+        cm.synchronize().expect(error_line!());
+    }
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.event_count(ApplicationEvent::RemoteHeld), 1);
+    assert_eq!(context.event_count(ApplicationEvent::RemoteResumed), 1);
+}
+
 #[test]
 fn received_status_before_accepted() {
     let context = start_outbound_call();
@@ -2102,6 +2910,7 @@ fn received_status_before_accepted() {
                 video_enabled: Some(true),
                 sharing_screen: None,
                 audio_enabled: Some(true),
+                on_hold: None,
             },
             1,
         )
@@ -2160,6 +2969,9 @@ fn call_timeout_before_connect() {
 
     assert_eq!(context.error_count(), 0);
     assert_eq!(context.event_count(ApplicationEvent::EndedTimeout), 1);
+    // The callee learns that nobody answered rather than that the call was hung up.
+    assert_eq!(context.ring_timeout_hangups_sent(), 1);
+    assert_eq!(context.normal_hangups_sent(), 0);
 }
 
 #[test]
@@ -2180,6 +2992,81 @@ fn call_timeout_after_connect() {
     assert!(cm.busy());
 }
 
+#[test]
+fn configured_ring_timeout() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    cm.call(remote_peer, CallMediaType::Audio, 1)
+        .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    // Anything shorter than the minimum is raised to it.
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default().with_ring_timeout(Duration::from_millis(10)),
+        None,
+    )
+    .expect(error_line!());
+    cm.synchronize().expect(error_line!());
+    assert_eq!(context.event_count(ApplicationEvent::EndedTimeout), 0);
+
+    // The minimum is 1 second, so we only need to sleep a bit longer than that.
+    thread::sleep(Duration::from_millis(1_200));
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.event_count(ApplicationEvent::EndedTimeout), 1);
+    assert!(!cm.busy());
+}
+
+#[test]
+fn connect_timeout_before_connect() {
+    test_init();
+
+    let context = start_outbound_call();
+    let mut cm = context.cm();
+    let mut active_call = context.active_call();
+
+    active_call.inject_connect_timeout().expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedConnectionFailure),
+        1
+    );
+    assert_eq!(context.network_failure_hangups_sent(), 1);
+    assert_eq!(context.normal_hangups_sent(), 0);
+}
+
+#[test]
+fn connect_timeout_after_connect() {
+    test_init();
+
+    let context = connected_outbound_call();
+    let mut cm = context.cm();
+    let mut active_call = context.active_call();
+
+    active_call.inject_connect_timeout().expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    // The call is already connected, so the timeout is ignored.
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(
+        context.event_count(ApplicationEvent::EndedConnectionFailure),
+        0
+    );
+    assert!(cm.busy());
+}
+
 #[test]
 fn outbound_proceed_with_error() {
     test_init();
@@ -2440,7 +3327,8 @@ fn glare_before_connect_winner() {
         "Test case not valid if incoming call-id can't be smaller than the active call-id."
     );
 
-    let call_id = CallId::new(context.active_call().call_id().as_u64() - 1);
+    let active_call_id = context.active_call().call_id();
+    let call_id = CallId::new(active_call_id.as_u64() - 1);
     cm.received_offer(
         remote_peer,
         call_id,
@@ -2457,6 +3345,15 @@ fn glare_before_connect_winner() {
     );
     assert_eq!(context.busys_sent(), 0);
     assert_eq!(context.event_count(ApplicationEvent::EndedRemoteGlare), 0);
+    assert_eq!(
+        cm.platform().expect(error_line!()).take_glare_resolutions(),
+        vec![GlareResolution {
+            local_call_id: active_call_id,
+            remote_call_id: call_id,
+            winning_call_id: Some(active_call_id),
+            converted_to_incoming: false,
+        }]
+    );
     assert_eq!(context.call_concluded_count(), 1);
 }
 
@@ -2484,7 +3381,8 @@ fn glare_before_connect_loser() {
         "Test case not valid if incoming call-id can't be greater than the active call-id."
     );
 
-    let call_id = CallId::new(context.active_call().call_id().as_u64() + 1);
+    let active_call_id = context.active_call().call_id();
+    let call_id = CallId::new(active_call_id.as_u64() + 1);
     cm.received_offer(
         remote_peer,
         call_id,
@@ -2502,6 +3400,15 @@ fn glare_before_connect_loser() {
         0
     );
     assert_eq!(context.busys_sent(), 0);
+    assert_eq!(
+        cm.platform().expect(error_line!()).take_glare_resolutions(),
+        vec![GlareResolution {
+            local_call_id: active_call_id,
+            remote_call_id: call_id,
+            winning_call_id: Some(call_id),
+            converted_to_incoming: true,
+        }]
+    );
     assert_eq!(context.call_concluded_count(), 1);
 }
 
@@ -2523,7 +3430,8 @@ fn glare_before_connect_equal() {
     info!("active remote_peer: {}", remote_peer);
 
     // The incoming offer's call_id will be equal to the active call_id.
-    let call_id = CallId::new(context.active_call().call_id().as_u64());
+    let active_call_id = context.active_call().call_id();
+    let call_id = CallId::new(active_call_id.as_u64());
     cm.received_offer(
         remote_peer,
         call_id,
@@ -2545,6 +3453,15 @@ fn glare_before_connect_equal() {
         1
     );
     assert_eq!(context.busys_sent(), 1);
+    assert_eq!(
+        cm.platform().expect(error_line!()).take_glare_resolutions(),
+        vec![GlareResolution {
+            local_call_id: active_call_id,
+            remote_call_id: call_id,
+            winning_call_id: None,
+            converted_to_incoming: false,
+        }]
+    );
     assert_eq!(context.call_concluded_count(), 2);
 }
 
@@ -3569,3 +4486,287 @@ fn group_call_ring_cancelled_by_another_device_before_join() {
         &messages[..]
     );
 }
+
+#[test]
+fn take_over_call() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    let call_id = CallId::new(context.prng.gen::<u64>());
+    cm.take_over_call(remote_peer, call_id, CallMediaType::Audio, 2)
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    assert_eq!(active_call.call_id(), call_id);
+    assert!(active_call.is_handoff());
+
+    cm.proceed(
+        call_id,
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default().with_data_mode(DataMode::Normal),
+        None,
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    cm.received_answer(call_id, random_received_answer(&context.prng, 1))
+        .expect(error_line!());
+    cm.received_ice(call_id, random_received_ice_candidate(&context.prng))
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    let mut active_connection = active_call.get_connection(1).expect(error_line!());
+    active_connection
+        .inject_ice_connected()
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    // The remote user already accepted the call, so don't ring.
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::ConnectedBeforeAccepted
+    );
+    assert_eq!(context.event_count(ApplicationEvent::RemoteRinging), 0);
+
+    active_connection
+        .inject_received_accepted_via_rtp_data(call_id)
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(
+        active_call.state().expect(error_line!()),
+        CallState::ConnectedAndAccepted
+    );
+    assert_eq!(context.event_count(ApplicationEvent::RemoteAccepted), 1);
+
+    // The hangup that ends the leg this device replaced is not for us.
+    cm.received_hangup(
+        call_id,
+        signaling::ReceivedHangup {
+            sender_device_id: 1,
+            hangup: signaling::Hangup::HandedOff(2),
+        },
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(context.event_count(ApplicationEvent::EndedHandedOff), 0);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.ended_count(), 0);
+    assert!(cm.busy());
+}
+
+#[derive(Clone, Default)]
+struct RecordingTransport {
+    sent: Arc<Mutex<Vec<&'static str>>>,
+}
+
+impl RecordingTransport {
+    fn sent(&self) -> Vec<&'static str> {
+        self.sent.lock().unwrap().clone()
+    }
+
+    fn record(&self, message: &'static str) -> ringrtc::common::Result<()> {
+        self.sent.lock().unwrap().push(message);
+        Ok(())
+    }
+}
+
+impl SignalingTransport<SimPlatform> for RecordingTransport {
+    fn send_offer(
+        &self,
+        _remote_peer: &String,
+        _call_id: CallId,
+        _offer: signaling::Offer,
+    ) -> ringrtc::common::Result<()> {
+        self.record("offer")
+    }
+
+    fn send_answer(
+        &self,
+        _remote_peer: &String,
+        _call_id: CallId,
+        _send: signaling::SendAnswer,
+    ) -> ringrtc::common::Result<()> {
+        self.record("answer")
+    }
+
+    fn send_ice(
+        &self,
+        _remote_peer: &String,
+        _call_id: CallId,
+        _send: signaling::SendIce,
+    ) -> ringrtc::common::Result<()> {
+        self.record("ice")
+    }
+
+    fn send_hangup(
+        &self,
+        _remote_peer: &String,
+        _call_id: CallId,
+        _send: signaling::SendHangup,
+    ) -> ringrtc::common::Result<()> {
+        self.record("hangup")
+    }
+
+    fn send_busy(&self, _remote_peer: &String, _call_id: CallId) -> ringrtc::common::Result<()> {
+        self.record("busy")
+    }
+
+    fn send_call_message(
+        &self,
+        _recipient_id: Vec<u8>,
+        _message: Vec<u8>,
+        _urgency: group_call::SignalingMessageUrgency,
+    ) -> ringrtc::common::Result<()> {
+        Ok(())
+    }
+
+    fn send_call_message_to_group(
+        &self,
+        _group_id: Vec<u8>,
+        _message: Vec<u8>,
+        _urgency: group_call::SignalingMessageUrgency,
+        _recipients_override: HashSet<Vec<u8>>,
+    ) -> ringrtc::common::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn outbound_call_over_signaling_transport() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let transport = RecordingTransport::default();
+    cm.set_signaling_transport(Box::new(transport.clone()))
+        .expect(error_line!());
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    cm.call(remote_peer, CallMediaType::Audio, 1)
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    cm.proceed(
+        active_call.call_id(),
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default().with_data_mode(DataMode::Normal),
+        None,
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(transport.sent(), vec!["offer"]);
+    assert_eq!(context.offers_sent(), 0);
+
+    cm.hangup().expect(error_line!());
+    cm.synchronize().expect(error_line!());
+
+    assert_eq!(transport.sent(), vec!["offer", "hangup"]);
+    assert_eq!(context.normal_hangups_sent(), 0);
+    assert_eq!(context.error_count(), 0);
+    assert_eq!(context.event_count(ApplicationEvent::EndedLocalHangup), 1);
+}
+
+#[derive(Clone, Default)]
+struct RecordingStore {
+    saved: Arc<Mutex<Option<Vec<u8>>>>,
+    saves: Arc<Mutex<usize>>,
+}
+
+impl SignalingStore<SimPlatform> for RecordingStore {
+    fn save(&self, _remote_peer: &String, pending: Vec<u8>) -> ringrtc::common::Result<()> {
+        *self.saves.lock().unwrap() += 1;
+        *self.saved.lock().unwrap() = Some(pending);
+        Ok(())
+    }
+
+    fn clear(&self) -> ringrtc::common::Result<()> {
+        *self.saved.lock().unwrap() = None;
+        Ok(())
+    }
+}
+
+#[test]
+fn outbound_call_pending_message_store() {
+    test_init();
+
+    let context = TestContext::new();
+    let mut cm = context.cm();
+
+    let store = RecordingStore::default();
+    cm.set_signaling_store(Box::new(store.clone()))
+        .expect(error_line!());
+
+    let remote_peer = format!("REMOTE_PEER-{}", context.prng.gen::<u16>());
+    cm.call(remote_peer.clone(), CallMediaType::Video, 1)
+        .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    let active_call = context.active_call();
+    let call_id = active_call.call_id();
+    cm.proceed(
+        call_id,
+        format!("CONTEXT-{}", context.prng.gen::<u16>()),
+        CallConfig::default().with_data_mode(DataMode::Normal),
+        None,
+    )
+    .expect(error_line!());
+
+    cm.synchronize().expect(error_line!());
+
+    // The offer was saved while pending, then cleared once the platform
+    // confirmed it as sent.
+    assert_eq!(context.offers_sent(), 1);
+    assert_eq!(*store.saves.lock().unwrap(), 1);
+    assert!(store.saved.lock().unwrap().is_none());
+
+    // Pretend the process died with the offer pending, and send it again
+    // from a new Call Manager.
+    let offer = random_received_offer(&context.prng, Duration::ZERO).offer;
+    let pending = signaling::PendingMessage {
+        call_id,
+        receiver_device_id: None,
+        message: signaling::Message::Offer(
+            signaling::Offer::new(CallMediaType::Video, offer.opaque).expect(error_line!()),
+        ),
+    };
+    let bytes = pending.to_bytes().expect(error_line!());
+
+    let restored = signaling::PendingMessage::from_bytes(&bytes).expect(error_line!());
+    assert_eq!(restored.call_id, call_id);
+    assert_eq!(restored.receiver_device_id, None);
+    match restored.message {
+        signaling::Message::Offer(offer) => {
+            assert_eq!(offer.call_media_type, CallMediaType::Video)
+        }
+        message => panic!("unexpected message: {}", message),
+    }
+
+    let restarted = TestContext::new();
+    let mut restarted_cm = restarted.cm();
+    restarted_cm
+        .resend_pending_message(remote_peer, bytes)
+        .expect(error_line!());
+
+    restarted_cm.synchronize().expect(error_line!());
+
+    assert_eq!(restarted.offers_sent(), 1);
+    assert_eq!(restarted.error_count(), 0);
+}